@@ -1,8 +1,15 @@
 use crate::actions::{self, ActionResult, CustomAction};
-use crate::distro_catalog::{self, ContainerImage, DistroCatalog, DownloadDistro, MsStoreDistroInfo};
+use crate::distro_catalog::{
+    self, CatalogImportReport, CatalogSelection, CatalogSubscriptionStatus, ContainerImage, DistroCatalog,
+    DownloadDistro, MsStoreDistroInfo,
+};
 use crate::download;
+use crate::environment::{
+    self, ApplyItemResult, ApplyStatus, CurrentState, DistroOrigin, EnvironmentFile, PlanItem, PlanKind, PlanTarget,
+};
 use crate::error::AppError;
-use crate::metadata::{self, DistroMetadata};
+use crate::lxc_catalog::{self, LxcCatalog};
+use crate::metadata::{self, DistroMetadata, VhdImportMode};
 use crate::settings::{self, AppSettings, WslConf, WslConfig};
 use crate::temp_file_guard::TempFileGuard;
 use crate::utils::{self, is_mock_mode};
@@ -11,8 +18,23 @@ use crate::validation::{
     validate_wsl_version,
 };
 use crate::wsl::resources::parse_memory_string;
-use crate::wsl::{reset_mock_state, set_mock_error, clear_mock_errors, set_stubborn_shutdown, was_force_shutdown_used, MockErrorType, CompactResult, Distribution, DistroResourceUsage, VhdSizeInfo, WslResourceUsage, WslService, WslVersionInfo, WslPreflightStatus, MountedDisk, MountDiskOptions, PhysicalDisk, InstalledTerminal};
-use crate::wsl::executor::{terminal_executor, wsl_executor, supports_distribution_id};
+use crate::wsl::customize::RootfsCustomization;
+use crate::wsl::distribution_conf::{WslPackageOptions, WslPackageReport};
+use crate::wsl::export_filter::{self, ExportFilter, FilterPresetInfo, FilterReport};
+use crate::wsl::encryption::{self, DecryptionKey, EncryptionKey, EncryptionKind, KeyPair};
+use crate::wsl::cloud_init::{self, CloudInitReport};
+use crate::wsl::provision::{ProvisioningReport, ProvisioningSpec};
+use crate::wsl::rootfs_inspect::{self, RootfsInspection};
+use crate::wsl::archive_files::{self, ArchiveListing, ArchiveSource, FileRestoreReport};
+use crate::wsl::migrate::{self, MigrationPreview, MigrationReport, MigrationSelection};
+use crate::wsl::backup_repository::{CheckReport, PruneReport};
+use crate::wsl::backup_targets::{self, BackupTargetConfig, TargetCredentials};
+use crate::wsl::backups::{
+    self, BackupManifest, BackupRun, BackupSchedule, BackupTargetInfo, BackupTrigger, DistroBackupStatus,
+};
+use crate::wsl::snapshots::{self, RestoreResult, RestoreTarget, Snapshot};
+use crate::wsl::{reset_mock_state, set_mock_error, clear_mock_errors, set_stubborn_shutdown, was_force_shutdown_used, MockErrorType, CloneResult, CompactResult, Distribution, DistroResourceUsage, VhdSizeInfo, WslResourceUsage, WslService, WslVersionInfo, WslPreflightStatus, MountedDisk, MountDiskOptions, PhysicalDisk, InstalledTerminal};
+use crate::wsl::executor::{terminal_executor, wsl_executor, supports_distribution_id, supports_vhd_workflows};
 use crate::{build_tray_menu, TrayState};
 use tauri::{AppHandle, Emitter, Manager};
 
@@ -781,33 +803,172 @@ pub async fn export_distribution(name: String, path: String) -> Result<(), Strin
     .map_err(|e| format!("Task failed: {}", e))?
 }
 
+/// Export a distribution to a tar file, leaving out what `filter` excludes
+#[tauri::command]
+pub async fn export_distribution_filtered(
+    name: String,
+    path: String,
+    filter: ExportFilter,
+) -> Result<FilterReport, String> {
+    validate_distro_name(&name).map_err(|e| e.to_string())?;
+    validate_file_path(&path).map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || {
+        WslService::export_distribution_filtered(&name, &path, &filter)
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Built-in export filter presets and their patterns
+#[tauri::command]
+pub fn list_export_filter_presets() -> Vec<FilterPresetInfo> {
+    export_filter::list_presets()
+}
+
+/// Export a WSL 2 distribution's disk to a `.vhdx` file
+#[tauri::command]
+pub async fn export_distribution_vhd(name: String, path: String) -> Result<(), String> {
+    validate_distro_name(&name).map_err(|e| e.to_string())?;
+    validate_file_path(&path).map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || {
+        WslService::export_distribution_vhd(&name, &path)
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Export a distribution to an encrypted archive (age format)
+#[tauri::command]
+pub async fn export_distribution_encrypted(
+    name: String,
+    path: String,
+    key: EncryptionKey,
+) -> Result<(), String> {
+    validate_distro_name(&name).map_err(|e| e.to_string())?;
+    validate_file_path(&path).map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || {
+        WslService::export_distribution_encrypted(&name, &path, &key)
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Generate a key pair for encrypting exports to a public key
+#[tauri::command]
+pub fn generate_encryption_key() -> KeyPair {
+    encryption::generate_key_pair()
+}
+
+#[tauri::command]
+pub async fn export_wsl_package(
+    name: String,
+    path: String,
+    options: WslPackageOptions,
+) -> Result<WslPackageReport, String> {
+    validate_distro_name(&name).map_err(|e| e.to_string())?;
+    validate_file_path(&path).map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || {
+        WslService::export_wsl_package(&name, &path, &options)
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Inspect a local rootfs archive or an `http(s)://` URL without extracting
+/// it, suggesting a name that does not clash with existing distributions.
+#[tauri::command]
+pub async fn inspect_rootfs(source: String) -> Result<RootfsInspection, String> {
+    let is_url = source.starts_with("http://") || source.starts_with("https://");
+    if is_url {
+        validate_url(&source).map_err(|e| e.to_string())?;
+    } else {
+        validate_file_path(&source).map_err(|e| e.to_string())?;
+    }
+    tokio::task::spawn_blocking(move || {
+        let mut inspection = if is_url && crate::utils::is_mock_mode() {
+            rootfs_inspect::mock_inspection(&source)
+        } else {
+            rootfs_inspect::inspect_source(&source)
+                .map_err(AppError::from)
+                .map_err(String::from)?
+        };
+        let existing: Vec<String> = WslService::list_distributions()
+            .map(|distros| distros.into_iter().map(|d| d.name).collect())
+            .unwrap_or_default();
+        inspection.suggested_name = rootfs_inspect::unique_name(&inspection.suggested_name, &existing);
+        Ok(inspection)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Reject invalid customizations up front rather than after a download
+fn validate_customization(customization: Option<&RootfsCustomization>) -> Result<(), String> {
+    customization
+        .map_or(Ok(()), |c| c.validate())
+        .map_err(AppError::from)
+        .map_err(String::from)
+}
+
+/// Reject customizing a source that cannot be rewritten (xz) before it is
+/// downloaded or imported
+fn validate_customization_source(
+    customization: Option<&RootfsCustomization>,
+    source: &str,
+) -> Result<(), String> {
+    customization
+        .map_or(Ok(()), |c| c.validate_source(source))
+        .map_err(AppError::from)
+        .map_err(String::from)
+}
+
 #[tauri::command]
 pub async fn import_distribution(
     name: String,
     install_location: String,
     tar_path: String,
+    customization: Option<RootfsCustomization>,
 ) -> Result<(), String> {
     validate_distro_name(&name).map_err(|e| e.to_string())?;
     validate_file_path(&install_location).map_err(|e| e.to_string())?;
     validate_file_path(&tar_path).map_err(|e| e.to_string())?;
+    validate_customization(customization.as_ref())?;
+    validate_customization_source(customization.as_ref(), &tar_path)?;
 
     let tar_path_clone = tar_path.clone();
     let name_clone = name.clone();
+    let terminal_command = settings::get_settings().terminal_command;
 
     tokio::task::spawn_blocking(move || {
-        let result = WslService::import_distribution(&name, &install_location, &tar_path);
+        let result = WslService::import_package(
+            &name,
+            &install_location,
+            &tar_path,
+            None,
+            &terminal_command,
+            customization.as_ref(),
+        );
 
         // Create metadata if import succeeded
-        if result.is_ok() {
+        if let Ok(package) = &result {
             use crate::wsl::executor::resource_monitor;
 
             let registry_info = resource_monitor().get_all_distro_registry_info();
             if let Some(info) = registry_info.get(&name_clone) {
-                let distro_metadata = metadata::DistroMetadata::new_import(
+                let mut distro_metadata = metadata::DistroMetadata::new_import(
                     info.id.clone(),
                     name_clone.clone(),
                     Some(tar_path_clone),
                 );
+                distro_metadata.set_imported_package(package.clone());
                 if let Err(e) = metadata::save_metadata(distro_metadata) {
                     log::warn!("Failed to save import metadata: {}", e);
                 } else {
@@ -818,18 +979,142 @@ pub async fn import_distribution(
             }
         }
 
-        result.map_err(|e| e.to_string())
+        result.map(|_| ()).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Whether an archive is encrypted and needs a passphrase or a secret key
+#[tauri::command]
+pub async fn detect_archive_encryption(path: String) -> Result<Option<EncryptionKind>, String> {
+    validate_file_path(&path).map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || {
+        if is_mock_mode() && !std::path::Path::new(&path).is_file() {
+            let is_age = std::path::Path::new(&path)
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case(encryption::FILE_EXTENSION));
+            return Ok(is_age.then_some(EncryptionKind::Passphrase));
+        }
+        encryption::detect(std::path::Path::new(&path))
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Import an encrypted archive, decrypting it straight into `wsl --import`
+#[tauri::command]
+pub async fn import_encrypted_distribution(
+    name: String,
+    install_location: String,
+    path: String,
+    key: DecryptionKey,
+    wsl_version: Option<u8>,
+) -> Result<(), String> {
+    validate_distro_name(&name).map_err(|e| e.to_string())?;
+    validate_file_path(&install_location).map_err(|e| e.to_string())?;
+    validate_file_path(&path).map_err(|e| e.to_string())?;
+    if let Some(v) = wsl_version {
+        validate_wsl_version(v).map_err(|e| e.to_string())?;
+    }
+    tokio::task::spawn_blocking(move || {
+        WslService::import_distribution_encrypted(&name, &install_location, &path, &key, wsl_version)
+            .map_err(AppError::from)
+            .map_err(String::from)?;
+
+        use crate::wsl::executor::resource_monitor;
+        let registry_info = resource_monitor().get_all_distro_registry_info();
+        if let Some(info) = registry_info.get(&name) {
+            let distro_metadata = DistroMetadata::new_import(info.id.clone(), name.clone(), Some(path));
+            if let Err(e) = metadata::save_metadata(distro_metadata) {
+                log::warn!("Failed to save import metadata: {}", e);
+            }
+        } else {
+            log::warn!("Could not find GUID for imported distribution '{}' - metadata not created", name);
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Record the source `.vhdx` and import mode for a VHD-based distribution
+fn save_vhd_import_metadata(name: &str, vhd_path: &str, mode: VhdImportMode) {
+    use crate::wsl::executor::resource_monitor;
+
+    let registry_info = resource_monitor().get_all_distro_registry_info();
+    if let Some(info) = registry_info.get(name) {
+        let distro_metadata =
+            DistroMetadata::new_vhd_import(info.id.clone(), name.to_string(), vhd_path.to_string(), mode);
+        if let Err(e) = metadata::save_metadata(distro_metadata) {
+            log::warn!("Failed to save VHD import metadata: {}", e);
+        }
+    } else {
+        log::warn!("Could not find GUID for imported distribution '{}' - metadata not created", name);
+    }
+}
+
+/// Import a `.vhdx` disk as a new distribution; WSL copies it into `install_location`
+#[tauri::command]
+pub async fn import_distribution_vhd(name: String, install_location: String, vhd_path: String) -> Result<(), String> {
+    validate_distro_name(&name).map_err(|e| e.to_string())?;
+    validate_file_path(&install_location).map_err(|e| e.to_string())?;
+    validate_file_path(&vhd_path).map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || {
+        WslService::import_distribution_vhd(&name, &install_location, &vhd_path, None)
+            .map_err(AppError::from)
+            .map_err(String::from)?;
+        save_vhd_import_metadata(&name, &vhd_path, VhdImportMode::Copy);
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Register an existing `.vhdx` as a distribution without copying it
+#[tauri::command]
+pub async fn register_vhd_in_place(name: String, vhd_path: String) -> Result<(), String> {
+    validate_distro_name(&name).map_err(|e| e.to_string())?;
+    validate_file_path(&vhd_path).map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || {
+        WslService::register_vhd_in_place(&name, &vhd_path)
+            .map_err(AppError::from)
+            .map_err(String::from)?;
+        save_vhd_import_metadata(&name, &vhd_path, VhdImportMode::InPlace);
+        Ok(())
     })
     .await
     .map_err(|e| format!("Task failed: {}", e))?
 }
 
+/// Whether the installed WSL supports VHD import, export and in-place registration
+#[tauri::command]
+pub fn check_vhd_support() -> bool {
+    supports_vhd_workflows()
+}
+
 #[tauri::command]
-pub async fn clone_distribution(source: String, new_name: String, install_location: Option<String>) -> Result<(), String> {
+pub async fn clone_distribution(
+    source: String,
+    new_name: String,
+    install_location: Option<String>,
+    customization: Option<RootfsCustomization>,
+    filter: Option<ExportFilter>,
+) -> Result<CloneResult, String> {
     validate_distro_name(&source).map_err(|e| e.to_string())?;
     validate_distro_name(&new_name).map_err(|e| e.to_string())?;
+    validate_customization(customization.as_ref())?;
     tokio::task::spawn_blocking(move || {
-        WslService::clone_distribution(&source, &new_name, install_location.as_deref()).map_err(|e| e.to_string())
+        WslService::clone_distribution(
+            &source,
+            &new_name,
+            install_location.as_deref(),
+            customization.as_ref(),
+            filter.as_ref(),
+        )
+            .map_err(|e| e.to_string())
     })
     .await
     .map_err(|e| format!("Task failed: {}", e))?
@@ -1033,6 +1318,7 @@ pub async fn create_from_image(
     distro_name: String,
     install_location: Option<String>,
     wsl_version: Option<u8>,
+    customization: Option<RootfsCustomization>,
 ) -> Result<(), String> {
     use crate::settings::{get_settings, ContainerRuntime};
 
@@ -1043,6 +1329,7 @@ pub async fn create_from_image(
     if let Some(v) = wsl_version {
         validate_wsl_version(v).map_err(|e| e.to_string())?;
     }
+    validate_customization(customization.as_ref())?;
 
     let settings = get_settings();
     let runtime = settings.container_runtime.clone();
@@ -1087,6 +1374,7 @@ pub async fn create_from_image(
                             }),
                         );
                     })),
+                    customization.as_ref(),
                 )
                 .map_err(|e| e.to_string())
             }
@@ -1098,6 +1386,7 @@ pub async fn create_from_image(
                     install_location.as_deref(),
                     wsl_version,
                     Some("docker"),
+                    customization.as_ref(),
                 )
                 .map_err(|e| e.to_string())
             }
@@ -1109,6 +1398,7 @@ pub async fn create_from_image(
                     install_location.as_deref(),
                     wsl_version,
                     Some("podman"),
+                    customization.as_ref(),
                 )
                 .map_err(|e| e.to_string())
             }
@@ -1120,6 +1410,7 @@ pub async fn create_from_image(
                     install_location.as_deref(),
                     wsl_version,
                     Some(cmd.as_str()),
+                    customization.as_ref(),
                 )
                 .map_err(|e| e.to_string())
             }
@@ -1165,6 +1456,7 @@ pub async fn custom_install_with_progress(
     custom_name: String,
     install_location: Option<String>,
     wsl_version: Option<u8>,
+    customization: Option<RootfsCustomization>,
 ) -> Result<(), String> {
     validate_distro_name(&custom_name).map_err(|e| e.to_string())?;
     if let Some(ref loc) = install_location {
@@ -1173,6 +1465,7 @@ pub async fn custom_install_with_progress(
     if let Some(v) = wsl_version {
         validate_wsl_version(v).map_err(|e| e.to_string())?;
     }
+    validate_customization(customization.as_ref())?;
 
     // In mock mode, use simulated download
     if is_mock_mode() {
@@ -1187,6 +1480,8 @@ pub async fn custom_install_with_progress(
         )
     })?;
 
+    validate_customization_source(customization.as_ref(), &download_url)?;
+
     // Get checksum from catalog (if available)
     let expected_checksum = distro_catalog::get_download_checksum(&distro_id);
 
@@ -1227,6 +1522,7 @@ pub async fn custom_install_with_progress(
         &location,
         &tar_path_str,
         wsl_version,
+        customization.as_ref(),
     );
 
     // Cleanup temp file automatically via Drop (guard will clean up when this function exits)
@@ -1394,6 +1690,136 @@ pub fn check_action_applies(action_id: String, distro: String) -> bool {
         .unwrap_or(false)
 }
 
+// Environment file commands
+
+/// Compute the plan for an environment file against the current state
+#[tauri::command]
+pub async fn plan_environment(path: String) -> Result<Vec<PlanItem>, String> {
+    validate_file_path(&path).map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || {
+        let env = EnvironmentFile::load(&path)?;
+        let state = CurrentState::read(&env)?;
+        Ok(environment::compute_plan(&env, &state))
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Apply an environment file in plan order.
+///
+/// The plan is recomputed here rather than taken from the frontend. Drifts are
+/// only reported, and items for a distribution whose creation failed are
+/// skipped. With `dry_run` nothing is changed.
+#[tauri::command]
+pub async fn apply_environment(
+    app: AppHandle,
+    path: String,
+    dry_run: bool,
+) -> Result<Vec<ApplyItemResult>, String> {
+    validate_file_path(&path).map_err(|e| e.to_string())?;
+    let (env, plan) = tokio::task::spawn_blocking(move || {
+        let env = EnvironmentFile::load(&path)?;
+        let state = CurrentState::read(&env)?;
+        let plan = environment::compute_plan(&env, &state);
+        Ok::<_, String>((env, plan))
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))??;
+
+    let mut failed_distros: Vec<String> = Vec::new();
+    let mut results = Vec::with_capacity(plan.len());
+    for item in plan {
+        let depends_on_distro = matches!(
+            item.target,
+            PlanTarget::Distro | PlanTarget::WslVersion | PlanTarget::WslConf
+        );
+        let (status, message) = if dry_run {
+            (ApplyStatus::DryRun, None)
+        } else if item.kind == PlanKind::Drift {
+            (ApplyStatus::Skipped, Some("Drift is not reconciled automatically".to_string()))
+        } else if depends_on_distro && failed_distros.iter().any(|n| n.eq_ignore_ascii_case(&item.name)) {
+            (ApplyStatus::Skipped, Some("Distribution was not created".to_string()))
+        } else {
+            match apply_environment_item(&app, &env, &item).await {
+                Ok(()) => (ApplyStatus::Applied, None),
+                Err(e) => {
+                    if item.target == PlanTarget::Distro {
+                        failed_distros.push(item.name.clone());
+                    }
+                    (ApplyStatus::Failed, Some(e))
+                }
+            }
+        };
+        results.push(ApplyItemResult { item, status, message });
+    }
+    Ok(results)
+}
+
+async fn apply_environment_item(app: &AppHandle, env: &EnvironmentFile, item: &PlanItem) -> Result<(), String> {
+    match item.target {
+        PlanTarget::WslConfig => {
+            let desired = env.wsl_config.clone().unwrap_or_default();
+            let merged = environment::merge_fields(&settings::read_wsl_config()?, &desired)?;
+            settings::write_wsl_config(merged)
+        }
+        PlanTarget::Distro => {
+            let spec = env.distro(&item.name).cloned().ok_or("Distribution is not in the file")?;
+            let name = spec.name.clone();
+            let location = spec.location.clone();
+            let version = spec.wsl_version.filter(|_| spec.source.supports_version());
+            match spec.source.clone() {
+                DistroOrigin::Store(id) => quick_install_distribution(id).await?,
+                DistroOrigin::Image(image) => {
+                    create_from_image(app.clone(), image, name.clone(), location, version, None).await?
+                }
+                DistroOrigin::Url(url) => {
+                    install_from_rootfs_url(app.clone(), url, name.clone(), location, version, None).await?
+                }
+                DistroOrigin::Lxc(image_id) => {
+                    install_lxc_image(app.clone(), image_id, name.clone(), location, version, None).await?
+                }
+                DistroOrigin::Import(tar_path) => {
+                    import_distribution(name.clone(), location.unwrap_or_default(), tar_path, None).await?
+                }
+                DistroOrigin::Clone(source) => {
+                    clone_distribution(source, name.clone(), location, None, None).await?;
+                }
+            }
+            match spec.wsl_version {
+                Some(version) if !spec.source.supports_version() => set_distro_version(name, version).await,
+                _ => Ok(()),
+            }
+        }
+        PlanTarget::WslVersion => {
+            let version = env.distro(&item.name).and_then(|d| d.wsl_version).ok_or("No WSL version in the file")?;
+            set_distro_version(item.name.clone(), version).await
+        }
+        PlanTarget::WslConf => {
+            let spec = env.distro(&item.name).cloned().ok_or("Distribution is not in the file")?;
+            tokio::task::spawn_blocking(move || {
+                let current = WslService::list_distributions()
+                    .map_err(|e| e.to_string())?
+                    .into_iter()
+                    .find(|d| d.name.eq_ignore_ascii_case(&spec.name))
+                    .ok_or_else(|| format!("Distribution '{}' not found", spec.name))?;
+                let existing = settings::read_wsl_conf(&current.name, current.id.as_deref())?;
+                let merged = environment::merge_fields(&existing, &spec.wsl_conf.unwrap_or_default())?;
+                settings::write_wsl_conf(&current.name, merged)
+            })
+            .await
+            .map_err(|e| format!("Task failed: {}", e))?
+        }
+        PlanTarget::Action => {
+            let action = env.action(&item.name).cloned().ok_or("Action is not in the file")?;
+            match item.kind {
+                PlanKind::Create => actions::add_action(action),
+                _ => actions::update_action(action),
+            }
+            .map(|_| ())
+        }
+    }
+}
+
 // Startup Actions command
 
 #[tauri::command]
@@ -1412,6 +1838,7 @@ pub async fn install_from_rootfs_url(
     name: String,
     install_location: Option<String>,
     wsl_version: Option<u8>,
+    customization: Option<RootfsCustomization>,
 ) -> Result<(), String> {
     validate_url(&url).map_err(|e| e.to_string())?;
     validate_distro_name(&name).map_err(|e| e.to_string())?;
@@ -1421,56 +1848,221 @@ pub async fn install_from_rootfs_url(
     if let Some(v) = wsl_version {
         validate_wsl_version(v).map_err(|e| e.to_string())?;
     }
+    validate_customization(customization.as_ref())?;
 
     // In mock mode, use simulated download
     if is_mock_mode() {
         return mock_install_with_progress(&app, &name, wsl_version).await;
     }
 
-    // Create temp file path with RAII guard for automatic cleanup
-    let temp_dir = std::env::temp_dir();
-    let tar_path = temp_dir.join(format!("wsl-rootfs-{}.tar.gz", std::process::id()));
-    let temp_guard = TempFileGuard::new(&tar_path);
-
-    // Download with progress events (no checksum for custom URLs)
-    download::download_with_progress_and_checksum(&app, &url, &tar_path, &name, None).await?;
+    // No checksum for custom URLs
+    download_and_import_rootfs(
+        &app,
+        &url,
+        &name,
+        install_location,
+        wsl_version,
+        None,
+        customization,
+        metadata::InstallSource::Lxc,
+        |_| {},
+    )
+    .await
+}
 
-    // Determine install location (use settings-based default if not specified)
-    let location = match install_location {
-        Some(ref loc) if !loc.is_empty() => loc.clone(),
-        _ => crate::settings::get_default_distro_path(&name),
-    };
+/// Install an image from the LXC catalog, verifying its published sha256
+#[tauri::command]
+pub async fn install_lxc_image(
+    app: AppHandle,
+    image_id: String,
+    name: String,
+    install_location: Option<String>,
+    wsl_version: Option<u8>,
+    customization: Option<RootfsCustomization>,
+) -> Result<(), String> {
+    validate_distro_name(&name).map_err(|e| e.to_string())?;
+    if let Some(ref loc) = install_location {
+        validate_file_path(loc).map_err(|e| e.to_string())?;
+    }
+    if let Some(v) = wsl_version {
+        validate_wsl_version(v).map_err(|e| e.to_string())?;
+    }
+    validate_customization(customization.as_ref())?;
 
-    // Create install directory
-    std::fs::create_dir_all(&location)
-        .map_err(|e| format!("Failed to create install directory: {}", e))?;
+    // In mock mode, use simulated download
+    if is_mock_mode() {
+        return mock_install_with_progress(&app, &name, wsl_version).await;
+    }
 
-    // Emit importing stage
-    let _ = app.emit(
-        "download-progress",
-        download::DownloadProgress {
-            distro_name: name.clone(),
-            stage: "importing".to_string(),
-            bytes_downloaded: 0,
-            total_bytes: None,
-            percent: None,
-        },
-    );
+    let id = image_id.clone();
+    let image = tokio::task::spawn_blocking(move || lxc_catalog::find_image(&id))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))??;
 
-    // Import the distribution
-    let tar_path_str = tar_path.to_string_lossy().to_string();
-    let import_result = WslService::import_distribution_with_version(
+    download_and_import_rootfs(
+        &app,
+        &image.download_url,
         &name,
-        &location,
-        &tar_path_str,
+        install_location,
         wsl_version,
-    );
+        image.sha256.clone(),
+        customization,
+        metadata::InstallSource::Lxc,
+        |m| m.catalog_entry = Some(image_id),
+    )
+    .await
+}
 
-    // Cleanup temp file automatically via Drop
-    drop(temp_guard);
+/// Install an entry from a distribution manifest directly, without touching
+/// `DistributionListUrl`. The host-architecture download is verified against
+/// the manifest's `Sha256` and imported like any other rootfs download.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn install_manifest_entry(
+    app: AppHandle,
+    manifest_url: String,
+    flavor: String,
+    entry_name: String,
+    name: String,
+    install_location: Option<String>,
+    wsl_version: Option<u8>,
+    customization: Option<RootfsCustomization>,
+) -> Result<(), String> {
+    validate_distro_name(&name).map_err(|e| e.to_string())?;
+    if let Some(ref loc) = install_location {
+        validate_file_path(loc).map_err(|e| e.to_string())?;
+    }
+    if let Some(v) = wsl_version {
+        validate_wsl_version(v).map_err(|e| e.to_string())?;
+    }
+    validate_customization(customization.as_ref())?;
 
-    // Create metadata if import succeeded
-    if import_result.is_ok() {
+    let (url, fl, entry) = (manifest_url.clone(), flavor.clone(), entry_name.clone());
+    let download = tokio::task::spawn_blocking(move || {
+        distro_sources::resolve_manifest_download(&url, &fl, &entry)
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))??;
+
+    // In mock mode, use simulated download
+    if is_mock_mode() {
+        return mock_install_with_progress(&app, &name, wsl_version).await;
+    }
+
+    download_and_import_rootfs(
+        &app,
+        &download.url,
+        &name,
+        install_location,
+        wsl_version,
+        Some(download.sha256),
+        customization,
+        metadata::InstallSource::Download,
+        |m| {
+            m.manifest_url = Some(manifest_url);
+            m.manifest_flavor = Some(flavor);
+        },
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn get_lxc_catalog(force_refresh: bool) -> Result<LxcCatalog, String> {
+    tokio::task::spawn_blocking(move || lxc_catalog::get_catalog(force_refresh))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[tauri::command]
+pub fn clear_lxc_catalog_cache() -> Result<(), String> {
+    lxc_catalog::clear_cache()
+}
+
+/// Download a rootfs tarball, apply `customization`, import it, and record
+/// metadata with the given install source. `describe` fills in
+/// source-specific metadata fields.
+#[allow(clippy::too_many_arguments)]
+async fn download_and_import_rootfs(
+    app: &AppHandle,
+    url: &str,
+    name: &str,
+    install_location: Option<String>,
+    wsl_version: Option<u8>,
+    expected_checksum: Option<String>,
+    customization: Option<RootfsCustomization>,
+    install_source: metadata::InstallSource,
+    describe: impl FnOnce(&mut metadata::DistroMetadata),
+) -> Result<(), String> {
+    validate_customization_source(customization.as_ref(), url)?;
+    let name = name.to_string();
+    let url = url.to_string();
+
+    // file:// sources (e.g. manifest entries for tarballs on a share) are
+    // verified and imported in place; anything else is downloaded first
+    let local = {
+        let (url, checksum) = (url.clone(), expected_checksum.clone());
+        tokio::task::spawn_blocking(move || {
+            distro_sources::verified_local_tarball(&url, checksum.as_deref())
+        })
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+        .map_err(|e| e.to_string())?
+    };
+
+    let (tar_path, temp_guard) = match local {
+        Some(path) => (path, None),
+        None => {
+            // Create temp file path with RAII guard for automatic cleanup
+            let temp_dir = std::env::temp_dir();
+            let tar_path = temp_dir.join(format!("wsl-rootfs-{}.tar.gz", std::process::id()));
+            let temp_guard = TempFileGuard::new(&tar_path);
+
+            // Download with progress events
+            download::download_with_progress_and_checksum(app, &url, &tar_path, &name, expected_checksum).await?;
+            (tar_path, Some(temp_guard))
+        }
+    };
+
+    // Determine install location (use settings-based default if not specified)
+    let location = match install_location {
+        Some(ref loc) if !loc.is_empty() => loc.clone(),
+        _ => crate::settings::get_default_distro_path(&name),
+    };
+
+    // Create install directory
+    std::fs::create_dir_all(&location)
+        .map_err(|e| format!("Failed to create install directory: {}", e))?;
+
+    // Emit importing stage
+    let _ = app.emit(
+        "download-progress",
+        download::DownloadProgress {
+            distro_name: name.clone(),
+            stage: "importing".to_string(),
+            bytes_downloaded: 0,
+            total_bytes: None,
+            percent: None,
+        },
+    );
+
+    // Import the distribution, applying wsl-distribution.conf for .wsl packages
+    let tar_path_str = tar_path.to_string_lossy().to_string();
+    let import_result = WslService::import_package(
+        &name,
+        &location,
+        &tar_path_str,
+        wsl_version,
+        &settings::get_settings().terminal_command,
+        customization.as_ref(),
+    );
+
+    // Cleanup temp file automatically via Drop
+    drop(temp_guard);
+
+    // Create metadata if import succeeded
+    if let Ok(package) = &import_result {
         use crate::wsl::executor::resource_monitor;
 
         let registry_info = resource_monitor().get_all_distro_registry_info();
@@ -1478,9 +2070,11 @@ pub async fn install_from_rootfs_url(
             let mut distro_metadata = metadata::DistroMetadata::new(
                 info.id.clone(),
                 name.clone(),
-                metadata::InstallSource::Lxc,
+                install_source,
             );
             distro_metadata.download_url = Some(url.clone());
+            distro_metadata.set_imported_package(package.clone());
+            describe(&mut distro_metadata);
             if let Err(e) = metadata::save_metadata(distro_metadata) {
                 log::warn!("Failed to save install metadata: {}", e);
             } else {
@@ -1519,14 +2113,32 @@ pub async fn install_from_rootfs_url(
         }
     }
 
-    import_result.map_err(|e| e.to_string())
+    import_result.map(|_| ()).map_err(|e| e.to_string())
 }
 
 // Distro Catalog commands
 
 #[tauri::command]
-pub fn get_distro_catalog() -> DistroCatalog {
-    distro_catalog::get_catalog()
+pub async fn get_distro_catalog() -> Result<DistroCatalog, String> {
+    tokio::task::spawn_blocking(|| {
+        // Refetch subscriptions whose cache has expired; failures fall back to the cached copy
+        distro_catalog::refresh_subscriptions(false);
+        distro_catalog::get_catalog()
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))
+}
+
+#[tauri::command]
+pub async fn refresh_catalog_subscriptions(force: bool) -> Result<Vec<CatalogSubscriptionStatus>, String> {
+    tokio::task::spawn_blocking(move || distro_catalog::refresh_subscriptions(force))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))
+}
+
+#[tauri::command]
+pub fn get_catalog_subscription_status() -> Vec<CatalogSubscriptionStatus> {
+    distro_catalog::get_subscription_status()
 }
 
 #[tauri::command]
@@ -1551,13 +2163,13 @@ pub fn reset_ms_store_distros() -> Result<DistroCatalog, String> {
 
 #[tauri::command]
 pub fn add_download_distro(distro: DownloadDistro) -> Result<DistroCatalog, String> {
-    validate_url(&distro.url).map_err(|e| e.to_string())?;
+    distro.validate_downloads()?;
     distro_catalog::add_download_distro(distro)
 }
 
 #[tauri::command]
 pub fn update_download_distro(distro: DownloadDistro) -> Result<DistroCatalog, String> {
-    validate_url(&distro.url).map_err(|e| e.to_string())?;
+    distro.validate_downloads()?;
     distro_catalog::update_download_distro(distro)
 }
 
@@ -1591,6 +2203,28 @@ pub fn delete_ms_store_distro(distro_id: String) -> Result<DistroCatalog, String
     distro_catalog::delete_ms_store_distro(&distro_id)
 }
 
+#[tauri::command]
+pub fn export_distro_catalog(selection: Option<CatalogSelection>) -> Result<String, String> {
+    distro_catalog::export_catalog(selection.as_ref())
+}
+
+#[tauri::command]
+pub fn export_distro_catalog_to_file(path: String, selection: Option<CatalogSelection>) -> Result<(), String> {
+    validate_file_path(&path).map_err(|e| e.to_string())?;
+    distro_catalog::export_catalog_to_file(&path, selection.as_ref())
+}
+
+#[tauri::command]
+pub fn import_distro_catalog(json: String, merge: bool) -> Result<CatalogImportReport, String> {
+    distro_catalog::import_catalog(&json, merge)
+}
+
+#[tauri::command]
+pub fn import_distro_catalog_from_file(path: String, merge: bool) -> Result<CatalogImportReport, String> {
+    validate_file_path(&path).map_err(|e| e.to_string())?;
+    distro_catalog::import_catalog_from_file(&path, merge)
+}
+
 // WSL Preflight & Version commands
 
 /// Check if WSL is installed and ready to use
@@ -1677,6 +2311,53 @@ pub async fn set_distro_default_user(name: String, username: String) -> Result<(
     .map_err(|e| format!("Task failed: {}", e))?
 }
 
+/// Create the default user of a freshly installed distribution and make it
+/// the default in wsl.conf
+#[tauri::command]
+pub async fn provision_distribution(name: String, spec: ProvisioningSpec) -> Result<ProvisioningReport, String> {
+    validate_distro_name(&name).map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || {
+        WslService::provision_distribution(&name, &spec)
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Check cloud-config user data before install; returns the top-level keys
+/// that will be ignored
+#[tauri::command]
+pub fn validate_cloud_config(user_data: String) -> Result<Vec<String>, String> {
+    cloud_init::validate_user_data(&user_data).map_err(|e| e.to_string())
+}
+
+/// Apply cloud-config user data to an installed distribution
+#[tauri::command]
+pub async fn apply_cloud_config(name: String, user_data: String) -> Result<CloudInitReport, String> {
+    validate_distro_name(&name).map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || {
+        WslService::apply_user_data(&name, &user_data)
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Re-run the cloud-config stored in the distribution's metadata
+#[tauri::command]
+pub async fn rerun_cloud_config(name: String) -> Result<CloudInitReport, String> {
+    validate_distro_name(&name).map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || {
+        WslService::rerun_user_data(&name)
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
 #[tauri::command]
 pub async fn resize_distribution(name: String, size: String) -> Result<(), String> {
     validate_distro_name(&name).map_err(|e| e.to_string())?;
@@ -1725,6 +2406,336 @@ pub async fn rename_distribution(
     .map_err(|e| format!("Task failed: {}", e))?
 }
 
+// Snapshot commands
+
+/// List snapshots, optionally only those of one distribution (newest first)
+#[tauri::command]
+pub async fn list_snapshots(distro_name: Option<String>) -> Result<Vec<Snapshot>, String> {
+    tokio::task::spawn_blocking(move || {
+        snapshots::list_snapshots(distro_name.as_deref())
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Take a named snapshot of a distribution
+#[tauri::command]
+pub async fn create_snapshot(
+    distro_name: String,
+    name: String,
+    description: Option<String>,
+) -> Result<Snapshot, String> {
+    validate_distro_name(&distro_name).map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || {
+        snapshots::create_snapshot(&distro_name, &name, description.as_deref())
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Restore a snapshot in place or as a new distribution
+#[tauri::command]
+pub async fn restore_snapshot(id: String, target: RestoreTarget) -> Result<RestoreResult, String> {
+    if let RestoreTarget::NewDistro { name, .. } = &target {
+        validate_distro_name(name).map_err(|e| e.to_string())?;
+    }
+    tokio::task::spawn_blocking(move || {
+        snapshots::restore_snapshot(&id, &target)
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Delete a snapshot and its file
+#[tauri::command]
+pub async fn delete_snapshot(id: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        snapshots::delete_snapshot(&id)
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+// Backup commands
+
+/// Backup schedule, last successful backup and next run of each distribution
+#[tauri::command]
+pub async fn get_backup_status() -> Result<Vec<DistroBackupStatus>, String> {
+    tokio::task::spawn_blocking(|| {
+        backups::list_backup_status()
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Create or replace a distribution's backup schedule
+#[tauri::command]
+pub async fn save_backup_schedule(schedule: BackupSchedule) -> Result<BackupSchedule, String> {
+    validate_distro_name(&schedule.distro_name).map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || {
+        backups::save_schedule(schedule)
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Remove a distribution's backup schedule, keeping its archives
+#[tauri::command]
+pub async fn delete_backup_schedule(distro_name: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        backups::delete_schedule(&distro_name)
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Back up a distribution now; the returned run may be failed or skipped
+#[tauri::command]
+pub async fn run_backup_now(distro_name: String) -> Result<BackupRun, String> {
+    validate_distro_name(&distro_name).map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || {
+        backups::run_backup(&distro_name, BackupTrigger::Manual)
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// The backup folder and the configured backup targets, without secrets
+#[tauri::command]
+pub async fn list_backup_targets() -> Result<Vec<BackupTargetInfo>, String> {
+    tokio::task::spawn_blocking(|| {
+        backups::list_targets()
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Create or update a backup target; stored credentials are kept when none are given
+#[tauri::command]
+pub async fn save_backup_target(
+    target: BackupTargetConfig,
+    credentials: Option<TargetCredentials>,
+) -> Result<BackupTargetInfo, String> {
+    tokio::task::spawn_blocking(move || {
+        backups::save_target(target, credentials)
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Remove a backup target and its credentials, keeping the archives on it
+#[tauri::command]
+pub async fn delete_backup_target(target_id: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        backups::delete_target(&target_id)
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Connect to a backup target; returns the number of backups found
+#[tauri::command]
+pub async fn test_backup_target(target_id: String) -> Result<usize, String> {
+    tokio::task::spawn_blocking(move || {
+        backups::test_target(&target_id)
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Backups stored on a target, newest first
+#[tauri::command]
+pub async fn list_target_backups(target_id: String) -> Result<Vec<BackupManifest>, String> {
+    tokio::task::spawn_blocking(move || {
+        backups::list_target_backups(&target_id)
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Restore a backup from a target in place or as a new distribution
+#[tauri::command]
+pub async fn restore_backup(
+    target_id: String,
+    backup_id: String,
+    target: RestoreTarget,
+) -> Result<RestoreResult, String> {
+    if let RestoreTarget::NewDistro { name, .. } = &target {
+        validate_distro_name(name).map_err(|e| e.to_string())?;
+    }
+    tokio::task::spawn_blocking(move || {
+        backups::restore_backup(&target_id, &backup_id, &target)
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Save a backup from a target as a plain tar file
+#[tauri::command]
+pub async fn export_backup_tar(
+    target_id: String,
+    backup_id: String,
+    destination: String,
+) -> Result<(), String> {
+    validate_file_path(&destination).map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || {
+        backups::export_backup_tar(&target_id, &backup_id, &destination)
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Check the integrity of the chunk repository on a target
+#[tauri::command]
+pub async fn check_backup_repository(
+    target_id: String,
+    verify_data: bool,
+) -> Result<CheckReport, String> {
+    tokio::task::spawn_blocking(move || {
+        backups::check_repository(&target_id, verify_data)
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Delete chunks no backup on the target refers to
+#[tauri::command]
+pub async fn prune_backup_repository(target_id: String) -> Result<PruneReport, String> {
+    tokio::task::spawn_blocking(move || {
+        backups::prune_repository(&target_id)
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Validate the path of a local archive source
+fn validate_archive_source(source: &ArchiveSource) -> Result<(), String> {
+    match source {
+        ArchiveSource::File { path, .. } => validate_file_path(path).map_err(|e| e.to_string()),
+        ArchiveSource::Backup { .. } => Ok(()),
+    }
+}
+
+/// List the files in an export or backup archive without extracting it
+#[tauri::command]
+pub async fn list_archive_contents(source: ArchiveSource) -> Result<ArchiveListing, String> {
+    validate_archive_source(&source)?;
+    tokio::task::spawn_blocking(move || {
+        archive_files::list_contents(&source)
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Restore selected files or directories from an archive into a distribution
+#[tauri::command]
+pub async fn restore_archive_files_to_distro(
+    source: ArchiveSource,
+    paths: Vec<String>,
+    distro_name: String,
+    destination: String,
+) -> Result<FileRestoreReport, String> {
+    validate_archive_source(&source)?;
+    validate_distro_name(&distro_name).map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || {
+        archive_files::restore_to_distro(&source, &paths, &distro_name, &destination)
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Extract selected files or directories from an archive into a Windows folder
+#[tauri::command]
+pub async fn extract_archive_files(
+    source: ArchiveSource,
+    paths: Vec<String>,
+    destination: String,
+) -> Result<FileRestoreReport, String> {
+    validate_archive_source(&source)?;
+    validate_file_path(&destination).map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || {
+        archive_files::extract_to_folder(&source, &paths, &destination)
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Show what migrating user data from one distribution to another would copy
+#[tauri::command]
+pub async fn preview_data_migration(
+    source: String,
+    target: String,
+    selection: MigrationSelection,
+) -> Result<MigrationPreview, String> {
+    validate_distro_name(&source).map_err(|e| e.to_string())?;
+    validate_distro_name(&target).map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || {
+        migrate::preview_migration(&source, &target, &selection)
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Copy home directory, crontab and other selected paths between distributions
+#[tauri::command]
+pub async fn migrate_user_data(
+    source: String,
+    target: String,
+    selection: MigrationSelection,
+) -> Result<MigrationReport, String> {
+    validate_distro_name(&source).map_err(|e| e.to_string())?;
+    validate_distro_name(&target).map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || {
+        migrate::migrate_user_data(&source, &target, &selection)
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
 // Disk Mount commands
 
 #[tauri::command]
@@ -1815,6 +2826,7 @@ async fn mock_install_with_progress(
                     "mock-location",
                     "mock-tarball.tar",
                     wsl_version,
+                    None,
                 )
             }
         })
@@ -1896,6 +2908,9 @@ pub fn reset_mock_state_cmd() -> Result<(), String> {
         download::reset_mock_download();
         metadata::reset_mock_metadata();
         actions::reset_mock_actions();
+        snapshots::reset_mock_snapshots();
+        backups::reset_mock_backups();
+        backup_targets::reset_mock_credentials();
         Ok(())
     } else {
         Err("reset_mock_state is only available in mock mode".to_string())
@@ -2122,7 +3137,7 @@ pub fn open_store_review() -> Result<(), String> {
 // ===========================================================================
 
 use crate::wsl::distro_sources::{
-    self, DistroSource, ManifestPreview,
+    self, DistroSource, GeneratedManifest, ManifestEntryRequest, ManifestPreview,
 };
 
 /// Read the currently registered WSL distribution source from HKLM.
@@ -2177,6 +3192,25 @@ pub async fn clear_distro_source() -> Result<(), String> {
     .map_err(|e| format!("Task failed: {}", e))?
 }
 
+/// Generate a `ModernDistributions` manifest from catalog download entries
+/// and/or local tarballs, write it to `output_path`, and return its `file://`
+/// URL so it can be applied as a distribution source.
+#[tauri::command]
+pub async fn generate_distro_manifest(
+    entries: Vec<ManifestEntryRequest>,
+    output_path: String,
+) -> Result<GeneratedManifest, String> {
+    validate_file_path(&output_path).map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || {
+        let catalog = distro_catalog::load_catalog();
+        distro_sources::generate_manifest(&entries, &catalog, &output_path)
+            .map_err(AppError::from)
+            .map_err(String::from)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
 #[cfg(test)]
 mod store_review_tests {
     use super::*;
@@ -18,6 +18,9 @@ pub const CONFIG_FILE_SETTINGS: &str = "settings.json";
 /// Custom actions configuration file
 pub const CONFIG_FILE_ACTIONS: &str = "custom-actions.json";
 
+/// Backup schedules and run history
+pub const CONFIG_FILE_BACKUPS: &str = "backups.json";
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -33,5 +36,6 @@ mod tests {
     fn test_config_files_have_json_extension() {
         assert!(CONFIG_FILE_SETTINGS.ends_with(".json"));
         assert!(CONFIG_FILE_ACTIONS.ends_with(".json"));
+        assert!(CONFIG_FILE_BACKUPS.ends_with(".json"));
     }
 }
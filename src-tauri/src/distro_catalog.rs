@@ -4,47 +4,183 @@
 //! - Microsoft Store metadata (display info for `wsl --list --online` results)
 //! - Direct download distributions (rootfs URLs)
 //! - Container images (Podman/Docker)
+//!
+//! The merged catalog is built from three layers, lowest priority first:
+//! 1. Built-in defaults embedded in the binary
+//! 2. Subscribed remote catalogs (see `CatalogSubscription` in settings), in
+//!    list order. An entry from a subscription replaces a built-in entry with
+//!    the same ID; when two subscriptions share an ID the earlier one wins.
+//!    Every replaced or dropped entry is reported in `DistroCatalog::conflicts`.
+//! 3. Local user overrides (`distro-catalog.json`), which always win
+//!
+//! Subscribed catalogs are fetched into a per-subscription disk cache and only
+//! refreshed once their TTL has expired, so `load_catalog` never touches the network.
 
-use crate::utils::get_config_file;
+use crate::settings::{self, CatalogSubscription};
+use crate::utils::{get_config_dir, get_config_file};
+use crate::validation::validate_url;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// Config file name for user catalog overrides
 const CATALOG_CONFIG_FILE: &str = "distro-catalog.json";
 
+/// Directory (under the config dir) holding cached subscription catalogs
+const SUBSCRIPTION_CACHE_DIR: &str = "catalog-cache";
+
+/// Remote catalogs larger than this are rejected
+const MAX_REMOTE_CATALOG_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Provenance label for entries that come from the embedded defaults
+const BUILT_IN_SOURCE: &str = "built-in";
+
+/// Format version written to exported catalog files
+const CATALOG_EXPORT_FORMAT_VERSION: u32 = 1;
+
 /// Default catalog embedded in the binary
 const DEFAULT_CATALOG_JSON: &str = include_str!("default_catalog.json");
 
 /// Metadata for Microsoft Store distributions
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct MsStoreDistroInfo {
     pub description: String,
     #[serde(default = "default_true")]
     pub enabled: bool,
+    /// Subscription that supplied this entry (None = built-in or local)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subscription_id: Option<String>,
+}
+
+/// CPU architecture of a rootfs download (matches the manifest's `Amd64Url`/`Arm64Url`)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum DistroArch {
+    Amd64,
+    Arm64,
+}
+
+impl DistroArch {
+    /// Parse a Windows `PROCESSOR_ARCHITECTURE` value
+    fn from_processor_architecture(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_uppercase().as_str() {
+            "AMD64" | "X64" => Some(Self::Amd64),
+            "ARM64" => Some(Self::Arm64),
+            _ => None,
+        }
+    }
+
+    /// Architecture of the machine WSL runs on
+    ///
+    /// On Windows the environment is preferred over the build target, since
+    /// `PROCESSOR_ARCHITEW6432` reports the native architecture to WOW64 processes.
+    pub fn host() -> Option<Self> {
+        let from_env = ["PROCESSOR_ARCHITEW6432", "PROCESSOR_ARCHITECTURE"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find_map(|value| Self::from_processor_architecture(&value));
+        if cfg!(target_os = "windows") && from_env.is_some() {
+            return from_env;
+        }
+        if cfg!(target_arch = "x86_64") {
+            Some(Self::Amd64)
+        } else if cfg!(target_arch = "aarch64") {
+            Some(Self::Arm64)
+        } else {
+            None
+        }
+    }
+}
+
+/// Download URL and checksum for one architecture
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchDownload {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
 }
 
 /// Direct download distribution entry
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// When `architectures` is empty, `url`/`sha256` apply to every host. Otherwise
+/// the per-architecture map is authoritative and hosts without an entry cannot
+/// install the distro.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct DownloadDistro {
     pub id: String,
     pub name: String,
     pub description: String,
+    /// Architecture-independent download (optional when `architectures` is set)
+    #[serde(default)]
     pub url: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub size: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sha256: Option<String>,
+    /// Per-architecture downloads
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub architectures: BTreeMap<DistroArch, ArchDownload>,
     #[serde(default = "default_true")]
     pub enabled: bool,
     #[serde(default)]
     pub is_built_in: bool,
+    /// Subscription that supplied this entry (None = built-in or local).
+    /// Subscribed entries are read-only; edits are stored as local overrides.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subscription_id: Option<String>,
+    /// True when a local entry overrides a built-in or subscribed one
+    #[serde(default)]
+    pub has_local_override: bool,
+    /// Whether a download exists for the host architecture. Computed on
+    /// load and only sent to the frontend when false; never read from or
+    /// written to catalog files.
+    #[serde(skip_deserializing, default = "default_true", skip_serializing_if = "is_true")]
+    pub available: bool,
+}
+
+impl DownloadDistro {
+    /// Download to use on the given architecture, if any
+    pub fn download_for(&self, arch: Option<DistroArch>) -> Option<ArchDownload> {
+        if self.architectures.is_empty() {
+            return (!self.url.trim().is_empty()).then(|| ArchDownload {
+                url: self.url.clone(),
+                sha256: self.sha256.clone(),
+            });
+        }
+        arch.and_then(|a| self.architectures.get(&a)).cloned()
+    }
+
+    /// Check every download URL and checksum on the entry
+    pub fn validate_downloads(&self) -> Result<(), String> {
+        if self.architectures.is_empty() || !self.url.is_empty() {
+            validate_url(&self.url).map_err(|e| e.to_string())?;
+        }
+        validate_sha256(self.sha256.as_deref())?;
+        for (arch, download) in &self.architectures {
+            validate_url(&download.url).map_err(|e| format!("{:?}: {}", arch, e))?;
+            validate_sha256(download.sha256.as_deref()).map_err(|e| format!("{:?}: {}", arch, e))?;
+        }
+        Ok(())
+    }
+}
+
+fn validate_sha256(sha256: Option<&str>) -> Result<(), String> {
+    match sha256 {
+        Some(sum) if sum.len() != 64 || !sum.chars().all(|c| c.is_ascii_hexdigit()) => {
+            Err("sha256 must be 64 hexadecimal characters".to_string())
+        }
+        _ => Ok(()),
+    }
 }
 
 /// Container image entry
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ContainerImage {
     pub id: String,
@@ -55,20 +191,58 @@ pub struct ContainerImage {
     pub enabled: bool,
     #[serde(default)]
     pub is_built_in: bool,
+    /// Subscription that supplied this entry (None = built-in or local).
+    /// Subscribed entries are read-only; edits are stored as local overrides.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subscription_id: Option<String>,
+    /// True when a local entry overrides a built-in or subscribed one
+    #[serde(default)]
+    pub has_local_override: bool,
 }
 
 fn default_true() -> bool {
     true
 }
 
+fn is_true(value: &bool) -> bool {
+    *value
+}
+
+/// Kind of catalog entry, used when reporting merge conflicts
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum CatalogEntryKind {
+    MsStoreDistro,
+    DownloadDistro,
+    ContainerImage,
+}
+
+/// An entry that was replaced or dropped while merging subscribed catalogs
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogConflict {
+    pub kind: CatalogEntryKind,
+    pub id: String,
+    /// Source whose entry is used ("built-in" or a subscription ID)
+    pub kept_source: String,
+    /// Source whose entry was discarded
+    pub ignored_source: String,
+}
+
 /// Full distribution catalog
+///
+/// Missing sections default to empty so remote catalogs may publish only
+/// the sections they care about.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct DistroCatalog {
     pub version: String,
     pub ms_store_distros: HashMap<String, MsStoreDistroInfo>,
     pub download_distros: Vec<DownloadDistro>,
     pub container_images: Vec<ContainerImage>,
+    /// Conflicts resolved while merging subscriptions (empty outside the merged catalog)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<CatalogConflict>,
 }
 
 impl Default for DistroCatalog {
@@ -78,10 +252,112 @@ impl Default for DistroCatalog {
             ms_store_distros: HashMap::new(),
             download_distros: Vec::new(),
             container_images: Vec::new(),
+            conflicts: Vec::new(),
         }
     }
 }
 
+/// Provenance bookkeeping shared by download distros and container images
+trait CatalogEntry: Clone {
+    const KIND: CatalogEntryKind;
+    fn id(&self) -> &str;
+    fn is_built_in(&self) -> bool;
+    fn subscription_id(&self) -> Option<&str>;
+    fn set_provenance(&mut self, is_built_in: bool, subscription_id: Option<String>, has_local_override: bool);
+    /// Check an entry coming from an imported file
+    fn validate(&self) -> Result<(), String>;
+
+    /// Copy with provenance cleared, for comparing and exporting entry content
+    fn without_provenance(&self) -> Self {
+        let mut entry = self.clone();
+        entry.set_provenance(false, None, false);
+        entry
+    }
+
+    /// Label of the layer this entry came from
+    fn source_label(&self) -> &str {
+        self.subscription_id().unwrap_or(BUILT_IN_SOURCE)
+    }
+}
+
+impl CatalogEntry for DownloadDistro {
+    const KIND: CatalogEntryKind = CatalogEntryKind::DownloadDistro;
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn is_built_in(&self) -> bool {
+        self.is_built_in
+    }
+
+    fn subscription_id(&self) -> Option<&str> {
+        self.subscription_id.as_deref()
+    }
+
+    fn set_provenance(&mut self, is_built_in: bool, subscription_id: Option<String>, has_local_override: bool) {
+        self.is_built_in = is_built_in;
+        self.subscription_id = subscription_id;
+        self.has_local_override = has_local_override;
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        validate_entry_text("id", &self.id)?;
+        validate_entry_text("name", &self.name)?;
+        self.validate_downloads()
+    }
+
+    fn without_provenance(&self) -> Self {
+        let mut entry = self.clone();
+        entry.set_provenance(false, None, false);
+        entry.available = true;
+        entry
+    }
+}
+
+impl CatalogEntry for ContainerImage {
+    const KIND: CatalogEntryKind = CatalogEntryKind::ContainerImage;
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn is_built_in(&self) -> bool {
+        self.is_built_in
+    }
+
+    fn subscription_id(&self) -> Option<&str> {
+        self.subscription_id.as_deref()
+    }
+
+    fn set_provenance(&mut self, is_built_in: bool, subscription_id: Option<String>, has_local_override: bool) {
+        self.is_built_in = is_built_in;
+        self.subscription_id = subscription_id;
+        self.has_local_override = has_local_override;
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        validate_entry_text("id", &self.id)?;
+        validate_entry_text("name", &self.name)?;
+        validate_entry_text("image", &self.image)?;
+        if self.image.chars().any(char::is_whitespace) {
+            return Err("image reference cannot contain whitespace".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Require a non-empty field without control characters
+fn validate_entry_text(field: &str, value: &str) -> Result<(), String> {
+    if value.trim().is_empty() {
+        return Err(format!("{} is required", field));
+    }
+    if value.chars().any(|c| c.is_control()) {
+        return Err(format!("{} cannot contain control characters", field));
+    }
+    Ok(())
+}
+
 /// Load the default catalog embedded in the binary
 pub fn get_default_catalog() -> DistroCatalog {
     serde_json::from_str(DEFAULT_CATALOG_JSON).unwrap_or_default()
@@ -102,66 +378,175 @@ fn load_user_catalog() -> Option<DistroCatalog> {
 /// Save user catalog to config file
 fn save_user_catalog(catalog: &DistroCatalog) -> Result<(), String> {
     let path = get_config_file(CATALOG_CONFIG_FILE);
-    let content = serde_json::to_string_pretty(catalog)
+    // Availability depends on the host, so it is computed on every load
+    let mut catalog = catalog.clone();
+    for distro in &mut catalog.download_distros {
+        distro.available = true;
+    }
+    let content = serde_json::to_string_pretty(&catalog)
         .map_err(|e| format!("Failed to serialize catalog: {}", e))?;
     fs::write(&path, content).map_err(|e| format!("Failed to write catalog file: {}", e))
 }
 
-/// Load merged catalog (defaults + user overrides)
+/// Load merged catalog (defaults + cached subscriptions + user overrides)
 pub fn load_catalog() -> DistroCatalog {
-    let mut catalog = get_default_catalog();
+    load_catalog_with(load_user_catalog())
+}
+
+/// Merge the defaults and cached subscriptions with the given user layer
+fn load_catalog_with(user_catalog: Option<DistroCatalog>) -> DistroCatalog {
+    let subscribed = load_subscribed_catalogs(
+        &subscription_cache_dir(),
+        &settings::get_catalog_subscriptions(),
+    );
+    let mut catalog = merge_catalogs(get_default_catalog(), &subscribed, user_catalog);
+    mark_availability(&mut catalog, DistroArch::host());
+    catalog
+}
+
+/// Flag download distros that have no download for the host architecture
+fn mark_availability(catalog: &mut DistroCatalog, arch: Option<DistroArch>) {
+    for distro in &mut catalog.download_distros {
+        distro.available = distro.download_for(arch).is_some();
+    }
+}
 
+/// Merge the three catalog layers, recording provenance on every entry
+///
+/// `subscribed` holds `(subscription ID, catalog)` pairs in priority order.
+fn merge_catalogs(
+    mut catalog: DistroCatalog,
+    subscribed: &[(String, DistroCatalog)],
+    user_catalog: Option<DistroCatalog>,
+) -> DistroCatalog {
     // Mark all default entries as built-in
     for distro in &mut catalog.download_distros {
-        distro.is_built_in = true;
+        distro.set_provenance(true, None, false);
     }
     for image in &mut catalog.container_images {
-        image.is_built_in = true;
+        image.set_provenance(true, None, false);
+    }
+    for info in catalog.ms_store_distros.values_mut() {
+        info.subscription_id = None;
     }
 
+    let mut conflicts = Vec::new();
+    for (subscription_id, sub_catalog) in subscribed {
+        for (key, value) in &sub_catalog.ms_store_distros {
+            match catalog.ms_store_distros.get(key) {
+                Some(existing) if existing.subscription_id.is_some() => {
+                    conflicts.push(CatalogConflict {
+                        kind: CatalogEntryKind::MsStoreDistro,
+                        id: key.clone(),
+                        kept_source: existing.subscription_id.clone().unwrap_or_default(),
+                        ignored_source: subscription_id.clone(),
+                    });
+                    continue;
+                }
+                Some(_) => conflicts.push(CatalogConflict {
+                    kind: CatalogEntryKind::MsStoreDistro,
+                    id: key.clone(),
+                    kept_source: subscription_id.clone(),
+                    ignored_source: BUILT_IN_SOURCE.to_string(),
+                }),
+                None => {}
+            }
+            let mut info = value.clone();
+            info.subscription_id = Some(subscription_id.clone());
+            catalog.ms_store_distros.insert(key.clone(), info);
+        }
+
+        merge_subscribed_entries(
+            &mut catalog.download_distros,
+            &sub_catalog.download_distros,
+            subscription_id,
+            &mut conflicts,
+        );
+        merge_subscribed_entries(
+            &mut catalog.container_images,
+            &sub_catalog.container_images,
+            subscription_id,
+            &mut conflicts,
+        );
+    }
+    catalog.conflicts = conflicts;
+
     // Merge user overrides if present
-    if let Some(user_catalog) = load_user_catalog() {
-        // Merge MS Store distros (user entries override defaults)
-        for (key, value) in user_catalog.ms_store_distros {
+    if let Some(user_catalog) = user_catalog {
+        // Merge MS Store distros (user entries override defaults and subscriptions)
+        for (key, mut value) in user_catalog.ms_store_distros {
+            value.subscription_id = catalog
+                .ms_store_distros
+                .get(&key)
+                .and_then(|existing| existing.subscription_id.clone());
             catalog.ms_store_distros.insert(key, value);
         }
 
-        // Merge download distros (user entries override by ID, or add new)
-        for user_distro in user_catalog.download_distros {
-            if let Some(existing) = catalog
-                .download_distros
-                .iter_mut()
-                .find(|d| d.id == user_distro.id)
-            {
-                // Override existing (keep is_built_in from default)
-                let is_built_in = existing.is_built_in;
-                *existing = user_distro;
-                existing.is_built_in = is_built_in;
-            } else {
-                // Add new user entry
-                catalog.download_distros.push(user_distro);
-            }
-        }
+        merge_user_entries(&mut catalog.download_distros, user_catalog.download_distros);
+        merge_user_entries(&mut catalog.container_images, user_catalog.container_images);
+    }
 
-        // Merge container images (user entries override by ID, or add new)
-        for user_image in user_catalog.container_images {
-            if let Some(existing) = catalog
-                .container_images
-                .iter_mut()
-                .find(|i| i.id == user_image.id)
-            {
-                // Override existing (keep is_built_in from default)
-                let is_built_in = existing.is_built_in;
-                *existing = user_image;
-                existing.is_built_in = is_built_in;
-            } else {
-                // Add new user entry
-                catalog.container_images.push(user_image);
+    catalog
+}
+
+/// Apply one subscription's entries on top of the built-ins and higher-priority subscriptions
+fn merge_subscribed_entries<T: CatalogEntry>(
+    entries: &mut Vec<T>,
+    incoming: &[T],
+    subscription_id: &str,
+    conflicts: &mut Vec<CatalogConflict>,
+) {
+    for entry in incoming {
+        // Invalid entries are reported in the subscription status instead
+        if entry.validate().is_err() {
+            continue;
+        }
+        let mut entry = entry.clone();
+        match entries.iter_mut().find(|e| e.id() == entry.id()) {
+            Some(existing) if existing.subscription_id().is_some() => {
+                // An earlier subscription already supplied this ID
+                conflicts.push(CatalogConflict {
+                    kind: T::KIND,
+                    id: entry.id().to_string(),
+                    kept_source: existing.source_label().to_string(),
+                    ignored_source: subscription_id.to_string(),
+                });
+            }
+            Some(existing) => {
+                conflicts.push(CatalogConflict {
+                    kind: T::KIND,
+                    id: entry.id().to_string(),
+                    kept_source: subscription_id.to_string(),
+                    ignored_source: BUILT_IN_SOURCE.to_string(),
+                });
+                entry.set_provenance(true, Some(subscription_id.to_string()), false);
+                *existing = entry;
+            }
+            None => {
+                entry.set_provenance(false, Some(subscription_id.to_string()), false);
+                entries.push(entry);
             }
         }
     }
+}
 
-    catalog
+/// Apply local user entries (override by ID, or add new)
+fn merge_user_entries<T: CatalogEntry>(entries: &mut Vec<T>, user_entries: Vec<T>) {
+    for mut user_entry in user_entries {
+        if let Some(existing) = entries.iter_mut().find(|e| e.id() == user_entry.id()) {
+            // Override existing (keep provenance of the entry being overridden)
+            user_entry.set_provenance(
+                existing.is_built_in(),
+                existing.subscription_id().map(str::to_string),
+                true,
+            );
+            *existing = user_entry;
+        } else {
+            // Add new user entry
+            user_entry.set_provenance(false, None, false);
+            entries.push(user_entry);
+        }
+    }
 }
 
 /// Get the full catalog
@@ -244,18 +629,19 @@ pub fn update_download_distro(distro: DownloadDistro) -> Result<DistroCatalog, S
 /// Delete a download distro (only user-added entries can be fully deleted)
 pub fn delete_download_distro(id: &str) -> Result<DistroCatalog, String> {
     let catalog = load_catalog();
-    let is_built_in = catalog
-        .download_distros
-        .iter()
-        .find(|d| d.id == id)
-        .map(|d| d.is_built_in)
-        .unwrap_or(false);
-
-    if is_built_in {
-        return Err(format!(
-            "Cannot delete built-in distro '{}'. You can disable it instead.",
-            id
-        ));
+    if let Some(existing) = catalog.download_distros.iter().find(|d| d.id == id) {
+        if existing.is_built_in {
+            return Err(format!(
+                "Cannot delete built-in distro '{}'. You can disable it instead.",
+                id
+            ));
+        }
+        if let Some(subscription_id) = &existing.subscription_id {
+            return Err(format!(
+                "Cannot delete distro '{}' from subscription '{}'. You can disable it instead.",
+                id, subscription_id
+            ));
+        }
     }
 
     let mut user_catalog = load_user_catalog().unwrap_or_default();
@@ -305,18 +691,19 @@ pub fn update_container_image(image: ContainerImage) -> Result<DistroCatalog, St
 /// Delete a container image (only user-added entries can be fully deleted)
 pub fn delete_container_image(id: &str) -> Result<DistroCatalog, String> {
     let catalog = load_catalog();
-    let is_built_in = catalog
-        .container_images
-        .iter()
-        .find(|i| i.id == id)
-        .map(|i| i.is_built_in)
-        .unwrap_or(false);
-
-    if is_built_in {
-        return Err(format!(
-            "Cannot delete built-in image '{}'. You can disable it instead.",
-            id
-        ));
+    if let Some(existing) = catalog.container_images.iter().find(|i| i.id == id) {
+        if existing.is_built_in {
+            return Err(format!(
+                "Cannot delete built-in image '{}'. You can disable it instead.",
+                id
+            ));
+        }
+        if let Some(subscription_id) = &existing.subscription_id {
+            return Err(format!(
+                "Cannot delete image '{}' from subscription '{}'. You can disable it instead.",
+                id, subscription_id
+            ));
+        }
     }
 
     let mut user_catalog = load_user_catalog().unwrap_or_default();
@@ -347,35 +734,550 @@ pub fn delete_ms_store_distro(distro_id: &str) -> Result<DistroCatalog, String>
     Ok(load_catalog())
 }
 
-// ==================== Helper Functions ====================
+// ==================== Import / Export ====================
 
-/// Get download URL for a distro by ID
-pub fn get_download_url(distro_id: &str) -> Option<String> {
-    let catalog = load_catalog();
-    catalog
+/// Versioned envelope for a shareable catalog file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogExport {
+    pub format_version: u32,
+    pub exported_at: String,
+    pub catalog: DistroCatalog,
+}
+
+/// IDs to include in an export (sections left empty are not exported)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CatalogSelection {
+    pub ms_store_distros: Vec<String>,
+    pub download_distros: Vec<String>,
+    pub container_images: Vec<String>,
+}
+
+/// An entry reported by an import
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogImportEntry {
+    pub kind: CatalogEntryKind,
+    pub id: String,
+    /// Why the entry was skipped (None for added/updated entries)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Outcome of a catalog import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogImportReport {
+    pub added: Vec<CatalogImportEntry>,
+    pub updated: Vec<CatalogImportEntry>,
+    pub skipped: Vec<CatalogImportEntry>,
+    /// Merged catalog after the import
+    pub catalog: DistroCatalog,
+}
+
+/// Build the export envelope from a merged catalog, optionally limited to a selection
+fn build_export(catalog: DistroCatalog, selection: Option<&CatalogSelection>) -> CatalogExport {
+    let mut exported = DistroCatalog {
+        version: catalog.version,
+        ..Default::default()
+    };
+
+    for (key, mut info) in catalog.ms_store_distros {
+        if selection.is_none_or(|s| s.ms_store_distros.contains(&key)) {
+            info.subscription_id = None;
+            exported.ms_store_distros.insert(key, info);
+        }
+    }
+    exported.download_distros = catalog
         .download_distros
         .iter()
-        .find(|d| d.id == distro_id && d.enabled)
-        .map(|d| d.url.clone())
+        .filter(|d| selection.is_none_or(|s| s.download_distros.contains(&d.id)))
+        .map(CatalogEntry::without_provenance)
+        .collect();
+    exported.container_images = catalog
+        .container_images
+        .iter()
+        .filter(|i| selection.is_none_or(|s| s.container_images.contains(&i.id)))
+        .map(CatalogEntry::without_provenance)
+        .collect();
+
+    CatalogExport {
+        format_version: CATALOG_EXPORT_FORMAT_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        catalog: exported,
+    }
 }
 
-/// Get checksum for a distro by ID
-pub fn get_download_checksum(distro_id: &str) -> Option<String> {
+/// Export the merged catalog (or the selected entries) as versioned JSON
+pub fn export_catalog(selection: Option<&CatalogSelection>) -> Result<String, String> {
+    let export = build_export(load_catalog(), selection);
+    serde_json::to_string_pretty(&export).map_err(|e| format!("Failed to export catalog: {}", e))
+}
+
+/// Export the catalog to a file at the specified path
+pub fn export_catalog_to_file(path: &str, selection: Option<&CatalogSelection>) -> Result<(), String> {
+    let json = export_catalog(selection)?;
+    fs::write(path, json).map_err(|e| format!("Failed to write file: {}", e))
+}
+
+/// Parse and schema-check an exported catalog file
+fn parse_catalog_export(json: &str) -> Result<CatalogExport, String> {
+    let export: CatalogExport =
+        serde_json::from_str(json).map_err(|e| format!("Failed to parse catalog: {}", e))?;
+    if export.format_version == 0 || export.format_version > CATALOG_EXPORT_FORMAT_VERSION {
+        return Err(format!(
+            "Unsupported catalog format version {} (expected {})",
+            export.format_version, CATALOG_EXPORT_FORMAT_VERSION
+        ));
+    }
+    Ok(export)
+}
+
+fn skipped(kind: CatalogEntryKind, id: &str, reason: impl Into<String>) -> CatalogImportEntry {
+    CatalogImportEntry {
+        kind,
+        id: id.to_string(),
+        reason: Some(reason.into()),
+    }
+}
+
+/// Apply imported entries of one kind to the user catalog
+///
+/// Entries are classified against `current`: new IDs are added, changed ones
+/// become local overrides, and identical or invalid ones are skipped without
+/// touching the user catalog.
+fn import_entries<T: CatalogEntry + PartialEq>(
+    current: &[T],
+    user_entries: &mut Vec<T>,
+    imported: Vec<T>,
+    report: &mut CatalogImportReport,
+) {
+    let mut seen: Vec<String> = Vec::new();
+    for entry in imported {
+        let entry = entry.without_provenance();
+        let id = entry.id().to_string();
+        if let Err(reason) = entry.validate() {
+            report.skipped.push(skipped(T::KIND, &id, reason));
+            continue;
+        }
+        if seen.contains(&id) {
+            report.skipped.push(skipped(T::KIND, &id, "duplicate ID in import file"));
+            continue;
+        }
+        seen.push(id.clone());
+
+        let item = CatalogImportEntry {
+            kind: T::KIND,
+            id: id.clone(),
+            reason: None,
+        };
+        match current.iter().find(|e| e.id() == id) {
+            None => report.added.push(item),
+            Some(existing) if existing.without_provenance() == entry => {
+                report.skipped.push(CatalogImportEntry {
+                    reason: Some("unchanged".to_string()),
+                    ..item
+                });
+                continue;
+            }
+            Some(_) => report.updated.push(item),
+        }
+
+        match user_entries.iter_mut().find(|e| e.id() == id) {
+            Some(user_entry) => *user_entry = entry,
+            None => user_entries.push(entry),
+        }
+    }
+}
+
+/// Apply an import to the user catalog, returning the new user catalog and the report
+///
+/// `base` is the catalog without local entries. With `merge` the existing
+/// local entries are kept and entries are compared against the merged
+/// catalog; otherwise the imported catalog replaces all local overrides and
+/// additions, so entries are compared against `base` alone.
+fn apply_import(
+    base: &DistroCatalog,
+    user_catalog: DistroCatalog,
+    imported: DistroCatalog,
+    merge: bool,
+) -> (DistroCatalog, CatalogImportReport) {
+    let (current, mut user_catalog) = if merge {
+        (merge_catalogs(base.clone(), &[], Some(user_catalog.clone())), user_catalog)
+    } else {
+        (base.clone(), DistroCatalog::default())
+    };
+    let mut report = CatalogImportReport {
+        added: Vec::new(),
+        updated: Vec::new(),
+        skipped: Vec::new(),
+        catalog: DistroCatalog::default(),
+    };
+
+    let mut ms_store: Vec<_> = imported.ms_store_distros.into_iter().collect();
+    ms_store.sort_by(|a, b| a.0.cmp(&b.0));
+    for (key, mut info) in ms_store {
+        info.subscription_id = None;
+        if let Err(reason) = validate_entry_text("id", &key) {
+            report.skipped.push(skipped(CatalogEntryKind::MsStoreDistro, &key, reason));
+            continue;
+        }
+        let existing = current.ms_store_distros.get(&key).map(|e| MsStoreDistroInfo {
+            subscription_id: None,
+            ..e.clone()
+        });
+        let item = CatalogImportEntry {
+            kind: CatalogEntryKind::MsStoreDistro,
+            id: key.clone(),
+            reason: None,
+        };
+        match existing {
+            None => report.added.push(item),
+            Some(e) if e != info => report.updated.push(item),
+            Some(_) => {
+                report.skipped.push(CatalogImportEntry {
+                    reason: Some("unchanged".to_string()),
+                    ..item
+                });
+                continue;
+            }
+        }
+        user_catalog.ms_store_distros.insert(key, info);
+    }
+
+    import_entries(
+        &current.download_distros,
+        &mut user_catalog.download_distros,
+        imported.download_distros,
+        &mut report,
+    );
+    import_entries(
+        &current.container_images,
+        &mut user_catalog.container_images,
+        imported.container_images,
+        &mut report,
+    );
+
+    (user_catalog, report)
+}
+
+/// Import a catalog from JSON, merging into or replacing the local entries
+pub fn import_catalog(json: &str, merge: bool) -> Result<CatalogImportReport, String> {
+    let export = parse_catalog_export(json)?;
+    let (user_catalog, mut report) = apply_import(
+        &load_catalog_with(None),
+        load_user_catalog().unwrap_or_default(),
+        export.catalog,
+        merge,
+    );
+    save_user_catalog(&user_catalog)?;
+    report.catalog = load_catalog();
+    Ok(report)
+}
+
+/// Import a catalog from a file at the specified path
+pub fn import_catalog_from_file(path: &str, merge: bool) -> Result<CatalogImportReport, String> {
+    let json = fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))?;
+    import_catalog(&json, merge)
+}
+
+// ==================== Subscriptions ====================
+
+/// Fetch state of a subscribed catalog, as shown in the UI
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogSubscriptionStatus {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    pub enabled: bool,
+    /// RFC 3339 timestamp of the last successful fetch
+    pub last_fetched: Option<String>,
+    /// True when the cached copy is missing or older than the subscription's TTL
+    pub stale: bool,
+    /// Error from the most recent fetch attempt (the last good copy stays in use)
+    pub last_error: Option<String>,
+    pub download_distro_count: usize,
+    pub container_image_count: usize,
+    /// Entries left out of the catalog because they failed validation
+    pub invalid_entries: Vec<CatalogImportEntry>,
+}
+
+/// On-disk cache entry for one subscription
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedSubscription {
+    /// URL the catalog was fetched from; a changed URL invalidates the cache
+    url: String,
+    #[serde(default)]
+    fetched_at: Option<String>,
+    #[serde(default)]
+    last_error: Option<String>,
+    #[serde(default)]
+    catalog: Option<DistroCatalog>,
+}
+
+impl CachedSubscription {
+    fn is_stale(&self, ttl_hours: u32, now: chrono::DateTime<chrono::Utc>) -> bool {
+        let Some(fetched_at) = self
+            .fetched_at
+            .as_deref()
+            .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        else {
+            return true;
+        };
+        now.signed_duration_since(fetched_at) >= chrono::Duration::hours(i64::from(ttl_hours))
+    }
+}
+
+fn subscription_cache_dir() -> PathBuf {
+    get_config_dir().join(SUBSCRIPTION_CACHE_DIR)
+}
+
+/// Cache file for a subscription (the ID is sanitized so it is always a plain file name)
+fn subscription_cache_path(cache_dir: &Path, subscription_id: &str) -> PathBuf {
+    let file_stem: String = subscription_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    cache_dir.join(format!("{}.json", file_stem))
+}
+
+/// Read a subscription's cache, ignoring it if it was fetched from a different URL
+fn read_subscription_cache(cache_dir: &Path, subscription: &CatalogSubscription) -> Option<CachedSubscription> {
+    fs::read_to_string(subscription_cache_path(cache_dir, &subscription.id))
+        .ok()
+        .and_then(|content| serde_json::from_str::<CachedSubscription>(&content).ok())
+        .filter(|cached| cached.url == subscription.url)
+}
+
+fn write_subscription_cache(
+    cache_dir: &Path,
+    subscription_id: &str,
+    cached: &CachedSubscription,
+) -> Result<(), String> {
+    fs::create_dir_all(cache_dir)
+        .map_err(|e| format!("Failed to create catalog cache directory: {}", e))?;
+    let content = serde_json::to_string_pretty(cached)
+        .map_err(|e| format!("Failed to serialize catalog cache: {}", e))?;
+    fs::write(subscription_cache_path(cache_dir, subscription_id), content)
+        .map_err(|e| format!("Failed to write catalog cache: {}", e))
+}
+
+/// Cached catalogs of all enabled subscriptions, in priority order
+fn load_subscribed_catalogs(
+    cache_dir: &Path,
+    subscriptions: &[CatalogSubscription],
+) -> Vec<(String, DistroCatalog)> {
+    subscriptions
+        .iter()
+        .filter(|s| s.enabled)
+        .filter_map(|s| {
+            read_subscription_cache(cache_dir, s)
+                .and_then(|cached| cached.catalog)
+                .map(|catalog| (s.id.clone(), catalog))
+        })
+        .collect()
+}
+
+/// Convert a file:// URL to a local or UNC path
+///
+/// `file:///C:/share/catalog.json` -> `C:/share/catalog.json`
+/// `file://server/share/catalog.json` -> `\\server\share\catalog.json`
+pub(crate) fn file_url_to_path(rest: &str) -> PathBuf {
+    if let Some(local) = rest.strip_prefix('/') {
+        // file:///C:/... (Windows drive) or file:////server/share (UNC with empty host)
+        if let Some(unc) = local.strip_prefix('/') {
+            return PathBuf::from(format!(r"\\{}", unc.trim_start_matches('/').replace('/', "\\")));
+        }
+        if local.as_bytes().get(1) == Some(&b':') {
+            return PathBuf::from(local);
+        }
+        return PathBuf::from(rest);
+    }
+    if rest.to_ascii_lowercase().starts_with("localhost/") {
+        return file_url_to_path(&rest["localhost".len()..]);
+    }
+    PathBuf::from(format!(r"\\{}", rest.replace('/', "\\")))
+}
+
+/// Fetch and parse a remote catalog (http://, https://, or file://)
+pub fn fetch_remote_catalog(url: &str) -> Result<DistroCatalog, String> {
+    let url = url.trim();
+    let lower = url.to_lowercase();
+    let body = if lower.starts_with("file://") {
+        let path = file_url_to_path(&url["file://".len()..]);
+        let meta = fs::metadata(&path)
+            .map_err(|e| format!("Failed to read catalog file {}: {}", path.display(), e))?;
+        if meta.len() > MAX_REMOTE_CATALOG_BYTES {
+            return Err(format!(
+                "Catalog file is {} bytes, exceeds {} byte limit",
+                meta.len(),
+                MAX_REMOTE_CATALOG_BYTES
+            ));
+        }
+        fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read catalog file {}: {}", path.display(), e))?
+    } else if lower.starts_with("http://") || lower.starts_with("https://") {
+        let client = crate::http_client::blocking_client(Some(Duration::from_secs(15)))
+            .map_err(|e| e.to_string())?;
+        let resp = client
+            .get(url)
+            .send()
+            .map_err(|e| format!("Failed to fetch catalog: {}", e))?;
+        if !resp.status().is_success() {
+            return Err(format!("Catalog fetch returned HTTP {}", resp.status()));
+        }
+        let mut buf = Vec::new();
+        resp.take(MAX_REMOTE_CATALOG_BYTES + 1)
+            .read_to_end(&mut buf)
+            .map_err(|e| format!("Failed to read catalog body: {}", e))?;
+        if buf.len() as u64 > MAX_REMOTE_CATALOG_BYTES {
+            return Err(format!(
+                "Catalog body exceeds {} byte limit",
+                MAX_REMOTE_CATALOG_BYTES
+            ));
+        }
+        String::from_utf8(buf).map_err(|e| format!("Catalog body is not valid UTF-8: {}", e))?
+    } else {
+        return Err(format!(
+            "Unsupported catalog URL scheme: '{}' (expected http://, https://, or file://)",
+            url
+        ));
+    };
+
+    serde_json::from_str(&body).map_err(|e| format!("Invalid catalog JSON: {}", e))
+}
+
+fn subscription_status(
+    subscription: &CatalogSubscription,
+    cached: Option<&CachedSubscription>,
+    now: chrono::DateTime<chrono::Utc>,
+) -> CatalogSubscriptionStatus {
+    let catalog = cached.and_then(|c| c.catalog.as_ref());
+    CatalogSubscriptionStatus {
+        id: subscription.id.clone(),
+        name: subscription.name.clone(),
+        url: subscription.url.clone(),
+        enabled: subscription.enabled,
+        last_fetched: cached.and_then(|c| c.fetched_at.clone()),
+        stale: cached.is_none_or(|c| c.is_stale(subscription.cache_ttl_hours, now)),
+        last_error: cached.and_then(|c| c.last_error.clone()),
+        download_distro_count: catalog.map_or(0, |c| count_valid(&c.download_distros)),
+        container_image_count: catalog.map_or(0, |c| count_valid(&c.container_images)),
+        invalid_entries: catalog.map_or_else(Vec::new, |c| {
+            let mut invalid = invalid_entries(&c.download_distros);
+            invalid.extend(invalid_entries(&c.container_images));
+            invalid
+        }),
+    }
+}
+
+fn count_valid<T: CatalogEntry>(entries: &[T]) -> usize {
+    entries.iter().filter(|e| e.validate().is_ok()).count()
+}
+
+/// Entries of a subscribed catalog that `merge_subscribed_entries` leaves out
+fn invalid_entries<T: CatalogEntry>(entries: &[T]) -> Vec<CatalogImportEntry> {
+    entries
+        .iter()
+        .filter_map(|e| e.validate().err().map(|reason| skipped(T::KIND, e.id(), reason)))
+        .collect()
+}
+
+/// Refetch enabled subscriptions whose cache has expired (or all of them when `force`)
+///
+/// A failed fetch keeps the previous copy and records the error on the cache entry.
+fn refresh_subscriptions_in(
+    cache_dir: &Path,
+    subscriptions: &[CatalogSubscription],
+    force: bool,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Vec<CatalogSubscriptionStatus> {
+    subscriptions
+        .iter()
+        .map(|subscription| {
+            let mut cached = read_subscription_cache(cache_dir, subscription);
+            let needs_fetch = subscription.enabled
+                && (force || cached.as_ref().is_none_or(|c| c.is_stale(subscription.cache_ttl_hours, now)));
+
+            if needs_fetch {
+                let mut entry = cached.take().unwrap_or_else(|| CachedSubscription {
+                    url: subscription.url.clone(),
+                    ..Default::default()
+                });
+                match fetch_remote_catalog(&subscription.url) {
+                    Ok(catalog) => {
+                        entry.catalog = Some(catalog);
+                        entry.fetched_at = Some(now.to_rfc3339());
+                        entry.last_error = None;
+                    }
+                    Err(e) => {
+                        log::warn!("Failed to refresh catalog subscription '{}': {}", subscription.id, e);
+                        entry.last_error = Some(e);
+                    }
+                }
+                if let Err(e) = write_subscription_cache(cache_dir, &subscription.id, &entry) {
+                    log::warn!("{}", e);
+                }
+                cached = Some(entry);
+            }
+
+            subscription_status(subscription, cached.as_ref(), now)
+        })
+        .collect()
+}
+
+/// Refresh expired subscriptions (or all enabled ones when `force`) and report their status
+pub fn refresh_subscriptions(force: bool) -> Vec<CatalogSubscriptionStatus> {
+    refresh_subscriptions_in(
+        &subscription_cache_dir(),
+        &settings::get_catalog_subscriptions(),
+        force,
+        chrono::Utc::now(),
+    )
+}
+
+/// Report the cached state of every subscription without fetching
+pub fn get_subscription_status() -> Vec<CatalogSubscriptionStatus> {
+    let cache_dir = subscription_cache_dir();
+    let now = chrono::Utc::now();
+    settings::get_catalog_subscriptions()
+        .iter()
+        .map(|s| subscription_status(s, read_subscription_cache(&cache_dir, s).as_ref(), now))
+        .collect()
+}
+
+// ==================== Helper Functions ====================
+
+/// Get the host-architecture download for an enabled distro by ID
+fn get_host_download(distro_id: &str) -> Option<ArchDownload> {
     let catalog = load_catalog();
     catalog
         .download_distros
         .iter()
         .find(|d| d.id == distro_id && d.enabled)
-        .and_then(|d| d.sha256.clone())
+        .and_then(|d| d.download_for(DistroArch::host()))
+}
+
+/// Get download URL for a distro by ID (for the host architecture)
+pub fn get_download_url(distro_id: &str) -> Option<String> {
+    get_host_download(distro_id).map(|d| d.url)
+}
+
+/// Get checksum for a distro by ID (for the host architecture)
+pub fn get_download_checksum(distro_id: &str) -> Option<String> {
+    get_host_download(distro_id).and_then(|d| d.sha256)
 }
 
-/// Get list of enabled download distro IDs
+/// Get list of enabled download distro IDs installable on the host architecture
 pub fn list_enabled_download_distros() -> Vec<String> {
     let catalog = load_catalog();
     catalog
         .download_distros
         .iter()
-        .filter(|d| d.enabled)
+        .filter(|d| d.enabled && d.available)
         .map(|d| d.id.clone())
         .collect()
 }
@@ -398,8 +1300,537 @@ mod tests {
         assert!(url.is_some());
         assert!(url.unwrap().contains("ubuntu"));
     }
-}
 
+    fn download(id: &str, url: &str) -> DownloadDistro {
+        DownloadDistro {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            url: url.to_string(),
+            size: None,
+            sha256: None,
+            architectures: BTreeMap::new(),
+            enabled: true,
+            is_built_in: false,
+            subscription_id: None,
+            has_local_override: false,
+            available: true,
+        }
+    }
+
+    fn catalog_with(download_distros: Vec<DownloadDistro>) -> DistroCatalog {
+        DistroCatalog {
+            download_distros,
+            ..Default::default()
+        }
+    }
+
+    fn subscription(id: &str, url: &str) -> CatalogSubscription {
+        CatalogSubscription {
+            id: id.to_string(),
+            name: id.to_string(),
+            url: url.to_string(),
+            enabled: true,
+            cache_ttl_hours: 24,
+        }
+    }
+
+    fn test_dir(name: &str) -> PathBuf {
+        crate::utils::unique_temp_dir(&format!("catalog-{}", name))
+    }
+
+    fn file_url(path: &Path) -> String {
+        format!("file://{}", path.display())
+    }
+
+    #[test]
+    fn test_merge_subscription_adds_entries_with_provenance() {
+        let defaults = catalog_with(vec![download("Builtin", "https://a/builtin.tar")]);
+        let subscribed = vec![(
+            "team".to_string(),
+            catalog_with(vec![download("TeamDistro", "https://team/distro.tar")]),
+        )];
+
+        let merged = merge_catalogs(defaults, &subscribed, None);
+
+        let team = merged.download_distros.iter().find(|d| d.id == "TeamDistro").unwrap();
+        assert_eq!(team.subscription_id.as_deref(), Some("team"));
+        assert!(!team.is_built_in);
+        let builtin = merged.download_distros.iter().find(|d| d.id == "Builtin").unwrap();
+        assert!(builtin.is_built_in);
+        assert!(builtin.subscription_id.is_none());
+        assert!(merged.conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_merge_subscription_replaces_builtin_and_reports_conflict() {
+        let defaults = catalog_with(vec![download("Ubuntu", "https://public/ubuntu.tar")]);
+        let subscribed = vec![(
+            "team".to_string(),
+            catalog_with(vec![download("Ubuntu", "https://mirror/ubuntu.tar")]),
+        )];
+
+        let merged = merge_catalogs(defaults, &subscribed, None);
+
+        let ubuntu = &merged.download_distros[0];
+        assert_eq!(ubuntu.url, "https://mirror/ubuntu.tar");
+        assert!(ubuntu.is_built_in);
+        assert_eq!(ubuntu.subscription_id.as_deref(), Some("team"));
+        assert_eq!(
+            merged.conflicts,
+            vec![CatalogConflict {
+                kind: CatalogEntryKind::DownloadDistro,
+                id: "Ubuntu".to_string(),
+                kept_source: "team".to_string(),
+                ignored_source: BUILT_IN_SOURCE.to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_invalid_subscribed_entries_are_reported_not_merged() {
+        let dir = test_dir("invalid");
+        let source = dir.join("team.json");
+        let cache_dir = dir.join("cache");
+        let mut bad_sum = download("BadSum", "https://team/sum.tar");
+        bad_sum.sha256 = Some("not-a-sum".to_string());
+        let remote = catalog_with(vec![
+            download("Good", "https://team/good.tar"),
+            download("FileUrl", "file:///C:/Windows/evil.tar"),
+            bad_sum,
+        ]);
+        fs::write(&source, serde_json::to_string(&remote).unwrap()).unwrap();
+        let subs = vec![subscription("team", &file_url(&source))];
+
+        let status = refresh_subscriptions_in(&cache_dir, &subs, true, chrono::Utc::now());
+        assert_eq!(status[0].download_distro_count, 1);
+        let invalid: Vec<&str> = status[0].invalid_entries.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(invalid, ["FileUrl", "BadSum"]);
+        assert!(status[0].invalid_entries.iter().all(|e| e.reason.is_some()));
 
+        let merged = merge_catalogs(
+            DistroCatalog::default(),
+            &load_subscribed_catalogs(&cache_dir, &subs),
+            None,
+        );
+        let ids: Vec<&str> = merged.download_distros.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(ids, ["Good"]);
 
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_merge_earlier_subscription_wins() {
+        let subscribed = vec![
+            ("first".to_string(), catalog_with(vec![download("Shared", "https://first/x.tar")])),
+            ("second".to_string(), catalog_with(vec![download("Shared", "https://second/x.tar")])),
+        ];
+
+        let merged = merge_catalogs(DistroCatalog::default(), &subscribed, None);
+
+        assert_eq!(merged.download_distros.len(), 1);
+        assert_eq!(merged.download_distros[0].url, "https://first/x.tar");
+        assert_eq!(merged.conflicts.len(), 1);
+        assert_eq!(merged.conflicts[0].kept_source, "first");
+        assert_eq!(merged.conflicts[0].ignored_source, "second");
+    }
+
+    #[test]
+    fn test_merge_local_override_keeps_subscription_provenance() {
+        let subscribed = vec![(
+            "team".to_string(),
+            catalog_with(vec![download("TeamDistro", "https://team/distro.tar")]),
+        )];
+        let mut local = download("TeamDistro", "https://team/distro.tar");
+        local.enabled = false;
+        // Stale provenance in the user file must not leak into the merged catalog
+        local.subscription_id = Some("bogus".to_string());
+        let user = catalog_with(vec![local, download("Mine", "https://me/mine.tar")]);
+
+        let merged = merge_catalogs(DistroCatalog::default(), &subscribed, Some(user));
+
+        let team = merged.download_distros.iter().find(|d| d.id == "TeamDistro").unwrap();
+        assert!(!team.enabled);
+        assert!(team.has_local_override);
+        assert_eq!(team.subscription_id.as_deref(), Some("team"));
+        let mine = merged.download_distros.iter().find(|d| d.id == "Mine").unwrap();
+        assert!(mine.subscription_id.is_none());
+        assert!(!mine.has_local_override);
+    }
+
+    #[test]
+    fn test_remote_catalog_sections_are_optional() {
+        let catalog: DistroCatalog =
+            serde_json::from_str(r#"{"containerImages":[{"id":"x","name":"X","description":"","image":"x:1"}]}"#)
+                .unwrap();
+        assert_eq!(catalog.container_images.len(), 1);
+        assert!(catalog.download_distros.is_empty());
+    }
+
+    #[test]
+    fn test_cache_staleness() {
+        let now = chrono::Utc::now();
+        let fresh = CachedSubscription {
+            fetched_at: Some((now - chrono::Duration::hours(1)).to_rfc3339()),
+            ..Default::default()
+        };
+        assert!(!fresh.is_stale(24, now));
+        assert!(fresh.is_stale(1, now));
+        assert!(CachedSubscription::default().is_stale(24, now));
+    }
+
+    #[test]
+    fn test_cache_path_is_sanitized() {
+        let path = subscription_cache_path(Path::new("cache"), "../team catalog");
+        assert_eq!(path, Path::new("cache").join("___team_catalog.json"));
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_file_url_to_path() {
+        assert_eq!(file_url_to_path("/tmp/catalog.json"), PathBuf::from("/tmp/catalog.json"));
+        assert_eq!(file_url_to_path("/C:/share/catalog.json"), PathBuf::from("C:/share/catalog.json"));
+        assert_eq!(
+            file_url_to_path("server/share/catalog.json"),
+            PathBuf::from(r"\\server\share\catalog.json")
+        );
+        assert_eq!(
+            file_url_to_path("//server/share/catalog.json"),
+            PathBuf::from(r"\\server\share\catalog.json")
+        );
+    }
+
+    #[test]
+    fn test_fetch_remote_catalog_rejects_unknown_scheme() {
+        let err = fetch_remote_catalog("ftp://example.com/catalog.json").unwrap_err();
+        assert!(err.contains("Unsupported catalog URL scheme"));
+    }
+
+    #[test]
+    fn test_refresh_uses_ttl_and_keeps_last_good_copy() {
+        let dir = test_dir("refresh");
+        let source = dir.join("team.json");
+        let cache_dir = dir.join("cache");
+        fs::write(
+            &source,
+            serde_json::to_string(&catalog_with(vec![download("TeamDistro", "https://team/a.tar")])).unwrap(),
+        )
+        .unwrap();
+        let subs = vec![subscription("team", &file_url(&source))];
+        let now = chrono::Utc::now();
+
+        let status = refresh_subscriptions_in(&cache_dir, &subs, false, now);
+        assert_eq!(status[0].download_distro_count, 1);
+        assert!(!status[0].stale);
+        assert!(status[0].last_error.is_none());
+
+        // Within the TTL the cached copy is used even though the source changed
+        fs::write(&source, "{ not json").unwrap();
+        let status = refresh_subscriptions_in(&cache_dir, &subs, false, now);
+        assert!(status[0].last_error.is_none());
+
+        // A forced refresh fails but keeps the last good catalog
+        let status = refresh_subscriptions_in(&cache_dir, &subs, true, now);
+        assert!(status[0].last_error.as_deref().unwrap().contains("Invalid catalog JSON"));
+        assert_eq!(status[0].download_distro_count, 1);
+
+        let loaded = load_subscribed_catalogs(&cache_dir, &subs);
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].0, "team");
+
+        // Disabled subscriptions and changed URLs contribute nothing
+        let mut disabled = subs.clone();
+        disabled[0].enabled = false;
+        assert!(load_subscribed_catalogs(&cache_dir, &disabled).is_empty());
+        let moved = vec![subscription("team", "file:///elsewhere.json")];
+        assert!(load_subscribed_catalogs(&cache_dir, &moved).is_empty());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_remote_catalog_over_http() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/catalog.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"version":"1.0","downloadDistros":[{"id":"T","name":"T","description":"","url":"https://t/t.tar"}]}"#,
+            ))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/missing.json"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let base = server.uri();
+        let (ok, missing) = tokio::task::spawn_blocking(move || {
+            (
+                fetch_remote_catalog(&format!("{}/catalog.json", base)),
+                fetch_remote_catalog(&format!("{}/missing.json", base)),
+            )
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(ok.unwrap().download_distros[0].id, "T");
+        assert!(missing.unwrap_err().contains("404"));
+    }
+
+    fn image(id: &str, reference: &str) -> ContainerImage {
+        ContainerImage {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            image: reference.to_string(),
+            enabled: true,
+            is_built_in: false,
+            subscription_id: None,
+            has_local_override: false,
+        }
+    }
+
+    #[test]
+    fn test_export_selection_strips_provenance() {
+        let subscribed = vec![(
+            "team".to_string(),
+            catalog_with(vec![download("TeamDistro", "https://team/distro.tar")]),
+        )];
+        let merged = merge_catalogs(
+            catalog_with(vec![download("Builtin", "https://a/builtin.tar")]),
+            &subscribed,
+            None,
+        );
+        let selection = CatalogSelection {
+            download_distros: vec!["TeamDistro".to_string()],
+            ..Default::default()
+        };
+
+        let export = build_export(merged, Some(&selection));
+
+        assert_eq!(export.format_version, CATALOG_EXPORT_FORMAT_VERSION);
+        assert_eq!(export.catalog.download_distros.len(), 1);
+        let distro = &export.catalog.download_distros[0];
+        assert_eq!(distro.id, "TeamDistro");
+        assert!(distro.subscription_id.is_none());
+        assert!(!distro.is_built_in);
+        assert!(export.catalog.container_images.is_empty());
+    }
+
+    #[test]
+    fn test_export_round_trips_through_parse() {
+        let export = build_export(get_default_catalog(), None);
+        let json = serde_json::to_string(&export).unwrap();
+        let parsed = parse_catalog_export(&json).unwrap();
+        assert_eq!(
+            parsed.catalog.download_distros.len(),
+            get_default_catalog().download_distros.len()
+        );
+    }
+
+    #[test]
+    fn test_parse_export_rejects_unknown_version() {
+        let json = r#"{"formatVersion":99,"exportedAt":"","catalog":{}}"#;
+        assert!(parse_catalog_export(json).unwrap_err().contains("Unsupported catalog format version"));
+        assert!(parse_catalog_export(r#"{"catalog":{}}"#).is_err());
+    }
+
+    #[test]
+    fn test_import_merge_reports_added_updated_skipped() {
+        let base = merge_catalogs(
+            DistroCatalog {
+                download_distros: vec![
+                    download("Same", "https://a/same.tar"),
+                    download("Changed", "https://a/old.tar"),
+                ],
+                container_images: vec![image("img", "docker.io/library/alpine:3")],
+                ..Default::default()
+            },
+            &[],
+            None,
+        );
+        let user = catalog_with(vec![download("Mine", "https://me/mine.tar")]);
+        let imported = DistroCatalog {
+            download_distros: vec![
+                download("Same", "https://a/same.tar"),
+                download("Changed", "https://a/new.tar"),
+                download("New", "https://b/new.tar"),
+                download("BadUrl", "ftp://b/new.tar"),
+                download("New", "https://b/dup.tar"),
+            ],
+            container_images: vec![image("bad-image", "")],
+            ..Default::default()
+        };
+
+        let (user, report) = apply_import(&base, user, imported, true);
+
+        let ids = |entries: &[CatalogImportEntry]| entries.iter().map(|e| e.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&report.added), vec!["New"]);
+        assert_eq!(ids(&report.updated), vec!["Changed"]);
+        assert_eq!(ids(&report.skipped), vec!["Same", "BadUrl", "New", "bad-image"]);
+        assert_eq!(report.skipped[0].reason.as_deref(), Some("unchanged"));
+        assert!(report.skipped[1].reason.as_deref().unwrap().contains("URL"));
+        assert_eq!(report.skipped[2].reason.as_deref(), Some("duplicate ID in import file"));
+        assert_eq!(report.skipped[3].kind, CatalogEntryKind::ContainerImage);
+
+        // Merge keeps existing local entries
+        assert!(user.download_distros.iter().any(|d| d.id == "Mine"));
+        let changed = user.download_distros.iter().find(|d| d.id == "Changed").unwrap();
+        assert_eq!(changed.url, "https://a/new.tar");
+        assert!(!user.download_distros.iter().any(|d| d.id == "BadUrl"));
+        // Unchanged entries do not become local overrides
+        assert!(!user.download_distros.iter().any(|d| d.id == "Same"));
+    }
+
+    #[test]
+    fn test_import_replace_drops_existing_local_entries() {
+        let base = merge_catalogs(DistroCatalog::default(), &[], None);
+        let user = catalog_with(vec![download("Mine", "https://me/mine.tar")]);
+        let imported = catalog_with(vec![download("Theirs", "https://them/theirs.tar")]);
 
+        let (user, report) = apply_import(&base, user, imported, false);
+
+        assert_eq!(report.added.len(), 1);
+        assert_eq!(user.download_distros.len(), 1);
+        assert_eq!(user.download_distros[0].id, "Theirs");
+    }
+
+    #[test]
+    fn test_import_replace_classifies_against_base_catalog() {
+        let base = merge_catalogs(
+            catalog_with(vec![
+                download("Same", "https://a/same.tar"),
+                download("Tweaked", "https://a/old.tar"),
+            ]),
+            &[],
+            None,
+        );
+        // The local override being dropped matches the import
+        let user = catalog_with(vec![download("Tweaked", "https://me/new.tar")]);
+        let imported = catalog_with(vec![
+            download("Same", "https://a/same.tar"),
+            download("Tweaked", "https://me/new.tar"),
+        ]);
+
+        let (user, report) = apply_import(&base, user, imported, false);
+
+        let ids = |entries: &[CatalogImportEntry]| entries.iter().map(|e| e.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&report.updated), vec!["Tweaked"]);
+        assert_eq!(ids(&report.skipped), vec!["Same"]);
+        assert_eq!(user.download_distros.len(), 1);
+        assert_eq!(user.download_distros[0].url, "https://me/new.tar");
+    }
+
+    #[test]
+    fn test_import_validates_checksum_format() {
+        let mut distro = download("Sum", "https://a/sum.tar");
+        distro.sha256 = Some("not-a-hash".to_string());
+        assert!(distro.validate().unwrap_err().contains("sha256"));
+        distro.sha256 = Some("a".repeat(64));
+        assert!(distro.validate().is_ok());
+    }
+
+    #[test]
+    fn test_processor_architecture_parsing() {
+        assert_eq!(DistroArch::from_processor_architecture("AMD64"), Some(DistroArch::Amd64));
+        assert_eq!(DistroArch::from_processor_architecture("arm64"), Some(DistroArch::Arm64));
+        assert_eq!(DistroArch::from_processor_architecture("x86"), None);
+    }
+
+    #[test]
+    fn test_download_for_picks_architecture() {
+        let mut distro = download("Multi", "https://a/legacy.tar");
+        // Without per-arch entries the legacy URL applies everywhere
+        assert_eq!(
+            distro.download_for(Some(DistroArch::Arm64)).unwrap().url,
+            "https://a/legacy.tar"
+        );
+
+        distro.architectures.insert(
+            DistroArch::Arm64,
+            ArchDownload {
+                url: "https://a/arm64.tar".to_string(),
+                sha256: Some("b".repeat(64)),
+            },
+        );
+        let arm = distro.download_for(Some(DistroArch::Arm64)).unwrap();
+        assert_eq!(arm.url, "https://a/arm64.tar");
+        assert_eq!(arm.sha256, Some("b".repeat(64)));
+        // Per-arch entries are authoritative: no amd64 entry means unavailable
+        assert!(distro.download_for(Some(DistroArch::Amd64)).is_none());
+        assert!(distro.download_for(None).is_none());
+    }
+
+    #[test]
+    fn test_mark_availability() {
+        let mut arm_only = download("ArmOnly", "");
+        arm_only.architectures.insert(
+            DistroArch::Arm64,
+            ArchDownload {
+                url: "https://a/arm64.tar".to_string(),
+                sha256: None,
+            },
+        );
+        let mut catalog = catalog_with(vec![arm_only, download("Any", "https://a/any.tar")]);
+
+        mark_availability(&mut catalog, Some(DistroArch::Amd64));
+        assert!(!catalog.download_distros[0].available);
+        assert!(catalog.download_distros[1].available);
+
+        mark_availability(&mut catalog, Some(DistroArch::Arm64));
+        assert!(catalog.download_distros[0].available);
+    }
+
+    #[test]
+    fn test_availability_is_not_read_back_from_files() {
+        let mut unavailable = download("ArmOnly", "https://a/any.tar");
+        unavailable.available = false;
+        let json = serde_json::to_string(&unavailable).unwrap();
+        assert!(json.contains("\"available\":false"));
+        let parsed: DownloadDistro = serde_json::from_str(&json).unwrap();
+        assert!(parsed.available);
+
+        let export = build_export(catalog_with(vec![unavailable]), None);
+        let exported = serde_json::to_string(&export).unwrap();
+        assert!(!exported.contains("available"));
+    }
+
+    #[test]
+    fn test_validate_downloads_checks_each_architecture() {
+        let mut distro = download("Multi", "");
+        distro.architectures.insert(
+            DistroArch::Amd64,
+            ArchDownload {
+                url: "https://a/amd64.tar".to_string(),
+                sha256: None,
+            },
+        );
+        assert!(distro.validate_downloads().is_ok());
+
+        distro.architectures.insert(
+            DistroArch::Arm64,
+            ArchDownload {
+                url: "ftp://a/arm64.tar".to_string(),
+                sha256: None,
+            },
+        );
+        assert!(distro.validate_downloads().unwrap_err().starts_with("Arm64"));
+
+        // Legacy entries still need a URL
+        assert!(download("Legacy", "").validate_downloads().is_err());
+    }
+
+    #[test]
+    fn test_default_catalog_has_per_arch_downloads() {
+        let catalog = get_default_catalog();
+        let ubuntu = catalog.download_distros.iter().find(|d| d.id == "Ubuntu-24.04").unwrap();
+        assert!(ubuntu.download_for(Some(DistroArch::Amd64)).unwrap().url.contains("amd64"));
+        assert!(ubuntu.download_for(Some(DistroArch::Arm64)).unwrap().url.contains("arm64"));
+        let arch = catalog.download_distros.iter().find(|d| d.id == "ArchLinux").unwrap();
+        assert!(arch.download_for(Some(DistroArch::Arm64)).is_none());
+    }
+}
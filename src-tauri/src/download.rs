@@ -1,5 +1,6 @@
 //! Download utilities with progress tracking and checksum verification
 
+use crate::http_client::{self, BandwidthLimiter};
 use futures_util::StreamExt;
 use serde::Serialize;
 use sha2::{Digest, Sha256};
@@ -183,7 +184,8 @@ async fn download_with_limits_impl<E: ProgressEmitter>(
     limits: DownloadLimits,
     expected_checksum: Option<String>,
 ) -> Result<(), String> {
-    let client = reqwest::Client::new();
+    let client = http_client::async_client(None).map_err(|e| e.to_string())?;
+    let mut limiter = BandwidthLimiter::from_settings();
 
     let response = client
         .get(url)
@@ -266,6 +268,7 @@ async fn download_with_limits_impl<E: ProgressEmitter>(
         hasher.update(&chunk);
 
         downloaded += chunk.len() as u64;
+        limiter.throttle(chunk.len()).await;
 
         // Check if downloaded size exceeds limit (handles cases where Content-Length is not available)
         if let Some(max_size) = limits.max_file_size {
@@ -0,0 +1,609 @@
+//! Declarative environment files
+//!
+//! An environment file describes the WSL setup a team wants: distributions
+//! (source, name, location, WSL version), `.wslconfig` values, per-distro
+//! `wsl.conf` and custom actions. [`compute_plan`] diffs it against the current
+//! state and the apply command in `commands.rs` walks the plan in order.
+//!
+//! Files ending in `.json` are read as JSON; anything else goes through the
+//! YAML subset in [`crate::yaml`]. Only values present in the file are
+//! managed: unset settings keep their current value, and distributions or
+//! actions missing from the file are left alone.
+
+use crate::actions::{self, CustomAction};
+use crate::settings::{self, WslConf, WslConfig};
+use crate::validation::{
+    validate_action_id, validate_distro_name, validate_file_path, validate_url,
+    validate_wsl_version,
+};
+use crate::wsl::{Distribution, WslService};
+use crate::yaml;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// Where a distribution comes from when it has to be created
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DistroOrigin {
+    /// Microsoft Store / online catalog ID (`wsl --install`)
+    Store(String),
+    /// Container image reference
+    Image(String),
+    /// Rootfs tarball URL
+    Url(String),
+    /// LXC image ID (e.g. `alpine/3.20`)
+    Lxc(String),
+    /// Local tar file
+    Import(String),
+    /// Existing distribution to clone
+    Clone(String),
+}
+
+impl DistroOrigin {
+    /// Whether the installer accepts a WSL version; others are converted after creation
+    pub fn supports_version(&self) -> bool {
+        matches!(self, Self::Image(_) | Self::Url(_) | Self::Lxc(_))
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Self::Store(id) => format!("store:{}", id),
+            Self::Image(image) => format!("image:{}", image),
+            Self::Url(url) => format!("url:{}", url),
+            Self::Lxc(id) => format!("lxc:{}", id),
+            Self::Import(path) => format!("import:{}", path),
+            Self::Clone(source) => format!("clone:{}", source),
+        }
+    }
+}
+
+/// Desired state of one distribution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DistroSpec {
+    pub name: String,
+    pub source: DistroOrigin,
+    pub location: Option<String>,
+    pub wsl_version: Option<u8>,
+    pub wsl_conf: Option<WslConf>,
+}
+
+/// Parsed environment file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentFile {
+    pub wsl_config: Option<WslConfig>,
+    #[serde(default)]
+    pub distros: Vec<DistroSpec>,
+    #[serde(default)]
+    pub actions: Vec<CustomAction>,
+}
+
+impl EnvironmentFile {
+    /// Parse file contents; `json` selects JSON instead of the YAML subset
+    pub fn parse(text: &str, json: bool) -> Result<Self, String> {
+        let value = if json {
+            serde_json::from_str(text).map_err(|e| format!("Invalid environment file: {}", e))?
+        } else {
+            yaml::parse(text).map_err(|e| format!("Invalid environment file at {}", e))?
+        };
+        let value = match value {
+            Value::Null => Value::Object(Map::new()),
+            value => value,
+        };
+        serde_json::from_value(value).map_err(|e| format!("Invalid environment file: {}", e))
+    }
+
+    /// Load and validate an environment file from disk
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read environment file: {}", e))?;
+        let json = Path::new(path)
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let env = Self::parse(&text, json)?;
+        env.validate()?;
+        Ok(env)
+    }
+
+    /// Check names, sources and versions before anything is planned
+    pub fn validate(&self) -> Result<(), String> {
+        let mut names = HashSet::new();
+        for distro in &self.distros {
+            validate_distro_name(&distro.name).map_err(|e| e.to_string())?;
+            if !names.insert(distro.name.to_lowercase()) {
+                return Err(format!(
+                    "Distribution '{}' is listed more than once",
+                    distro.name
+                ));
+            }
+            if let Some(version) = distro.wsl_version {
+                validate_wsl_version(version).map_err(|e| e.to_string())?;
+            }
+            if let Some(ref location) = distro.location {
+                validate_file_path(location).map_err(|e| e.to_string())?;
+            }
+            match &distro.source {
+                DistroOrigin::Store(id) => {
+                    if !id.eq_ignore_ascii_case(&distro.name) || distro.location.is_some() {
+                        return Err(format!(
+                            "Distribution '{}': store installs use the store ID as name and the default location",
+                            distro.name
+                        ));
+                    }
+                }
+                DistroOrigin::Url(url) => validate_url(url).map_err(|e| e.to_string())?,
+                DistroOrigin::Import(path) => {
+                    validate_file_path(path).map_err(|e| e.to_string())?;
+                    if distro.location.is_none() {
+                        return Err(format!(
+                            "Distribution '{}': imports require a location",
+                            distro.name
+                        ));
+                    }
+                }
+                DistroOrigin::Clone(source) => {
+                    validate_distro_name(source).map_err(|e| e.to_string())?
+                }
+                DistroOrigin::Image(reference) | DistroOrigin::Lxc(reference) => {
+                    if reference.trim().is_empty() {
+                        return Err(format!("Distribution '{}': source is empty", distro.name));
+                    }
+                }
+            }
+        }
+
+        let mut ids = HashSet::new();
+        for action in &self.actions {
+            validate_action_id(&action.id).map_err(|e| e.to_string())?;
+            if !ids.insert(action.id.as_str()) {
+                return Err(format!("Action '{}' is listed more than once", action.id));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn distro(&self, name: &str) -> Option<&DistroSpec> {
+        self.distros
+            .iter()
+            .find(|d| d.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn action(&self, id: &str) -> Option<&CustomAction> {
+        self.actions.iter().find(|a| a.id == id)
+    }
+}
+
+/// Current state the file is compared against
+#[derive(Debug, Clone, Default)]
+pub struct CurrentState {
+    pub distros: Vec<Distribution>,
+    pub wsl_config: WslConfig,
+    /// wsl.conf of existing distributions that the file manages, keyed by lowercase name
+    pub wsl_confs: HashMap<String, WslConf>,
+    pub actions: Vec<CustomAction>,
+}
+
+impl CurrentState {
+    /// Read only the parts of the system the file refers to
+    pub fn read(env: &EnvironmentFile) -> Result<Self, String> {
+        let distros = WslService::list_distributions().map_err(|e| e.to_string())?;
+        let wsl_config = match env.wsl_config {
+            Some(_) => settings::read_wsl_config()?,
+            None => WslConfig::default(),
+        };
+        let mut wsl_confs = HashMap::new();
+        for spec in env.distros.iter().filter(|d| d.wsl_conf.is_some()) {
+            if let Some(current) = find_distro(&distros, &spec.name) {
+                let conf = settings::read_wsl_conf(&current.name, current.id.as_deref())?;
+                wsl_confs.insert(spec.name.to_lowercase(), conf);
+            }
+        }
+        Ok(Self {
+            distros,
+            wsl_config,
+            wsl_confs,
+            actions: actions::load_actions(),
+        })
+    }
+}
+
+fn find_distro<'a>(distros: &'a [Distribution], name: &str) -> Option<&'a Distribution> {
+    distros.iter().find(|d| d.name.eq_ignore_ascii_case(name))
+}
+
+/// What a plan item does when applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PlanKind {
+    Create,
+    Update,
+    /// Differs from the file but is not changed automatically
+    Drift,
+}
+
+/// What a plan item applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PlanTarget {
+    WslConfig,
+    Distro,
+    WslVersion,
+    WslConf,
+    Action,
+}
+
+/// One differing field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldChange {
+    pub field: String,
+    pub current: Option<String>,
+    pub desired: Option<String>,
+}
+
+/// One step of the plan
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanItem {
+    pub kind: PlanKind,
+    pub target: PlanTarget,
+    /// Distribution name or action ID (empty for `.wslconfig`)
+    pub name: String,
+    pub changes: Vec<FieldChange>,
+}
+
+/// Outcome of one plan item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ApplyStatus {
+    DryRun,
+    Applied,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyItemResult {
+    pub item: PlanItem,
+    pub status: ApplyStatus,
+    pub message: Option<String>,
+}
+
+fn display_value(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+fn to_object<T: Serialize>(value: &T) -> Map<String, Value> {
+    match serde_json::to_value(value) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    }
+}
+
+/// Fields set in `desired` whose value differs from `current`
+fn diff_fields<C: Serialize, D: Serialize>(current: &C, desired: &D) -> Vec<FieldChange> {
+    let current = to_object(current);
+    to_object(desired)
+        .into_iter()
+        .filter(|(_, value)| !value.is_null())
+        .filter(|(key, value)| current.get(key) != Some(value))
+        .map(|(key, value)| FieldChange {
+            current: current.get(&key).and_then(display_value),
+            desired: display_value(&value),
+            field: key,
+        })
+        .collect()
+}
+
+/// Overlay the fields set in `desired` onto `current`
+pub fn merge_fields<T: Serialize + serde::de::DeserializeOwned>(
+    current: &T,
+    desired: &T,
+) -> Result<T, String> {
+    let mut merged = to_object(current);
+    for (key, value) in to_object(desired) {
+        if !value.is_null() {
+            merged.insert(key, value);
+        }
+    }
+    serde_json::from_value(Value::Object(merged)).map_err(|e| e.to_string())
+}
+
+fn same_location(current: &str, desired: &str) -> bool {
+    let trim = |p: &str| p.trim_end_matches(['\\', '/']).replace('/', "\\");
+    trim(current).eq_ignore_ascii_case(&trim(desired))
+}
+
+/// Diff the environment file against the current state.
+///
+/// Items are ordered so they can be applied top to bottom: `.wslconfig`,
+/// distribution creates, version conversions, wsl.conf changes, actions, and
+/// finally drifts that are only reported.
+pub fn compute_plan(env: &EnvironmentFile, state: &CurrentState) -> Vec<PlanItem> {
+    let mut items = Vec::new();
+    let mut versions = Vec::new();
+    let mut confs = Vec::new();
+    let mut drifts = Vec::new();
+
+    if let Some(ref desired) = env.wsl_config {
+        let changes = diff_fields(&state.wsl_config, desired);
+        if !changes.is_empty() {
+            items.push(PlanItem {
+                kind: PlanKind::Update,
+                target: PlanTarget::WslConfig,
+                name: String::new(),
+                changes,
+            });
+        }
+    }
+
+    for spec in &env.distros {
+        let current = find_distro(&state.distros, &spec.name);
+        match current {
+            None => {
+                let mut changes = vec![FieldChange {
+                    field: "source".into(),
+                    current: None,
+                    desired: Some(spec.source.describe()),
+                }];
+                if let Some(ref location) = spec.location {
+                    changes.push(FieldChange {
+                        field: "location".into(),
+                        current: None,
+                        desired: Some(location.clone()),
+                    });
+                }
+                if let Some(version) = spec.wsl_version {
+                    changes.push(FieldChange {
+                        field: "wslVersion".into(),
+                        current: None,
+                        desired: Some(version.to_string()),
+                    });
+                }
+                items.push(PlanItem {
+                    kind: PlanKind::Create,
+                    target: PlanTarget::Distro,
+                    name: spec.name.clone(),
+                    changes,
+                });
+            }
+            Some(current) => {
+                if let Some(version) = spec.wsl_version.filter(|v| *v != current.version) {
+                    versions.push(PlanItem {
+                        kind: PlanKind::Update,
+                        target: PlanTarget::WslVersion,
+                        name: spec.name.clone(),
+                        changes: vec![FieldChange {
+                            field: "wslVersion".into(),
+                            current: Some(current.version.to_string()),
+                            desired: Some(version.to_string()),
+                        }],
+                    });
+                }
+                if let (Some(desired), Some(location)) = (&spec.location, &current.location) {
+                    if !same_location(location, desired) {
+                        drifts.push(PlanItem {
+                            kind: PlanKind::Drift,
+                            target: PlanTarget::Distro,
+                            name: spec.name.clone(),
+                            changes: vec![FieldChange {
+                                field: "location".into(),
+                                current: Some(location.clone()),
+                                desired: Some(desired.clone()),
+                            }],
+                        });
+                    }
+                }
+            }
+        }
+
+        if let Some(ref desired) = spec.wsl_conf {
+            let existing = state.wsl_confs.get(&spec.name.to_lowercase());
+            let changes = diff_fields(existing.unwrap_or(&WslConf::default()), desired);
+            if !changes.is_empty() {
+                confs.push(PlanItem {
+                    kind: if current.is_some() && existing.is_some() {
+                        PlanKind::Update
+                    } else {
+                        PlanKind::Create
+                    },
+                    target: PlanTarget::WslConf,
+                    name: spec.name.clone(),
+                    changes,
+                });
+            }
+        }
+    }
+
+    items.append(&mut versions);
+    items.append(&mut confs);
+
+    for desired in &env.actions {
+        let item = match state.actions.iter().find(|a| a.id == desired.id) {
+            None => PlanItem {
+                kind: PlanKind::Create,
+                target: PlanTarget::Action,
+                name: desired.id.clone(),
+                changes: diff_fields(&Value::Null, desired),
+            },
+            Some(current) => {
+                let changes = diff_fields(current, desired);
+                if changes.is_empty() {
+                    continue;
+                }
+                PlanItem {
+                    kind: PlanKind::Update,
+                    target: PlanTarget::Action,
+                    name: desired.id.clone(),
+                    changes,
+                }
+            }
+        };
+        items.push(item);
+    }
+
+    items.append(&mut drifts);
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::DistroScope;
+    use crate::wsl::DistroState;
+
+    const SAMPLE: &str = r#"
+wslConfig:
+  memory: 8GB
+  networkingMode: mirrored
+distros:
+  - name: Ubuntu
+    source: {store: Ubuntu}
+    wslVersion: 2
+    wslConf:
+      bootSystemd: true
+  - name: dev-alpine
+    source:
+      lxc: alpine/3.20
+    location: D:\WSL\dev-alpine
+    wslConf:
+      networkHostname: dev
+actions:
+  - id: update
+    name: Update packages
+    icon: refresh
+    command: sudo apt update
+    scope: {type: all}
+    confirmBeforeRun: false
+    showOutput: true
+    order: 1
+"#;
+
+    fn distro(name: &str, version: u8, location: &str) -> Distribution {
+        Distribution {
+            id: None,
+            name: name.into(),
+            state: DistroState::Stopped,
+            version,
+            is_default: false,
+            location: Some(location.into()),
+        }
+    }
+
+    #[test]
+    fn parses_and_validates() {
+        let env = EnvironmentFile::parse(SAMPLE, false).unwrap();
+        env.validate().unwrap();
+        assert_eq!(env.distros.len(), 2);
+        assert_eq!(
+            env.distros[1].source,
+            DistroOrigin::Lxc("alpine/3.20".into())
+        );
+        assert_eq!(env.actions[0].scope, DistroScope::All);
+        assert!(env.wsl_config.as_ref().unwrap().processors.is_none());
+
+        let json = serde_json::to_string(&env).unwrap();
+        assert_eq!(
+            EnvironmentFile::parse(&json, true).unwrap().distros.len(),
+            2
+        );
+        assert!(EnvironmentFile::parse("", false)
+            .unwrap()
+            .distros
+            .is_empty());
+
+        let bad = [
+            "distros:\n  - name: a\n    source: {store: Ubuntu}",
+            "distros:\n  - name: a\n    source: {import: C:\\a.tar}",
+            "distros:\n  - name: a\n    source: {lxc: x}\n  - name: A\n    source: {lxc: y}",
+            "distros:\n  - name: a\n    source: {lxc: x}\n    wslVersion: 3",
+        ];
+        for text in bad {
+            assert!(
+                EnvironmentFile::parse(text, false)
+                    .unwrap()
+                    .validate()
+                    .is_err(),
+                "{}",
+                text
+            );
+        }
+        assert!(
+            EnvironmentFile::parse("distros:\n  - name: a\n    source: {ftp: x}", false).is_err()
+        );
+    }
+
+    #[test]
+    fn plans_creates_updates_and_drifts() {
+        let env = EnvironmentFile::parse(SAMPLE, false).unwrap();
+        let mut state = CurrentState {
+            distros: vec![distro("ubuntu", 1, r"C:\Users\me\AppData\Ubuntu")],
+            wsl_config: WslConfig {
+                memory: Some("8GB".into()),
+                processors: Some(4),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        state.wsl_confs.insert("ubuntu".into(), WslConf::default());
+
+        let plan = compute_plan(&env, &state);
+        let summary: Vec<_> = plan
+            .iter()
+            .map(|i| (i.kind, i.target, i.name.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (PlanKind::Update, PlanTarget::WslConfig, ""),
+                (PlanKind::Create, PlanTarget::Distro, "dev-alpine"),
+                (PlanKind::Update, PlanTarget::WslVersion, "Ubuntu"),
+                (PlanKind::Update, PlanTarget::WslConf, "Ubuntu"),
+                (PlanKind::Create, PlanTarget::WslConf, "dev-alpine"),
+                (PlanKind::Create, PlanTarget::Action, "update"),
+            ]
+        );
+        assert_eq!(
+            plan[0].changes,
+            vec![FieldChange {
+                field: "networkingMode".into(),
+                current: None,
+                desired: Some("mirrored".into()),
+            }]
+        );
+        assert_eq!(
+            plan[1].changes[0].desired.as_deref(),
+            Some("lxc:alpine/3.20")
+        );
+
+        // Converge, then move the alpine distro elsewhere
+        state.wsl_config =
+            merge_fields(&state.wsl_config, env.wsl_config.as_ref().unwrap()).unwrap();
+        assert_eq!(state.wsl_config.processors, Some(4));
+        state.distros = vec![
+            distro("Ubuntu", 2, r"C:\Users\me\AppData\Ubuntu"),
+            distro("dev-alpine", 2, r"E:\elsewhere"),
+        ];
+        for spec in &env.distros {
+            let conf = spec.wsl_conf.clone().unwrap();
+            state.wsl_confs.insert(spec.name.to_lowercase(), conf);
+        }
+        state.actions = env.actions.clone();
+        let plan = compute_plan(&env, &state);
+        assert_eq!(plan.len(), 1);
+        assert_eq!(plan[0].kind, PlanKind::Drift);
+        assert_eq!(plan[0].changes[0].field, "location");
+
+        state.distros[1].location = Some(r"d:/WSL/dev-alpine/".into());
+        assert!(compute_plan(&env, &state).is_empty());
+    }
+}
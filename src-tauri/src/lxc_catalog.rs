@@ -0,0 +1,573 @@
+//! LXC image server (simplestreams) client
+//!
+//! Reads the community image catalog published at `lxc_base_url`
+//! (https://images.linuxcontainers.org by default):
+//! - `streams/v1/index.json` points at the image-downloads stream
+//! - `streams/v1/images.json` lists products keyed `{os}:{release}:{arch}:{variant}`,
+//!   each with date-keyed versions holding downloadable items
+//!
+//! Only `rootfs.tar.xz` items for the host architecture are kept. The parsed
+//! list is cached on disk for `cache_duration_hours`; release stability is
+//! filtered when reading so toggling `show_unstable_releases` needs no refetch.
+
+use crate::distro_catalog::DistroArch;
+use crate::settings::{self, DistributionSourceSettings};
+use crate::utils::get_config_file;
+use crate::validation::validate_url;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::time::Duration;
+
+/// Cache file for the parsed image list
+const LXC_CACHE_FILE: &str = "lxc-catalog-cache.json";
+
+/// Index path relative to the server base URL
+const INDEX_PATH: &str = "streams/v1/index.json";
+
+/// Index datatype of the image stream
+const IMAGE_DOWNLOADS_DATATYPE: &str = "image-downloads";
+
+/// images.json is a few MB today; anything far larger is not a simplestreams catalog
+const MAX_STREAM_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Item names that hold a plain root filesystem tarball
+const ROOTFS_ITEMS: [&str; 2] = ["rootfs.tar.xz", "root.tar.xz"];
+
+// ==================== Simplestreams JSON shape ====================
+
+#[derive(Debug, Deserialize)]
+struct StreamIndex {
+    index: HashMap<String, StreamIndexEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamIndexEntry {
+    datatype: String,
+    path: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImagesStream {
+    products: HashMap<String, Product>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Product {
+    #[serde(default)]
+    aliases: Option<String>,
+    arch: String,
+    os: String,
+    release: String,
+    #[serde(default)]
+    release_title: Option<String>,
+    #[serde(default)]
+    variant: Option<String>,
+    #[serde(default)]
+    versions: BTreeMap<String, ProductVersion>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProductVersion {
+    #[serde(default)]
+    items: HashMap<String, ProductItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProductItem {
+    path: String,
+    #[serde(default)]
+    sha256: Option<String>,
+    #[serde(default)]
+    size: u64,
+}
+
+// ==================== Parsed types ====================
+
+/// A rootfs image published by the LXC image server
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LxcImage {
+    /// Product key: `{os}:{release}:{arch}:{variant}`
+    pub id: String,
+    pub os: String,
+    pub release: String,
+    pub release_title: String,
+    pub arch: String,
+    pub variant: String,
+    pub aliases: Vec<String>,
+    pub download_url: String,
+    pub size_bytes: u64,
+    pub sha256: Option<String>,
+    /// Version key of the build (e.g. "20241215_13:00")
+    pub build_date: String,
+}
+
+/// Image list returned to the UI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LxcCatalog {
+    pub images: Vec<LxcImage>,
+    /// RFC 3339 timestamp of the fetch the list came from
+    pub last_updated: String,
+    /// RFC 3339 timestamp after which the list is refetched
+    pub expires_at: String,
+}
+
+/// On-disk cache of the parsed image list (all stabilities, host arch only)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LxcCatalogCache {
+    base_url: String,
+    arch: String,
+    fetched_at: String,
+    images: Vec<LxcImage>,
+}
+
+impl LxcCatalogCache {
+    fn expires_at(&self, cache_duration_hours: u32) -> Option<chrono::DateTime<chrono::Utc>> {
+        chrono::DateTime::parse_from_rfc3339(&self.fetched_at)
+            .ok()
+            .map(|t| {
+                t.with_timezone(&chrono::Utc)
+                    + chrono::Duration::hours(i64::from(cache_duration_hours))
+            })
+    }
+
+    fn is_valid_for(
+        &self,
+        base_url: &str,
+        arch: &str,
+        cache_duration_hours: u32,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> bool {
+        self.base_url == base_url
+            && self.arch == arch
+            && self
+                .expires_at(cache_duration_hours)
+                .is_some_and(|expires| now < expires)
+    }
+
+    fn into_catalog(self, cache_duration_hours: u32, show_unstable: bool) -> LxcCatalog {
+        let expires_at = self
+            .expires_at(cache_duration_hours)
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_default();
+        LxcCatalog {
+            images: self
+                .images
+                .into_iter()
+                .filter(|i| show_unstable || is_stable_release(&i.release))
+                .collect(),
+            last_updated: self.fetched_at,
+            expires_at,
+        }
+    }
+}
+
+// ==================== Parsing ====================
+
+/// Architecture name used by the LXC image server
+fn lxc_arch_name(arch: DistroArch) -> &'static str {
+    match arch {
+        DistroArch::Amd64 => "amd64",
+        DistroArch::Arm64 => "arm64",
+    }
+}
+
+/// Whether a release is considered stable (not edge/dev/rolling-unstable)
+pub fn is_stable_release(release: &str) -> bool {
+    let lower = release.to_lowercase();
+    !["edge", "dev", "rawhide", "sid", "unstable"]
+        .iter()
+        .any(|marker| lower.contains(marker))
+}
+
+/// Find the images.json path in a simplestreams index
+fn parse_index(body: &str) -> Result<String, String> {
+    let index: StreamIndex =
+        serde_json::from_str(body).map_err(|e| format!("Invalid simplestreams index: {}", e))?;
+    index
+        .index
+        .into_values()
+        .find(|entry| entry.datatype == IMAGE_DOWNLOADS_DATATYPE)
+        .map(|entry| entry.path)
+        .ok_or_else(|| "Simplestreams index has no image-downloads stream".to_string())
+}
+
+/// Parse images.json into rootfs images for one architecture
+fn parse_images(body: &str, base_url: &str, arch: &str) -> Result<Vec<LxcImage>, String> {
+    let stream: ImagesStream = serde_json::from_str(body)
+        .map_err(|e| format!("Invalid simplestreams images list: {}", e))?;
+    let base_url = base_url.trim_end_matches('/');
+
+    let mut images: Vec<LxcImage> = stream
+        .products
+        .into_iter()
+        .filter(|(_, product)| product.arch == arch)
+        .filter_map(|(id, product)| {
+            // Newest build that ships a rootfs tarball
+            let (build_date, item) =
+                product.versions.iter().rev().find_map(|(date, version)| {
+                    ROOTFS_ITEMS
+                        .iter()
+                        .find_map(|name| version.items.get(*name))
+                        .map(|item| (date.clone(), item))
+                })?;
+            Some(LxcImage {
+                download_url: format!("{}/{}", base_url, item.path.trim_start_matches('/')),
+                size_bytes: item.size,
+                sha256: item.sha256.clone(),
+                build_date,
+                aliases: product
+                    .aliases
+                    .as_deref()
+                    .map(|a| {
+                        a.split(',')
+                            .map(|s| s.trim().to_string())
+                            .filter(|s| !s.is_empty())
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                release_title: product
+                    .release_title
+                    .clone()
+                    .unwrap_or_else(|| product.release.clone()),
+                variant: product
+                    .variant
+                    .clone()
+                    .unwrap_or_else(|| "default".to_string()),
+                id,
+                os: product.os,
+                release: product.release,
+                arch: product.arch,
+            })
+        })
+        .collect();
+
+    images.sort_by(|a, b| {
+        a.os.cmp(&b.os)
+            .then_with(|| b.release.cmp(&a.release))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    Ok(images)
+}
+
+// ==================== Fetching and caching ====================
+
+fn fetch_text(url: &str) -> Result<String, String> {
+    let client = crate::http_client::blocking_client(Some(Duration::from_secs(60)))
+        .map_err(|e| e.to_string())?;
+    let resp = client
+        .get(url)
+        .send()
+        .map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+    if !resp.status().is_success() {
+        return Err(format!("{} returned HTTP {}", url, resp.status()));
+    }
+    let mut buf = Vec::new();
+    resp.take(MAX_STREAM_BYTES + 1)
+        .read_to_end(&mut buf)
+        .map_err(|e| format!("Failed to read {}: {}", url, e))?;
+    if buf.len() as u64 > MAX_STREAM_BYTES {
+        return Err(format!("{} exceeds {} byte limit", url, MAX_STREAM_BYTES));
+    }
+    String::from_utf8(buf).map_err(|e| format!("{} is not valid UTF-8: {}", url, e))
+}
+
+/// Fetch index.json and images.json from the server and parse the rootfs images
+fn fetch_images(base_url: &str, arch: &str) -> Result<Vec<LxcImage>, String> {
+    let base = base_url.trim_end_matches('/');
+    let images_path = parse_index(&fetch_text(&format!("{}/{}", base, INDEX_PATH))?)?;
+    let body = fetch_text(&format!("{}/{}", base, images_path.trim_start_matches('/')))?;
+    parse_images(&body, base, arch)
+}
+
+fn read_cache(path: &Path) -> Option<LxcCatalogCache> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+}
+
+fn write_cache(path: &Path, cache: &LxcCatalogCache) -> Result<(), String> {
+    let content = serde_json::to_string(cache)
+        .map_err(|e| format!("Failed to serialize LXC cache: {}", e))?;
+    fs::write(path, content).map_err(|e| format!("Failed to write LXC cache: {}", e))
+}
+
+/// Load the image list from the cache, fetching when expired or forced
+fn load_catalog_cache(
+    cache_path: &Path,
+    config: &DistributionSourceSettings,
+    arch: &str,
+    force_refresh: bool,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<LxcCatalogCache, String> {
+    if !force_refresh {
+        if let Some(cached) = read_cache(cache_path) {
+            if cached.is_valid_for(&config.lxc_base_url, arch, config.cache_duration_hours, now) {
+                return Ok(cached);
+            }
+        }
+    }
+
+    validate_url(&config.lxc_base_url).map_err(|e| e.to_string())?;
+    log::info!("Fetching LXC image catalog from {}", config.lxc_base_url);
+    let cache = LxcCatalogCache {
+        base_url: config.lxc_base_url.clone(),
+        arch: arch.to_string(),
+        fetched_at: now.to_rfc3339(),
+        images: fetch_images(&config.lxc_base_url, arch)?,
+    };
+    if let Err(e) = write_cache(cache_path, &cache) {
+        log::warn!("{}", e);
+    }
+    Ok(cache)
+}
+
+fn host_arch_name() -> Result<&'static str, String> {
+    DistroArch::host()
+        .map(lxc_arch_name)
+        .ok_or_else(|| "Unsupported host architecture for LXC images".to_string())
+}
+
+/// Get the LXC image catalog for the host architecture, honoring the settings
+pub fn get_catalog(force_refresh: bool) -> Result<LxcCatalog, String> {
+    let config = settings::get_settings().distribution_sources;
+    if !config.lxc_enabled {
+        return Err("Community catalog (LXC images) is disabled in settings".to_string());
+    }
+    let cache = load_catalog_cache(
+        &get_config_file(LXC_CACHE_FILE),
+        &config,
+        host_arch_name()?,
+        force_refresh,
+        chrono::Utc::now(),
+    )?;
+    Ok(cache.into_catalog(config.cache_duration_hours, config.show_unstable_releases))
+}
+
+/// Look up an image by product key (regardless of the stability filter)
+pub fn find_image(image_id: &str) -> Result<LxcImage, String> {
+    let config = settings::get_settings().distribution_sources;
+    let cache = load_catalog_cache(
+        &get_config_file(LXC_CACHE_FILE),
+        &config,
+        host_arch_name()?,
+        false,
+        chrono::Utc::now(),
+    )?;
+    cache
+        .images
+        .into_iter()
+        .find(|i| i.id == image_id)
+        .ok_or_else(|| format!("LXC image '{}' not found in catalog", image_id))
+}
+
+/// Delete the on-disk image list cache
+pub fn clear_cache() -> Result<(), String> {
+    let path = get_config_file(LXC_CACHE_FILE);
+    if path.exists() {
+        fs::remove_file(&path).map_err(|e| format!("Failed to remove LXC cache: {}", e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INDEX_JSON: &str = r#"{
+        "format": "index:1.0",
+        "index": {
+            "images": {
+                "datatype": "image-downloads",
+                "path": "streams/v1/images.json",
+                "products": ["alpine:3.20:amd64:default"]
+            }
+        }
+    }"#;
+
+    const IMAGES_JSON: &str = r#"{
+        "content_id": "images",
+        "datatype": "image-downloads",
+        "format": "products:1.0",
+        "products": {
+            "alpine:3.20:amd64:default": {
+                "aliases": "alpine/3.20/default, alpine/3.20",
+                "arch": "amd64",
+                "os": "Alpine",
+                "release": "3.20",
+                "release_title": "3.20",
+                "variant": "default",
+                "versions": {
+                    "20241214_13:00": {
+                        "items": {
+                            "rootfs.tar.xz": { "ftype": "root.tar.xz", "path": "images/alpine/3.20/amd64/default/20241214_13:00/rootfs.tar.xz", "sha256": "old", "size": 100 }
+                        }
+                    },
+                    "20241215_13:00": {
+                        "items": {
+                            "rootfs.tar.xz": { "ftype": "root.tar.xz", "path": "images/alpine/3.20/amd64/default/20241215_13:00/rootfs.tar.xz", "sha256": "new", "size": 200 },
+                            "lxd.tar.xz": { "ftype": "lxd.tar.xz", "path": "images/x/lxd.tar.xz", "size": 1 }
+                        }
+                    }
+                }
+            },
+            "alpine:edge:amd64:default": {
+                "aliases": "alpine/edge",
+                "arch": "amd64",
+                "os": "Alpine",
+                "release": "edge",
+                "release_title": "edge",
+                "variant": "default",
+                "versions": {
+                    "20241215_13:00": {
+                        "items": {
+                            "rootfs.tar.xz": { "ftype": "root.tar.xz", "path": "images/alpine/edge/rootfs.tar.xz", "sha256": "edge", "size": 300 }
+                        }
+                    }
+                }
+            },
+            "alpine:3.20:arm64:default": {
+                "arch": "arm64",
+                "os": "Alpine",
+                "release": "3.20",
+                "versions": {
+                    "20241215_13:00": {
+                        "items": {
+                            "rootfs.tar.xz": { "ftype": "root.tar.xz", "path": "images/arm.tar.xz", "size": 1 }
+                        }
+                    }
+                }
+            },
+            "ubuntu:noble:amd64:cloud": {
+                "arch": "amd64",
+                "os": "Ubuntu",
+                "release": "noble",
+                "variant": "cloud",
+                "versions": {
+                    "20241215_07:42": {
+                        "items": {
+                            "disk.qcow2": { "ftype": "disk-kvm.img", "path": "images/disk.qcow2", "size": 1 }
+                        }
+                    }
+                }
+            }
+        }
+    }"#;
+
+    fn config(base_url: &str) -> DistributionSourceSettings {
+        DistributionSourceSettings {
+            lxc_enabled: true,
+            lxc_base_url: base_url.to_string(),
+            cache_duration_hours: 24,
+            show_unstable_releases: false,
+        }
+    }
+
+    #[test]
+    fn test_parse_index_finds_image_stream() {
+        assert_eq!(parse_index(INDEX_JSON).unwrap(), "streams/v1/images.json");
+        assert!(parse_index(r#"{"index":{}}"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_images_filters_arch_and_rootfs() {
+        let images = parse_images(IMAGES_JSON, "https://images.example/", "amd64").unwrap();
+
+        // arm64 product and the image without a rootfs item are dropped
+        let ids: Vec<_> = images.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec!["alpine:edge:amd64:default", "alpine:3.20:amd64:default"]
+        );
+
+        let alpine = images.iter().find(|i| i.release == "3.20").unwrap();
+        assert_eq!(alpine.build_date, "20241215_13:00");
+        assert_eq!(alpine.sha256.as_deref(), Some("new"));
+        assert_eq!(alpine.size_bytes, 200);
+        assert_eq!(
+            alpine.download_url,
+            "https://images.example/images/alpine/3.20/amd64/default/20241215_13:00/rootfs.tar.xz"
+        );
+        assert_eq!(alpine.aliases, vec!["alpine/3.20/default", "alpine/3.20"]);
+    }
+
+    #[test]
+    fn test_parse_images_defaults_optional_fields() {
+        let images = parse_images(IMAGES_JSON, "https://images.example", "arm64").unwrap();
+        assert_eq!(images.len(), 1);
+        assert_eq!(images[0].variant, "default");
+        assert_eq!(images[0].release_title, "3.20");
+        assert!(images[0].sha256.is_none());
+    }
+
+    #[test]
+    fn test_stable_release_filter() {
+        assert!(is_stable_release("3.20"));
+        assert!(is_stable_release("current"));
+        assert!(!is_stable_release("edge"));
+        assert!(!is_stable_release("sid"));
+        assert!(!is_stable_release("rawhide"));
+    }
+
+    #[test]
+    fn test_cache_validity_and_stability_filter() {
+        let now = chrono::Utc::now();
+        let cache = LxcCatalogCache {
+            base_url: "https://images.example".to_string(),
+            arch: "amd64".to_string(),
+            fetched_at: (now - chrono::Duration::hours(2)).to_rfc3339(),
+            images: parse_images(IMAGES_JSON, "https://images.example", "amd64").unwrap(),
+        };
+
+        assert!(cache.is_valid_for("https://images.example", "amd64", 24, now));
+        assert!(!cache.is_valid_for("https://images.example", "amd64", 1, now));
+        assert!(!cache.is_valid_for("https://other.example", "amd64", 24, now));
+        assert!(!cache.is_valid_for("https://images.example", "arm64", 24, now));
+
+        assert_eq!(cache.clone().into_catalog(24, false).images.len(), 1);
+        assert_eq!(cache.into_catalog(24, true).images.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_load_catalog_cache_fetches_then_uses_cache() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/streams/v1/index.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(INDEX_JSON))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/streams/v1/images.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(IMAGES_JSON))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let cache_path = crate::utils::unique_temp_dir("lxc-cache").join("cache.json");
+        let config = config(&server.uri());
+
+        let path_clone = cache_path.clone();
+        let (first, second) = tokio::task::spawn_blocking(move || {
+            let now = chrono::Utc::now();
+            let first = load_catalog_cache(&path_clone, &config, "amd64", false, now);
+            // Second call within the cache window must not hit the server again
+            let second = load_catalog_cache(&path_clone, &config, "amd64", false, now);
+            (first, second)
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(first.unwrap().images.len(), 2);
+        assert_eq!(second.unwrap().images.len(), 2);
+        let _ = fs::remove_dir_all(cache_path.parent().unwrap());
+    }
+}
@@ -6,7 +6,10 @@ mod commands;
 mod constants;
 mod distro_catalog;
 mod download;
+mod environment;
 mod error;
+mod http_client;
+mod lxc_catalog;
 mod metadata;
 mod oci;
 mod settings;
@@ -14,22 +17,24 @@ mod temp_file_guard;
 mod utils;
 mod validation;
 mod wsl;
+mod yaml;
 
 use commands::{
     add_container_image, add_custom_action, add_download_distro,
-    check_action_applies, clone_distribution, create_from_image,
+    check_action_applies, plan_environment, apply_environment, list_snapshots, create_snapshot, restore_snapshot, delete_snapshot, get_backup_status, save_backup_schedule, delete_backup_schedule, run_backup_now, list_backup_targets, save_backup_target, delete_backup_target, test_backup_target, list_target_backups, restore_backup, export_backup_tar, check_backup_repository, prune_backup_repository, list_archive_contents, restore_archive_files_to_distro, extract_archive_files, preview_data_migration, migrate_user_data, clone_distribution, create_from_image,
     custom_install_with_progress, delete_container_image,
     delete_custom_action, delete_distribution, delete_download_distro, delete_ms_store_distro,
     execute_custom_action, export_custom_actions, export_custom_actions_to_file, export_distribution,
-    get_custom_actions, get_distro_catalog, get_distribution_disk_size,
+    export_wsl_package, export_distribution_vhd, export_distribution_filtered, list_export_filter_presets, import_distribution_vhd, export_distribution_encrypted, import_encrypted_distribution, detect_archive_encryption, generate_encryption_key, register_vhd_in_place, check_vhd_support, inspect_rootfs,
+    get_custom_actions, get_distro_catalog, export_distro_catalog, export_distro_catalog_to_file, import_distro_catalog, import_distro_catalog_from_file, refresh_catalog_subscriptions, get_catalog_subscription_status, get_distribution_disk_size,
     get_distribution_vhd_size, get_distribution_os_info, get_resource_stats, get_wsl_health, check_wsl_preflight, get_wsl_version, get_wsl_ip, get_system_distro_info, get_settings,
     get_startup_actions_for_distro, get_wsl_conf, get_wsl_conf_raw, get_wsl_config, hide_window, import_custom_actions, import_custom_actions_from_file,
-    import_distribution, install_from_rootfs_url, is_mock_mode_cmd, list_distributions,
+    import_distribution, install_from_rootfs_url, install_lxc_image, get_lxc_catalog, clear_lxc_catalog_cache, is_mock_mode_cmd, list_distributions,
     list_downloadable_distributions, list_online_distributions, move_distribution, open_file_explorer, open_folder, open_ide,
     get_distribution_location, get_default_distro_path, parse_image_reference,
     open_terminal, open_system_terminal, run_action_in_terminal, quick_install_distribution, quit_app, refresh_tray_menu, rename_distribution, resize_distribution, compact_distribution,
     reset_distro_catalog, reset_download_distros, reset_container_images, reset_ms_store_distros, reset_mock_state_cmd, set_mock_error_cmd, clear_mock_errors_cmd, set_stubborn_shutdown_cmd, was_force_shutdown_used_cmd, set_mock_download_cmd, reset_mock_download_cmd, set_mock_update_result_cmd, get_installed_terminals, restart_distribution, save_settings,
-    save_wsl_conf, save_wsl_config, set_default_distribution, set_distro_default_user, set_distro_version, set_sparse, shutdown_all, force_kill_wsl, start_distribution,
+    save_wsl_conf, save_wsl_config, set_default_distribution, set_distro_default_user, provision_distribution, validate_cloud_config, apply_cloud_config, rerun_cloud_config, set_distro_version, set_sparse, shutdown_all, force_kill_wsl, start_distribution,
     stop_distribution, force_stop_distribution, update_container_image, update_custom_action, update_download_distro,
     update_ms_store_distro, update_wsl, validate_install_path,
     // Disk Mount commands
@@ -46,6 +51,7 @@ use commands::{
     detect_rdp, check_wsl_config_timeouts, check_wsl_config_pending, open_rdp, open_terminal_with_message,
     // Distribution sources (HKLM DistributionListUrl)
     get_distro_source, preview_distro_manifest, apply_distro_source, clear_distro_source,
+    generate_distro_manifest, install_manifest_entry,
     // GPU commands
     get_distro_gpu_status, check_nvidia_container_toolkit,
 };
@@ -309,6 +315,12 @@ fn main() {
                 log::info!("Debug logging enabled from settings");
             }
 
+            // Run scheduled backups in the background; the UI refreshes on the event
+            let backup_handle = app.handle().clone();
+            wsl::backups::start_scheduler(move |runs| {
+                let _ = backup_handle.emit("backups-updated", runs.len());
+            });
+
             // Create initial tray menu (skip WSL query to avoid blocking startup)
             let menu = build_tray_menu(app.handle(), true)?;
 
@@ -464,6 +476,18 @@ fn main() {
             open_ide,
             restart_distribution,
             export_distribution,
+            export_wsl_package,
+            export_distribution_encrypted,
+            import_encrypted_distribution,
+            detect_archive_encryption,
+            generate_encryption_key,
+            export_distribution_vhd,
+            export_distribution_filtered,
+            list_export_filter_presets,
+            import_distribution_vhd,
+            register_vhd_in_place,
+            check_vhd_support,
+            inspect_rootfs,
             import_distribution,
             clone_distribution,
             validate_install_path,
@@ -497,12 +521,19 @@ fn main() {
             import_custom_actions,
             import_custom_actions_from_file,
             check_action_applies,
+            plan_environment,
+            apply_environment,
             // Startup Actions command
             get_startup_actions_for_distro,
             // Install from URL
             install_from_rootfs_url,
+            install_lxc_image,
+            get_lxc_catalog,
+            clear_lxc_catalog_cache,
             // Distro Catalog commands
             get_distro_catalog,
+            refresh_catalog_subscriptions,
+            get_catalog_subscription_status,
             reset_distro_catalog,
             reset_download_distros,
             reset_container_images,
@@ -515,6 +546,10 @@ fn main() {
             delete_container_image,
             update_ms_store_distro,
             delete_ms_store_distro,
+            export_distro_catalog,
+            export_distro_catalog_to_file,
+            import_distro_catalog,
+            import_distro_catalog_from_file,
             // OCI Image commands
             parse_image_reference,
             // WSL Preflight & Version commands
@@ -529,6 +564,32 @@ fn main() {
             move_distribution,
             set_sparse,
             set_distro_default_user,
+            list_snapshots,
+            create_snapshot,
+            restore_snapshot,
+            delete_snapshot,
+            get_backup_status,
+            save_backup_schedule,
+            delete_backup_schedule,
+            run_backup_now,
+            list_backup_targets,
+            save_backup_target,
+            delete_backup_target,
+            test_backup_target,
+            list_target_backups,
+            restore_backup,
+            export_backup_tar,
+            check_backup_repository,
+            prune_backup_repository,
+            list_archive_contents,
+            restore_archive_files_to_distro,
+            extract_archive_files,
+            preview_data_migration,
+            migrate_user_data,
+            provision_distribution,
+            validate_cloud_config,
+            apply_cloud_config,
+            rerun_cloud_config,
             set_distro_version,
             resize_distribution,
             compact_distribution,
@@ -573,6 +634,8 @@ fn main() {
             preview_distro_manifest,
             apply_distro_source,
             clear_distro_source,
+            generate_distro_manifest,
+            install_manifest_entry,
             // GPU commands
             get_distro_gpu_status,
             check_nvidia_container_toolkit,
@@ -10,7 +10,10 @@
 //! - Storage functions → wsl2-ui-infra/adapters/filesystem/metadata_repo.rs (implements DistroRepository port)
 
 use crate::utils::{get_config_file, is_mock_mode};
+use crate::wsl::cloud_init::ProvisioningStatus;
+use crate::wsl::distribution_conf::DistributionConf;
 use crate::wsl::executor::resource_monitor;
+use crate::wsl::ImportedPackage;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
@@ -43,6 +46,16 @@ pub enum InstallSource {
     Unknown,
 }
 
+/// How a `.vhdx` import registered the distribution's disk
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum VhdImportMode {
+    /// `wsl --import --vhd`: the VHDX was copied into the install location
+    Copy,
+    /// `wsl --import-in-place`: the original VHDX is the distribution's disk
+    InPlace,
+}
+
 impl Default for InstallSource {
     fn default() -> Self {
         InstallSource::Unknown
@@ -80,9 +93,42 @@ pub struct DistroMetadata {
     /// Original tar file path for imported distros
     #[serde(skip_serializing_if = "Option::is_none")]
     pub import_path: Option<String>,
+    /// Distribution manifest URL for distros installed from a manifest entry
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manifest_url: Option<String>,
+    /// Manifest flavor (the `ModernDistributions` key) of that entry
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manifest_flavor: Option<String>,
+    /// Parsed `/etc/wsl-distribution.conf` for `.wsl` packages
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distribution_conf: Option<DistributionConf>,
+    /// Icon extracted from the package
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon_path: Option<String>,
+    /// Windows Terminal fragment generated for the package
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub terminal_fragment_path: Option<String>,
+    /// Last cloud-config run, kept so a failed run can be re-run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provisioning: Option<ProvisioningStatus>,
+    /// Set for VHD imports; `import_path` then holds the source `.vhdx`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vhd_import: Option<VhdImportMode>,
+    /// ID of the snapshot this distribution was last restored from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restored_from_snapshot: Option<String>,
 }
 
 impl DistroMetadata {
+    /// Record the `wsl-distribution.conf` applied during a package import
+    pub fn set_imported_package(&mut self, package: Option<ImportedPackage>) {
+        if let Some(package) = package {
+            self.distribution_conf = Some(package.conf);
+            self.icon_path = package.applied.icon_path;
+            self.terminal_fragment_path = package.applied.terminal_fragment_path;
+        }
+    }
+
     /// Create new metadata for a distribution
     pub fn new(distro_id: String, distro_name: String, install_source: InstallSource) -> Self {
         Self {
@@ -95,6 +141,14 @@ impl DistroMetadata {
             catalog_entry: None,
             cloned_from: None,
             import_path: None,
+            manifest_url: None,
+            manifest_flavor: None,
+            distribution_conf: None,
+            icon_path: None,
+            terminal_fragment_path: None,
+            provisioning: None,
+            vhd_import: None,
+            restored_from_snapshot: None,
         }
     }
 
@@ -110,6 +164,14 @@ impl DistroMetadata {
             catalog_entry: None,
             cloned_from: Some(source_id),
             import_path: None,
+            manifest_url: None,
+            manifest_flavor: None,
+            distribution_conf: None,
+            icon_path: None,
+            terminal_fragment_path: None,
+            provisioning: None,
+            vhd_import: None,
+            restored_from_snapshot: None,
         }
     }
 
@@ -125,6 +187,22 @@ impl DistroMetadata {
             catalog_entry: None,
             cloned_from: None,
             import_path: tar_path,
+            manifest_url: None,
+            manifest_flavor: None,
+            distribution_conf: None,
+            icon_path: None,
+            terminal_fragment_path: None,
+            provisioning: None,
+            vhd_import: None,
+            restored_from_snapshot: None,
+        }
+    }
+
+    /// Create metadata for a distribution imported from a `.vhdx`
+    pub fn new_vhd_import(distro_id: String, distro_name: String, vhd_path: String, mode: VhdImportMode) -> Self {
+        Self {
+            vhd_import: Some(mode),
+            ..Self::new_import(distro_id, distro_name, Some(vhd_path))
         }
     }
 }
@@ -212,6 +290,14 @@ fn get_initial_mock_metadata() -> HashMap<String, DistroMetadata> {
             catalog_entry: Some("Ubuntu".to_string()),
             cloned_from: None,
             import_path: None,
+            manifest_url: None,
+            manifest_flavor: None,
+            distribution_conf: None,
+            icon_path: None,
+            terminal_fragment_path: None,
+            provisioning: None,
+            vhd_import: None,
+            restored_from_snapshot: None,
         },
     );
 
@@ -228,6 +314,14 @@ fn get_initial_mock_metadata() -> HashMap<String, DistroMetadata> {
             catalog_entry: Some("debian/bookworm".to_string()),
             cloned_from: None,
             import_path: None,
+            manifest_url: None,
+            manifest_flavor: None,
+            distribution_conf: None,
+            icon_path: None,
+            terminal_fragment_path: None,
+            provisioning: None,
+            vhd_import: None,
+            restored_from_snapshot: None,
         },
     );
 
@@ -244,6 +338,14 @@ fn get_initial_mock_metadata() -> HashMap<String, DistroMetadata> {
             catalog_entry: None,
             cloned_from: None,
             import_path: None,
+            manifest_url: None,
+            manifest_flavor: None,
+            distribution_conf: None,
+            icon_path: None,
+            terminal_fragment_path: None,
+            provisioning: None,
+            vhd_import: None,
+            restored_from_snapshot: None,
         },
     );
 
@@ -260,6 +362,14 @@ fn get_initial_mock_metadata() -> HashMap<String, DistroMetadata> {
             catalog_entry: None,
             cloned_from: None,
             import_path: None,
+            manifest_url: None,
+            manifest_flavor: None,
+            distribution_conf: None,
+            icon_path: None,
+            terminal_fragment_path: None,
+            provisioning: None,
+            vhd_import: None,
+            restored_from_snapshot: None,
         },
     );
 
@@ -276,6 +386,14 @@ fn get_initial_mock_metadata() -> HashMap<String, DistroMetadata> {
             catalog_entry: None,
             cloned_from: None,
             import_path: Some("C:\\WSL\\Backups\\fedora-backup.tar".to_string()),
+            manifest_url: None,
+            manifest_flavor: None,
+            distribution_conf: None,
+            icon_path: None,
+            terminal_fragment_path: None,
+            provisioning: None,
+            vhd_import: None,
+            restored_from_snapshot: None,
         },
     );
 
@@ -292,6 +410,14 @@ fn get_initial_mock_metadata() -> HashMap<String, DistroMetadata> {
             catalog_entry: None,
             cloned_from: Some("{mock-guid-0000-0000-0000-000000000000}".to_string()),
             import_path: None,
+            manifest_url: None,
+            manifest_flavor: None,
+            distribution_conf: None,
+            icon_path: None,
+            terminal_fragment_path: None,
+            provisioning: None,
+            vhd_import: None,
+            restored_from_snapshot: None,
         },
     );
 
@@ -308,6 +434,14 @@ fn get_initial_mock_metadata() -> HashMap<String, DistroMetadata> {
             catalog_entry: None,
             cloned_from: None,
             import_path: None,
+            manifest_url: None,
+            manifest_flavor: None,
+            distribution_conf: None,
+            icon_path: None,
+            terminal_fragment_path: None,
+            provisioning: None,
+            vhd_import: None,
+            restored_from_snapshot: None,
         },
     );
 
@@ -379,6 +513,14 @@ fn migrate_v1_to_v2(legacy: LegacyMetadataStore) -> MetadataStore {
                 catalog_entry: legacy_meta.catalog_entry,
                 cloned_from: None,
                 import_path: None,
+                manifest_url: None,
+                manifest_flavor: None,
+                distribution_conf: None,
+                icon_path: None,
+                terminal_fragment_path: None,
+                provisioning: None,
+                vhd_import: None,
+                restored_from_snapshot: None,
             };
             new_distros.insert(info.id.clone(), new_meta);
             migrated_count += 1;
@@ -704,6 +846,14 @@ mod tests {
             catalog_entry: None,
             cloned_from: None,
             import_path: None,
+            manifest_url: None,
+            manifest_flavor: None,
+            distribution_conf: None,
+            icon_path: None,
+            terminal_fragment_path: None,
+            provisioning: None,
+            vhd_import: None,
+            restored_from_snapshot: None,
         };
 
         let json = serde_json::to_string_pretty(&metadata).unwrap();
@@ -25,7 +25,7 @@ pub fn pull_and_create_rootfs(
     progress: Option<ProgressCallback>,
 ) -> Result<PathBuf, OciError> {
     let image = ImageReference::parse(image_ref)?;
-    let mut client = RegistryClient::new();
+    let mut client = RegistryClient::new()?;
 
     // Report progress
     if let Some(ref cb) = progress {
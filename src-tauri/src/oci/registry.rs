@@ -8,6 +8,7 @@ use std::io::Write;
 use std::path::Path;
 
 use super::types::*;
+use crate::http_client::{self, BandwidthLimiter};
 
 const MANIFEST_V2: &str = "application/vnd.docker.distribution.manifest.v2+json";
 const MANIFEST_LIST: &str = "application/vnd.docker.distribution.manifest.list.v2+json";
@@ -21,16 +22,14 @@ pub struct RegistryClient {
 }
 
 impl RegistryClient {
-    pub fn new() -> Self {
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(300))
-            .build()
-            .expect("Failed to create HTTP client");
+    pub fn new() -> Result<Self, OciError> {
+        let client = http_client::blocking_client(Some(std::time::Duration::from_secs(300)))
+            .map_err(|e| OciError::NetworkError(e.to_string()))?;
 
-        Self {
+        Ok(Self {
             client,
             token: None,
-        }
+        })
     }
 
     /// Get the registry URL for API calls
@@ -220,6 +219,7 @@ impl RegistryClient {
 
         let mut file = std::fs::File::create(output_path)?;
         let mut reader = response;
+        let mut limiter = BandwidthLimiter::from_settings();
 
         let mut buffer = [0u8; 8192];
         loop {
@@ -232,6 +232,7 @@ impl RegistryClient {
 
             file.write_all(&buffer[..bytes_read])?;
             downloaded += bytes_read as u64;
+            limiter.throttle_blocking(bytes_read);
 
             if let Some(ref cb) = progress {
                 cb(downloaded, total_size, digest);
@@ -389,13 +390,13 @@ mod tests {
     // Tests for RegistryClient creation
     #[test]
     fn test_registry_client_creation() {
-        let client = RegistryClient::new();
+        let client = RegistryClient::new().unwrap();
         assert!(client.token.is_none());
     }
 
     #[test]
     fn test_registry_client_registry_url() {
-        let client = RegistryClient::new();
+        let client = RegistryClient::new().unwrap();
 
         assert_eq!(client.registry_url("docker.io"), "https://registry-1.docker.io");
         assert_eq!(client.registry_url("ghcr.io"), "https://ghcr.io");
@@ -68,6 +68,73 @@ pub struct DistributionSourceSettings {
     pub show_unstable_releases: bool,
 }
 
+/// How outbound HTTP requests pick a proxy
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyMode {
+    /// Use the system proxy (Windows Internet Settings / HTTP(S)_PROXY env vars)
+    #[default]
+    System,
+    /// Connect directly, ignoring any system proxy
+    None,
+    /// Use the proxy URL configured in `NetworkSettings::proxy_url`
+    Manual,
+}
+
+/// Network settings shared by every HTTP client the app creates
+/// (rootfs downloads, OCI registry pulls, distribution manifest fetches)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkSettings {
+    /// Proxy selection mode
+    #[serde(default)]
+    pub proxy_mode: ProxyMode,
+    /// Proxy URL for manual mode (e.g. "http://proxy.corp:8080", credentials allowed in the URL)
+    #[serde(default)]
+    pub proxy_url: Option<String>,
+    /// Hosts that bypass the manual proxy (e.g. "localhost", ".corp.example", "10.0.0.0/8")
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
+    /// Paths to PEM files with extra trusted CA certificates (e.g. a TLS-inspecting proxy's root)
+    #[serde(default)]
+    pub extra_ca_certificates: Vec<String>,
+    /// User-Agent header override. None = "wsl-ui/<version>"
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    /// Download bandwidth cap in KiB/s. None or 0 = unlimited
+    #[serde(default)]
+    pub bandwidth_limit_kbps: Option<u64>,
+}
+
+/// A remote distro catalog the app subscribes to
+///
+/// The URL must serve a catalog in the same JSON format as `distro-catalog.json`
+/// (http://, https://, or file:// for a network share).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogSubscription {
+    /// Stable identifier, used as the provenance tag on merged entries
+    pub id: String,
+    /// Display name shown next to subscribed entries
+    pub name: String,
+    /// Catalog URL
+    pub url: String,
+    /// Disabled subscriptions keep their cache but contribute no entries
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// How long a fetched copy is used before it is refreshed
+    #[serde(default = "default_catalog_ttl_hours")]
+    pub cache_ttl_hours: u32,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_catalog_ttl_hours() -> u32 {
+    24
+}
+
 /// Container runtime options for pulling images
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -95,6 +162,17 @@ pub enum CloseAction {
     Quit,
 }
 
+/// What a scheduled backup does when its distribution is running
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum BackupRunningPolicy {
+    /// Record the run as skipped and try again at the next slot
+    #[default]
+    Skip,
+    /// Terminate the distribution, export it and start it again
+    StopThenExport,
+}
+
 /// Review prompt state for tracking Microsoft Store review requests
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 #[serde(rename_all = "lowercase")]
@@ -144,11 +222,28 @@ pub struct AppSettings {
     pub wsl_timeouts: WslTimeoutConfig,
     pub executable_paths: ExecutablePaths,
     pub distribution_sources: DistributionSourceSettings,
+    /// Proxy, CA certificate and bandwidth settings for outbound HTTP
+    #[serde(default)]
+    pub network: NetworkSettings,
+    /// Remote distro catalogs merged into the local catalog, in priority order
+    #[serde(default)]
+    pub catalog_subscriptions: Vec<CatalogSubscription>,
     /// Container runtime for pulling OCI images
     pub container_runtime: ContainerRuntime,
     /// Default base path for new WSL installations (unexpanded, e.g. "%LOCALAPPDATA%\\wsl")
     /// None = use default "%LOCALAPPDATA%\\wsl"
     pub default_install_base_path: Option<String>,
+    /// Directory holding distribution snapshots and their index (unexpanded)
+    /// None = use "snapshots" in the app config directory
+    #[serde(default)]
+    pub snapshot_directory: Option<String>,
+    /// Directory holding scheduled backup archives (unexpanded)
+    /// None = use "backups" in the app config directory
+    #[serde(default)]
+    pub backup_directory: Option<String>,
+    /// How scheduled backups treat running distributions
+    #[serde(default)]
+    pub backup_running_policy: BackupRunningPolicy,
     /// Enable debug logging (more verbose logs for troubleshooting)
     pub debug_logging: bool,
     /// Current state of the review prompt workflow
@@ -283,6 +378,18 @@ pub fn get_executable_paths() -> ExecutablePaths {
     get_settings().executable_paths
 }
 
+/// Get current network configuration
+/// This reads from settings on each call to allow runtime mutability
+pub fn get_network_settings() -> NetworkSettings {
+    get_settings().network
+}
+
+/// Get the configured remote catalog subscriptions
+/// This reads from settings on each call to allow runtime mutability
+pub fn get_catalog_subscriptions() -> Vec<CatalogSubscription> {
+    get_settings().catalog_subscriptions
+}
+
 /// Default base path for WSL installations (unexpanded)
 const DEFAULT_INSTALL_BASE_PATH: &str = r"%LOCALAPPDATA%\wsl";
 
@@ -323,6 +430,24 @@ pub fn get_default_distro_path(name: &str) -> String {
     format!(r"{}\{}", base, name)
 }
 
+/// Get the snapshot directory (expanded)
+/// Returns the configured path from settings, or falls back to the app config directory
+pub fn get_snapshot_directory() -> std::path::PathBuf {
+    match get_settings().snapshot_directory.as_deref().filter(|s| !s.trim().is_empty()) {
+        Some(path) => std::path::PathBuf::from(expand_env_vars(path)),
+        None => crate::utils::get_config_dir().join("snapshots"),
+    }
+}
+
+/// Get the backup directory (expanded)
+/// Returns the configured path from settings, or falls back to the app config directory
+pub fn get_backup_directory() -> std::path::PathBuf {
+    match get_settings().backup_directory.as_deref().filter(|s| !s.trim().is_empty()) {
+        Some(path) => std::path::PathBuf::from(expand_env_vars(path)),
+        None => crate::utils::get_config_dir().join("backups"),
+    }
+}
+
 /// Save settings
 pub fn save_settings(settings: AppSettings) -> Result<(), String> {
     save_settings_to_file(&settings)?;
@@ -552,7 +677,7 @@ systemd=true
 
 /// Parse wsl.conf INI content
 /// Parse wsl.conf INI content using configparser library
-fn parse_wsl_conf(content: &str) -> Result<WslConf, String> {
+pub(crate) fn parse_wsl_conf(content: &str) -> Result<WslConf, String> {
     let mut ini = Ini::new_cs();
     ini.set_comment_symbols(&['#', ';']);
 
@@ -616,7 +741,7 @@ pub fn write_wsl_conf(distro_name: &str, config: WslConf) -> Result<(), String>
 }
 
 /// Serialize WslConf to INI format
-fn serialize_wsl_conf(config: &WslConf) -> String {
+pub(crate) fn serialize_wsl_conf(config: &WslConf) -> String {
     let mut sections: Vec<String> = vec![];
 
     // [automount]
@@ -72,6 +72,30 @@ pub fn get_user_profile() -> PathBuf {
     PathBuf::from(profile)
 }
 
+/// Create an empty directory under the system temp dir for a test. The name
+/// carries the process ID, a timestamp and a counter, so tests running in
+/// parallel or in concurrent runs never share one.
+#[cfg(test)]
+pub fn unique_temp_dir(prefix: &str) -> PathBuf {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let dir = std::env::temp_dir().join(format!(
+        "wsl-ui-{}-{}-{}-{}",
+        prefix,
+        std::process::id(),
+        unique,
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -94,6 +118,16 @@ mod tests {
         assert!(file.to_string_lossy().contains(APP_NAME));
     }
 
+    #[test]
+    fn test_unique_temp_dir_is_fresh_per_call() {
+        let first = unique_temp_dir("utils");
+        let second = unique_temp_dir("utils");
+        assert_ne!(first, second);
+        assert!(first.is_dir() && second.is_dir());
+        let _ = std::fs::remove_dir_all(&first);
+        let _ = std::fs::remove_dir_all(&second);
+    }
+
     #[test]
     fn test_is_mock_mode_on_non_windows() {
         // On non-Windows, should always return true
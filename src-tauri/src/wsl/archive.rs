@@ -0,0 +1,463 @@
+//! Streaming access to rootfs archives
+//!
+//! Reads plain and gzip-compressed tarballs (including `.wsl` packages, which
+//! are gzip tarballs) without extracting them to disk, and rewrites tar
+//! streams member by member (`rewrite`) for package export and rootfs
+//! customization.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufReader, Read, Write};
+use std::path::Path;
+
+use flate2::read::GzDecoder;
+use tar::{Archive, EntryType, Header};
+
+use super::types::WslError;
+
+/// Largest single file `read_files` will buffer in memory.
+const MAX_BUFFERED_FILE_BYTES: u64 = 16 * 1024 * 1024;
+
+/// How many symlink hops `read_files` follows before giving up.
+const MAX_SYMLINK_HOPS: usize = 4;
+
+/// Open an archive for streaming, transparently handling gzip compression.
+pub fn open_archive(path: &Path) -> Result<Archive<Box<dyn Read>>, WslError> {
+    let file = BufReader::new(File::open(path).map_err(|e| {
+        WslError::CommandFailed(format!("Failed to open {}: {}", path.display(), e))
+    })?);
+    open_reader(Box::new(file), &path.display().to_string())
+}
+
+/// Wrap any byte stream (a file or an HTTP response body) as an archive,
+/// sniffing the compression from its first bytes. `label` names the source
+/// in error messages.
+pub fn open_reader(mut reader: Box<dyn Read>, label: &str) -> Result<Archive<Box<dyn Read>>, WslError> {
+    let mut magic = [0u8; 6];
+    let mut n = 0;
+    // Network streams may return short reads, so fill the sniff buffer fully.
+    while n < magic.len() {
+        match reader.read(&mut magic[n..])? {
+            0 => break,
+            read => n += read,
+        }
+    }
+    let head = std::io::Cursor::new(magic[..n].to_vec());
+    let stream = head.chain(reader);
+
+    let reader: Box<dyn Read> = match &magic[..n] {
+        [0x1f, 0x8b, ..] => Box::new(GzDecoder::new(stream)),
+        [0xfd, b'7', b'z', b'X', b'Z', 0x00] => {
+            return Err(WslError::CommandFailed(format!(
+                "{} is xz-compressed, which is not supported; use a .tar or .tar.gz archive",
+                label
+            )))
+        }
+        _ => Box::new(stream),
+    };
+    Ok(Archive::new(reader))
+}
+
+/// Normalize an archive member path: `./etc/os-release` and `/etc/os-release`
+/// both become `etc/os-release`.
+pub fn normalize_entry_path(path: &str) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            p => parts.push(p),
+        }
+    }
+    parts.join("/")
+}
+
+/// Resolve a symlink target relative to the link's own location.
+pub fn resolve_link(link: &str, target: &str) -> String {
+    if target.starts_with('/') {
+        return normalize_entry_path(target);
+    }
+    let parent = link.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+    normalize_entry_path(&format!("{}/{}", parent, target))
+}
+
+enum Member {
+    Data(Vec<u8>),
+    Link(String),
+}
+
+/// One pass over the archive, buffering the wanted regular files and
+/// recording symlink targets for wanted links.
+fn scan(path: &Path, wanted: &HashSet<String>) -> Result<HashMap<String, Member>, WslError> {
+    let mut found = HashMap::new();
+    let mut archive = open_archive(path)?;
+    let entries = archive
+        .entries()
+        .map_err(|e| WslError::CommandFailed(format!("Failed to read archive: {}", e)))?;
+    for entry in entries {
+        let mut entry =
+            entry.map_err(|e| WslError::CommandFailed(format!("Corrupt archive entry: {}", e)))?;
+        let name = normalize_entry_path(&entry.path()?.to_string_lossy());
+        if !wanted.contains(&name) {
+            continue;
+        }
+        match entry.header().entry_type() {
+            EntryType::Symlink | EntryType::Link => {
+                if let Some(target) = entry.link_name()? {
+                    let target = target.to_string_lossy();
+                    let resolved = if entry.header().entry_type() == EntryType::Link {
+                        normalize_entry_path(&target)
+                    } else {
+                        resolve_link(&name, &target)
+                    };
+                    found.insert(name, Member::Link(resolved));
+                }
+            }
+            EntryType::Regular | EntryType::Continuous => {
+                if entry.size() > MAX_BUFFERED_FILE_BYTES {
+                    continue;
+                }
+                let mut data = Vec::with_capacity(entry.size() as usize);
+                entry.read_to_end(&mut data)?;
+                found.insert(name, Member::Data(data));
+            }
+            _ => {}
+        }
+        if found.len() == wanted.len() {
+            break;
+        }
+    }
+    Ok(found)
+}
+
+/// Read the contents of specific files from an archive, following symlinks
+/// (e.g. Ubuntu's `/etc/wsl-distribution.conf -> /usr/lib/wsl/...`). Paths are
+/// given and returned in normalized form. Missing files are simply absent
+/// from the result.
+pub fn read_files(path: &Path, names: &[&str]) -> Result<HashMap<String, Vec<u8>>, WslError> {
+    // requested name -> name currently being looked up
+    let mut pending: HashMap<String, String> = names
+        .iter()
+        .map(|n| (normalize_entry_path(n), normalize_entry_path(n)))
+        .collect();
+    let mut result = HashMap::new();
+
+    for _ in 0..=MAX_SYMLINK_HOPS {
+        if pending.is_empty() {
+            break;
+        }
+        let wanted: HashSet<String> = pending.values().cloned().collect();
+        let mut found = scan(path, &wanted)?;
+        let mut next = HashMap::new();
+        for (requested, lookup) in pending {
+            match found.get(&lookup) {
+                Some(Member::Data(data)) => {
+                    result.insert(requested, data.clone());
+                }
+                Some(Member::Link(target)) => {
+                    next.insert(requested, target.clone());
+                }
+                None => {}
+            }
+        }
+        found.clear();
+        pending = next;
+    }
+    Ok(result)
+}
+
+// ---------------------------------------------------------------------------
+// Rewriting
+// ---------------------------------------------------------------------------
+
+/// What `rewrite` does with an archive member.
+pub enum EntryAction {
+    Keep,
+    Drop,
+    /// Replace the contents, keeping ownership, mode and extended attributes
+    Replace(Vec<u8>),
+    /// Buffer the contents and pass them to `TarRewriter::edit`. Members that
+    /// are not regular files, or are too large to buffer, are kept as-is.
+    Edit,
+}
+
+/// A file or directory added after the original members.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppendEntry {
+    pub path: String,
+    /// `None` for a directory
+    pub data: Option<Vec<u8>>,
+    pub mode: u32,
+    pub uid: u64,
+    pub gid: u64,
+}
+
+impl AppendEntry {
+    /// A root-owned regular file.
+    pub fn file(path: impl Into<String>, data: impl Into<Vec<u8>>, mode: u32) -> Self {
+        Self {
+            path: normalize_entry_path(&path.into()),
+            data: Some(data.into()),
+            mode,
+            uid: 0,
+            gid: 0,
+        }
+    }
+
+    pub fn owned_by(mut self, uid: u64, gid: u64) -> Self {
+        self.uid = uid;
+        self.gid = gid;
+        self
+    }
+}
+
+/// Decides, member by member, how a tar stream is rewritten.
+pub trait TarRewriter {
+    /// Called for every member with its normalized path.
+    fn action(&mut self, path: &str, header: &Header) -> EntryAction;
+
+    /// New contents for a member that returned `EntryAction::Edit`.
+    fn edit(&mut self, _path: &str, data: Vec<u8>) -> Vec<u8> {
+        data
+    }
+
+    /// Members appended once the input is exhausted. Missing parent
+    /// directories are created automatically.
+    fn finish(&mut self) -> Vec<AppendEntry>;
+}
+
+/// Copy the metadata of a header into a fresh GNU header, so long paths
+/// survive regardless of the source format.
+pub(crate) fn copy_header(src: &Header) -> std::io::Result<Header> {
+    let mut header = Header::new_gnu();
+    header.set_entry_type(src.entry_type());
+    // Ownership and timestamps may be blank in hand-built archives.
+    header.set_mode(src.mode()?);
+    header.set_uid(src.uid().unwrap_or(0));
+    header.set_gid(src.gid().unwrap_or(0));
+    header.set_mtime(src.mtime().unwrap_or(0));
+    header.set_size(src.entry_size()?);
+    if let Ok(Some(user)) = src.username() {
+        let _ = header.set_username(user);
+    }
+    if let Ok(Some(group)) = src.groupname() {
+        let _ = header.set_groupname(group);
+    }
+    if let (Ok(Some(major)), Ok(Some(minor))) = (src.device_major(), src.device_minor()) {
+        let _ = header.set_device_major(major);
+        let _ = header.set_device_minor(minor);
+    }
+    Ok(header)
+}
+
+/// Extended attributes (file capabilities, SELinux labels) carried as PAX
+/// records.
+pub(crate) fn xattrs<R: Read>(entry: &mut tar::Entry<R>) -> std::io::Result<Vec<(String, Vec<u8>)>> {
+    Ok(entry
+        .pax_extensions()?
+        .map(|exts| {
+            exts.filter_map(Result::ok)
+                .filter_map(|ext| {
+                    let key = ext.key().ok()?;
+                    (key.starts_with("SCHILY.xattr.") || key.starts_with("LIBARCHIVE.xattr."))
+                        .then(|| (key.to_string(), ext.value_bytes().to_vec()))
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Record `path` and all of its ancestors as existing directories.
+fn record_parents(dirs: &mut HashSet<String>, path: &str) {
+    let mut current = path;
+    while let Some((parent, _)) = current.rsplit_once('/') {
+        if !dirs.insert(parent.to_string()) {
+            break;
+        }
+        current = parent;
+    }
+}
+
+/// Whether `rewrite` hands a member returning `EntryAction::Edit` to
+/// `TarRewriter::edit`. Symlinks, special files and members too large to
+/// buffer are kept unchanged instead.
+pub(crate) fn is_editable(header: &Header) -> bool {
+    matches!(header.entry_type(), EntryType::Regular | EntryType::Continuous)
+        && header.size().is_ok_and(|size| size <= MAX_BUFFERED_FILE_BYTES)
+}
+
+/// Stream `input` into `output` as a tar archive, letting `rewriter` keep,
+/// drop, replace or edit each member and append new ones at the end.
+/// Returns the output writer so compressors can be finished by the caller.
+pub fn rewrite<R: Read, W: Write>(
+    input: R,
+    output: W,
+    rewriter: &mut dyn TarRewriter,
+) -> Result<W, WslError> {
+    let io_err = |e: std::io::Error| WslError::CommandFailed(format!("Failed to rewrite archive: {}", e));
+    let mut archive = Archive::new(input);
+    let mut builder = tar::Builder::new(output);
+    let mut dirs: HashSet<String> = HashSet::new();
+
+    for entry in archive.entries().map_err(io_err)? {
+        let mut entry = entry.map_err(io_err)?;
+        let raw_path = entry.path().map_err(io_err)?.into_owned();
+        let path = normalize_entry_path(&raw_path.to_string_lossy());
+        let entry_type = entry.header().entry_type();
+
+        let mut action = rewriter.action(&path, entry.header());
+        if matches!(action, EntryAction::Drop) {
+            continue;
+        }
+        if matches!(action, EntryAction::Edit) {
+            action = if is_editable(entry.header()) {
+                let mut data = Vec::with_capacity(entry.size() as usize);
+                entry.read_to_end(&mut data).map_err(io_err)?;
+                EntryAction::Replace(rewriter.edit(&path, data))
+            } else {
+                EntryAction::Keep
+            };
+        }
+
+        record_parents(&mut dirs, &path);
+        if entry_type == EntryType::Directory {
+            dirs.insert(path);
+        }
+
+        let mut header = copy_header(entry.header()).map_err(io_err)?;
+        let xattrs = xattrs(&mut entry).map_err(io_err)?;
+        if !xattrs.is_empty() {
+            builder
+                .append_pax_extensions(xattrs.iter().map(|(k, v)| (k.as_str(), v.as_slice())))
+                .map_err(io_err)?;
+        }
+
+        match action {
+            EntryAction::Replace(data) => {
+                header.set_entry_type(EntryType::Regular);
+                header.set_size(data.len() as u64);
+                builder.append_data(&mut header, &raw_path, data.as_slice()).map_err(io_err)?;
+            }
+            _ => match entry_type {
+                EntryType::Symlink | EntryType::Link => {
+                    let target = entry.link_name().map_err(io_err)?.unwrap_or_default().into_owned();
+                    header.set_size(0);
+                    builder.append_link(&mut header, &raw_path, &target).map_err(io_err)?;
+                }
+                _ => builder.append_data(&mut header, &raw_path, &mut entry).map_err(io_err)?,
+            },
+        }
+    }
+
+    let mtime = chrono::Utc::now().timestamp().max(0) as u64;
+    for appended in rewriter.finish() {
+        // Parents first, so extractors don't invent their permissions.
+        let mut missing = Vec::new();
+        let mut current = appended.path.as_str();
+        while let Some((parent, _)) = current.rsplit_once('/') {
+            if dirs.contains(parent) {
+                break;
+            }
+            missing.push(parent.to_string());
+            current = parent;
+        }
+        for dir in missing.into_iter().rev() {
+            let mut header = Header::new_gnu();
+            header.set_entry_type(EntryType::Directory);
+            header.set_mode(0o755);
+            header.set_size(0);
+            header.set_mtime(mtime);
+            builder.append_data(&mut header, &dir, std::io::empty()).map_err(io_err)?;
+            dirs.insert(dir);
+        }
+
+        let mut header = Header::new_gnu();
+        header.set_mode(appended.mode);
+        header.set_uid(appended.uid);
+        header.set_gid(appended.gid);
+        header.set_mtime(mtime);
+        match &appended.data {
+            Some(data) => {
+                header.set_entry_type(EntryType::Regular);
+                header.set_size(data.len() as u64);
+                builder.append_data(&mut header, &appended.path, data.as_slice()).map_err(io_err)?;
+            }
+            None => {
+                if dirs.contains(&appended.path) {
+                    continue;
+                }
+                header.set_entry_type(EntryType::Directory);
+                header.set_size(0);
+                builder.append_data(&mut header, &appended.path, std::io::empty()).map_err(io_err)?;
+                dirs.insert(appended.path.clone());
+            }
+        }
+    }
+
+    builder.into_inner().map_err(io_err)
+}
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    use std::path::Path;
+
+    /// Build a gzip tarball at `path` from `(name, contents)` pairs. Names
+    /// ending in `@target` become symlinks pointing at `target`.
+    pub fn write_tar_gz(path: &Path, files: &[(&str, &[u8])]) {
+        let file = std::fs::File::create(path).unwrap();
+        let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::fast());
+        let mut builder = tar::Builder::new(encoder);
+        for (name, data) in files {
+            let mut header = tar::Header::new_gnu();
+            if let Some((link, target)) = name.split_once('@') {
+                header.set_entry_type(tar::EntryType::Symlink);
+                header.set_size(0);
+                header.set_mode(0o777);
+                builder.append_link(&mut header, link, target).unwrap();
+            } else {
+                header.set_size(data.len() as u64);
+                header.set_mode(0o644);
+                header.set_cksum();
+                builder.append_data(&mut header, name, *data).unwrap();
+            }
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_support::write_tar_gz;
+    use super::*;
+
+    #[test]
+    fn normalize_entry_path_strips_prefixes_and_dots() {
+        assert_eq!(normalize_entry_path("./etc/os-release"), "etc/os-release");
+        assert_eq!(normalize_entry_path("/usr/lib/../lib/wsl/x"), "usr/lib/wsl/x");
+        assert_eq!(resolve_link("etc/wsl-distribution.conf", "../usr/lib/wsl/conf"), "usr/lib/wsl/conf");
+        assert_eq!(resolve_link("etc/a", "/usr/b"), "usr/b");
+    }
+
+    #[test]
+    fn read_files_follows_symlinks_in_gzip_archive() {
+        let dir = crate::utils::unique_temp_dir("archive");
+        let path = dir.join("rootfs.tar.gz");
+        write_tar_gz(
+            &path,
+            &[
+                ("./etc/wsl-distribution.conf@/usr/lib/wsl/distribution.conf", b""),
+                ("./usr/lib/wsl/distribution.conf", b"[oobe]\n"),
+                ("./etc/hostname", b"box\n"),
+            ],
+        );
+
+        let files = read_files(&path, &["/etc/wsl-distribution.conf", "etc/hostname", "etc/missing"]).unwrap();
+        assert_eq!(files.get("etc/wsl-distribution.conf").unwrap(), b"[oobe]\n");
+        assert_eq!(files.get("etc/hostname").unwrap(), b"box\n");
+        assert!(!files.contains_key("etc/missing"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,839 @@
+//! Browsing and restoring individual files from export archives
+//!
+//! Lists the members of an exported tarball, `.wsl` package, encrypted
+//! export or backup without extracting it, and restores selected files or
+//! directories either into a distribution (streamed into `tar -x` running as
+//! root) or into a Windows folder.
+//!
+//! A selected path is restored under its own name: selecting
+//! `home/alice/projects` and restoring to `/home/alice` recreates
+//! `/home/alice/projects` with everything below it.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+
+use log::info;
+use serde::{Deserialize, Serialize};
+use tar::{Archive, EntryType};
+
+use super::archive::{self, normalize_entry_path};
+use super::backups;
+use super::encryption::{self, DecryptionKey};
+use super::executor::wsl_executor;
+use super::types::WslError;
+use crate::utils::is_mock_mode;
+use crate::validation::validate_file_path;
+
+/// Characters Windows does not allow in file names (besides `/`)
+const WINDOWS_RESERVED_CHARS: [char; 8] = ['<', '>', ':', '"', '|', '?', '*', '\\'];
+
+/// Where the archive comes from
+#[derive(Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ArchiveSource {
+    /// A local `.tar`, `.tar.gz` or `.wsl` export, or an encrypted export
+    /// together with its passphrase or secret key
+    File {
+        path: String,
+        #[serde(default)]
+        key: Option<DecryptionKey>,
+    },
+    /// A backup on a backup target
+    #[serde(rename_all = "camelCase")]
+    Backup {
+        target_id: String,
+        backup_id: String,
+    },
+}
+
+/// Type of an archive member
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ArchiveEntryKind {
+    File,
+    Directory,
+    Symlink,
+    Hardlink,
+    /// Device nodes, FIFOs and other special files
+    Other,
+}
+
+/// One member of an archive
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveEntry {
+    /// Normalized path without a leading `/`, e.g. `etc/hostname`
+    pub path: String,
+    pub kind: ArchiveEntryKind,
+    /// Size in bytes (0 for everything but files)
+    pub size: u64,
+    /// Permission bits, e.g. `0o644`
+    pub mode: u32,
+    pub uid: u64,
+    pub gid: u64,
+    /// Modification time in seconds since the Unix epoch
+    pub mtime: u64,
+    /// Target of a symlink or hard link
+    pub link_target: Option<String>,
+}
+
+/// Contents of an archive, sorted by path. Every parent directory has an
+/// entry, so the list can be shown as a tree.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveListing {
+    pub entries: Vec<ArchiveEntry>,
+    /// Total size of all files
+    pub total_bytes: u64,
+}
+
+/// A selected member that was not restored
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedEntry {
+    pub path: String,
+    pub reason: String,
+}
+
+/// Outcome of restoring selected files
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileRestoreReport {
+    /// Members restored, including directories and links
+    pub restored: usize,
+    /// Bytes of file data restored
+    pub bytes: u64,
+    pub skipped: Vec<SkippedEntry>,
+}
+
+/// In mock mode, backups and missing files are listed with canned contents
+fn is_mock_source(source: &ArchiveSource) -> bool {
+    is_mock_mode()
+        && match source {
+            ArchiveSource::File { path, .. } => !Path::new(path).is_file(),
+            ArchiveSource::Backup { .. } => true,
+        }
+}
+
+/// Open the decompressed (and decrypted) tar stream of a source
+fn open_source(source: &ArchiveSource) -> Result<Box<dyn Read>, WslError> {
+    let (path, key) = match source {
+        ArchiveSource::File { path, key } => (PathBuf::from(path), key.as_ref()),
+        ArchiveSource::Backup {
+            target_id,
+            backup_id,
+        } => (backups::browse_tar(target_id, backup_id)?, None),
+    };
+    let file = BufReader::new(File::open(&path).map_err(|e| {
+        WslError::CommandFailed(format!("Failed to open {}: {}", path.display(), e))
+    })?);
+    let reader: Box<dyn Read> = match (encryption::detect(&path)?, key) {
+        (None, _) => Box::new(file),
+        (Some(_), Some(key)) => Box::new(encryption::decrypt(key, file)?),
+        (Some(_), None) => {
+            return Err(WslError::CommandFailed(format!(
+                "'{}' is encrypted. Enter its passphrase or key to browse it.",
+                path.display()
+            )))
+        }
+    };
+    Ok(archive::open_reader(reader, &path.display().to_string())?.into_inner())
+}
+
+fn read_error(e: std::io::Error) -> WslError {
+    WslError::CommandFailed(format!("Failed to read archive: {}", e))
+}
+
+fn entry_kind(entry_type: EntryType) -> ArchiveEntryKind {
+    match entry_type {
+        EntryType::Regular | EntryType::Continuous => ArchiveEntryKind::File,
+        EntryType::Directory => ArchiveEntryKind::Directory,
+        EntryType::Symlink => ArchiveEntryKind::Symlink,
+        EntryType::Link => ArchiveEntryKind::Hardlink,
+        _ => ArchiveEntryKind::Other,
+    }
+}
+
+fn directory_entry(path: String) -> ArchiveEntry {
+    ArchiveEntry {
+        path,
+        kind: ArchiveEntryKind::Directory,
+        size: 0,
+        mode: 0o755,
+        uid: 0,
+        gid: 0,
+        mtime: 0,
+        link_target: None,
+    }
+}
+
+/// List the members of a tar stream
+fn list_reader<R: Read>(input: R) -> Result<ArchiveListing, WslError> {
+    let mut entries = Vec::new();
+    let mut directories = HashSet::new();
+
+    let mut archive = Archive::new(input);
+    for entry in archive.entries().map_err(read_error)? {
+        let entry = entry.map_err(read_error)?;
+        let path = normalize_entry_path(&entry.path().map_err(read_error)?.to_string_lossy());
+        if path.is_empty() {
+            continue;
+        }
+        let header = entry.header();
+        let kind = entry_kind(header.entry_type());
+        let size = if kind == ArchiveEntryKind::File {
+            entry.size()
+        } else {
+            0
+        };
+        let link_target = match kind {
+            ArchiveEntryKind::Symlink | ArchiveEntryKind::Hardlink => entry
+                .link_name()
+                .map_err(read_error)?
+                .map(|target| target.to_string_lossy().into_owned()),
+            _ => None,
+        };
+        if kind == ArchiveEntryKind::Directory {
+            directories.insert(path.clone());
+        }
+        entries.push(ArchiveEntry {
+            path,
+            kind,
+            size,
+            mode: header.mode().unwrap_or(0) & 0o7777,
+            uid: header.uid().unwrap_or(0),
+            gid: header.gid().unwrap_or(0),
+            mtime: header.mtime().unwrap_or(0),
+            link_target,
+        });
+    }
+
+    // Archives do not always list every directory; add the missing ones so
+    // the listing forms a complete tree
+    let mut missing = HashSet::new();
+    for entry in &entries {
+        let mut current = entry.path.as_str();
+        while let Some((parent, _)) = current.rsplit_once('/') {
+            if directories.contains(parent) || !missing.insert(parent.to_string()) {
+                break;
+            }
+            current = parent;
+        }
+    }
+    entries.extend(missing.into_iter().map(directory_entry));
+
+    // A path stored twice is extracted from its last copy; keep that one
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    entries.dedup_by(|later, earlier| {
+        let duplicate = later.path == earlier.path;
+        if duplicate {
+            std::mem::swap(later, earlier);
+        }
+        duplicate
+    });
+    let total_bytes = entries.iter().map(|e| e.size).sum();
+
+    Ok(ArchiveListing {
+        entries,
+        total_bytes,
+    })
+}
+
+/// List the contents of an export or backup without extracting it
+pub fn list_contents(source: &ArchiveSource) -> Result<ArchiveListing, WslError> {
+    if is_mock_source(source) {
+        return list_reader(mock_archive().as_slice());
+    }
+    list_reader(open_source(source)?)
+}
+
+/// Selected archive paths and the names they are restored under
+struct Selection {
+    /// Normalized paths, outermost first
+    paths: Vec<String>,
+}
+
+impl Selection {
+    fn new(paths: &[String]) -> Result<Self, WslError> {
+        let mut paths: Vec<String> = paths.iter().map(|p| normalize_entry_path(p)).collect();
+        if paths.is_empty() {
+            return Err(WslError::CommandFailed(
+                "Select at least one file or directory to restore".to_string(),
+            ));
+        }
+        if paths.iter().any(|p| p.is_empty()) {
+            return Err(WslError::CommandFailed(
+                "The archive root cannot be restored file by file; import the archive instead"
+                    .to_string(),
+            ));
+        }
+        paths.sort_by_key(|p| p.matches('/').count());
+        Ok(Self { paths })
+    }
+
+    /// Path `path` is restored as, relative to the destination; `None` when
+    /// it is not selected
+    fn target(&self, path: &str) -> Option<String> {
+        self.paths.iter().find_map(|selected| {
+            let inside = path == selected
+                || path
+                    .strip_prefix(selected.as_str())
+                    .is_some_and(|rest| rest.starts_with('/'));
+            inside.then(|| {
+                let parent_len = selected.rfind('/').map_or(0, |i| i + 1);
+                path[parent_len..].to_string()
+            })
+        })
+    }
+}
+
+/// Copy the selected members of `input` into a new tar stream, renamed
+/// relative to the destination. Hard links are kept when their target is
+/// restored too.
+fn write_selection<R: Read, W: Write>(
+    input: R,
+    output: W,
+    selection: &Selection,
+    report: &mut FileRestoreReport,
+) -> Result<W, WslError> {
+    let write_error =
+        |e: std::io::Error| WslError::CommandFailed(format!("Failed to write archive: {}", e));
+    let mut archive = Archive::new(input);
+    let mut builder = tar::Builder::new(output);
+    // archive path -> restored path
+    let mut restored: HashMap<String, String> = HashMap::new();
+
+    for entry in archive.entries().map_err(read_error)? {
+        let mut entry = entry.map_err(read_error)?;
+        let path = normalize_entry_path(&entry.path().map_err(read_error)?.to_string_lossy());
+        let Some(target) = selection.target(&path) else {
+            continue;
+        };
+        let entry_type = entry.header().entry_type();
+        let link = match entry_type {
+            EntryType::Symlink => Some(
+                entry
+                    .link_name()
+                    .map_err(read_error)?
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .into_owned(),
+            ),
+            EntryType::Link => {
+                let linked = normalize_entry_path(
+                    &entry
+                        .link_name()
+                        .map_err(read_error)?
+                        .unwrap_or_default()
+                        .to_string_lossy(),
+                );
+                match restored.get(&linked) {
+                    Some(linked_target) => Some(linked_target.clone()),
+                    None => {
+                        report.skipped.push(SkippedEntry {
+                            path,
+                            reason: format!("hard link to {}, which is not restored", linked),
+                        });
+                        continue;
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        let mut header = archive::copy_header(entry.header()).map_err(read_error)?;
+        let xattrs = archive::xattrs(&mut entry).map_err(read_error)?;
+        if !xattrs.is_empty() {
+            builder
+                .append_pax_extensions(xattrs.iter().map(|(k, v)| (k.as_str(), v.as_slice())))
+                .map_err(write_error)?;
+        }
+        match link {
+            Some(link) => {
+                header.set_size(0);
+                builder
+                    .append_link(&mut header, &target, &link)
+                    .map_err(write_error)?;
+            }
+            None => {
+                if matches!(entry_type, EntryType::Regular | EntryType::Continuous) {
+                    report.bytes += entry.size();
+                }
+                builder
+                    .append_data(&mut header, &target, &mut entry)
+                    .map_err(write_error)?;
+            }
+        }
+        report.restored += 1;
+        restored.insert(path, target);
+    }
+
+    builder.into_inner().map_err(write_error)
+}
+
+pub(super) fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Normalize an absolute Linux path chosen as the restore destination
+fn distro_destination(destination: &str) -> Result<String, WslError> {
+    let trimmed = destination.trim();
+    if !trimmed.starts_with('/') || trimmed.contains('\0') || trimmed.split('/').any(|c| c == "..")
+    {
+        return Err(WslError::CommandFailed(format!(
+            "'{}' is not an absolute Linux path",
+            destination
+        )));
+    }
+    Ok(format!("/{}", normalize_entry_path(trimmed)))
+}
+
+/// Fail when nothing in the archive matched the selection
+fn ensure_restored(report: FileRestoreReport) -> Result<FileRestoreReport, WslError> {
+    if report.restored == 0 && report.skipped.is_empty() {
+        return Err(WslError::CommandFailed(
+            "None of the selected paths are in the archive".to_string(),
+        ));
+    }
+    Ok(report)
+}
+
+/// Restore selected files or directories into a distribution. The selection
+/// is streamed as a tar into `tar -x` running as root in `destination`,
+/// keeping ownership, permissions and extended attributes.
+pub fn restore_to_distro(
+    source: &ArchiveSource,
+    paths: &[String],
+    distro: &str,
+    destination: &str,
+) -> Result<FileRestoreReport, WslError> {
+    let selection = Selection::new(paths)?;
+    let destination = distro_destination(destination)?;
+    let mut input = if is_mock_source(source) {
+        Box::new(std::io::Cursor::new(mock_archive())) as Box<dyn Read>
+    } else {
+        open_source(source)?
+    };
+
+    let command = format!(
+        "mkdir -p {0} && tar -xf - -C {0}",
+        shell_quote(&destination)
+    );
+    let mut report = FileRestoreReport::default();
+    let output = wsl_executor().exec_as_root_with_input(distro, None, &command, &mut |stdin| {
+        write_selection(&mut input, stdin, &selection, &mut report)
+            .map(|_| ())
+            .map_err(|e| std::io::Error::other(e.to_string()))
+    })?;
+    if !output.success {
+        let detail = if output.stderr.trim().is_empty() {
+            output.stdout
+        } else {
+            output.stderr
+        };
+        return Err(WslError::CommandFailed(format!(
+            "Failed to restore files into '{}': {}",
+            distro,
+            detail.trim()
+        )));
+    }
+
+    let report = ensure_restored(report)?;
+    info!(
+        "Restored {} entries ({} bytes) into '{}' at {}",
+        report.restored, report.bytes, distro, destination
+    );
+    Ok(report)
+}
+
+/// Whether Windows maps `component` to a device (`CON`, `nul.txt`,
+/// `COM1`, ...) in every directory, regardless of extension.
+fn is_windows_device_name(component: &str) -> bool {
+    let stem = component
+        .split('.')
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches(' ')
+        .to_uppercase();
+    match stem.as_str() {
+        "CON" | "PRN" | "AUX" | "NUL" | "CONIN$" | "CONOUT$" => true,
+        _ => ["COM", "LPT"].iter().any(|prefix| {
+            let mut rest = stem.strip_prefix(prefix).unwrap_or_default().chars();
+            matches!(
+                (rest.next(), rest.next()),
+                (Some('0'..='9' | '\u{b9}' | '\u{b2}' | '\u{b3}'), None)
+            )
+        }),
+    }
+}
+
+/// Relative Windows path for a restored member, or why it cannot be created
+fn windows_relative_path(relative: &str) -> Result<PathBuf, String> {
+    validate_file_path(relative).map_err(|e| e.to_string())?;
+    if relative.contains(WINDOWS_RESERVED_CHARS) {
+        return Err("name contains characters Windows does not allow".to_string());
+    }
+    for component in relative.split('/') {
+        if component.ends_with(['.', ' ']) {
+            return Err("name ends with a dot or space, which Windows drops".to_string());
+        }
+        if is_windows_device_name(component) {
+            return Err("name is reserved for a device on Windows".to_string());
+        }
+    }
+    Ok(relative.split('/').collect())
+}
+
+/// Extract selected files or directories into a Windows folder. Members
+/// whose names are unsafe or invalid on Windows, symlinks and special files
+/// are skipped and listed in the report.
+pub fn extract_to_folder(
+    source: &ArchiveSource,
+    paths: &[String],
+    destination: &str,
+) -> Result<FileRestoreReport, WslError> {
+    validate_file_path(destination).map_err(|e| WslError::CommandFailed(e.to_string()))?;
+    let selection = Selection::new(paths)?;
+    if is_mock_source(source) {
+        info!("Mock: extracted {} paths to {}", paths.len(), destination);
+        return Ok(FileRestoreReport {
+            restored: paths.len(),
+            ..Default::default()
+        });
+    }
+
+    let root = PathBuf::from(destination);
+    let write_error = |path: &Path, e: std::io::Error| {
+        WslError::CommandFailed(format!("Failed to write {}: {}", path.display(), e))
+    };
+    std::fs::create_dir_all(&root).map_err(|e| write_error(&root, e))?;
+
+    let mut report = FileRestoreReport::default();
+    // archive path -> extracted file, for hard links
+    let mut extracted: HashMap<String, PathBuf> = HashMap::new();
+    let mut archive = Archive::new(open_source(source)?);
+    for entry in archive.entries().map_err(read_error)? {
+        let mut entry = entry.map_err(read_error)?;
+        let path = normalize_entry_path(&entry.path().map_err(read_error)?.to_string_lossy());
+        let Some(target) = selection.target(&path) else {
+            continue;
+        };
+        let mut skip = |reason: &str| {
+            report.skipped.push(SkippedEntry {
+                path: path.clone(),
+                reason: reason.to_string(),
+            })
+        };
+        let local = match windows_relative_path(&target) {
+            Ok(relative) => root.join(relative),
+            Err(reason) => {
+                skip(&reason);
+                continue;
+            }
+        };
+
+        let entry_type = entry.header().entry_type();
+        let parent_ready = |local: &Path| match local.parent() {
+            Some(parent) => std::fs::create_dir_all(parent).map_err(|e| write_error(parent, e)),
+            None => Ok(()),
+        };
+        match entry_type {
+            EntryType::Directory => {
+                std::fs::create_dir_all(&local).map_err(|e| write_error(&local, e))?;
+            }
+            EntryType::Regular | EntryType::Continuous => {
+                parent_ready(&local)?;
+                let mut file = File::create(&local).map_err(|e| write_error(&local, e))?;
+                report.bytes += std::io::copy(&mut entry, &mut file).map_err(read_error)?;
+                extracted.insert(path.clone(), local.clone());
+            }
+            EntryType::Link => {
+                let linked = normalize_entry_path(
+                    &entry
+                        .link_name()
+                        .map_err(read_error)?
+                        .unwrap_or_default()
+                        .to_string_lossy(),
+                );
+                let Some(original) = extracted.get(&linked) else {
+                    skip(&format!("hard link to {}, which is not restored", linked));
+                    continue;
+                };
+                parent_ready(&local)?;
+                std::fs::copy(original, &local).map_err(|e| write_error(&local, e))?;
+                extracted.insert(path.clone(), local.clone());
+            }
+            EntryType::Symlink => {
+                skip("symbolic links are not extracted to Windows folders");
+                continue;
+            }
+            _ => {
+                skip("special files are not extracted to Windows folders");
+                continue;
+            }
+        }
+        report.restored += 1;
+    }
+
+    let report = ensure_restored(report)?;
+    info!(
+        "Extracted {} entries ({} bytes) to {}",
+        report.restored, report.bytes, destination
+    );
+    Ok(report)
+}
+
+/// Small rootfs used in mock mode
+fn mock_archive() -> Vec<u8> {
+    let mut builder = tar::Builder::new(Vec::new());
+    let files: [(&str, &[u8]); 5] = [
+        ("./etc/hostname", b"mock\n"),
+        ("./etc/os-release", b"ID=ubuntu\nVERSION_ID=\"24.04\"\n"),
+        ("./home/user/.bashrc", b"alias ll='ls -l'\n"),
+        ("./home/user/notes.txt", b"Remember to back up\n"),
+        ("./usr/bin/bash", b"\x7fELF"),
+    ];
+    for (path, data) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(1_700_000_000);
+        builder
+            .append_data(&mut header, path, data)
+            .expect("in-memory tar");
+    }
+    let mut header = tar::Header::new_gnu();
+    header.set_entry_type(EntryType::Symlink);
+    header.set_size(0);
+    header.set_mode(0o777);
+    builder
+        .append_link(&mut header, "./usr/bin/sh", "bash")
+        .expect("in-memory tar");
+    builder.into_inner().expect("in-memory tar")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_archive() -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut append = |path: &str, entry_type: EntryType, data: &[u8], link: Option<&str>| {
+            let mut header = tar::Header::new_gnu();
+            header.set_entry_type(entry_type);
+            header.set_size(data.len() as u64);
+            header.set_mode(if entry_type == EntryType::Directory {
+                0o700
+            } else {
+                0o640
+            });
+            header.set_uid(1000);
+            match link {
+                Some(link) => builder.append_link(&mut header, path, link).unwrap(),
+                None => builder.append_data(&mut header, path, data).unwrap(),
+            }
+        };
+        append("./home/alice/", EntryType::Directory, b"", None);
+        append("./home/alice/notes.txt", EntryType::Regular, b"old", None);
+        append(
+            "./home/alice/project/main.rs",
+            EntryType::Regular,
+            b"fn main() {}",
+            None,
+        );
+        append(
+            "./home/alice/project/copy.rs",
+            EntryType::Link,
+            b"",
+            Some("./home/alice/project/main.rs"),
+        );
+        append(
+            "./home/alice/project/latest",
+            EntryType::Symlink,
+            b"",
+            Some("main.rs"),
+        );
+        append(
+            "./home/alice/notes-link",
+            EntryType::Link,
+            b"",
+            Some("./home/alice/notes.txt"),
+        );
+        append("./home/alice/notes.txt", EntryType::Regular, b"newer", None);
+        builder.into_inner().unwrap()
+    }
+
+    fn names(data: &[u8]) -> Vec<(String, EntryType)> {
+        let mut archive = Archive::new(data);
+        archive
+            .entries()
+            .unwrap()
+            .map(|e| {
+                let e = e.unwrap();
+                (
+                    e.path().unwrap().to_string_lossy().into_owned(),
+                    e.header().entry_type(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn listing_fills_in_parent_directories_and_keeps_the_last_copy() {
+        let listing = list_reader(sample_archive().as_slice()).unwrap();
+        let paths: Vec<&str> = listing.entries.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "home",
+                "home/alice",
+                "home/alice/notes-link",
+                "home/alice/notes.txt",
+                "home/alice/project",
+                "home/alice/project/copy.rs",
+                "home/alice/project/latest",
+                "home/alice/project/main.rs",
+            ]
+        );
+        let notes = &listing.entries[3];
+        assert_eq!(
+            (notes.kind, notes.size, notes.mode, notes.uid),
+            (ArchiveEntryKind::File, 5, 0o640, 1000)
+        );
+        assert_eq!(listing.entries[6].link_target.as_deref(), Some("main.rs"));
+        assert_eq!(listing.entries[0].kind, ArchiveEntryKind::Directory);
+        assert_eq!(listing.total_bytes, 12 + 5);
+    }
+
+    #[test]
+    fn selection_restores_paths_under_their_own_names() {
+        let selection = Selection::new(&[
+            "/home/alice/project/main.rs".to_string(),
+            "home/alice/project".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(
+            selection.target("home/alice/project").as_deref(),
+            Some("project")
+        );
+        assert_eq!(
+            selection.target("home/alice/project/main.rs").as_deref(),
+            Some("project/main.rs")
+        );
+        assert_eq!(selection.target("home/alice/projects"), None);
+        assert!(Selection::new(&[]).is_err());
+        assert!(Selection::new(&["./".to_string()]).is_err());
+
+        let mut report = FileRestoreReport::default();
+        let selection = Selection::new(&[
+            "home/alice/project".to_string(),
+            "home/alice/notes-link".to_string(),
+        ])
+        .unwrap();
+        let output = write_selection(
+            sample_archive().as_slice(),
+            Vec::new(),
+            &selection,
+            &mut report,
+        )
+        .unwrap();
+        assert_eq!(
+            names(&output),
+            [
+                ("project/main.rs".to_string(), EntryType::Regular),
+                ("project/copy.rs".to_string(), EntryType::Link),
+                ("project/latest".to_string(), EntryType::Symlink),
+            ]
+        );
+        assert_eq!((report.restored, report.bytes), (3, 12));
+        assert_eq!(report.skipped.len(), 1);
+        assert!(report.skipped[0].reason.contains("home/alice/notes.txt"));
+    }
+
+    #[test]
+    fn extracting_to_a_folder_skips_links_and_unsafe_names() {
+        let dir = crate::utils::unique_temp_dir("archive-files");
+        let archive_path = dir.join("export.tar");
+        let mut data = sample_archive();
+        // Replace the end-of-archive blocks with one more member
+        data.truncate(data.len() - 1024);
+        let mut builder = tar::Builder::new(data);
+        let mut header = tar::Header::new_gnu();
+        header.set_size(1);
+        header.set_mode(0o644);
+        builder
+            .append_data(&mut header, "home/alice/project/a:b", &b"x"[..])
+            .unwrap();
+        std::fs::write(&archive_path, builder.into_inner().unwrap()).unwrap();
+
+        let source = ArchiveSource::File {
+            path: archive_path.to_string_lossy().into_owned(),
+            key: None,
+        };
+        let out = dir.join("restored");
+        let report = extract_to_folder(
+            &source,
+            &["home/alice/project".to_string()],
+            &out.to_string_lossy(),
+        )
+        .unwrap();
+        assert_eq!(
+            std::fs::read(out.join("project").join("main.rs")).unwrap(),
+            b"fn main() {}"
+        );
+        assert_eq!(
+            std::fs::read(out.join("project").join("copy.rs")).unwrap(),
+            b"fn main() {}"
+        );
+        assert_eq!(report.restored, 2);
+        let skipped: Vec<&str> = report.skipped.iter().map(|s| s.path.as_str()).collect();
+        assert_eq!(
+            skipped,
+            ["home/alice/project/latest", "home/alice/project/a:b"]
+        );
+
+        let missing = extract_to_folder(&source, &["etc".to_string()], &out.to_string_lossy());
+        assert!(missing
+            .unwrap_err()
+            .to_string()
+            .contains("None of the selected"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn windows_paths_refuse_names_windows_cannot_create() {
+        assert_eq!(
+            windows_relative_path("project/main.rs").unwrap(),
+            PathBuf::from("project").join("main.rs")
+        );
+        for name in [
+            "CON",
+            "docs/nul.txt",
+            "com1",
+            "logs/LPT9.log",
+            "COM\u{b9}",
+            "conin$",
+            "aux .tar.gz",
+            "notes.",
+            "dir /file",
+            "project/trailing ",
+            "a:b",
+        ] {
+            assert!(windows_relative_path(name).is_err(), "{} should be refused", name);
+        }
+        for name in ["console.log", "nullable/x", "COM10", "lpt", "my.con", ".bashrc"] {
+            assert!(windows_relative_path(name).is_ok(), "{} should be allowed", name);
+        }
+    }
+
+    #[test]
+    fn distro_destination_must_be_absolute() {
+        assert_eq!(distro_destination("/home/alice/").unwrap(), "/home/alice");
+        assert_eq!(distro_destination("/").unwrap(), "/");
+        assert!(distro_destination("home").is_err());
+        assert!(distro_destination("/home/../etc").is_err());
+        assert_eq!(shell_quote("/tmp/it's"), "'/tmp/it'\\''s'");
+    }
+}
@@ -0,0 +1,216 @@
+//! Content-defined chunking (FastCDC with normalized chunking)
+//!
+//! Chunk boundaries depend only on the bytes around them, so inserting or
+//! removing data in one file of a tar stream changes the chunks around that
+//! spot and leaves the rest of the stream chunked the same way.
+
+use std::io::Read;
+
+/// Chunk size limits
+#[derive(Debug, Clone, Copy)]
+pub struct ChunkerParams {
+    pub min_size: usize,
+    /// Target average size is `2^avg_bits`
+    pub avg_bits: u32,
+    pub max_size: usize,
+}
+
+impl ChunkerParams {
+    /// 512 KiB minimum, 2 MiB average, 8 MiB maximum
+    pub const DEFAULT: ChunkerParams = ChunkerParams {
+        min_size: 512 * 1024,
+        avg_bits: 21,
+        max_size: 8 * 1024 * 1024,
+    };
+
+    fn avg_size(&self) -> usize {
+        1 << self.avg_bits
+    }
+
+    /// Stricter mask used before the average size is reached
+    fn mask_small(&self) -> u64 {
+        top_bits(self.avg_bits + 2)
+    }
+
+    /// Looser mask used after the average size is reached
+    fn mask_large(&self) -> u64 {
+        top_bits(self.avg_bits.saturating_sub(2).max(1))
+    }
+}
+
+fn top_bits(n: u32) -> u64 {
+    !0u64 << (64 - n.min(63))
+}
+
+/// Random-looking table for the gear hash, generated with SplitMix64 so it is
+/// the same on every machine (chunk boundaries must never change)
+const GEAR: [u64; 256] = {
+    let mut table = [0u64; 256];
+    let mut state: u64 = 0x5753_4c2d_5549_2d43; // "WSL-UI-C"
+    let mut i = 0;
+    while i < 256 {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        table[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    table
+};
+
+/// Length of the first chunk of `data`; `data` is the whole rest of the
+/// stream or at least `max_size` bytes of it
+fn cut_point(data: &[u8], params: &ChunkerParams) -> usize {
+    let len = data.len();
+    if len <= params.min_size {
+        return len;
+    }
+    let normal = params.avg_size().min(len);
+    let end = params.max_size.min(len);
+    let (mask_small, mask_large) = (params.mask_small(), params.mask_large());
+
+    let mut hash: u64 = 0;
+    let mut i = params.min_size;
+    while i < normal {
+        hash = (hash << 1).wrapping_add(GEAR[data[i] as usize]);
+        if hash & mask_small == 0 {
+            return i + 1;
+        }
+        i += 1;
+    }
+    while i < end {
+        hash = (hash << 1).wrapping_add(GEAR[data[i] as usize]);
+        if hash & mask_large == 0 {
+            return i + 1;
+        }
+        i += 1;
+    }
+    end
+}
+
+/// Splits a stream into content-defined chunks
+pub struct Chunker<R: Read> {
+    reader: R,
+    params: ChunkerParams,
+    buffer: Vec<u8>,
+    eof: bool,
+}
+
+impl<R: Read> Chunker<R> {
+    pub fn new(reader: R, params: ChunkerParams) -> Self {
+        Self {
+            reader,
+            params,
+            buffer: Vec::with_capacity(params.max_size),
+            eof: false,
+        }
+    }
+
+    /// Fill the buffer up to `max_size` bytes or the end of the stream
+    fn fill(&mut self) -> std::io::Result<()> {
+        while !self.eof && self.buffer.len() < self.params.max_size {
+            let start = self.buffer.len();
+            self.buffer.resize(self.params.max_size, 0);
+            match self.reader.read(&mut self.buffer[start..]) {
+                Ok(0) => {
+                    self.buffer.truncate(start);
+                    self.eof = true;
+                }
+                Ok(n) => self.buffer.truncate(start + n),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {
+                    self.buffer.truncate(start)
+                }
+                Err(e) => {
+                    self.buffer.truncate(start);
+                    return Err(e);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Iterator for Chunker<R> {
+    type Item = std::io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Err(e) = self.fill() {
+            return Some(Err(e));
+        }
+        if self.buffer.is_empty() {
+            return None;
+        }
+        let cut = cut_point(&self.buffer, &self.params);
+        let rest = self.buffer.split_off(cut);
+        Some(Ok(std::mem::replace(&mut self.buffer, rest)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SMALL: ChunkerParams = ChunkerParams {
+        min_size: 64,
+        avg_bits: 8,
+        max_size: 1024,
+    };
+
+    /// Deterministic pseudo-random bytes
+    fn noise(len: usize, seed: u64) -> Vec<u8> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect()
+    }
+
+    fn chunks(data: &[u8]) -> Vec<Vec<u8>> {
+        Chunker::new(data, SMALL).collect::<Result<_, _>>().unwrap()
+    }
+
+    #[test]
+    fn chunks_reassemble_and_respect_limits() {
+        let data = noise(50_000, 1);
+        let parts = chunks(&data);
+        assert_eq!(parts.concat(), data);
+        assert!(parts.len() > 20);
+        for part in &parts[..parts.len() - 1] {
+            assert!(part.len() > SMALL.min_size && part.len() <= SMALL.max_size);
+        }
+        assert!(chunks(&[]).is_empty());
+        assert_eq!(chunks(&[7; 10]), vec![vec![7; 10]]);
+        let zeros = chunks(&[0; 5000]);
+        assert_eq!(zeros.concat().len(), 5000);
+        assert!(zeros.iter().all(|c| c.len() <= SMALL.max_size));
+    }
+
+    #[test]
+    fn an_insertion_only_changes_nearby_chunks() {
+        let original = noise(50_000, 2);
+        let mut edited = original.clone();
+        edited.splice(25_000..25_000, b"a few inserted bytes".iter().copied());
+
+        let before = chunks(&original);
+        let after = chunks(&edited);
+        let shared = after.iter().filter(|c| before.contains(c)).count();
+        assert!(
+            shared + 3 >= after.len(),
+            "only {} of {} chunks shared",
+            shared,
+            after.len()
+        );
+    }
+
+    #[test]
+    fn gear_table_is_stable() {
+        // Changing the table would re-chunk every existing repository
+        assert_eq!(GEAR[0], 0x3fdf_c013_cf79_0790);
+        assert_eq!(GEAR[255], 0x524e_fb0e_f0ee_7865);
+    }
+}
@@ -0,0 +1,521 @@
+//! Deduplicated, chunked backup repository
+//!
+//! A repository lives on a backup target next to ordinary archives. The
+//! exported tar stream is split into content-defined chunks (see
+//! [`chunker`]); each chunk is stored once, gzip-compressed, under
+//! `chunk-<sha256>`. A backup is a small `<id>.index` listing its chunks in
+//! order, so consecutive backups of the same distribution only store the
+//! chunks that changed.
+//!
+//! Chunks are shared by all backups on the target, so they are only deleted
+//! by [`prune`], which removes chunks no index refers to. Writes and prunes
+//! are serialized within the app; a repository must not be written by two
+//! machines at once.
+
+mod chunker;
+
+pub use chunker::{Chunker, ChunkerParams};
+
+use super::backup_targets::BackupTarget;
+use super::types::WslError;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Key prefix of chunk objects
+const CHUNK_PREFIX: &str = "chunk-";
+
+/// Key suffix of backup indexes
+pub const INDEX_SUFFIX: &str = ".index";
+
+/// Current index format version
+const INDEX_VERSION: u32 = 1;
+
+lazy_static::lazy_static! {
+    /// Keeps a prune from deleting chunks a running backup has just
+    /// uploaded or deduplicated against
+    static ref REPOSITORY_LOCK: Mutex<()> = Mutex::new(());
+}
+
+/// One chunk of a backup
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkRef {
+    /// Lowercase hex SHA256 of the uncompressed chunk
+    pub hash: String,
+    pub size: u64,
+}
+
+/// The chunks of one backup, in stream order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepositoryIndex {
+    pub version: u32,
+    pub backup_id: String,
+    /// Size and SHA256 of the reassembled tar
+    pub size_bytes: u64,
+    pub sha256: String,
+    pub chunks: Vec<ChunkRef>,
+}
+
+/// What storing a backup wrote
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StoreStats {
+    pub chunks: usize,
+    pub new_chunks: usize,
+    /// Compressed bytes uploaded, including the index
+    pub stored_bytes: u64,
+}
+
+/// A chunk an index refers to that is not in the repository
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingChunk {
+    pub backup_id: String,
+    pub hash: String,
+}
+
+/// Result of [`check`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckReport {
+    pub backups: usize,
+    pub chunks: usize,
+    /// Compressed size of all chunks
+    pub stored_bytes: u64,
+    /// Total size of all backups before deduplication
+    pub logical_bytes: u64,
+    pub missing_chunks: Vec<MissingChunk>,
+    /// Chunks whose content does not match their hash (only with `verify_data`)
+    pub corrupt_chunks: Vec<String>,
+    /// Chunks no backup refers to; removed by a prune
+    pub unreferenced_chunks: usize,
+    pub unreadable_indexes: Vec<String>,
+    pub data_verified: bool,
+}
+
+impl CheckReport {
+    pub fn is_healthy(&self) -> bool {
+        self.missing_chunks.is_empty()
+            && self.corrupt_chunks.is_empty()
+            && self.unreadable_indexes.is_empty()
+    }
+}
+
+/// Result of [`prune`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneReport {
+    pub deleted_chunks: usize,
+    pub freed_bytes: u64,
+}
+
+pub fn index_key(backup_id: &str) -> String {
+    format!("{}{}", backup_id, INDEX_SUFFIX)
+}
+
+fn chunk_key(hash: &str) -> String {
+    format!("{}{}", CHUNK_PREFIX, hash)
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// Chunk hashes and compressed sizes in the repository
+fn stored_chunks(target: &dyn BackupTarget) -> Result<HashMap<String, u64>, WslError> {
+    Ok(target
+        .list()?
+        .into_iter()
+        .filter_map(|o| {
+            o.key
+                .strip_prefix(CHUNK_PREFIX)
+                .map(|hash| (hash.to_string(), o.size_bytes))
+        })
+        .collect())
+}
+
+/// IDs of the backups that have an index
+fn backup_ids(target: &dyn BackupTarget) -> Result<Vec<String>, WslError> {
+    Ok(target
+        .list()?
+        .into_iter()
+        .filter_map(|o| o.key.strip_suffix(INDEX_SUFFIX).map(String::from))
+        .collect())
+}
+
+fn compress(data: &[u8]) -> Result<Vec<u8>, WslError> {
+    let mut encoder = GzEncoder::new(Vec::with_capacity(data.len() / 2), Compression::default());
+    encoder.write_all(data)?;
+    Ok(encoder.finish()?)
+}
+
+/// Download a chunk, decompress it and check its hash
+fn read_chunk(target: &dyn BackupTarget, hash: &str) -> Result<Vec<u8>, WslError> {
+    let compressed = target.get_bytes(&chunk_key(hash))?;
+    let mut data = Vec::new();
+    GzDecoder::new(compressed.as_slice())
+        .read_to_end(&mut data)
+        .map_err(|e| WslError::CommandFailed(format!("Chunk {} is corrupted: {}", hash, e)))?;
+    let actual = sha256_hex(&data);
+    if actual != hash {
+        return Err(WslError::CommandFailed(format!(
+            "Chunk {} is corrupted: content hashes to {}",
+            hash, actual
+        )));
+    }
+    Ok(data)
+}
+
+/// Chunk `tar` into the repository and write its index. Chunks already in
+/// the repository are not uploaded again.
+pub fn store_tar(
+    target: &dyn BackupTarget,
+    tar: &Path,
+    backup_id: &str,
+    params: ChunkerParams,
+) -> Result<(RepositoryIndex, StoreStats), WslError> {
+    let _guard = REPOSITORY_LOCK.lock().unwrap_or_else(|p| p.into_inner());
+    let mut known: HashSet<String> = stored_chunks(target)?.into_keys().collect();
+    let file = std::fs::File::open(tar)?;
+
+    let mut whole = Sha256::new();
+    let mut chunks = Vec::new();
+    let mut stats = StoreStats {
+        chunks: 0,
+        new_chunks: 0,
+        stored_bytes: 0,
+    };
+    for chunk in Chunker::new(std::io::BufReader::new(file), params) {
+        let chunk = chunk?;
+        whole.update(&chunk);
+        let hash = sha256_hex(&chunk);
+        if !known.contains(&hash) {
+            let compressed = compress(&chunk)?;
+            target.put_bytes(&chunk_key(&hash), &compressed)?;
+            stats.new_chunks += 1;
+            stats.stored_bytes += compressed.len() as u64;
+            known.insert(hash.clone());
+        }
+        chunks.push(ChunkRef {
+            hash,
+            size: chunk.len() as u64,
+        });
+    }
+    stats.chunks = chunks.len();
+
+    let index = RepositoryIndex {
+        version: INDEX_VERSION,
+        backup_id: backup_id.to_string(),
+        size_bytes: chunks.iter().map(|c| c.size).sum(),
+        sha256: format!("{:x}", whole.finalize()),
+        chunks,
+    };
+    let json = serde_json::to_vec(&index)
+        .map_err(|e| WslError::ParseError(format!("Failed to serialize index: {}", e)))?;
+    target.put_bytes(&index_key(backup_id), &json)?;
+    stats.stored_bytes += json.len() as u64;
+    info!(
+        "Stored '{}': {} chunks, {} new ({} bytes)",
+        backup_id, stats.chunks, stats.new_chunks, stats.stored_bytes
+    );
+    Ok((index, stats))
+}
+
+pub fn read_index(target: &dyn BackupTarget, backup_id: &str) -> Result<RepositoryIndex, WslError> {
+    let data = target.get_bytes(&index_key(backup_id))?;
+    let index: RepositoryIndex = serde_json::from_slice(&data)
+        .map_err(|e| WslError::ParseError(format!("Invalid index of '{}': {}", backup_id, e)))?;
+    if index.version > INDEX_VERSION {
+        return Err(WslError::ParseError(format!(
+            "Index of '{}' has unsupported version {}",
+            backup_id, index.version
+        )));
+    }
+    Ok(index)
+}
+
+/// Reassemble a backup into a tar file, verifying every chunk and the
+/// whole stream. A partial file is removed on error.
+pub fn restore_tar(
+    target: &dyn BackupTarget,
+    backup_id: &str,
+    destination: &Path,
+) -> Result<(), WslError> {
+    let index = read_index(target, backup_id)?;
+    let result = (|| {
+        let mut output = std::io::BufWriter::new(std::fs::File::create(destination)?);
+        let mut whole = Sha256::new();
+        for chunk in &index.chunks {
+            let data = read_chunk(target, &chunk.hash)?;
+            whole.update(&data);
+            output.write_all(&data)?;
+        }
+        output.flush()?;
+        let actual = format!("{:x}", whole.finalize());
+        if actual != index.sha256 {
+            return Err(WslError::CommandFailed(format!(
+                "Backup '{}' is corrupted: expected SHA256 {}, got {}",
+                backup_id, index.sha256, actual
+            )));
+        }
+        Ok(())
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(destination);
+    }
+    result
+}
+
+/// Check that every chunk each index refers to exists; with `verify_data`,
+/// also download every referenced chunk and check its content
+pub fn check(target: &dyn BackupTarget, verify_data: bool) -> Result<CheckReport, WslError> {
+    let stored = stored_chunks(target)?;
+    let mut report = CheckReport {
+        chunks: stored.len(),
+        stored_bytes: stored.values().sum(),
+        data_verified: verify_data,
+        ..CheckReport::default()
+    };
+
+    let mut referenced = HashSet::new();
+    for backup_id in backup_ids(target)? {
+        let index = match read_index(target, &backup_id) {
+            Ok(index) => index,
+            Err(e) => {
+                warn!("Unreadable repository index '{}': {}", backup_id, e);
+                report.unreadable_indexes.push(backup_id);
+                continue;
+            }
+        };
+        report.backups += 1;
+        report.logical_bytes += index.size_bytes;
+        for chunk in index.chunks {
+            if !stored.contains_key(&chunk.hash) {
+                report.missing_chunks.push(MissingChunk {
+                    backup_id: backup_id.clone(),
+                    hash: chunk.hash.clone(),
+                });
+            }
+            referenced.insert(chunk.hash);
+        }
+    }
+    report.unreferenced_chunks = stored.keys().filter(|h| !referenced.contains(*h)).count();
+
+    if verify_data {
+        let mut hashes: Vec<&String> = referenced
+            .iter()
+            .filter(|h| stored.contains_key(*h))
+            .collect();
+        hashes.sort();
+        for hash in hashes {
+            if let Err(e) = read_chunk(target, hash) {
+                warn!("{}", e);
+                report.corrupt_chunks.push(hash.clone());
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Delete chunks no index refers to. Refuses to run while any index is
+/// unreadable, since its chunks would be lost.
+pub fn prune(target: &dyn BackupTarget) -> Result<PruneReport, WslError> {
+    let _guard = REPOSITORY_LOCK.lock().unwrap_or_else(|p| p.into_inner());
+    let stored = stored_chunks(target)?;
+
+    let mut referenced = HashSet::new();
+    for backup_id in backup_ids(target)? {
+        let index = read_index(target, &backup_id).map_err(|e| {
+            WslError::CommandFailed(format!("Prune stopped, no chunks were deleted: {}", e))
+        })?;
+        referenced.extend(index.chunks.into_iter().map(|c| c.hash));
+    }
+
+    let mut report = PruneReport::default();
+    for (hash, size) in stored {
+        if !referenced.contains(&hash) {
+            target.delete(&chunk_key(&hash))?;
+            report.deleted_chunks += 1;
+            report.freed_bytes += size;
+        }
+    }
+    info!(
+        "Pruned {} chunks ({} bytes)",
+        report.deleted_chunks, report.freed_bytes
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wsl::backup_targets::RemoteObject;
+    use std::cell::RefCell;
+    use std::collections::BTreeMap;
+
+    /// Target keeping objects in memory
+    #[derive(Default)]
+    struct MemoryTarget {
+        objects: RefCell<BTreeMap<String, Vec<u8>>>,
+    }
+
+    impl BackupTarget for MemoryTarget {
+        fn put_file(&self, source: &Path, key: &str, _sha256: &str) -> Result<(), WslError> {
+            self.put_bytes(key, &std::fs::read(source)?)
+        }
+        fn put_bytes(&self, key: &str, data: &[u8]) -> Result<(), WslError> {
+            self.objects
+                .borrow_mut()
+                .insert(key.to_string(), data.to_vec());
+            Ok(())
+        }
+        fn get_file(&self, key: &str, destination: &Path) -> Result<(), WslError> {
+            Ok(std::fs::write(destination, self.get_bytes(key)?)?)
+        }
+        fn get_bytes(&self, key: &str) -> Result<Vec<u8>, WslError> {
+            self.objects
+                .borrow()
+                .get(key)
+                .cloned()
+                .ok_or_else(|| WslError::CommandFailed(format!("{} not found", key)))
+        }
+        fn list(&self) -> Result<Vec<RemoteObject>, WslError> {
+            Ok(self
+                .objects
+                .borrow()
+                .iter()
+                .map(|(key, data)| RemoteObject {
+                    key: key.clone(),
+                    size_bytes: data.len() as u64,
+                })
+                .collect())
+        }
+        fn delete(&self, key: &str) -> Result<(), WslError> {
+            self.objects.borrow_mut().remove(key);
+            Ok(())
+        }
+    }
+
+    const SMALL: ChunkerParams = ChunkerParams {
+        min_size: 256,
+        avg_bits: 10,
+        max_size: 4096,
+    };
+
+    fn noise(len: usize, seed: u64) -> Vec<u8> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect()
+    }
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        crate::utils::unique_temp_dir(&format!("repo-{}", name))
+    }
+
+    fn chunk_count(target: &MemoryTarget) -> usize {
+        stored_chunks(target).unwrap().len()
+    }
+
+    #[test]
+    fn second_backup_only_stores_changed_chunks_and_restores() {
+        let dir = temp_dir("store");
+        let target = MemoryTarget::default();
+        let first = noise(200_000, 3);
+        let mut second = first.clone();
+        second[100_000..100_100].copy_from_slice(&[0xAA; 100]);
+
+        std::fs::write(dir.join("a.tar"), &first).unwrap();
+        let (index, stats) = store_tar(&target, &dir.join("a.tar"), "Ubuntu-1", SMALL).unwrap();
+        assert_eq!(stats.new_chunks, stats.chunks);
+        assert_eq!(index.size_bytes, 200_000);
+        let after_first = chunk_count(&target);
+
+        std::fs::write(dir.join("b.tar"), &second).unwrap();
+        let (_, stats) = store_tar(&target, &dir.join("b.tar"), "Ubuntu-2", SMALL).unwrap();
+        assert!(
+            stats.new_chunks >= 1 && stats.new_chunks <= 3,
+            "{:?}",
+            stats
+        );
+        assert_eq!(chunk_count(&target), after_first + stats.new_chunks);
+
+        restore_tar(&target, "Ubuntu-2", &dir.join("restored.tar")).unwrap();
+        assert_eq!(std::fs::read(dir.join("restored.tar")).unwrap(), second);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn check_finds_missing_and_corrupt_chunks() {
+        let dir = temp_dir("check");
+        let target = MemoryTarget::default();
+        std::fs::write(dir.join("a.tar"), noise(50_000, 4)).unwrap();
+        let (index, _) = store_tar(&target, &dir.join("a.tar"), "Ubuntu-1", SMALL).unwrap();
+
+        let report = check(&target, true).unwrap();
+        assert!(report.is_healthy());
+        assert_eq!(report.backups, 1);
+        assert_eq!(report.logical_bytes, 50_000);
+
+        let missing = &index.chunks[0].hash;
+        let corrupt = &index.chunks[1].hash;
+        target.delete(&chunk_key(missing)).unwrap();
+        target
+            .put_bytes(&chunk_key(corrupt), &compress(b"garbage").unwrap())
+            .unwrap();
+        target.put_bytes("Debian-1.index", b"not json").unwrap();
+
+        let quick = check(&target, false).unwrap();
+        assert_eq!(quick.missing_chunks.len(), 1);
+        assert!(quick.corrupt_chunks.is_empty());
+        assert_eq!(quick.unreadable_indexes, vec!["Debian-1"]);
+
+        let full = check(&target, true).unwrap();
+        assert_eq!(full.corrupt_chunks, vec![corrupt.clone()]);
+        assert!(!full.is_healthy());
+
+        let restored = dir.join("restored.tar");
+        assert!(restore_tar(&target, "Ubuntu-1", &restored).is_err());
+        assert!(!restored.exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn prune_deletes_only_unreferenced_chunks() {
+        let dir = temp_dir("prune");
+        let target = MemoryTarget::default();
+        std::fs::write(dir.join("a.tar"), noise(40_000, 5)).unwrap();
+        std::fs::write(dir.join("b.tar"), noise(40_000, 6)).unwrap();
+        store_tar(&target, &dir.join("a.tar"), "Ubuntu-1", SMALL).unwrap();
+        let (kept, _) = store_tar(&target, &dir.join("b.tar"), "Ubuntu-2", SMALL).unwrap();
+        assert_eq!(prune(&target).unwrap(), PruneReport::default());
+
+        target.delete(&index_key("Ubuntu-1")).unwrap();
+        let report = prune(&target).unwrap();
+        assert!(report.deleted_chunks > 0 && report.freed_bytes > 0);
+        let remaining: HashSet<String> = stored_chunks(&target).unwrap().into_keys().collect();
+        let expected: HashSet<String> = kept.chunks.into_iter().map(|c| c.hash).collect();
+        assert_eq!(remaining, expected);
+
+        target.put_bytes("Broken-1.index", b"{").unwrap();
+        store_tar(&target, &dir.join("a.tar"), "Ubuntu-3", SMALL).unwrap();
+        target.delete(&index_key("Ubuntu-3")).unwrap();
+        let before = chunk_count(&target);
+        assert!(prune(&target).is_err());
+        assert_eq!(chunk_count(&target), before);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,176 @@
+//! Target credentials in the Windows Credential Manager
+//!
+//! Each target's user name and secret are kept as a generic credential named
+//! `wsl-ui:backup-target:<id>`. Windows encrypts it with the user's logon
+//! credentials, so no secret is written to the app's config files.
+
+#[cfg(windows)]
+use super::wide;
+use super::TargetCredentials;
+use crate::wsl::types::WslError;
+
+#[cfg(any(windows, test))]
+fn credential_name(target_id: &str) -> String {
+    format!("{}:backup-target:{}", crate::constants::APP_NAME, target_id)
+}
+
+fn credential_error(action: &str, target_id: &str, error: impl std::fmt::Display) -> WslError {
+    WslError::CommandFailed(format!(
+        "Failed to {} credentials of backup target '{}': {}",
+        action, target_id, error
+    ))
+}
+
+/// Credentials stored for `target_id`, if any
+#[cfg(windows)]
+pub fn read(target_id: &str) -> Result<Option<TargetCredentials>, WslError> {
+    use windows_sys::Win32::Foundation::ERROR_NOT_FOUND;
+    use windows_sys::Win32::Security::Credentials::{
+        CredFree, CredReadW, CREDENTIALW, CRED_TYPE_GENERIC,
+    };
+
+    let name = wide(&credential_name(target_id));
+    let mut credential: *mut CREDENTIALW = std::ptr::null_mut();
+    // SAFETY: `name` is NUL-terminated and outlives the call
+    if unsafe { CredReadW(name.as_ptr(), CRED_TYPE_GENERIC, 0, &mut credential) } == 0 {
+        let error = std::io::Error::last_os_error();
+        if error.raw_os_error() == Some(ERROR_NOT_FOUND as i32) {
+            return Ok(None);
+        }
+        return Err(credential_error("read", target_id, error));
+    }
+
+    // SAFETY: CredReadW succeeded, so `credential` points to a valid
+    // CREDENTIALW until it is released with CredFree below
+    let (username, secret) = unsafe {
+        let credential = &*credential;
+        let username = if credential.UserName.is_null() {
+            String::new()
+        } else {
+            let len = (0..)
+                .take_while(|&i| *credential.UserName.add(i) != 0)
+                .count();
+            String::from_utf16_lossy(std::slice::from_raw_parts(credential.UserName, len))
+        };
+        let secret = if credential.CredentialBlob.is_null() {
+            Vec::new()
+        } else {
+            std::slice::from_raw_parts(
+                credential.CredentialBlob,
+                credential.CredentialBlobSize as usize,
+            )
+            .to_vec()
+        };
+        (username, secret)
+    };
+    // SAFETY: `credential` was allocated by CredReadW and is not used again
+    unsafe { CredFree(credential as *const _) };
+
+    let secret = String::from_utf8(secret).map_err(|e| credential_error("read", target_id, e))?;
+    Ok(Some(TargetCredentials { username, secret }))
+}
+
+/// Store the credentials of `target_id`, replacing any previous ones
+#[cfg(windows)]
+pub fn write(target_id: &str, credentials: &TargetCredentials) -> Result<(), WslError> {
+    use windows_sys::Win32::Foundation::FILETIME;
+    use windows_sys::Win32::Security::Credentials::{
+        CredWriteW, CREDENTIALW, CRED_PERSIST_LOCAL_MACHINE, CRED_TYPE_GENERIC,
+    };
+
+    let mut name = wide(&credential_name(target_id));
+    let mut username = wide(&credentials.username);
+    let mut secret = credentials.secret.as_bytes().to_vec();
+    let credential = CREDENTIALW {
+        Flags: 0,
+        Type: CRED_TYPE_GENERIC,
+        TargetName: name.as_mut_ptr(),
+        Comment: std::ptr::null_mut(),
+        LastWritten: FILETIME {
+            dwLowDateTime: 0,
+            dwHighDateTime: 0,
+        },
+        CredentialBlobSize: secret.len() as u32,
+        CredentialBlob: secret.as_mut_ptr(),
+        Persist: CRED_PERSIST_LOCAL_MACHINE,
+        AttributeCount: 0,
+        Attributes: std::ptr::null_mut(),
+        TargetAlias: std::ptr::null_mut(),
+        UserName: username.as_mut_ptr(),
+    };
+    // SAFETY: every pointer in `credential` refers to a buffer that outlives
+    // the call; CredWriteW copies what it keeps
+    if unsafe { CredWriteW(&credential, 0) } == 0 {
+        return Err(credential_error(
+            "store",
+            target_id,
+            std::io::Error::last_os_error(),
+        ));
+    }
+    Ok(())
+}
+
+/// Remove the credentials of `target_id`; missing credentials are fine
+#[cfg(windows)]
+pub fn delete(target_id: &str) -> Result<(), WslError> {
+    use windows_sys::Win32::Foundation::ERROR_NOT_FOUND;
+    use windows_sys::Win32::Security::Credentials::{CredDeleteW, CRED_TYPE_GENERIC};
+
+    let name = wide(&credential_name(target_id));
+    // SAFETY: `name` is NUL-terminated and outlives the call
+    if unsafe { CredDeleteW(name.as_ptr(), CRED_TYPE_GENERIC, 0) } == 0 {
+        let error = std::io::Error::last_os_error();
+        if error.raw_os_error() != Some(ERROR_NOT_FOUND as i32) {
+            return Err(credential_error("remove", target_id, error));
+        }
+    }
+    Ok(())
+}
+
+// The Credential Manager only exists on Windows; elsewhere the app always
+// runs in mock mode and never reaches these.
+
+#[cfg(not(windows))]
+pub fn read(target_id: &str) -> Result<Option<TargetCredentials>, WslError> {
+    Err(credential_error("read", target_id, "requires Windows"))
+}
+
+#[cfg(not(windows))]
+pub fn write(target_id: &str, _credentials: &TargetCredentials) -> Result<(), WslError> {
+    Err(credential_error("store", target_id, "requires Windows"))
+}
+
+#[cfg(not(windows))]
+pub fn delete(target_id: &str) -> Result<(), WslError> {
+    Err(credential_error("remove", target_id, "requires Windows"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn credential_names_are_namespaced_per_target() {
+        assert_eq!(credential_name("nas"), "wsl-ui:backup-target:nas");
+        assert_ne!(credential_name("nas"), credential_name("s3"));
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn the_store_requires_windows() {
+        let credentials = TargetCredentials {
+            username: "backup".to_string(),
+            secret: "secret".to_string(),
+        };
+        for err in [
+            read("nas").unwrap_err(),
+            write("nas", &credentials).unwrap_err(),
+            delete("nas").unwrap_err(),
+        ] {
+            let err = err.to_string();
+            assert!(err.contains("backup target 'nas'"), "{}", err);
+            assert!(err.contains("requires Windows"), "{}", err);
+            assert!(!err.contains("secret"), "{}", err);
+        }
+    }
+}
@@ -0,0 +1,255 @@
+//! Local folder and SMB share targets
+
+use super::{BackupTarget, RemoteObject, TargetCredentials};
+use crate::wsl::snapshots::{hash_file, io_error};
+use crate::wsl::types::WslError;
+use log::info;
+use std::path::{Path, PathBuf};
+
+/// Target storing archives as files in a directory (local or UNC path)
+pub struct FilesystemTarget {
+    root: PathBuf,
+}
+
+impl FilesystemTarget {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Connect to the share of a UNC root, so the share is reachable with
+    /// the target's credentials rather than the user's
+    pub fn connect_share(&self, credentials: &TargetCredentials) -> Result<(), WslError> {
+        if crate::utils::is_mock_mode() {
+            return Ok(());
+        }
+        let share = share_root(&self.root.to_string_lossy()).ok_or_else(|| {
+            WslError::CommandFailed(format!("'{}' is not a UNC path", self.root.display()))
+        })?;
+        add_connection(&share, credentials)?;
+        info!("Connected to {}", share);
+        Ok(())
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf, WslError> {
+        if key.is_empty() || key.contains(['/', '\\']) || key.starts_with('.') {
+            return Err(WslError::CommandFailed(format!(
+                "Invalid backup key '{}'",
+                key
+            )));
+        }
+        Ok(self.root.join(key))
+    }
+
+    fn ensure_root(&self) -> Result<(), WslError> {
+        std::fs::create_dir_all(&self.root).map_err(|e| io_error("Failed to create", &self.root, e))
+    }
+}
+
+/// Connect with `WNetAddConnection2W`, so the password never appears on a
+/// command line
+#[cfg(windows)]
+fn add_connection(share: &str, credentials: &TargetCredentials) -> Result<(), WslError> {
+    use windows_sys::Win32::Foundation::{ERROR_SESSION_CREDENTIAL_CONFLICT, NO_ERROR};
+    use windows_sys::Win32::NetworkManagement::WNet::{
+        WNetAddConnection2W, CONNECT_TEMPORARY, NETRESOURCEW, RESOURCETYPE_DISK,
+    };
+
+    let mut remote = super::wide(share);
+    let password = super::wide(&credentials.secret);
+    let username = (!credentials.username.is_empty()).then(|| super::wide(&credentials.username));
+    let resource = NETRESOURCEW {
+        dwScope: 0,
+        dwType: RESOURCETYPE_DISK,
+        dwDisplayType: 0,
+        dwUsage: 0,
+        lpLocalName: std::ptr::null_mut(),
+        lpRemoteName: remote.as_mut_ptr(),
+        lpComment: std::ptr::null_mut(),
+        lpProvider: std::ptr::null_mut(),
+    };
+    // SAFETY: all strings are NUL-terminated and outlive the call
+    let result = unsafe {
+        WNetAddConnection2W(
+            &resource,
+            password.as_ptr(),
+            username.as_ref().map_or(std::ptr::null(), |u| u.as_ptr()),
+            CONNECT_TEMPORARY,
+        )
+    };
+    if result == ERROR_SESSION_CREDENTIAL_CONFLICT {
+        // Windows allows one set of credentials per server and session
+        return Err(WslError::CommandFailed(format!(
+            "{} is already connected with other credentials. Disconnect the \
+             existing connection to the server, or clear the target's \
+             credentials to use that connection.",
+            share
+        )));
+    }
+    if result != NO_ERROR {
+        return Err(WslError::CommandFailed(format!(
+            "Failed to connect to {}: {}",
+            share,
+            std::io::Error::from_raw_os_error(result as i32)
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn add_connection(share: &str, _credentials: &TargetCredentials) -> Result<(), WslError> {
+    Err(WslError::CommandFailed(format!(
+        "Connecting to {} requires Windows",
+        share
+    )))
+}
+
+/// `\\server\share` of a UNC path
+pub(super) fn share_root(path: &str) -> Option<String> {
+    let rest = path.strip_prefix(r"\\")?;
+    let mut parts = rest.split('\\').filter(|p| !p.is_empty());
+    let server = parts.next()?;
+    let share = parts.next()?;
+    Some(format!(r"\\{}\{}", server, share))
+}
+
+impl BackupTarget for FilesystemTarget {
+    fn put_file(&self, source: &Path, key: &str, sha256: &str) -> Result<(), WslError> {
+        self.ensure_root()?;
+        let destination = self.path(key)?;
+        let partial = self.root.join(format!(".{}.partial", key));
+
+        // A rename is enough when the source is on the same volume
+        if std::fs::rename(source, &partial).is_err() {
+            std::fs::copy(source, &partial)
+                .map_err(|e| io_error("Failed to copy to", &partial, e))?;
+        }
+        let actual = hash_file(&partial)?;
+        if !actual.eq_ignore_ascii_case(sha256) {
+            let _ = std::fs::remove_file(&partial);
+            return Err(WslError::CommandFailed(format!(
+                "Checksum mismatch writing {}: expected {}, got {}",
+                destination.display(),
+                sha256,
+                actual
+            )));
+        }
+        std::fs::rename(&partial, &destination)
+            .map_err(|e| io_error("Failed to write", &destination, e))
+    }
+
+    fn put_bytes(&self, key: &str, data: &[u8]) -> Result<(), WslError> {
+        self.ensure_root()?;
+        let destination = self.path(key)?;
+        let partial = self.root.join(format!(".{}.partial", key));
+        std::fs::write(&partial, data).map_err(|e| io_error("Failed to write", &partial, e))?;
+        std::fs::rename(&partial, &destination)
+            .map_err(|e| io_error("Failed to write", &destination, e))
+    }
+
+    fn get_file(&self, key: &str, destination: &Path) -> Result<(), WslError> {
+        let path = self.path(key)?;
+        std::fs::copy(&path, destination)
+            .map(|_| ())
+            .map_err(|e| io_error("Failed to copy", &path, e))
+    }
+
+    fn get_bytes(&self, key: &str) -> Result<Vec<u8>, WslError> {
+        let path = self.path(key)?;
+        std::fs::read(&path).map_err(|e| io_error("Failed to read", &path, e))
+    }
+
+    fn list(&self) -> Result<Vec<RemoteObject>, WslError> {
+        if !self.root.exists() {
+            return Ok(Vec::new());
+        }
+        let entries =
+            std::fs::read_dir(&self.root).map_err(|e| io_error("Failed to read", &self.root, e))?;
+        let mut objects = Vec::new();
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            let key = entry.file_name().to_string_lossy().to_string();
+            if metadata.is_file() && !key.starts_with('.') {
+                objects.push(RemoteObject {
+                    key,
+                    size_bytes: metadata.len(),
+                });
+            }
+        }
+        objects.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(objects)
+    }
+
+    fn delete(&self, key: &str) -> Result<(), WslError> {
+        let path = self.path(key)?;
+        match std::fs::remove_file(&path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(io_error("Failed to delete", &path, e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn share_root_of_unc_paths() {
+        assert_eq!(
+            share_root(r"\\nas\backups\wsl\daily"),
+            Some(r"\\nas\backups".to_string())
+        );
+        assert_eq!(
+            share_root(r"\\nas\backups"),
+            Some(r"\\nas\backups".to_string())
+        );
+        assert_eq!(share_root(r"\\nas"), None);
+        assert_eq!(share_root(r"C:\backups"), None);
+    }
+
+    #[test]
+    fn files_round_trip_with_checksum_verification() {
+        let dir = crate::utils::unique_temp_dir("fs-target");
+        let target = FilesystemTarget::new(dir.join("store"));
+
+        let source = dir.join("archive.tar.gz");
+        std::fs::write(&source, b"backup data").unwrap();
+        let sha256 = hash_file(&source).unwrap();
+        assert!(target.put_file(&source, "Ubuntu-1.tar.gz", "00").is_err());
+
+        std::fs::write(&source, b"backup data").unwrap();
+        target
+            .put_file(&source, "Ubuntu-1.tar.gz", &sha256)
+            .unwrap();
+        target.put_bytes("Ubuntu-1.json", b"{}").unwrap();
+        assert_eq!(
+            target.list().unwrap(),
+            vec![
+                RemoteObject {
+                    key: "Ubuntu-1.json".to_string(),
+                    size_bytes: 2
+                },
+                RemoteObject {
+                    key: "Ubuntu-1.tar.gz".to_string(),
+                    size_bytes: 11
+                },
+            ]
+        );
+
+        let restored = dir.join("restored.tar.gz");
+        target.get_file("Ubuntu-1.tar.gz", &restored).unwrap();
+        assert_eq!(std::fs::read(&restored).unwrap(), b"backup data");
+        assert_eq!(target.get_bytes("Ubuntu-1.json").unwrap(), b"{}");
+        target.put_bytes("Ubuntu-1.json", b"[]").unwrap();
+        assert_eq!(target.get_bytes("Ubuntu-1.json").unwrap(), b"[]");
+        assert!(!dir.join("store").join(".Ubuntu-1.json.partial").exists());
+        assert!(target.get_bytes("../settings.json").is_err());
+
+        target.delete("Ubuntu-1.tar.gz").unwrap();
+        target.delete("Ubuntu-1.tar.gz").unwrap();
+        assert_eq!(target.list().unwrap().len(), 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,297 @@
+//! Storage targets for backup archives
+//!
+//! A target stores archives and their manifests under flat keys. Local
+//! folders and SMB shares (UNC paths) are handled by [`FilesystemTarget`],
+//! S3-compatible object storage by [`S3Target`]. Target definitions live in
+//! the backup store; credentials are kept per target in the Windows
+//! Credential Manager and are never sent back to the frontend.
+
+mod credential_store;
+mod filesystem;
+mod s3;
+
+pub use filesystem::FilesystemTarget;
+pub use s3::S3Target;
+
+use super::types::WslError;
+use crate::utils::is_mock_mode;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// ID of the built-in target that stores archives in the backup directory
+pub const DEFAULT_TARGET_ID: &str = "local";
+
+/// Where a target stores its archives
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum TargetLocation {
+    /// Folder on a local disk
+    Local { path: String },
+    /// SMB share addressed by a UNC path, e.g. `\\nas\backups\wsl`
+    #[serde(rename_all = "camelCase")]
+    NetworkShare { path: String },
+    /// S3-compatible object storage (AWS S3, MinIO, ...)
+    #[serde(rename_all = "camelCase")]
+    S3 {
+        /// Service URL, e.g. `https://s3.eu-west-1.amazonaws.com` or `http://nas:9000`
+        endpoint: String,
+        region: String,
+        bucket: String,
+        /// Key prefix, e.g. `wsl/`
+        #[serde(default)]
+        prefix: String,
+        /// Address the bucket in the path instead of the host name (MinIO)
+        #[serde(default)]
+        path_style: bool,
+    },
+}
+
+/// A configured backup target
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupTargetConfig {
+    pub id: String,
+    pub name: String,
+    pub location: TargetLocation,
+}
+
+/// Credentials of a target: user name and password for SMB shares, access
+/// key ID and secret access key for S3
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetCredentials {
+    pub username: String,
+    pub secret: String,
+}
+
+/// An object stored in a target
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteObject {
+    pub key: String,
+    pub size_bytes: u64,
+}
+
+/// Storage operations every target supports
+pub trait BackupTarget {
+    /// Store the file at `source` under `key`; the source may be moved. The
+    /// stored copy is checked against `sha256` (lowercase hex) before the
+    /// upload counts as done.
+    fn put_file(&self, source: &Path, key: &str, sha256: &str) -> Result<(), WslError>;
+    /// Store a small object such as a manifest
+    fn put_bytes(&self, key: &str, data: &[u8]) -> Result<(), WslError>;
+    /// Download `key` to `destination`
+    fn get_file(&self, key: &str, destination: &Path) -> Result<(), WslError>;
+    fn get_bytes(&self, key: &str) -> Result<Vec<u8>, WslError>;
+    /// Objects in the target (only those under the prefix for S3)
+    fn list(&self) -> Result<Vec<RemoteObject>, WslError>;
+    fn delete(&self, key: &str) -> Result<(), WslError>;
+}
+
+/// Open a target for reading and writing
+pub fn open_target(
+    config: &BackupTargetConfig,
+    credentials: Option<TargetCredentials>,
+) -> Result<Box<dyn BackupTarget>, WslError> {
+    match &config.location {
+        TargetLocation::Local { path } => Ok(Box::new(FilesystemTarget::new(path))),
+        TargetLocation::NetworkShare { path } => {
+            let target = FilesystemTarget::new(path);
+            if let Some(credentials) = credentials {
+                target.connect_share(&credentials)?;
+            }
+            Ok(Box::new(target))
+        }
+        TargetLocation::S3 {
+            endpoint,
+            region,
+            bucket,
+            prefix,
+            path_style,
+        } => {
+            let credentials = credentials.ok_or_else(|| {
+                WslError::CommandFailed(format!(
+                    "Backup target '{}' has no access key configured",
+                    config.name
+                ))
+            })?;
+            let client = crate::http_client::blocking_client(None)
+                .map_err(|e| WslError::CommandFailed(e.to_string()))?;
+            Ok(Box::new(S3Target::new(
+                client,
+                endpoint,
+                region,
+                bucket,
+                prefix,
+                *path_style,
+                credentials,
+            )?))
+        }
+    }
+}
+
+/// Check a target definition before it is saved
+pub fn validate_target(config: &BackupTargetConfig) -> Result<(), WslError> {
+    if config.name.trim().is_empty() {
+        return Err(WslError::CommandFailed(
+            "Target name cannot be empty".to_string(),
+        ));
+    }
+    match &config.location {
+        TargetLocation::Local { path } if path.trim().is_empty() => Err(WslError::CommandFailed(
+            "Backup folder cannot be empty".to_string(),
+        )),
+        TargetLocation::NetworkShare { path } if filesystem::share_root(path).is_none() => {
+            Err(WslError::CommandFailed(format!(
+                "'{}' is not a UNC path (expected \\\\server\\share\\...)",
+                path
+            )))
+        }
+        TargetLocation::S3 {
+            endpoint, bucket, ..
+        } => {
+            let endpoint = endpoint.trim();
+            if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+                return Err(WslError::CommandFailed(format!(
+                    "Invalid S3 endpoint '{}', expected an http:// or https:// URL",
+                    endpoint
+                )));
+            }
+            if bucket.trim().is_empty() {
+                return Err(WslError::CommandFailed(
+                    "S3 bucket cannot be empty".to_string(),
+                ));
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+lazy_static::lazy_static! {
+    /// Credentials for mock mode (nothing is written to the Credential Manager)
+    static ref MOCK_CREDENTIALS: Mutex<HashMap<String, TargetCredentials>> = Mutex::new(HashMap::new());
+}
+
+/// Credentials stored for a target, if any
+pub fn get_credentials(target_id: &str) -> Result<Option<TargetCredentials>, WslError> {
+    if is_mock_mode() {
+        let store = MOCK_CREDENTIALS.lock().unwrap_or_else(|p| p.into_inner());
+        return Ok(store.get(target_id).cloned());
+    }
+    credential_store::read(target_id)
+}
+
+/// Store (`Some`) or remove (`None`) the credentials of a target
+pub fn set_credentials(
+    target_id: &str,
+    credentials: Option<TargetCredentials>,
+) -> Result<(), WslError> {
+    if is_mock_mode() {
+        let mut store = MOCK_CREDENTIALS.lock().unwrap_or_else(|p| p.into_inner());
+        match credentials {
+            Some(credentials) => store.insert(target_id.to_string(), credentials),
+            None => store.remove(target_id),
+        };
+        return Ok(());
+    }
+    match credentials {
+        Some(credentials) => credential_store::write(target_id, &credentials),
+        None => credential_store::delete(target_id),
+    }
+}
+
+/// Reset mock credentials (for E2E test cleanup)
+pub fn reset_mock_credentials() {
+    if let Ok(mut guard) = MOCK_CREDENTIALS.lock() {
+        guard.clear();
+    }
+}
+
+/// NUL-terminated UTF-16 for Win32 calls
+#[cfg(windows)]
+fn wide(text: &str) -> Vec<u16> {
+    text.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+/// Lowercase hex SHA256 of a byte slice
+fn sha256_hex(data: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    format!("{:x}", Sha256::digest(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(location: TargetLocation) -> BackupTargetConfig {
+        BackupTargetConfig {
+            id: "nas".to_string(),
+            name: "NAS".to_string(),
+            location,
+        }
+    }
+
+    #[test]
+    fn locations_serialize_with_kind_tag() {
+        let s3 = config(TargetLocation::S3 {
+            endpoint: "http://nas:9000".to_string(),
+            region: "us-east-1".to_string(),
+            bucket: "backups".to_string(),
+            prefix: "wsl/".to_string(),
+            path_style: true,
+        });
+        let json = serde_json::to_value(&s3).unwrap();
+        assert_eq!(json["location"]["kind"], "s3");
+        assert_eq!(json["location"]["pathStyle"], true);
+
+        let share: BackupTargetConfig = serde_json::from_str(
+            r#"{"id":"nas","name":"NAS","location":{"kind":"networkShare","path":"\\\\nas\\wsl"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            share.location,
+            TargetLocation::NetworkShare {
+                path: r"\\nas\wsl".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn targets_are_validated() {
+        assert!(validate_target(&config(TargetLocation::Local {
+            path: r"D:\Backups".to_string()
+        }))
+        .is_ok());
+        assert!(validate_target(&config(TargetLocation::Local {
+            path: " ".to_string()
+        }))
+        .is_err());
+        assert!(validate_target(&config(TargetLocation::NetworkShare {
+            path: r"\\nas\wsl".to_string()
+        }))
+        .is_ok());
+        assert!(validate_target(&config(TargetLocation::NetworkShare {
+            path: r"Z:\wsl".to_string()
+        }))
+        .is_err());
+        let s3 = |endpoint: &str, bucket: &str| {
+            config(TargetLocation::S3 {
+                endpoint: endpoint.to_string(),
+                region: String::new(),
+                bucket: bucket.to_string(),
+                prefix: String::new(),
+                path_style: false,
+            })
+        };
+        assert!(validate_target(&s3("https://s3.amazonaws.com", "backups")).is_ok());
+        assert!(validate_target(&s3("s3.amazonaws.com", "backups")).is_err());
+        assert!(validate_target(&s3("https://s3.amazonaws.com", "")).is_err());
+        let unnamed = BackupTargetConfig {
+            name: " ".to_string(),
+            ..s3("https://s3.amazonaws.com", "backups")
+        };
+        assert!(validate_target(&unnamed).is_err());
+    }
+}
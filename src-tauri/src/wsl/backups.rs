@@ -0,0 +1,1697 @@
+//! Scheduled backups with grandfather-father-son retention
+//!
+//! Schedules, backup targets and the run history are kept in `backups.json` in
+//! the app config directory. Each backup is a `wsl --export` tarball compressed
+//! with gzip and stored on the schedule's backup target (see
+//! [`super::backup_targets`]) together with a JSON manifest, so the archives on
+//! a target can be listed and restored without the local history. The built-in
+//! target is the backup directory (see [`crate::settings::get_backup_directory`]).
+//!
+//! [`start_scheduler`] wakes up once a minute and runs every schedule whose
+//! latest slot has passed since its last scheduled run, so a slot missed while
+//! the app was closed is caught up on the next start. Running distributions are
+//! skipped or stopped first depending on
+//! [`crate::settings::BackupRunningPolicy`]. After each successful run the
+//! distribution's archives are pruned to the newest one on each of the last N
+//! days plus the newest one in each of the last M weeks.
+//!
+//! A schedule can use the [`BackupFormat::Repository`] format instead, which
+//! stores the tar stream in the target's deduplicated chunk repository (see
+//! [`super::backup_repository`]); retention then deletes the backup's index and
+//! prunes chunks no other backup uses.
+//!
+//! A schedule's [`ExportFilter`] leaves caches and other bulky paths out of
+//! its backups; the excluded size is recorded with each run.
+
+use super::backup_repository::{self, CheckReport, ChunkerParams, PruneReport};
+use super::backup_targets::{
+    self, open_target, validate_target, BackupTarget, BackupTargetConfig, TargetCredentials,
+    TargetLocation, DEFAULT_TARGET_ID,
+};
+use super::core;
+use super::export_filter::{ExportFilter, FilterReport};
+use super::import_export;
+use super::snapshots::{
+    self, export_quiesced, hash_file, io_error, timestamped_id, ArchiveOrigin, RestoreResult,
+    RestoreTarget,
+};
+use super::types::{DistroState, WslError};
+use crate::constants::CONFIG_FILE_BACKUPS;
+use crate::settings::{get_backup_directory, get_settings, BackupRunningPolicy};
+use crate::utils::{get_config_file, is_mock_mode};
+use chrono::{Datelike, Duration, Local, NaiveDateTime, NaiveTime};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Current backup store version
+const CURRENT_VERSION: &str = "1.0";
+
+/// Size reported for backups taken in mock mode (no file is written)
+const MOCK_BACKUP_SIZE: u64 = 268_435_456;
+
+/// How often the scheduler checks for due backups
+const SCHEDULER_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Run records kept per distribution, not counting runs whose archive is retained
+const MAX_HISTORY_PER_DISTRO: usize = 50;
+
+/// How often a schedule runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BackupFrequency {
+    Daily,
+    Weekly,
+}
+
+/// How a backup is stored on its target
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BackupFormat {
+    /// A self-contained gzip-compressed tarball
+    #[default]
+    Archive,
+    /// Chunks in the target's deduplicated repository plus an index
+    Repository,
+}
+
+/// Backup schedule of one distribution
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupSchedule {
+    pub distro_name: String,
+    pub enabled: bool,
+    pub frequency: BackupFrequency,
+    /// Local time of day, "HH:MM"
+    pub time: String,
+    /// Day of week for weekly schedules, 0 = Monday
+    #[serde(default)]
+    pub weekday: u8,
+    /// Days for which the newest archive is kept
+    pub keep_daily: u32,
+    /// Weeks for which the newest archive is kept
+    pub keep_weekly: u32,
+    /// When the schedule was last saved (RFC 3339); earlier slots are not run
+    #[serde(default)]
+    pub updated_at: String,
+    /// Target the archives are stored on; the backup directory if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_id: Option<String>,
+    #[serde(default)]
+    pub format: BackupFormat,
+    /// Paths left out of the backups
+    #[serde(default)]
+    pub filter: ExportFilter,
+}
+
+/// What started a backup run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BackupTrigger {
+    Scheduled,
+    Manual,
+}
+
+/// Outcome of a backup run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BackupRunStatus {
+    Success,
+    Failed,
+    /// The distribution was running and the policy is to skip it
+    Skipped,
+}
+
+/// Record of one backup run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupRun {
+    pub id: String,
+    pub distro_name: String,
+    pub trigger: BackupTrigger,
+    pub status: BackupRunStatus,
+    /// RFC 3339 timestamps
+    pub started_at: String,
+    pub finished_at: String,
+    /// Target the archive was stored on; the backup directory if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_id: Option<String>,
+    #[serde(default)]
+    pub format: BackupFormat,
+    /// Archive key on the target (the index key for repository backups)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_name: Option<String>,
+    /// Archive size, or the tar size for repository backups
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<u64>,
+    /// Bytes a repository backup added to the repository after deduplication
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stored_bytes: Option<u64>,
+    /// File data the schedule's filter left out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub excluded_bytes: Option<u64>,
+    /// Lowercase hex SHA256 of the archive, or of the tar for repository backups
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    /// Error or skip reason
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Whether retention has deleted the archive
+    #[serde(default)]
+    pub pruned: bool,
+}
+
+impl BackupRun {
+    /// Whether the run produced an archive that still exists
+    fn has_archive(&self) -> bool {
+        self.status == BackupRunStatus::Success && !self.pruned && self.file_name.is_some()
+    }
+
+    fn target_id(&self) -> &str {
+        self.target_id.as_deref().unwrap_or(DEFAULT_TARGET_ID)
+    }
+}
+
+/// Description of an archive, stored next to it on the target as `<id>.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupManifest {
+    pub id: String,
+    pub distro_name: String,
+    #[serde(default)]
+    pub distro_id: Option<String>,
+    /// RFC 3339
+    pub created_at: String,
+    #[serde(default)]
+    pub format: BackupFormat,
+    /// Archive key, or index key for repository backups
+    pub file_name: String,
+    pub size_bytes: u64,
+    /// Lowercase hex SHA256 of the archive, or of the tar for repository backups
+    pub sha256: String,
+    pub wsl_version: u8,
+    #[serde(default)]
+    pub default_user: Option<String>,
+    /// Filter applied to the export; the backup is not a full copy when set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<ExportFilter>,
+}
+
+impl BackupManifest {
+    /// Manifest of a run recorded before manifests were written
+    fn from_run(run: &BackupRun) -> Option<Self> {
+        Some(Self {
+            id: run.id.clone(),
+            distro_name: run.distro_name.clone(),
+            distro_id: None,
+            created_at: run.started_at.clone(),
+            format: run.format,
+            file_name: run.file_name.clone()?,
+            size_bytes: run.size_bytes.unwrap_or(0),
+            sha256: run.sha256.clone()?,
+            wsl_version: 2,
+            default_user: None,
+            filter: None,
+        })
+    }
+}
+
+/// A backup target as shown in the UI; secrets are never included
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupTargetInfo {
+    #[serde(flatten)]
+    pub config: BackupTargetConfig,
+    pub has_credentials: bool,
+    /// The backup directory, which is always available and cannot be edited
+    pub built_in: bool,
+}
+
+/// Backup state of one distribution, as shown in the UI
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DistroBackupStatus {
+    pub distro_name: String,
+    pub schedule: Option<BackupSchedule>,
+    pub last_success: Option<BackupRun>,
+    pub last_run: Option<BackupRun>,
+    /// Next scheduled slot (RFC 3339), if the schedule is enabled
+    pub next_run: Option<String>,
+    /// Archives currently kept
+    pub archive_count: usize,
+}
+
+/// On-disk backup store
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupStore {
+    version: String,
+    #[serde(default)]
+    targets: Vec<BackupTargetConfig>,
+    #[serde(default)]
+    schedules: Vec<BackupSchedule>,
+    #[serde(default)]
+    runs: Vec<BackupRun>,
+}
+
+impl Default for BackupStore {
+    fn default() -> Self {
+        Self {
+            version: CURRENT_VERSION.to_string(),
+            targets: Vec::new(),
+            schedules: Vec::new(),
+            runs: Vec::new(),
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    /// Serializes read-modify-write cycles on the store file
+    static ref STORE_LOCK: Mutex<()> = Mutex::new(());
+    /// Backup store for mock mode (no files are written)
+    static ref MOCK_STORE: Mutex<BackupStore> = Mutex::new(BackupStore::default());
+    /// Distributions with a backup in progress
+    static ref IN_PROGRESS: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+}
+
+fn load_store(path: &Path) -> Result<BackupStore, WslError> {
+    if !path.exists() {
+        return Ok(BackupStore::default());
+    }
+    let content = std::fs::read_to_string(path).map_err(|e| io_error("Failed to read", path, e))?;
+    serde_json::from_str(&content).map_err(|e| {
+        WslError::ParseError(format!("Invalid backup store {}: {}", path.display(), e))
+    })
+}
+
+fn save_store(path: &Path, store: &BackupStore) -> Result<(), WslError> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| io_error("Failed to create", dir, e))?;
+    }
+    let content = serde_json::to_string_pretty(store)
+        .map_err(|e| WslError::ParseError(format!("Failed to serialize backup store: {}", e)))?;
+    std::fs::write(path, content).map_err(|e| io_error("Failed to write", path, e))
+}
+
+/// Run `f` against the store and persist the result
+fn update_store<T>(f: impl FnOnce(&mut BackupStore) -> Result<T, WslError>) -> Result<T, WslError> {
+    if is_mock_mode() {
+        let mut store = MOCK_STORE.lock().unwrap_or_else(|p| p.into_inner());
+        return f(&mut store);
+    }
+
+    let _guard = STORE_LOCK.lock().unwrap_or_else(|p| p.into_inner());
+    let path = get_config_file(CONFIG_FILE_BACKUPS);
+    let mut store = load_store(&path)?;
+    let result = f(&mut store)?;
+    save_store(&path, &store)?;
+    Ok(result)
+}
+
+fn read_store() -> Result<BackupStore, WslError> {
+    if is_mock_mode() {
+        return Ok(MOCK_STORE.lock().unwrap_or_else(|p| p.into_inner()).clone());
+    }
+    let _guard = STORE_LOCK.lock().unwrap_or_else(|p| p.into_inner());
+    load_store(&get_config_file(CONFIG_FILE_BACKUPS))
+}
+
+/// Reset the mock backup store (for E2E test cleanup)
+pub fn reset_mock_backups() {
+    if let Ok(mut guard) = MOCK_STORE.lock() {
+        *guard = BackupStore::default();
+    }
+}
+
+fn parse_time(time: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(time.trim(), "%H:%M").ok()
+}
+
+/// Local wall-clock time of an RFC 3339 timestamp
+fn parse_local(timestamp: &str) -> Option<NaiveDateTime> {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|t| t.with_timezone(&Local).naive_local())
+}
+
+/// Order RFC 3339 timestamps newest first by the instant they name. Their
+/// strings do not sort by time once the UTC offset changes (DST, or a
+/// machine moved to another timezone).
+fn newest_first(a: &str, b: &str) -> std::cmp::Ordering {
+    match (
+        chrono::DateTime::parse_from_rfc3339(a),
+        chrono::DateTime::parse_from_rfc3339(b),
+    ) {
+        (Ok(a), Ok(b)) => b.cmp(&a),
+        _ => b.cmp(a),
+    }
+}
+
+fn period(frequency: BackupFrequency) -> Duration {
+    match frequency {
+        BackupFrequency::Daily => Duration::days(1),
+        BackupFrequency::Weekly => Duration::weeks(1),
+    }
+}
+
+/// The most recent slot of `schedule` at or before `now`
+fn latest_slot(schedule: &BackupSchedule, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let time = parse_time(&schedule.time)?;
+    let days_back = match schedule.frequency {
+        BackupFrequency::Daily => 0,
+        BackupFrequency::Weekly => {
+            (now.weekday().num_days_from_monday() + 7 - u32::from(schedule.weekday)) % 7
+        }
+    };
+    let slot = (now.date() - Duration::days(i64::from(days_back))).and_time(time);
+    Some(if slot <= now {
+        slot
+    } else {
+        slot - period(schedule.frequency)
+    })
+}
+
+/// Whether a slot has passed since the schedule was saved and last ran
+fn is_due(
+    schedule: &BackupSchedule,
+    last_scheduled_run: Option<NaiveDateTime>,
+    now: NaiveDateTime,
+) -> bool {
+    if !schedule.enabled {
+        return false;
+    }
+    let Some(slot) = latest_slot(schedule, now) else {
+        return false;
+    };
+    let reference = [last_scheduled_run, parse_local(&schedule.updated_at)]
+        .into_iter()
+        .flatten()
+        .max();
+    reference.is_none_or(|reference| slot > reference)
+}
+
+/// IDs of the archives kept by grandfather-father-son retention: the newest
+/// archive on each of the `keep_daily` most recent days that have one, plus the
+/// newest archive in each of the `keep_weekly` most recent ISO weeks.
+fn retained_ids(
+    archives: &[(String, NaiveDateTime)],
+    keep_daily: u32,
+    keep_weekly: u32,
+) -> HashSet<String> {
+    let mut newest_first: Vec<&(String, NaiveDateTime)> = archives.iter().collect();
+    newest_first.sort_by_key(|archive| std::cmp::Reverse(archive.1));
+
+    let mut keep = HashSet::new();
+    let mut days = Vec::new();
+    let mut weeks = Vec::new();
+    for (id, taken_at) in newest_first {
+        let day = taken_at.date();
+        if !days.contains(&day) && days.len() < keep_daily as usize {
+            days.push(day);
+            keep.insert(id.clone());
+        }
+        let week = taken_at.iso_week();
+        if !weeks.contains(&week) && weeks.len() < keep_weekly as usize {
+            weeks.push(week);
+            keep.insert(id.clone());
+        }
+    }
+    keep
+}
+
+/// Mark archives of `distro_name` outside the retention window as pruned and
+/// return the pruned runs
+fn apply_retention(runs: &mut [BackupRun], schedule: &BackupSchedule) -> Vec<BackupRun> {
+    let archives: Vec<(String, NaiveDateTime)> = runs
+        .iter()
+        .filter(|r| r.distro_name == schedule.distro_name && r.has_archive())
+        .filter_map(|r| parse_local(&r.started_at).map(|t| (r.id.clone(), t)))
+        .collect();
+    let keep = retained_ids(&archives, schedule.keep_daily, schedule.keep_weekly);
+
+    let mut pruned = Vec::new();
+    for run in runs.iter_mut() {
+        if run.distro_name == schedule.distro_name && run.has_archive() && !keep.contains(&run.id) {
+            run.pruned = true;
+            pruned.push(run.clone());
+        }
+    }
+    pruned
+}
+
+/// Drop the oldest run records of each distribution beyond
+/// [`MAX_HISTORY_PER_DISTRO`], never dropping one whose archive is kept
+fn trim_history(runs: &mut Vec<BackupRun>) {
+    let mut seen: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+    let mut sorted: Vec<BackupRun> = std::mem::take(runs);
+    sorted.sort_by(|a, b| newest_first(&a.started_at, &b.started_at));
+    for run in sorted {
+        let count = seen.entry(run.distro_name.clone()).or_default();
+        *count += 1;
+        if *count <= MAX_HISTORY_PER_DISTRO || run.has_archive() {
+            runs.push(run);
+        }
+    }
+    runs.reverse();
+}
+
+fn validate_schedule(schedule: &BackupSchedule) -> Result<(), WslError> {
+    if schedule.distro_name.trim().is_empty() {
+        return Err(WslError::CommandFailed(
+            "Distribution name cannot be empty".to_string(),
+        ));
+    }
+    if parse_time(&schedule.time).is_none() {
+        return Err(WslError::CommandFailed(format!(
+            "Invalid backup time '{}', expected HH:MM",
+            schedule.time
+        )));
+    }
+    if schedule.weekday > 6 {
+        return Err(WslError::CommandFailed(format!(
+            "Invalid weekday {}, expected 0 (Monday) to 6 (Sunday)",
+            schedule.weekday
+        )));
+    }
+    if schedule.keep_daily == 0 && schedule.keep_weekly == 0 {
+        return Err(WslError::CommandFailed(
+            "Retention must keep at least one daily or weekly backup".to_string(),
+        ));
+    }
+    schedule.filter.validate()
+}
+
+/// Create or replace the schedule of a distribution
+pub fn save_schedule(schedule: BackupSchedule) -> Result<BackupSchedule, WslError> {
+    validate_schedule(&schedule)?;
+    let schedule = BackupSchedule {
+        time: schedule.time.trim().to_string(),
+        updated_at: Local::now().to_rfc3339(),
+        ..schedule
+    };
+    update_store(|store| {
+        if let Some(target_id) = &schedule.target_id {
+            find_target(store, target_id)?;
+        }
+        store
+            .schedules
+            .retain(|s| s.distro_name != schedule.distro_name);
+        store.schedules.push(schedule.clone());
+        Ok(())
+    })?;
+    info!(
+        "Saved {:?} backup schedule for '{}'",
+        schedule.frequency, schedule.distro_name
+    );
+    Ok(schedule)
+}
+
+/// Remove the schedule of a distribution; its archives and history are kept
+pub fn delete_schedule(distro_name: &str) -> Result<(), WslError> {
+    update_store(|store| {
+        store.schedules.retain(|s| s.distro_name != distro_name);
+        Ok(())
+    })
+}
+
+/// Backup state of every installed distribution and of any distribution
+/// that has a schedule or run history
+pub fn list_backup_status() -> Result<Vec<DistroBackupStatus>, WslError> {
+    let store = read_store()?;
+    let mut names: Vec<String> = core::list_distributions()?
+        .into_iter()
+        .map(|d| d.name)
+        .collect();
+    for name in store
+        .schedules
+        .iter()
+        .map(|s| &s.distro_name)
+        .chain(store.runs.iter().map(|r| &r.distro_name))
+    {
+        if !names.contains(name) {
+            names.push(name.clone());
+        }
+    }
+
+    let now = Local::now().naive_local();
+    Ok(names
+        .into_iter()
+        .map(|name| {
+            let mut runs: Vec<&BackupRun> = store
+                .runs
+                .iter()
+                .filter(|r| r.distro_name == name)
+                .collect();
+            runs.sort_by(|a, b| newest_first(&a.started_at, &b.started_at));
+            let schedule = store
+                .schedules
+                .iter()
+                .find(|s| s.distro_name == name)
+                .cloned();
+            let next_run = schedule.as_ref().filter(|s| s.enabled).and_then(|s| {
+                let slot = latest_slot(s, now)? + period(s.frequency);
+                slot.and_local_timezone(Local)
+                    .earliest()
+                    .map(|t| t.to_rfc3339())
+            });
+            DistroBackupStatus {
+                last_success: runs
+                    .iter()
+                    .find(|r| r.status == BackupRunStatus::Success)
+                    .map(|r| (*r).clone()),
+                last_run: runs.first().map(|r| (*r).clone()),
+                archive_count: runs.iter().filter(|r| r.has_archive()).count(),
+                distro_name: name,
+                schedule,
+                next_run,
+            }
+        })
+        .collect())
+}
+
+/// The built-in target: the backup directory
+fn default_target() -> BackupTargetConfig {
+    BackupTargetConfig {
+        id: DEFAULT_TARGET_ID.to_string(),
+        name: "Backup folder".to_string(),
+        location: TargetLocation::Local {
+            path: get_backup_directory().to_string_lossy().to_string(),
+        },
+    }
+}
+
+fn find_target(store: &BackupStore, target_id: &str) -> Result<BackupTargetConfig, WslError> {
+    if target_id == DEFAULT_TARGET_ID {
+        return Ok(default_target());
+    }
+    store
+        .targets
+        .iter()
+        .find(|t| t.id == target_id)
+        .cloned()
+        .ok_or_else(|| WslError::CommandFailed(format!("Backup target '{}' not found", target_id)))
+}
+
+fn open(config: &BackupTargetConfig) -> Result<Box<dyn BackupTarget>, WslError> {
+    open_target(config, backup_targets::get_credentials(&config.id)?)
+}
+
+/// Where archives are staged before upload and after download
+fn staging_directory() -> PathBuf {
+    get_backup_directory().join(".staging")
+}
+
+/// Build a unique target ID from its name
+fn target_id(name: &str, existing: &[BackupTargetConfig]) -> String {
+    let slug: String = name
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let slug = slug.trim_matches('-');
+    let base = if slug.is_empty() { "target" } else { slug };
+    let mut id = base.to_string();
+    let mut counter = 2;
+    while id == DEFAULT_TARGET_ID || existing.iter().any(|t| t.id == id) {
+        id = format!("{}-{}", base, counter);
+        counter += 1;
+    }
+    id
+}
+
+/// The built-in target followed by the configured ones
+pub fn list_targets() -> Result<Vec<BackupTargetInfo>, WslError> {
+    let store = read_store()?;
+    let mut targets = vec![BackupTargetInfo {
+        config: default_target(),
+        has_credentials: false,
+        built_in: true,
+    }];
+    for config in store.targets {
+        targets.push(BackupTargetInfo {
+            has_credentials: backup_targets::get_credentials(&config.id)?.is_some(),
+            config,
+            built_in: false,
+        });
+    }
+    Ok(targets)
+}
+
+/// Create (empty ID) or update a target. Credentials replace the stored ones
+/// when given and are kept otherwise.
+pub fn save_target(
+    config: BackupTargetConfig,
+    credentials: Option<TargetCredentials>,
+) -> Result<BackupTargetInfo, WslError> {
+    validate_target(&config)?;
+    if config.id == DEFAULT_TARGET_ID {
+        return Err(WslError::CommandFailed(
+            "The backup folder target cannot be changed here; set the backup folder instead"
+                .to_string(),
+        ));
+    }
+    let config = update_store(|store| {
+        let config = if config.id.is_empty() {
+            BackupTargetConfig {
+                id: target_id(&config.name, &store.targets),
+                name: config.name.trim().to_string(),
+                ..config
+            }
+        } else {
+            find_target(store, &config.id)?;
+            BackupTargetConfig {
+                name: config.name.trim().to_string(),
+                ..config
+            }
+        };
+        match store.targets.iter_mut().find(|t| t.id == config.id) {
+            Some(existing) => *existing = config.clone(),
+            None => store.targets.push(config.clone()),
+        }
+        Ok(config)
+    })?;
+    if let Some(credentials) = credentials {
+        backup_targets::set_credentials(&config.id, Some(credentials))?;
+    }
+    info!("Saved backup target '{}'", config.id);
+    Ok(BackupTargetInfo {
+        has_credentials: backup_targets::get_credentials(&config.id)?.is_some(),
+        config,
+        built_in: false,
+    })
+}
+
+/// Remove a target and its credentials; archives on it are left in place
+pub fn delete_target(target_id: &str) -> Result<(), WslError> {
+    if target_id == DEFAULT_TARGET_ID {
+        return Err(WslError::CommandFailed(
+            "The backup folder target cannot be removed".to_string(),
+        ));
+    }
+    update_store(|store| {
+        if let Some(schedule) = store
+            .schedules
+            .iter()
+            .find(|s| s.target_id.as_deref() == Some(target_id))
+        {
+            return Err(WslError::CommandFailed(format!(
+                "Backup target '{}' is used by the schedule of '{}'",
+                target_id, schedule.distro_name
+            )));
+        }
+        store.targets.retain(|t| t.id != target_id);
+        Ok(())
+    })?;
+    backup_targets::set_credentials(target_id, None)?;
+    info!("Deleted backup target '{}'", target_id);
+    Ok(())
+}
+
+/// Connect to a target and return the number of backups on it
+pub fn test_target(target_id: &str) -> Result<usize, WslError> {
+    list_target_backups(target_id).map(|backups| backups.len())
+}
+
+/// Backups stored on a target, newest first. Archives without a manifest
+/// are listed from the run history.
+pub fn list_target_backups(target_id: &str) -> Result<Vec<BackupManifest>, WslError> {
+    let store = read_store()?;
+    let config = find_target(&store, target_id)?;
+    let recorded = store
+        .runs
+        .iter()
+        .filter(|r| r.has_archive() && r.target_id() == target_id);
+
+    let mut backups: Vec<BackupManifest> = if is_mock_mode() {
+        open(&config)?;
+        recorded.filter_map(BackupManifest::from_run).collect()
+    } else {
+        let target = open(&config)?;
+        let objects = target.list()?;
+        let keys: HashSet<&str> = objects.iter().map(|o| o.key.as_str()).collect();
+        let has_object = |key: &str| keys.contains(key);
+
+        let mut backups = Vec::new();
+        for object in objects.iter().filter(|o| o.key.ends_with(".json")) {
+            let manifest = target.get_bytes(&object.key).and_then(|data| {
+                serde_json::from_slice::<BackupManifest>(&data)
+                    .map_err(|e| WslError::ParseError(format!("{}: {}", object.key, e)))
+            });
+            match manifest {
+                Ok(manifest) if has_object(&manifest.file_name) => backups.push(manifest),
+                Ok(_) => {}
+                Err(e) => warn!("Skipping unreadable backup manifest: {}", e),
+            }
+        }
+        for run in recorded {
+            let listed = backups.iter().any(|b| b.id == run.id);
+            if !listed && run.file_name.as_deref().is_some_and(has_object) {
+                backups.extend(BackupManifest::from_run(run));
+            }
+        }
+        backups
+    };
+    backups.sort_by(|a, b| newest_first(&a.created_at, &b.created_at));
+    Ok(backups)
+}
+
+/// Download a backup from a target, verify it and restore it in place or as
+/// a new distribution
+pub fn restore_backup(
+    target_id: &str,
+    backup_id: &str,
+    restore_to: &RestoreTarget,
+) -> Result<RestoreResult, WslError> {
+    let manifest = find_backup(target_id, backup_id)?;
+    let origin = ArchiveOrigin {
+        distro_name: &manifest.distro_name,
+        distro_id: manifest.distro_id.as_deref(),
+        wsl_version: manifest.wsl_version,
+        default_user: manifest.default_user.as_deref(),
+    };
+
+    let (result, previous_id) = if is_mock_mode() {
+        snapshots::import_archive(&manifest.file_name, &origin, restore_to)?
+    } else {
+        let staging = staging_directory();
+        std::fs::create_dir_all(&staging).map_err(|e| io_error("Failed to create", &staging, e))?;
+        let tar = staging.join(format!("{}.tar", manifest.id));
+        let result = download_tar(target_id, &manifest, &tar)
+            .and_then(|()| snapshots::import_archive(&tar.to_string_lossy(), &origin, restore_to));
+        let _ = std::fs::remove_file(&tar);
+        result?
+    };
+    info!(
+        "Restored backup '{}' as '{}'",
+        backup_id, result.distro_name
+    );
+
+    match &result.distro_id {
+        Some(new_id) => snapshots::carry_metadata(
+            &result.distro_name,
+            previous_id.as_deref(),
+            new_id,
+            manifest.distro_id.as_deref(),
+            None,
+        ),
+        None => warn!(
+            "Could not find GUID for restored distribution '{}' - metadata not updated",
+            result.distro_name
+        ),
+    }
+    Ok(result)
+}
+
+/// Look up a backup on a target
+fn find_backup(target_id: &str, backup_id: &str) -> Result<BackupManifest, WslError> {
+    list_target_backups(target_id)?
+        .into_iter()
+        .find(|b| b.id == backup_id)
+        .ok_or_else(|| {
+            WslError::CommandFailed(format!(
+                "Backup '{}' not found on target '{}'",
+                backup_id, target_id
+            ))
+        })
+}
+
+/// Save a backup from a target as a plain tar file, e.g. to import it
+/// elsewhere with `wsl --import`
+pub fn export_backup_tar(
+    target_id: &str,
+    backup_id: &str,
+    destination: &str,
+) -> Result<(), WslError> {
+    let manifest = find_backup(target_id, backup_id)?;
+    if is_mock_mode() {
+        info!("Mock: saved backup '{}' to {}", backup_id, destination);
+        return Ok(());
+    }
+    download_tar(target_id, &manifest, Path::new(destination))?;
+    info!("Saved backup '{}' to {}", backup_id, destination);
+    Ok(())
+}
+
+/// Download a backup as a tar in the staging directory so its files can be
+/// browsed and restored. Only the most recently browsed backup is kept, so
+/// repeated listings and restores from it do not download it again.
+pub fn browse_tar(target_id: &str, backup_id: &str) -> Result<PathBuf, WslError> {
+    let manifest = find_backup(target_id, backup_id)?;
+    let staging = staging_directory();
+    let tar = staging.join(format!("browse-{}.tar", manifest.id));
+    if tar.is_file() {
+        return Ok(tar);
+    }
+
+    std::fs::create_dir_all(&staging).map_err(|e| io_error("Failed to create", &staging, e))?;
+    if let Ok(entries) = std::fs::read_dir(&staging) {
+        for entry in entries.flatten() {
+            if entry.file_name().to_string_lossy().starts_with("browse-") {
+                let _ = std::fs::remove_file(entry.path());
+            }
+        }
+    }
+    let partial = staging.join(format!("browse-{}.tar.partial", manifest.id));
+    download_tar(target_id, &manifest, &partial)
+        .and_then(|()| {
+            std::fs::rename(&partial, &tar).map_err(|e| io_error("Failed to rename", &partial, e))
+        })
+        .inspect_err(|_| {
+            let _ = std::fs::remove_file(&partial);
+        })?;
+    Ok(tar)
+}
+
+/// Download a backup and write its verified tar stream to `tar`
+fn download_tar(target_id: &str, manifest: &BackupManifest, tar: &Path) -> Result<(), WslError> {
+    match manifest.format {
+        BackupFormat::Archive => {
+            let staging = staging_directory();
+            std::fs::create_dir_all(&staging)
+                .map_err(|e| io_error("Failed to create", &staging, e))?;
+            let archive = staging.join(&manifest.file_name);
+            let result =
+                download(target_id, manifest, &archive).and_then(|()| decompress(&archive, tar));
+            let _ = std::fs::remove_file(&archive);
+            if result.is_err() {
+                let _ = std::fs::remove_file(tar);
+            }
+            result
+        }
+        BackupFormat::Repository => {
+            let target = open(&find_target(&read_store()?, target_id)?)?;
+            info!("Reassembling backup '{}' from '{}'", manifest.id, target_id);
+            backup_repository::restore_tar(target.as_ref(), &manifest.id, tar)
+        }
+    }
+}
+
+/// Download an archive and check it against its manifest
+fn download(
+    target_id: &str,
+    manifest: &BackupManifest,
+    destination: &Path,
+) -> Result<(), WslError> {
+    let target = open(&find_target(&read_store()?, target_id)?)?;
+    info!("Downloading backup '{}' from '{}'", manifest.id, target_id);
+    target.get_file(&manifest.file_name, destination)?;
+    let actual = hash_file(destination)?;
+    if !actual.eq_ignore_ascii_case(&manifest.sha256) {
+        return Err(WslError::CommandFailed(format!(
+            "Backup '{}' is corrupted: expected SHA256 {}, got {}",
+            manifest.id, manifest.sha256, actual
+        )));
+    }
+    Ok(())
+}
+
+/// Export `distro_name` into `dir/file_name`, gzipped for the archive format.
+/// Returns the size, the SHA256 and, when `filter` excludes anything, what it
+/// left out.
+fn write_archive(
+    distro_name: &str,
+    dir: &Path,
+    file_name: &str,
+    format: BackupFormat,
+    filter: &ExportFilter,
+) -> Result<(u64, String, Option<FilterReport>), WslError> {
+    if is_mock_mode() {
+        let report = if filter.is_empty() {
+            import_export::export_distribution(distro_name, file_name)?;
+            None
+        } else {
+            Some(import_export::export_distribution_filtered(
+                distro_name,
+                file_name,
+                filter,
+            )?)
+        };
+        return Ok((
+            MOCK_BACKUP_SIZE,
+            super::distro_sources::sha256_reader(std::io::empty())?,
+            report,
+        ));
+    }
+
+    std::fs::create_dir_all(dir).map_err(|e| io_error("Failed to create", dir, e))?;
+    let archive = dir.join(file_name);
+    let result = match format {
+        // The filtered tar stream is compressed as it is exported
+        BackupFormat::Archive if !filter.is_empty() => {
+            compress_filtered(distro_name, filter, &archive).map(Some)
+        }
+        BackupFormat::Archive => {
+            let tar = dir.join(format!("{}.partial", file_name.trim_end_matches(".gz")));
+            let result = import_export::export_distribution(distro_name, &tar.to_string_lossy())
+                .and_then(|()| compress(&tar, &archive));
+            let _ = std::fs::remove_file(&tar);
+            result.map(|()| None)
+        }
+        BackupFormat::Repository if !filter.is_empty() => {
+            import_export::export_distribution_filtered(
+                distro_name,
+                &archive.to_string_lossy(),
+                filter,
+            )
+            .map(Some)
+        }
+        BackupFormat::Repository => {
+            import_export::export_distribution(distro_name, &archive.to_string_lossy())
+                .map(|()| None)
+        }
+    };
+    let report = match result {
+        Ok(report) => report,
+        Err(e) => {
+            let _ = std::fs::remove_file(&archive);
+            return Err(e);
+        }
+    };
+
+    let size = std::fs::metadata(&archive)
+        .map_err(|e| io_error("Failed to read", &archive, e))?
+        .len();
+    Ok((size, hash_file(&archive)?, report))
+}
+
+fn compress_filtered(
+    distro_name: &str,
+    filter: &ExportFilter,
+    destination: &Path,
+) -> Result<FilterReport, WslError> {
+    let output = std::fs::File::create(destination)
+        .map_err(|e| io_error("Failed to create", destination, e))?;
+    let encoder = GzEncoder::new(std::io::BufWriter::new(output), Compression::default());
+    let (encoder, report) = import_export::export_filtered_to(distro_name, filter, encoder)?;
+    encoder
+        .finish()
+        .and_then(|output| output.into_inner().map_err(|e| e.into_error()))
+        .map_err(|e| io_error("Failed to compress", destination, e))?;
+    Ok(report)
+}
+
+fn compress(source: &Path, destination: &Path) -> Result<(), WslError> {
+    let mut input = std::io::BufReader::new(
+        std::fs::File::open(source).map_err(|e| io_error("Failed to open", source, e))?,
+    );
+    let output = std::fs::File::create(destination)
+        .map_err(|e| io_error("Failed to create", destination, e))?;
+    let mut encoder = GzEncoder::new(std::io::BufWriter::new(output), Compression::default());
+    std::io::copy(&mut input, &mut encoder)
+        .and_then(|_| encoder.finish())
+        .map_err(|e| io_error("Failed to compress", destination, e))?;
+    Ok(())
+}
+
+fn decompress(source: &Path, destination: &Path) -> Result<(), WslError> {
+    let mut decoder = GzDecoder::new(std::io::BufReader::new(
+        std::fs::File::open(source).map_err(|e| io_error("Failed to open", source, e))?,
+    ));
+    let mut output = std::io::BufWriter::new(
+        std::fs::File::create(destination)
+            .map_err(|e| io_error("Failed to create", destination, e))?,
+    );
+    std::io::copy(&mut decoder, &mut output)
+        .and_then(|_| std::io::Write::flush(&mut output))
+        .map_err(|e| io_error("Failed to decompress", source, e))?;
+    Ok(())
+}
+
+/// An exported archive in the staging directory
+struct ExportedArchive {
+    size_bytes: u64,
+    sha256: String,
+    distro_id: Option<String>,
+    wsl_version: u8,
+    default_user: Option<String>,
+    filter_report: Option<FilterReport>,
+    /// Note for the run record
+    note: Option<String>,
+}
+
+/// Export the distribution, honouring the running-distribution policy
+fn export_for_backup(
+    distro_name: &str,
+    dir: &Path,
+    file_name: &str,
+    format: BackupFormat,
+    filter: &ExportFilter,
+) -> Result<ExportedArchive, (BackupRunStatus, String)> {
+    let distro = core::list_distributions()
+        .map_err(|e| (BackupRunStatus::Failed, e.to_string()))?
+        .into_iter()
+        .find(|d| d.name == distro_name)
+        .ok_or_else(|| {
+            (
+                BackupRunStatus::Failed,
+                WslError::DistroNotFound(distro_name.to_string()).to_string(),
+            )
+        })?;
+
+    let was_running = distro.state == DistroState::Running;
+    if was_running && get_settings().backup_running_policy == BackupRunningPolicy::Skip {
+        return Err((
+            BackupRunStatus::Skipped,
+            "Distribution was running".to_string(),
+        ));
+    }
+    let (default_user, result) = export_quiesced(&distro, true, || {
+        write_archive(distro_name, dir, file_name, format, filter)
+    });
+
+    let mut note = None;
+    if was_running {
+        if let Err(e) = core::start_distribution(distro_name, distro.id.as_deref()) {
+            warn!("Failed to restart '{}' after backup: {}", distro_name, e);
+            note = Some(format!("Distribution was not restarted: {}", e));
+        }
+    }
+    let (size_bytes, sha256, filter_report) =
+        result.map_err(|e| (BackupRunStatus::Failed, e.to_string()))?;
+    Ok(ExportedArchive {
+        size_bytes,
+        sha256,
+        distro_id: distro
+            .id
+            .clone()
+            .or_else(|| crate::metadata::get_distro_id_by_name(distro_name)),
+        wsl_version: distro.version,
+        default_user,
+        filter_report,
+        note,
+    })
+}
+
+/// A backup stored on its target
+struct StoredBackup {
+    manifest: BackupManifest,
+    /// Bytes added to the repository (repository format only)
+    stored_bytes: Option<u64>,
+    filter_report: Option<FilterReport>,
+    /// Note for the run record
+    note: Option<String>,
+}
+
+/// Export a distribution and store it and its manifest on `target`
+fn back_up_to(
+    target: &BackupTargetConfig,
+    format: BackupFormat,
+    filter: &ExportFilter,
+    distro_name: &str,
+    id: &str,
+    created_at: &str,
+) -> Result<StoredBackup, (BackupRunStatus, String)> {
+    let failed = |e: WslError| (BackupRunStatus::Failed, e.to_string());
+    // Connect first so a missing share or key fails before the export
+    let storage = open(target).map_err(failed)?;
+    let staged_name = match format {
+        BackupFormat::Archive => format!("{}.tar.gz", id),
+        BackupFormat::Repository => format!("{}.tar", id),
+    };
+    let staging = staging_directory();
+    let archive = export_for_backup(distro_name, &staging, &staged_name, format, filter)?;
+    let mut manifest = BackupManifest {
+        id: id.to_string(),
+        distro_name: distro_name.to_string(),
+        distro_id: archive.distro_id,
+        created_at: created_at.to_string(),
+        format,
+        file_name: match format {
+            BackupFormat::Archive => staged_name.clone(),
+            BackupFormat::Repository => backup_repository::index_key(id),
+        },
+        size_bytes: archive.size_bytes,
+        sha256: archive.sha256,
+        wsl_version: archive.wsl_version,
+        default_user: archive.default_user,
+        filter: (!filter.is_empty()).then(|| filter.clone()),
+    };
+    if is_mock_mode() {
+        return Ok(StoredBackup {
+            manifest,
+            stored_bytes: None,
+            filter_report: archive.filter_report,
+            note: archive.note,
+        });
+    }
+
+    let staged = staging.join(&staged_name);
+    let result = match format {
+        BackupFormat::Archive => storage
+            .put_file(&staged, &manifest.file_name, &manifest.sha256)
+            .map(|()| None),
+        BackupFormat::Repository => {
+            backup_repository::store_tar(storage.as_ref(), &staged, id, ChunkerParams::DEFAULT).map(
+                |(index, stats)| {
+                    manifest.sha256 = index.sha256;
+                    Some(stats.stored_bytes)
+                },
+            )
+        }
+    };
+    let _ = std::fs::remove_file(&staged);
+    let stored_bytes = result.map_err(failed)?;
+    let json = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| WslError::ParseError(format!("Failed to serialize backup manifest: {}", e)))
+        .map_err(failed)?;
+    storage
+        .put_bytes(&manifest_key(id), &json)
+        .map_err(failed)?;
+    Ok(StoredBackup {
+        manifest,
+        stored_bytes,
+        filter_report: archive.filter_report,
+        note: archive.note,
+    })
+}
+
+fn manifest_key(backup_id: &str) -> String {
+    format!("{}.json", backup_id)
+}
+
+/// Back up a distribution now and record the run.
+///
+/// The returned run describes the outcome; an `Err` means the run could not
+/// be started or recorded.
+pub fn run_backup(distro_name: &str, trigger: BackupTrigger) -> Result<BackupRun, WslError> {
+    {
+        let mut in_progress = IN_PROGRESS.lock().unwrap_or_else(|p| p.into_inner());
+        if !in_progress.insert(distro_name.to_string()) {
+            return Err(WslError::CommandFailed(format!(
+                "A backup of '{}' is already running",
+                distro_name
+            )));
+        }
+    }
+    let result = run_backup_inner(distro_name, trigger);
+    IN_PROGRESS
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+        .remove(distro_name);
+    result
+}
+
+fn run_backup_inner(distro_name: &str, trigger: BackupTrigger) -> Result<BackupRun, WslError> {
+    let started = Local::now();
+    let store = read_store()?;
+    let id = timestamped_id(distro_name, &started, |id| {
+        store.runs.iter().any(|r| r.id == id)
+    });
+    let schedule = store
+        .schedules
+        .iter()
+        .find(|s| s.distro_name == distro_name);
+    let target_id = schedule.and_then(|s| s.target_id.clone());
+    let format = schedule.map(|s| s.format).unwrap_or_default();
+    let filter = schedule.map(|s| s.filter.clone()).unwrap_or_default();
+
+    info!("Starting {:?} backup '{}'", trigger, id);
+    let outcome = find_target(&store, target_id.as_deref().unwrap_or(DEFAULT_TARGET_ID))
+        .map_err(|e| (BackupRunStatus::Failed, e.to_string()))
+        .and_then(|target| {
+            back_up_to(
+                &target,
+                format,
+                &filter,
+                distro_name,
+                &id,
+                &started.to_rfc3339(),
+            )
+        });
+    let mut run = BackupRun {
+        id,
+        distro_name: distro_name.to_string(),
+        trigger,
+        status: BackupRunStatus::Success,
+        started_at: started.to_rfc3339(),
+        finished_at: Local::now().to_rfc3339(),
+        target_id,
+        format,
+        file_name: None,
+        size_bytes: None,
+        stored_bytes: None,
+        excluded_bytes: None,
+        sha256: None,
+        message: None,
+        pruned: false,
+    };
+    match outcome {
+        Ok(stored) => {
+            info!(
+                "Backup '{}' saved ({} bytes)",
+                run.id, stored.manifest.size_bytes
+            );
+            run.file_name = Some(stored.manifest.file_name);
+            run.size_bytes = Some(stored.manifest.size_bytes);
+            run.stored_bytes = stored.stored_bytes;
+            run.excluded_bytes = stored.filter_report.map(|r| r.excluded_bytes);
+            run.sha256 = Some(stored.manifest.sha256);
+            run.message = stored.note;
+        }
+        Err((status, message)) => {
+            warn!("Backup '{}' {:?}: {}", run.id, status, message);
+            run.status = status;
+            run.message = Some(message);
+        }
+    }
+
+    let (pruned, store) = update_store(|store| {
+        store.runs.push(run.clone());
+        let pruned = match store
+            .schedules
+            .iter()
+            .find(|s| s.distro_name == distro_name)
+        {
+            Some(schedule) if run.status == BackupRunStatus::Success => {
+                let schedule = schedule.clone();
+                apply_retention(&mut store.runs, &schedule)
+            }
+            _ => Vec::new(),
+        };
+        trim_history(&mut store.runs);
+        Ok((pruned, store.clone()))
+    })?;
+    if !is_mock_mode() {
+        for pruned_run in &pruned {
+            delete_archive(&store, pruned_run);
+        }
+        let repositories: HashSet<&str> = pruned
+            .iter()
+            .filter(|r| r.format == BackupFormat::Repository)
+            .map(BackupRun::target_id)
+            .collect();
+        for target_id in repositories {
+            if let Err(e) = prune_repository(target_id) {
+                warn!("Failed to prune repository on '{}': {}", target_id, e);
+            }
+        }
+    }
+    Ok(run)
+}
+
+/// Check the chunk repository on a target; `verify_data` also downloads and
+/// verifies every chunk
+pub fn check_repository(target_id: &str, verify_data: bool) -> Result<CheckReport, WslError> {
+    let store = read_store()?;
+    let config = find_target(&store, target_id)?;
+    if is_mock_mode() {
+        return Ok(CheckReport {
+            backups: store
+                .runs
+                .iter()
+                .filter(|r| {
+                    r.has_archive()
+                        && r.format == BackupFormat::Repository
+                        && r.target_id() == target_id
+                })
+                .count(),
+            data_verified: verify_data,
+            ..CheckReport::default()
+        });
+    }
+    let report = backup_repository::check(open(&config)?.as_ref(), verify_data)?;
+    info!(
+        "Checked repository on '{}': {} backups, {} chunks, healthy: {}",
+        target_id,
+        report.backups,
+        report.chunks,
+        report.is_healthy()
+    );
+    Ok(report)
+}
+
+/// Delete chunks no backup on the target refers to
+pub fn prune_repository(target_id: &str) -> Result<PruneReport, WslError> {
+    let config = find_target(&read_store()?, target_id)?;
+    if is_mock_mode() {
+        return Ok(PruneReport::default());
+    }
+    backup_repository::prune(open(&config)?.as_ref())
+}
+
+/// Delete a pruned archive (or repository index) and its manifest from the
+/// run's target
+fn delete_archive(store: &BackupStore, run: &BackupRun) {
+    let Some(file_name) = &run.file_name else {
+        return;
+    };
+    let deleted = find_target(store, run.target_id())
+        .and_then(|config| open(&config))
+        .and_then(|target| {
+            target.delete(file_name)?;
+            target.delete(&manifest_key(&run.id))
+        });
+    match deleted {
+        Ok(()) => info!(
+            "Retention removed backup '{}' from '{}'",
+            run.id,
+            run.target_id()
+        ),
+        Err(e) => warn!("Failed to delete backup '{}': {}", run.id, e),
+    }
+}
+
+/// Run every schedule that is due and return the recorded runs
+pub fn run_due_backups() -> Vec<BackupRun> {
+    let store = match read_store() {
+        Ok(store) => store,
+        Err(e) => {
+            warn!("Failed to read backup schedules: {}", e);
+            return Vec::new();
+        }
+    };
+    let now = Local::now().naive_local();
+
+    let mut runs = Vec::new();
+    for schedule in &store.schedules {
+        let last_scheduled_run = store
+            .runs
+            .iter()
+            .filter(|r| {
+                r.distro_name == schedule.distro_name && r.trigger == BackupTrigger::Scheduled
+            })
+            .filter_map(|r| parse_local(&r.started_at))
+            .max();
+        if !is_due(schedule, last_scheduled_run, now) {
+            continue;
+        }
+        match run_backup(&schedule.distro_name, BackupTrigger::Scheduled) {
+            Ok(run) => runs.push(run),
+            Err(e) => warn!(
+                "Scheduled backup of '{}' did not run: {}",
+                schedule.distro_name, e
+            ),
+        }
+    }
+    runs
+}
+
+/// Check for due backups once a minute on a background thread. `on_runs` is
+/// called with the runs of each check that recorded any.
+pub fn start_scheduler(on_runs: impl Fn(&[BackupRun]) + Send + 'static) {
+    let spawned = std::thread::Builder::new()
+        .name("backup-scheduler".to_string())
+        .spawn(move || loop {
+            std::thread::sleep(SCHEDULER_INTERVAL);
+            let runs = run_due_backups();
+            if !runs.is_empty() {
+                on_runs(&runs);
+            }
+        });
+    if let Err(e) = spawned {
+        warn!("Failed to start backup scheduler: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, min, 0)
+            .unwrap()
+    }
+
+    fn schedule(frequency: BackupFrequency, time: &str, weekday: u8) -> BackupSchedule {
+        BackupSchedule {
+            distro_name: "Ubuntu".to_string(),
+            enabled: true,
+            frequency,
+            time: time.to_string(),
+            weekday,
+            keep_daily: 7,
+            keep_weekly: 4,
+            updated_at: String::new(),
+            target_id: None,
+            format: BackupFormat::Archive,
+            filter: ExportFilter::default(),
+        }
+    }
+
+    #[test]
+    fn latest_slot_for_daily_and_weekly_schedules() {
+        let daily = schedule(BackupFrequency::Daily, "02:30", 0);
+        // 2026-10-14 is a Wednesday
+        assert_eq!(
+            latest_slot(&daily, at(2026, 10, 14, 3, 0)),
+            Some(at(2026, 10, 14, 2, 30))
+        );
+        assert_eq!(
+            latest_slot(&daily, at(2026, 10, 14, 2, 0)),
+            Some(at(2026, 10, 13, 2, 30))
+        );
+
+        let sunday = schedule(BackupFrequency::Weekly, "22:00", 6);
+        assert_eq!(
+            latest_slot(&sunday, at(2026, 10, 14, 12, 0)),
+            Some(at(2026, 10, 11, 22, 0))
+        );
+        let wednesday = schedule(BackupFrequency::Weekly, "22:00", 2);
+        assert_eq!(
+            latest_slot(&wednesday, at(2026, 10, 14, 12, 0)),
+            Some(at(2026, 10, 7, 22, 0))
+        );
+        assert_eq!(
+            latest_slot(
+                &schedule(BackupFrequency::Daily, "25:00", 0),
+                at(2026, 10, 14, 12, 0)
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn due_after_a_missed_slot_but_not_twice() {
+        let daily = schedule(BackupFrequency::Daily, "02:30", 0);
+        let now = at(2026, 10, 14, 9, 0);
+        assert!(is_due(&daily, Some(at(2026, 10, 13, 2, 30)), now));
+        assert!(!is_due(&daily, Some(at(2026, 10, 14, 2, 31)), now));
+        assert!(is_due(&daily, None, now));
+
+        let disabled = BackupSchedule {
+            enabled: false,
+            ..daily.clone()
+        };
+        assert!(!is_due(&disabled, None, now));
+
+        // A schedule saved after today's slot waits for tomorrow
+        let saved_later = BackupSchedule {
+            updated_at: at(2026, 10, 14, 8, 0)
+                .and_local_timezone(Local)
+                .unwrap()
+                .to_rfc3339(),
+            ..daily
+        };
+        assert!(!is_due(&saved_later, None, now));
+        assert!(is_due(&saved_later, None, at(2026, 10, 15, 2, 30)));
+    }
+
+    #[test]
+    fn retention_keeps_newest_per_day_and_week() {
+        // Two backups a day from Mon 2026-09-14 to Wed 2026-10-14
+        let mut archives = Vec::new();
+        let mut day = NaiveDate::from_ymd_opt(2026, 9, 14).unwrap();
+        while day <= NaiveDate::from_ymd_opt(2026, 10, 14).unwrap() {
+            for hour in [1, 13] {
+                let taken_at = day.and_hms_opt(hour, 0, 0).unwrap();
+                archives.push((taken_at.format("%m%d-%H").to_string(), taken_at));
+            }
+            day = day.succ_opt().unwrap();
+        }
+
+        let mut kept: Vec<String> = retained_ids(&archives, 3, 3).into_iter().collect();
+        kept.sort();
+        // Last three days, plus the newest of the two previous weeks
+        // (Sunday 10-11 and Sunday 10-04); this week's newest is 10-14.
+        assert_eq!(
+            kept,
+            vec!["1004-13", "1011-13", "1012-13", "1013-13", "1014-13"]
+        );
+
+        assert_eq!(retained_ids(&archives, 0, 1).len(), 1);
+        assert!(retained_ids(&[], 7, 4).is_empty());
+    }
+
+    #[test]
+    fn retention_marks_old_archives_pruned_and_history_is_trimmed() {
+        let run = |id: &str, started_at: NaiveDateTime, status: BackupRunStatus| BackupRun {
+            id: id.to_string(),
+            distro_name: "Ubuntu".to_string(),
+            trigger: BackupTrigger::Scheduled,
+            status,
+            started_at: started_at.and_local_timezone(Local).unwrap().to_rfc3339(),
+            finished_at: String::new(),
+            target_id: None,
+            format: BackupFormat::Archive,
+            file_name: (status == BackupRunStatus::Success).then(|| format!("{}.tar.gz", id)),
+            size_bytes: None,
+            stored_bytes: None,
+            excluded_bytes: None,
+            sha256: None,
+            message: None,
+            pruned: false,
+        };
+        let mut runs = vec![
+            run("a", at(2026, 10, 12, 2, 0), BackupRunStatus::Success),
+            run("b", at(2026, 10, 13, 2, 0), BackupRunStatus::Success),
+            run("c", at(2026, 10, 13, 9, 0), BackupRunStatus::Skipped),
+            run("d", at(2026, 10, 14, 2, 0), BackupRunStatus::Success),
+        ];
+        let policy = BackupSchedule {
+            keep_daily: 2,
+            keep_weekly: 0,
+            ..schedule(BackupFrequency::Daily, "02:00", 0)
+        };
+        let pruned: Vec<String> = apply_retention(&mut runs, &policy)
+            .into_iter()
+            .map(|r| r.id)
+            .collect();
+        assert_eq!(pruned, vec!["a"]);
+        assert!(runs[0].pruned && !runs[1].pruned && !runs[3].pruned);
+
+        for i in 0..MAX_HISTORY_PER_DISTRO {
+            runs.push(run(
+                &format!("skip-{}", i),
+                at(2026, 10, 15, 0, 0) + Duration::minutes(i as i64),
+                BackupRunStatus::Skipped,
+            ));
+        }
+        trim_history(&mut runs);
+        let ids: Vec<&str> = runs.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(runs.len(), MAX_HISTORY_PER_DISTRO + 2);
+        assert_eq!(&ids[..2], &["b", "d"]);
+        assert_eq!(ids.last(), Some(&"skip-49"));
+    }
+
+    #[test]
+    fn history_is_trimmed_by_time_across_offset_changes() {
+        let run = |id: &str, started_at: String| BackupRun {
+            id: id.to_string(),
+            distro_name: "Ubuntu".to_string(),
+            trigger: BackupTrigger::Scheduled,
+            status: BackupRunStatus::Skipped,
+            started_at,
+            finished_at: String::new(),
+            target_id: None,
+            format: BackupFormat::Archive,
+            file_name: None,
+            size_bytes: None,
+            stored_bytes: None,
+            excluded_bytes: None,
+            sha256: None,
+            message: None,
+            pruned: false,
+        };
+        // 00:59 UTC, before the clocks went back; its string sorts last
+        let mut runs = vec![run("summer", "2026-10-25T02:59:00+02:00".to_string())];
+        for i in 0..MAX_HISTORY_PER_DISTRO {
+            runs.push(run(
+                &format!("winter-{}", i),
+                format!("2026-10-25T02:{:02}:00+01:00", i),
+            ));
+        }
+        assert_eq!(
+            newest_first(&runs[0].started_at, &runs[1].started_at),
+            std::cmp::Ordering::Greater
+        );
+
+        trim_history(&mut runs);
+        assert_eq!(runs.len(), MAX_HISTORY_PER_DISTRO);
+        assert!(runs.iter().all(|r| r.id != "summer"));
+    }
+
+    #[test]
+    fn target_ids_are_unique_slugs() {
+        let existing = vec![BackupTargetConfig {
+            id: "office-nas".to_string(),
+            name: "Office NAS".to_string(),
+            location: TargetLocation::Local {
+                path: "D:\\Backups".to_string(),
+            },
+        }];
+        assert_eq!(target_id("Office NAS", &existing), "office-nas-2");
+        assert_eq!(target_id("MinIO (home)", &existing), "minio--home");
+        assert_eq!(target_id("Local", &existing), "local-2");
+        assert_eq!(target_id("  ", &existing), "target");
+    }
+
+    #[test]
+    fn manifests_round_trip_and_fall_back_to_runs() {
+        let manifest = BackupManifest {
+            id: "Ubuntu-20261014-020000".to_string(),
+            distro_name: "Ubuntu".to_string(),
+            distro_id: Some("{guid}".to_string()),
+            created_at: "2026-10-14T02:00:00+02:00".to_string(),
+            format: BackupFormat::Archive,
+            file_name: "Ubuntu-20261014-020000.tar.gz".to_string(),
+            size_bytes: 42,
+            sha256: "ab".repeat(32),
+            wsl_version: 2,
+            default_user: Some("dev".to_string()),
+            filter: None,
+        };
+        let json = serde_json::to_string(&manifest).unwrap();
+        assert!(json.contains("\"distroName\":\"Ubuntu\""));
+        assert_eq!(
+            serde_json::from_str::<BackupManifest>(&json).unwrap(),
+            manifest
+        );
+        // Manifests written before repositories existed are archives
+        let legacy = json.replace("\"format\":\"archive\",", "");
+        assert_ne!(legacy, json);
+        assert_eq!(
+            serde_json::from_str::<BackupManifest>(&legacy).unwrap(),
+            manifest
+        );
+
+        let run = BackupRun {
+            id: manifest.id.clone(),
+            distro_name: "Ubuntu".to_string(),
+            trigger: BackupTrigger::Manual,
+            status: BackupRunStatus::Success,
+            started_at: manifest.created_at.clone(),
+            finished_at: String::new(),
+            target_id: None,
+            format: BackupFormat::Repository,
+            file_name: Some(manifest.file_name.clone()),
+            size_bytes: Some(42),
+            stored_bytes: Some(7),
+            excluded_bytes: None,
+            sha256: Some(manifest.sha256.clone()),
+            message: None,
+            pruned: false,
+        };
+        assert_eq!(run.target_id(), DEFAULT_TARGET_ID);
+        let from_run = BackupManifest::from_run(&run).unwrap();
+        assert_eq!(from_run.file_name, manifest.file_name);
+        assert_eq!(from_run.format, BackupFormat::Repository);
+        assert_eq!(from_run.default_user, None);
+        assert!(BackupManifest::from_run(&BackupRun {
+            sha256: None,
+            ..run
+        })
+        .is_none());
+    }
+
+    #[test]
+    fn schedules_are_validated() {
+        assert!(validate_schedule(&schedule(BackupFrequency::Daily, "02:30", 0)).is_ok());
+        assert!(validate_schedule(&schedule(BackupFrequency::Daily, "2:30pm", 0)).is_err());
+        assert!(validate_schedule(&schedule(BackupFrequency::Weekly, "02:30", 7)).is_err());
+        let keeps_nothing = BackupSchedule {
+            keep_daily: 0,
+            keep_weekly: 0,
+            ..schedule(BackupFrequency::Daily, "02:30", 0)
+        };
+        assert!(validate_schedule(&keeps_nothing).is_err());
+    }
+}
@@ -0,0 +1,848 @@
+//! cloud-init style user data
+//!
+//! Interprets a `#cloud-config` subset after install, without cloud-init in
+//! the image: `users`, `write_files`, `packages` (with `package_update` and
+//! `package_upgrade`), `timezone`, `locale` and `runcmd`. Every module runs
+//! as root through the executor; the package module picks the distribution's
+//! package manager. Modules run independently, so one failure does not stop
+//! the others, and the outcome is stored in the distribution's metadata so a
+//! failed run can be re-run. The document is read with the YAML subset in
+//! [`crate::yaml`].
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use shell_escape::unix::escape;
+
+use super::provision::{apply_spec, exec_root_script, validate_account_name};
+use super::provision::{ProvisioningSpec, SudoPolicy};
+use super::types::WslError;
+use crate::metadata::{self, DistroMetadata, InstallSource};
+use crate::utils::is_mock_mode;
+use crate::yaml;
+
+/// Largest `write_files` content; it is passed on the `wsl.exe` command line.
+const MAX_WRITE_FILE_BYTES: usize = 16 * 1024;
+
+/// Lines of module output kept in the report (the full output is logged).
+const REPORT_OUTPUT_LINES: usize = 40;
+
+/// Top-level keys this module understands.
+const SUPPORTED_KEYS: &[&str] = &[
+    "users",
+    "write_files",
+    "packages",
+    "package_update",
+    "package_upgrade",
+    "timezone",
+    "locale",
+    "runcmd",
+];
+
+/// A string or a list of strings, as many cloud-config keys accept.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum StringOrList {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// `sudo:` is `false`, one sudoers rule, or a list of rules.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum SudoRules {
+    Flag(bool),
+    Rules(StringOrList),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CloudUser {
+    pub name: String,
+    #[serde(default)]
+    pub uid: Option<u32>,
+    #[serde(default)]
+    pub groups: Option<StringOrList>,
+    #[serde(default)]
+    pub sudo: Option<SudoRules>,
+    #[serde(default)]
+    pub passwd: Option<String>,
+    #[serde(default)]
+    pub hashed_passwd: Option<String>,
+    #[serde(default)]
+    pub plain_text_passwd: Option<String>,
+    #[serde(default)]
+    pub shell: Option<String>,
+}
+
+/// `users:` entries are a user mapping or a bare name (`default` is skipped).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum UserEntry {
+    Name(String),
+    User(Box<CloudUser>),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WriteFile {
+    pub path: String,
+    #[serde(default)]
+    pub content: String,
+    /// Octal mode such as `'0644'`
+    #[serde(default)]
+    pub permissions: Option<String>,
+    /// `user` or `user:group`
+    #[serde(default)]
+    pub owner: Option<String>,
+    /// Only plain text is supported
+    #[serde(default)]
+    pub encoding: Option<String>,
+    #[serde(default)]
+    pub append: bool,
+}
+
+/// The supported cloud-config subset.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct CloudConfig {
+    pub users: Vec<UserEntry>,
+    pub write_files: Vec<WriteFile>,
+    pub packages: Vec<String>,
+    pub package_update: bool,
+    pub package_upgrade: bool,
+    pub timezone: Option<String>,
+    pub locale: Option<String>,
+    /// Shell strings, or argument lists run without a shell
+    pub runcmd: Vec<StringOrList>,
+}
+
+fn invalid(msg: String) -> WslError {
+    WslError::CommandFailed(format!("Invalid cloud-config: {}", msg))
+}
+
+fn split_list(value: &StringOrList) -> Vec<String> {
+    match value {
+        StringOrList::One(s) => s
+            .split([',', ' '])
+            .filter(|g| !g.is_empty())
+            .map(str::to_string)
+            .collect(),
+        StringOrList::Many(items) => items.iter().map(|g| g.trim().to_string()).collect(),
+    }
+}
+
+impl CloudUser {
+    fn to_spec(&self) -> Result<ProvisioningSpec, WslError> {
+        if self.plain_text_passwd.is_some() {
+            return Err(invalid(format!(
+                "user '{}': plain_text_passwd is not supported, use hashed_passwd",
+                self.name
+            )));
+        }
+        let sudo = match &self.sudo {
+            None | Some(SudoRules::Flag(false)) => SudoPolicy::None,
+            Some(SudoRules::Flag(true)) => SudoPolicy::Password,
+            Some(SudoRules::Rules(rules)) => {
+                let rules = match rules {
+                    StringOrList::One(rule) => vec![rule.clone()],
+                    StringOrList::Many(rules) => rules.clone(),
+                };
+                if rules.iter().any(|r| r.contains("NOPASSWD")) {
+                    SudoPolicy::NoPassword
+                } else {
+                    SudoPolicy::Password
+                }
+            }
+        };
+        Ok(ProvisioningSpec {
+            username: self.name.clone(),
+            uid: self.uid,
+            groups: self.groups.as_ref().map(split_list).unwrap_or_default(),
+            sudo,
+            password_hash: self.hashed_passwd.clone().or_else(|| self.passwd.clone()),
+            shell: self.shell.clone(),
+        })
+    }
+}
+
+impl WriteFile {
+    /// `(mode, owner)` with defaults applied.
+    fn resolved(&self) -> Result<(String, String), WslError> {
+        let path = &self.path;
+        if !path.starts_with('/')
+            || path.ends_with('/')
+            || path.split('/').any(|part| part == "..")
+            || path.chars().any(char::is_control)
+        {
+            return Err(invalid(format!(
+                "write_files path '{}' must be an absolute file path",
+                path
+            )));
+        }
+        if let Some(encoding) = &self.encoding {
+            if !matches!(encoding.as_str(), "text/plain" | "text") {
+                return Err(invalid(format!(
+                    "write_files '{}': encoding '{}' is not supported",
+                    path, encoding
+                )));
+            }
+        }
+        if self.content.len() > MAX_WRITE_FILE_BYTES {
+            return Err(invalid(format!(
+                "write_files '{}' is larger than {} KiB",
+                path,
+                MAX_WRITE_FILE_BYTES / 1024
+            )));
+        }
+        let mode = self.permissions.as_deref().unwrap_or("0644");
+        if !(3..=4).contains(&mode.len()) || !mode.chars().all(|c| ('0'..='7').contains(&c)) {
+            return Err(invalid(format!(
+                "write_files '{}': invalid permissions '{}'",
+                path, mode
+            )));
+        }
+        let owner = self.owner.as_deref().unwrap_or("root:root");
+        let (user, group) = owner.split_once(':').unwrap_or((owner, ""));
+        validate_account_name("write_files owner", user)?;
+        if !group.is_empty() {
+            validate_account_name("write_files group", group)?;
+        }
+        Ok((mode.to_string(), owner.to_string()))
+    }
+}
+
+fn validate_package(name: &str) -> Result<(), WslError> {
+    let valid = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphanumeric())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || ".+-_:=~*@".contains(c));
+    if valid {
+        Ok(())
+    } else {
+        Err(invalid(format!("invalid package name '{}'", name)))
+    }
+}
+
+impl CloudConfig {
+    /// Parse user data; returns the config and the top-level keys that are
+    /// ignored because this subset does not handle them.
+    pub fn parse(user_data: &str) -> Result<(Self, Vec<String>), WslError> {
+        let first = user_data.lines().map(str::trim).find(|l| !l.is_empty());
+        if first.is_some_and(|l| l.starts_with("#!") || l.starts_with("#include")) {
+            return Err(invalid(
+                "only #cloud-config user data is supported".to_string(),
+            ));
+        }
+        let parsed = yaml::parse(user_data)
+            .map_err(|e| WslError::CommandFailed(format!("Invalid cloud-config at {}", e)))?;
+        let value = match parsed {
+            Value::Null => Value::Object(Map::new()),
+            value @ Value::Object(_) => value,
+            _ => return Err(invalid("the document must be a mapping".to_string())),
+        };
+        let ignored = value
+            .as_object()
+            .into_iter()
+            .flat_map(|map| map.keys())
+            .filter(|key| !SUPPORTED_KEYS.contains(&key.as_str()))
+            .cloned()
+            .collect();
+        let config: CloudConfig =
+            serde_json::from_value(value).map_err(|e| invalid(e.to_string()))?;
+        Ok((config, ignored))
+    }
+
+    /// Users to create, in order; the first becomes the default user.
+    fn user_specs(&self) -> Result<Vec<ProvisioningSpec>, WslError> {
+        let mut specs: Vec<ProvisioningSpec> = Vec::new();
+        for entry in &self.users {
+            let spec = match entry {
+                UserEntry::Name(name) if name == "default" => continue,
+                UserEntry::Name(name) => ProvisioningSpec {
+                    username: name.clone(),
+                    sudo: SudoPolicy::None,
+                    ..Default::default()
+                },
+                UserEntry::User(user) => user.to_spec()?,
+            };
+            spec.validate()?;
+            if specs.iter().any(|s| s.username == spec.username) {
+                return Err(invalid(format!("user '{}' is listed twice", spec.username)));
+            }
+            specs.push(spec);
+        }
+        Ok(specs)
+    }
+
+    pub fn validate(&self) -> Result<(), WslError> {
+        self.user_specs()?;
+        for file in &self.write_files {
+            file.resolved()?;
+        }
+        for package in &self.packages {
+            validate_package(package)?;
+        }
+        if let Some(tz) = &self.timezone {
+            let valid = !tz.is_empty()
+                && !tz.split('/').any(|part| part.is_empty() || part == "..")
+                && tz
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "/_+-".contains(c));
+            if !valid {
+                return Err(invalid(format!("invalid timezone '{}'", tz)));
+            }
+        }
+        if let Some(locale) = &self.locale {
+            let valid = locale
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic())
+                && locale
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "._@-".contains(c));
+            if !valid {
+                return Err(invalid(format!("invalid locale '{}'", locale)));
+            }
+        }
+        for command in &self.runcmd {
+            let empty = match command {
+                StringOrList::One(cmd) => cmd.trim().is_empty(),
+                StringOrList::Many(args) => args.is_empty(),
+            };
+            if empty {
+                return Err(invalid("runcmd entries cannot be empty".to_string()));
+            }
+        }
+        if self.modules().is_empty() {
+            return Err(invalid("nothing to apply".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Modules with work to do, in execution order.
+    fn modules(&self) -> Vec<CloudInitModule> {
+        let mut modules = Vec::new();
+        let default_only = self
+            .users
+            .iter()
+            .all(|u| matches!(u, UserEntry::Name(n) if n == "default"));
+        if !default_only {
+            modules.push(CloudInitModule::Users);
+        }
+        if !self.write_files.is_empty() {
+            modules.push(CloudInitModule::WriteFiles);
+        }
+        if !self.packages.is_empty() || self.package_update || self.package_upgrade {
+            modules.push(CloudInitModule::Packages);
+        }
+        if self.timezone.is_some() {
+            modules.push(CloudInitModule::Timezone);
+        }
+        if self.locale.is_some() {
+            modules.push(CloudInitModule::Locale);
+        }
+        if !self.runcmd.is_empty() {
+            modules.push(CloudInitModule::Runcmd);
+        }
+        modules
+    }
+}
+
+// === Package managers ===
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PackageManager {
+    Apt,
+    Dnf,
+    Yum,
+    Zypper,
+    Pacman,
+    Apk,
+    Xbps,
+}
+
+/// Binaries probed in order, and the package manager each identifies.
+const PACKAGE_MANAGERS: &[(&str, PackageManager)] = &[
+    ("apt-get", PackageManager::Apt),
+    ("dnf", PackageManager::Dnf),
+    ("yum", PackageManager::Yum),
+    ("zypper", PackageManager::Zypper),
+    ("pacman", PackageManager::Pacman),
+    ("apk", PackageManager::Apk),
+    ("xbps-install", PackageManager::Xbps),
+];
+
+impl PackageManager {
+    /// Script printing the first package manager binary found.
+    fn detect_script() -> String {
+        let binaries: Vec<&str> = PACKAGE_MANAGERS.iter().map(|(bin, _)| *bin).collect();
+        format!(
+            "for pm in {}; do command -v $pm >/dev/null 2>&1 && {{ echo $pm; exit 0; }}; done\necho 'No supported package manager found' >&2; exit 1",
+            binaries.join(" ")
+        )
+    }
+
+    fn from_binary(binary: &str) -> Option<Self> {
+        PACKAGE_MANAGERS
+            .iter()
+            .find(|(bin, _)| *bin == binary)
+            .map(|(_, pm)| *pm)
+    }
+
+    /// Refresh, upgrade and install commands, non-interactive throughout.
+    fn script(&self, packages: &[String], update: bool, upgrade: bool) -> String {
+        let (refresh, upgrade_cmd, install) = match self {
+            PackageManager::Apt => ("apt-get update", "apt-get -y upgrade", "apt-get install -y"),
+            PackageManager::Dnf => ("dnf -y makecache", "dnf -y upgrade", "dnf -y install"),
+            PackageManager::Yum => ("yum -y makecache", "yum -y update", "yum -y install"),
+            PackageManager::Zypper => (
+                "zypper --non-interactive refresh",
+                "zypper --non-interactive update",
+                "zypper --non-interactive install",
+            ),
+            PackageManager::Pacman => (
+                "pacman -Sy --noconfirm",
+                "pacman -Su --noconfirm",
+                "pacman -S --noconfirm --needed",
+            ),
+            PackageManager::Apk => ("apk update", "apk upgrade", "apk add"),
+            PackageManager::Xbps => ("xbps-install -S", "xbps-install -yu", "xbps-install -y"),
+        };
+        let mut script = String::from("set -e\n");
+        if *self == PackageManager::Apt {
+            script.push_str("export DEBIAN_FRONTEND=noninteractive\n");
+        }
+        // Fresh images usually ship without package lists, so installing
+        // implies a refresh (as cloud-init does)
+        if update || upgrade || !packages.is_empty() {
+            script.push_str(refresh);
+            script.push('\n');
+        }
+        if upgrade {
+            script.push_str(upgrade_cmd);
+            script.push('\n');
+        }
+        if !packages.is_empty() {
+            let names: Vec<String> = packages
+                .iter()
+                .map(|p| escape(p.into()).to_string())
+                .collect();
+            script.push_str(&format!("{} {}\n", install, names.join(" ")));
+        }
+        script
+    }
+}
+
+// === Module scripts ===
+
+fn write_file_script(file: &WriteFile, mode: &str, owner: &str) -> String {
+    let path = escape(file.path.as_str().into());
+    let redirect = if file.append { ">>" } else { ">" };
+    format!(
+        "set -e\nmkdir -p \"$(dirname {path})\"\nprintf '%s' {content} {redirect} {path}\nchmod {mode} {path}\nchown {owner} {path}\necho \"Wrote {shown}\"",
+        content = escape(file.content.as_str().into()),
+        shown = file.path.replace(['"', '$', '`', '\\'], "_"),
+    )
+}
+
+fn timezone_script(tz: &str) -> String {
+    format!(
+        r#"set -e
+[ -f /usr/share/zoneinfo/{tz} ] || {{ echo "Unknown timezone {tz} (is tzdata installed?)" >&2; exit 1; }}
+ln -sf /usr/share/zoneinfo/{tz} /etc/localtime
+echo '{tz}' > /etc/timezone
+echo "Timezone set to {tz}""#
+    )
+}
+
+/// Generate the locale where `locale-gen` exists (Debian, Ubuntu, Arch) and
+/// make it the system default for each family's config file.
+fn locale_script(locale: &str) -> String {
+    format!(
+        r#"set -e
+LOCALE='{locale}'
+if [ -f /etc/locale.gen ] && command -v locale-gen >/dev/null 2>&1; then
+  if ! grep -q "^$LOCALE " /etc/locale.gen; then
+    sed -i "s/^# *$LOCALE /$LOCALE /" /etc/locale.gen
+    grep -q "^$LOCALE " /etc/locale.gen || echo "$LOCALE ${{LOCALE#*.}}" >> /etc/locale.gen
+  fi
+  locale-gen
+fi
+if command -v update-locale >/dev/null 2>&1; then update-locale LANG="$LOCALE"; fi
+printf 'LANG=%s\n' "$LOCALE" > /etc/locale.conf
+if [ -f /etc/alpine-release ]; then
+  mkdir -p /etc/profile.d
+  printf 'export LANG=%s\n' "$LOCALE" > /etc/profile.d/locale.sh
+fi
+echo "Locale set to $LOCALE""#
+    )
+}
+
+fn runcmd_script(command: &StringOrList) -> String {
+    match command {
+        StringOrList::One(cmd) => cmd.clone(),
+        StringOrList::Many(args) => args
+            .iter()
+            .map(|a| escape(a.as_str().into()).to_string())
+            .collect::<Vec<_>>()
+            .join(" "),
+    }
+}
+
+// === Running ===
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CloudInitModule {
+    Users,
+    WriteFiles,
+    Packages,
+    Timezone,
+    Locale,
+    Runcmd,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudInitModuleResult {
+    pub module: CloudInitModule,
+    pub success: bool,
+    /// Tail of the module's output
+    pub output: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudInitReport {
+    pub distro_name: String,
+    pub modules: Vec<CloudInitModuleResult>,
+    /// Top-level keys outside the supported subset
+    pub ignored_keys: Vec<String>,
+    pub success: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProvisioningState {
+    /// Started but never finished (e.g. the app was closed mid-run)
+    Running,
+    Succeeded,
+    Failed,
+}
+
+/// Stored in [`DistroMetadata`] after each cloud-config run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProvisioningStatus {
+    pub state: ProvisioningState,
+    /// The user data, kept for re-runs
+    pub user_data: String,
+    #[serde(default)]
+    pub failed_modules: Vec<CloudInitModule>,
+    pub updated_at: String,
+}
+
+fn tail(output: &str, lines: usize) -> String {
+    let all: Vec<&str> = output.lines().collect();
+    all[all.len().saturating_sub(lines)..].join("\n")
+}
+
+fn run_users(distro: &str, specs: &[ProvisioningSpec]) -> Result<String, String> {
+    let mut log = Vec::new();
+    let mut failed = false;
+    for (i, spec) in specs.iter().enumerate() {
+        for step in apply_spec(distro, spec, i == 0) {
+            let status = if step.skipped {
+                "skipped"
+            } else if step.success {
+                "ok"
+            } else {
+                failed = true;
+                "failed"
+            };
+            log.push(format!(
+                "{} {:?}: {} {}",
+                spec.username, step.kind, status, step.message
+            ));
+        }
+    }
+    let log = log.join("\n");
+    if failed {
+        Err(log)
+    } else {
+        Ok(log)
+    }
+}
+
+fn run_packages(distro: &str, config: &CloudConfig) -> Result<String, String> {
+    let binary = exec_root_script(distro, &PackageManager::detect_script())?;
+    let manager = PackageManager::from_binary(binary.trim())
+        .ok_or_else(|| format!("Unexpected package manager '{}'", binary.trim()))?;
+    let output = exec_root_script(
+        distro,
+        &manager.script(
+            &config.packages,
+            config.package_update,
+            config.package_upgrade,
+        ),
+    )
+    .map_err(|e| format!("Using {}\n{}", binary.trim(), e))?;
+    Ok(format!("Using {}\n{}", binary.trim(), output))
+}
+
+fn run_module(
+    distro: &str,
+    config: &CloudConfig,
+    specs: &[ProvisioningSpec],
+    module: CloudInitModule,
+) -> Result<String, String> {
+    match module {
+        CloudInitModule::Users => run_users(distro, specs),
+        CloudInitModule::WriteFiles => {
+            let mut log = Vec::new();
+            for file in &config.write_files {
+                let (mode, owner) = file.resolved().map_err(|e| e.to_string())?;
+                let out = exec_root_script(distro, &write_file_script(file, &mode, &owner))
+                    .map_err(|e| format!("{}\n{}: {}", log.join("\n"), file.path, e))?;
+                log.push(out);
+            }
+            Ok(log.join("\n"))
+        }
+        CloudInitModule::Packages => run_packages(distro, config),
+        CloudInitModule::Timezone => exec_root_script(
+            distro,
+            &timezone_script(config.timezone.as_deref().unwrap_or_default()),
+        ),
+        CloudInitModule::Locale => exec_root_script(
+            distro,
+            &locale_script(config.locale.as_deref().unwrap_or_default()),
+        ),
+        CloudInitModule::Runcmd => {
+            let mut log = Vec::new();
+            for (i, command) in config.runcmd.iter().enumerate() {
+                match exec_root_script(distro, &runcmd_script(command)) {
+                    Ok(out) => log.push(out),
+                    Err(e) => {
+                        log.push(format!("runcmd[{}] failed: {}", i, e));
+                        return Err(log.join("\n"));
+                    }
+                }
+            }
+            Ok(log.join("\n"))
+        }
+    }
+}
+
+/// Check user data without running it; returns the ignored top-level keys.
+pub fn validate_user_data(user_data: &str) -> Result<Vec<String>, WslError> {
+    let (config, ignored) = CloudConfig::parse(user_data)?;
+    config.validate()?;
+    Ok(ignored)
+}
+
+fn record_status(distro: &str, status: ProvisioningStatus) {
+    let existing = metadata::get_metadata_by_name(distro).or_else(|| {
+        metadata::get_distro_id_by_name(distro)
+            .map(|id| DistroMetadata::new(id, distro.to_string(), InstallSource::Unknown))
+    });
+    let Some(mut meta) = existing else {
+        warn!(
+            "No metadata for '{}', cloud-config status not saved",
+            distro
+        );
+        return;
+    };
+    meta.provisioning = Some(status);
+    if let Err(e) = metadata::save_metadata(meta) {
+        warn!("Failed to save cloud-config status for '{}': {}", distro, e);
+    }
+}
+
+/// Apply cloud-config user data to an installed distribution. Invalid user
+/// data is rejected before anything runs; module failures are reported
+/// per module and recorded in the distribution's metadata.
+pub fn apply_user_data(distro: &str, user_data: &str) -> Result<CloudInitReport, WslError> {
+    let (config, ignored_keys) = CloudConfig::parse(user_data)?;
+    config.validate()?;
+    let specs = config.user_specs()?;
+    if !ignored_keys.is_empty() {
+        warn!(
+            "cloud-config for '{}' ignores: {}",
+            distro,
+            ignored_keys.join(", ")
+        );
+    }
+
+    let status = |state, failed_modules| ProvisioningStatus {
+        state,
+        user_data: user_data.to_string(),
+        failed_modules,
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    };
+    record_status(distro, status(ProvisioningState::Running, Vec::new()));
+
+    let mut modules = Vec::new();
+    for module in config.modules() {
+        info!("cloud-config '{}': running {:?}", distro, module);
+        let result = if is_mock_mode() {
+            Ok("Mock: done".to_string())
+        } else {
+            run_module(distro, &config, &specs, module)
+        };
+        let (success, output) = match result {
+            Ok(output) => {
+                info!("cloud-config '{}' {:?} output:\n{}", distro, module, output);
+                (true, output)
+            }
+            Err(output) => {
+                warn!("cloud-config '{}' {:?} failed:\n{}", distro, module, output);
+                (false, output)
+            }
+        };
+        modules.push(CloudInitModuleResult {
+            module,
+            success,
+            output: tail(&output, REPORT_OUTPUT_LINES),
+        });
+    }
+
+    let failed: Vec<CloudInitModule> = modules
+        .iter()
+        .filter(|m| !m.success)
+        .map(|m| m.module)
+        .collect();
+    let success = failed.is_empty();
+    let state = if success {
+        ProvisioningState::Succeeded
+    } else {
+        ProvisioningState::Failed
+    };
+    record_status(distro, status(state, failed));
+
+    Ok(CloudInitReport {
+        distro_name: distro.to_string(),
+        modules,
+        ignored_keys,
+        success,
+    })
+}
+
+/// Run the user data stored by the last [`apply_user_data`] again.
+pub fn rerun_user_data(distro: &str) -> Result<CloudInitReport, WslError> {
+    let user_data = metadata::get_metadata_by_name(distro)
+        .and_then(|m| m.provisioning)
+        .map(|p| p.user_data)
+        .ok_or_else(|| {
+            WslError::CommandFailed(format!("No cloud-config has been applied to '{}'", distro))
+        })?;
+    apply_user_data(distro, &user_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"#cloud-config
+timezone: Europe/London   # comment
+locale: en_GB.UTF-8
+package_update: true
+packages: [git, "build-essential"]
+users:
+  - default
+  - name: dev
+    uid: 1001
+    groups: docker, video
+    sudo: ALL=(ALL) NOPASSWD:ALL
+    shell: /bin/bash
+write_files:
+- path: /etc/motd
+  permissions: '0600'
+  content: |
+    Welcome
+      indented # not a comment
+
+  append: true
+runcmd:
+  - "echo 'a: b' > /tmp/x"
+  - [touch, /tmp/y z]
+unknown_module: {a: 1}
+"#;
+
+    #[test]
+    fn builds_config_and_specs() {
+        let (config, ignored) = CloudConfig::parse(SAMPLE).unwrap();
+        assert_eq!(ignored, vec!["unknown_module".to_string()]);
+        config.validate().unwrap();
+
+        let specs = config.user_specs().unwrap();
+        assert_eq!(specs.len(), 1);
+        assert_eq!(specs[0].username, "dev");
+        assert_eq!(specs[0].groups, vec!["docker", "video"]);
+        assert_eq!(specs[0].sudo, SudoPolicy::NoPassword);
+        assert_eq!(
+            config.modules(),
+            vec![
+                CloudInitModule::Users,
+                CloudInitModule::WriteFiles,
+                CloudInitModule::Packages,
+                CloudInitModule::Timezone,
+                CloudInitModule::Locale,
+                CloudInitModule::Runcmd,
+            ]
+        );
+
+        let rejects = |yaml: &str| {
+            CloudConfig::parse(yaml)
+                .and_then(|(c, _)| c.validate())
+                .is_err()
+        };
+        assert!(rejects("#!/bin/sh\necho hi"));
+        assert!(rejects(
+            "users:\n  - name: x\n    plain_text_passwd: secret"
+        ));
+        assert!(rejects("write_files:\n  - path: ../etc/x"));
+        assert!(rejects("write_files:\n  - path: /x\n    encoding: b64"));
+        assert!(rejects(
+            "write_files:\n  - path: /x\n    permissions: '0999'"
+        ));
+        assert!(rejects("packages: [\"git; rm -rf /\"]"));
+        assert!(rejects("timezone: ../../etc/shadow"));
+        assert!(rejects("users: [default]"));
+    }
+
+    #[test]
+    fn scripts_per_family() {
+        let packages = vec!["git".to_string(), "curl".to_string()];
+        let apt = PackageManager::Apt.script(&packages, false, false);
+        assert!(apt.contains("DEBIAN_FRONTEND=noninteractive"));
+        assert!(apt.contains("apt-get update\napt-get install -y git curl"));
+        let apk = PackageManager::Apk.script(&[], true, true);
+        assert_eq!(apk, "set -e\napk update\napk upgrade\n");
+        assert!(PackageManager::Pacman
+            .script(&packages, false, false)
+            .contains("pacman -S --noconfirm --needed git curl"));
+        assert_eq!(
+            PackageManager::from_binary("xbps-install"),
+            Some(PackageManager::Xbps)
+        );
+
+        let file = WriteFile {
+            path: "/etc/my app.conf".to_string(),
+            content: "it's $HOME".to_string(),
+            permissions: None,
+            owner: Some("dev".to_string()),
+            encoding: None,
+            append: false,
+        };
+        let (mode, owner) = file.resolved().unwrap();
+        let script = write_file_script(&file, &mode, &owner);
+        assert!(script.contains("printf '%s' 'it'\\''s $HOME' > '/etc/my app.conf'"));
+        assert!(script.contains("chmod 0644 '/etc/my app.conf'"));
+        assert!(script.contains("chown dev '/etc/my app.conf'"));
+
+        let argv = StringOrList::Many(vec!["touch".to_string(), "/tmp/a b".to_string()]);
+        assert_eq!(runcmd_script(&argv), "touch '/tmp/a b'");
+    }
+}
@@ -192,6 +192,12 @@ pub fn delete_distribution(name: &str) -> Result<(), WslError> {
 
     // Delete metadata after successful unregister
     if let Some(id) = distro_id {
+        if let Some(meta) = metadata::get_metadata(&id) {
+            super::distribution_conf::remove_applied_files(
+                meta.icon_path.as_deref(),
+                meta.terminal_fragment_path.as_deref(),
+            );
+        }
         if let Err(e) = metadata::delete_metadata(&id) {
             warn!("Failed to delete metadata (non-fatal): {}", e);
         } else {
@@ -376,17 +382,7 @@ pub fn set_default_user(name: &str, username: &str) -> Result<(), WslError> {
     }
 
     // Validate username format (basic Linux username rules)
-    if username.is_empty() {
-        return Err(WslError::CommandFailed("Username cannot be empty".to_string()));
-    }
-    if !username.chars().next().unwrap().is_ascii_lowercase() {
-        return Err(WslError::CommandFailed("Username must start with a lowercase letter".to_string()));
-    }
-    if !username.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-') {
-        return Err(WslError::CommandFailed(
-            "Username can only contain lowercase letters, digits, underscores, and hyphens".to_string()
-        ));
-    }
+    super::provision::validate_account_name("Username", username)?;
 
     let output = wsl_executor().set_default_user(name, username)?;
 
@@ -0,0 +1,739 @@
+//! Rootfs customization before import
+//!
+//! Rewrites a rootfs tar stream before `wsl --import` so new distributions
+//! start configured: `/etc/wsl.conf` generated from a `WslConf`, extra
+//! `/etc/hosts` entries, trusted CA certificates, skeleton dotfiles and
+//! arbitrary files, all with explicit ownership and modes. Unlike writing the
+//! files after import with `exec_as_root`, this needs no running distribution.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use super::archive::{self, AppendEntry, EntryAction, TarRewriter};
+use super::types::WslError;
+use crate::settings::{self, WslConf};
+
+const WSL_CONF_PATH: &str = "etc/wsl.conf";
+const HOSTS_PATH: &str = "etc/hosts";
+const SKEL_DIR: &str = "etc/skel";
+
+/// Marker line preceding the entries we add to `/etc/hosts`.
+const HOSTS_MARKER: &str = "# Added by WSL UI";
+
+/// Where each distro family looks for extra trust anchors, checked in order.
+const CA_ANCHOR_DIRS: &[&str] = &[
+    "usr/local/share/ca-certificates", // Debian, Ubuntu, Alpine
+    "etc/pki/ca-trust/source/anchors", // Fedora, RHEL
+    "etc/pki/trust/anchors",           // openSUSE
+    "etc/ca-certificates/trust-source/anchors", // Arch
+];
+
+/// Pre-built bundles we append to, so the certificates are trusted before
+/// `update-ca-certificates`/`update-ca-trust` ever runs.
+const CA_BUNDLES: &[&str] = &[
+    "etc/ssl/certs/ca-certificates.crt",
+    "etc/pki/ca-trust/extracted/pem/tls-ca-bundle.pem",
+    "etc/pki/tls/certs/ca-bundle.crt",
+    "var/lib/ca-certificates/ca-bundle.pem",
+];
+
+/// An `/etc/hosts` line.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostsEntry {
+    pub address: String,
+    pub hostnames: Vec<String>,
+}
+
+/// A file written at an absolute path inside the distribution.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct InjectedFile {
+    pub path: String,
+    pub content: String,
+    /// Octal permission bits; defaults to 0644
+    pub mode: Option<u32>,
+    pub uid: u64,
+    pub gid: u64,
+}
+
+/// Files and configuration injected into a rootfs before import.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RootfsCustomization {
+    /// Keys set here override those in the archive's `/etc/wsl.conf`
+    pub wsl_conf: Option<WslConf>,
+    /// Appended to `/etc/hosts`; also turns off `generateHosts` unless the
+    /// `wsl_conf` sets it explicitly, since WSL would overwrite the file
+    pub hosts_entries: Vec<HostsEntry>,
+    /// Local PEM files added as trust anchors and to the CA bundle
+    pub ca_certificates: Vec<String>,
+    /// Local files copied into `/etc/skel` and every existing home directory
+    pub skeleton_files: Vec<String>,
+    pub files: Vec<InjectedFile>,
+}
+
+impl RootfsCustomization {
+    pub fn is_empty(&self) -> bool {
+        self.wsl_conf.is_none()
+            && self.hosts_entries.is_empty()
+            && self.ca_certificates.is_empty()
+            && self.skeleton_files.is_empty()
+            && self.files.is_empty()
+    }
+
+    /// Check entries and that referenced local files are readable, so a bad
+    /// customization fails before any download or export starts.
+    pub fn validate(&self) -> Result<(), WslError> {
+        self.resolve().map(|_| ())
+    }
+
+    /// Reject customizing an xz archive (LXC images ship as `rootfs.tar.xz`),
+    /// which the rewriter cannot decode, before it is downloaded.
+    pub fn validate_source(&self, source: &str) -> Result<(), WslError> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        let name = source.split(['?', '#']).next().unwrap_or_default().to_ascii_lowercase();
+        if name.ends_with(".xz") || name.ends_with(".txz") {
+            return Err(WslError::ParseError(format!(
+                "{} is xz-compressed and cannot be customized; use a .tar or .tar.gz archive or install without customization",
+                source
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check entries and read the referenced local files.
+    fn resolve(&self) -> Result<Resolved, WslError> {
+        let invalid = |msg: String| WslError::ParseError(msg);
+
+        for entry in &self.hosts_entries {
+            entry
+                .address
+                .trim()
+                .parse::<IpAddr>()
+                .map_err(|_| invalid(format!("Invalid hosts address '{}'", entry.address)))?;
+            if entry.hostnames.is_empty() {
+                return Err(invalid(format!(
+                    "Hosts entry {} has no hostnames",
+                    entry.address
+                )));
+            }
+            if let Some(bad) = entry.hostnames.iter().find(|h| !is_valid_hostname(h)) {
+                return Err(invalid(format!("Invalid hostname '{}'", bad)));
+            }
+        }
+
+        let read = |path: &str| {
+            std::fs::read(path)
+                .map_err(|e| WslError::CommandFailed(format!("Failed to read {}: {}", path, e)))
+        };
+        let mut certificates = Vec::new();
+        for path in &self.ca_certificates {
+            let pem = String::from_utf8(read(path)?)
+                .ok()
+                .filter(|pem| pem.contains("-----BEGIN CERTIFICATE-----"))
+                .ok_or_else(|| invalid(format!("{} is not a PEM certificate", path)))?;
+            certificates.push((format!("{}.crt", file_stem(path)), pem));
+        }
+
+        let mut skeleton = Vec::new();
+        for path in &self.skeleton_files {
+            let name = Path::new(path)
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .ok_or_else(|| invalid(format!("Invalid skeleton file '{}'", path)))?;
+            skeleton.push((name, read(path)?));
+        }
+
+        let mut files = Vec::new();
+        for file in &self.files {
+            let path = archive::normalize_entry_path(&file.path);
+            if !file.path.starts_with('/')
+                || file.path.split('/').any(|p| p == "..")
+                || path.is_empty()
+            {
+                return Err(invalid(format!(
+                    "File path '{}' must be absolute",
+                    file.path
+                )));
+            }
+            let mode = file.mode.unwrap_or(0o644);
+            if mode > 0o7777 {
+                return Err(invalid(format!(
+                    "Invalid mode {:o} for {}",
+                    mode, file.path
+                )));
+            }
+            files.push(
+                AppendEntry::file(path, file.content.as_bytes(), mode).owned_by(file.uid, file.gid),
+            );
+        }
+
+        let mut wsl_conf = self.wsl_conf.clone();
+        if !self.hosts_entries.is_empty() {
+            let conf = wsl_conf.get_or_insert_with(WslConf::default);
+            conf.network_generate_hosts.get_or_insert(false);
+        }
+
+        Ok(Resolved {
+            wsl_conf,
+            hosts: self
+                .hosts_entries
+                .iter()
+                .map(|e| format!("{}\t{}", e.address.trim(), e.hostnames.join(" ")))
+                .collect(),
+            certificates,
+            skeleton,
+            files,
+        })
+    }
+}
+
+fn is_valid_hostname(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 253
+        && name.split('.').all(|label| {
+            !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+/// File name without extension, restricted to safe characters.
+fn file_stem(path: &str) -> String {
+    let stem = Path::new(path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let cleaned: String = stem
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if cleaned.is_empty() {
+        "custom".to_string()
+    } else {
+        cleaned
+    }
+}
+
+/// Apply the keys set in `patch` on top of `base`.
+fn merge_wsl_conf(mut base: WslConf, patch: &WslConf) -> WslConf {
+    macro_rules! overlay {
+        ($($field:ident),*) => {
+            $(if patch.$field.is_some() {
+                base.$field = patch.$field.clone();
+            })*
+        };
+    }
+    overlay!(
+        automount_enabled,
+        automount_mount_fs_tab,
+        automount_root,
+        automount_options,
+        network_generate_hosts,
+        network_generate_resolv_conf,
+        network_hostname,
+        interop_enabled,
+        interop_append_windows_path,
+        user_default,
+        boot_systemd,
+        boot_command
+    );
+    base
+}
+
+/// Customization with local files loaded.
+struct Resolved {
+    wsl_conf: Option<WslConf>,
+    hosts: Vec<String>,
+    certificates: Vec<(String, String)>,
+    skeleton: Vec<(String, Vec<u8>)>,
+    files: Vec<AppendEntry>,
+}
+
+struct CustomizeRewriter {
+    resolved: Resolved,
+    /// `home/<user>` and `root` directories with their owners
+    homes: HashMap<String, (u64, u64)>,
+    anchor_dirs: Vec<&'static str>,
+    wrote_wsl_conf: bool,
+    wrote_hosts: bool,
+    changed: Vec<String>,
+}
+
+impl CustomizeRewriter {
+    fn new(resolved: Resolved) -> Self {
+        Self {
+            resolved,
+            homes: HashMap::new(),
+            anchor_dirs: Vec::new(),
+            wrote_wsl_conf: false,
+            wrote_hosts: false,
+            changed: Vec::new(),
+        }
+    }
+
+    fn is_home(path: &str) -> bool {
+        path == "root"
+            || path
+                .strip_prefix("home/")
+                .map(|user| !user.is_empty() && !user.contains('/'))
+                .unwrap_or(false)
+    }
+
+    fn hosts_block(&self) -> String {
+        format!("\n{}\n{}\n", HOSTS_MARKER, self.resolved.hosts.join("\n"))
+    }
+
+    fn pem_block(&self) -> String {
+        self.resolved
+            .certificates
+            .iter()
+            .map(|(_, pem)| format!("\n{}\n", pem.trim()))
+            .collect()
+    }
+
+    /// Whether `path` is a skeleton target (`etc/skel/x` or `<home>/x`).
+    fn is_skeleton_target(&self, path: &str) -> bool {
+        let Some((dir, name)) = path.rsplit_once('/') else {
+            return false;
+        };
+        (dir == SKEL_DIR || self.homes.contains_key(dir))
+            && self.resolved.skeleton.iter().any(|(n, _)| n == name)
+    }
+}
+
+impl TarRewriter for CustomizeRewriter {
+    fn action(&mut self, path: &str, header: &tar::Header) -> EntryAction {
+        if header.entry_type() == tar::EntryType::Directory {
+            if Self::is_home(path) {
+                self.homes.insert(
+                    path.to_string(),
+                    (header.uid().unwrap_or(0), header.gid().unwrap_or(0)),
+                );
+            }
+            if let Some(dir) = CA_ANCHOR_DIRS.iter().find(|d| **d == path) {
+                self.anchor_dirs.push(dir);
+            }
+            return EntryAction::Keep;
+        }
+
+        let edit = match path {
+            WSL_CONF_PATH => self.resolved.wsl_conf.is_some(),
+            HOSTS_PATH => !self.resolved.hosts.is_empty(),
+            p => CA_BUNDLES.contains(&p) && !self.resolved.certificates.is_empty(),
+        };
+        if edit {
+            // `rewrite` keeps symlinked or oversized members untouched, so
+            // drop such a wsl.conf or hosts and let `finish` append a fresh
+            // one. `edit` records the change once it has actually run.
+            if archive::is_editable(header) || CA_BUNDLES.contains(&path) {
+                return EntryAction::Edit;
+            }
+            return EntryAction::Drop;
+        }
+
+        // Replaced by appended entries carrying their own ownership.
+        if self.resolved.files.iter().any(|f| f.path == path) || self.is_skeleton_target(path) {
+            self.changed.push(path.to_string());
+            return EntryAction::Drop;
+        }
+        EntryAction::Keep
+    }
+
+    fn edit(&mut self, path: &str, data: Vec<u8>) -> Vec<u8> {
+        match path {
+            WSL_CONF_PATH => self.wrote_wsl_conf = true,
+            HOSTS_PATH => self.wrote_hosts = true,
+            _ => {}
+        }
+        self.changed.push(path.to_string());
+
+        let mut content = String::from_utf8_lossy(&data).into_owned();
+        match path {
+            WSL_CONF_PATH => {
+                // Fall back to a fresh file if the existing one does not parse.
+                let base = settings::parse_wsl_conf(&content).unwrap_or_default();
+                let patch = self.resolved.wsl_conf.as_ref().cloned().unwrap_or_default();
+                return settings::serialize_wsl_conf(&merge_wsl_conf(base, &patch)).into_bytes();
+            }
+            HOSTS_PATH => content.push_str(&self.hosts_block()),
+            _ => content.push_str(&self.pem_block()),
+        }
+        content.into_bytes()
+    }
+
+    fn finish(&mut self) -> Vec<AppendEntry> {
+        let mut entries = Vec::new();
+
+        if let Some(conf) = self
+            .resolved
+            .wsl_conf
+            .as_ref()
+            .filter(|_| !self.wrote_wsl_conf)
+        {
+            let content = settings::serialize_wsl_conf(conf);
+            entries.push(AppendEntry::file(WSL_CONF_PATH, content, 0o644));
+        }
+        if !self.wrote_hosts && !self.resolved.hosts.is_empty() {
+            let content = format!(
+                "127.0.0.1\tlocalhost\n::1\tlocalhost ip6-localhost ip6-loopback\n{}",
+                self.hosts_block()
+            );
+            entries.push(AppendEntry::file(HOSTS_PATH, content, 0o644));
+        }
+
+        if !self.resolved.certificates.is_empty() {
+            let dirs = if self.anchor_dirs.is_empty() {
+                vec![CA_ANCHOR_DIRS[0]]
+            } else {
+                self.anchor_dirs.clone()
+            };
+            for dir in dirs {
+                for (name, pem) in &self.resolved.certificates {
+                    entries.push(AppendEntry::file(
+                        format!("{}/{}", dir, name),
+                        pem.as_bytes(),
+                        0o644,
+                    ));
+                }
+            }
+        }
+
+        for (name, data) in &self.resolved.skeleton {
+            entries.push(AppendEntry::file(
+                format!("{}/{}", SKEL_DIR, name),
+                data.clone(),
+                0o644,
+            ));
+            let mut homes: Vec<_> = self.homes.iter().collect();
+            homes.sort();
+            for (home, (uid, gid)) in homes {
+                entries.push(
+                    AppendEntry::file(format!("{}/{}", home, name), data.clone(), 0o644)
+                        .owned_by(*uid, *gid),
+                );
+            }
+        }
+
+        entries.append(&mut self.resolved.files);
+        self.changed.extend(entries.iter().map(|e| e.path.clone()));
+        entries
+    }
+}
+
+/// Apply `customization` while copying the tar stream from `input` to
+/// `output`. Returns the paths that were added or changed.
+pub fn customize_stream<R: std::io::Read, W: Write>(
+    input: R,
+    output: W,
+    customization: &RootfsCustomization,
+) -> Result<Vec<String>, WslError> {
+    let mut rewriter = CustomizeRewriter::new(customization.resolve()?);
+    archive::rewrite(input, output, &mut rewriter)?
+        .flush()
+        .map_err(|e| WslError::CommandFailed(format!("Failed to write rootfs: {}", e)))?;
+    Ok(rewriter.changed)
+}
+
+/// Write a customized copy of `tar_path` (plain or gzip) to a temporary
+/// uncompressed tarball and return its path. The caller removes it.
+pub fn customize_rootfs(
+    tar_path: &Path,
+    customization: &RootfsCustomization,
+) -> Result<PathBuf, WslError> {
+    let output_path = std::env::temp_dir().join(format!(
+        "wsl-customized-{}-{}.tar",
+        std::process::id(),
+        chrono::Utc::now().timestamp_millis()
+    ));
+    let input = archive::open_reader(
+        Box::new(BufReader::new(File::open(tar_path).map_err(|e| {
+            WslError::CommandFailed(format!("Failed to open {}: {}", tar_path.display(), e))
+        })?)),
+        &tar_path.display().to_string(),
+    )?
+    .into_inner();
+    let output = BufWriter::new(File::create(&output_path).map_err(|e| {
+        WslError::CommandFailed(format!("Failed to create {}: {}", output_path.display(), e))
+    })?);
+
+    match customize_stream(input, output, customization) {
+        Ok(changed) => {
+            info!(
+                "Customized rootfs {}: {}",
+                tar_path.display(),
+                changed.join(", ")
+            );
+            Ok(output_path)
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(&output_path);
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tar_with(files: &[(&str, &[u8])], dirs: &[(&str, u64)]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (name, uid) in dirs {
+            let mut header = tar::Header::new_gnu();
+            header.set_entry_type(tar::EntryType::Directory);
+            header.set_mode(0o755);
+            header.set_uid(*uid);
+            header.set_gid(*uid);
+            header.set_size(0);
+            builder
+                .append_data(&mut header, name, std::io::empty())
+                .unwrap();
+        }
+        for (name, data) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            builder.append_data(&mut header, name, *data).unwrap();
+        }
+        builder.into_inner().unwrap()
+    }
+
+    /// Read back `(path, mode, uid, contents)` for every regular file.
+    fn read_back(data: &[u8]) -> HashMap<String, (u32, u64, String)> {
+        let mut archive = tar::Archive::new(data);
+        archive
+            .entries()
+            .unwrap()
+            .filter_map(|e| {
+                let mut e = e.unwrap();
+                if e.header().entry_type() != tar::EntryType::Regular {
+                    return None;
+                }
+                let path = archive::normalize_entry_path(&e.path().unwrap().to_string_lossy());
+                let (mode, uid) = (e.header().mode().unwrap(), e.header().uid().unwrap());
+                let mut content = String::new();
+                std::io::Read::read_to_string(&mut e, &mut content).unwrap();
+                Some((path, (mode, uid, content)))
+            })
+            .collect()
+    }
+
+    fn write_temp(name: &str, content: &str) -> String {
+        let dir = crate::utils::unique_temp_dir("customize");
+        let path = dir.join(name);
+        std::fs::write(&path, content).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn injects_configuration_into_existing_rootfs() {
+        let input = tar_with(
+            &[
+                ("./etc/hosts", b"127.0.0.1 localhost\n"),
+                (
+                    "./etc/wsl.conf",
+                    b"[user]\ndefault=old\n[interop]\nappendWindowsPath=false\n",
+                ),
+                ("./etc/ssl/certs/ca-certificates.crt", b"EXISTING\n"),
+                ("./home/dev/.bashrc", b"old\n"),
+            ],
+            &[
+                ("./home/dev", 1000),
+                ("./root", 0),
+                ("./usr/local/share/ca-certificates", 0),
+            ],
+        );
+        let customization = RootfsCustomization {
+            wsl_conf: Some(WslConf {
+                boot_systemd: Some(true),
+                user_default: Some("dev".to_string()),
+                ..Default::default()
+            }),
+            hosts_entries: vec![HostsEntry {
+                address: "10.0.0.5".to_string(),
+                hostnames: vec!["build.internal".to_string(), "build".to_string()],
+            }],
+            ca_certificates: vec![write_temp(
+                "corp root.pem",
+                "-----BEGIN CERTIFICATE-----\nMIIB\n-----END CERTIFICATE-----\n",
+            )],
+            skeleton_files: vec![write_temp(".bashrc", "alias ll='ls -l'\n")],
+            files: vec![InjectedFile {
+                path: "/opt/tools/setup.sh".to_string(),
+                content: "#!/bin/sh\n".to_string(),
+                mode: Some(0o750),
+                uid: 1000,
+                gid: 1000,
+            }],
+        };
+
+        let mut output = Vec::new();
+        customize_stream(input.as_slice(), &mut output, &customization).unwrap();
+        let files = read_back(&output);
+
+        let wsl_conf = &files["etc/wsl.conf"].2;
+        assert!(wsl_conf.contains("default=dev"));
+        assert!(wsl_conf.contains("appendWindowsPath=false"));
+        assert!(wsl_conf.contains("systemd=true"));
+        assert!(wsl_conf.contains("generateHosts=false"));
+        assert_eq!(
+            files["etc/hosts"].2,
+            "127.0.0.1 localhost\n\n# Added by WSL UI\n10.0.0.5\tbuild.internal build\n"
+        );
+        assert!(files["etc/ssl/certs/ca-certificates.crt"]
+            .2
+            .starts_with("EXISTING\n"));
+        assert!(files["etc/ssl/certs/ca-certificates.crt"]
+            .2
+            .contains("MIIB"));
+        assert!(files.contains_key("usr/local/share/ca-certificates/corp_root.crt"));
+        assert_eq!(
+            files["etc/skel/.bashrc"],
+            (0o644, 0, "alias ll='ls -l'\n".to_string())
+        );
+        assert_eq!(files["home/dev/.bashrc"].1, 1000);
+        assert_eq!(files["home/dev/.bashrc"].2, "alias ll='ls -l'\n");
+        assert_eq!(files["root/.bashrc"].1, 0);
+        assert_eq!(files["opt/tools/setup.sh"].0, 0o750);
+        assert_eq!(files["opt/tools/setup.sh"].1, 1000);
+
+        // Replaced files are not duplicated
+        let mut archive = tar::Archive::new(output.as_slice());
+        let bashrc_count = archive
+            .entries()
+            .unwrap()
+            .filter(|e| {
+                e.as_ref()
+                    .unwrap()
+                    .path()
+                    .unwrap()
+                    .to_string_lossy()
+                    .ends_with("home/dev/.bashrc")
+            })
+            .count();
+        assert_eq!(bashrc_count, 1);
+    }
+
+    #[test]
+    fn hosts_only_keeps_existing_wsl_conf() {
+        let input = tar_with(&[("./etc/wsl.conf", b"[boot]\nsystemd=true\n")], &[]);
+        let customization = RootfsCustomization {
+            hosts_entries: vec![HostsEntry {
+                address: "::1".to_string(),
+                hostnames: vec!["dev.local".to_string()],
+            }],
+            ..Default::default()
+        };
+        let mut output = Vec::new();
+        customize_stream(input.as_slice(), &mut output, &customization).unwrap();
+        let files = read_back(&output);
+        assert!(files["etc/wsl.conf"].2.contains("systemd=true"));
+        assert!(files["etc/wsl.conf"].2.contains("generateHosts=false"));
+        assert!(files["etc/hosts"].2.contains("localhost"));
+        assert!(files["etc/hosts"].2.contains("::1\tdev.local"));
+    }
+
+    #[test]
+    fn symlinked_config_files_are_replaced_not_skipped() {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (name, target) in [
+            ("./etc/wsl.conf", "../run/wsl.conf"),
+            ("./etc/hosts", "../run/hosts"),
+            ("./etc/ssl/certs/ca-certificates.crt", "../../run/bundle.crt"),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_entry_type(tar::EntryType::Symlink);
+            header.set_mode(0o777);
+            header.set_size(0);
+            builder.append_link(&mut header, name, target).unwrap();
+        }
+        let input = builder.into_inner().unwrap();
+
+        let customization = RootfsCustomization {
+            wsl_conf: Some(WslConf {
+                boot_systemd: Some(true),
+                ..Default::default()
+            }),
+            hosts_entries: vec![HostsEntry {
+                address: "10.0.0.5".to_string(),
+                hostnames: vec!["build".to_string()],
+            }],
+            ca_certificates: vec![write_temp(
+                "symlinked.pem",
+                "-----BEGIN CERTIFICATE-----\nMIIB\n-----END CERTIFICATE-----\n",
+            )],
+            ..Default::default()
+        };
+        let mut output = Vec::new();
+        let changed = customize_stream(input.as_slice(), &mut output, &customization).unwrap();
+        let files = read_back(&output);
+
+        assert!(files["etc/wsl.conf"].2.contains("systemd=true"));
+        assert!(files["etc/hosts"].2.contains("10.0.0.5\tbuild"));
+        // A symlinked bundle is left alone and not reported as changed
+        assert!(!files.contains_key("etc/ssl/certs/ca-certificates.crt"));
+        assert!(!changed.iter().any(|p| p == "etc/ssl/certs/ca-certificates.crt"));
+        assert_eq!(changed.iter().filter(|p| *p == "etc/wsl.conf").count(), 1);
+        assert_eq!(changed.iter().filter(|p| *p == "etc/hosts").count(), 1);
+    }
+
+    #[test]
+    fn rejects_xz_sources_only_when_customizing() {
+        let url = "https://images.example/ubuntu/rootfs.tar.xz?sig=1";
+        assert!(RootfsCustomization::default().validate_source(url).is_ok());
+
+        let customization = RootfsCustomization {
+            hosts_entries: vec![HostsEntry {
+                address: "10.0.0.5".to_string(),
+                hostnames: vec!["build".to_string()],
+            }],
+            ..Default::default()
+        };
+        assert!(customization.validate_source(url).is_err());
+        assert!(customization.validate_source(r"C:\images\alpine.TXZ").is_err());
+        assert!(customization.validate_source("https://x.test/rootfs.tar.gz").is_ok());
+        assert!(customization.validate_source("https://x.test/image.wsl").is_ok());
+    }
+
+    #[test]
+    fn rejects_invalid_customizations() {
+        let bad_address = RootfsCustomization {
+            hosts_entries: vec![HostsEntry {
+                address: "not-an-ip".to_string(),
+                hostnames: vec!["x".to_string()],
+            }],
+            ..Default::default()
+        };
+        assert!(bad_address.resolve().is_err());
+
+        let relative = RootfsCustomization {
+            files: vec![InjectedFile {
+                path: "etc/../../x".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert!(relative.resolve().is_err());
+
+        let not_pem = RootfsCustomization {
+            ca_certificates: vec![write_temp("bad.crt", "hello")],
+            ..Default::default()
+        };
+        assert!(not_pem.resolve().is_err());
+        assert!(RootfsCustomization::default().is_empty());
+    }
+}
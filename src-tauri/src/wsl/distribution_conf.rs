@@ -0,0 +1,694 @@
+//! `.wsl` distribution packages and `/etc/wsl-distribution.conf`
+//!
+//! Modern WSL packages are gzip tarballs that ship `/etc/wsl-distribution.conf`
+//! describing first-run behaviour:
+//!
+//! ```ini
+//! [oobe]
+//! command = /usr/lib/wsl/oobe.sh
+//! defaultUid = 1000
+//! defaultName = MyDistro
+//!
+//! [shortcut]
+//! enabled = true
+//! icon = /usr/lib/wsl/distro.ico
+//!
+//! [windowsterminal]
+//! enabled = true
+//! ProfileTemplate = /usr/lib/wsl/terminal-profile.json
+//! ```
+//!
+//! `wsl --import` ignores this file, so we read it from the archive before
+//! import and apply it afterwards: the icon is extracted next to the install,
+//! a Windows Terminal profile fragment is written, and the OOBE command is run
+//! in a terminal so it can prompt for a user.
+//!
+//! The authoring side (`write_package`) turns a `wsl --export` tar stream into
+//! a `.wsl` package with a generated configuration and machine-specific state
+//! removed.
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use configparser::ini::Ini;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use super::archive;
+use super::types::WslError;
+use crate::utils::is_mock_mode;
+
+/// Path of the configuration file inside the archive.
+pub const DISTRIBUTION_CONF_PATH: &str = "etc/wsl-distribution.conf";
+
+/// Folder under `%LOCALAPPDATA%\Microsoft\Windows Terminal\Fragments` that
+/// holds the profile fragments we generate.
+const TERMINAL_FRAGMENT_APP: &str = "WSL UI";
+
+/// Parsed `/etc/wsl-distribution.conf`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DistributionConf {
+    /// `[oobe] command` - run as root on first launch
+    pub oobe_command: Option<String>,
+    /// `[oobe] defaultUid` - becomes the default user once OOBE succeeds
+    pub oobe_default_uid: Option<u32>,
+    /// `[oobe] defaultName` - suggested distribution name
+    pub oobe_default_name: Option<String>,
+    /// `[shortcut] enabled`
+    pub shortcut_enabled: bool,
+    /// `[shortcut] icon` - path of an `.ico` inside the distribution
+    pub shortcut_icon: Option<String>,
+    /// `[windowsterminal] enabled`
+    pub terminal_profile_enabled: bool,
+    /// `[windowsterminal] ProfileTemplate` - path of a JSON fragment template
+    pub terminal_profile_template: Option<String>,
+}
+
+impl Default for DistributionConf {
+    fn default() -> Self {
+        // WSL treats a missing `enabled` key as enabled.
+        Self {
+            oobe_command: None,
+            oobe_default_uid: None,
+            oobe_default_name: None,
+            shortcut_enabled: true,
+            shortcut_icon: None,
+            terminal_profile_enabled: true,
+            terminal_profile_template: None,
+        }
+    }
+}
+
+/// Configuration plus the archive files it references.
+#[derive(Debug, Clone, Default)]
+pub struct DistributionPackage {
+    pub conf: DistributionConf,
+    pub icon: Option<Vec<u8>>,
+    pub terminal_profile: Option<String>,
+}
+
+/// What was produced on disk when a package was applied.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AppliedDistributionConf {
+    pub icon_path: Option<String>,
+    pub terminal_fragment_path: Option<String>,
+}
+
+/// Parse the INI content of `wsl-distribution.conf`.
+pub fn parse_distribution_conf(content: &str) -> Result<DistributionConf, WslError> {
+    // Keys are case-insensitive in WSL (`ProfileTemplate`, `defaultUid`).
+    let mut ini = Ini::new();
+    ini.set_comment_symbols(&['#', ';']);
+    ini.read(content.to_string())
+        .map_err(|e| WslError::ParseError(format!("Failed to parse wsl-distribution.conf: {}", e)))?;
+
+    let get_str = |section: &str, key: &str| {
+        ini.get(section, key)
+            .map(|v| v.trim().trim_matches('"').to_string())
+            .filter(|v| !v.is_empty())
+    };
+    let get_bool = |section: &str, key: &str| ini.getbool(section, key).ok().flatten().unwrap_or(true);
+
+    let oobe_default_uid = match get_str("oobe", "defaultuid") {
+        Some(uid) => Some(uid.parse::<u32>().map_err(|_| {
+            WslError::ParseError(format!("Invalid [oobe] defaultUid '{}'", uid))
+        })?),
+        None => None,
+    };
+
+    Ok(DistributionConf {
+        oobe_command: get_str("oobe", "command"),
+        oobe_default_uid,
+        oobe_default_name: get_str("oobe", "defaultname"),
+        shortcut_enabled: get_bool("shortcut", "enabled"),
+        shortcut_icon: get_str("shortcut", "icon"),
+        terminal_profile_enabled: get_bool("windowsterminal", "enabled"),
+        terminal_profile_template: get_str("windowsterminal", "profiletemplate"),
+    })
+}
+
+/// Read `wsl-distribution.conf` and the icon/profile template it references
+/// from a tarball. Returns `Ok(None)` for plain rootfs archives without one.
+pub fn read_package(tar_path: &Path) -> Result<Option<DistributionPackage>, WslError> {
+    let files = archive::read_files(tar_path, &[DISTRIBUTION_CONF_PATH])?;
+    let Some(raw) = files.get(DISTRIBUTION_CONF_PATH) else {
+        return Ok(None);
+    };
+    let conf = parse_distribution_conf(&String::from_utf8_lossy(raw))?;
+
+    let icon = conf
+        .shortcut_icon
+        .as_deref()
+        .filter(|_| conf.shortcut_enabled)
+        .map(archive::normalize_entry_path);
+    let template = conf
+        .terminal_profile_template
+        .as_deref()
+        .filter(|_| conf.terminal_profile_enabled)
+        .map(archive::normalize_entry_path);
+    let wanted: Vec<&str> = icon.iter().chain(template.iter()).map(String::as_str).collect();
+    let mut referenced = if wanted.is_empty() {
+        Default::default()
+    } else {
+        archive::read_files(tar_path, &wanted)?
+    };
+
+    Ok(Some(DistributionPackage {
+        icon: icon.and_then(|p| referenced.remove(&p)),
+        terminal_profile: template
+            .and_then(|p| referenced.remove(&p))
+            .map(|b| String::from_utf8_lossy(&b).into_owned()),
+        conf,
+    }))
+}
+
+/// Build a Windows Terminal fragment for `name`. The package's template may
+/// be a full fragment (`{"profiles": [...], "schemes": [...]}`) or a single
+/// profile object; either way every profile is pointed at the distribution.
+pub fn build_terminal_fragment(
+    name: &str,
+    template: Option<&str>,
+    icon_path: Option<&str>,
+) -> serde_json::Value {
+    use serde_json::{json, Value};
+
+    let parsed = template
+        .and_then(|t| serde_json::from_str::<Value>(t).ok())
+        .filter(Value::is_object)
+        .unwrap_or_else(|| json!({}));
+    let mut fragment = if parsed.get("profiles").map(Value::is_array).unwrap_or(false) {
+        parsed
+    } else {
+        json!({ "profiles": [parsed] })
+    };
+
+    if let Some(profiles) = fragment.get_mut("profiles").and_then(Value::as_array_mut) {
+        if profiles.is_empty() {
+            profiles.push(json!({}));
+        }
+        for profile in profiles.iter_mut().filter_map(Value::as_object_mut) {
+            profile.insert("name".to_string(), json!(name));
+            profile.insert("commandline".to_string(), json!(format!("wsl.exe -d {}", name)));
+            if let Some(icon) = icon_path {
+                profile.insert("icon".to_string(), json!(icon));
+            }
+        }
+    }
+    fragment
+}
+
+/// Where the terminal fragment for `name` lives under `local_app_data`.
+fn terminal_fragment_path(local_app_data: &Path, name: &str) -> PathBuf {
+    local_app_data
+        .join("Microsoft")
+        .join("Windows Terminal")
+        .join("Fragments")
+        .join(TERMINAL_FRAGMENT_APP)
+        .join(format!("{}.json", name))
+}
+
+/// Shell command that runs the OOBE step and, on success, makes
+/// `defaultUid` the default user via `/etc/wsl.conf` (unless the distro
+/// already sets one). Uses `&&`/`||` only, since Windows Terminal treats `;`
+/// as a tab separator.
+pub fn oobe_terminal_command(conf: &DistributionConf) -> Option<String> {
+    let command = conf.oobe_command.as_deref()?;
+    Some(match conf.oobe_default_uid {
+        Some(uid) => format!(
+            "{} && u=$(getent passwd {} | cut -d: -f1) && [ -n \"$u\" ] && \
+             (grep -qs '^\\[user\\]' /etc/wsl.conf || printf '\\n[user]\\ndefault=%s\\n' \"$u\" >> /etc/wsl.conf)",
+            command, uid
+        ),
+        None => command.to_string(),
+    })
+}
+
+/// Apply a package to a freshly imported distribution. Failures are logged
+/// rather than returned: the import itself already succeeded.
+pub fn apply_package(
+    name: &str,
+    install_location: &str,
+    package: &DistributionPackage,
+    terminal_command: &str,
+) -> AppliedDistributionConf {
+    let mut applied = AppliedDistributionConf::default();
+    if is_mock_mode() {
+        info!("Mock: skipping wsl-distribution.conf side effects for '{}'", name);
+        return applied;
+    }
+
+    if let Some(icon) = &package.icon {
+        let path = Path::new(install_location).join("shortcut.ico");
+        match std::fs::write(&path, icon) {
+            Ok(()) => applied.icon_path = Some(path.to_string_lossy().into_owned()),
+            Err(e) => warn!("Failed to extract icon for '{}': {}", name, e),
+        }
+    }
+
+    if package.conf.terminal_profile_enabled {
+        let local_app_data = std::env::var("LOCALAPPDATA").unwrap_or_default();
+        if !local_app_data.is_empty() {
+            let path = terminal_fragment_path(Path::new(&local_app_data), name);
+            let fragment = build_terminal_fragment(
+                name,
+                package.terminal_profile.as_deref(),
+                applied.icon_path.as_deref(),
+            );
+            let written = path
+                .parent()
+                .map(std::fs::create_dir_all)
+                .transpose()
+                .and_then(|_| {
+                    std::fs::write(&path, serde_json::to_string_pretty(&fragment).unwrap_or_default())
+                });
+            match written {
+                Ok(()) => applied.terminal_fragment_path = Some(path.to_string_lossy().into_owned()),
+                Err(e) => warn!("Failed to write terminal fragment for '{}': {}", name, e),
+            }
+        }
+    }
+
+    if let Some(command) = oobe_terminal_command(&package.conf) {
+        info!("Running OOBE command for '{}'", name);
+        if let Err(e) =
+            super::terminal::open_terminal_with_command(name, None, &command, terminal_command)
+        {
+            warn!("Failed to start OOBE for '{}': {}", name, e);
+        }
+    }
+
+    applied
+}
+
+/// Remove files generated by `apply_package` (called on unregister).
+pub fn remove_applied_files(icon_path: Option<&str>, terminal_fragment_path: Option<&str>) {
+    for path in icon_path.into_iter().chain(terminal_fragment_path) {
+        if let Err(e) = std::fs::remove_file(path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                warn!("Failed to remove {}: {}", path, e);
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Package authoring
+// ---------------------------------------------------------------------------
+
+/// Where generated package files are placed inside the distribution.
+const PACKAGE_OOBE_PATH: &str = "usr/lib/wsl/oobe.sh";
+const PACKAGE_ICON_PATH: &str = "usr/lib/wsl/distribution.ico";
+const PACKAGE_TERMINAL_PATH: &str = "usr/lib/wsl/terminal-profile.json";
+
+/// Windows Terminal appearance for the generated profile template.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TerminalProfileOptions {
+    /// Name of a color scheme (e.g. "Campbell", "One Half Dark")
+    pub color_scheme: Option<String>,
+    /// `#RRGGBB` overrides applied on top of the scheme
+    pub background: Option<String>,
+    pub foreground: Option<String>,
+    pub cursor_color: Option<String>,
+}
+
+/// Choices for a `.wsl` package export.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WslPackageOptions {
+    /// Suggested distribution name (`[oobe] defaultName`)
+    pub friendly_name: Option<String>,
+    /// Shell script run as root on first launch
+    pub oobe_script: Option<String>,
+    /// UID that becomes the default user after OOBE
+    pub default_uid: Option<u32>,
+    /// Local `.ico` file to embed as the shortcut icon
+    pub icon_path: Option<String>,
+    /// Terminal profile colors; `None` disables the profile
+    pub terminal: Option<TerminalProfileOptions>,
+}
+
+/// Result of writing a `.wsl` package.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WslPackageReport {
+    pub path: String,
+    pub size_bytes: u64,
+    /// Archive members removed or emptied as machine-specific state
+    pub stripped: Vec<String>,
+}
+
+fn is_hex_color(value: &str) -> bool {
+    value.len() == 7 && value.starts_with('#') && value[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+impl WslPackageOptions {
+    pub fn validate(&self) -> Result<(), WslError> {
+        if let Some(name) = &self.friendly_name {
+            if name.chars().any(|c| c.is_whitespace() || c.is_control()) {
+                return Err(WslError::ParseError(
+                    "Default name cannot contain whitespace".to_string(),
+                ));
+            }
+        }
+        if let Some(icon) = &self.icon_path {
+            if !icon.to_lowercase().ends_with(".ico") || !Path::new(icon).is_file() {
+                return Err(WslError::ParseError(format!(
+                    "Icon must be an existing .ico file: {}",
+                    icon
+                )));
+            }
+        }
+        if let Some(terminal) = &self.terminal {
+            for color in [&terminal.background, &terminal.foreground, &terminal.cursor_color]
+                .into_iter()
+                .flatten()
+            {
+                if !is_hex_color(color) {
+                    return Err(WslError::ParseError(format!(
+                        "Terminal color '{}' must be #RRGGBB",
+                        color
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn oobe_script(&self) -> Option<&str> {
+        self.oobe_script.as_deref().filter(|s| !s.trim().is_empty())
+    }
+}
+
+/// Render `wsl-distribution.conf` for the chosen options.
+pub fn generate_distribution_conf(options: &WslPackageOptions) -> String {
+    let mut out = String::new();
+    let friendly = options.friendly_name.as_deref().filter(|s| !s.is_empty());
+    if options.oobe_script().is_some() || friendly.is_some() || options.default_uid.is_some() {
+        out.push_str("[oobe]\n");
+        if options.oobe_script().is_some() {
+            out.push_str(&format!("command = /{}\n", PACKAGE_OOBE_PATH));
+        }
+        if let Some(uid) = options.default_uid {
+            out.push_str(&format!("defaultUid = {}\n", uid));
+        }
+        if let Some(name) = friendly {
+            out.push_str(&format!("defaultName = {}\n", name));
+        }
+        out.push('\n');
+    }
+    out.push_str("[shortcut]\n");
+    match options.icon_path {
+        Some(_) => out.push_str(&format!("enabled = true\nicon = /{}\n", PACKAGE_ICON_PATH)),
+        None => out.push_str("enabled = false\n"),
+    }
+    out.push_str("\n[windowsterminal]\n");
+    match options.terminal {
+        Some(_) => out.push_str(&format!(
+            "enabled = true\nProfileTemplate = /{}\n",
+            PACKAGE_TERMINAL_PATH
+        )),
+        None => out.push_str("enabled = false\n"),
+    }
+    out
+}
+
+/// Render the Windows Terminal profile template for the chosen colors.
+fn terminal_profile_template(terminal: &TerminalProfileOptions) -> serde_json::Value {
+    let mut profile = serde_json::Map::new();
+    let fields = [
+        ("colorScheme", &terminal.color_scheme),
+        ("background", &terminal.background),
+        ("foreground", &terminal.foreground),
+        ("cursorColor", &terminal.cursor_color),
+    ];
+    for (key, value) in fields {
+        if let Some(value) = value.as_deref().filter(|v| !v.is_empty()) {
+            profile.insert(key.to_string(), serde_json::json!(value));
+        }
+    }
+    serde_json::json!({ "profiles": [profile] })
+}
+
+/// Strips machine-specific state and appends the generated package files.
+struct PackageRewriter {
+    files: Vec<archive::AppendEntry>,
+    stripped: Vec<String>,
+}
+
+impl archive::TarRewriter for PackageRewriter {
+    fn action(&mut self, path: &str, _header: &tar::Header) -> archive::EntryAction {
+        use archive::EntryAction;
+        let action = match path {
+            // Kept empty so systemd regenerates it on first boot.
+            "etc/machine-id" => EntryAction::Replace(Vec::new()),
+            "var/lib/dbus/machine-id" => EntryAction::Drop,
+            p if p.starts_with("etc/ssh/ssh_host_") => EntryAction::Drop,
+            // Replaced by the generated files.
+            p if self.files.iter().any(|f| f.path == p) => return EntryAction::Drop,
+            _ => return EntryAction::Keep,
+        };
+        self.stripped.push(path.to_string());
+        action
+    }
+
+    fn finish(&mut self) -> Vec<archive::AppendEntry> {
+        std::mem::take(&mut self.files)
+    }
+}
+
+/// Rewrite an exported tar stream into a `.wsl` package: machine-specific
+/// state is stripped, extended attributes are preserved, and the generated
+/// `wsl-distribution.conf` plus its OOBE script, icon and terminal template
+/// are appended. Returns the stripped member paths.
+pub fn write_package<R: Read, W: Write>(
+    input: R,
+    output: W,
+    options: &WslPackageOptions,
+) -> Result<Vec<String>, WslError> {
+    use archive::AppendEntry;
+
+    let mut files = vec![AppendEntry::file(
+        DISTRIBUTION_CONF_PATH,
+        generate_distribution_conf(options),
+        0o644,
+    )];
+    if let Some(script) = options.oobe_script() {
+        files.push(AppendEntry::file(PACKAGE_OOBE_PATH, script, 0o755));
+    }
+    if let Some(path) = &options.icon_path {
+        let icon = std::fs::read(path).map_err(|e| {
+            WslError::CommandFailed(format!("Failed to read icon {}: {}", path, e))
+        })?;
+        files.push(AppendEntry::file(PACKAGE_ICON_PATH, icon, 0o644));
+    }
+    if let Some(terminal) = &options.terminal {
+        let template = serde_json::to_vec_pretty(&terminal_profile_template(terminal))
+            .map_err(|e| WslError::ParseError(e.to_string()))?;
+        files.push(AppendEntry::file(PACKAGE_TERMINAL_PATH, template, 0o644));
+    }
+
+    let mut rewriter = PackageRewriter {
+        files,
+        stripped: Vec::new(),
+    };
+    archive::rewrite(input, output, &mut rewriter)?
+        .flush()
+        .map_err(|e| WslError::CommandFailed(format!("Failed to write package: {}", e)))?;
+    Ok(rewriter.stripped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wsl::archive::test_support::write_tar_gz;
+
+    const CONF: &str = "[oobe]\ncommand = /usr/lib/wsl/oobe.sh\ndefaultUid = 1000\ndefaultName = \"Custom\"\n\n\
+        [shortcut]\nicon = /usr/lib/wsl/custom.ico\n\n\
+        [windowsterminal]\nProfileTemplate = /usr/lib/wsl/terminal.json\n";
+
+    #[test]
+    fn parses_all_sections_with_case_insensitive_keys() {
+        let conf = parse_distribution_conf(CONF).unwrap();
+        assert_eq!(conf.oobe_command.as_deref(), Some("/usr/lib/wsl/oobe.sh"));
+        assert_eq!(conf.oobe_default_uid, Some(1000));
+        assert_eq!(conf.oobe_default_name.as_deref(), Some("Custom"));
+        assert!(conf.shortcut_enabled);
+        assert_eq!(conf.shortcut_icon.as_deref(), Some("/usr/lib/wsl/custom.ico"));
+        assert!(conf.terminal_profile_enabled);
+        assert_eq!(conf.terminal_profile_template.as_deref(), Some("/usr/lib/wsl/terminal.json"));
+    }
+
+    #[test]
+    fn parses_disabled_sections_and_rejects_bad_uid() {
+        let conf = parse_distribution_conf("[shortcut]\nenabled=false\n[windowsterminal]\nenabled=false\n").unwrap();
+        assert!(!conf.shortcut_enabled);
+        assert!(!conf.terminal_profile_enabled);
+        assert!(conf.oobe_command.is_none());
+
+        assert!(parse_distribution_conf("[oobe]\ndefaultUid=abc\n").is_err());
+    }
+
+    #[test]
+    fn reads_package_with_referenced_files() {
+        let dir = crate::utils::unique_temp_dir("distconf");
+        let wsl = dir.join("custom.wsl");
+        write_tar_gz(
+            &wsl,
+            &[
+                ("etc/wsl-distribution.conf", CONF.as_bytes()),
+                ("usr/lib/wsl/custom.ico", b"ICO"),
+                ("usr/lib/wsl/terminal.json", br#"{"profiles":[{"colorScheme":"Custom"}]}"#),
+            ],
+        );
+        let plain = dir.join("plain.tar.gz");
+        write_tar_gz(&plain, &[("etc/os-release", b"ID=x\n")]);
+
+        let package = read_package(&wsl).unwrap().expect("package conf present");
+        assert_eq!(package.icon.as_deref(), Some(&b"ICO"[..]));
+        assert!(package.terminal_profile.unwrap().contains("colorScheme"));
+        assert!(read_package(&plain).unwrap().is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn terminal_fragment_points_profiles_at_distro() {
+        let fragment = build_terminal_fragment(
+            "Custom",
+            Some(r#"{"profiles":[{"colorScheme":"Custom","name":"x"}],"schemes":[{"name":"Custom"}]}"#),
+            Some(r"C:\WSL\Custom\shortcut.ico"),
+        );
+        let profile = &fragment["profiles"][0];
+        assert_eq!(profile["name"], "Custom");
+        assert_eq!(profile["commandline"], "wsl.exe -d Custom");
+        assert_eq!(profile["colorScheme"], "Custom");
+        assert_eq!(profile["icon"], r"C:\WSL\Custom\shortcut.ico");
+        assert_eq!(fragment["schemes"][0]["name"], "Custom");
+
+        let bare = build_terminal_fragment("Plain", Some("not json"), None);
+        assert_eq!(bare["profiles"][0]["commandline"], "wsl.exe -d Plain");
+        assert!(bare["profiles"][0].get("icon").is_none());
+
+        let path = terminal_fragment_path(Path::new("L"), "Plain");
+        assert!(path.ends_with("Fragments/WSL UI/Plain.json") || path.ends_with(r"Fragments\WSL UI\Plain.json"));
+    }
+
+    #[test]
+    fn oobe_command_sets_default_user_only_with_uid() {
+        let mut conf = parse_distribution_conf(CONF).unwrap();
+        let cmd = oobe_terminal_command(&conf).unwrap();
+        assert!(cmd.starts_with("/usr/lib/wsl/oobe.sh && "));
+        assert!(cmd.contains("getent passwd 1000"));
+        assert!(!cmd.contains(';'));
+
+        conf.oobe_default_uid = None;
+        assert_eq!(oobe_terminal_command(&conf).unwrap(), "/usr/lib/wsl/oobe.sh");
+        conf.oobe_command = None;
+        assert!(oobe_terminal_command(&conf).is_none());
+    }
+
+    fn tar_bytes(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (name, data) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, *data).unwrap();
+        }
+        builder.into_inner().unwrap()
+    }
+
+    #[test]
+    fn write_package_strips_machine_state_and_round_trips() {
+        let dir = crate::utils::unique_temp_dir("package");
+        let icon = dir.join("custom.ico");
+        std::fs::write(&icon, b"ICO").unwrap();
+
+        let long_name = format!("./usr/share/{}/file", "x".repeat(120));
+        let input = tar_bytes(&[
+            ("./etc/machine-id", b"0123456789abcdef\n"),
+            ("./etc/ssh/ssh_host_ed25519_key", b"secret"),
+            ("./etc/ssh/sshd_config", b"Port 22\n"),
+            ("./etc/wsl-distribution.conf", b"[oobe]\ncommand=/old\n"),
+            (long_name.as_str(), b"long"),
+        ]);
+        let options = WslPackageOptions {
+            friendly_name: Some("Custom".to_string()),
+            oobe_script: Some("#!/bin/sh\nadduser me\n".to_string()),
+            default_uid: Some(1000),
+            icon_path: Some(icon.to_string_lossy().into_owned()),
+            terminal: Some(TerminalProfileOptions {
+                color_scheme: Some("Campbell".to_string()),
+                background: Some("#101010".to_string()),
+                ..Default::default()
+            }),
+        };
+        options.validate().unwrap();
+
+        let out = dir.join("custom.wsl");
+        let file = std::fs::File::create(&out).unwrap();
+        let gz = flate2::write::GzEncoder::new(file, flate2::Compression::fast());
+        let stripped = write_package(input.as_slice(), gz, &options).unwrap();
+        assert!(stripped.contains(&"etc/machine-id".to_string()));
+        assert!(stripped.contains(&"etc/ssh/ssh_host_ed25519_key".to_string()));
+        assert!(!stripped.contains(&"etc/ssh/sshd_config".to_string()));
+
+        let package = read_package(&out).unwrap().expect("generated conf");
+        assert_eq!(package.conf.oobe_command.as_deref(), Some("/usr/lib/wsl/oobe.sh"));
+        assert_eq!(package.conf.oobe_default_uid, Some(1000));
+        assert_eq!(package.conf.oobe_default_name.as_deref(), Some("Custom"));
+        assert_eq!(package.icon.as_deref(), Some(&b"ICO"[..]));
+        let template: serde_json::Value =
+            serde_json::from_str(&package.terminal_profile.unwrap()).unwrap();
+        assert_eq!(template["profiles"][0]["colorScheme"], "Campbell");
+        assert_eq!(template["profiles"][0]["background"], "#101010");
+
+        let files = archive::read_files(
+            &out,
+            &["etc/machine-id", "etc/ssh/ssh_host_ed25519_key", "etc/ssh/sshd_config", &long_name],
+        )
+        .unwrap();
+        assert_eq!(files.get("etc/machine-id").map(Vec::len), Some(0));
+        assert!(!files.contains_key("etc/ssh/ssh_host_ed25519_key"));
+        assert_eq!(files.get("etc/ssh/sshd_config").unwrap(), b"Port 22\n");
+        assert_eq!(files.get(&archive::normalize_entry_path(&long_name)).unwrap(), b"long");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn generated_conf_disables_unused_sections() {
+        let conf = generate_distribution_conf(&WslPackageOptions::default());
+        let parsed = parse_distribution_conf(&conf).unwrap();
+        assert!(parsed.oobe_command.is_none());
+        assert!(!parsed.shortcut_enabled);
+        assert!(!parsed.terminal_profile_enabled);
+    }
+
+    #[test]
+    fn package_options_validation() {
+        let bad_color = WslPackageOptions {
+            terminal: Some(TerminalProfileOptions {
+                foreground: Some("red".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert!(bad_color.validate().is_err());
+
+        let bad_name = WslPackageOptions {
+            friendly_name: Some("My Distro".to_string()),
+            ..Default::default()
+        };
+        assert!(bad_name.validate().is_err());
+
+        let missing_icon = WslPackageOptions {
+            icon_path: Some("/nonexistent/icon.ico".to_string()),
+            ..Default::default()
+        };
+        assert!(missing_icon.validate().is_err());
+    }
+}
@@ -16,6 +16,7 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::distro_catalog::DistroArch;
 use crate::utils::is_mock_mode;
 use crate::wsl::types::WslError;
 
@@ -60,7 +61,7 @@ pub struct DistroSource {
     pub mode: DistroSourceMode,
 }
 
-/// Hash variant of an entry in the manifest preview.
+/// Entry from the `ModernDistributions` section of a manifest.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ManifestEntryPreview {
@@ -76,14 +77,79 @@ pub struct ManifestEntryPreview {
     pub has_amd64: bool,
     /// Whether an Arm64 download URL is present.
     pub has_arm64: bool,
+    /// Amd64 download URL, if present.
+    #[serde(default)]
+    pub amd64_url: Option<String>,
+    /// Amd64 hash as lowercase hex (the manifest's `0x` prefix is stripped).
+    #[serde(default)]
+    pub amd64_sha256: Option<String>,
+    /// Arm64 download URL, if present.
+    #[serde(default)]
+    pub arm64_url: Option<String>,
+    /// Arm64 hash as lowercase hex (the manifest's `0x` prefix is stripped).
+    #[serde(default)]
+    pub arm64_sha256: Option<String>,
 }
 
-/// Parsed preview of a `ModernDistributions` manifest.
+/// Entry from the legacy `Distributions` array (Store-packaged distros).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LegacyDistributionPreview {
+    pub name: String,
+    pub friendly_name: String,
+    /// Microsoft Store product ID (e.g. "9PDXGNCFSCZV")
+    #[serde(default)]
+    pub store_app_id: Option<String>,
+    /// Appx package family name (e.g. "CanonicalGroupLimited.Ubuntu_79rhkp1fndgsc")
+    #[serde(default)]
+    pub package_family_name: Option<String>,
+    pub amd64: bool,
+    pub arm64: bool,
+    #[serde(default)]
+    pub amd64_package_url: Option<String>,
+    #[serde(default)]
+    pub arm64_package_url: Option<String>,
+}
+
+/// Category of a manifest validation warning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ManifestWarningKind {
+    /// A download URL has no `Sha256`; WSL cannot verify it.
+    MissingHash,
+    /// A `Sha256` value is not 64 hex characters.
+    InvalidHash,
+    /// The same name appears more than once in a section.
+    DuplicateName,
+    /// More than one entry in a flavor is marked `Default`.
+    MultipleDefaults,
+}
+
+/// Non-fatal problem found while parsing a manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestWarning {
+    pub kind: ManifestWarningKind,
+    #[serde(default)]
+    pub flavor: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub arch: Option<DistroArch>,
+    /// English description, for logs and as a UI fallback.
+    pub message: String,
+}
+
+/// Parsed preview of a distribution manifest.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ManifestPreview {
     pub url: String,
     pub entries: Vec<ManifestEntryPreview>,
+    #[serde(default)]
+    pub legacy_distributions: Vec<LegacyDistributionPreview>,
+    #[serde(default)]
+    pub warnings: Vec<ManifestWarning>,
 }
 
 // ---------------------------------------------------------------------------
@@ -91,87 +157,228 @@ pub struct ManifestPreview {
 // ---------------------------------------------------------------------------
 
 #[derive(Debug, Deserialize)]
-struct ModernDistributionsRoot {
-    #[serde(rename = "ModernDistributions")]
-    modern_distributions: serde_json::Map<String, serde_json::Value>,
+struct ManifestRoot {
+    #[serde(rename = "ModernDistributions", default)]
+    modern_distributions: Option<serde_json::Map<String, serde_json::Value>>,
+    #[serde(rename = "Distributions", default)]
+    distributions: Option<Vec<LegacyDistributionEntry>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct ModernDistributionEntry {
     #[serde(rename = "Name")]
     name: String,
-    #[serde(rename = "FriendlyName", default)]
+    #[serde(rename = "FriendlyName", default, skip_serializing_if = "Option::is_none")]
     friendly_name: Option<String>,
     #[serde(rename = "Default", default)]
     default: bool,
-    #[serde(rename = "Amd64Url", default)]
+    #[serde(rename = "Amd64Url", default, skip_serializing_if = "Option::is_none")]
     amd64_url: Option<ModernUrl>,
-    #[serde(rename = "Arm64Url", default)]
+    #[serde(rename = "Arm64Url", default, skip_serializing_if = "Option::is_none")]
     arm64_url: Option<ModernUrl>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct ModernUrl {
     #[serde(rename = "Url", default)]
     url: Option<String>,
+    #[serde(rename = "Sha256", default, skip_serializing_if = "Option::is_none")]
+    sha256: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LegacyDistributionEntry {
+    #[serde(rename = "Name")]
+    name: String,
+    #[serde(rename = "FriendlyName", default)]
+    friendly_name: Option<String>,
+    #[serde(rename = "StoreAppId", default)]
+    store_app_id: Option<String>,
+    #[serde(rename = "PackageFamilyName", default)]
+    package_family_name: Option<String>,
+    #[serde(rename = "Amd64", default)]
+    amd64: bool,
+    #[serde(rename = "Arm64", default)]
+    arm64: bool,
+    #[serde(rename = "Amd64PackageUrl", default)]
+    amd64_package_url: Option<String>,
+    #[serde(rename = "Arm64PackageUrl", default)]
+    arm64_package_url: Option<String>,
 }
 
-/// Parse a `ModernDistributions` manifest JSON into a UI preview.
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Normalize a manifest hash (`0xABC…`) to lowercase hex. Returns `None` when
+/// the value is not a 64-character hex digest.
+fn normalize_sha256(value: &str) -> Option<String> {
+    let trimmed = value.trim();
+    let hex = trimmed
+        .strip_prefix("0x")
+        .or_else(|| trimmed.strip_prefix("0X"))
+        .unwrap_or(trimmed);
+    (hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit())).then(|| hex.to_lowercase())
+}
+
+/// Extract the URL and normalized hash of one architecture, recording a
+/// warning when the hash is missing or malformed.
+fn parse_modern_url(
+    value: Option<ModernUrl>,
+    arch: DistroArch,
+    flavor: &str,
+    name: &str,
+    warnings: &mut Vec<ManifestWarning>,
+) -> (Option<String>, Option<String>) {
+    let Some(value) = value else {
+        return (None, None);
+    };
+    let Some(url) = non_empty(value.url) else {
+        return (None, None);
+    };
+    let warning = |kind, message| ManifestWarning {
+        kind,
+        flavor: Some(flavor.to_string()),
+        name: Some(name.to_string()),
+        arch: Some(arch),
+        message,
+    };
+    let sha256 = match non_empty(value.sha256) {
+        None => {
+            warnings.push(warning(
+                ManifestWarningKind::MissingHash,
+                format!("{} ({:?}) has no Sha256 hash", name, arch),
+            ));
+            None
+        }
+        Some(raw) => {
+            let normalized = normalize_sha256(&raw);
+            if normalized.is_none() {
+                warnings.push(warning(
+                    ManifestWarningKind::InvalidHash,
+                    format!("{} ({:?}) has an invalid Sha256 hash '{}'", name, arch, raw),
+                ));
+            }
+            normalized
+        }
+    };
+    (Some(url), sha256)
+}
+
+/// Parse a distribution manifest JSON into a UI preview.
 ///
-/// Validates the top-level `ModernDistributions` key exists and that each
-/// listed entry has a `Name`. Empty flavor arrays produce no entries but are
-/// not an error (a manifest author may legitimately ship an empty version of
-/// a flavor).
+/// Both the `ModernDistributions` map and the legacy `Distributions` array
+/// are read; at least one must be present. Entries without a `Name` are a hard
+/// error, while missing or malformed hashes, duplicate names and multiple
+/// defaults per flavor become warnings so the preview can flag them before the
+/// manifest is applied. Empty flavor arrays produce no entries but are not an
+/// error (a manifest author may legitimately ship an empty version of a flavor).
 pub fn parse_manifest(url: &str, body: &str) -> Result<ManifestPreview, WslError> {
-    let root: ModernDistributionsRoot = serde_json::from_str(body).map_err(|e| {
-        WslError::ParseError(format!(
-            "Manifest is not valid ModernDistributions JSON: {}",
-            e
-        ))
+    let root: ManifestRoot = serde_json::from_str(body).map_err(|e| {
+        WslError::ParseError(format!("Manifest is not valid distribution JSON: {}", e))
     })?;
+    if root.modern_distributions.is_none() && root.distributions.is_none() {
+        return Err(WslError::ParseError(
+            "Manifest has neither a ModernDistributions nor a Distributions section".to_string(),
+        ));
+    }
 
     let mut entries = Vec::new();
-    for (flavor, value) in &root.modern_distributions {
-        let arr: Vec<ModernDistributionEntry> =
-            serde_json::from_value(value.clone()).map_err(|e| {
-                WslError::ParseError(format!(
-                    "Manifest flavor '{}' is not a list of distributions: {}",
-                    flavor, e
-                ))
-            })?;
+    let mut warnings = Vec::new();
+    let mut seen_names = std::collections::HashSet::new();
+    for (flavor, value) in root.modern_distributions.unwrap_or_default() {
+        let arr: Vec<ModernDistributionEntry> = serde_json::from_value(value).map_err(|e| {
+            WslError::ParseError(format!(
+                "Manifest flavor '{}' is not a list of distributions: {}",
+                flavor, e
+            ))
+        })?;
+        let mut defaults = 0;
         for entry in arr {
-            if entry.name.trim().is_empty() {
+            let name = entry.name.trim().to_string();
+            if name.is_empty() {
                 return Err(WslError::ParseError(format!(
                     "Manifest flavor '{}' has an entry with an empty Name",
                     flavor
                 )));
             }
-            let has_amd64 = entry
-                .amd64_url
-                .as_ref()
-                .and_then(|u| u.url.as_deref())
-                .map(|s| !s.trim().is_empty())
-                .unwrap_or(false);
-            let has_arm64 = entry
-                .arm64_url
-                .as_ref()
-                .and_then(|u| u.url.as_deref())
-                .map(|s| !s.trim().is_empty())
-                .unwrap_or(false);
+            if !seen_names.insert(name.to_lowercase()) {
+                warnings.push(ManifestWarning {
+                    kind: ManifestWarningKind::DuplicateName,
+                    flavor: Some(flavor.clone()),
+                    name: Some(name.clone()),
+                    arch: None,
+                    message: format!("'{}' is listed more than once", name),
+                });
+            }
+            if entry.default {
+                defaults += 1;
+            }
+            let (amd64_url, amd64_sha256) =
+                parse_modern_url(entry.amd64_url, DistroArch::Amd64, &flavor, &name, &mut warnings);
+            let (arm64_url, arm64_sha256) =
+                parse_modern_url(entry.arm64_url, DistroArch::Arm64, &flavor, &name, &mut warnings);
             entries.push(ManifestEntryPreview {
                 flavor: flavor.clone(),
-                friendly_name: entry.friendly_name.unwrap_or_else(|| entry.name.clone()),
-                name: entry.name,
+                friendly_name: non_empty(entry.friendly_name).unwrap_or_else(|| name.clone()),
+                name,
                 default: entry.default,
-                has_amd64,
-                has_arm64,
+                has_amd64: amd64_url.is_some(),
+                has_arm64: arm64_url.is_some(),
+                amd64_url,
+                amd64_sha256,
+                arm64_url,
+                arm64_sha256,
+            });
+        }
+        if defaults > 1 {
+            warnings.push(ManifestWarning {
+                kind: ManifestWarningKind::MultipleDefaults,
+                flavor: Some(flavor.clone()),
+                name: None,
+                arch: None,
+                message: format!("Flavor '{}' has {} default distributions", flavor, defaults),
             });
         }
     }
 
+    let mut legacy_distributions = Vec::new();
+    let mut seen_legacy = std::collections::HashSet::new();
+    for entry in root.distributions.unwrap_or_default() {
+        let name = entry.name.trim().to_string();
+        if name.is_empty() {
+            return Err(WslError::ParseError(
+                "Manifest Distributions list has an entry with an empty Name".to_string(),
+            ));
+        }
+        if !seen_legacy.insert(name.to_lowercase()) {
+            warnings.push(ManifestWarning {
+                kind: ManifestWarningKind::DuplicateName,
+                flavor: None,
+                name: Some(name.clone()),
+                arch: None,
+                message: format!("'{}' is listed more than once in Distributions", name),
+            });
+        }
+        legacy_distributions.push(LegacyDistributionPreview {
+            friendly_name: non_empty(entry.friendly_name).unwrap_or_else(|| name.clone()),
+            name,
+            store_app_id: non_empty(entry.store_app_id),
+            package_family_name: non_empty(entry.package_family_name),
+            amd64: entry.amd64,
+            arm64: entry.arm64,
+            amd64_package_url: non_empty(entry.amd64_package_url),
+            arm64_package_url: non_empty(entry.arm64_package_url),
+        });
+    }
+
     Ok(ManifestPreview {
         url: url.to_string(),
         entries,
+        legacy_distributions,
+        warnings,
     })
 }
 
@@ -459,6 +666,7 @@ pub fn fetch_and_preview(url: &str) -> Result<ManifestPreview, WslError> {
 
 #[cfg(any(not(target_os = "windows"), test))]
 fn mock_preview(url: &str) -> ManifestPreview {
+    let hash = |c: char| Some(c.to_string().repeat(64));
     ManifestPreview {
         url: url.to_string(),
         entries: vec![
@@ -469,6 +677,10 @@ fn mock_preview(url: &str) -> ManifestPreview {
                 default: true,
                 has_amd64: true,
                 has_arm64: true,
+                amd64_url: Some("https://example.test/ubuntu-26.04-amd64.wsl".to_string()),
+                amd64_sha256: hash('a'),
+                arm64_url: Some("https://example.test/ubuntu-26.04-arm64.wsl".to_string()),
+                arm64_sha256: hash('b'),
             },
             ManifestEntryPreview {
                 flavor: "Rocky".to_string(),
@@ -477,8 +689,20 @@ fn mock_preview(url: &str) -> ManifestPreview {
                 default: false,
                 has_amd64: true,
                 has_arm64: false,
+                amd64_url: Some("https://example.test/rocky-10.1-amd64.wsl".to_string()),
+                amd64_sha256: None,
+                arm64_url: None,
+                arm64_sha256: None,
             },
         ],
+        legacy_distributions: Vec::new(),
+        warnings: vec![ManifestWarning {
+            kind: ManifestWarningKind::MissingHash,
+            flavor: Some("Rocky".to_string()),
+            name: Some("Rocky-10.1".to_string()),
+            arch: Some(DistroArch::Amd64),
+            message: "Rocky-10.1 (Amd64) has no Sha256 hash".to_string(),
+        }],
     }
 }
 
@@ -487,9 +711,102 @@ fn mock_preview(url: &str) -> ManifestPreview {
     ManifestPreview {
         url: url.to_string(),
         entries: Vec::new(),
+        legacy_distributions: Vec::new(),
+        warnings: Vec::new(),
     }
 }
 
+// ---------------------------------------------------------------------------
+// Direct install from a manifest entry
+// ---------------------------------------------------------------------------
+
+/// A verified download picked from a manifest entry for one architecture.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestDownload {
+    pub url: String,
+    /// Expected lowercase hex SHA256
+    pub sha256: String,
+    pub arch: DistroArch,
+}
+
+/// Pick the download for `arch` from the `flavor`/`name` entry of a parsed
+/// manifest. Entries without a valid `Sha256` for that architecture are
+/// refused so a direct install is never unverified.
+pub fn select_manifest_download(
+    preview: &ManifestPreview,
+    flavor: &str,
+    name: &str,
+    arch: Option<DistroArch>,
+) -> Result<ManifestDownload, WslError> {
+    let entry = preview
+        .entries
+        .iter()
+        .find(|e| e.flavor == flavor && e.name == name)
+        .ok_or_else(|| {
+            WslError::DistroNotFound(format!("{} ({}) in {}", name, flavor, preview.url))
+        })?;
+    let arch = arch.ok_or_else(|| {
+        WslError::CommandFailed("Unsupported host architecture".to_string())
+    })?;
+    let (url, sha256) = match arch {
+        DistroArch::Amd64 => (&entry.amd64_url, &entry.amd64_sha256),
+        DistroArch::Arm64 => (&entry.arm64_url, &entry.arm64_sha256),
+    };
+    let url = url.clone().ok_or_else(|| {
+        WslError::CommandFailed(format!("{} has no {:?} download", name, arch))
+    })?;
+    let sha256 = sha256.clone().ok_or_else(|| {
+        WslError::CommandFailed(format!(
+            "{} has no valid Sha256 for {:?}; refusing to install an unverified download",
+            name, arch
+        ))
+    })?;
+    Ok(ManifestDownload { url, sha256, arch })
+}
+
+/// Fetch a manifest and resolve the host-architecture download of one entry.
+/// Nothing is written to the registry.
+pub fn resolve_manifest_download(
+    manifest_url: &str,
+    flavor: &str,
+    name: &str,
+) -> Result<ManifestDownload, WslError> {
+    let preview = fetch_and_preview(manifest_url)?;
+    select_manifest_download(&preview, flavor, name, DistroArch::host())
+}
+
+/// Resolve a `file://` download to the local tarball it names, checking it
+/// against `sha256` so it can be imported in place rather than fetched over
+/// HTTP. Returns `None` for any other scheme.
+pub fn verified_local_tarball(
+    url: &str,
+    sha256: Option<&str>,
+) -> Result<Option<std::path::PathBuf>, WslError> {
+    let url = url.trim();
+    if !url.to_ascii_lowercase().starts_with("file://") {
+        return Ok(None);
+    }
+    let path = crate::distro_catalog::file_url_to_path(&url["file://".len()..]);
+    if !path.is_file() {
+        return Err(WslError::CommandFailed(format!(
+            "Tarball {} is not accessible",
+            path.display()
+        )));
+    }
+    if let Some(expected) = sha256 {
+        let actual = sha256_file(&path)?;
+        if !actual.eq_ignore_ascii_case(expected.trim().trim_start_matches("0x")) {
+            return Err(WslError::CommandFailed(format!(
+                "Checksum mismatch for {}: expected {}, got {}",
+                path.display(),
+                expected,
+                actual
+            )));
+        }
+    }
+    Ok(Some(path))
+}
+
 // Cap manifest fetches at 10 MB so a malicious or misconfigured server
 // cannot exhaust process memory with a multi-gigabyte body.
 const MAX_MANIFEST_BYTES: usize = 10 * 1024 * 1024;
@@ -510,10 +827,8 @@ fn fetch_url_body(url: &str) -> Result<String, WslError> {
             .map_err(|e| WslError::CommandFailed(format!("Failed to read manifest file: {}", e)));
     }
 
-    let client = reqwest::blocking::Client::builder()
-        .timeout(std::time::Duration::from_secs(15))
-        .build()
-        .map_err(|e| WslError::CommandFailed(format!("Failed to build HTTP client: {}", e)))?;
+    let client = crate::http_client::blocking_client(Some(std::time::Duration::from_secs(15)))
+        .map_err(|e| WslError::CommandFailed(e.to_string()))?;
 
     let resp = client
         .get(url)
@@ -552,6 +867,314 @@ fn fetch_url_body(url: &str) -> Result<String, WslError> {
         .map_err(|e| WslError::CommandFailed(format!("Manifest body is not valid UTF-8: {}", e)))
 }
 
+// ---------------------------------------------------------------------------
+// Manifest generation
+// ---------------------------------------------------------------------------
+
+/// Where the downloads for a generated manifest entry come from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ManifestEntrySource {
+    /// A download distro from the app catalog. Its per-architecture URLs and
+    /// published checksums are used; missing checksums are computed by
+    /// streaming the download.
+    #[serde(rename_all = "camelCase")]
+    Catalog { distro_id: String },
+    /// Local tarballs, referenced as `file://` URLs and hashed on disk.
+    #[serde(rename_all = "camelCase")]
+    LocalFiles {
+        #[serde(default)]
+        amd64_path: Option<String>,
+        #[serde(default)]
+        arm64_path: Option<String>,
+    },
+}
+
+/// One distribution to include in a generated manifest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestEntryRequest {
+    /// Flavor key under `ModernDistributions` (e.g. "Ubuntu")
+    pub flavor: String,
+    /// Version name passed to `wsl --install` (e.g. "Ubuntu-26.04")
+    pub name: String,
+    /// Friendly display name; falls back to the catalog name when omitted.
+    #[serde(default)]
+    pub friendly_name: Option<String>,
+    /// Whether this entry is the default for its flavor.
+    #[serde(default)]
+    pub default: bool,
+    pub source: ManifestEntrySource,
+}
+
+/// Result of writing a generated manifest to disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratedManifest {
+    /// Path the manifest was written to.
+    pub path: String,
+    /// `file://` URL that can be applied as a distribution source.
+    pub url: String,
+    /// Preview of the written file, produced by `parse_manifest`.
+    pub preview: ManifestPreview,
+}
+
+/// Format a hex digest the way `ModernDistributions` manifests expect.
+fn manifest_sha256(hex: &str) -> String {
+    format!("0x{}", hex.trim().trim_start_matches("0x").to_lowercase())
+}
+
+/// Compute the lowercase hex SHA256 of everything readable from `reader`.
+pub(crate) fn sha256_reader<R: std::io::Read>(mut reader: R) -> std::io::Result<String> {
+    use sha2::{Digest, Sha256};
+
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn sha256_file(path: &std::path::Path) -> Result<String, WslError> {
+    let file = std::fs::File::open(path).map_err(|e| {
+        WslError::CommandFailed(format!("Failed to open {}: {}", path.display(), e))
+    })?;
+    sha256_reader(std::io::BufReader::new(file)).map_err(|e| {
+        WslError::CommandFailed(format!("Failed to hash {}: {}", path.display(), e))
+    })
+}
+
+/// Stream a remote download through the hasher without writing it to disk.
+fn sha256_remote(url: &str) -> Result<String, WslError> {
+    let client = crate::http_client::blocking_client(None)
+        .map_err(|e| WslError::CommandFailed(e.to_string()))?;
+    let resp = client
+        .get(url)
+        .send()
+        .map_err(|e| WslError::CommandFailed(format!("Failed to download {}: {}", url, e)))?;
+    if !resp.status().is_success() {
+        return Err(WslError::CommandFailed(format!(
+            "Download of {} returned HTTP {}",
+            url,
+            resp.status()
+        )));
+    }
+    sha256_reader(resp)
+        .map_err(|e| WslError::CommandFailed(format!("Failed to hash {}: {}", url, e)))
+}
+
+/// Build a `file://` URL for a local path (`C:\x\y` -> `file:///C:/x/y`).
+fn path_to_file_url(path: &std::path::Path) -> String {
+    let s = path.to_string_lossy().replace('\\', "/");
+    if let Some(unc) = s.strip_prefix("//") {
+        format!("file:////{}", unc)
+    } else if s.starts_with('/') {
+        format!("file://{}", s)
+    } else {
+        format!("file:///{}", s)
+    }
+}
+
+/// Strip the verbatim `\\?\` prefix that canonicalize() yields on Windows,
+/// turning `\\?\UNC\server\share` back into `\\server\share`, so URLs
+/// built from the path stay readable by WSL.
+fn strip_verbatim_prefix(path: &std::path::Path) -> std::path::PathBuf {
+    let display = path.to_string_lossy();
+    match display.strip_prefix(r"\\?\UNC\") {
+        Some(unc) => std::path::PathBuf::from(format!(r"\\{}", unc)),
+        None => std::path::PathBuf::from(display.strip_prefix(r"\\?\").unwrap_or(&display)),
+    }
+}
+
+fn local_download(path: &str) -> Result<ModernUrl, WslError> {
+    let path = std::path::Path::new(path.trim());
+    let abs = std::fs::canonicalize(path).map_err(|e| {
+        WslError::CommandFailed(format!("Tarball {} is not accessible: {}", path.display(), e))
+    })?;
+    if !abs.is_file() {
+        return Err(WslError::CommandFailed(format!(
+            "Tarball {} is not a file",
+            path.display()
+        )));
+    }
+    let clean = strip_verbatim_prefix(&abs);
+    Ok(ModernUrl {
+        url: Some(path_to_file_url(&clean)),
+        sha256: Some(manifest_sha256(&sha256_file(&clean)?)),
+    })
+}
+
+fn catalog_download<F>(
+    download: crate::distro_catalog::ArchDownload,
+    hash_remote: &F,
+) -> Result<ModernUrl, WslError>
+where
+    F: Fn(&str) -> Result<String, WslError>,
+{
+    let sha256 = match download.sha256 {
+        Some(hex) => hex,
+        None => hash_remote(&download.url)?,
+    };
+    Ok(ModernUrl {
+        url: Some(download.url),
+        sha256: Some(manifest_sha256(&sha256)),
+    })
+}
+
+/// Resolve the requested entries into a `ModernDistributions` manifest.
+///
+/// Names must be unique across the manifest, each flavor may have at most one
+/// default, and every entry needs at least one architecture. Catalog entries
+/// without an architecture map only publish their legacy URL as `Amd64Url`.
+fn build_manifest_with<F>(
+    requests: &[ManifestEntryRequest],
+    catalog: &crate::distro_catalog::DistroCatalog,
+    hash_remote: F,
+) -> Result<String, WslError>
+where
+    F: Fn(&str) -> Result<String, WslError>,
+{
+    use crate::distro_catalog::DistroArch;
+    use std::collections::{BTreeMap, HashSet};
+
+    if requests.is_empty() {
+        return Err(WslError::ParseError(
+            "A manifest needs at least one distribution".to_string(),
+        ));
+    }
+
+    let mut flavors: BTreeMap<String, Vec<ModernDistributionEntry>> = BTreeMap::new();
+    let mut names = HashSet::new();
+    for req in requests {
+        let flavor = req.flavor.trim();
+        let name = req.name.trim();
+        if flavor.is_empty() || name.is_empty() {
+            return Err(WslError::ParseError(
+                "Manifest entries need a flavor and a name".to_string(),
+            ));
+        }
+        if !names.insert(name.to_lowercase()) {
+            return Err(WslError::ParseError(format!(
+                "Duplicate distribution name '{}'",
+                name
+            )));
+        }
+
+        let (friendly_name, amd64_url, arm64_url) = match &req.source {
+            ManifestEntrySource::Catalog { distro_id } => {
+                let distro = catalog
+                    .download_distros
+                    .iter()
+                    .find(|d| d.id == *distro_id)
+                    .ok_or_else(|| WslError::DistroNotFound(distro_id.clone()))?;
+                let (amd64, arm64) = if distro.architectures.is_empty() {
+                    (distro.download_for(None), None)
+                } else {
+                    (
+                        distro.architectures.get(&DistroArch::Amd64).cloned(),
+                        distro.architectures.get(&DistroArch::Arm64).cloned(),
+                    )
+                };
+                (
+                    distro.name.clone(),
+                    amd64.map(|d| catalog_download(d, &hash_remote)).transpose()?,
+                    arm64.map(|d| catalog_download(d, &hash_remote)).transpose()?,
+                )
+            }
+            ManifestEntrySource::LocalFiles {
+                amd64_path,
+                arm64_path,
+            } => {
+                let non_empty = |p: &Option<String>| {
+                    p.as_deref().filter(|s| !s.trim().is_empty()).map(str::to_string)
+                };
+                (
+                    name.to_string(),
+                    non_empty(amd64_path).map(|p| local_download(&p)).transpose()?,
+                    non_empty(arm64_path).map(|p| local_download(&p)).transpose()?,
+                )
+            }
+        };
+
+        if amd64_url.is_none() && arm64_url.is_none() {
+            return Err(WslError::ParseError(format!(
+                "'{}' has no Amd64 or Arm64 download",
+                name
+            )));
+        }
+
+        let entries = flavors.entry(flavor.to_string()).or_default();
+        if req.default && entries.iter().any(|e| e.default) {
+            return Err(WslError::ParseError(format!(
+                "Flavor '{}' has more than one default distribution",
+                flavor
+            )));
+        }
+        entries.push(ModernDistributionEntry {
+            name: name.to_string(),
+            friendly_name: Some(
+                req.friendly_name
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(str::to_string)
+                    .unwrap_or(friendly_name),
+            ),
+            default: req.default,
+            amd64_url,
+            arm64_url,
+        });
+    }
+
+    let root = serde_json::json!({ "ModernDistributions": flavors });
+    serde_json::to_string_pretty(&root)
+        .map_err(|e| WslError::ParseError(format!("Failed to serialize manifest: {}", e)))
+}
+
+/// Build a manifest from catalog entries and/or local tarballs.
+pub fn build_manifest(
+    requests: &[ManifestEntryRequest],
+    catalog: &crate::distro_catalog::DistroCatalog,
+) -> Result<String, WslError> {
+    build_manifest_with(requests, catalog, sha256_remote)
+}
+
+/// Generate a manifest, write it to `output_path`, and read it back through
+/// `parse_manifest` so a file that WSL would reject is never reported as
+/// success.
+pub fn generate_manifest(
+    requests: &[ManifestEntryRequest],
+    catalog: &crate::distro_catalog::DistroCatalog,
+    output_path: &str,
+) -> Result<GeneratedManifest, WslError> {
+    let body = build_manifest(requests, catalog)?;
+    write_manifest(&body, output_path)
+}
+
+fn write_manifest(body: &str, output_path: &str) -> Result<GeneratedManifest, WslError> {
+    let path = std::path::Path::new(output_path.trim());
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, body)?;
+
+    let written = std::fs::read_to_string(path)?;
+    let clean = strip_verbatim_prefix(&std::fs::canonicalize(path)?);
+    let url = path_to_file_url(&clean);
+    let preview = parse_manifest(&url, &written)?;
+
+    Ok(GeneratedManifest {
+        path: clean.to_string_lossy().to_string(),
+        url,
+        preview,
+    })
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -567,7 +1190,7 @@ mod tests {
                     "Name": "Ubuntu-26.04",
                     "FriendlyName": "Ubuntu 26.04 LTS",
                     "Default": true,
-                    "Amd64Url": { "Url": "https://example.test/ubuntu-26.04-amd64.wsl", "Sha256": "0xdead" },
+                    "Amd64Url": { "Url": "https://example.test/ubuntu-26.04-amd64.wsl", "Sha256": "0xDEADBEEFDEADBEEFDEADBEEFDEADBEEFDEADBEEFDEADBEEFDEADBEEFDEADBEEF" },
                     "Arm64Url": { "Url": "https://example.test/ubuntu-26.04-arm64.wsl", "Sha256": "0xbeef" }
                 },
                 {
@@ -610,10 +1233,92 @@ mod tests {
         assert!(rocky.has_amd64);
         assert!(!rocky.has_arm64);
         assert!(!rocky.default);
+
+        assert_eq!(
+            ubuntu_26.amd64_sha256.as_deref(),
+            Some("deadbeef".repeat(8).as_str())
+        );
+        assert_eq!(ubuntu_26.arm64_sha256, None);
+        let kinds: Vec<_> = p.warnings.iter().map(|w| w.kind).collect();
+        assert!(kinds.contains(&ManifestWarningKind::InvalidHash));
+        assert!(!kinds.contains(&ManifestWarningKind::MultipleDefaults));
     }
 
     #[test]
-    fn rejects_missing_modern_distributions_root() {
+    fn parses_legacy_distributions_section() {
+        let body = r#"{
+            "Distributions": [
+                {
+                    "Name": "Ubuntu",
+                    "FriendlyName": "Ubuntu",
+                    "StoreAppId": "9PDXGNCFSCZV",
+                    "Amd64": true,
+                    "Arm64": true,
+                    "Amd64PackageUrl": "https://example.test/ubuntu.appxbundle",
+                    "Arm64PackageUrl": "https://example.test/ubuntu.appxbundle",
+                    "PackageFamilyName": "CanonicalGroupLimited.Ubuntu_79rhkp1fndgsc"
+                },
+                { "Name": "ubuntu", "Amd64": true }
+            ]
+        }"#;
+        let p = parse_manifest("u", body).unwrap();
+        assert!(p.entries.is_empty());
+        assert_eq!(p.legacy_distributions.len(), 2);
+        let ubuntu = &p.legacy_distributions[0];
+        assert_eq!(ubuntu.store_app_id.as_deref(), Some("9PDXGNCFSCZV"));
+        assert_eq!(
+            ubuntu.package_family_name.as_deref(),
+            Some("CanonicalGroupLimited.Ubuntu_79rhkp1fndgsc")
+        );
+        assert!(ubuntu.amd64 && ubuntu.arm64);
+        assert_eq!(p.legacy_distributions[1].friendly_name, "ubuntu");
+        assert_eq!(p.warnings.len(), 1);
+        assert_eq!(p.warnings[0].kind, ManifestWarningKind::DuplicateName);
+    }
+
+    #[test]
+    fn warns_on_missing_hash_duplicates_and_multiple_defaults() {
+        let body = r#"{"ModernDistributions":{
+            "Debian":[
+                {"Name":"Debian-13","Default":true,"Amd64Url":{"Url":"https://example.test/a.wsl"}},
+                {"Name":"debian-13","Default":true,"Arm64Url":{"Url":"https://example.test/b.wsl","Sha256":"  "}}
+            ]
+        }}"#;
+        let p = parse_manifest("u", body).unwrap();
+        let count = |kind| p.warnings.iter().filter(|w| w.kind == kind).count();
+        assert_eq!(count(ManifestWarningKind::MissingHash), 2);
+        assert_eq!(count(ManifestWarningKind::DuplicateName), 1);
+        assert_eq!(count(ManifestWarningKind::MultipleDefaults), 1);
+        let missing = p
+            .warnings
+            .iter()
+            .find(|w| w.kind == ManifestWarningKind::MissingHash)
+            .unwrap();
+        assert_eq!(missing.arch, Some(DistroArch::Amd64));
+        assert_eq!(missing.flavor.as_deref(), Some("Debian"));
+    }
+
+    #[test]
+    fn select_manifest_download_picks_verified_arch() {
+        let p = parse_manifest("https://example.test/m.json", SAMPLE_MANIFEST).unwrap();
+
+        let amd64 =
+            select_manifest_download(&p, "Ubuntu", "Ubuntu-26.04", Some(DistroArch::Amd64)).unwrap();
+        assert_eq!(amd64.url, "https://example.test/ubuntu-26.04-amd64.wsl");
+        assert_eq!(amd64.sha256, "deadbeef".repeat(8));
+
+        // Arm64 hash is malformed in the sample, so it is refused.
+        assert!(select_manifest_download(&p, "Ubuntu", "Ubuntu-26.04", Some(DistroArch::Arm64)).is_err());
+        assert!(select_manifest_download(&p, "Rocky", "Rocky-10.1", Some(DistroArch::Arm64)).is_err());
+        assert!(select_manifest_download(&p, "Ubuntu", "Ubuntu-26.04", None).is_err());
+        assert!(matches!(
+            select_manifest_download(&p, "Rocky", "Ubuntu-26.04", Some(DistroArch::Amd64)),
+            Err(WslError::DistroNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_manifest_without_any_distribution_section() {
         let err = parse_manifest("u", r#"{"Something": []}"#).unwrap_err();
         assert!(matches!(err, WslError::ParseError(_)));
     }
@@ -698,4 +1403,266 @@ mod tests {
             VALUE_DISTRIBUTION_LIST_URL
         );
     }
+
+    fn temp_dir(tag: &str) -> std::path::PathBuf {
+        crate::utils::unique_temp_dir(&format!("manifest-{}", tag))
+    }
+
+    fn catalog_with_download(
+        id: &str,
+        architectures: &[(crate::distro_catalog::DistroArch, &str, Option<String>)],
+    ) -> crate::distro_catalog::DistroCatalog {
+        use crate::distro_catalog::{ArchDownload, DistroCatalog, DownloadDistro};
+
+        let mut distro = DownloadDistro {
+            id: id.to_string(),
+            name: format!("{} (catalog)", id),
+            description: String::new(),
+            url: "https://example.test/legacy.tar.gz".to_string(),
+            size: None,
+            sha256: None,
+            architectures: Default::default(),
+            enabled: true,
+            is_built_in: false,
+            subscription_id: None,
+            has_local_override: false,
+            available: true,
+        };
+        for (arch, url, sha) in architectures {
+            distro.architectures.insert(
+                *arch,
+                ArchDownload {
+                    url: url.to_string(),
+                    sha256: sha.clone(),
+                },
+            );
+        }
+        DistroCatalog {
+            download_distros: vec![distro],
+            ..Default::default()
+        }
+    }
+
+    fn request(flavor: &str, name: &str, source: ManifestEntrySource) -> ManifestEntryRequest {
+        ManifestEntryRequest {
+            flavor: flavor.to_string(),
+            name: name.to_string(),
+            friendly_name: None,
+            default: false,
+            source,
+        }
+    }
+
+    fn no_remote(url: &str) -> Result<String, WslError> {
+        panic!("unexpected remote hash of {}", url)
+    }
+
+    #[test]
+    fn generated_manifest_from_local_tarballs_round_trips() {
+        let dir = temp_dir("local");
+        let tarball = dir.join("rootfs.tar.gz");
+        std::fs::write(&tarball, b"hello").unwrap();
+
+        let mut req = request(
+            "Custom",
+            "Custom-1.0",
+            ManifestEntrySource::LocalFiles {
+                amd64_path: Some(tarball.to_string_lossy().to_string()),
+                arm64_path: None,
+            },
+        );
+        req.default = true;
+        req.friendly_name = Some("Custom Linux 1.0".to_string());
+
+        let out = dir.join("out").join("manifest.json");
+        let generated = generate_manifest(
+            &[req],
+            &Default::default(),
+            &out.to_string_lossy(),
+        )
+        .unwrap();
+
+        assert!(generated.url.starts_with("file://"));
+        assert_eq!(generated.preview.entries.len(), 1);
+        let entry = &generated.preview.entries[0];
+        assert_eq!(entry.flavor, "Custom");
+        assert_eq!(entry.friendly_name, "Custom Linux 1.0");
+        assert!(entry.default && entry.has_amd64 && !entry.has_arm64);
+        assert!(generated.preview.warnings.is_empty());
+
+        let body = std::fs::read_to_string(&out).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        let amd64 = &json["ModernDistributions"]["Custom"][0]["Amd64Url"];
+        assert_eq!(
+            amd64["Sha256"],
+            "0x2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+        assert!(amd64["Url"].as_str().unwrap().ends_with("/rootfs.tar.gz"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn local_entry_from_generated_manifest_resolves_to_verified_tarball() {
+        let dir = temp_dir("local-install");
+        let tarball = dir.join("rootfs.tar.gz");
+        std::fs::write(&tarball, b"hello").unwrap();
+
+        let req = request(
+            "Custom",
+            "Custom-1.0",
+            ManifestEntrySource::LocalFiles {
+                amd64_path: Some(tarball.to_string_lossy().to_string()),
+                arm64_path: None,
+            },
+        );
+        let out = dir.join("manifest.json");
+        let generated =
+            generate_manifest(&[req], &Default::default(), &out.to_string_lossy()).unwrap();
+
+        let download = select_manifest_download(
+            &generated.preview,
+            "Custom",
+            "Custom-1.0",
+            Some(DistroArch::Amd64),
+        )
+        .unwrap();
+        let local = verified_local_tarball(&download.url, Some(&download.sha256))
+            .unwrap()
+            .expect("file:// download should resolve to a local path");
+        assert_eq!(std::fs::read(&local).unwrap(), b"hello");
+
+        // A tarball changed after the manifest was generated is refused
+        std::fs::write(&tarball, b"tampered").unwrap();
+        assert!(verified_local_tarball(&download.url, Some(&download.sha256)).is_err());
+
+        assert_eq!(
+            verified_local_tarball("https://example.test/rootfs.tar.gz", None).unwrap(),
+            None
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn catalog_entries_use_published_checksums_per_arch() {
+        use crate::distro_catalog::DistroArch;
+
+        let catalog = catalog_with_download(
+            "ubuntu",
+            &[
+                (DistroArch::Amd64, "https://example.test/amd64.tar.gz", Some("A".repeat(64))),
+                (DistroArch::Arm64, "https://example.test/arm64.tar.gz", Some("b".repeat(64))),
+            ],
+        );
+        let body = build_manifest_with(
+            &[request(
+                "Ubuntu",
+                "Ubuntu-24.04",
+                ManifestEntrySource::Catalog {
+                    distro_id: "ubuntu".to_string(),
+                },
+            )],
+            &catalog,
+            no_remote,
+        )
+        .unwrap();
+
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        let entry = &json["ModernDistributions"]["Ubuntu"][0];
+        assert_eq!(entry["FriendlyName"], "ubuntu (catalog)");
+        assert_eq!(entry["Amd64Url"]["Sha256"], format!("0x{}", "a".repeat(64)));
+        assert_eq!(entry["Arm64Url"]["Url"], "https://example.test/arm64.tar.gz");
+        parse_manifest("u", &body).unwrap();
+    }
+
+    #[test]
+    fn catalog_entries_without_checksum_are_hashed() {
+        let catalog = catalog_with_download("legacy", &[]);
+        let body = build_manifest_with(
+            &[request(
+                "Legacy",
+                "Legacy-1",
+                ManifestEntrySource::Catalog {
+                    distro_id: "legacy".to_string(),
+                },
+            )],
+            &catalog,
+            |url| {
+                assert_eq!(url, "https://example.test/legacy.tar.gz");
+                Ok("c".repeat(64))
+            },
+        )
+        .unwrap();
+
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        let entry = &json["ModernDistributions"]["Legacy"][0];
+        assert_eq!(entry["Amd64Url"]["Sha256"], format!("0x{}", "c".repeat(64)));
+        assert!(entry.get("Arm64Url").is_none());
+    }
+
+    #[test]
+    fn build_manifest_rejects_invalid_requests() {
+        let catalog = catalog_with_download("legacy", &[]);
+        let source = || ManifestEntrySource::Catalog {
+            distro_id: "legacy".to_string(),
+        };
+        let hash = |_: &str| Ok("c".repeat(64));
+
+        assert!(build_manifest_with(&[], &catalog, hash).is_err());
+
+        let dupes = [request("A", "Same", source()), request("B", "same", source())];
+        assert!(build_manifest_with(&dupes, &catalog, hash).is_err());
+
+        let mut first = request("A", "One", source());
+        let mut second = request("A", "Two", source());
+        first.default = true;
+        second.default = true;
+        assert!(build_manifest_with(&[first, second], &catalog, hash).is_err());
+
+        let missing = request(
+            "A",
+            "Missing",
+            ManifestEntrySource::Catalog {
+                distro_id: "nope".to_string(),
+            },
+        );
+        assert!(matches!(
+            build_manifest_with(&[missing], &catalog, hash),
+            Err(WslError::DistroNotFound(_))
+        ));
+
+        let empty = request(
+            "A",
+            "Empty",
+            ManifestEntrySource::LocalFiles {
+                amd64_path: None,
+                arm64_path: Some("  ".to_string()),
+            },
+        );
+        assert!(build_manifest_with(&[empty], &catalog, hash).is_err());
+    }
+
+    #[test]
+    fn path_to_file_url_handles_drive_unc_and_unix_paths() {
+        use std::path::Path;
+        assert_eq!(path_to_file_url(Path::new(r"C:\wsl\m.json")), "file:///C:/wsl/m.json");
+        assert_eq!(
+            path_to_file_url(Path::new(r"\\server\share\m.json")),
+            "file:////server/share/m.json"
+        );
+        assert_eq!(path_to_file_url(Path::new("/tmp/m.json")), "file:///tmp/m.json");
+    }
+
+    #[test]
+    fn verbatim_prefix_is_stripped_from_drive_and_unc_paths() {
+        use std::path::Path;
+        let unc = strip_verbatim_prefix(Path::new(r"\\?\UNC\server\share\m.json"));
+        assert_eq!(unc, Path::new(r"\\server\share\m.json"));
+        assert_eq!(path_to_file_url(&unc), "file:////server/share/m.json");
+
+        let drive = strip_verbatim_prefix(Path::new(r"\\?\C:\wsl\m.json"));
+        assert_eq!(path_to_file_url(&drive), "file:///C:/wsl/m.json");
+        assert_eq!(strip_verbatim_prefix(Path::new("/tmp/m.json")), Path::new("/tmp/m.json"));
+    }
 }
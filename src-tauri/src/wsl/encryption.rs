@@ -0,0 +1,336 @@
+//! Encrypted export archives
+//!
+//! Exports can be encrypted with a passphrase or to an X25519 recipient key
+//! (`age1...`). Archives use the standard age format, binary or ASCII-armored,
+//! so they can also be decrypted with the `age` command-line tool.
+//!
+//! Encryption and decryption are streamed: `wsl --export` writes into the
+//! encryptor and `wsl --import` reads from the decryptor, so the plaintext tar
+//! never touches the disk. The passphrase or key is checked against the
+//! archive header before anything is handed to `wsl --import`.
+
+use super::types::WslError;
+use age::armor::ArmoredReader;
+use age::secrecy::{ExposeSecret, SecretString};
+use age::stream::StreamWriter;
+use age::{DecryptError, Decryptor, Encryptor};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+/// File extension of encrypted exports
+pub const FILE_EXTENSION: &str = "age";
+
+/// Shortest passphrase accepted for new archives
+const MIN_PASSPHRASE_LEN: usize = 8;
+
+const BINARY_MAGIC: &[u8] = b"age-encryption.org/v1";
+const ARMOR_MAGIC: &[u8] = b"-----BEGIN AGE ENCRYPTED FILE-----";
+
+/// How to encrypt an export. Not `Debug`, so secrets cannot end up in logs.
+#[derive(Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum EncryptionKey {
+    Passphrase {
+        passphrase: String,
+    },
+    /// Public key of the recipient, `age1...`
+    #[serde(rename_all = "camelCase")]
+    Recipient {
+        public_key: String,
+    },
+}
+
+/// How to decrypt an archive
+#[derive(Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum DecryptionKey {
+    Passphrase {
+        passphrase: String,
+    },
+    /// Secret key, `AGE-SECRET-KEY-1...`, or the contents of an age identity file
+    #[serde(rename_all = "camelCase")]
+    Identity {
+        secret_key: String,
+    },
+}
+
+/// What an encrypted archive needs to be opened
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EncryptionKind {
+    Passphrase,
+    Recipient,
+}
+
+/// A freshly generated recipient key pair
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyPair {
+    pub public_key: String,
+    pub secret_key: String,
+}
+
+/// Generate a key pair; exports are encrypted to the public key and opened
+/// with the secret key
+pub fn generate_key_pair() -> KeyPair {
+    let identity = age::x25519::Identity::generate();
+    KeyPair {
+        public_key: identity.to_public().to_string(),
+        secret_key: identity.to_string().expose_secret().to_string(),
+    }
+}
+
+fn parse_recipient(public_key: &str) -> Result<age::x25519::Recipient, WslError> {
+    age::x25519::Recipient::from_str(public_key.trim()).map_err(|_| {
+        WslError::CommandFailed(format!(
+            "'{}' is not a valid recipient key (expected age1...)",
+            public_key.trim()
+        ))
+    })
+}
+
+/// Check an encryption key before a long export starts
+pub fn validate_encryption_key(key: &EncryptionKey) -> Result<(), WslError> {
+    match key {
+        EncryptionKey::Passphrase { passphrase }
+            if passphrase.chars().count() < MIN_PASSPHRASE_LEN =>
+        {
+            Err(WslError::CommandFailed(format!(
+                "Passphrase must be at least {} characters",
+                MIN_PASSPHRASE_LEN
+            )))
+        }
+        EncryptionKey::Passphrase { .. } => Ok(()),
+        EncryptionKey::Recipient { public_key } => parse_recipient(public_key).map(|_| ()),
+    }
+}
+
+/// Wrap `output` so everything written to it is encrypted. The returned
+/// writer must be finished with [`StreamWriter::finish`].
+pub fn encrypt<W: Write>(key: &EncryptionKey, output: W) -> Result<StreamWriter<W>, WslError> {
+    validate_encryption_key(key)?;
+    let encryptor = match key {
+        EncryptionKey::Passphrase { passphrase } => {
+            Encryptor::with_user_passphrase(SecretString::from(passphrase.clone()))
+        }
+        EncryptionKey::Recipient { public_key } => {
+            let recipient = parse_recipient(public_key)?;
+            Encryptor::with_recipients(std::iter::once(&recipient as &dyn age::Recipient))
+                .map_err(|e| WslError::CommandFailed(format!("Failed to encrypt: {}", e)))?
+        }
+    };
+    Ok(encryptor.wrap_output(output)?)
+}
+
+/// Whether the start of a file is an age header
+fn has_age_magic(head: &[u8]) -> bool {
+    head.starts_with(BINARY_MAGIC) || head.starts_with(ARMOR_MAGIC)
+}
+
+/// Whether an archive is encrypted, and with what. `None` for plain archives.
+pub fn detect(path: &Path) -> Result<Option<EncryptionKind>, WslError> {
+    let mut reader = BufReader::new(std::fs::File::open(path)?);
+    if !has_age_magic(reader.fill_buf()?) {
+        return Ok(None);
+    }
+    let decryptor = Decryptor::new(ArmoredReader::new(reader)).map_err(|e| {
+        WslError::ParseError(format!(
+            "Unreadable encrypted archive {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    Ok(Some(if decryptor.is_scrypt() {
+        EncryptionKind::Passphrase
+    } else {
+        EncryptionKind::Recipient
+    }))
+}
+
+/// Open an encrypted archive. Fails here, before any plaintext is read, if
+/// the passphrase or key does not match; corruption later in the stream
+/// surfaces as an `InvalidData` read error.
+pub fn decrypt<R: Read>(key: &DecryptionKey, input: R) -> Result<impl Read, WslError> {
+    let decryptor = Decryptor::new(ArmoredReader::new(input))
+        .map_err(|e| WslError::ParseError(format!("Not a readable encrypted archive: {}", e)))?;
+    let result = match (key, decryptor.is_scrypt()) {
+        (DecryptionKey::Passphrase { passphrase }, true) => {
+            let identity = age::scrypt::Identity::new(SecretString::from(passphrase.clone()));
+            decryptor.decrypt(std::iter::once(&identity as &dyn age::Identity))
+        }
+        (DecryptionKey::Identity { secret_key }, false) => {
+            let identities = age::IdentityFile::from_buffer(secret_key.as_bytes())
+                .and_then(|file| {
+                    file.into_identities()
+                        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+                })
+                .map_err(|_| {
+                    WslError::CommandFailed(
+                        "Not a valid secret key (expected AGE-SECRET-KEY-1...)".to_string(),
+                    )
+                })?;
+            if identities.is_empty() {
+                return Err(WslError::CommandFailed(
+                    "No secret key found (expected AGE-SECRET-KEY-1...)".to_string(),
+                ));
+            }
+            decryptor.decrypt(identities.iter().map(|i| i.as_ref()))
+        }
+        (DecryptionKey::Passphrase { .. }, false) => {
+            return Err(WslError::CommandFailed(
+                "This archive is encrypted to a key, not a passphrase".to_string(),
+            ))
+        }
+        (DecryptionKey::Identity { .. }, true) => {
+            return Err(WslError::CommandFailed(
+                "This archive is encrypted with a passphrase, not a key".to_string(),
+            ))
+        }
+    };
+    let stream = result.map_err(|e| match (e, key) {
+        (DecryptError::DecryptionFailed, DecryptionKey::Passphrase { .. }) => {
+            WslError::CommandFailed("Wrong passphrase".to_string())
+        }
+        (DecryptError::NoMatchingKeys, _) => {
+            WslError::CommandFailed("The key does not match this archive".to_string())
+        }
+        (e, _) => WslError::CommandFailed(format!("Failed to decrypt: {}", e)),
+    })?;
+    Ok(DecryptedStream(stream))
+}
+
+/// Plaintext side of an encrypted archive. Read errors (a damaged or
+/// truncated archive) get a message that says so. The end of the stream is
+/// only reported once the final chunk authenticates, so a truncated archive
+/// fails before a reader such as `wsl --import` sees a clean end of file.
+struct DecryptedStream<R>(R);
+
+impl<R: Read> Read for DecryptedStream<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.0.read(buf).map_err(|e| {
+            if e.kind() == std::io::ErrorKind::Interrupted {
+                e
+            } else {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("Encrypted archive is damaged or truncated: {}", e),
+                )
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encrypt_bytes(key: &EncryptionKey, data: &[u8]) -> Vec<u8> {
+        let mut writer = encrypt(key, Vec::new()).unwrap();
+        writer.write_all(data).unwrap();
+        writer.finish().unwrap()
+    }
+
+    fn decrypt_bytes(key: &DecryptionKey, data: &[u8]) -> Result<Vec<u8>, WslError> {
+        let mut reader = decrypt(key, data)?;
+        let mut plain = Vec::new();
+        reader.read_to_end(&mut plain)?;
+        Ok(plain)
+    }
+
+    fn passphrase(p: &str) -> DecryptionKey {
+        DecryptionKey::Passphrase {
+            passphrase: p.to_string(),
+        }
+    }
+
+    #[test]
+    fn round_trips_with_a_recipient_key() {
+        let pair = generate_key_pair();
+        assert!(pair.public_key.starts_with("age1"));
+        assert!(pair.secret_key.starts_with("AGE-SECRET-KEY-1"));
+        let data = vec![42u8; 200_000];
+        let encrypted = encrypt_bytes(
+            &EncryptionKey::Recipient {
+                public_key: pair.public_key.clone(),
+            },
+            &data,
+        );
+        assert!(has_age_magic(&encrypted));
+
+        let identity = DecryptionKey::Identity {
+            secret_key: format!("# created: today\n{}\n", pair.secret_key),
+        };
+        assert_eq!(decrypt_bytes(&identity, &encrypted).unwrap(), data);
+
+        let other = DecryptionKey::Identity {
+            secret_key: generate_key_pair().secret_key,
+        };
+        let err = decrypt_bytes(&other, &encrypted).unwrap_err().to_string();
+        assert!(err.contains("does not match"), "{}", err);
+        let err = decrypt_bytes(&passphrase("hunter22"), &encrypted)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("not a passphrase"), "{}", err);
+    }
+
+    #[test]
+    fn round_trips_with_a_passphrase_and_detects_damage() {
+        let key = EncryptionKey::Passphrase {
+            passphrase: "correct horse".to_string(),
+        };
+        let data = b"distro tarball".repeat(10_000);
+        let encrypted = encrypt_bytes(&key, &data);
+        assert_eq!(
+            decrypt_bytes(&passphrase("correct horse"), &encrypted).unwrap(),
+            data
+        );
+        let err = decrypt_bytes(&passphrase("wrong horse"), &encrypted)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Wrong passphrase"), "{}", err);
+
+        // The key check passes, the damage is only found while streaming
+        let mut tampered = encrypted.clone();
+        let last = tampered.len() - 10;
+        tampered[last] ^= 1;
+        let err = decrypt_bytes(&passphrase("correct horse"), &tampered)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("damaged or truncated"), "{}", err);
+        let truncated = &encrypted[..encrypted.len() - 100];
+        assert!(decrypt_bytes(&passphrase("correct horse"), truncated).is_err());
+    }
+
+    #[test]
+    fn detects_encrypted_archives_and_validates_keys() {
+        let dir = crate::utils::unique_temp_dir("encryption");
+        let pair = generate_key_pair();
+        let encrypted = dir.join("Ubuntu.tar.age");
+        std::fs::write(
+            &encrypted,
+            encrypt_bytes(
+                &EncryptionKey::Recipient {
+                    public_key: pair.public_key,
+                },
+                b"tar",
+            ),
+        )
+        .unwrap();
+        let plain = dir.join("Ubuntu.tar");
+        std::fs::write(&plain, b"ustar").unwrap();
+        assert_eq!(detect(&encrypted).unwrap(), Some(EncryptionKind::Recipient));
+        assert_eq!(detect(&plain).unwrap(), None);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert!(validate_encryption_key(&EncryptionKey::Passphrase {
+            passphrase: "short".to_string()
+        })
+        .is_err());
+        assert!(validate_encryption_key(&EncryptionKey::Recipient {
+            public_key: "age1nope".to_string()
+        })
+        .is_err());
+    }
+}
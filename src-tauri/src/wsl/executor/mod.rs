@@ -155,6 +155,7 @@ pub fn set_mock_update_result(result: MockUpdateResult) {
 
 // 0 = unchecked, 1 = supported, 2 = not supported
 static DISTRIBUTION_ID_SUPPORT: AtomicU8 = AtomicU8::new(0);
+static VHD_SUPPORT: AtomicU8 = AtomicU8::new(0);
 
 /// Check whether the installed WSL version supports `--distribution-id`.
 /// This flag was introduced in WSL 2.4.4. On older versions, using it causes
@@ -165,7 +166,7 @@ pub fn supports_distribution_id() -> bool {
         return cached == 1;
     }
 
-    let supported = probe_distribution_id_support();
+    let supported = probe_wsl_version_gte(2, 4, 4);
     DISTRIBUTION_ID_SUPPORT.store(if supported { 1 } else { 2 }, Ordering::Relaxed);
 
     if !supported {
@@ -175,9 +176,32 @@ pub fn supports_distribution_id() -> bool {
     supported
 }
 
-/// Probe for --distribution-id support by checking the WSL version string.
-/// Returns true if WSL version >= 2.4.4.
-fn probe_distribution_id_support() -> bool {
+/// Check whether the installed WSL supports the VHD workflows used here:
+/// `--export --format vhd`, `--import --vhd` and `--import-in-place`.
+/// `--format` replaced the older `--vhd` export flag in WSL 2.4.4, so that is
+/// the minimum. Always true in mock mode. The result is cached after first probe.
+pub fn supports_vhd_workflows() -> bool {
+    if crate::utils::is_mock_mode() {
+        return true;
+    }
+    let cached = VHD_SUPPORT.load(Ordering::Relaxed);
+    if cached != 0 {
+        return cached == 1;
+    }
+
+    let supported = probe_wsl_version_gte(2, 4, 4);
+    VHD_SUPPORT.store(if supported { 1 } else { 2 }, Ordering::Relaxed);
+
+    if !supported {
+        log::info!("WSL does not support VHD import/export (version < 2.4.4)");
+    }
+
+    supported
+}
+
+/// Probe the WSL version string from `wsl --version`.
+/// Returns true if the WSL version is >= the given threshold.
+fn probe_wsl_version_gte(req_major: u32, req_minor: u32, req_patch: u32) -> bool {
     use crate::settings::get_executable_paths;
     use crate::utils::hidden_command;
     use std::process::Stdio;
@@ -203,7 +227,7 @@ fn probe_distribution_id_support() -> bool {
         if lower.starts_with("wsl version:") {
             if let Some(version_str) = lower.split(':').nth(1) {
                 let version_str = version_str.trim();
-                return is_version_gte(version_str, 2, 4, 4);
+                return is_version_gte(version_str, req_major, req_minor, req_patch);
             }
         }
     }
@@ -4,6 +4,7 @@
 //! allowing parsing logic to be tested.
 
 use std::collections::HashMap;
+use std::io::{Read, Write};
 use std::sync::Mutex;
 use std::time::Duration;
 use log::debug;
@@ -512,6 +513,35 @@ openSUSE-Tumbleweed                    openSUSE Tumbleweed
         })
     }
 
+    fn import_vhd(&self, name: &str, location: &str, vhd: &str, version: Option<u8>) -> Result<CommandOutput, WslError> {
+        if let Some(err) = self.check_error("import_vhd") {
+            return Err(err);
+        }
+        self.import(name, location, vhd, version)
+    }
+
+    fn import_in_place(&self, name: &str, _vhd: &str) -> Result<CommandOutput, WslError> {
+        if let Some(err) = self.check_error("import_in_place") {
+            return Err(err);
+        }
+        debug!("Mock: import-in-place name='{}'", name);
+        self.simulate_delay(500);
+
+        let mut state = self.state.lock().unwrap();
+        state.distributions.push(MockDistro {
+            name: name.to_string(),
+            state: MockDistroState::Stopped,
+            version: 2,
+            is_default: false,
+        });
+
+        Ok(CommandOutput {
+            stdout: String::new(),
+            stderr: String::new(),
+            success: true,
+        })
+    }
+
     fn export(&self, distro: &str, _file: &str, _format: Option<&str>) -> Result<CommandOutput, WslError> {
         if let Some(err) = self.check_error("export") {
             return Err(err);
@@ -535,6 +565,36 @@ openSUSE-Tumbleweed                    openSUSE Tumbleweed
         }
     }
 
+    fn export_to_writer(&self, distro: &str, output: &mut dyn Write) -> Result<CommandOutput, WslError> {
+        if let Some(err) = self.check_error("export") {
+            return Err(err);
+        }
+        debug!("Mock: export distro='{}' to stream", distro);
+        self.simulate_delay(500);
+
+        if !self.state.lock().unwrap().distributions.iter().any(|d| d.name == distro) {
+            return Ok(CommandOutput {
+                stdout: String::new(),
+                stderr: "There is no distribution with the supplied name.\n".to_string(),
+                success: false,
+            });
+        }
+        // An empty tar archive: two zeroed 512-byte end-of-archive blocks
+        output.write_all(&[0u8; 1024])?;
+        Ok(CommandOutput {
+            stdout: String::new(),
+            stderr: String::new(),
+            success: true,
+        })
+    }
+
+    fn import_from_reader(&self, name: &str, location: &str, input: &mut dyn Read, version: Option<u8>) -> Result<CommandOutput, WslError> {
+        // Consume the whole stream so decryption errors surface like they would
+        // while wsl.exe reads stdin
+        std::io::copy(input, &mut std::io::sink())?;
+        self.import(name, location, "-", version)
+    }
+
     fn set_default(&self, distro: &str) -> Result<CommandOutput, WslError> {
         if let Some(err) = self.check_error("set_default") {
             return Err(err);
@@ -791,6 +851,20 @@ Default Version: 2
         self.exec(distro, id, command)
     }
 
+    fn exec_as_root_with_input(&self, distro: &str, id: Option<&str>, command: &str, input: &mut dyn FnMut(&mut dyn Write) -> std::io::Result<()>) -> Result<CommandOutput, WslError> {
+        input(&mut std::io::sink())?;
+        self.exec_as_root(distro, id, command)
+    }
+
+    fn exec_as_root_to_writer(&self, distro: &str, id: Option<&str>, command: &str, output: &mut dyn Write) -> Result<CommandOutput, WslError> {
+        let result = self.exec_as_root(distro, id, command)?;
+        if result.success {
+            // An empty tar archive, like the mock export
+            output.write_all(&[0u8; 1024])?;
+        }
+        Ok(result)
+    }
+
     fn get_ip(&self) -> Result<CommandOutput, WslError> {
         if let Some(err) = self.check_error("get_ip") {
             return Err(err);
@@ -914,6 +988,19 @@ mod tests {
     }
 
 
+    #[test]
+    fn test_vhd_imports_register_wsl2_distros() {
+        let executor = MockWslExecutor::new();
+        executor.import_in_place("InPlace", "C:\\disks\\ext4.vhdx").unwrap();
+        executor.set_error("import_vhd", MockErrorType::CommandFailed);
+        assert!(executor.import_vhd("Copied", "C:\\wsl", "C:\\disks\\ext4.vhdx", None).is_err());
+
+        let state = executor.state.lock().unwrap();
+        let distro = state.distributions.iter().find(|d| d.name == "InPlace").unwrap();
+        assert_eq!(distro.version, 2);
+        assert!(!state.distributions.iter().any(|d| d.name == "Copied"));
+    }
+
     #[test]
     fn test_check_preflight_returns_ready_by_default() {
         let executor = MockWslExecutor::new();
@@ -11,6 +11,8 @@ pub use mock::MockWslExecutor;
 pub use mock::MockUpdateResult;
 pub use real::RealWslExecutor;
 
+use std::io::{Read, Write};
+
 use crate::wsl::types::{WslError, WslPreflightStatus};
 
 /// Result type for command output
@@ -59,9 +61,21 @@ pub trait WslCommandExecutor: Send + Sync {
     /// Execute `wsl --import <name> <location> <tarball>` with optional version
     fn import(&self, name: &str, location: &str, tarball: &str, version: Option<u8>) -> Result<CommandOutput, WslError>;
 
-    /// Execute `wsl --export <distro> <file>` with optional format
+    /// Execute `wsl --import <name> <location> <vhdx> --vhd` with optional version
+    fn import_vhd(&self, name: &str, location: &str, vhd: &str, version: Option<u8>) -> Result<CommandOutput, WslError>;
+
+    /// Execute `wsl --import-in-place <name> <vhdx>` (registers the disk without copying it)
+    fn import_in_place(&self, name: &str, vhd: &str) -> Result<CommandOutput, WslError>;
+
+    /// Execute `wsl --export <distro> <file>` with optional format (`tar`, `tar.gz`, `tar.xz`, `vhd`)
     fn export(&self, distro: &str, file: &str, format: Option<&str>) -> Result<CommandOutput, WslError>;
 
+    /// Execute `wsl --export <distro> -` and copy the tar stream to `output`
+    fn export_to_writer(&self, distro: &str, output: &mut dyn Write) -> Result<CommandOutput, WslError>;
+
+    /// Execute `wsl --import <name> <location> -` and feed the tar stream from `input`
+    fn import_from_reader(&self, name: &str, location: &str, input: &mut dyn Read, version: Option<u8>) -> Result<CommandOutput, WslError>;
+
     // === Configuration Operations ===
 
     /// Execute `wsl --set-default <distro>`
@@ -122,6 +136,15 @@ pub trait WslCommandExecutor: Send + Sync {
     /// Useful for writing to system files like /etc/wsl.conf
     fn exec_as_root(&self, distro: &str, id: Option<&str>, command: &str) -> Result<CommandOutput, WslError>;
 
+    /// Execute a command inside a distribution as root, writing its standard
+    /// input with `input` (e.g. a tar stream for `tar -x`). Has no timeout.
+    fn exec_as_root_with_input(&self, distro: &str, id: Option<&str>, command: &str, input: &mut dyn FnMut(&mut dyn Write) -> std::io::Result<()>) -> Result<CommandOutput, WslError>;
+
+    /// Execute a command inside a distribution as root, streaming its
+    /// standard output into `output` (e.g. a tar stream from `tar -c`).
+    /// Has no timeout; `stdout` of the returned output is empty.
+    fn exec_as_root_to_writer(&self, distro: &str, id: Option<&str>, command: &str, output: &mut dyn Write) -> Result<CommandOutput, WslError>;
+
     /// Get WSL2 network IP address
     /// Uses system distro with `ip route` for reliable IP detection
     fn get_ip(&self) -> Result<CommandOutput, WslError>;
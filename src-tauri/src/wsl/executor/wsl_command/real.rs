@@ -1,5 +1,6 @@
 //! Real WSL command executor - calls actual wsl.exe
 
+use std::io::{Read, Write};
 use std::process::Stdio;
 use std::time::Duration;
  use log::{debug, error, info};
@@ -151,6 +152,82 @@ impl RealWslExecutor {
     fn execute_long(&self, args: &[&str]) -> Result<CommandOutput, WslError> {
         self.execute_with_timeout(args, self.long_timeout())
     }
+
+    /// Execute a WSL command that streams a tarball through stdin or stdout.
+    ///
+    /// `pump` runs on the calling thread with the child's stdin or stdout;
+    /// the other pipes are drained in background threads. There is no
+    /// timeout: the stream makes progress for as long as the transfer runs.
+    /// If `pump` fails the child is killed so a half-fed import or export
+    /// does not linger. A child that exits before reading all of its input
+    /// is reported as failed, with its own stderr.
+    fn execute_streaming(
+        &self,
+        args: &[&str],
+        feed_stdin: bool,
+        pump: impl FnOnce(&mut std::process::Child) -> std::io::Result<()>,
+    ) -> Result<CommandOutput, WslError> {
+        debug!("Executing streaming WSL command: {:?}", args);
+
+        let paths = get_executable_paths();
+        let mut command = hidden_command(&paths.wsl);
+        command.args(args).stderr(Stdio::piped());
+        if feed_stdin {
+            command.stdin(Stdio::piped()).stdout(Stdio::piped());
+        } else {
+            command.stdin(Stdio::null()).stdout(Stdio::piped());
+        }
+        let mut child = command.spawn().map_err(|e| {
+            error!("Failed to spawn WSL command: {}", e);
+            WslError::CommandFailed(e.to_string())
+        })?;
+
+        let drain = |pipe: Option<Box<dyn std::io::Read + Send>>| {
+            std::thread::spawn(move || {
+                let mut buf = Vec::new();
+                if let Some(mut pipe) = pipe {
+                    let _ = pipe.read_to_end(&mut buf);
+                }
+                buf
+            })
+        };
+        let stderr_thread = drain(child.stderr.take().map(|p| Box::new(p) as Box<dyn std::io::Read + Send>));
+        let stdout_thread = if feed_stdin {
+            Some(drain(child.stdout.take().map(|p| Box::new(p) as Box<dyn std::io::Read + Send>)))
+        } else {
+            None
+        };
+
+        let mut pumped = pump(&mut child);
+        // The command exited before reading all of its input; its exit
+        // status and stderr say why
+        let input_refused =
+            feed_stdin && matches!(&pumped, Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe);
+        if input_refused {
+            pumped = Ok(());
+        } else if pumped.is_err() {
+            let _ = child.kill();
+        }
+        let status = child.wait();
+        let stdout_bytes = stdout_thread.and_then(|t| t.join().ok()).unwrap_or_default();
+        let stderr_bytes = stderr_thread.join().unwrap_or_default();
+        let stderr = decode_wsl_output(&stderr_bytes);
+
+        if let Err(e) = pumped {
+            error!("Streaming WSL command failed: {}", e);
+            return Err(WslError::IoError(e));
+        }
+        let status = status.map_err(|e| WslError::CommandFailed(e.to_string()))?;
+        if !status.success() {
+            debug!("WSL command returned non-zero: {}", stderr);
+        }
+
+        Ok(CommandOutput {
+            stdout: decode_wsl_output(&stdout_bytes),
+            stderr,
+            success: status.success() && !input_refused,
+        })
+    }
 }
 
 impl Default for RealWslExecutor {
@@ -222,6 +299,21 @@ impl WslCommandExecutor for RealWslExecutor {
         self.execute_long(&args)
     }
 
+    fn import_vhd(&self, name: &str, location: &str, vhd: &str, version: Option<u8>) -> Result<CommandOutput, WslError> {
+        let mut args = vec!["--import", name, location, vhd, "--vhd"];
+        let version_str;
+        if let Some(v) = version {
+            version_str = v.to_string();
+            args.push("--version");
+            args.push(&version_str);
+        }
+        self.execute_long(&args)
+    }
+
+    fn import_in_place(&self, name: &str, vhd: &str) -> Result<CommandOutput, WslError> {
+        self.execute_long(&["--import-in-place", name, vhd])
+    }
+
     fn export(&self, distro: &str, file: &str, format: Option<&str>) -> Result<CommandOutput, WslError> {
         let mut args = vec!["--export", distro, file];
         if let Some(fmt) = format {
@@ -231,6 +323,30 @@ impl WslCommandExecutor for RealWslExecutor {
         self.execute_long(&args)
     }
 
+    fn export_to_writer(&self, distro: &str, output: &mut dyn Write) -> Result<CommandOutput, WslError> {
+        self.execute_streaming(&["--export", distro, "-"], false, |child| {
+            let mut stdout = child.stdout.take().expect("stdout is piped");
+            std::io::copy(&mut stdout, output)?;
+            output.flush()
+        })
+    }
+
+    fn import_from_reader(&self, name: &str, location: &str, input: &mut dyn Read, version: Option<u8>) -> Result<CommandOutput, WslError> {
+        let mut args = vec!["--import", name, location, "-"];
+        let version_str;
+        if let Some(v) = version {
+            version_str = v.to_string();
+            args.push("--version");
+            args.push(&version_str);
+        }
+        self.execute_streaming(&args, true, |child| {
+            // Dropping stdin at the end of this closure signals end of stream
+            let mut stdin = child.stdin.take().expect("stdin is piped");
+            std::io::copy(input, &mut stdin)?;
+            stdin.flush()
+        })
+    }
+
     fn set_default(&self, distro: &str) -> Result<CommandOutput, WslError> {
         self.execute(&["--set-default", distro])
     }
@@ -425,6 +541,30 @@ impl WslCommandExecutor for RealWslExecutor {
         }
     }
 
+    fn exec_as_root_with_input(&self, distro: &str, id: Option<&str>, command: &str, input: &mut dyn FnMut(&mut dyn Write) -> std::io::Result<()>) -> Result<CommandOutput, WslError> {
+        let args = match id.filter(|_| supports_distribution_id()) {
+            Some(guid) => vec!["--distribution-id", guid, "-u", "root", "--", "sh", "-c", command],
+            None => vec!["-d", distro, "-u", "root", "--", "sh", "-c", command],
+        };
+        self.execute_streaming(&args, true, |child| {
+            let mut stdin = child.stdin.take().expect("stdin is piped");
+            input(&mut stdin)?;
+            stdin.flush()
+        })
+    }
+
+    fn exec_as_root_to_writer(&self, distro: &str, id: Option<&str>, command: &str, output: &mut dyn Write) -> Result<CommandOutput, WslError> {
+        let args = match id.filter(|_| supports_distribution_id()) {
+            Some(guid) => vec!["--distribution-id", guid, "-u", "root", "--", "sh", "-c", command],
+            None => vec!["-d", distro, "-u", "root", "--", "sh", "-c", command],
+        };
+        self.execute_streaming(&args, false, |child| {
+            let mut stdout = child.stdout.take().expect("stdout is piped");
+            std::io::copy(&mut stdout, output)?;
+            output.flush()
+        })
+    }
+
     fn exec_with_timeout(&self, distro: &str, id: Option<&str>, command: &str, timeout_secs: u64) -> Result<CommandOutput, WslError> {
         let timeout = Duration::from_secs(timeout_secs);
         // Use --distribution-id if available and supported for more reliable identification
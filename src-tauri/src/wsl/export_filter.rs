@@ -0,0 +1,518 @@
+//! Include and exclude filters for exports, clones and backups
+//!
+//! The `wsl --export` tar stream is filtered as it is read, so excluded files
+//! never reach the disk and filters work for WSL 1 and WSL 2 distributions
+//! alike.
+//!
+//! Patterns are globs over paths relative to the distribution root: `*` and
+//! `?` match within one path component, `**` matches any number of
+//! components and a leading `~/` stands for every home directory (`home/*/`
+//! and `root/`). A pattern without a `/` matches that name at any depth, so
+//! `node_modules` excludes every `node_modules` directory. Excluding a
+//! directory excludes everything below it. Include patterns are exceptions:
+//! a path matched by one is kept even when an exclude pattern matches it.
+
+use std::collections::HashSet;
+use std::io::{Read, Write};
+
+use serde::{Deserialize, Serialize};
+use tar::{EntryType, Header};
+
+use super::archive::{self, AppendEntry, EntryAction, TarRewriter};
+use super::types::WslError;
+
+/// Longest accepted pattern
+const MAX_PATTERN_LENGTH: usize = 512;
+
+/// Built-in exclude lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FilterPreset {
+    /// Build output and tool caches in home directories
+    DevCaches,
+    /// Packages downloaded by system and language package managers
+    PackageManagerCaches,
+    /// Docker, containerd and Podman images, containers and volumes
+    ContainerData,
+}
+
+impl FilterPreset {
+    pub const ALL: [FilterPreset; 3] = [
+        FilterPreset::DevCaches,
+        FilterPreset::PackageManagerCaches,
+        FilterPreset::ContainerData,
+    ];
+
+    /// Exclude patterns of the preset
+    pub fn patterns(self) -> &'static [&'static str] {
+        match self {
+            FilterPreset::DevCaches => &[
+                "~/.cache",
+                "~/**/node_modules",
+                "~/**/target",
+                "~/**/__pycache__",
+                "~/**/.pytest_cache",
+                "~/**/.mypy_cache",
+                "~/**/.tox",
+                "~/**/.next/cache",
+                "~/.gradle/caches",
+            ],
+            FilterPreset::PackageManagerCaches => &[
+                "var/cache/apt/archives/*.deb",
+                "var/cache/dnf",
+                "var/cache/yum",
+                "var/cache/pacman/pkg",
+                "var/cache/zypp/packages",
+                "var/cache/apk",
+                "~/.npm/_cacache",
+                "~/.cache/pip",
+                "~/.cache/yarn",
+                "~/.local/share/pnpm/store",
+                "~/.cargo/registry/cache",
+                "~/go/pkg/mod/cache",
+            ],
+            FilterPreset::ContainerData => &[
+                "var/lib/docker",
+                "var/lib/containerd",
+                "var/lib/containers",
+                "~/.local/share/containers",
+                "~/.local/share/docker",
+            ],
+        }
+    }
+}
+
+/// A preset and its patterns, for display
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterPresetInfo {
+    pub preset: FilterPreset,
+    pub patterns: Vec<String>,
+}
+
+/// List the built-in presets and their patterns
+pub fn list_presets() -> Vec<FilterPresetInfo> {
+    FilterPreset::ALL
+        .iter()
+        .map(|&preset| FilterPresetInfo {
+            preset,
+            patterns: preset.patterns().iter().map(|p| p.to_string()).collect(),
+        })
+        .collect()
+}
+
+/// Paths to leave out of an export
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportFilter {
+    #[serde(default)]
+    pub presets: Vec<FilterPreset>,
+    /// Glob patterns of paths to leave out
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Glob patterns of paths to keep even when they are excluded
+    #[serde(default)]
+    pub include: Vec<String>,
+}
+
+impl ExportFilter {
+    /// Whether the filter leaves nothing out
+    pub fn is_empty(&self) -> bool {
+        self.presets.is_empty() && self.exclude.iter().all(|p| p.trim().is_empty())
+    }
+
+    /// Check that every pattern is usable
+    pub fn validate(&self) -> Result<(), WslError> {
+        self.compile().map(|_| ())
+    }
+
+    fn compile(&self) -> Result<CompiledFilter, WslError> {
+        let presets: HashSet<FilterPreset> = self.presets.iter().copied().collect();
+        let mut exclude = Vec::new();
+        for preset in FilterPreset::ALL.iter().filter(|p| presets.contains(p)) {
+            for pattern in preset.patterns() {
+                exclude.extend(compile_pattern(pattern)?);
+            }
+        }
+        for pattern in &self.exclude {
+            exclude.extend(compile_pattern(pattern)?);
+        }
+        let mut include = Vec::new();
+        for pattern in &self.include {
+            include.extend(compile_pattern(pattern)?);
+        }
+        Ok(CompiledFilter { exclude, include })
+    }
+}
+
+/// What a filter kept and left out
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterReport {
+    /// Files, directories and links left out
+    pub excluded_entries: u64,
+    /// File data left out
+    pub excluded_bytes: u64,
+    /// File data kept
+    pub included_bytes: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    /// `**`
+    AnyDepth,
+    Glob(String),
+}
+
+type Pattern = Vec<Segment>;
+
+/// Compile a user pattern; `~/` expands to two patterns
+fn compile_pattern(raw: &str) -> Result<Vec<Pattern>, WslError> {
+    let invalid = |reason: &str| {
+        WslError::CommandFailed(format!("Invalid filter pattern '{}': {}", raw, reason))
+    };
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return Ok(Vec::new());
+    }
+    if trimmed.len() > MAX_PATTERN_LENGTH {
+        return Err(invalid("pattern is too long"));
+    }
+    if trimmed.contains('\0') || trimmed.contains('\\') {
+        return Err(invalid("use / to separate path components"));
+    }
+
+    let expanded: Vec<String> = match trimmed.strip_prefix("~/") {
+        Some(rest) => vec![format!("home/*/{}", rest), format!("root/{}", rest)],
+        None => {
+            let anchored = trimmed.starts_with('/');
+            let path = trimmed.trim_start_matches('/').trim_start_matches("./");
+            if anchored || path.trim_end_matches('/').contains('/') {
+                vec![path.to_string()]
+            } else {
+                vec![format!("**/{}", path)]
+            }
+        }
+    };
+
+    expanded
+        .iter()
+        .map(|pattern| {
+            let segments: Pattern = pattern
+                .split('/')
+                .filter(|c| !c.is_empty() && *c != ".")
+                .map(|c| match c {
+                    "**" => Ok(Segment::AnyDepth),
+                    ".." => Err(invalid("'..' is not allowed")),
+                    _ => Ok(Segment::Glob(c.to_string())),
+                })
+                .collect::<Result<_, _>>()?;
+            let matches_anything = |s: &Segment| match s {
+                Segment::AnyDepth => true,
+                Segment::Glob(glob) => glob.chars().all(|c| c == '*'),
+            };
+            if segments.iter().all(matches_anything) {
+                return Err(invalid("the pattern matches the whole distribution"));
+            }
+            Ok(segments)
+        })
+        .collect()
+}
+
+/// Match one path component against a glob with `*` and `?`
+fn glob_match(glob: &str, name: &str) -> bool {
+    let glob: Vec<char> = glob.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut g, mut n) = (0, 0);
+    // Position after the last `*` and the name position it matched up to
+    let mut backtrack = None;
+    while n < name.len() {
+        match glob.get(g) {
+            Some('*') => {
+                backtrack = Some((g + 1, n));
+                g += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                g += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((after_star, matched)) => {
+                    g = after_star;
+                    n = matched + 1;
+                    backtrack = Some((after_star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    glob[g..].iter().all(|&c| c == '*')
+}
+
+fn match_components(pattern: &[Segment], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((Segment::AnyDepth, rest)) => {
+            (0..=path.len()).any(|skip| match_components(rest, &path[skip..]))
+        }
+        Some((Segment::Glob(glob), rest)) => path
+            .split_first()
+            .is_some_and(|(first, tail)| glob_match(glob, first) && match_components(rest, tail)),
+    }
+}
+
+/// Whether `pattern` can match a path below `path`
+fn matches_below(pattern: &[Segment], path: &[&str]) -> bool {
+    match (pattern.split_first(), path.split_first()) {
+        (None, _) => false,
+        (Some(_), None) | (Some((Segment::AnyDepth, _)), _) => true,
+        (Some((Segment::Glob(glob), rest)), Some((first, tail))) => {
+            glob_match(glob, first) && matches_below(rest, tail)
+        }
+    }
+}
+
+struct CompiledFilter {
+    exclude: Vec<Pattern>,
+    include: Vec<Pattern>,
+}
+
+impl CompiledFilter {
+    /// Whether a pattern in `patterns` matches `path` or a directory above it
+    fn matches(patterns: &[Pattern], components: &[&str]) -> bool {
+        patterns.iter().any(|pattern| {
+            (1..=components.len()).any(|len| match_components(pattern, &components[..len]))
+        })
+    }
+
+    /// Whether `path` (normalized, without a leading `/`) is exported.
+    /// Directories above an included path are kept, so they are not
+    /// recreated as root-owned when the included path is extracted.
+    fn keeps(&self, path: &str, is_dir: bool) -> bool {
+        let components: Vec<&str> = path.split('/').collect();
+        !Self::matches(&self.exclude, &components)
+            || Self::matches(&self.include, &components)
+            || (is_dir && self.include.iter().any(|p| matches_below(p, &components)))
+    }
+}
+
+struct FilterRewriter {
+    filter: CompiledFilter,
+    /// Files left out, so hard links to them can be left out too
+    dropped: HashSet<String>,
+    report: FilterReport,
+}
+
+impl TarRewriter for FilterRewriter {
+    fn action(&mut self, path: &str, header: &Header) -> EntryAction {
+        let size = match header.entry_type() {
+            EntryType::Regular | EntryType::Continuous => header.entry_size().unwrap_or(0),
+            _ => 0,
+        };
+        let link_dropped = header.entry_type() == EntryType::Link
+            && header.link_name().ok().flatten().is_some_and(|target| {
+                self.dropped
+                    .contains(&archive::normalize_entry_path(&target.to_string_lossy()))
+            });
+        if path.is_empty()
+            || (self.filter.keeps(path, header.entry_type().is_dir()) && !link_dropped)
+        {
+            self.report.included_bytes += size;
+            return EntryAction::Keep;
+        }
+        self.report.excluded_entries += 1;
+        self.report.excluded_bytes += size;
+        self.dropped.insert(path.to_string());
+        EntryAction::Drop
+    }
+
+    fn finish(&mut self) -> Vec<AppendEntry> {
+        Vec::new()
+    }
+}
+
+/// Copy the tar stream from `input` to `output`, leaving out what `filter`
+/// excludes. Returns the output writer so it can be finished by the caller.
+pub fn filter_stream<R: Read, W: Write>(
+    input: R,
+    output: W,
+    filter: &ExportFilter,
+) -> Result<(W, FilterReport), WslError> {
+    let mut rewriter = FilterRewriter {
+        filter: filter.compile()?,
+        dropped: HashSet::new(),
+        report: FilterReport::default(),
+    };
+    let output = archive::rewrite(input, output, &mut rewriter)?;
+    Ok((output, rewriter.report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keeps(filter: &ExportFilter, path: &str) -> bool {
+        filter.compile().unwrap().keeps(path, false)
+    }
+
+    #[test]
+    fn glob_matches_within_a_component() {
+        assert!(glob_match("*.deb", "curl_8.5_amd64.deb"));
+        assert!(glob_match("node_modules", "node_modules"));
+        assert!(glob_match("ca?he", "cache"));
+        assert!(glob_match("*a*b", "xaab"));
+        assert!(!glob_match("*.deb", "lock"));
+        assert!(!glob_match("cache", "caches"));
+    }
+
+    #[test]
+    fn patterns_match_directories_and_everything_below() {
+        let filter = ExportFilter {
+            presets: vec![FilterPreset::DevCaches],
+            exclude: vec!["/var/log".to_string(), "*.iso".to_string()],
+            include: vec!["~/src/keep/node_modules".to_string()],
+        };
+        assert!(!keeps(&filter, "home/alice/.cache"));
+        assert!(!keeps(&filter, "home/alice/.cache/pip/wheel"));
+        assert!(!keeps(&filter, "root/.cache"));
+        assert!(!keeps(
+            &filter,
+            "home/alice/src/app/node_modules/react/index.js"
+        ));
+        assert!(!keeps(&filter, "home/alice/src/app/target/debug/app"));
+        assert!(!keeps(&filter, "var/log/syslog"));
+        assert!(!keeps(&filter, "opt/images/ubuntu.iso"));
+        assert!(keeps(
+            &filter,
+            "home/alice/src/keep/node_modules/left-pad/index.js"
+        ));
+        assert!(keeps(&filter, "home/alice/.cachefile"));
+        assert!(keeps(&filter, "usr/lib/node_modules/npm/index.js"));
+        assert!(keeps(&filter, "srv/var/log"));
+        assert!(keeps(&filter, "etc/hostname"));
+    }
+
+    #[test]
+    fn directories_above_an_include_are_kept() {
+        let filter = ExportFilter {
+            exclude: vec!["~/.cache".to_string()],
+            include: vec!["~/.cache/keep".to_string()],
+            ..Default::default()
+        };
+        let compiled = filter.compile().unwrap();
+        assert!(compiled.keeps("home/alice/.cache", true));
+        assert!(compiled.keeps("root/.cache", true));
+        assert!(compiled.keeps("home/alice/.cache/keep/data", false));
+        assert!(!compiled.keeps("home/alice/.cache", false));
+        assert!(!compiled.keeps("home/alice/.cache/pip", true));
+
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut append = |path: &str, entry_type: EntryType| {
+            let mut header = Header::new_gnu();
+            header.set_entry_type(entry_type);
+            header.set_mode(0o700);
+            header.set_uid(1000);
+            header.set_size(0);
+            builder.append_data(&mut header, path, &b""[..]).unwrap();
+        };
+        append("./home/alice/.cache/", EntryType::Directory);
+        append("./home/alice/.cache/pip/", EntryType::Directory);
+        append("./home/alice/.cache/pip/wheel", EntryType::Regular);
+        append("./home/alice/.cache/keep/", EntryType::Directory);
+        append("./home/alice/.cache/keep/data", EntryType::Regular);
+        let input = builder.into_inner().unwrap();
+
+        let (output, _) = filter_stream(input.as_slice(), Vec::new(), &filter).unwrap();
+        let mut archive = tar::Archive::new(output.as_slice());
+        let entries: Vec<(String, u64, u32)> = archive
+            .entries()
+            .unwrap()
+            .map(|e| {
+                let e = e.unwrap();
+                let header = e.header();
+                (
+                    e.path().unwrap().to_string_lossy().into_owned(),
+                    header.uid().unwrap(),
+                    header.mode().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            entries,
+            [
+                ("home/alice/.cache/".to_string(), 1000, 0o700),
+                ("home/alice/.cache/keep/".to_string(), 1000, 0o700),
+                ("home/alice/.cache/keep/data".to_string(), 1000, 0o700),
+            ]
+        );
+    }
+
+    #[test]
+    fn unusable_patterns_are_rejected() {
+        for pattern in ["**", "*", "/*/", "/", "home/../etc", "C:\\Users"] {
+            let filter = ExportFilter {
+                exclude: vec![pattern.to_string()],
+                ..Default::default()
+            };
+            assert!(filter.validate().is_err(), "{}", pattern);
+        }
+        let blank = ExportFilter {
+            exclude: vec!["  ".to_string()],
+            include: vec!["home".to_string()],
+            ..Default::default()
+        };
+        assert!(blank.is_empty());
+        assert!(blank.validate().is_ok());
+    }
+
+    #[test]
+    fn stream_filter_reports_excluded_size_and_drops_dangling_hard_links() {
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut append = |path: &str, data: &[u8], link: Option<&str>| {
+            let mut header = Header::new_gnu();
+            header.set_mode(0o644);
+            header.set_size(data.len() as u64);
+            match link {
+                Some(link) => {
+                    header.set_entry_type(EntryType::Link);
+                    builder.append_link(&mut header, path, link).unwrap();
+                }
+                None => builder.append_data(&mut header, path, data).unwrap(),
+            }
+        };
+        append("./etc/hostname", b"dev\n", None);
+        append(
+            "./home/alice/app/node_modules/x/index.js",
+            &[b'x'; 100],
+            None,
+        );
+        append("./home/alice/app/index.js", &[b'y'; 10], None);
+        append(
+            "./home/alice/link.js",
+            b"",
+            Some("./home/alice/app/node_modules/x/index.js"),
+        );
+        let input = builder.into_inner().unwrap();
+
+        let filter = ExportFilter {
+            exclude: vec!["node_modules".to_string()],
+            ..Default::default()
+        };
+        let (output, report) = filter_stream(input.as_slice(), Vec::new(), &filter).unwrap();
+        assert_eq!(
+            report,
+            FilterReport {
+                excluded_entries: 2,
+                excluded_bytes: 100,
+                included_bytes: 14,
+            }
+        );
+        let mut archive = tar::Archive::new(output.as_slice());
+        let paths: Vec<String> = archive
+            .entries()
+            .unwrap()
+            .map(|e| e.unwrap().path().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(paths, ["etc/hostname", "home/alice/app/index.js"]);
+    }
+}
@@ -1,12 +1,23 @@
 //! Import and export operations for WSL distributions
 //!
-//! Functions for exporting distributions to tar files, importing from tar files,
-//! and cloning distributions.
-
-use super::executor::{resource_monitor, wsl_executor};
+//! Functions for exporting distributions to tar files, `.vhdx` disks and
+//! `.wsl` packages, importing from tar files, `.vhdx` disks (copied or
+//! registered in place) and `.wsl` packages (optionally customized before
+//! import), encrypted export and import, filtered export (see
+//! [`super::export_filter`]) and cloning distributions.
+
+use super::distribution_conf::{
+    self, AppliedDistributionConf, DistributionConf, WslPackageOptions, WslPackageReport,
+};
+use super::customize::{self, RootfsCustomization};
+use super::encryption::{self, DecryptionKey, EncryptionKey};
+use super::export_filter::{self, ExportFilter, FilterReport};
+use super::executor::wsl_command::CommandOutput;
+use super::executor::{resource_monitor, supports_vhd_workflows, wsl_executor};
 use super::types::WslError;
 use crate::metadata::{self, DistroMetadata};
 use log::{info, warn};
+use serde::Serialize;
 
 /// Create the install location directory (and any missing parents) before
 /// invoking `wsl --import`. Without this, importing into a fresh path under
@@ -50,35 +61,331 @@ pub fn export_distribution(name: &str, path: &str) -> Result<(), WslError> {
     Ok(())
 }
 
-/// Import a distribution from a tar file
-pub fn import_distribution(name: &str, install_location: &str, tar_path: &str) -> Result<(), WslError> {
+/// Export a distribution to a tar file, leaving out what `filter` excludes.
+/// A partial file is removed if the export fails.
+pub fn export_distribution_filtered(
+    name: &str,
+    path: &str,
+    filter: &ExportFilter,
+) -> Result<FilterReport, WslError> {
+    filter.validate()?;
+
+    if crate::utils::is_mock_mode() {
+        let (_, report) = export_filtered_to(name, filter, std::io::sink())?;
+        info!("Mock: Exported '{}' filtered to '{}'", name, path);
+        return Ok(report);
+    }
+
+    let result = (|| {
+        let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        let (file, report) = export_filtered_to(name, filter, file)?;
+        file.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        Ok::<_, WslError>(report)
+    })();
+    let report = result.inspect_err(|_| {
+        let _ = std::fs::remove_file(path);
+    })?;
+
+    info!(
+        "Exported '{}' to '{}', leaving out {} entries ({} bytes)",
+        name, path, report.excluded_entries, report.excluded_bytes
+    );
+    Ok(report)
+}
+
+/// Stream `wsl --export` through the filter into `output`
+pub(crate) fn export_filtered_to<W: std::io::Write + Send>(
+    name: &str,
+    filter: &ExportFilter,
+    output: W,
+) -> Result<(W, FilterReport), WslError> {
+    export_through(name, |input| export_filter::filter_stream(input, output, filter))
+}
+
+/// Run `wsl --export` into a pipe that `consume` reads on a second thread
+fn export_through<T: Send>(
+    name: &str,
+    consume: impl FnOnce(&mut dyn std::io::Read) -> Result<T, WslError> + Send,
+) -> Result<T, WslError> {
+    pipe_through(|writer| wsl_executor().export_to_writer(name, writer), consume)
+}
+
+/// Feed what `produce` writes to `consume` through a pipe. Whatever
+/// `consume` leaves unread, such as the record padding after the tar
+/// end-of-archive marker, is drained so `produce` never writes into a
+/// closed pipe.
+fn pipe_through<T: Send>(
+    produce: impl FnOnce(&mut dyn std::io::Write) -> Result<CommandOutput, WslError>,
+    consume: impl FnOnce(&mut dyn std::io::Read) -> Result<T, WslError> + Send,
+) -> Result<T, WslError> {
+    let (reader, mut writer) = std::io::pipe()?;
+    std::thread::scope(|scope| {
+        let consuming = scope.spawn(move || {
+            let mut input = std::io::BufReader::new(reader);
+            let consumed = consume(&mut input)?;
+            std::io::copy(&mut input, &mut std::io::sink())?;
+            Ok::<_, WslError>(consumed)
+        });
+        let exported = produce(&mut writer);
+        // Closing the pipe ends the stream for the consumer
+        drop(writer);
+        let consumed = consuming
+            .join()
+            .map_err(|_| WslError::CommandFailed("Export stream stopped unexpectedly".to_string()))?;
+        // A consumer error also breaks the pipe, so report it first
+        let consumed = consumed?;
+        let exported = exported?;
+        if !exported.success {
+            return Err(WslError::CommandFailed(extract_error_message(
+                &exported,
+                "Export failed with no error message",
+            )));
+        }
+        Ok(consumed)
+    })
+}
+
+/// Export a distribution to an encrypted archive. The tar stream from
+/// `wsl --export` is encrypted as it is read, so no plaintext reaches the
+/// disk; a partial archive is removed if the export fails.
+pub fn export_distribution_encrypted(
+    name: &str,
+    path: &str,
+    key: &EncryptionKey,
+) -> Result<(), WslError> {
+    encryption::validate_encryption_key(key)?;
+
+    if crate::utils::is_mock_mode() {
+        export_encrypted_to(name, key, std::io::sink())?;
+        info!("Mock: Exported '{}' encrypted to '{}'", name, path);
+        return Ok(());
+    }
+
+    let result = (|| {
+        let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        let file = export_encrypted_to(name, key, file)?;
+        file.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        Ok::<_, WslError>(())
+    })();
+    result.inspect_err(|_| {
+        let _ = std::fs::remove_file(path);
+    })?;
+
+    info!("Exported '{}' encrypted to '{}'", name, path);
+    Ok(())
+}
+
+fn export_encrypted_to<W: std::io::Write>(
+    name: &str,
+    key: &EncryptionKey,
+    output: W,
+) -> Result<W, WslError> {
+    let mut writer = encryption::encrypt(key, output)?;
+    let output = wsl_executor().export_to_writer(name, &mut writer)?;
+    if !output.success {
+        return Err(WslError::CommandFailed(extract_error_message(
+            &output,
+            "Export failed with no error message",
+        )));
+    }
+    Ok(writer.finish()?)
+}
+
+/// Fail early when the installed WSL cannot run VHD imports and exports
+fn ensure_vhd_supported() -> Result<(), WslError> {
+    if supports_vhd_workflows() {
+        Ok(())
+    } else {
+        Err(WslError::CommandFailed(
+            "VHD import and export require WSL 2.4.4 or later. Run 'wsl --update' and try again."
+                .to_string(),
+        ))
+    }
+}
+
+/// Check a VHD path and requested WSL version. WSL only accepts `.vhdx`
+/// disks for VHD workflows, and they are always WSL 2.
+fn validate_vhd_request(path: &str, wsl_version: Option<u8>) -> Result<(), WslError> {
+    let is_vhdx = std::path::Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("vhdx"));
+    if !is_vhdx {
+        return Err(WslError::CommandFailed(format!(
+            "'{}' is not a .vhdx file",
+            path
+        )));
+    }
+    if wsl_version.is_some_and(|v| v != 2) {
+        return Err(WslError::CommandFailed(
+            "VHD-based distributions are always WSL 2".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Export a WSL 2 distribution's disk to a `.vhdx` file (`--format vhd`)
+pub fn export_distribution_vhd(name: &str, path: &str) -> Result<(), WslError> {
+    ensure_vhd_supported()?;
+    validate_vhd_request(path, None)?;
+
+    let distro = super::core::list_distributions()?
+        .into_iter()
+        .find(|d| d.name == name)
+        .ok_or_else(|| WslError::DistroNotFound(name.to_string()))?;
+    if distro.version != 2 {
+        return Err(WslError::CommandFailed(format!(
+            "'{}' is a WSL 1 distribution; convert it to WSL 2 or export it as a tar file",
+            name
+        )));
+    }
+
+    let output = wsl_executor().export(name, path, Some("vhd"))?;
+    if !output.success {
+        return Err(WslError::CommandFailed(extract_error_message(
+            &output,
+            "VHD export failed with no error message",
+        )));
+    }
+    info!("Exported '{}' as VHD to '{}'", name, path);
+    Ok(())
+}
+
+/// Import a `.vhdx` disk as a new distribution (`--import --vhd`). WSL copies
+/// the disk into `install_location`.
+pub fn import_distribution_vhd(
+    name: &str,
+    install_location: &str,
+    vhd_path: &str,
+    wsl_version: Option<u8>,
+) -> Result<(), WslError> {
+    ensure_vhd_supported()?;
+    validate_vhd_request(vhd_path, wsl_version)?;
+    if !crate::utils::is_mock_mode() && !std::path::Path::new(vhd_path).is_file() {
+        return Err(WslError::CommandFailed(format!(
+            "VHD file not found: {}",
+            vhd_path
+        )));
+    }
     ensure_install_location_exists(install_location)?;
-    let output = wsl_executor().import(name, install_location, tar_path, None)?;
 
+    let output = wsl_executor().import_vhd(name, install_location, vhd_path, wsl_version)?;
     if !output.success {
-        // WSL often writes errors to stdout instead of stderr
-        let error_msg = if !output.stderr.trim().is_empty() {
-            output.stderr
-        } else if !output.stdout.trim().is_empty() {
-            output.stdout
-        } else {
-            "Import failed with no error message".to_string()
-        };
-        return Err(WslError::CommandFailed(error_msg));
+        return Err(WslError::CommandFailed(extract_error_message(
+            &output,
+            "VHD import failed with no error message",
+        )));
+    }
+    info!("Imported '{}' from VHD '{}'", name, vhd_path);
+    Ok(())
+}
+
+/// Register an existing `.vhdx` as a distribution without copying it
+/// (`--import-in-place`). The file becomes the distribution's disk, so
+/// unregistering the distribution deletes it.
+pub fn register_vhd_in_place(name: &str, vhd_path: &str) -> Result<(), WslError> {
+    ensure_vhd_supported()?;
+    validate_vhd_request(vhd_path, None)?;
+    if !crate::utils::is_mock_mode() && !std::path::Path::new(vhd_path).is_file() {
+        return Err(WslError::CommandFailed(format!(
+            "VHD file not found: {}",
+            vhd_path
+        )));
     }
 
+    let output = wsl_executor().import_in_place(name, vhd_path)?;
+    if !output.success {
+        return Err(WslError::CommandFailed(extract_error_message(
+            &output,
+            "In-place registration failed with no error message",
+        )));
+    }
+    info!("Registered '{}' in place from '{}'", name, vhd_path);
     Ok(())
 }
 
-/// Import a distribution with optional WSL version
+/// Export a distribution as a `.wsl` package. The `wsl --export` tar is
+/// rewritten into a gzip stream with a generated `wsl-distribution.conf` and
+/// machine-specific state (machine-id, SSH host keys) removed.
+pub fn export_wsl_package(
+    name: &str,
+    path: &str,
+    options: &WslPackageOptions,
+) -> Result<WslPackageReport, WslError> {
+    options.validate()?;
+
+    if crate::utils::is_mock_mode() {
+        let output = wsl_executor().export(name, "/tmp/mock-package.tar", None)?;
+        if !output.success {
+            return Err(WslError::CommandFailed(output.stderr));
+        }
+        info!("Mock: Exported '{}' as .wsl package to '{}'", name, path);
+        return Ok(WslPackageReport {
+            path: path.to_string(),
+            ..Default::default()
+        });
+    }
+
+    let result = (|| {
+        let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        let (file, stripped) = export_package_to(name, options, file)?;
+        file.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        Ok::<_, WslError>(stripped)
+    })();
+    let stripped = result.inspect_err(|_| {
+        let _ = std::fs::remove_file(path);
+    })?;
+
+    info!("Exported '{}' as .wsl package to '{}' (stripped {} entries)", name, path, stripped.len());
+    Ok(WslPackageReport {
+        path: path.to_string(),
+        size_bytes: std::fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        stripped,
+    })
+}
+
+/// Stream `wsl --export` into `output` as a gzipped `.wsl` package. The
+/// export writes into a pipe that a second thread rewrites, so the plain
+/// rootfs tar never reaches the disk.
+fn export_package_to<W: std::io::Write + Send>(
+    name: &str,
+    options: &WslPackageOptions,
+    output: W,
+) -> Result<(W, Vec<String>), WslError> {
+    export_through(name, |input| {
+        let mut encoder = flate2::write::GzEncoder::new(output, flate2::Compression::default());
+        let stripped = distribution_conf::write_package(input, &mut encoder, options)?;
+        Ok((encoder.finish()?, stripped))
+    })
+}
+
+/// Import a distribution with optional WSL version, applying `customization`
+/// to the tarball first. The customized copy is removed once WSL is done with it.
 pub fn import_distribution_with_version(
     name: &str,
     install_location: &str,
     tar_path: &str,
     wsl_version: Option<u8>,
+    customization: Option<&RootfsCustomization>,
 ) -> Result<(), WslError> {
+    ensure_not_encrypted(tar_path)?;
     ensure_install_location_exists(install_location)?;
-    let output = wsl_executor().import(name, install_location, tar_path, wsl_version)?;
+
+    let customized = match customization {
+        Some(c) if !c.is_empty() && !crate::utils::is_mock_mode() => {
+            info!("Customizing rootfs for '{}' before import", name);
+            Some(customize::customize_rootfs(std::path::Path::new(tar_path), c)?)
+        }
+        _ => None,
+    };
+    let import_path = customized
+        .as_ref()
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_else(|| tar_path.to_string());
+    let output = wsl_executor().import(name, install_location, &import_path, wsl_version);
+    if let Some(path) = &customized {
+        let _ = std::fs::remove_file(path);
+    }
+    let output = output?;
 
     if !output.success {
         // WSL often writes errors to stdout instead of stderr
@@ -95,54 +402,270 @@ pub fn import_distribution_with_version(
     Ok(())
 }
 
+/// Refuse to hand an encrypted archive to `wsl --import` as if it were a tar
+fn ensure_not_encrypted(tar_path: &str) -> Result<(), WslError> {
+    match encryption::detect(std::path::Path::new(tar_path)) {
+        Ok(Some(_)) => Err(WslError::CommandFailed(format!(
+            "'{}' is encrypted. Enter its passphrase or key to import it.",
+            tar_path
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Import an encrypted archive. The passphrase or key is checked against
+/// the archive header before WSL is started, then the archive is decrypted
+/// straight into `wsl --import` so the plaintext tar never reaches the disk.
+/// A damaged or truncated archive stops the import before WSL sees the end
+/// of the stream.
+pub fn import_distribution_encrypted(
+    name: &str,
+    install_location: &str,
+    path: &str,
+    key: &DecryptionKey,
+    wsl_version: Option<u8>,
+) -> Result<(), WslError> {
+    let mut stream: Box<dyn std::io::Read> = match std::fs::File::open(path) {
+        Ok(file) => Box::new(encryption::decrypt(key, std::io::BufReader::new(file))?),
+        Err(_) if crate::utils::is_mock_mode() => Box::new(std::io::empty()),
+        Err(e) => {
+            return Err(WslError::CommandFailed(format!(
+                "Cannot open '{}': {}",
+                path, e
+            )))
+        }
+    };
+    ensure_install_location_exists(install_location)?;
+    let output = wsl_executor().import_from_reader(name, install_location, &mut stream, wsl_version)?;
+
+    if !output.success {
+        return Err(WslError::CommandFailed(extract_error_message(
+            &output,
+            "Import failed with no error message",
+        )));
+    }
+
+    info!("Imported '{}' from encrypted archive '{}'", name, path);
+    Ok(())
+}
+
+/// `wsl-distribution.conf` found in an imported package and what applying it
+/// produced
+#[derive(Debug, Clone)]
+pub struct ImportedPackage {
+    pub conf: DistributionConf,
+    pub applied: AppliedDistributionConf,
+}
+
+/// Import a tarball or `.wsl` package. `/etc/wsl-distribution.conf` is read
+/// from the archive before import and applied once the import succeeds.
+/// Returns `None` for plain rootfs archives.
+pub fn import_package(
+    name: &str,
+    install_location: &str,
+    tar_path: &str,
+    wsl_version: Option<u8>,
+    terminal_command: &str,
+    customization: Option<&RootfsCustomization>,
+) -> Result<Option<ImportedPackage>, WslError> {
+    ensure_not_encrypted(tar_path)?;
+    let package = match distribution_conf::read_package(std::path::Path::new(tar_path)) {
+        Ok(package) => package,
+        Err(e) => {
+            warn!("Could not read wsl-distribution.conf from '{}': {}", tar_path, e);
+            None
+        }
+    };
+
+    import_distribution_with_version(name, install_location, tar_path, wsl_version, customization)?;
+
+    Ok(package.map(|package| {
+        info!("Applying wsl-distribution.conf for '{}'", name);
+        let applied =
+            distribution_conf::apply_package(name, install_location, &package, terminal_command);
+        ImportedPackage {
+            conf: package.conf,
+            applied,
+        }
+    }))
+}
+
+/// How a clone copied the source distribution
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CloneMethod {
+    /// `--export --format vhd` + `--import --vhd`: the ext4 disk is copied
+    /// as-is, keeping sparse files and extended attributes
+    Vhd,
+    /// `--export` + `--import` through a temporary tarball
+    Tar,
+}
+
+/// Why a WSL 2 clone could not use the VHD path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CloneFallback {
+    /// The source is a WSL 1 distribution (no virtual disk)
+    Wsl1Source,
+    /// The installed WSL is older than 2.4.4
+    VhdUnsupported,
+    /// Rootfs customization needs the tar stream
+    Customized,
+    /// `--export --format vhd` failed, so the clone was retried as tar
+    VhdExportFailed,
+    /// Include and exclude filters need the tar stream
+    Filtered,
+}
+
+/// Outcome of [`clone_distribution`]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloneResult {
+    pub method: CloneMethod,
+    /// Set when the tar path was used for a reason other than preference
+    pub fallback: Option<CloneFallback>,
+    /// What the export filter left out, for filtered clones
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter_report: Option<FilterReport>,
+}
+
+/// Pick the clone method for a source distribution. The VHD path needs a
+/// WSL 2 source, WSL 2.4.4+ and no customization or filter (which work on
+/// the tar stream).
+fn select_clone_method(
+    source_version: u8,
+    vhd_supported: bool,
+    customized: bool,
+    filtered: bool,
+) -> (CloneMethod, Option<CloneFallback>) {
+    if source_version != 2 {
+        (CloneMethod::Tar, Some(CloneFallback::Wsl1Source))
+    } else if !vhd_supported {
+        (CloneMethod::Tar, Some(CloneFallback::VhdUnsupported))
+    } else if customized {
+        (CloneMethod::Tar, Some(CloneFallback::Customized))
+    } else if filtered {
+        (CloneMethod::Tar, Some(CloneFallback::Filtered))
+    } else {
+        (CloneMethod::Vhd, None)
+    }
+}
+
 /// Clone a distribution (export + import with new name)
 ///
-/// If `install_location` is None, uses the default from settings.
+/// WSL 2 sources are copied as a `.vhdx` disk when the installed WSL supports
+/// it; WSL 1 sources, older WSL builds, customized and filtered clones go
+/// through a tar export. If `install_location` is None, uses the default from
+/// settings.
 /// Creates metadata for the cloned distribution automatically.
-pub fn clone_distribution(source: &str, new_name: &str, install_location: Option<&str>) -> Result<(), WslError> {
+pub fn clone_distribution(
+    source: &str,
+    new_name: &str,
+    install_location: Option<&str>,
+    customization: Option<&RootfsCustomization>,
+    filter: Option<&ExportFilter>,
+) -> Result<CloneResult, WslError> {
     use crate::settings::get_default_distro_path;
     use crate::utils::is_mock_mode;
 
     info!("Cloning distribution '{}' to '{}'", source, new_name);
+    let filter = filter.filter(|f| !f.is_empty());
+    if let Some(filter) = filter {
+        filter.validate()?;
+    }
+
+    let source_version = super::core::list_distributions()?
+        .into_iter()
+        .find(|d| d.name == source)
+        .map(|d| d.version)
+        .ok_or_else(|| WslError::DistroNotFound(source.to_string()))?;
+    let (method, fallback) = select_clone_method(
+        source_version,
+        supports_vhd_workflows(),
+        customization.is_some_and(|c| !c.is_empty()),
+        filter.is_some(),
+    );
 
     // In mock mode, just call the mock import/export without filesystem operations
     if is_mock_mode() {
+        let (temp_path, format) = match method {
+            CloneMethod::Vhd => ("/tmp/mock-clone.vhdx", Some("vhd")),
+            CloneMethod::Tar => ("/tmp/mock-clone.tar", None),
+        };
+
         // Mock export (just validates source exists)
-        let export_output = wsl_executor().export(source, "/tmp/mock-clone.tar", None)?;
-        if !export_output.success {
-            return Err(WslError::CommandFailed(export_output.stderr));
-        }
+        let filter_report = match (method, filter) {
+            (CloneMethod::Tar, Some(filter)) => {
+                Some(export_filtered_to(source, filter, std::io::sink())?.1)
+            }
+            _ => {
+                let export_output = wsl_executor().export(source, temp_path, format)?;
+                if !export_output.success {
+                    return Err(WslError::CommandFailed(export_output.stderr));
+                }
+                None
+            }
+        };
 
         // Mock import (adds to mock state)
-        let import_output = wsl_executor().import(new_name, "/tmp/mock-location", "/tmp/mock-clone.tar", Some(2))?;
+        let import_output = match method {
+            CloneMethod::Vhd => wsl_executor().import_vhd(new_name, "/tmp/mock-location", temp_path, None)?,
+            CloneMethod::Tar => wsl_executor().import(new_name, "/tmp/mock-location", temp_path, Some(2))?,
+        };
         if !import_output.success {
             return Err(WslError::CommandFailed(import_output.stderr));
         }
 
-        info!("Mock: Cloned distribution '{}' to '{}'", source, new_name);
-        return Ok(());
+        info!("Mock: Cloned distribution '{}' to '{}' via {:?}", source, new_name, method);
+        return Ok(CloneResult { method, fallback, filter_report });
     }
 
     // Get source distro's GUID before cloning (for metadata lineage)
     let registry_info = resource_monitor().get_all_distro_registry_info();
     let source_id = registry_info.get(source).map(|info| info.id.clone());
 
-    // Create temp file path
+    // Create temp file paths
     let temp_dir = std::env::temp_dir();
-    let temp_file = temp_dir.join(format!("wsl-clone-{}.tar", std::process::id()));
+    let temp_vhd = temp_dir.join(format!("wsl-clone-{}.vhdx", std::process::id()));
+    let temp_tar = temp_dir.join(format!("wsl-clone-{}.tar", std::process::id()));
+
+    // Export to temp file, retrying as tar if the VHD export fails
+    let (method, fallback, temp_file, filter_report) = match method {
+        CloneMethod::Vhd => match export_distribution_vhd(source, &temp_vhd.to_string_lossy()) {
+            Ok(()) => (CloneMethod::Vhd, None, temp_vhd, None),
+            Err(e) => {
+                warn!("VHD export of '{}' failed, falling back to tar: {}", source, e);
+                let _ = std::fs::remove_file(&temp_vhd);
+                export_distribution(source, &temp_tar.to_string_lossy())?;
+                (CloneMethod::Tar, Some(CloneFallback::VhdExportFailed), temp_tar, None)
+            }
+        },
+        CloneMethod::Tar => {
+            let filter_report = match filter {
+                Some(filter) => Some(export_distribution_filtered(source, &temp_tar.to_string_lossy(), filter)?),
+                None => {
+                    export_distribution(source, &temp_tar.to_string_lossy())?;
+                    None
+                }
+            };
+            (CloneMethod::Tar, fallback, temp_tar, filter_report)
+        }
+    };
     let temp_path = temp_file.to_string_lossy().to_string();
 
-    // Export to temp file
-    export_distribution(source, &temp_path)?;
-
     // Use provided location or default from settings
     let final_location = match install_location {
         Some(loc) if !loc.trim().is_empty() => loc.to_string(),
         _ => get_default_distro_path(new_name),
     };
 
-    // Import with new name (install dir is created inside import_distribution)
-    let result = import_distribution(new_name, &final_location, &temp_path);
+    // Import with new name (install dir is created inside the import)
+    let result = match method {
+        CloneMethod::Vhd => import_distribution_vhd(new_name, &final_location, &temp_path, None),
+        CloneMethod::Tar => {
+            import_distribution_with_version(new_name, &final_location, &temp_path, None, customization)
+        }
+    };
 
     // Clean up temp file (ignore errors)
     let _ = std::fs::remove_file(&temp_file);
@@ -167,12 +690,13 @@ pub fn clone_distribution(source: &str, new_name: &str, install_location: Option
         }
     }
 
-    result
+    result?;
+    info!("Cloned '{}' to '{}' via {:?}", source, new_name, method);
+    Ok(CloneResult { method, fallback, filter_report })
 }
 
 /// Helper to extract error message from WSL command output
 /// WSL often writes errors to stdout instead of stderr
-#[cfg(test)]
 fn extract_error_message(output: &super::executor::wsl_command::CommandOutput, default_msg: &str) -> String {
     if !output.stderr.trim().is_empty() {
         output.stderr.clone()
@@ -186,7 +710,6 @@ fn extract_error_message(output: &super::executor::wsl_command::CommandOutput, d
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::wsl::executor::wsl_command::CommandOutput;
 
     #[test]
     fn test_extract_error_message_prefers_stderr() {
@@ -238,6 +761,36 @@ mod tests {
         assert_eq!(extract_error_message(&output, "fallback"), "fallback");
     }
 
+    #[test]
+    fn test_validate_vhd_request() {
+        assert!(validate_vhd_request(r"D:\disks\ext4.vhdx", None).is_ok());
+        assert!(validate_vhd_request(r"D:\disks\ext4.VHDX", Some(2)).is_ok());
+        assert!(validate_vhd_request(r"D:\disks\ext4.vhd", None).is_err());
+        assert!(validate_vhd_request(r"D:\disks\rootfs.tar", None).is_err());
+        assert!(validate_vhd_request(r"D:\disks\ext4.vhdx", Some(1)).is_err());
+    }
+
+    #[test]
+    fn test_select_clone_method() {
+        assert_eq!(select_clone_method(2, true, false, false), (CloneMethod::Vhd, None));
+        assert_eq!(
+            select_clone_method(1, true, false, false),
+            (CloneMethod::Tar, Some(CloneFallback::Wsl1Source))
+        );
+        assert_eq!(
+            select_clone_method(2, false, false, false),
+            (CloneMethod::Tar, Some(CloneFallback::VhdUnsupported))
+        );
+        assert_eq!(
+            select_clone_method(2, true, true, true),
+            (CloneMethod::Tar, Some(CloneFallback::Customized))
+        );
+        assert_eq!(
+            select_clone_method(2, true, false, true),
+            (CloneMethod::Tar, Some(CloneFallback::Filtered))
+        );
+    }
+
     #[test]
     fn test_location_selection_uses_provided_path() {
         let provided = Some("C:\\WSL\\MyDistro");
@@ -342,9 +895,93 @@ mod tests {
         // Same process should get same path (deterministic)
         assert_eq!(temp_file1, temp_file2);
     }
-}
 
+    #[test]
+    fn test_encrypted_archive_checks_key_and_is_refused_as_plain_tar() {
+        use std::io::Write;
+        let dir = crate::utils::unique_temp_dir("encrypted-import");
+        let archive = dir.join("Ubuntu.tar.age");
+        let key = EncryptionKey::Passphrase {
+            passphrase: "correct horse".to_string(),
+        };
+        let mut writer = encryption::encrypt(&key, std::fs::File::create(&archive).unwrap()).unwrap();
+        writer.write_all(&[0u8; 1024]).unwrap();
+        writer.finish().unwrap();
+        let archive = archive.to_string_lossy().into_owned();
+
+        let wrong = DecryptionKey::Passphrase {
+            passphrase: "wrong horse".to_string(),
+        };
+        let err = import_distribution_encrypted("EncryptedTest", "C:\\wsl\\EncryptedTest", &archive, &wrong, None)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Wrong passphrase"), "{}", err);
+
+        let err = import_package("EncryptedTest", "C:\\wsl\\EncryptedTest", &archive, None, "auto", None)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("is encrypted"), "{}", err);
 
+        let err = import_distribution_with_version("EncryptedTest", "C:\\wsl\\EncryptedTest", &archive, Some(2), None)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("is encrypted"), "{}", err);
 
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 
+    #[test]
+    fn test_package_export_streams_without_temp_file() {
+        let options = WslPackageOptions {
+            friendly_name: Some("Streamed".to_string()),
+            ..Default::default()
+        };
+        let (package, _) = export_package_to("Ubuntu", &options, Vec::new()).unwrap();
+
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(package.as_slice()));
+        let paths: Vec<String> = archive
+            .entries()
+            .unwrap()
+            .map(|e| e.unwrap().path().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert!(paths
+            .iter()
+            .any(|p| p.ends_with(distribution_conf::DISTRIBUTION_CONF_PATH)));
+
+        assert!(export_package_to("Missing", &options, Vec::new()).is_err());
+    }
 
+    #[test]
+    fn test_filtered_export_drains_padding_after_end_marker() {
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(5);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, "etc/hostname", &b"host\n"[..]).unwrap();
+        let mut archive = builder.into_inner().unwrap();
+        // More zero records than a pipe buffers, so a reader dropped at the
+        // end-of-archive marker leaves the writer with a broken pipe
+        archive.resize(archive.len() + 64 * 10240, 0);
+
+        let filter = ExportFilter {
+            exclude: vec!["/var/log".to_string()],
+            ..Default::default()
+        };
+        let (output, _) = pipe_through(
+            |writer| {
+                writer.write_all(&archive)?;
+                Ok(CommandOutput {
+                    stdout: String::new(),
+                    stderr: String::new(),
+                    success: true,
+                })
+            },
+            |input| export_filter::filter_stream(input, Vec::new(), &filter),
+        )
+        .unwrap();
+
+        let mut filtered = tar::Archive::new(output.as_slice());
+        assert_eq!(filtered.entries().unwrap().count(), 1);
+    }
+}
@@ -8,6 +8,7 @@ use crate::metadata::{self, DistroMetadata, InstallSource};
 use log::{info, warn};
 
 use super::executor::{resource_monitor, terminal_executor, wsl_executor};
+use super::customize::RootfsCustomization;
 use super::executor::terminal::ContainerRuntime;
 use super::import_export::import_distribution_with_version;
 use super::types::WslError;
@@ -152,6 +153,7 @@ pub fn create_from_image(
     install_location: Option<&str>,
     wsl_version: Option<u8>,
     runtime_hint: Option<&str>,
+    customization: Option<&RootfsCustomization>,
 ) -> Result<(), WslError> {
     info!("Creating distribution '{}' from container image '{}'", distro_name, image);
 
@@ -205,7 +207,7 @@ pub fn create_from_image(
 
     // Step 5: Import with optional WSL version
     let import_result =
-        import_distribution_with_version(distro_name, &location, &tar_path_str, wsl_version);
+        import_distribution_with_version(distro_name, &location, &tar_path_str, wsl_version, customization);
 
     // Step 6: Cleanup
     let _ = executor.container_rm(runtime, &container_id);
@@ -245,6 +247,7 @@ pub fn create_from_oci_image(
     install_location: Option<&str>,
     wsl_version: Option<u8>,
     progress: Option<crate::oci::ProgressCallback>,
+    customization: Option<&RootfsCustomization>,
 ) -> Result<(), WslError> {
     info!("Creating distribution '{}' from OCI image '{}'", distro_name, image);
 
@@ -276,7 +279,7 @@ pub fn create_from_oci_image(
 
     // Import with optional WSL version
     let import_result =
-        import_distribution_with_version(distro_name, &location, &tar_path_str, wsl_version);
+        import_distribution_with_version(distro_name, &location, &tar_path_str, wsl_version, customization);
 
     // Cleanup temp directory
     let _ = std::fs::remove_dir_all(&oci_work_dir);
@@ -0,0 +1,931 @@
+//! Migrating user data between distributions
+//!
+//! Copies the default user's home directory, crontab, optionally the SSH
+//! host keys and any further paths from one distribution into another, e.g.
+//! when moving to a new Ubuntu LTS. `tar -c` runs as root in the source and
+//! its output is rewritten in-process and streamed straight into `tar -x`
+//! in the target, so nothing is written to the Windows disk.
+//!
+//! The home directory and crontab are renamed for the target's default
+//! user, and files owned by the source user are given the target user's
+//! UID and GID, so a migration from `alice` (1000) to `bob` (1001) leaves
+//! `/home/bob` owned by `bob`.
+
+use std::collections::HashMap;
+use std::io::{BufReader, Read, Write};
+
+use log::info;
+use serde::{Deserialize, Serialize};
+use tar::{Archive, EntryType, Header};
+
+use super::archive::{self, normalize_entry_path};
+use super::archive_files::shell_quote;
+use super::core;
+use super::executor::wsl_executor;
+use super::types::WslError;
+use crate::utils::is_mock_mode;
+
+/// Crontab spool directories, most specific first: Debian, Alpine, Fedora
+const CRON_DIRS: [&str; 3] = [
+    "/var/spool/cron/crontabs",
+    "/etc/crontabs",
+    "/var/spool/cron",
+];
+
+/// Paths that are never migrated because they are not part of the disk
+const VIRTUAL_DIRS: [&str; 4] = ["/proc", "/sys", "/dev", "/run"];
+
+/// Extracts the migration stream as root, keeping ownership and permissions
+const EXTRACT_COMMAND: &str = "tar -xpf - -C /";
+
+/// What to copy from the source distribution
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MigrationSelection {
+    /// The default user's home directory, copied into the target user's home
+    pub home: bool,
+    /// `/etc/ssh/ssh_host_*`, replacing the target's host keys
+    pub ssh_host_keys: bool,
+    /// The default user's crontab
+    pub crontab: bool,
+    /// Further absolute paths, copied to the same place
+    pub paths: Vec<String>,
+}
+
+impl Default for MigrationSelection {
+    fn default() -> Self {
+        Self {
+            home: true,
+            ssh_host_keys: false,
+            crontab: true,
+            paths: Vec::new(),
+        }
+    }
+}
+
+/// The user `wsl -d <name>` logs in as
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DistroUser {
+    pub name: String,
+    pub uid: u64,
+    pub gid: u64,
+    /// Name of the primary group
+    pub group: String,
+    pub home: String,
+}
+
+/// Why a path is part of the migration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MigrationItemKind {
+    Home,
+    SshHostKey,
+    Crontab,
+    Path,
+}
+
+/// A path that will be copied
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationItem {
+    pub kind: MigrationItemKind,
+    pub source_path: String,
+    /// Where it ends up in the target; differs for the home directory and
+    /// crontab when the users differ
+    pub target_path: String,
+    /// Apparent size of the path and everything below it
+    pub bytes: u64,
+    /// Number of files, directories and links
+    pub files: u64,
+}
+
+/// What a migration would copy
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationPreview {
+    pub source_user: DistroUser,
+    pub target_user: DistroUser,
+    pub items: Vec<MigrationItem>,
+    /// Selected paths that do not exist in the source
+    pub missing: Vec<String>,
+    pub total_bytes: u64,
+    pub total_files: u64,
+    /// Whether files owned by the source user get the target user's IDs
+    pub remaps_owner: bool,
+}
+
+/// Outcome of a migration
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationReport {
+    /// Files, directories and links copied
+    pub entries: u64,
+    /// Bytes of file data copied
+    pub bytes: u64,
+    /// Entries whose owner was changed to the target user
+    pub remapped: u64,
+}
+
+/// Default user of a distribution and where it keeps crontabs
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DistroProfile {
+    user: DistroUser,
+    cron_dir: Option<String>,
+}
+
+/// Prints the default user's name, IDs, group and home, then the first
+/// crontab spool directory that exists
+fn probe_command() -> String {
+    let dirs: Vec<String> = CRON_DIRS.iter().map(|d| shell_quote(d)).collect();
+    format!(
+        "printf '%s\\n' \"$(id -un)\" \"$(id -u)\" \"$(id -g)\" \"$(id -gn)\"; \
+         h=$(getent passwd \"$(id -u)\" 2>/dev/null | cut -d: -f6); printf '%s\\n' \"${{h:-$HOME}}\"; \
+         for d in {}; do if [ -d \"$d\" ]; then echo \"$d\"; break; fi; done",
+        dirs.join(" ")
+    )
+}
+
+fn parse_profile(distro: &str, stdout: &str) -> Result<DistroProfile, WslError> {
+    let invalid = || {
+        WslError::ParseError(format!(
+            "Could not determine the default user of '{}'",
+            distro
+        ))
+    };
+    let lines: Vec<&str> = stdout.lines().map(str::trim).collect();
+    let [name, uid, gid, group, home, rest @ ..] = lines.as_slice() else {
+        return Err(invalid());
+    };
+    // A home of `/` would migrate the whole distribution
+    if name.is_empty() || !home.starts_with('/') || normalize_entry_path(home).is_empty() {
+        return Err(invalid());
+    }
+    Ok(DistroProfile {
+        user: DistroUser {
+            name: name.to_string(),
+            uid: uid.parse().map_err(|_| invalid())?,
+            gid: gid.parse().map_err(|_| invalid())?,
+            group: group.to_string(),
+            home: format!("/{}", normalize_entry_path(home)),
+        },
+        cron_dir: rest
+            .first()
+            .filter(|d| d.starts_with('/'))
+            .map(|d| d.to_string()),
+    })
+}
+
+fn probe(distro: &str) -> Result<DistroProfile, WslError> {
+    if is_mock_mode() {
+        return parse_profile(
+            distro,
+            "alice\n1000\n1000\nalice\n/home/alice\n/var/spool/cron/crontabs\n",
+        );
+    }
+    let output = wsl_executor().exec(distro, None, &probe_command())?;
+    if !output.success {
+        return Err(WslError::CommandFailed(format!(
+            "Could not read the default user of '{}': {}",
+            distro,
+            output.stderr.trim()
+        )));
+    }
+    parse_profile(distro, &output.stdout)
+}
+
+/// Normalize a further path selected for migration
+fn selected_path(path: &str) -> Result<String, WslError> {
+    let trimmed = path.trim();
+    let invalid =
+        |reason: &str| WslError::CommandFailed(format!("Cannot migrate '{}': {}", trimmed, reason));
+    if !trimmed.starts_with('/') {
+        return Err(invalid("not an absolute Linux path"));
+    }
+    if trimmed.chars().any(char::is_control) || trimmed.split('/').any(|c| c == "..") {
+        return Err(invalid("path contains '..' or control characters"));
+    }
+    let normalized = format!("/{}", normalize_entry_path(trimmed));
+    if normalized == "/" {
+        return Err(invalid("the whole distribution cannot be migrated"));
+    }
+    if VIRTUAL_DIRS.iter().any(|d| is_within(&normalized, d)) {
+        return Err(invalid("not part of the distribution's disk"));
+    }
+    Ok(normalized)
+}
+
+/// Whether `path` is `dir` or below it
+fn is_within(path: &str, dir: &str) -> bool {
+    path == dir
+        || dir == "/"
+        || path
+            .strip_prefix(dir)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Validate a selection before anything runs
+pub fn validate_selection(selection: &MigrationSelection) -> Result<(), WslError> {
+    for path in &selection.paths {
+        selected_path(path)?;
+    }
+    if !selection.home
+        && !selection.ssh_host_keys
+        && !selection.crontab
+        && selection.paths.is_empty()
+    {
+        return Err(WslError::CommandFailed(
+            "Select at least one thing to migrate".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// A path to look up in the source, with where it goes in the target.
+/// `pattern` is a shell glob and only used for the SSH host keys.
+struct Candidate {
+    kind: MigrationItemKind,
+    source: String,
+    target: Option<String>,
+    pattern: bool,
+}
+
+fn candidates(
+    selection: &MigrationSelection,
+    source: &DistroProfile,
+    target: &DistroProfile,
+) -> Result<Vec<Candidate>, WslError> {
+    let mut candidates = Vec::new();
+    if selection.home {
+        candidates.push(Candidate {
+            kind: MigrationItemKind::Home,
+            source: source.user.home.clone(),
+            target: Some(target.user.home.clone()),
+            pattern: false,
+        });
+    }
+    if selection.ssh_host_keys {
+        candidates.push(Candidate {
+            kind: MigrationItemKind::SshHostKey,
+            source: "/etc/ssh/ssh_host_*".to_string(),
+            target: None,
+            pattern: true,
+        });
+    }
+    if selection.crontab {
+        let source_dir = source.cron_dir.as_deref().unwrap_or(CRON_DIRS[0]);
+        let target_dir = target.cron_dir.as_deref().unwrap_or(source_dir);
+        candidates.push(Candidate {
+            kind: MigrationItemKind::Crontab,
+            source: format!("{}/{}", source_dir, source.user.name),
+            target: Some(format!("{}/{}", target_dir, target.user.name)),
+            pattern: false,
+        });
+    }
+    for path in &selection.paths {
+        candidates.push(Candidate {
+            kind: MigrationItemKind::Path,
+            source: selected_path(path)?,
+            target: None,
+            pattern: false,
+        });
+    }
+    Ok(candidates)
+}
+
+/// Prints `found <index> <path> <bytes> <files>` or `missing <index> <path>`,
+/// tab-separated, for every candidate. Busybox `du` has no `-b`, so sizes
+/// fall back to whole kilobytes there.
+fn resolve_command(candidates: &[Candidate]) -> String {
+    let mut script = String::from(
+        "report() { if [ -e \"$2\" ] || [ -L \"$2\" ]; then \
+         b=$(du -sb \"$2\" 2>/dev/null | cut -f1); \
+         [ -n \"$b\" ] || b=$(( $(du -sk \"$2\" | cut -f1) * 1024 )); \
+         printf 'found\\t%s\\t%s\\t%s\\t%s\\n' \"$1\" \"$2\" \"$b\" \"$(find \"$2\" | wc -l)\"; \
+         else printf 'missing\\t%s\\t%s\\n' \"$1\" \"$2\"; fi; }\n",
+    );
+    for (index, candidate) in candidates.iter().enumerate() {
+        let path = if candidate.pattern {
+            candidate.source.clone()
+        } else {
+            shell_quote(&candidate.source)
+        };
+        script.push_str(&format!(
+            "for p in {}; do report {} \"$p\"; done\n",
+            path, index
+        ));
+    }
+    script
+}
+
+/// Turn the output of `resolve_command` into items and missing paths.
+/// Paths below another selected path are dropped, since they are copied
+/// with it.
+fn parse_resolution(
+    candidates: &[Candidate],
+    stdout: &str,
+) -> Result<(Vec<MigrationItem>, Vec<String>), WslError> {
+    let invalid = |line: &str| WslError::ParseError(format!("Unexpected size report: {}", line));
+    let mut items = Vec::new();
+    let mut missing = Vec::new();
+    for line in stdout.lines().filter(|l| !l.trim().is_empty()) {
+        let fields: Vec<&str> = line.split('\t').collect();
+        let candidate = fields
+            .get(1)
+            .and_then(|i| i.parse::<usize>().ok())
+            .and_then(|i| candidates.get(i))
+            .ok_or_else(|| invalid(line))?;
+        match fields.as_slice() {
+            ["missing", _, path] => missing.push(path.to_string()),
+            ["found", _, path, bytes, files] => {
+                let source_path = format!("/{}", normalize_entry_path(path));
+                items.push(MigrationItem {
+                    kind: candidate.kind,
+                    target_path: candidate
+                        .target
+                        .clone()
+                        .unwrap_or_else(|| source_path.clone()),
+                    source_path,
+                    bytes: bytes.trim().parse().map_err(|_| invalid(line))?,
+                    files: files.trim().parse().map_err(|_| invalid(line))?,
+                });
+            }
+            _ => return Err(invalid(line)),
+        }
+    }
+
+    let nested: Vec<bool> = items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            items.iter().enumerate().any(|(j, other)| {
+                i != j
+                    && is_within(&item.source_path, &other.source_path)
+                    && (item.source_path != other.source_path || j < i)
+            })
+        })
+        .collect();
+    let items = items
+        .into_iter()
+        .zip(nested)
+        .filter_map(|(item, nested)| (!nested).then_some(item))
+        .collect();
+    Ok((items, missing))
+}
+
+/// Everything needed to run or preview a migration
+struct MigrationPlan {
+    source: DistroUser,
+    target: DistroUser,
+    items: Vec<MigrationItem>,
+    missing: Vec<String>,
+}
+
+impl MigrationPlan {
+    fn remaps_owner(&self) -> bool {
+        self.source.uid != self.target.uid || self.source.gid != self.target.gid
+    }
+
+    /// The item an archive path belongs to and its path in the target
+    fn map(&self, path: &str) -> Option<(&MigrationItem, String)> {
+        let absolute = format!("/{}", path);
+        self.items
+            .iter()
+            .find(|item| is_within(&absolute, &item.source_path))
+            .map(|item| {
+                let rest = &absolute[item.source_path.len()..];
+                let mapped = format!("{}{}", item.target_path, rest);
+                (item, normalize_entry_path(&mapped))
+            })
+    }
+
+    /// Files owned by the source user belong to the target user. Root is
+    /// only remapped inside its own home and crontab, so system files keep
+    /// their owner when migrating from root.
+    fn remaps(&self, item: Option<&MigrationItem>) -> bool {
+        self.source.uid != 0
+            || item.is_some_and(|i| {
+                matches!(i.kind, MigrationItemKind::Home | MigrationItemKind::Crontab)
+            })
+    }
+
+    fn into_preview(self) -> MigrationPreview {
+        MigrationPreview {
+            total_bytes: self.items.iter().map(|i| i.bytes).sum(),
+            total_files: self.items.iter().map(|i| i.files).sum(),
+            remaps_owner: self.remaps_owner(),
+            source_user: self.source,
+            target_user: self.target,
+            items: self.items,
+            missing: self.missing,
+        }
+    }
+}
+
+fn plan(
+    source: &str,
+    target: &str,
+    selection: &MigrationSelection,
+) -> Result<MigrationPlan, WslError> {
+    validate_selection(selection)?;
+    if source == target {
+        return Err(WslError::CommandFailed(
+            "Source and target must be different distributions".to_string(),
+        ));
+    }
+    let installed = core::list_distributions()?;
+    for name in [source, target] {
+        if !installed.iter().any(|d| d.name == name) {
+            return Err(WslError::DistroNotFound(name.to_string()));
+        }
+    }
+
+    let source_profile = probe(source)?;
+    let target_profile = probe(target)?;
+    let candidates = candidates(selection, &source_profile, &target_profile)?;
+    let (items, missing) = if is_mock_mode() {
+        parse_resolution(&candidates, &mock_resolution(&candidates))?
+    } else {
+        let output = wsl_executor().exec_as_root(source, None, &resolve_command(&candidates))?;
+        if !output.success {
+            return Err(WslError::CommandFailed(format!(
+                "Could not measure the selected paths in '{}': {}",
+                source,
+                output.stderr.trim()
+            )));
+        }
+        parse_resolution(&candidates, &output.stdout)?
+    };
+    Ok(MigrationPlan {
+        source: source_profile.user,
+        target: target_profile.user,
+        items,
+        missing,
+    })
+}
+
+/// Show what a migration from `source` to `target` would copy and how
+/// large it is, without copying anything
+pub fn preview_migration(
+    source: &str,
+    target: &str,
+    selection: &MigrationSelection,
+) -> Result<MigrationPreview, WslError> {
+    Ok(plan(source, target, selection)?.into_preview())
+}
+
+/// Rewrite the tar stream from the source for the target: rename paths of
+/// the home directory and crontab, give files of the source user the target
+/// user's IDs and follow the renames in symlinks and hard links.
+fn remap_stream<R: Read, W: Write>(
+    input: R,
+    output: W,
+    plan: &MigrationPlan,
+    report: &mut MigrationReport,
+) -> Result<W, WslError> {
+    let read_error = |e: std::io::Error| {
+        WslError::CommandFailed(format!("Failed to read the source files: {}", e))
+    };
+    let write_error = |e: std::io::Error| match e.kind() {
+        // The target stopped reading; its exit status and stderr say why
+        std::io::ErrorKind::BrokenPipe => WslError::IoError(e),
+        _ => WslError::CommandFailed(format!("Failed to write the target files: {}", e)),
+    };
+    let mut archive = Archive::new(input);
+    let mut builder = tar::Builder::new(output);
+    // Hard link targets that were renamed
+    let mut renamed: HashMap<String, String> = HashMap::new();
+
+    for entry in archive.entries().map_err(read_error)? {
+        let mut entry = entry.map_err(read_error)?;
+        let path = normalize_entry_path(&entry.path().map_err(read_error)?.to_string_lossy());
+        let (item, target) = match plan.map(&path) {
+            Some((item, target)) => (Some(item), target),
+            None => (None, path.clone()),
+        };
+        let entry_type = entry.header().entry_type();
+        let link = match entry_type {
+            EntryType::Symlink => {
+                let link = entry.link_name().map_err(read_error)?.unwrap_or_default();
+                let link = link.to_string_lossy();
+                Some(match link.strip_prefix('/').and_then(|l| plan.map(l)) {
+                    Some((_, mapped)) => format!("/{}", mapped),
+                    None => link.into_owned(),
+                })
+            }
+            EntryType::Link => {
+                let linked = normalize_entry_path(
+                    &entry
+                        .link_name()
+                        .map_err(read_error)?
+                        .unwrap_or_default()
+                        .to_string_lossy(),
+                );
+                Some(renamed.get(&linked).cloned().unwrap_or(linked))
+            }
+            _ => None,
+        };
+
+        let mut header = archive::copy_header(entry.header()).map_err(read_error)?;
+        if plan.remaps(item) && remap_owner(&mut header, &plan.source, &plan.target) {
+            report.remapped += 1;
+        }
+        let xattrs = archive::xattrs(&mut entry).map_err(read_error)?;
+        if !xattrs.is_empty() {
+            builder
+                .append_pax_extensions(xattrs.iter().map(|(k, v)| (k.as_str(), v.as_slice())))
+                .map_err(write_error)?;
+        }
+        match link {
+            Some(link) => {
+                header.set_size(0);
+                builder
+                    .append_link(&mut header, &target, &link)
+                    .map_err(write_error)?;
+            }
+            None => {
+                if matches!(entry_type, EntryType::Regular | EntryType::Continuous) {
+                    report.bytes += entry.size();
+                }
+                builder
+                    .append_data(&mut header, &target, &mut entry)
+                    .map_err(write_error)?;
+            }
+        }
+        report.entries += 1;
+        if target != path {
+            renamed.insert(path, target);
+        }
+    }
+
+    builder.into_inner().map_err(write_error)
+}
+
+/// Give a member owned by `source` to `target`; returns whether it changed
+fn remap_owner(header: &mut Header, source: &DistroUser, target: &DistroUser) -> bool {
+    let mut changed = false;
+    if header.uid().ok() == Some(source.uid) {
+        changed |= source.uid != target.uid;
+        header.set_uid(target.uid);
+        let _ = header.set_username(&target.name);
+    }
+    if header.gid().ok() == Some(source.gid) {
+        changed |= source.gid != target.gid;
+        header.set_gid(target.gid);
+        let _ = header.set_groupname(&target.group);
+    }
+    changed
+}
+
+/// Copy the selected data from `source` into `target`. Files that exist in
+/// the target are overwritten.
+pub fn migrate_user_data(
+    source: &str,
+    target: &str,
+    selection: &MigrationSelection,
+) -> Result<MigrationReport, WslError> {
+    let plan = plan(source, target, selection)?;
+    if plan.items.is_empty() {
+        return Err(WslError::CommandFailed(format!(
+            "None of the selected paths exist in '{}'",
+            source
+        )));
+    }
+    let paths: Vec<String> = plan
+        .items
+        .iter()
+        .map(|item| shell_quote(item.source_path.trim_start_matches('/')))
+        .collect();
+    let create = format!("tar -cf - -C / {}", paths.join(" "));
+
+    let (reader, mut writer) = std::io::pipe()?;
+    let (extracted, remapped, created, report) = std::thread::scope(|scope| {
+        let plan = &plan;
+        let extracting = scope.spawn(move || {
+            let mut report = MigrationReport::default();
+            let mut remapped = Ok(());
+            let mut reader = Some(reader);
+            let extracted = wsl_executor().exec_as_root_with_input(
+                target,
+                None,
+                EXTRACT_COMMAND,
+                &mut |stdin| {
+                    let mut input =
+                        BufReader::new(reader.take().expect("the stream is only read once"));
+                    remapped = remap_stream(&mut input, stdin, plan, &mut report).map(|_| ());
+                    match &remapped {
+                        // Drain the padding after the end-of-archive marker
+                        // so the source tar does not fail on a closed pipe
+                        Ok(()) => std::io::copy(&mut input, &mut std::io::sink()).map(|_| ()),
+                        Err(WslError::IoError(e)) if e.kind() == std::io::ErrorKind::BrokenPipe => {
+                            Err(std::io::ErrorKind::BrokenPipe.into())
+                        }
+                        Err(_) => Err(std::io::Error::other("the source stream was not rewritten")),
+                    }
+                },
+            );
+            (extracted, remapped, report)
+        });
+        let created = wsl_executor().exec_as_root_to_writer(source, None, &create, &mut writer);
+        // Closing the pipe ends the stream for the target
+        drop(writer);
+        let (extracted, remapped, report) = extracting
+            .join()
+            .map_err(|_| WslError::CommandFailed("Migration stopped unexpectedly".to_string()))?;
+        Ok::<_, WslError>((extracted, remapped, created, report))
+    })?;
+
+    // A failing side breaks the pipe for the other one, so report the side
+    // that failed on its own first
+    if let Ok(created) = &created {
+        if !created.success {
+            return Err(WslError::CommandFailed(format!(
+                "Failed to read the selected files in '{}': {}",
+                source,
+                created.stderr.trim()
+            )));
+        }
+    }
+    match remapped {
+        // The target stopped reading; its own status below says why
+        Err(WslError::IoError(e)) if e.kind() == std::io::ErrorKind::BrokenPipe => {}
+        Err(e) => return Err(e),
+        Ok(()) => {}
+    }
+    let extracted = extracted?;
+    if !extracted.success {
+        return Err(WslError::CommandFailed(format!(
+            "Failed to copy the files into '{}': {}",
+            target,
+            extracted.stderr.trim()
+        )));
+    }
+    created?;
+
+    info!(
+        "Migrated {} entries ({} bytes) from '{}' to '{}', {} remapped to {}",
+        report.entries, report.bytes, source, target, report.remapped, plan.target.name
+    );
+    Ok(report)
+}
+
+/// Canned sizes for mock mode: every candidate exists except the crontab
+fn mock_resolution(candidates: &[Candidate]) -> String {
+    candidates
+        .iter()
+        .enumerate()
+        .map(|(index, candidate)| match candidate.kind {
+            MigrationItemKind::Crontab => format!("missing\t{}\t{}\n", index, candidate.source),
+            MigrationItemKind::SshHostKey => format!(
+                "found\t{0}\t/etc/ssh/ssh_host_ed25519_key\t411\t1\nfound\t{0}\t/etc/ssh/ssh_host_ed25519_key.pub\t103\t1\n",
+                index
+            ),
+            _ => format!("found\t{}\t{}\t52428800\t1200\n", index, candidate.source),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(name: &str, uid: u64) -> DistroUser {
+        DistroUser {
+            name: name.to_string(),
+            uid,
+            gid: uid,
+            group: name.to_string(),
+            home: if uid == 0 {
+                "/root".to_string()
+            } else {
+                format!("/home/{}", name)
+            },
+        }
+    }
+
+    fn profile(name: &str, uid: u64, cron_dir: Option<&str>) -> DistroProfile {
+        DistroProfile {
+            user: user(name, uid),
+            cron_dir: cron_dir.map(str::to_string),
+        }
+    }
+
+    fn header(entry_type: EntryType, size: u64, uid: u64, name: &str) -> Header {
+        let mut header = Header::new_gnu();
+        header.set_entry_type(entry_type);
+        header.set_size(size);
+        header.set_mode(if entry_type == EntryType::Directory {
+            0o755
+        } else {
+            0o644
+        });
+        header.set_uid(uid);
+        header.set_gid(uid);
+        header.set_username(name).unwrap();
+        header.set_groupname(name).unwrap();
+        header
+    }
+
+    #[test]
+    fn profile_is_parsed_from_probe_output() {
+        let parsed = parse_profile(
+            "Ubuntu",
+            "alice\n1000\n1000\nalice\n/home/alice/\n/etc/crontabs\n",
+        )
+        .unwrap();
+        assert_eq!(parsed, profile("alice", 1000, Some("/etc/crontabs")));
+
+        let no_cron = parse_profile("Ubuntu", "root\n0\n0\nroot\n/root\n").unwrap();
+        assert_eq!(no_cron, profile("root", 0, None));
+
+        assert!(parse_profile("Ubuntu", "alice\n1000\n").is_err());
+        assert!(parse_profile("Ubuntu", "nobody\n65534\n65534\nnogroup\n/\n").is_err());
+        assert!(parse_profile("Ubuntu", "alice\nx\n1000\nalice\n/home/alice\n").is_err());
+    }
+
+    #[test]
+    fn selection_rejects_unsafe_paths() {
+        assert_eq!(selected_path(" /opt/tools/ ").unwrap(), "/opt/tools");
+        assert!(selected_path("opt/tools").is_err());
+        assert!(selected_path("/").is_err());
+        assert!(selected_path("/home/../etc").is_err());
+        assert!(selected_path("/proc/1").is_err());
+        assert!(selected_path("/opt/a\nb").is_err());
+        assert_eq!(selected_path("/proceed").unwrap(), "/proceed");
+
+        let nothing = MigrationSelection {
+            home: false,
+            crontab: false,
+            ..Default::default()
+        };
+        assert!(validate_selection(&nothing).is_err());
+        assert!(validate_selection(&MigrationSelection::default()).is_ok());
+    }
+
+    #[test]
+    fn resolution_maps_home_and_crontab_to_the_target_user() {
+        let selection = MigrationSelection {
+            ssh_host_keys: true,
+            paths: vec!["/home/alice/.ssh".to_string(), "/opt/tools".to_string()],
+            ..Default::default()
+        };
+        let candidates = candidates(
+            &selection,
+            &profile("alice", 1000, Some("/var/spool/cron/crontabs")),
+            &profile("bob", 1001, None),
+        )
+        .unwrap();
+        let script = resolve_command(&candidates);
+        assert!(script.contains("for p in /etc/ssh/ssh_host_*; do report 1"));
+        assert!(script.contains("for p in '/home/alice'; do report 0"));
+
+        let stdout = "found\t0\t/home/alice\t52428800\t1200\n\
+                      found\t1\t/etc/ssh/ssh_host_rsa_key\t2602\t1\n\
+                      found\t2\t/var/spool/cron/crontabs/alice\t  96\t      1\n\
+                      found\t3\t/home/alice/.ssh\t4096\t3\n\
+                      missing\t4\t/opt/tools\n";
+        let (items, missing) = parse_resolution(&candidates, stdout).unwrap();
+        assert_eq!(missing, vec!["/opt/tools"]);
+        let paths: Vec<(&str, &str)> = items
+            .iter()
+            .map(|i| (i.source_path.as_str(), i.target_path.as_str()))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                ("/home/alice", "/home/bob"),
+                ("/etc/ssh/ssh_host_rsa_key", "/etc/ssh/ssh_host_rsa_key"),
+                (
+                    "/var/spool/cron/crontabs/alice",
+                    "/var/spool/cron/crontabs/bob"
+                ),
+            ]
+        );
+        assert_eq!(items[2].bytes, 96);
+        assert!(parse_resolution(&candidates, "found\t9\t/x\t1\t1\n").is_err());
+    }
+
+    #[test]
+    fn stream_is_renamed_and_reowned_for_the_target_user() {
+        let plan = MigrationPlan {
+            source: user("alice", 1000),
+            target: user("bob", 1001),
+            items: vec![
+                MigrationItem {
+                    kind: MigrationItemKind::Home,
+                    source_path: "/home/alice".to_string(),
+                    target_path: "/home/bob".to_string(),
+                    bytes: 0,
+                    files: 0,
+                },
+                MigrationItem {
+                    kind: MigrationItemKind::SshHostKey,
+                    source_path: "/etc/ssh/ssh_host_rsa_key".to_string(),
+                    target_path: "/etc/ssh/ssh_host_rsa_key".to_string(),
+                    bytes: 0,
+                    files: 0,
+                },
+            ],
+            missing: Vec::new(),
+        };
+
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut dir = header(EntryType::Directory, 0, 1000, "alice");
+        builder
+            .append_data(&mut dir, "home/alice/", std::io::empty())
+            .unwrap();
+        let mut file = header(EntryType::Regular, 5, 1000, "alice");
+        builder
+            .append_data(&mut file, "home/alice/.bashrc", &b"ls -l"[..])
+            .unwrap();
+        let mut link = header(EntryType::Symlink, 0, 1000, "alice");
+        builder
+            .append_link(&mut link, "home/alice/rc", "/home/alice/.bashrc")
+            .unwrap();
+        let mut hard = header(EntryType::Link, 0, 1000, "alice");
+        builder
+            .append_link(&mut hard, "home/alice/rc2", "home/alice/.bashrc")
+            .unwrap();
+        let mut key = header(EntryType::Regular, 3, 0, "root");
+        builder
+            .append_data(&mut key, "etc/ssh/ssh_host_rsa_key", &b"key"[..])
+            .unwrap();
+        let input = builder.into_inner().unwrap();
+
+        let mut report = MigrationReport::default();
+        let output = remap_stream(input.as_slice(), Vec::new(), &plan, &mut report).unwrap();
+        assert_eq!(
+            report,
+            MigrationReport {
+                entries: 5,
+                bytes: 8,
+                remapped: 4
+            }
+        );
+
+        let mut archive = Archive::new(output.as_slice());
+        let members: Vec<(String, u64, String, Option<String>)> = archive
+            .entries()
+            .unwrap()
+            .map(|e| {
+                let e = e.unwrap();
+                (
+                    normalize_entry_path(&e.path().unwrap().to_string_lossy()),
+                    e.header().uid().unwrap(),
+                    e.header().username().unwrap().unwrap().to_string(),
+                    e.link_name()
+                        .unwrap()
+                        .map(|l| l.to_string_lossy().into_owned()),
+                )
+            })
+            .collect();
+        assert_eq!(
+            members,
+            vec![
+                ("home/bob".to_string(), 1001, "bob".to_string(), None),
+                (
+                    "home/bob/.bashrc".to_string(),
+                    1001,
+                    "bob".to_string(),
+                    None
+                ),
+                (
+                    "home/bob/rc".to_string(),
+                    1001,
+                    "bob".to_string(),
+                    Some("/home/bob/.bashrc".to_string())
+                ),
+                (
+                    "home/bob/rc2".to_string(),
+                    1001,
+                    "bob".to_string(),
+                    Some("home/bob/.bashrc".to_string())
+                ),
+                (
+                    "etc/ssh/ssh_host_rsa_key".to_string(),
+                    0,
+                    "root".to_string(),
+                    None
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn mock_preview_and_migration() {
+        let preview =
+            preview_migration("Ubuntu", "Debian", &MigrationSelection::default()).unwrap();
+        assert_eq!(preview.items.len(), 1);
+        assert_eq!(preview.items[0].kind, MigrationItemKind::Home);
+        assert_eq!(preview.missing, vec!["/var/spool/cron/crontabs/alice"]);
+        assert_eq!(preview.total_bytes, 52428800);
+        assert!(!preview.remaps_owner);
+
+        assert!(migrate_user_data("Ubuntu", "Debian", &MigrationSelection::default()).is_ok());
+        assert!(preview_migration("Ubuntu", "Ubuntu", &MigrationSelection::default()).is_err());
+        assert!(matches!(
+            preview_migration("Ubuntu", "Missing", &MigrationSelection::default()),
+            Err(WslError::DistroNotFound(_))
+        ));
+    }
+}
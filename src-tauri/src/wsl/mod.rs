@@ -4,20 +4,43 @@
 //! (WSL) distributions, including:
 //! - Core operations: list, start, stop, delete, restart, update
 //! - Terminal and IDE integration
-//! - Import/export operations
-//! - Distribution installation
+//! - Import/export operations, including `.wsl` distribution packages and
+//!   passphrase- or key-encrypted archives
+//! - Include and exclude filters for exports, clones and backups
+//! - Point-in-time snapshots with in-place or side-by-side restore
+//! - Scheduled, compressed backups with grandfather-father-son retention
+//!   to local folders, network shares or S3-compatible storage
+//! - A deduplicated, chunked backup repository format
+//! - Browsing export and backup archives and restoring individual files
+//! - Migrating user data such as home directories between distributions
+//! - Rootfs archive inspection and pre-import customization
+//! - Distribution installation, first-boot user provisioning and cloud-config
 //! - Disk and OS information
 //! - Resource monitoring
 //! - Version information
 
+mod archive;
+pub mod archive_files;
+pub mod backup_repository;
+pub mod backup_targets;
+pub mod backups;
+pub mod cloud_init;
 mod core;
+pub mod customize;
+pub mod distribution_conf;
 pub mod distro_sources;
+pub mod encryption;
 pub mod executor;
+pub mod export_filter;
 mod import_export;
 mod info;
 mod install;
+pub mod migrate;
+pub mod provision;
 pub mod resources;
+pub mod rootfs_inspect;
 mod service;
+pub mod snapshots;
 mod terminal;
 mod types;
 
@@ -33,6 +56,9 @@ pub use info::{SystemDistroInfo, VhdSizeInfo, WslVersionInfo};
 // Re-export terminal types
 pub use executor::terminal::InstalledTerminal;
 
+// Re-export package import types
+pub use import_export::{CloneResult, ImportedPackage};
+
 // Re-export service for backward compatibility
 pub use service::WslService;
 
@@ -0,0 +1,531 @@
+//! First-boot provisioning
+//!
+//! Creates the default user of a freshly installed distribution without the
+//! interactive OOBE: account, supplementary groups, sudo policy and password
+//! hash are applied with `exec_as_root`, the user is made the default in
+//! `/etc/wsl.conf`, and the result is verified. Commands fall back between
+//! shadow-utils (`useradd`), Debian `adduser` and BusyBox so the same spec
+//! works on Ubuntu, Fedora, Arch and Alpine alike.
+
+use std::collections::HashMap;
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use super::executor::wsl_executor;
+use super::types::WslError;
+use crate::settings;
+use crate::utils::is_mock_mode;
+
+/// Who may use `sudo` and how
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SudoPolicy {
+    /// No sudoers entry
+    None,
+    /// `ALL=(ALL:ALL) ALL`, asks for the user's password
+    #[default]
+    Password,
+    /// `ALL=(ALL:ALL) NOPASSWD: ALL`
+    NoPassword,
+}
+
+/// The default user to create after install
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProvisioningSpec {
+    pub username: String,
+    /// Defaults to the distribution's next free UID (usually 1000)
+    pub uid: Option<u32>,
+    /// Supplementary groups, created when missing
+    pub groups: Vec<String>,
+    pub sudo: SudoPolicy,
+    /// crypt(3) hash such as `$6$...` (from `openssl passwd -6`). Without one
+    /// the account has no usable password, which suits `NoPassword` sudo.
+    pub password_hash: Option<String>,
+    /// Login shell; defaults to `/bin/bash`, or `/bin/sh` when bash is missing
+    pub shell: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProvisionStepKind {
+    CreateUser,
+    Groups,
+    Sudo,
+    Password,
+    DefaultUser,
+    Verify,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProvisionStep {
+    pub kind: ProvisionStepKind,
+    pub success: bool,
+    /// Not run because an earlier step failed
+    pub skipped: bool,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProvisioningReport {
+    pub distro_name: String,
+    pub username: String,
+    pub steps: Vec<ProvisionStep>,
+    pub success: bool,
+}
+
+/// Check a Linux user or group name: lowercase letter first, then lowercase
+/// letters, digits, `_` or `-`, at most 32 characters.
+pub(crate) fn validate_account_name(label: &str, name: &str) -> Result<(), WslError> {
+    let invalid = |msg: String| Err(WslError::CommandFailed(msg));
+    match name.chars().next() {
+        None => return invalid(format!("{} cannot be empty", label)),
+        Some(c) if !c.is_ascii_lowercase() => {
+            return invalid(format!("{} must start with a lowercase letter", label))
+        }
+        _ => {}
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+    {
+        return invalid(format!(
+            "{} can only contain lowercase letters, digits, underscores, and hyphens",
+            label
+        ));
+    }
+    if name.len() > 32 {
+        return invalid(format!("{} must be at most 32 characters", label));
+    }
+    Ok(())
+}
+
+impl ProvisioningSpec {
+    pub fn validate(&self) -> Result<(), WslError> {
+        let invalid = |msg: String| Err(WslError::CommandFailed(msg));
+        validate_account_name("Username", &self.username)?;
+        if self.username == "root" {
+            return invalid("Cannot provision the root user".to_string());
+        }
+        for group in &self.groups {
+            validate_account_name("Group name", group)?;
+        }
+        if let Some(uid) = self.uid {
+            if !(1..=60000).contains(&uid) {
+                return invalid(format!("UID {} must be between 1 and 60000", uid));
+            }
+        }
+        if let Some(hash) = &self.password_hash {
+            let well_formed = hash.starts_with('$')
+                && hash.len() > 3
+                && hash
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "$./=,-".contains(c));
+            if !well_formed {
+                return invalid("Password must be a crypt hash such as $6$...".to_string());
+            }
+        }
+        if let Some(shell) = &self.shell {
+            if !shell.starts_with('/')
+                || !shell
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "/._-".contains(c))
+            {
+                return invalid(format!("Invalid shell '{}'", shell));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Create the account unless it already exists.
+fn create_user_script(spec: &ProvisioningSpec) -> String {
+    let user = &spec.username;
+    let shell = match &spec.shell {
+        Some(shell) => format!(
+            "SHELL_PATH='{shell}'\n[ -x \"$SHELL_PATH\" ] || {{ echo \"$SHELL_PATH not found\" >&2; exit 1; }}"
+        ),
+        None => "SHELL_PATH=/bin/bash\n[ -x \"$SHELL_PATH\" ] || SHELL_PATH=/bin/sh".to_string(),
+    };
+    let (uid_short, uid_long) = match spec.uid {
+        Some(uid) => (format!(" -u {uid}"), format!(" --uid {uid}")),
+        None => (String::new(), String::new()),
+    };
+    format!(
+        r#"set -e
+if id -u '{user}' >/dev/null 2>&1; then echo "User {user} already exists"; exit 0; fi
+{shell}
+if command -v useradd >/dev/null 2>&1; then
+  useradd -m -s "$SHELL_PATH"{uid_short} '{user}'
+elif adduser --help 2>&1 | grep -qi busybox; then
+  adduser -D -s "$SHELL_PATH"{uid_short} '{user}'
+else
+  adduser --disabled-password --gecos '' --shell "$SHELL_PATH"{uid_long} '{user}'
+fi
+echo "Created {user} ($(id -u '{user}'))""#
+    )
+}
+
+/// Create missing groups and add the user to each.
+fn groups_script(spec: &ProvisioningSpec) -> String {
+    let user = &spec.username;
+    let mut script = String::from("set -e\n");
+    for group in &spec.groups {
+        script.push_str(&format!(
+            r#"if ! grep -q '^{group}:' /etc/group; then
+  if command -v groupadd >/dev/null 2>&1; then groupadd '{group}'; else addgroup '{group}'; fi
+fi
+if command -v usermod >/dev/null 2>&1; then usermod -aG '{group}' '{user}'; else addgroup '{user}' '{group}'; fi
+"#
+        ));
+    }
+    script.push_str(&format!("echo \"Groups: $(id -nG '{user}')\""));
+    script
+}
+
+/// Write `/etc/sudoers.d/<user>`, checked with `visudo` where available.
+fn sudo_script(spec: &ProvisioningSpec) -> String {
+    let user = &spec.username;
+    let rule = match spec.sudo {
+        SudoPolicy::NoPassword => "ALL=(ALL:ALL) NOPASSWD: ALL",
+        _ => "ALL=(ALL:ALL) ALL",
+    };
+    format!(
+        r#"set -e
+command -v sudo >/dev/null 2>&1 || {{ echo "sudo is not installed" >&2; exit 1; }}
+mkdir -p /etc/sudoers.d
+printf '%s\n' '{user} {rule}' > /etc/sudoers.d/{user}
+chmod 0440 /etc/sudoers.d/{user}
+if command -v visudo >/dev/null 2>&1 && ! visudo -cf /etc/sudoers.d/{user} >/dev/null; then
+  rm -f /etc/sudoers.d/{user}; echo "visudo rejected the sudoers entry" >&2; exit 1
+fi
+echo "{user} {rule}""#
+    )
+}
+
+/// Set the password hash; BusyBox and shadow-utils both accept `chpasswd -e`.
+fn password_script(spec: &ProvisioningSpec, hash: &str) -> String {
+    let user = &spec.username;
+    format!(
+        r#"set -e
+if command -v chpasswd >/dev/null 2>&1; then
+  printf '%s\n' '{user}:{hash}' | chpasswd -e
+else
+  usermod -p '{hash}' '{user}'
+fi
+echo "Password set""#
+    )
+}
+
+/// Print `key=value` facts about the user for [`verify`].
+fn verify_script(spec: &ProvisioningSpec) -> String {
+    let user = &spec.username;
+    format!(
+        r#"echo "uid=$(id -u '{user}' 2>/dev/null)"
+echo "shell=$(grep '^{user}:' /etc/passwd | cut -d: -f7)"
+echo "home=$(grep '^{user}:' /etc/passwd | cut -d: -f6)"
+echo "groups=$(id -nG '{user}' 2>/dev/null)"
+[ -f /etc/sudoers.d/{user} ] && echo "sudoers=yes" || echo "sudoers=no"
+grep -q '^{user}:[$]' /etc/shadow 2>/dev/null && echo "password=yes" || echo "password=no""#
+    )
+}
+
+/// Compare the facts printed by [`verify_script`] with the spec.
+fn verify(spec: &ProvisioningSpec, output: &str) -> Result<String, String> {
+    let facts: HashMap<&str, &str> = output
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(k, v)| (k.trim(), v.trim()))
+        .collect();
+    let fact = |key: &str| facts.get(key).copied().unwrap_or_default();
+
+    let mut problems = Vec::new();
+    if fact("uid").is_empty() {
+        return Err(format!("User {} does not exist", spec.username));
+    }
+    if let Some(uid) = spec.uid {
+        if fact("uid") != uid.to_string() {
+            problems.push(format!("UID is {} instead of {}", fact("uid"), uid));
+        }
+    }
+    if let Some(shell) = &spec.shell {
+        if fact("shell") != shell {
+            problems.push(format!("shell is {} instead of {}", fact("shell"), shell));
+        }
+    }
+    let groups: Vec<&str> = fact("groups").split_whitespace().collect();
+    for group in &spec.groups {
+        if !groups.contains(&group.as_str()) {
+            problems.push(format!("not in group {}", group));
+        }
+    }
+    if (spec.sudo != SudoPolicy::None) != (fact("sudoers") == "yes") {
+        problems.push("sudoers entry does not match the sudo policy".to_string());
+    }
+    if spec.password_hash.is_some() && fact("password") != "yes" {
+        problems.push("password is not set".to_string());
+    }
+
+    if problems.is_empty() {
+        Ok(format!(
+            "uid {}, shell {}, home {}, groups {}",
+            fact("uid"),
+            fact("shell"),
+            fact("home"),
+            fact("groups")
+        ))
+    } else {
+        Err(problems.join("; "))
+    }
+}
+
+/// Run a shell script as root; returns stdout on success and stderr (or
+/// stdout when stderr is empty) on failure.
+pub(crate) fn exec_root_script(distro: &str, script: &str) -> Result<String, String> {
+    match wsl_executor().exec_as_root(distro, None, script) {
+        Ok(output) if output.success => Ok(output.stdout.trim().to_string()),
+        Ok(output) => {
+            let detail = if output.stderr.trim().is_empty() {
+                output.stdout
+            } else {
+                output.stderr
+            };
+            Err(detail.trim().to_string())
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Run one shell step as root and turn the outcome into a report line.
+fn run_step(distro: &str, kind: ProvisionStepKind, script: &str) -> ProvisionStep {
+    let (success, message) = match exec_root_script(distro, script) {
+        Ok(stdout) => (true, stdout),
+        Err(detail) => (false, detail),
+    };
+    ProvisionStep {
+        kind,
+        success,
+        skipped: false,
+        message,
+    }
+}
+
+/// Make the user the default in `/etc/wsl.conf` and restart the distribution
+/// so the next launch picks it up.
+fn set_default_user(distro: &str, username: &str) -> ProvisionStep {
+    let result = settings::read_wsl_conf(distro, None).and_then(|mut conf| {
+        conf.user_default = Some(username.to_string());
+        settings::write_wsl_conf(distro, conf)
+    });
+    if result.is_ok() {
+        if let Err(e) = wsl_executor().terminate(distro) {
+            warn!("Could not terminate '{}' after provisioning: {}", distro, e);
+        }
+    }
+    ProvisionStep {
+        kind: ProvisionStepKind::DefaultUser,
+        success: result.is_ok(),
+        skipped: false,
+        message: result
+            .map(|_| format!("[user] default={}", username))
+            .unwrap_or_else(|e| e),
+    }
+}
+
+/// Steps that apply to `spec`, in order.
+fn planned_steps(spec: &ProvisioningSpec, make_default: bool) -> Vec<ProvisionStepKind> {
+    let mut steps = vec![ProvisionStepKind::CreateUser];
+    if !spec.groups.is_empty() {
+        steps.push(ProvisionStepKind::Groups);
+    }
+    if spec.sudo != SudoPolicy::None {
+        steps.push(ProvisionStepKind::Sudo);
+    }
+    if spec.password_hash.is_some() {
+        steps.push(ProvisionStepKind::Password);
+    }
+    if make_default {
+        steps.push(ProvisionStepKind::DefaultUser);
+    }
+    steps.push(ProvisionStepKind::Verify);
+    steps
+}
+
+/// Apply `spec` to an installed distribution. Invalid specs are rejected
+/// up front; failures while running are reported per step, and once one step
+/// fails the rest are marked skipped.
+pub fn provision_distribution(
+    distro: &str,
+    spec: &ProvisioningSpec,
+) -> Result<ProvisioningReport, WslError> {
+    spec.validate()?;
+    info!("Provisioning user '{}' in '{}'", spec.username, distro);
+
+    let steps = apply_spec(distro, spec, true);
+    let success = steps.iter().all(|s| s.success);
+    Ok(ProvisioningReport {
+        distro_name: distro.to_string(),
+        username: spec.username.clone(),
+        steps,
+        success,
+    })
+}
+
+/// Run the steps for an already validated `spec`; the default-user step is
+/// only included when `make_default` is set.
+pub(crate) fn apply_spec(
+    distro: &str,
+    spec: &ProvisioningSpec,
+    make_default: bool,
+) -> Vec<ProvisionStep> {
+    let mut steps: Vec<ProvisionStep> = Vec::new();
+    for kind in planned_steps(spec, make_default) {
+        if steps.iter().any(|s| !s.success) {
+            steps.push(ProvisionStep {
+                kind,
+                success: false,
+                skipped: true,
+                message: String::new(),
+            });
+            continue;
+        }
+        if is_mock_mode() {
+            steps.push(ProvisionStep {
+                kind,
+                success: true,
+                skipped: false,
+                message: "Mock: done".to_string(),
+            });
+            continue;
+        }
+
+        let step = match kind {
+            ProvisionStepKind::CreateUser => run_step(distro, kind, &create_user_script(spec)),
+            ProvisionStepKind::Groups => run_step(distro, kind, &groups_script(spec)),
+            ProvisionStepKind::Sudo => run_step(distro, kind, &sudo_script(spec)),
+            ProvisionStepKind::Password => {
+                let hash = spec.password_hash.as_deref().unwrap_or_default();
+                run_step(distro, kind, &password_script(spec, hash))
+            }
+            ProvisionStepKind::DefaultUser => set_default_user(distro, &spec.username),
+            ProvisionStepKind::Verify => {
+                let mut step = run_step(distro, kind, &verify_script(spec));
+                if step.success {
+                    match verify(spec, &step.message) {
+                        Ok(summary) => step.message = summary,
+                        Err(problems) => {
+                            step.success = false;
+                            step.message = problems;
+                        }
+                    }
+                }
+                step
+            }
+        };
+        if !step.success {
+            warn!(
+                "Provisioning step {:?} failed for '{}': {}",
+                kind, distro, step.message
+            );
+        }
+        steps.push(step);
+    }
+    steps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec() -> ProvisioningSpec {
+        ProvisioningSpec {
+            username: "dev".to_string(),
+            uid: Some(1001),
+            groups: vec!["docker".to_string(), "wheel".to_string()],
+            sudo: SudoPolicy::NoPassword,
+            password_hash: Some("$6$salt$abc./def".to_string()),
+            shell: Some("/bin/zsh".to_string()),
+        }
+    }
+
+    #[test]
+    fn validates_spec() {
+        assert!(spec().validate().is_ok());
+        let bad = |f: fn(&mut ProvisioningSpec)| {
+            let mut s = spec();
+            f(&mut s);
+            s.validate().is_err()
+        };
+        assert!(bad(|s| s.username = "Dev".to_string()));
+        assert!(bad(|s| s.username = "root".to_string()));
+        assert!(bad(|s| s.username = "a'b".to_string()));
+        assert!(bad(|s| s.groups.push("sudo;rm".to_string())));
+        assert!(bad(|s| s.uid = Some(0)));
+        assert!(bad(|s| s.password_hash = Some("hunter2".to_string())));
+        assert!(bad(
+            |s| s.password_hash = Some("$6$x$y' ; rm -rf /".to_string())
+        ));
+        assert!(bad(|s| s.shell = Some("bash".to_string())));
+    }
+
+    #[test]
+    fn scripts_cover_all_account_tools() {
+        let create = create_user_script(&spec());
+        assert!(create.contains("useradd -m -s \"$SHELL_PATH\" -u 1001 'dev'"));
+        assert!(create.contains("adduser -D -s \"$SHELL_PATH\" -u 1001 'dev'"));
+        assert!(create.contains(
+            "adduser --disabled-password --gecos '' --shell \"$SHELL_PATH\" --uid 1001 'dev'"
+        ));
+
+        let groups = groups_script(&spec());
+        assert!(groups.contains("usermod -aG 'docker' 'dev'"));
+        assert!(groups.contains("addgroup 'dev' 'wheel'"));
+
+        assert!(sudo_script(&spec()).contains("'dev ALL=(ALL:ALL) NOPASSWD: ALL'"));
+        let password_sudo = ProvisioningSpec {
+            sudo: SudoPolicy::Password,
+            ..spec()
+        };
+        assert!(sudo_script(&password_sudo).contains("'dev ALL=(ALL:ALL) ALL'"));
+    }
+
+    #[test]
+    fn verify_reports_mismatches() {
+        let ok = "uid=1001\nshell=/bin/zsh\nhome=/home/dev\ngroups=dev docker wheel\nsudoers=yes\npassword=yes\n";
+        assert_eq!(
+            verify(&spec(), ok).unwrap(),
+            "uid 1001, shell /bin/zsh, home /home/dev, groups dev docker wheel"
+        );
+
+        let wrong = "uid=1000\nshell=/bin/sh\ngroups=dev\nsudoers=no\npassword=no\n";
+        let problems = verify(&spec(), wrong).unwrap_err();
+        assert!(problems.contains("UID is 1000 instead of 1001"));
+        assert!(problems.contains("not in group docker"));
+        assert!(problems.contains("sudoers entry"));
+        assert!(problems.contains("password is not set"));
+
+        assert!(verify(&spec(), "uid=\n").is_err());
+    }
+
+    #[test]
+    fn plans_only_requested_steps() {
+        let minimal = ProvisioningSpec {
+            username: "dev".to_string(),
+            sudo: SudoPolicy::None,
+            ..Default::default()
+        };
+        assert_eq!(
+            planned_steps(&minimal, true),
+            vec![
+                ProvisionStepKind::CreateUser,
+                ProvisionStepKind::DefaultUser,
+                ProvisionStepKind::Verify
+            ]
+        );
+        assert_eq!(planned_steps(&spec(), true).len(), 6);
+        assert!(!planned_steps(&spec(), false).contains(&ProvisionStepKind::DefaultUser));
+    }
+}
@@ -0,0 +1,616 @@
+//! Rootfs archive inspection
+//!
+//! Streams a rootfs tarball (local file or HTTP download) once, without
+//! extracting it, and reports what it contains: the `/etc/os-release`
+//! identity, package manager, init system, size, and the WSL configuration
+//! files it ships. The report also suggests a distribution name and logo and
+//! flags archives that are unlikely to boot under WSL.
+
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tar::EntryType;
+
+use super::archive;
+use super::distribution_conf::{parse_distribution_conf, DISTRIBUTION_CONF_PATH};
+use super::types::WslError;
+
+/// `os-release` files larger than this are ignored.
+const MAX_OS_RELEASE_BYTES: u64 = 64 * 1024;
+
+/// Distinct top-level directories tracked for the nested-root check.
+const MAX_TOP_LEVEL_ENTRIES: usize = 64;
+
+const WSL_CONF_PATH: &str = "etc/wsl.conf";
+const OS_RELEASE_PATHS: [&str; 2] = ["etc/os-release", "usr/lib/os-release"];
+const BIN_DIRS: [&str; 4] = ["bin", "sbin", "usr/bin", "usr/sbin"];
+
+/// Package managers in detection order, with the binaries that identify them.
+const PACKAGE_MANAGERS: &[(&str, &[&str])] = &[
+    ("apt", &["apt-get", "dpkg"]),
+    ("dnf", &["dnf", "dnf5"]),
+    ("yum", &["yum"]),
+    ("zypper", &["zypper"]),
+    ("pacman", &["pacman"]),
+    ("apk", &["apk"]),
+    ("xbps", &["xbps-install"]),
+    ("emerge", &["emerge"]),
+    ("nix", &["nix-env"]),
+];
+
+/// Top-level names that belong to a normal root filesystem.
+const ROOTFS_TOP_LEVEL: &[&str] = &[
+    "bin", "boot", "dev", "etc", "home", "lib", "lib32", "lib64", "libx32", "media", "mnt", "nix",
+    "opt", "proc", "root", "run", "sbin", "srv", "sys", "tmp", "usr", "var",
+];
+
+/// Problems that make an archive unlikely to work as a WSL distribution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RootfsWarningKind {
+    /// The archive has no entries
+    Empty,
+    /// No `/bin/sh`, so WSL cannot start a shell
+    MissingShell,
+    /// No `/etc/os-release` or `/usr/lib/os-release`
+    MissingOsRelease,
+    /// Everything sits under one non-standard folder (e.g. `rootfs/etc/...`)
+    NestedRoot,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RootfsWarning {
+    pub kind: RootfsWarningKind,
+    pub message: String,
+}
+
+/// What a rootfs archive contains.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RootfsInspection {
+    /// `os-release` `ID`
+    pub os_id: Option<String>,
+    /// `os-release` `VERSION_ID`
+    pub version_id: Option<String>,
+    /// `os-release` `PRETTY_NAME`
+    pub pretty_name: Option<String>,
+    /// `apt`, `dnf`, `yum`, `zypper`, `pacman`, `apk`, `xbps`, `emerge` or `nix`
+    pub package_manager: Option<String>,
+    /// `systemd`, `openrc`, `runit`, `s6`, `busybox` or `sysvinit`
+    pub init_system: Option<String>,
+    /// Sum of all member sizes
+    pub uncompressed_bytes: u64,
+    /// Number of regular files
+    pub file_count: u64,
+    pub has_wsl_conf: bool,
+    pub has_distribution_conf: bool,
+    /// Distribution name from the package's `defaultName`, `os-release` or
+    /// the archive name
+    pub suggested_name: String,
+    /// Logo key for the frontend's distro logo lookup
+    pub suggested_icon: Option<String>,
+    pub warnings: Vec<RootfsWarning>,
+}
+
+/// What was seen for a watched path.
+enum Seen {
+    File,
+    Link(String),
+}
+
+/// State accumulated during the single pass over the archive.
+#[derive(Default)]
+struct Scan {
+    watched: HashSet<String>,
+    seen: HashMap<String, Seen>,
+    /// Top-level symlinks such as `bin -> usr/bin` on merged-/usr systems
+    top_links: HashMap<String, String>,
+    top_level: HashSet<String>,
+    os_release: HashMap<String, Vec<u8>>,
+    /// Inline `wsl-distribution.conf` (symlinked ones are not followed)
+    distribution_conf: Option<String>,
+    uncompressed_bytes: u64,
+    file_count: u64,
+    entries: u64,
+}
+
+impl Scan {
+    fn new() -> Self {
+        let mut watched: HashSet<String> = [
+            "bin/sh",
+            "usr/bin/sh",
+            "sbin/init",
+            "usr/sbin/init",
+            "usr/lib/systemd/systemd",
+            "lib/systemd/systemd",
+            "etc/inittab",
+            WSL_CONF_PATH,
+            DISTRIBUTION_CONF_PATH,
+        ]
+        .iter()
+        .map(|p| p.to_string())
+        .collect();
+        watched.extend(OS_RELEASE_PATHS.iter().map(|p| p.to_string()));
+        let binaries = PACKAGE_MANAGERS
+            .iter()
+            .flat_map(|(_, bins)| bins.iter())
+            .chain(["openrc", "openrc-run", "runit", "s6-svscan"].iter());
+        for bin in binaries {
+            for dir in BIN_DIRS {
+                watched.insert(format!("{}/{}", dir, bin));
+            }
+        }
+        Self {
+            watched,
+            ..Default::default()
+        }
+    }
+
+    fn visit<R: Read>(&mut self, entry: &mut tar::Entry<R>) -> Result<(), WslError> {
+        let path = archive::normalize_entry_path(&entry.path()?.to_string_lossy());
+        if path.is_empty() {
+            return Ok(());
+        }
+        self.entries += 1;
+        self.uncompressed_bytes += entry.size();
+
+        let entry_type = entry.header().entry_type();
+        if matches!(entry_type, EntryType::Regular | EntryType::Continuous) {
+            self.file_count += 1;
+        }
+
+        let top = path.split('/').next().unwrap_or_default();
+        if self.top_level.len() < MAX_TOP_LEVEL_ENTRIES {
+            self.top_level.insert(top.to_string());
+        }
+        if entry_type == EntryType::Symlink && !path.contains('/') {
+            if let Some(target) = entry.link_name()? {
+                self.top_links
+                    .insert(path.clone(), archive::resolve_link(&path, &target.to_string_lossy()));
+            }
+        }
+
+        if !self.watched.contains(&path) {
+            return Ok(());
+        }
+        match entry_type {
+            EntryType::Symlink | EntryType::Link => {
+                let target = entry
+                    .link_name()?
+                    .map(|t| t.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                let resolved = if entry_type == EntryType::Link {
+                    archive::normalize_entry_path(&target)
+                } else {
+                    archive::resolve_link(&path, &target)
+                };
+                self.seen.insert(path, Seen::Link(resolved));
+            }
+            _ => {
+                if OS_RELEASE_PATHS.contains(&path.as_str()) && entry.size() <= MAX_OS_RELEASE_BYTES {
+                    let mut data = Vec::new();
+                    entry.read_to_end(&mut data)?;
+                    self.os_release.insert(path.clone(), data);
+                } else if path == DISTRIBUTION_CONF_PATH && entry.size() <= MAX_OS_RELEASE_BYTES {
+                    let mut data = String::new();
+                    if entry.read_to_string(&mut data).is_ok() {
+                        self.distribution_conf = Some(data);
+                    }
+                }
+                self.seen.insert(path, Seen::File);
+            }
+        }
+        Ok(())
+    }
+
+    /// Map `path` through a top-level directory symlink (`bin/sh` ->
+    /// `usr/bin/sh` when `bin -> usr/bin`).
+    fn via_top_link(&self, path: &str) -> Option<String> {
+        let (top, rest) = path.split_once('/')?;
+        self.top_links.get(top).map(|target| format!("{}/{}", target, rest))
+    }
+
+    /// Whether `path` exists, following top-level directory symlinks.
+    fn exists(&self, path: &str) -> bool {
+        self.seen.contains_key(path)
+            || self
+                .via_top_link(path)
+                .map(|p| self.seen.contains_key(&p))
+                .unwrap_or(false)
+    }
+
+    fn link_target(&self, path: &str) -> Option<&str> {
+        let found = self
+            .seen
+            .get(path)
+            .or_else(|| self.via_top_link(path).and_then(|p| self.seen.get(&p)));
+        match found {
+            Some(Seen::Link(target)) => Some(target),
+            _ => None,
+        }
+    }
+
+    fn has_binary(&self, name: &str) -> bool {
+        BIN_DIRS.iter().any(|dir| self.exists(&format!("{}/{}", dir, name)))
+    }
+
+    fn package_manager(&self) -> Option<String> {
+        PACKAGE_MANAGERS
+            .iter()
+            .find(|(_, bins)| bins.iter().any(|bin| self.has_binary(bin)))
+            .map(|(name, _)| name.to_string())
+    }
+
+    fn init_system(&self) -> Option<String> {
+        let init_target = self
+            .link_target("sbin/init")
+            .map(|t| t.rsplit('/').next().unwrap_or(t).to_string());
+        let detected = if self.exists("usr/lib/systemd/systemd")
+            || self.exists("lib/systemd/systemd")
+            || init_target.as_deref() == Some("systemd")
+        {
+            "systemd"
+        } else if self.has_binary("openrc") || self.has_binary("openrc-run") {
+            "openrc"
+        } else if self.has_binary("runit") {
+            "runit"
+        } else if self.has_binary("s6-svscan") {
+            "s6"
+        } else if init_target.as_deref() == Some("busybox") {
+            "busybox"
+        } else if self.exists("etc/inittab") || self.exists("sbin/init") {
+            "sysvinit"
+        } else {
+            return None;
+        };
+        Some(detected.to_string())
+    }
+
+    fn os_release(&self) -> Option<HashMap<String, String>> {
+        // `/etc/os-release` is usually a symlink to `/usr/lib/os-release`.
+        let data = OS_RELEASE_PATHS.iter().find_map(|p| self.os_release.get(*p))?;
+        Some(parse_os_release(&String::from_utf8_lossy(data)))
+    }
+}
+
+/// Parse `KEY=value` lines, removing shell quoting.
+pub fn parse_os_release(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.starts_with('#') {
+                return None;
+            }
+            let (key, value) = line.split_once('=')?;
+            let value = value.trim().trim_matches('"').trim_matches('\'');
+            Some((key.trim().to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Keep characters WSL accepts in distribution names.
+fn sanitize_name(raw: &str) -> String {
+    let cleaned: String = raw
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') { c } else { '-' })
+        .collect();
+    cleaned
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Archive file name without its extensions and export date stamp.
+fn name_from_source(source: &str) -> String {
+    let file = source
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or(source)
+        .split(['?', '#'])
+        .next()
+        .unwrap_or_default();
+    let lower = file.to_ascii_lowercase();
+    let stem_len = [".tar.gz", ".tar.xz", ".tgz", ".tar", ".wsl"]
+        .iter()
+        .find(|ext| lower.ends_with(*ext))
+        .map(|ext| file.len() - ext.len())
+        .unwrap_or(file.len());
+    let stem = &file[..stem_len];
+    // Drop the `-YYYY-MM-DD` suffix our own exports carry.
+    // Byte offsets may fall inside a multi-byte character, so use `get`.
+    let split = stem
+        .len()
+        .checked_sub(11)
+        .and_then(|at| Some((stem.get(..at)?, stem.get(at..)?)));
+    let stem = match split {
+        Some((head, date))
+            if date.chars().enumerate().all(|(i, c)| match i {
+                0 | 5 | 8 => c == '-',
+                _ => c.is_ascii_digit(),
+            }) =>
+        {
+            head
+        }
+        _ => stem,
+    };
+    sanitize_name(stem)
+}
+
+/// Suggest a distribution name: `NAME`'s first word plus `VERSION_ID`
+/// (`Ubuntu-24.04`, `Alpine-3.20`), else the archive name.
+fn suggest_name(os_release: Option<&HashMap<String, String>>, source: &str) -> String {
+    let base = os_release.and_then(|os| {
+        os.get("NAME")
+            .and_then(|n| n.split_whitespace().next())
+            .or_else(|| os.get("ID").map(String::as_str))
+            .map(sanitize_name)
+            .filter(|n| !n.is_empty())
+            .map(|n| match os.get("VERSION_ID").map(|v| sanitize_name(v)) {
+                Some(version) if !version.is_empty() => format!("{}-{}", n, version),
+                _ => n,
+            })
+    });
+    base.or_else(|| Some(name_from_source(source)).filter(|n| !n.is_empty()))
+        .unwrap_or_else(|| "Linux".to_string())
+}
+
+/// Append `-2`, `-3`, ... until `name` does not clash (case-insensitively)
+/// with an existing distribution.
+pub fn unique_name(name: &str, existing: &[String]) -> String {
+    let taken = |candidate: &str| existing.iter().any(|e| e.eq_ignore_ascii_case(candidate));
+    if !taken(name) {
+        return name.to_string();
+    }
+    (2..)
+        .map(|n| format!("{}-{}", name, n))
+        .find(|candidate| !taken(candidate))
+        .unwrap_or_else(|| name.to_string())
+}
+
+/// Inspect an opened archive stream. `source` is only used for naming.
+pub fn inspect_reader(reader: Box<dyn Read>, source: &str) -> Result<RootfsInspection, WslError> {
+    let mut archive = archive::open_reader(reader, source)?;
+    let mut scan = Scan::new();
+    let entries = archive
+        .entries()
+        .map_err(|e| WslError::CommandFailed(format!("Failed to read archive: {}", e)))?;
+    for entry in entries {
+        let mut entry =
+            entry.map_err(|e| WslError::CommandFailed(format!("Corrupt archive entry: {}", e)))?;
+        scan.visit(&mut entry)?;
+    }
+
+    let os_release = scan.os_release();
+    let mut warnings = Vec::new();
+    let mut warn = |kind, message: &str| {
+        warnings.push(RootfsWarning {
+            kind,
+            message: message.to_string(),
+        })
+    };
+    if scan.entries == 0 {
+        warn(RootfsWarningKind::Empty, "The archive contains no files");
+    } else {
+        if !scan.exists("bin/sh") {
+            warn(
+                RootfsWarningKind::MissingShell,
+                "No /bin/sh found; WSL will not be able to start a shell",
+            );
+        }
+        if os_release.is_none() {
+            warn(
+                RootfsWarningKind::MissingOsRelease,
+                "No /etc/os-release found; the distribution cannot be identified",
+            );
+        }
+        if scan.top_level.len() == 1
+            && scan
+                .top_level
+                .iter()
+                .all(|top| !ROOTFS_TOP_LEVEL.contains(&top.as_str()))
+        {
+            warn(
+                RootfsWarningKind::NestedRoot,
+                "All files are inside a single folder; the root filesystem should be at the top of the archive",
+            );
+        }
+    }
+
+    // A package's own `[oobe] defaultName` beats anything we derive.
+    let package_name = scan
+        .distribution_conf
+        .as_deref()
+        .and_then(|conf| parse_distribution_conf(conf).ok())
+        .and_then(|conf| conf.oobe_default_name)
+        .map(|name| sanitize_name(&name))
+        .filter(|name| !name.is_empty());
+    let get = |key: &str| os_release.as_ref().and_then(|os| os.get(key)).filter(|v| !v.is_empty()).cloned();
+    Ok(RootfsInspection {
+        os_id: get("ID"),
+        version_id: get("VERSION_ID"),
+        pretty_name: get("PRETTY_NAME"),
+        package_manager: scan.package_manager(),
+        init_system: scan.init_system(),
+        uncompressed_bytes: scan.uncompressed_bytes,
+        file_count: scan.file_count,
+        has_wsl_conf: scan.exists(WSL_CONF_PATH),
+        has_distribution_conf: scan.exists(DISTRIBUTION_CONF_PATH),
+        suggested_name: package_name.unwrap_or_else(|| suggest_name(os_release.as_ref(), source)),
+        suggested_icon: get("ID").map(|id| id.to_lowercase()),
+        warnings,
+    })
+}
+
+/// Inspect a local archive.
+pub fn inspect_file(path: &Path) -> Result<RootfsInspection, WslError> {
+    let file = std::fs::File::open(path).map_err(|e| {
+        WslError::CommandFailed(format!("Failed to open {}: {}", path.display(), e))
+    })?;
+    inspect_reader(Box::new(std::io::BufReader::new(file)), &path.to_string_lossy())
+}
+
+/// Inspect an archive by streaming it over HTTP; nothing is written to disk.
+pub fn inspect_url(url: &str) -> Result<RootfsInspection, WslError> {
+    let client = crate::http_client::blocking_client(None)
+        .map_err(|e| WslError::CommandFailed(e.to_string()))?;
+    let resp = client
+        .get(url)
+        .send()
+        .map_err(|e| WslError::CommandFailed(format!("Failed to download {}: {}", url, e)))?;
+    if !resp.status().is_success() {
+        return Err(WslError::CommandFailed(format!(
+            "Download of {} returned HTTP {}",
+            url,
+            resp.status()
+        )));
+    }
+    inspect_reader(Box::new(resp), url)
+}
+
+/// Inspect a local path or an `http(s)://` URL.
+pub fn inspect_source(source: &str) -> Result<RootfsInspection, WslError> {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        inspect_url(source)
+    } else {
+        inspect_file(Path::new(source))
+    }
+}
+
+/// Canned inspection used in mock mode for remote sources.
+pub fn mock_inspection(source: &str) -> RootfsInspection {
+    let os_release = parse_os_release(
+        "ID=ubuntu\nVERSION_ID=\"24.04\"\nNAME=\"Ubuntu\"\nPRETTY_NAME=\"Ubuntu 24.04.1 LTS\"\n",
+    );
+    RootfsInspection {
+        os_id: Some("ubuntu".to_string()),
+        version_id: Some("24.04".to_string()),
+        pretty_name: Some("Ubuntu 24.04.1 LTS".to_string()),
+        package_manager: Some("apt".to_string()),
+        init_system: Some("systemd".to_string()),
+        uncompressed_bytes: 1_127_415_808,
+        file_count: 28_406,
+        has_wsl_conf: true,
+        has_distribution_conf: true,
+        suggested_name: suggest_name(Some(&os_release), source),
+        suggested_icon: Some("ubuntu".to_string()),
+        warnings: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::archive::test_support::write_tar_gz;
+    use super::*;
+
+    fn inspect(name: &str, files: &[(&str, &[u8])]) -> RootfsInspection {
+        let dir = crate::utils::unique_temp_dir(&format!("inspect-{}", name));
+        let path = dir.join(format!("{}.tar.gz", name));
+        write_tar_gz(&path, files);
+        let result = inspect_file(&path).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+        result
+    }
+
+    #[test]
+    fn inspects_merged_usr_debian_rootfs() {
+        let result = inspect(
+            "debian",
+            &[
+                ("./bin@usr/bin", b""),
+                ("./sbin@usr/sbin", b""),
+                ("./etc/os-release@../usr/lib/os-release", b""),
+                (
+                    "./usr/lib/os-release",
+                    b"PRETTY_NAME=\"Debian GNU/Linux 12 (bookworm)\"\nNAME=\"Debian GNU/Linux\"\nVERSION_ID=\"12\"\nID=debian\n",
+                ),
+                ("./usr/bin/sh@dash", b""),
+                ("./usr/bin/dash", b"ELF"),
+                ("./usr/bin/apt-get", b"ELF"),
+                ("./usr/lib/systemd/systemd", b"ELF"),
+                ("./usr/sbin/init@../lib/systemd/systemd", b""),
+                ("./etc/wsl.conf", b"[boot]\nsystemd=true\n"),
+            ],
+        );
+        assert_eq!(result.os_id.as_deref(), Some("debian"));
+        assert_eq!(result.version_id.as_deref(), Some("12"));
+        assert_eq!(result.pretty_name.as_deref(), Some("Debian GNU/Linux 12 (bookworm)"));
+        assert_eq!(result.package_manager.as_deref(), Some("apt"));
+        assert_eq!(result.init_system.as_deref(), Some("systemd"));
+        assert_eq!(result.file_count, 5);
+        assert!(result.has_wsl_conf);
+        assert!(!result.has_distribution_conf);
+        assert_eq!(result.suggested_name, "Debian-12");
+        assert_eq!(result.suggested_icon.as_deref(), Some("debian"));
+        assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+    }
+
+    #[test]
+    fn detects_alpine_busybox() {
+        let result = inspect(
+            "alpine",
+            &[
+                ("./etc/os-release", b"NAME=\"Alpine Linux\"\nID=alpine\nVERSION_ID=3.20.3\n"),
+                ("./bin/busybox", b"ELF"),
+                ("./bin/sh@/bin/busybox", b""),
+                ("./sbin/init@/bin/busybox", b""),
+                ("./sbin/apk", b"ELF"),
+            ],
+        );
+        assert_eq!(result.package_manager.as_deref(), Some("apk"));
+        assert_eq!(result.init_system.as_deref(), Some("busybox"));
+        assert_eq!(result.suggested_name, "Alpine-3.20.3");
+    }
+
+    #[test]
+    fn package_default_name_wins() {
+        let result = inspect(
+            "package",
+            &[
+                ("./etc/os-release", b"NAME=Fedora
+ID=fedora
+VERSION_ID=41
+"),
+                ("./etc/wsl-distribution.conf", b"[oobe]\ndefaultName = FedoraDev\n"),
+                ("./bin/sh", b"ELF"),
+            ],
+        );
+        assert!(result.has_distribution_conf);
+        assert_eq!(result.suggested_name, "FedoraDev");
+    }
+
+    #[test]
+    fn warns_about_unusable_archives() {
+        let result = inspect("nested", &[("./rootfs/etc/hostname", b"box\n")]);
+        let kinds: Vec<_> = result.warnings.iter().map(|w| w.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                RootfsWarningKind::MissingShell,
+                RootfsWarningKind::MissingOsRelease,
+                RootfsWarningKind::NestedRoot
+            ]
+        );
+        assert_eq!(result.suggested_name, "nested");
+
+        let empty = inspect("empty", &[]);
+        assert_eq!(empty.warnings[0].kind, RootfsWarningKind::Empty);
+    }
+
+    #[test]
+    fn names_from_source_and_uniqueness() {
+        assert_eq!(name_from_source("C:\\backups\\dev-box-2024-05-01.tar"), "dev-box");
+        assert_eq!(name_from_source("https://x/y/my rootfs.tar.gz?sig=1"), "my-rootfs");
+        assert_eq!(name_from_source("custom.wsl"), "custom");
+        assert_eq!(name_from_source("Dämon-rootfs.tar"), "D-mon-rootfs");
+        assert_eq!(name_from_source("/srv/Dämon-2024-05-01.TAR.GZ"), "D-mon");
+        assert_eq!(name_from_source("日本語.tar"), "");
+        let existing = vec!["Ubuntu-24.04".to_string(), "ubuntu-24.04-2".to_string()];
+        assert_eq!(unique_name("Ubuntu-24.04", &existing), "Ubuntu-24.04-3");
+        assert_eq!(unique_name("Debian", &existing), "Debian");
+    }
+}
@@ -3,10 +3,17 @@
 //! Provides a unified API for all WSL operations, maintaining backward
 //! compatibility while delegating to specialized modules.
 
+use super::customize::RootfsCustomization;
+use super::distribution_conf::{WslPackageOptions, WslPackageReport};
+use super::encryption::{DecryptionKey, EncryptionKey};
+use super::export_filter::{ExportFilter, FilterReport};
 use super::executor::wsl_executor;
 use super::info::{VhdSizeInfo, WslVersionInfo};
+use super::cloud_init::{self, CloudInitReport};
+use super::provision::{self, ProvisioningReport, ProvisioningSpec};
 use super::resources::{self, DistroResourceUsage, WslResourceUsage};
 use super::types::{CompactResult, Distribution, WslError, WslPreflightStatus, MountedDisk, MountDiskOptions, PhysicalDisk};
+use super::import_export::CloneResult;
 use super::{core, import_export, info, install, terminal};
 
 /// WSL Service - facade for all WSL operations
@@ -104,13 +111,36 @@ impl WslService {
         import_export::export_distribution(name, path)
     }
 
-    /// Import a distribution from a tar file
-    pub fn import_distribution(
+    /// Export a distribution to a tar file, leaving out what `filter` excludes
+    pub fn export_distribution_filtered(
         name: &str,
-        install_location: &str,
-        tar_path: &str,
+        path: &str,
+        filter: &ExportFilter,
+    ) -> Result<FilterReport, WslError> {
+        import_export::export_distribution_filtered(name, path, filter)
+    }
+
+    /// Export a WSL 2 distribution's disk to a `.vhdx` file
+    pub fn export_distribution_vhd(name: &str, path: &str) -> Result<(), WslError> {
+        import_export::export_distribution_vhd(name, path)
+    }
+
+    /// Export a distribution as a `.wsl` package with a generated configuration
+    pub fn export_wsl_package(
+        name: &str,
+        path: &str,
+        options: &WslPackageOptions,
+    ) -> Result<WslPackageReport, WslError> {
+        import_export::export_wsl_package(name, path, options)
+    }
+
+    /// Export a distribution to a passphrase- or key-encrypted archive
+    pub fn export_distribution_encrypted(
+        name: &str,
+        path: &str,
+        key: &EncryptionKey,
     ) -> Result<(), WslError> {
-        import_export::import_distribution(name, install_location, tar_path)
+        import_export::export_distribution_encrypted(name, path, key)
     }
 
     /// Import a distribution from a tar file with specific WSL version
@@ -119,15 +149,61 @@ impl WslService {
         install_location: &str,
         tar_path: &str,
         wsl_version: Option<u8>,
+        customization: Option<&RootfsCustomization>,
+    ) -> Result<(), WslError> {
+        import_export::import_distribution_with_version(name, install_location, tar_path, wsl_version, customization)
+    }
+
+    /// Import a `.vhdx` disk as a new distribution (the disk is copied)
+    pub fn import_distribution_vhd(
+        name: &str,
+        install_location: &str,
+        vhd_path: &str,
+        wsl_version: Option<u8>,
+    ) -> Result<(), WslError> {
+        import_export::import_distribution_vhd(name, install_location, vhd_path, wsl_version)
+    }
+
+    /// Register an existing `.vhdx` as a distribution without copying it
+    pub fn register_vhd_in_place(name: &str, vhd_path: &str) -> Result<(), WslError> {
+        import_export::register_vhd_in_place(name, vhd_path)
+    }
+
+    /// Import a tarball or `.wsl` package, applying its `wsl-distribution.conf`
+    pub fn import_package(
+        name: &str,
+        install_location: &str,
+        tar_path: &str,
+        wsl_version: Option<u8>,
+        terminal_command: &str,
+        customization: Option<&RootfsCustomization>,
+    ) -> Result<Option<import_export::ImportedPackage>, WslError> {
+        import_export::import_package(name, install_location, tar_path, wsl_version, terminal_command, customization)
+    }
+
+    /// Import an encrypted archive, decrypting it straight into `wsl --import`
+    pub fn import_distribution_encrypted(
+        name: &str,
+        install_location: &str,
+        path: &str,
+        key: &DecryptionKey,
+        wsl_version: Option<u8>,
     ) -> Result<(), WslError> {
-        import_export::import_distribution_with_version(name, install_location, tar_path, wsl_version)
+        import_export::import_distribution_encrypted(name, install_location, path, key, wsl_version)
     }
 
-    /// Clone a distribution (export + import with new name)
+    /// Clone a distribution (export + import with new name), via `.vhdx` for
+    /// WSL 2 sources when supported and tar otherwise
     ///
     /// If `install_location` is None, defaults to `%LOCALAPPDATA%\wsl\<new_name>`
-    pub fn clone_distribution(source: &str, new_name: &str, install_location: Option<&str>) -> Result<(), WslError> {
-        import_export::clone_distribution(source, new_name, install_location)
+    pub fn clone_distribution(
+        source: &str,
+        new_name: &str,
+        install_location: Option<&str>,
+        customization: Option<&RootfsCustomization>,
+        filter: Option<&ExportFilter>,
+    ) -> Result<CloneResult, WslError> {
+        import_export::clone_distribution(source, new_name, install_location, customization, filter)
     }
 
     // ==================== Installation ====================
@@ -156,8 +232,9 @@ impl WslService {
         install_location: Option<&str>,
         wsl_version: Option<u8>,
         runtime_hint: Option<&str>,
+        customization: Option<&RootfsCustomization>,
     ) -> Result<(), WslError> {
-        install::create_from_image(image, distro_name, install_location, wsl_version, runtime_hint)
+        install::create_from_image(image, distro_name, install_location, wsl_version, runtime_hint, customization)
     }
 
     /// Create a new distribution from an OCI container image (native - no Docker/Podman required)
@@ -167,8 +244,9 @@ impl WslService {
         install_location: Option<&str>,
         wsl_version: Option<u8>,
         progress: Option<crate::oci::ProgressCallback>,
+        customization: Option<&RootfsCustomization>,
     ) -> Result<(), WslError> {
-        install::create_from_oci_image(image, distro_name, install_location, wsl_version, progress)
+        install::create_from_oci_image(image, distro_name, install_location, wsl_version, progress, customization)
     }
 
     // ==================== Information ====================
@@ -286,6 +364,21 @@ impl WslService {
         core::set_default_user(name, username)
     }
 
+    /// Create and configure the default user of a new distribution
+    pub fn provision_distribution(name: &str, spec: &ProvisioningSpec) -> Result<ProvisioningReport, WslError> {
+        provision::provision_distribution(name, spec)
+    }
+
+    /// Apply cloud-config user data to a distribution
+    pub fn apply_user_data(name: &str, user_data: &str) -> Result<CloudInitReport, WslError> {
+        cloud_init::apply_user_data(name, user_data)
+    }
+
+    /// Run the last applied cloud-config again
+    pub fn rerun_user_data(name: &str) -> Result<CloudInitReport, WslError> {
+        cloud_init::rerun_user_data(name)
+    }
+
     /// Resize a distribution's virtual disk
     /// Size should be a string like "50GB" or "1TB"
     pub fn resize_distribution(name: &str, size: &str) -> Result<(), WslError> {
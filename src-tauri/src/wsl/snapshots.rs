@@ -0,0 +1,729 @@
+//! Point-in-time snapshots of distributions
+//!
+//! A snapshot is a `wsl --export` tarball kept in the snapshot directory
+//! (see [`crate::settings::get_snapshot_directory`]) together with a
+//! `snapshots.json` index recording when it was taken, its size and SHA256,
+//! the source distribution's GUID, WSL version and default user.
+//!
+//! Restoring re-imports the tarball either in place (the distribution is
+//! unregistered and re-created under the same name and location) or as a new
+//! distribution. The default user is reapplied and metadata follows the new
+//! GUID, so snapshots stay linked to the restored distribution.
+
+use super::core;
+use super::executor::{resource_monitor, wsl_executor};
+use super::import_export;
+use super::types::{DistroState, Distribution, WslError};
+use crate::metadata::{self, DistroMetadata, InstallSource};
+use crate::settings::{get_default_distro_path, get_snapshot_directory};
+use crate::utils::is_mock_mode;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Snapshot index file inside the snapshot directory
+const SNAPSHOT_INDEX_FILE: &str = "snapshots.json";
+
+/// Current snapshot index version
+const CURRENT_VERSION: &str = "1.0";
+
+/// Size reported for snapshots taken in mock mode (no file is written)
+const MOCK_SNAPSHOT_SIZE: u64 = 1_073_741_824;
+
+/// A stored snapshot of a distribution
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snapshot {
+    /// Unique ID, also the file stem of the snapshot tarball
+    pub id: String,
+    /// User-facing label, e.g. "before do-release-upgrade"
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Distribution name when the snapshot was taken
+    pub distro_name: String,
+    /// Distribution GUID (updated when the distribution is restored in place)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distro_id: Option<String>,
+    /// ISO 8601 timestamp
+    pub created_at: String,
+    /// Tarball name, relative to the snapshot directory
+    pub file_name: String,
+    pub size_bytes: u64,
+    /// Lowercase hex SHA256 of the tarball
+    pub sha256: String,
+    pub wsl_version: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_user: Option<String>,
+}
+
+/// On-disk snapshot index
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SnapshotIndex {
+    version: String,
+    #[serde(default)]
+    snapshots: Vec<Snapshot>,
+}
+
+impl Default for SnapshotIndex {
+    fn default() -> Self {
+        Self {
+            version: CURRENT_VERSION.to_string(),
+            snapshots: Vec::new(),
+        }
+    }
+}
+
+/// Where a snapshot is restored to
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "mode", rename_all = "camelCase")]
+pub enum RestoreTarget {
+    /// Replace the distribution the snapshot was taken from, keeping its name
+    /// and install location
+    InPlace,
+    /// Create a new distribution; `location` defaults to the install base path
+    NewDistro {
+        name: String,
+        location: Option<String>,
+    },
+}
+
+/// Outcome of [`restore_snapshot`]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreResult {
+    pub distro_name: String,
+    pub distro_id: Option<String>,
+    /// Whether the snapshot's default user was set on the restored distribution
+    pub default_user_restored: bool,
+}
+
+lazy_static::lazy_static! {
+    /// Serializes read-modify-write cycles on the index file
+    static ref INDEX_LOCK: Mutex<()> = Mutex::new(());
+    /// Snapshot index for mock mode (no files are written)
+    static ref MOCK_SNAPSHOTS: Mutex<Vec<Snapshot>> = Mutex::new(Vec::new());
+}
+
+pub(super) fn io_error(context: &str, path: &Path, e: std::io::Error) -> WslError {
+    WslError::CommandFailed(format!("{} {}: {}", context, path.display(), e))
+}
+
+fn load_index(dir: &Path) -> Result<SnapshotIndex, WslError> {
+    let path = dir.join(SNAPSHOT_INDEX_FILE);
+    if !path.exists() {
+        return Ok(SnapshotIndex::default());
+    }
+    let content =
+        std::fs::read_to_string(&path).map_err(|e| io_error("Failed to read", &path, e))?;
+    serde_json::from_str(&content).map_err(|e| {
+        WslError::ParseError(format!("Invalid snapshot index {}: {}", path.display(), e))
+    })
+}
+
+fn save_index(dir: &Path, index: &SnapshotIndex) -> Result<(), WslError> {
+    std::fs::create_dir_all(dir).map_err(|e| io_error("Failed to create", dir, e))?;
+    let path = dir.join(SNAPSHOT_INDEX_FILE);
+    let content = serde_json::to_string_pretty(index)
+        .map_err(|e| WslError::ParseError(format!("Failed to serialize snapshot index: {}", e)))?;
+    std::fs::write(&path, content).map_err(|e| io_error("Failed to write", &path, e))
+}
+
+/// Run `f` against the snapshot list and persist the result
+fn update_index<T>(
+    f: impl FnOnce(&mut Vec<Snapshot>) -> Result<T, WslError>,
+) -> Result<T, WslError> {
+    if is_mock_mode() {
+        let mut snapshots = MOCK_SNAPSHOTS.lock().unwrap_or_else(|p| p.into_inner());
+        return f(&mut snapshots);
+    }
+
+    let _guard = INDEX_LOCK.lock().unwrap_or_else(|p| p.into_inner());
+    let dir = get_snapshot_directory();
+    let mut index = load_index(&dir)?;
+    let result = f(&mut index.snapshots)?;
+    save_index(&dir, &index)?;
+    Ok(result)
+}
+
+fn read_index() -> Result<Vec<Snapshot>, WslError> {
+    if is_mock_mode() {
+        return Ok(MOCK_SNAPSHOTS
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .clone());
+    }
+    let _guard = INDEX_LOCK.lock().unwrap_or_else(|p| p.into_inner());
+    Ok(load_index(&get_snapshot_directory())?.snapshots)
+}
+
+/// Reset the mock snapshot index (for E2E test cleanup)
+pub fn reset_mock_snapshots() {
+    if let Ok(mut guard) = MOCK_SNAPSHOTS.lock() {
+        guard.clear();
+    }
+}
+
+/// Snapshots taken from a distribution, matched by GUID, or by name for
+/// snapshots without one. Newest first.
+fn snapshots_for(snapshots: &[Snapshot], name: &str, id: Option<&str>) -> Vec<Snapshot> {
+    let mut matching: Vec<Snapshot> = snapshots
+        .iter()
+        .filter(|s| match (&s.distro_id, id) {
+            (Some(snapshot_id), Some(id)) => snapshot_id.eq_ignore_ascii_case(id),
+            _ => s.distro_name == name,
+        })
+        .cloned()
+        .collect();
+    matching.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    matching
+}
+
+/// Build an ID from the distribution name and `time` (`Name-YYYYMMDD-HHMMSS`),
+/// adding a counter while `taken` reports it in use. Shared by snapshots and
+/// backup runs.
+pub(super) fn timestamped_id<Tz: chrono::TimeZone>(
+    distro_name: &str,
+    time: &chrono::DateTime<Tz>,
+    taken: impl Fn(&str) -> bool,
+) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let base = format!("{}-{}", distro_name, time.format("%Y%m%d-%H%M%S"));
+    let mut id = base.clone();
+    let mut counter = 2;
+    while taken(&id) {
+        id = format!("{}-{}", base, counter);
+        counter += 1;
+    }
+    id
+}
+
+/// List snapshots, optionally only those of one distribution
+pub fn list_snapshots(distro_name: Option<&str>) -> Result<Vec<Snapshot>, WslError> {
+    let snapshots = read_index()?;
+    Ok(match distro_name {
+        Some(name) => snapshots_for(
+            &snapshots,
+            name,
+            metadata::get_distro_id_by_name(name).as_deref(),
+        ),
+        None => {
+            let mut all = snapshots;
+            all.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+            all
+        }
+    })
+}
+
+/// The user `wsl -d <name>` logs in as, if it can be determined
+fn read_default_user(name: &str) -> Option<String> {
+    match wsl_executor().exec(name, None, "whoami") {
+        Ok(output) if output.success && !output.stdout.trim().is_empty() => {
+            Some(output.stdout.trim().to_string())
+        }
+        Ok(_) => None,
+        Err(e) => {
+            warn!("Could not read default user of '{}': {}", name, e);
+            None
+        }
+    }
+}
+
+/// Read the default user of `distro`, stop it and run `export`. Reading the
+/// default user starts the distribution; stopping it again lets the export
+/// see a quiescent filesystem. A distribution that was already running is
+/// only stopped when `stop_running` is set, and is never restarted here.
+pub(super) fn export_quiesced<T>(
+    distro: &Distribution,
+    stop_running: bool,
+    export: impl FnOnce() -> T,
+) -> (Option<String>, T) {
+    let default_user = read_default_user(&distro.name);
+    let was_running = distro.state == DistroState::Running;
+    if !was_running || stop_running {
+        if was_running {
+            info!("Stopping '{}' for export", distro.name);
+        }
+        let _ = wsl_executor().terminate(&distro.name);
+    }
+    (default_user, export())
+}
+
+/// Take a snapshot of `distro_name`
+pub fn create_snapshot(
+    distro_name: &str,
+    label: &str,
+    description: Option<&str>,
+) -> Result<Snapshot, WslError> {
+    let label = label.trim();
+    if label.is_empty() {
+        return Err(WslError::CommandFailed(
+            "Snapshot name cannot be empty".to_string(),
+        ));
+    }
+
+    let distro = core::list_distributions()?
+        .into_iter()
+        .find(|d| d.name == distro_name)
+        .ok_or_else(|| WslError::DistroNotFound(distro_name.to_string()))?;
+
+    let now = chrono::Utc::now();
+    let existing = read_index()?;
+    let id = timestamped_id(distro_name, &now, |id| existing.iter().any(|s| s.id == id));
+    let file_name = format!("{}.tar", id);
+
+    info!("Creating snapshot '{}' of '{}'", id, distro_name);
+    let (default_user, exported) = export_quiesced(&distro, false, || {
+        if is_mock_mode() {
+            import_export::export_distribution(distro_name, &file_name)?;
+            return Ok((
+                MOCK_SNAPSHOT_SIZE,
+                super::distro_sources::sha256_reader(std::io::empty())?,
+            ));
+        }
+        let dir = get_snapshot_directory();
+        std::fs::create_dir_all(&dir).map_err(|e| io_error("Failed to create", &dir, e))?;
+        let path = dir.join(&file_name);
+        if let Err(e) = import_export::export_distribution(distro_name, &path.to_string_lossy()) {
+            let _ = std::fs::remove_file(&path);
+            return Err(e);
+        }
+        let size = std::fs::metadata(&path)
+            .map_err(|e| io_error("Failed to read", &path, e))?
+            .len();
+        Ok::<_, WslError>((size, hash_file(&path)?))
+    });
+    let (size_bytes, sha256) = exported?;
+
+    let snapshot = Snapshot {
+        id,
+        name: label.to_string(),
+        description: description
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+            .map(String::from),
+        distro_name: distro_name.to_string(),
+        distro_id: distro
+            .id
+            .clone()
+            .or_else(|| metadata::get_distro_id_by_name(distro_name)),
+        created_at: now.to_rfc3339(),
+        file_name,
+        size_bytes,
+        sha256,
+        wsl_version: distro.version,
+        default_user,
+    };
+
+    update_index(|snapshots| {
+        snapshots.push(snapshot.clone());
+        Ok(())
+    })?;
+    info!(
+        "Snapshot '{}' saved ({} bytes)",
+        snapshot.id, snapshot.size_bytes
+    );
+    Ok(snapshot)
+}
+
+pub(super) fn hash_file(path: &Path) -> Result<String, WslError> {
+    let file = std::fs::File::open(path).map_err(|e| io_error("Failed to open", path, e))?;
+    super::distro_sources::sha256_reader(std::io::BufReader::new(file))
+        .map_err(|e| io_error("Failed to hash", path, e))
+}
+
+fn find_snapshot(id: &str) -> Result<Snapshot, WslError> {
+    read_index()?
+        .into_iter()
+        .find(|s| s.id == id)
+        .ok_or_else(|| WslError::CommandFailed(format!("Snapshot '{}' not found", id)))
+}
+
+/// Delete a snapshot and its tarball
+pub fn delete_snapshot(id: &str) -> Result<(), WslError> {
+    let snapshot = update_index(|snapshots| {
+        let position = snapshots
+            .iter()
+            .position(|s| s.id == id)
+            .ok_or_else(|| WslError::CommandFailed(format!("Snapshot '{}' not found", id)))?;
+        Ok(snapshots.remove(position))
+    })?;
+
+    if !is_mock_mode() {
+        let path = get_snapshot_directory().join(&snapshot.file_name);
+        if let Err(e) = std::fs::remove_file(&path) {
+            warn!("Failed to delete snapshot file {}: {}", path.display(), e);
+        }
+    }
+    info!("Deleted snapshot '{}'", id);
+    Ok(())
+}
+
+/// The distribution a restorable tarball was taken from
+pub(super) struct ArchiveOrigin<'a> {
+    pub distro_name: &'a str,
+    pub distro_id: Option<&'a str>,
+    pub wsl_version: u8,
+    pub default_user: Option<&'a str>,
+}
+
+/// Restore a snapshot in place or as a new distribution
+pub fn restore_snapshot(id: &str, target: &RestoreTarget) -> Result<RestoreResult, WslError> {
+    let snapshot = find_snapshot(id)?;
+    let file = get_snapshot_directory().join(&snapshot.file_name);
+
+    // Verify before anything is unregistered
+    if !is_mock_mode() {
+        if !file.is_file() {
+            return Err(WslError::CommandFailed(format!(
+                "Snapshot file not found: {}",
+                file.display()
+            )));
+        }
+        let actual = hash_file(&file)?;
+        if !actual.eq_ignore_ascii_case(&snapshot.sha256) {
+            return Err(WslError::CommandFailed(format!(
+                "Snapshot '{}' is corrupted: expected SHA256 {}, got {}",
+                id, snapshot.sha256, actual
+            )));
+        }
+    }
+
+    let origin = ArchiveOrigin {
+        distro_name: &snapshot.distro_name,
+        distro_id: snapshot.distro_id.as_deref(),
+        wsl_version: snapshot.wsl_version,
+        default_user: snapshot.default_user.as_deref(),
+    };
+    let (result, previous_id) = import_archive(&file.to_string_lossy(), &origin, target)?;
+    info!("Restored snapshot '{}' as '{}'", id, result.distro_name);
+
+    if let Some(new_id) = &result.distro_id {
+        relink_metadata(&snapshot, &result.distro_name, previous_id.as_deref(), new_id);
+    } else {
+        warn!(
+            "Could not find GUID for restored distribution '{}' - metadata not updated",
+            result.distro_name
+        );
+    }
+    Ok(result)
+}
+
+/// Replace the registered distribution `name` with a tarball. The tarball is
+/// imported under a staging name first, so a failed import leaves the
+/// original untouched; only then is the original unregistered and the
+/// import renamed and moved into its place.
+fn replace_distribution(
+    name: &str,
+    location: &str,
+    tar_path: &str,
+    wsl_version: u8,
+    distros: &[Distribution],
+) -> Result<(), WslError> {
+    let staging_name = (1..)
+        .map(|n| match n {
+            1 => format!("{}-restore", name),
+            n => format!("{}-restore{}", name, n),
+        })
+        .find(|candidate| {
+            !distros
+                .iter()
+                .any(|d| d.name.eq_ignore_ascii_case(candidate))
+        })
+        .expect("a staging name is free");
+    let staging_location = format!("{}-restore", location.trim_end_matches(['\\', '/']));
+    info!(
+        "Importing {} as '{}' before replacing '{}'",
+        tar_path, staging_name, name
+    );
+    import_export::import_distribution_with_version(
+        &staging_name,
+        &staging_location,
+        tar_path,
+        Some(wsl_version),
+        None,
+    )?;
+
+    info!("Unregistering '{}' to restore {}", name, tar_path);
+    let _ = wsl_executor().terminate(name);
+    let output = wsl_executor().unregister(name)?;
+    if !output.success {
+        let _ = wsl_executor().unregister(&staging_name);
+        return Err(WslError::CommandFailed(output.stderr));
+    }
+
+    let kept_as = |e: WslError| {
+        WslError::CommandFailed(format!(
+            "'{}' was restored as '{}' but could not be renamed: {}",
+            name, staging_name, e
+        ))
+    };
+    let staging_id = core::list_distributions()
+        .map_err(kept_as)?
+        .into_iter()
+        .find(|d| d.name == staging_name)
+        .and_then(|d| d.id)
+        .ok_or_else(|| kept_as(WslError::DistroNotFound(staging_name.clone())))?;
+    core::rename_distribution(&staging_id, name, &core::RenameOptions::default())
+        .map_err(kept_as)?;
+
+    if !is_mock_mode() {
+        let _ = std::fs::create_dir_all(location);
+    }
+    match wsl_executor().move_distro(name, location) {
+        Ok(output) if output.success => {
+            if !is_mock_mode() {
+                let _ = std::fs::remove_dir(&staging_location);
+            }
+        }
+        Ok(output) => warn!(
+            "Restored '{}' stays in {}: {}",
+            name,
+            staging_location,
+            output.stderr.trim()
+        ),
+        Err(e) => warn!("Restored '{}' stays in {}: {}", name, staging_location, e),
+    }
+    Ok(())
+}
+
+/// Import a verified tarball in place of `origin` or as a new distribution,
+/// then reapply the default user. Also returns the GUID of the distribution
+/// an in-place restore replaced.
+pub(super) fn import_archive(
+    tar_path: &str,
+    origin: &ArchiveOrigin,
+    target: &RestoreTarget,
+) -> Result<(RestoreResult, Option<String>), WslError> {
+    let registry_info = resource_monitor().get_all_distro_registry_info();
+
+    let (name, previous_id) = match target {
+        RestoreTarget::InPlace => {
+            // Follow the GUID so a renamed distribution is still replaced
+            let distros = core::list_distributions()?;
+            let current = distros
+                .iter()
+                .find(|d| match (&d.id, origin.distro_id) {
+                    (Some(id), Some(origin_id)) => id.eq_ignore_ascii_case(origin_id),
+                    _ => false,
+                })
+                .or_else(|| distros.iter().find(|d| d.name == origin.distro_name));
+            let name = current.map_or_else(|| origin.distro_name.to_string(), |d| d.name.clone());
+            let location = registry_info
+                .get(&name)
+                .and_then(|info| info.base_path.clone())
+                .unwrap_or_else(|| get_default_distro_path(&name));
+            let previous_id = current
+                .and_then(|d| d.id.clone())
+                .or_else(|| metadata::get_distro_id_by_name(&name));
+
+            if current.is_some() {
+                replace_distribution(&name, &location, tar_path, origin.wsl_version, &distros)?;
+            } else {
+                import_export::import_distribution_with_version(
+                    &name,
+                    &location,
+                    tar_path,
+                    Some(origin.wsl_version),
+                    None,
+                )?;
+            }
+            (name, previous_id)
+        }
+        RestoreTarget::NewDistro { name, location } => {
+            if core::list_distributions()?
+                .iter()
+                .any(|d| d.name.eq_ignore_ascii_case(name))
+            {
+                return Err(WslError::CommandFailed(format!(
+                    "A distribution named '{}' already exists",
+                    name
+                )));
+            }
+            let location = match location {
+                Some(loc) if !loc.trim().is_empty() => loc.clone(),
+                _ => get_default_distro_path(name),
+            };
+            import_export::import_distribution_with_version(
+                name,
+                &location,
+                tar_path,
+                Some(origin.wsl_version),
+                None,
+            )?;
+            (name.clone(), None)
+        }
+    };
+
+    let default_user_restored = match origin.default_user {
+        Some(user) => match core::set_default_user(&name, user) {
+            Ok(()) => true,
+            Err(e) => {
+                warn!(
+                    "Failed to restore default user '{}' on '{}': {}",
+                    user, name, e
+                );
+                false
+            }
+        },
+        None => false,
+    };
+
+    let new_id = resource_monitor()
+        .get_all_distro_registry_info()
+        .get(&name)
+        .map(|info| info.id.clone());
+
+    Ok((
+        RestoreResult {
+            distro_name: name,
+            distro_id: new_id,
+            default_user_restored,
+        },
+        previous_id,
+    ))
+}
+
+/// Move metadata and snapshot links from the replaced GUID to the new one
+fn relink_metadata(snapshot: &Snapshot, name: &str, previous_id: Option<&str>, new_id: &str) {
+    carry_metadata(
+        name,
+        previous_id,
+        new_id,
+        snapshot.distro_id.as_deref(),
+        Some(&snapshot.id),
+    );
+
+    if let Some(old_id) = previous_id {
+        let relinked = update_index(|snapshots| {
+            for s in snapshots.iter_mut() {
+                if s.distro_id
+                    .as_deref()
+                    .is_some_and(|id| id.eq_ignore_ascii_case(old_id))
+                {
+                    s.distro_id = Some(new_id.to_string());
+                }
+            }
+            Ok(())
+        });
+        if let Err(e) = relinked {
+            warn!("Failed to relink snapshots to '{}': {}", name, e);
+        }
+    }
+}
+
+/// Move metadata of a restored distribution from the replaced GUID to the
+/// new one. A restore as a new distribution records lineage like a clone.
+pub(super) fn carry_metadata(
+    name: &str,
+    previous_id: Option<&str>,
+    new_id: &str,
+    source_id: Option<&str>,
+    snapshot_id: Option<&str>,
+) {
+    let mut meta = match previous_id.and_then(metadata::get_metadata) {
+        Some(existing) => DistroMetadata {
+            distro_id: new_id.to_string(),
+            ..existing
+        },
+        None if previous_id.is_some() => {
+            DistroMetadata::new(new_id.to_string(), name.to_string(), InstallSource::Unknown)
+        }
+        None => DistroMetadata::new_clone(
+            new_id.to_string(),
+            name.to_string(),
+            source_id.unwrap_or("unknown").to_string(),
+        ),
+    };
+    if let Some(snapshot_id) = snapshot_id {
+        meta.restored_from_snapshot = Some(snapshot_id.to_string());
+    }
+
+    if let Some(old_id) = previous_id {
+        if let Err(e) = metadata::delete_metadata(old_id) {
+            warn!("Failed to remove metadata of replaced distribution: {}", e);
+        }
+    }
+    if let Err(e) = metadata::save_metadata(meta) {
+        warn!(
+            "Failed to save metadata for restored distribution '{}': {}",
+            name, e
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(id: &str, distro: &str, guid: Option<&str>, created_at: &str) -> Snapshot {
+        Snapshot {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: None,
+            distro_name: distro.to_string(),
+            distro_id: guid.map(String::from),
+            created_at: created_at.to_string(),
+            file_name: format!("{}.tar", id),
+            size_bytes: 1,
+            sha256: String::new(),
+            wsl_version: 2,
+            default_user: Some("dev".to_string()),
+        }
+    }
+
+    #[test]
+    fn snapshots_match_by_guid_then_name() {
+        let all = vec![
+            snapshot("a", "Ubuntu", Some("{GUID-1}"), "2026-01-01T00:00:00Z"),
+            snapshot("b", "Ubuntu-old", Some("{guid-1}"), "2026-02-01T00:00:00Z"),
+            snapshot("c", "Ubuntu", Some("{GUID-2}"), "2026-03-01T00:00:00Z"),
+            snapshot("d", "Ubuntu", None, "2026-04-01T00:00:00Z"),
+        ];
+        let ids: Vec<String> = snapshots_for(&all, "Ubuntu", Some("{GUID-1}"))
+            .into_iter()
+            .map(|s| s.id)
+            .collect();
+        assert_eq!(ids, vec!["d", "b", "a"]);
+
+        let ids: Vec<String> = snapshots_for(&all, "Ubuntu", None)
+            .into_iter()
+            .map(|s| s.id)
+            .collect();
+        assert_eq!(ids, vec!["d", "c", "a"]);
+    }
+
+    #[test]
+    fn snapshot_ids_are_unique_and_index_round_trips() {
+        let existing = vec![snapshot(
+            "Ubuntu-20260101-000000",
+            "Ubuntu",
+            None,
+            "2026-01-01T00:00:00Z",
+        )];
+        let time = chrono::DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z").unwrap();
+        let taken = |id: &str| existing.iter().any(|s| s.id == id);
+        assert_eq!(
+            timestamped_id("Ubuntu", &time, taken),
+            "Ubuntu-20260101-000000-2"
+        );
+        assert_eq!(
+            timestamped_id("Debian", &time, taken),
+            "Debian-20260101-000000"
+        );
+
+        let dir = crate::utils::unique_temp_dir("snapshots");
+        let index = SnapshotIndex {
+            version: CURRENT_VERSION.to_string(),
+            snapshots: existing.clone(),
+        };
+        save_index(&dir, &index).unwrap();
+        assert_eq!(load_index(&dir).unwrap().snapshots, existing);
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(load_index(&dir).unwrap().snapshots.is_empty());
+    }
+}
@@ -0,0 +1,592 @@
+//! A small YAML reader for configuration documents
+//!
+//! Covers what hand-written config files use in practice: block mappings and
+//! sequences, plain and quoted scalars, `|`/`>` block scalars and flat,
+//! single-line flow collections of scalars. Anything else is rejected rather
+//! than read differently from a full YAML parser: anchors, aliases and tags,
+//! block scalar indentation indicators, nested or multi-line flow
+//! collections, directives and multi-document streams, and multi-line plain
+//! scalars. Plain scalars become strings unless they are `true`/`false`,
+//! `null`/`~` or an integer without leading zeros, so modes such as `0644`
+//! stay strings.
+
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+/// A syntax error, or a construct outside the subset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct YamlError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for YamlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+fn yaml_error(line: usize, msg: impl fmt::Display) -> YamlError {
+    YamlError {
+        line,
+        message: msg.to_string(),
+    }
+}
+
+fn leading_spaces(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+fn is_blank_or_comment(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.is_empty() || trimmed.starts_with('#')
+}
+
+fn is_seq_item(text: &str) -> bool {
+    text == "-" || text.starts_with("- ")
+}
+
+/// Cut a trailing ` # comment` that is outside quotes.
+fn strip_comment(text: &str) -> &str {
+    let mut quote = None;
+    let mut prev_space = true;
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if c == q => quote = None,
+            (None, '#') if prev_space => return text[..i].trim_end(),
+            _ => {}
+        }
+        prev_space = c == ' ' || c == '\t';
+    }
+    text.trim_end()
+}
+
+/// Split `key: rest`; `None` when the text is not a mapping entry.
+fn split_key(text: &str) -> Option<(String, String)> {
+    if text.starts_with(['[', '{']) {
+        return None;
+    }
+    if let Some(q) = text.chars().next().filter(|c| *c == '"' || *c == '\'') {
+        let end = text[1..].find(q)? + 1;
+        let after = &text[end + 1..];
+        let rest = after.strip_prefix(':')?;
+        if !rest.is_empty() && !rest.starts_with(' ') {
+            return None;
+        }
+        return Some((text[1..end].to_string(), rest.trim_start().to_string()));
+    }
+    let mut search = 0;
+    while let Some(i) = text[search..].find(':') {
+        let at = search + i;
+        let rest = &text[at + 1..];
+        if rest.is_empty() || rest.starts_with(' ') {
+            let key = text[..at].trim_end();
+            if key.is_empty() || key.contains(" #") {
+                return None;
+            }
+            return Some((key.to_string(), rest.trim_start().to_string()));
+        }
+        search = at + 1;
+    }
+    None
+}
+
+/// Split a flow collection body on commas outside quotes. A trailing comma
+/// is allowed; empty items are not.
+fn split_flow(line: usize, body: &str) -> Result<Vec<&str>, YamlError> {
+    let mut parts = Vec::new();
+    let mut quote = None;
+    let mut start = 0;
+    for (i, c) in body.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if c == q => quote = None,
+            (None, ',') => {
+                parts.push(body[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(body[start..].trim());
+    if parts.last() == Some(&"") {
+        parts.pop();
+    }
+    if parts.iter().any(|p| p.is_empty()) {
+        return Err(yaml_error(line, "empty entry in flow collection"));
+    }
+    Ok(parts)
+}
+
+fn nested_flow_error(line: usize) -> YamlError {
+    yaml_error(line, "nested flow collections are not supported")
+}
+
+fn parse_double_quoted(line: usize, text: &str) -> Result<String, YamlError> {
+    let body = text
+        .strip_prefix('"')
+        .and_then(|t| t.strip_suffix('"'))
+        .ok_or_else(|| yaml_error(line, "unterminated double-quoted string"))?;
+    let mut out = String::new();
+    let mut chars = body.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('r') => out.push('\r'),
+            Some('0') => out.push('\0'),
+            Some(c @ ('"' | '\\' | '/' | ' ')) => out.push(c),
+            other => {
+                return Err(yaml_error(
+                    line,
+                    format!("unsupported escape \\{}", other.unwrap_or(' ')),
+                ))
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Parse a single-line value: quoted, flow collection or plain scalar.
+fn parse_inline(line: usize, text: &str) -> Result<Value, YamlError> {
+    let text = text.trim();
+    if let Some(body) = text.strip_prefix('[') {
+        let body = body
+            .strip_suffix(']')
+            .ok_or_else(|| yaml_error(line, "flow sequences must fit on one line"))?;
+        return split_flow(line, body)?
+            .into_iter()
+            .map(|item| {
+                if item.starts_with(['[', '{']) {
+                    Err(nested_flow_error(line))
+                } else if split_key(item).is_some() {
+                    Err(yaml_error(
+                        line,
+                        "mappings inside flow sequences are not supported",
+                    ))
+                } else {
+                    parse_inline(line, item)
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array);
+    }
+    if let Some(body) = text.strip_prefix('{') {
+        let body = body
+            .strip_suffix('}')
+            .ok_or_else(|| yaml_error(line, "flow mappings must fit on one line"))?;
+        let mut map = Map::new();
+        for item in split_flow(line, body)? {
+            let (key, value) = split_key(item)
+                .ok_or_else(|| yaml_error(line, format!("expected 'key: value' in '{}'", item)))?;
+            if value.starts_with(['[', '{']) {
+                return Err(nested_flow_error(line));
+            }
+            if map.insert(key.clone(), parse_inline(line, &value)?).is_some() {
+                return Err(yaml_error(line, format!("duplicate key '{}'", key)));
+            }
+        }
+        return Ok(Value::Object(map));
+    }
+    if text.starts_with('"') {
+        return parse_double_quoted(line, text).map(Value::String);
+    }
+    if let Some(body) = text.strip_prefix('\'') {
+        let body = body
+            .strip_suffix('\'')
+            .ok_or_else(|| yaml_error(line, "unterminated single-quoted string"))?;
+        return Ok(Value::String(body.replace("''", "'")));
+    }
+    if text.starts_with(['&', '*', '!']) {
+        return Err(yaml_error(
+            line,
+            "anchors, aliases and tags are not supported",
+        ));
+    }
+    Ok(match text {
+        "" | "~" | "null" | "Null" | "NULL" => Value::Null,
+        "true" | "True" | "TRUE" => Value::Bool(true),
+        "false" | "False" | "FALSE" => Value::Bool(false),
+        _ => {
+            let digits = text.strip_prefix('-').unwrap_or(text);
+            let is_int = !digits.is_empty()
+                && digits.chars().all(|c| c.is_ascii_digit())
+                && (digits == "0" || !digits.starts_with('0'));
+            match text.parse::<i64>() {
+                Ok(n) if is_int => Value::from(n),
+                // Leading zeros (`0644`) stay strings, like file modes should
+                _ => Value::String(text.to_string()),
+            }
+        }
+    })
+}
+
+/// Indentation-driven reader over the document's lines.
+struct YamlReader<'a> {
+    lines: Vec<&'a str>,
+    pos: usize,
+    /// The rest of a `- key: value` item, read as if it were its own line at
+    /// the given indentation
+    inline: Option<(usize, String)>,
+    /// Whether document content has been read, after which `---` would
+    /// start a second document
+    started: bool,
+}
+
+impl<'a> YamlReader<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            lines: text.lines().collect(),
+            pos: 0,
+            inline: None,
+            started: false,
+        }
+    }
+
+    /// 1-based number of the line being read, for errors.
+    fn line_number(&self) -> usize {
+        if self.inline.is_some() {
+            self.pos
+        } else {
+            self.pos + 1
+        }
+    }
+
+    /// The next significant line as (indentation, text).
+    fn peek(&mut self) -> Result<Option<(usize, String)>, YamlError> {
+        if let Some(inline) = &self.inline {
+            return Ok(Some(inline.clone()));
+        }
+        while let Some(line) = self.lines.get(self.pos) {
+            if is_blank_or_comment(line) {
+                self.pos += 1;
+                continue;
+            }
+            let marker = strip_comment(line);
+            if marker == "---" && !self.started {
+                self.started = true;
+                self.pos += 1;
+                continue;
+            }
+            if marker == "---" || marker == "..." || marker.starts_with("--- ") {
+                return Err(yaml_error(
+                    self.pos + 1,
+                    "multi-document streams are not supported",
+                ));
+            }
+            if line.starts_with('%') {
+                return Err(yaml_error(self.pos + 1, "directives are not supported"));
+            }
+            self.started = true;
+            let indent = leading_spaces(line);
+            if line[indent..].starts_with('\t') {
+                return Err(yaml_error(
+                    self.pos + 1,
+                    "tabs are not allowed for indentation",
+                ));
+            }
+            return Ok(Some((indent, line[indent..].trim_end().to_string())));
+        }
+        Ok(None)
+    }
+
+    fn advance(&mut self) {
+        if self.inline.take().is_none() {
+            self.pos += 1;
+        }
+    }
+
+    fn parse_node(&mut self, min_indent: usize) -> Result<Value, YamlError> {
+        let Some((indent, text)) = self.peek()? else {
+            return Ok(Value::Null);
+        };
+        if indent < min_indent {
+            return Ok(Value::Null);
+        }
+        if is_seq_item(&text) {
+            self.parse_seq(indent)
+        } else if split_key(&text).is_some() {
+            self.parse_map(indent)
+        } else {
+            let line = self.line_number();
+            self.advance();
+            parse_inline(line, strip_comment(&text))
+        }
+    }
+
+    fn parse_map(&mut self, indent: usize) -> Result<Value, YamlError> {
+        let mut map = Map::new();
+        while let Some((i, text)) = self.peek()? {
+            if i > indent {
+                return Err(yaml_error(self.line_number(), "unexpected indentation"));
+            }
+            if i < indent || is_seq_item(&text) {
+                break;
+            }
+            let line = self.line_number();
+            let (key, rest) =
+                split_key(&text).ok_or_else(|| yaml_error(line, "expected 'key: value'"))?;
+            if key.starts_with(['&', '*', '!']) {
+                return Err(yaml_error(
+                    line,
+                    "anchors, aliases and tags are not supported",
+                ));
+            }
+            self.advance();
+            let value = self.parse_value(line, indent, &rest)?;
+            if map.insert(key.clone(), value).is_some() {
+                return Err(yaml_error(line, format!("duplicate key '{}'", key)));
+            }
+        }
+        Ok(Value::Object(map))
+    }
+
+    fn parse_seq(&mut self, indent: usize) -> Result<Value, YamlError> {
+        let mut items = Vec::new();
+        while let Some((i, text)) = self.peek()? {
+            if i > indent {
+                return Err(yaml_error(self.line_number(), "unexpected indentation"));
+            }
+            if i < indent || !is_seq_item(&text) {
+                break;
+            }
+            let line = self.line_number();
+            self.advance();
+            let rest = &text[1..];
+            let body = rest.trim_start();
+            let offset = indent + 1 + (rest.len() - body.len());
+            let item = if is_seq_item(body) || split_key(body).is_some() {
+                self.inline = Some((offset, body.to_string()));
+                self.parse_node(offset)?
+            } else {
+                self.parse_value(line, indent, body)?
+            };
+            items.push(item);
+        }
+        Ok(Value::Array(items))
+    }
+
+    /// The value after `key:` or `-`: inline, a block scalar, or a nested
+    /// block on the following lines.
+    fn parse_value(&mut self, line: usize, parent: usize, rest: &str) -> Result<Value, YamlError> {
+        let rest = strip_comment(rest);
+        if rest.starts_with(['|', '>']) {
+            return self.block_scalar(line, parent, rest);
+        }
+        if !rest.is_empty() {
+            return parse_inline(line, rest);
+        }
+        match self.peek()? {
+            // A sequence may sit at its key's own indentation
+            Some((i, text)) if i == parent && is_seq_item(&text) => self.parse_seq(i),
+            Some((i, _)) if i > parent => self.parse_node(i),
+            _ => Ok(Value::Null),
+        }
+    }
+
+    fn block_scalar(
+        &mut self,
+        line: usize,
+        parent: usize,
+        header: &str,
+    ) -> Result<Value, YamlError> {
+        let indicators = &header[1..];
+        if indicators.chars().any(|c| c.is_ascii_digit()) {
+            return Err(yaml_error(
+                line,
+                "block scalar indentation indicators are not supported",
+            ));
+        }
+        if indicators.len() > 1 || !indicators.chars().all(|c| c == '-' || c == '+') {
+            return Err(yaml_error(
+                line,
+                format!("invalid block scalar header '{}'", header),
+            ));
+        }
+        let mut body: Vec<&str> = Vec::new();
+        let mut block_indent = None;
+        while let Some(raw) = self.lines.get(self.pos) {
+            if raw.trim().is_empty() {
+                body.push("");
+                self.pos += 1;
+                continue;
+            }
+            let indent = leading_spaces(raw);
+            if indent <= parent || block_indent.is_some_and(|b| indent < b) {
+                break;
+            }
+            let b = *block_indent.get_or_insert(indent);
+            body.push(&raw[b..]);
+            self.pos += 1;
+        }
+
+        let content_end = body
+            .iter()
+            .rposition(|l| !l.is_empty())
+            .map_or(0, |i| i + 1);
+        let trailing = body.len() - content_end;
+        let body = &body[..content_end];
+        let mut text = if header.starts_with('>') {
+            let mut folded = String::new();
+            let mut after_break = true;
+            for line in body {
+                if line.is_empty() {
+                    folded.push('\n');
+                    after_break = true;
+                } else {
+                    if !after_break {
+                        folded.push(' ');
+                    }
+                    folded.push_str(line);
+                    after_break = false;
+                }
+            }
+            folded
+        } else {
+            body.join("\n")
+        };
+        if indicators.contains('+') {
+            text.push_str(&"\n".repeat(trailing + usize::from(!body.is_empty())));
+        } else if !indicators.contains('-') && !body.is_empty() {
+            text.push('\n');
+        }
+        Ok(Value::String(text))
+    }
+}
+
+/// Parse a YAML document of the supported subset into a JSON value.
+pub fn parse(text: &str) -> Result<Value, YamlError> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut reader = YamlReader::new(text);
+    let value = reader.parse_node(0)?;
+    if reader.peek()?.is_some() {
+        return Err(yaml_error(reader.line_number(), "unexpected content"));
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_supported_subset() {
+        let doc = r#"#cloud-config
+name: dev   # comment
+list: [git, "build essential"]
+users:
+  - default
+  - name: dev
+    uid: 1001
+files:
+- path: /etc/motd
+  content: |
+    Welcome
+      indented # not a comment
+
+  append: true
+cmds:
+  - "echo 'a: b'"
+  - [touch, /tmp/y z]
+empty: {a: 1}
+"#;
+        let value = parse(doc).unwrap();
+        assert_eq!(value["name"], json!("dev"));
+        assert_eq!(value["list"], json!(["git", "build essential"]));
+        assert_eq!(
+            value["users"],
+            json!(["default", {"name": "dev", "uid": 1001}])
+        );
+        assert_eq!(
+            value["files"][0]["content"],
+            json!("Welcome\n  indented # not a comment\n")
+        );
+        assert_eq!(value["files"][0]["append"], json!(true));
+        assert_eq!(value["cmds"], json!(["echo 'a: b'", ["touch", "/tmp/y z"]]));
+        assert_eq!(value["empty"], json!({"a": 1}));
+
+        let folded = parse("a: >-\n  one\n  two\n\n  three\nb: 'it''s'\nc: 0644").unwrap();
+        assert_eq!(
+            folded,
+            json!({"a": "one two\nthree", "b": "it's", "c": "0644"})
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_input() {
+        assert_eq!(parse("a: 1\n  b: 2").unwrap_err().line, 2);
+        assert!(parse("a: 1\na: 2").is_err());
+        assert!(parse("a:\n\tb: 1").is_err());
+    }
+
+    #[test]
+    fn rejects_anchors_aliases_and_tags() {
+        for doc in [
+            "a: &x 1",
+            "a: *x",
+            "a: !!str 1",
+            "base: &b\n  k: v",
+            "&x a: 1",
+            "- &x a: 1",
+            "- *x",
+            "<<: *base",
+        ] {
+            let err = parse(doc).unwrap_err();
+            assert!(err.message.contains("anchors"), "{}: {}", doc, err);
+        }
+    }
+
+    #[test]
+    fn rejects_block_scalar_indentation_indicators() {
+        for doc in ["a: |2\n   text", "a: >1-\n text", "a: |+2\n  text"] {
+            let err = parse(doc).unwrap_err();
+            assert!(err.message.contains("indentation indicator"), "{}: {}", doc, err);
+        }
+        assert!(parse("a: |x\n  text").is_err());
+        assert!(parse("a: |-+\n  text").is_err());
+    }
+
+    #[test]
+    fn rejects_nested_and_multiline_flow_collections() {
+        for doc in [
+            "a: [1, [2]]",
+            "a: [1, {b: 2}]",
+            "a: {b: [1, 2]}",
+            "a: {b: {c: 1}}",
+            "a: [b: 1]",
+            "a: [1,, 2]",
+            "a: {b: 1, b: 2}",
+            "a: [1,\n  2]",
+            "a: {b: 1,\n  c: 2}",
+        ] {
+            assert!(parse(doc).is_err(), "{} should be rejected", doc);
+        }
+        assert_eq!(parse("a: [1, 2,]").unwrap(), json!({"a": [1, 2]}));
+        assert_eq!(parse("a: []").unwrap(), json!({"a": []}));
+    }
+
+    #[test]
+    fn rejects_multi_document_streams() {
+        assert_eq!(parse("---\na: 1").unwrap(), json!({"a": 1}));
+        assert_eq!(parse("# header\n--- # start\na: 1").unwrap(), json!({"a": 1}));
+        for doc in [
+            "a: 1\n---\nb: 2",
+            "---\na: 1\n---\nb: 2",
+            "a: 1\n...",
+            "--- a: 1",
+            "%YAML 1.2\n---\na: 1",
+        ] {
+            assert!(parse(doc).is_err(), "{} should be rejected", doc);
+        }
+        let err = parse("a: 1\n---\nb: 2").unwrap_err();
+        assert_eq!(err.line, 2);
+        assert!(err.message.contains("multi-document"));
+    }
+}
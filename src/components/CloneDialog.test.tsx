@@ -3,9 +3,11 @@ import { render, screen, fireEvent, act } from '@testing-library/react';
 import { CloneDialog } from './CloneDialog';
 import { wslService } from '../services/wslService';
 import { useDistroStore } from '../store/distroStore';
+import { useNotificationStore } from '../store/notificationStore';
 
 // Mock the stores
 vi.mock('../store/distroStore');
+vi.mock('../store/notificationStore');
 
 // Mock WSL service
 vi.mock('../services/wslService', () => ({
@@ -23,6 +25,7 @@ vi.mock('@tauri-apps/plugin-dialog', () => ({
 
 describe('CloneDialog', () => {
   const mockFetchDistros = vi.fn();
+  const mockAddNotification = vi.fn();
   const mockOnClose = vi.fn();
 
   const defaultProps = {
@@ -45,7 +48,11 @@ describe('CloneDialog', () => {
 
     vi.mocked(wslService.getDefaultDistroPath).mockResolvedValue('C:\\WSL\\Ubuntu-clone');
     vi.mocked(wslService.validateInstallPath).mockResolvedValue({ isValid: true });
-    vi.mocked(wslService.cloneDistribution).mockResolvedValue(undefined);
+    vi.mocked(useNotificationStore).mockReturnValue({
+      addNotification: mockAddNotification,
+    } as any);
+
+    vi.mocked(wslService.cloneDistribution).mockResolvedValue({ method: 'vhd', fallback: null });
   });
 
   afterEach(() => {
@@ -204,7 +211,32 @@ describe('CloneDialog', () => {
       expect(wslService.cloneDistribution).toHaveBeenCalledWith(
         'Ubuntu',
         'Ubuntu-clone',
-        undefined // default path
+        undefined, // default path
+        undefined // no customization
+      );
+    });
+
+    it('reports the clone method and fallback reason', async () => {
+      vi.mocked(wslService.cloneDistribution).mockResolvedValue({ method: 'tar', fallback: 'wsl1Source' });
+
+      render(<CloneDialog {...defaultProps} />);
+
+      // Wait for path validation
+      await act(async () => {
+        await vi.advanceTimersByTimeAsync(500);
+      });
+
+      fireEvent.click(screen.getByTestId('clone-confirm-button'));
+
+      await act(async () => {
+        await vi.advanceTimersByTimeAsync(100);
+      });
+
+      expect(mockAddNotification).toHaveBeenCalledWith(
+        expect.objectContaining({
+          type: 'success',
+          message: expect.stringContaining('WSL 1'),
+        })
       );
     });
 
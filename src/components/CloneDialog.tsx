@@ -1,9 +1,15 @@
-import { useState, useEffect } from "react";
+import { useState, useEffect, useCallback } from "react";
 import { useTranslation } from "react-i18next";
 import { open } from "@tauri-apps/plugin-dialog";
 import { wslService } from "../services/wslService";
 import { useDistroStore } from "../store/distroStore";
+import { useNotificationStore } from "../store/notificationStore";
+import { cleanExportFilter, EMPTY_EXPORT_FILTER, formatExcludedBytes } from "../types/exportFilter";
+import type { ExportFilter } from "../types/exportFilter";
+import type { RootfsCustomization } from "../types/rootfs";
+import { ExportFilterEditor } from "./ExportFilterEditor";
 import { CopyIcon } from "./icons";
+import { RootfsCustomizationEditor } from "./RootfsCustomizationEditor";
 import { Portal } from "./ui/Portal";
 import { Input, PathInput } from "./ui/Input";
 
@@ -25,7 +31,16 @@ export function CloneDialog({ isOpen, sourceName, onClose }: CloneDialogProps) {
   const [isValidatingPath, setIsValidatingPath] = useState(false);
   // Default path fetched from backend (with env vars expanded)
   const [defaultPath, setDefaultPath] = useState<string>("");
+  const [customization, setCustomization] = useState<RootfsCustomization | undefined>();
+  const [customizationError, setCustomizationError] = useState<string | null>(null);
+  const [filter, setFilter] = useState<ExportFilter>(EMPTY_EXPORT_FILTER);
   const { distributions, fetchDistros } = useDistroStore();
+  const { addNotification } = useNotificationStore();
+
+  const handleCustomizationChange = useCallback((value: RootfsCustomization | undefined, err: string | null) => {
+    setCustomization(value);
+    setCustomizationError(err);
+  }, []);
 
   // Fetch default path from backend when name changes
   useEffect(() => {
@@ -58,6 +73,7 @@ export function CloneDialog({ isOpen, sourceName, onClose }: CloneDialogProps) {
       setNewName(`${sourceName}-clone`);
       setIsCustomPath(false);
       setCustomPath("");
+      setFilter(EMPTY_EXPORT_FILTER);
       setError(null);
       setPathError(null);
     }
@@ -159,7 +175,18 @@ export function CloneDialog({ isOpen, sourceName, onClose }: CloneDialogProps) {
     try {
       // Pass custom path if set, otherwise undefined (backend uses default)
       const locationToUse = isCustomPath && customPath.trim() ? customPath.trim() : undefined;
-      await wslService.cloneDistribution(sourceName, trimmedName, locationToUse);
+      const result = await wslService.cloneDistribution(sourceName, trimmedName, locationToUse, customization, cleanExportFilter(filter));
+      addNotification({
+        type: "success",
+        title: t('clone.successTitle'),
+        message: [
+          t('clone.successMessage', { name: trimmedName, method: t(`clone.method.${result.method}`) }),
+          result.fallback ? t(`clone.fallback.${result.fallback}`) : null,
+          result.filterReport
+            ? t('clone.excluded', { size: formatExcludedBytes(result.filterReport.excludedBytes), count: result.filterReport.excludedEntries })
+            : null,
+        ].filter(Boolean).join(" "),
+      });
       await fetchDistros();
       handleClose();
     } catch (err) {
@@ -263,6 +290,11 @@ export function CloneDialog({ isOpen, sourceName, onClose }: CloneDialogProps) {
           />
         </div>
 
+        <div className="mb-4 space-y-3">
+          <ExportFilterEditor value={filter} onChange={setFilter} disabled={isCloning} />
+          <RootfsCustomizationEditor onChange={handleCustomizationChange} disabled={isCloning} />
+        </div>
+
         {isCloning && (
           <div data-testid="clone-progress" className="mb-4 p-3 bg-theme-bg-tertiary border border-theme-border-secondary rounded-lg">
             <div className="flex items-center gap-3 text-theme-text-secondary text-sm">
@@ -297,7 +329,7 @@ export function CloneDialog({ isOpen, sourceName, onClose }: CloneDialogProps) {
           </button>
           <button
             onClick={handleClone}
-            disabled={isCloning || !newName.trim() || !!validationError || !!pathError || isValidatingPath}
+            disabled={isCloning || !newName.trim() || !!validationError || !!pathError || isValidatingPath || !!customizationError}
             data-testid="clone-confirm-button"
             className="px-4 py-2 text-sm font-medium bg-theme-accent-primary hover:opacity-90 text-theme-bg-primary rounded-lg transition-colors disabled:opacity-50 disabled:cursor-not-allowed flex items-center gap-2"
           >
@@ -0,0 +1,84 @@
+import { useState, useEffect } from "react";
+import { useTranslation } from "react-i18next";
+import { wslService } from "../services/wslService";
+import { ChevronDownIcon } from "./icons";
+import { TextArea } from "./ui/Input";
+
+interface CloudConfigEditorProps {
+  /**
+   * Called with the user data (undefined when empty) and a validation error,
+   * if any. Must be stable (wrap in useCallback).
+   */
+  onChange: (userData: string | undefined, error: string | null) => void;
+  disabled?: boolean;
+}
+
+/** Collapsible `#cloud-config` user data applied after install */
+export function CloudConfigEditor({ onChange, disabled }: CloudConfigEditorProps) {
+  const { t } = useTranslation("dialogs");
+  const [expanded, setExpanded] = useState(false);
+  const [userData, setUserData] = useState("");
+  const [error, setError] = useState<string | null>(null);
+  const [ignoredKeys, setIgnoredKeys] = useState<string[]>([]);
+
+  // Validate in the backend (debounced) so the dialog can block install
+  useEffect(() => {
+    const trimmed = userData.trim();
+    if (!trimmed) {
+      setError(null);
+      setIgnoredKeys([]);
+      return;
+    }
+    const timeoutId = setTimeout(async () => {
+      try {
+        setIgnoredKeys(await wslService.validateCloudConfig(userData));
+        setError(null);
+      } catch (err) {
+        setIgnoredKeys([]);
+        setError(err instanceof Error ? err.message : String(err));
+      }
+    }, 300);
+    return () => clearTimeout(timeoutId);
+  }, [userData]);
+
+  useEffect(() => {
+    onChange(userData.trim() ? userData : undefined, error);
+  }, [userData, error, onChange]);
+
+  return (
+    <div data-testid="cloud-config-editor" className="border border-theme-border-secondary rounded-lg">
+      <button
+        type="button"
+        onClick={() => setExpanded(!expanded)}
+        data-testid="cloud-config-toggle"
+        className="w-full flex items-center justify-between px-3 py-2 text-sm font-medium text-theme-text-primary"
+      >
+        {t('cloudConfig.title')}
+        <ChevronDownIcon size="sm" className={`transition-transform ${expanded ? "rotate-180" : ""}`} />
+      </button>
+
+      {expanded && (
+        <div className="px-3 pb-3 space-y-2">
+          <p className="text-xs text-theme-text-muted">{t('cloudConfig.description')}</p>
+          <TextArea
+            value={userData}
+            onChange={(e) => setUserData(e.target.value)}
+            placeholder={"#cloud-config\npackages: [git, curl]\ntimezone: Europe/London\nruncmd:\n  - echo done"}
+            rows={8}
+            className="font-mono text-xs"
+            disabled={disabled}
+            data-testid="cloud-config-input"
+          />
+          {ignoredKeys.length > 0 && (
+            <p className="text-xs text-theme-status-warning">
+              {t('cloudConfig.ignoredKeys', { keys: ignoredKeys.join(", ") })}
+            </p>
+          )}
+          {error && (
+            <p data-testid="cloud-config-error" className="text-xs text-theme-status-error">{error}</p>
+          )}
+        </div>
+      )}
+    </div>
+  );
+}
@@ -7,6 +7,7 @@ import {
   INSTALL_SOURCE_COLORS,
 } from "../types/distribution";
 import { useResourceStore } from "../store/resourceStore";
+import { useDistroStore } from "../store/distroStore";
 import { Portal } from "./ui/Portal";
 import {
   InfoIcon,
@@ -160,6 +161,11 @@ export function DistroInfoDialog({
   const [wslConfLoading, setWslConfLoading] = useState(false);
   const [wslConfError, setWslConfError] = useState<string | null>(null);
 
+  // cloud-config re-run state
+  const { fetchDistros } = useDistroStore();
+  const [isRerunning, setIsRerunning] = useState(false);
+  const [rerunError, setRerunError] = useState<string | null>(null);
+
   // Handle Escape key to close the dialog (global listener for reliability)
   useEffect(() => {
     if (!isOpen) return;
@@ -200,6 +206,23 @@ export function DistroInfoDialog({
 
   if (!isOpen) return null;
 
+  const handleRerunCloudConfig = async () => {
+    setIsRerunning(true);
+    setRerunError(null);
+    try {
+      const report = await wslService.rerunCloudConfig(distro.name);
+      const failed = report.modules.filter((m) => !m.success);
+      if (failed.length > 0) {
+        setRerunError(failed.map((m) => `${t(`cloudConfig.modules.${m.module}`)}: ${m.output}`).join("\n"));
+      }
+      await fetchDistros(true);
+    } catch (err) {
+      setRerunError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setIsRerunning(false);
+    }
+  };
+
   const installSource = metadata?.installSource || "unknown";
   const sourceColor = INSTALL_SOURCE_COLORS[installSource];
 
@@ -371,6 +394,39 @@ export function DistroInfoDialog({
                   testId="info-source-ref"
                 />
               )}
+              {metadata?.vhdImport && (
+                <InfoRow
+                  label={t('distroInfo.vhdImport')}
+                  value={t(`distroInfo.vhdImportMode.${metadata.vhdImport}`)}
+                  testId="info-vhd-import"
+                />
+              )}
+              {metadata?.restoredFromSnapshot && (
+                <InfoRow
+                  label={t('distroInfo.restoredFromSnapshot')}
+                  value={<span className="font-mono text-xs">{metadata.restoredFromSnapshot}</span>}
+                  testId="info-restored-snapshot"
+                />
+              )}
+              {metadata?.manifestUrl && (
+                <InfoRow
+                  label={t('distroInfo.manifest')}
+                  value={
+                    <span className="flex items-center gap-1">
+                      <span
+                        className="truncate max-w-[320px] font-mono text-xs"
+                        title={metadata.manifestUrl}
+                      >
+                        {metadata.manifestFlavor
+                          ? `${metadata.manifestFlavor} · ${metadata.manifestUrl}`
+                          : metadata.manifestUrl}
+                      </span>
+                      <CopyButton text={metadata.manifestUrl} label="URL" />
+                    </span>
+                  }
+                  testId="info-manifest"
+                />
+              )}
               <InfoRow
                 label={t('distroInfo.installed')}
                 value={
@@ -385,6 +441,42 @@ export function DistroInfoDialog({
                 }
                 testId="info-installed-at"
               />
+              {metadata?.provisioning && (
+                <InfoRow
+                  label={t('distroInfo.cloudConfig')}
+                  value={
+                    <span className="flex items-center gap-2">
+                      <span
+                        className={
+                          metadata.provisioning.state === "succeeded"
+                            ? "text-theme-status-success"
+                            : "text-theme-status-error"
+                        }
+                        title={formatDate(metadata.provisioning.updatedAt)}
+                      >
+                        {t(`distroInfo.cloudConfigState.${metadata.provisioning.state}`)}
+                        {metadata.provisioning.failedModules.length > 0 &&
+                          ` (${metadata.provisioning.failedModules
+                            .map((m) => t(`cloudConfig.modules.${m}`))
+                            .join(", ")})`}
+                      </span>
+                      <button
+                        type="button"
+                        onClick={handleRerunCloudConfig}
+                        disabled={isRerunning}
+                        data-testid="info-cloud-config-rerun"
+                        className="px-2 py-0.5 text-xs font-medium text-theme-text-secondary bg-theme-bg-tertiary hover:bg-theme-bg-hover rounded transition-colors disabled:opacity-50"
+                      >
+                        {isRerunning ? t('distroInfo.cloudConfigRerunning') : t('distroInfo.cloudConfigRerun')}
+                      </button>
+                    </span>
+                  }
+                  testId="info-cloud-config"
+                />
+              )}
+              {rerunError && (
+                <p className="text-xs text-theme-status-error whitespace-pre-wrap py-1">{rerunError}</p>
+              )}
             </div>
           </div>
 
@@ -196,12 +196,17 @@ export function DistroSourcesSettings() {
     if (!editingId || !downloadForm.name || !downloadForm.url) return;
 
     try {
+      // Keep checksums and per-architecture downloads unless the URL was changed
+      const original = catalog?.downloadDistros.find((d) => d.id === editingId);
+      const urlUnchanged = original?.url === downloadForm.url;
       const distro: DownloadDistro = {
         id: editingId,
         name: downloadForm.name,
         description: downloadForm.description,
         url: downloadForm.url,
         size: downloadForm.size || undefined,
+        sha256: urlUnchanged ? original?.sha256 : undefined,
+        architectures: urlUnchanged ? original?.architectures : undefined,
         enabled: true,
       };
       const updated = await wslService.updateDownloadDistro(distro);
@@ -640,6 +645,19 @@ export function DistroSourcesSettings() {
                           {t('distroSources.badge.builtIn')}
                         </span>
                       )}
+                      {distro.subscriptionId && (
+                        <span
+                          className="text-xs px-1.5 py-0.5 bg-theme-bg-tertiary text-theme-text-muted rounded-sm"
+                          title={t('distroSources.badge.subscribedHint')}
+                        >
+                          {t('distroSources.badge.subscribed', { source: distro.subscriptionId })}
+                        </span>
+                      )}
+                      {distro.hasLocalOverride && (
+                        <span className="text-xs px-1.5 py-0.5 bg-theme-bg-tertiary text-theme-text-muted rounded-sm">
+                          {t('distroSources.badge.overridden')}
+                        </span>
+                      )}
                     </div>
                     <p className="text-xs text-theme-text-muted truncate">{distro.description}</p>
                     {distro.size && <p className="text-xs text-theme-text-muted/70">{distro.size}</p>}
@@ -651,7 +669,7 @@ export function DistroSourcesSettings() {
                     colorScheme="blue"
                     onClick={() => startEditDownload(distro)}
                   />
-                  {!distro.isBuiltIn && (
+                  {!distro.isBuiltIn && !distro.subscriptionId && (
                     <IconButton
                       icon={<TrashIcon size="sm" />}
                       label={t('distroSources.action.delete')}
@@ -788,6 +806,19 @@ export function DistroSourcesSettings() {
                           {t('distroSources.badge.builtIn')}
                         </span>
                       )}
+                      {image.subscriptionId && (
+                        <span
+                          className="text-xs px-1.5 py-0.5 bg-theme-bg-tertiary text-theme-text-muted rounded-sm"
+                          title={t('distroSources.badge.subscribedHint')}
+                        >
+                          {t('distroSources.badge.subscribed', { source: image.subscriptionId })}
+                        </span>
+                      )}
+                      {image.hasLocalOverride && (
+                        <span className="text-xs px-1.5 py-0.5 bg-theme-bg-tertiary text-theme-text-muted rounded-sm">
+                          {t('distroSources.badge.overridden')}
+                        </span>
+                      )}
                     </div>
                     <p className="text-xs text-theme-text-muted">{image.description}</p>
                     <p className="text-xs text-theme-text-muted/70 truncate">{image.image}</p>
@@ -799,7 +830,7 @@ export function DistroSourcesSettings() {
                     colorScheme="orange"
                     onClick={() => startEditContainer(image)}
                   />
-                  {!image.isBuiltIn && (
+                  {!image.isBuiltIn && !image.subscriptionId && (
                     <IconButton
                       icon={<TrashIcon size="sm" />}
                       label={t('distroSources.action.delete')}
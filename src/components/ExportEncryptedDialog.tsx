@@ -0,0 +1,257 @@
+import { useState, useEffect } from "react";
+import { useTranslation } from "react-i18next";
+import { wslService } from "../services/wslService";
+import { useNotificationStore } from "../store/notificationStore";
+import type { EncryptionKey } from "../types/encryption";
+import { LockIcon } from "./icons";
+import { Portal } from "./ui/Portal";
+import { Input, RadioButton, TextArea } from "./ui/Input";
+
+interface ExportEncryptedDialogProps {
+  isOpen: boolean;
+  distroName: string;
+  onClose: () => void;
+}
+
+type Mode = "passphrase" | "recipient";
+
+const MIN_PASSPHRASE_LENGTH = 8;
+const RECIPIENT_KEY = /^age1[0-9a-z]{58}$/;
+
+export function ExportEncryptedDialog({ isOpen, distroName, onClose }: ExportEncryptedDialogProps) {
+  const { t } = useTranslation("dialogs");
+  const { addNotification } = useNotificationStore();
+  const [mode, setMode] = useState<Mode>("passphrase");
+  const [passphrase, setPassphrase] = useState("");
+  const [confirmPassphrase, setConfirmPassphrase] = useState("");
+  const [publicKey, setPublicKey] = useState("");
+  const [generatedSecretKey, setGeneratedSecretKey] = useState<string | null>(null);
+  const [isExporting, setIsExporting] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (isOpen) {
+      setMode("passphrase");
+      setPassphrase("");
+      setConfirmPassphrase("");
+      setPublicKey("");
+      setGeneratedSecretKey(null);
+      setError(null);
+    }
+  }, [isOpen, distroName]);
+
+  const validationError = (() => {
+    if (mode === "passphrase") {
+      if (passphrase && passphrase.length < MIN_PASSPHRASE_LENGTH) {
+        return t('exportEncrypted.errorPassphraseShort', { min: MIN_PASSPHRASE_LENGTH }) as string;
+      }
+      if (confirmPassphrase && passphrase !== confirmPassphrase) {
+        return t('exportEncrypted.errorPassphraseMismatch') as string;
+      }
+    } else if (publicKey.trim() && !RECIPIENT_KEY.test(publicKey.trim())) {
+      return t('exportEncrypted.errorPublicKey') as string;
+    }
+    return null;
+  })();
+
+  const isComplete = mode === "passphrase" ? !!passphrase && passphrase === confirmPassphrase : !!publicKey.trim();
+
+  if (!isOpen) return null;
+
+  const handleGenerate = async () => {
+    try {
+      const pair = await wslService.generateEncryptionKey();
+      setPublicKey(pair.publicKey);
+      setGeneratedSecretKey(pair.secretKey);
+    } catch (err) {
+      setError(typeof err === "string" ? err : err instanceof Error ? err.message : String(err));
+    }
+  };
+
+  const handleCopySecretKey = async () => {
+    if (!generatedSecretKey) return;
+    try {
+      await navigator.clipboard.writeText(generatedSecretKey);
+    } catch {
+      // Clipboard API unavailable; the key can still be selected and copied
+    }
+  };
+
+  const handleExport = async () => {
+    if (validationError || !isComplete) return;
+
+    const key: EncryptionKey =
+      mode === "passphrase" ? { kind: "passphrase", passphrase } : { kind: "recipient", publicKey: publicKey.trim() };
+
+    setError(null);
+    setIsExporting(true);
+    try {
+      const path = await wslService.exportDistributionEncrypted(distroName, key);
+      if (!path) return;
+      addNotification({
+        type: "success",
+        title: t('exportEncrypted.successTitle'),
+        message: t('exportEncrypted.successMessage', { path }),
+      });
+      onClose();
+    } catch (err) {
+      const errorMessage = typeof err === "string" ? err : err instanceof Error ? err.message : t('exportEncrypted.errorFailed');
+      setError(errorMessage);
+    } finally {
+      setIsExporting(false);
+    }
+  };
+
+  return (
+    <Portal>
+      <div className="fixed inset-0 z-[100] flex items-center justify-center">
+        <div className="absolute inset-0 bg-theme-bg-primary/80 backdrop-blur-xs" onClick={isExporting ? undefined : onClose} />
+
+        <div
+          role="dialog"
+          aria-modal="true"
+          data-testid="export-encrypted-dialog"
+          className="relative bg-theme-bg-secondary border border-theme-border-secondary rounded-xl shadow-2xl shadow-black/50 max-w-lg w-full mx-4 p-6 max-h-[90vh] overflow-y-auto"
+        >
+          <h2 className="text-xl font-semibold text-theme-text-primary mb-2">{t('exportEncrypted.title')}</h2>
+          <p className="text-sm text-theme-text-secondary mb-4">
+            {t('exportEncrypted.subtitle')} <span className="text-theme-status-warning font-medium">{distroName}</span>
+          </p>
+
+          {error && (
+            <div
+              data-testid="export-encrypted-error"
+              className="mb-4 p-3 bg-[rgba(var(--status-error-rgb),0.2)] border border-[rgba(var(--status-error-rgb),0.4)] rounded-lg text-theme-status-error text-sm"
+            >
+              {error}
+            </div>
+          )}
+
+          <div className="space-y-4 mb-6">
+            <div className="flex gap-4">
+              <RadioButton
+                name="exportEncryptionMode"
+                checked={mode === "passphrase"}
+                onChange={() => setMode("passphrase")}
+                disabled={isExporting}
+                inline
+                labelClassName="text-theme-text-secondary"
+                label={t('exportEncrypted.modePassphrase')}
+              />
+              <RadioButton
+                name="exportEncryptionMode"
+                checked={mode === "recipient"}
+                onChange={() => setMode("recipient")}
+                disabled={isExporting}
+                inline
+                labelClassName="text-theme-text-secondary"
+                label={t('exportEncrypted.modeRecipient')}
+              />
+            </div>
+
+            {mode === "passphrase" ? (
+              <>
+                <Input
+                  type="password"
+                  label={t('exportEncrypted.passphraseLabel')}
+                  helperText={t('exportEncrypted.passphraseHelp')}
+                  value={passphrase}
+                  onChange={(e) => setPassphrase(e.target.value)}
+                  autoComplete="new-password"
+                  disabled={isExporting}
+                  data-testid="export-encrypted-passphrase-input"
+                />
+                <Input
+                  type="password"
+                  label={t('exportEncrypted.confirmLabel')}
+                  value={confirmPassphrase}
+                  onChange={(e) => setConfirmPassphrase(e.target.value)}
+                  autoComplete="new-password"
+                  disabled={isExporting}
+                  data-testid="export-encrypted-confirm-input"
+                />
+              </>
+            ) : (
+              <>
+                <Input
+                  label={t('exportEncrypted.publicKeyLabel')}
+                  helperText={t('exportEncrypted.publicKeyHelp')}
+                  value={publicKey}
+                  onChange={(e) => {
+                    setPublicKey(e.target.value);
+                    setGeneratedSecretKey(null);
+                  }}
+                  placeholder="age1..."
+                  className="font-mono"
+                  disabled={isExporting}
+                  data-testid="export-encrypted-public-key-input"
+                />
+                <button
+                  type="button"
+                  onClick={handleGenerate}
+                  disabled={isExporting}
+                  data-testid="export-encrypted-generate-button"
+                  className="px-3 py-1.5 text-xs font-medium text-theme-text-secondary bg-theme-bg-tertiary hover:bg-theme-bg-hover rounded-lg transition-colors disabled:opacity-50"
+                >
+                  {t('exportEncrypted.generate')}
+                </button>
+                {generatedSecretKey && (
+                  <div className="space-y-2">
+                    <TextArea
+                      label={t('exportEncrypted.secretKeyLabel')}
+                      value={generatedSecretKey}
+                      readOnly
+                      rows={2}
+                      className="font-mono"
+                      data-testid="export-encrypted-secret-key"
+                    />
+                    <div className="flex items-start justify-between gap-3">
+                      <p className="text-xs text-theme-status-warning">{t('exportEncrypted.secretKeyWarning')}</p>
+                      <button
+                        type="button"
+                        onClick={handleCopySecretKey}
+                        className="shrink-0 px-3 py-1.5 text-xs font-medium text-theme-text-secondary bg-theme-bg-tertiary hover:bg-theme-bg-hover rounded-lg transition-colors"
+                      >
+                        {t('exportEncrypted.copySecretKey')}
+                      </button>
+                    </div>
+                  </div>
+                )}
+              </>
+            )}
+
+            <p className="text-xs text-theme-text-muted">{t('exportEncrypted.streamNote')}</p>
+            {validationError && (
+              <p data-testid="export-encrypted-validation-error" className="text-xs text-theme-status-error">{validationError}</p>
+            )}
+          </div>
+
+          <div className="flex items-center justify-end gap-3">
+            <button
+              onClick={onClose}
+              disabled={isExporting}
+              className="px-4 py-2 text-sm font-medium text-theme-text-secondary bg-theme-bg-tertiary hover:bg-theme-bg-hover rounded-lg transition-colors disabled:opacity-50"
+            >
+              {t('common:button.cancel')}
+            </button>
+            <button
+              onClick={handleExport}
+              disabled={isExporting || !!validationError || !isComplete}
+              data-testid="export-encrypted-confirm-button"
+              className="px-4 py-2 text-sm font-medium bg-theme-accent-primary hover:opacity-90 text-theme-bg-primary rounded-lg transition-colors disabled:opacity-50 disabled:cursor-not-allowed flex items-center gap-2"
+            >
+              {isExporting ? (
+                t('exportEncrypted.exporting')
+              ) : (
+                <>
+                  <LockIcon size="sm" />
+                  {t('exportEncrypted.export')}
+                </>
+              )}
+            </button>
+          </div>
+        </div>
+      </div>
+    </Portal>
+  );
+}
@@ -0,0 +1,102 @@
+import { useState, useEffect } from "react";
+import { useTranslation } from "react-i18next";
+import { wslService } from "../services/wslService";
+import { FILTER_PRESETS } from "../types/exportFilter";
+import type { ExportFilter, FilterPreset } from "../types/exportFilter";
+import { ChevronDownIcon } from "./icons";
+import { Checkbox, TextArea } from "./ui/Input";
+
+interface ExportFilterEditorProps {
+  value: ExportFilter;
+  onChange: (filter: ExportFilter) => void;
+  disabled?: boolean;
+  /** Start expanded instead of collapsed */
+  defaultExpanded?: boolean;
+}
+
+const toLines = (patterns: string[]) => patterns.join("\n");
+const fromLines = (text: string) => text.split("\n");
+
+/** Collapsible editor for the presets and glob patterns left out of an export, clone or backup */
+export function ExportFilterEditor({ value, onChange, disabled, defaultExpanded = false }: ExportFilterEditorProps) {
+  const { t } = useTranslation("dialogs");
+  const [expanded, setExpanded] = useState(defaultExpanded);
+  const [presetPatterns, setPresetPatterns] = useState<Partial<Record<FilterPreset, string[]>>>({});
+
+  useEffect(() => {
+    if (!expanded) return;
+    wslService
+      .listExportFilterPresets()
+      .then((presets) => setPresetPatterns(Object.fromEntries(presets.map((p) => [p.preset, p.patterns]))))
+      .catch(() => setPresetPatterns({}));
+  }, [expanded]);
+
+  const togglePreset = (preset: FilterPreset, checked: boolean) => {
+    const presets = checked ? [...value.presets, preset] : value.presets.filter((p) => p !== preset);
+    onChange({ ...value, presets: FILTER_PRESETS.filter((p) => presets.includes(p)) });
+  };
+
+  const activeCount = value.presets.length + value.exclude.filter((p) => p.trim()).length;
+
+  return (
+    <div data-testid="export-filter" className="border border-theme-border-secondary rounded-lg">
+      <button
+        type="button"
+        onClick={() => setExpanded(!expanded)}
+        data-testid="export-filter-toggle"
+        className="w-full flex items-center justify-between px-3 py-2 text-sm font-medium text-theme-text-primary"
+      >
+        <span>
+          {t('exportFilter.title')}
+          {activeCount > 0 && (
+            <span className="ml-2 text-xs font-normal text-theme-text-muted">{t('exportFilter.activeCount', { count: activeCount })}</span>
+          )}
+        </span>
+        <ChevronDownIcon size="sm" className={`transition-transform ${expanded ? "rotate-180" : ""}`} />
+      </button>
+
+      {expanded && (
+        <div className="px-3 pb-3 space-y-3">
+          <p className="text-xs text-theme-text-muted">{t('exportFilter.description')}</p>
+
+          <div className="space-y-2">
+            {FILTER_PRESETS.map((preset) => (
+              <Checkbox
+                key={preset}
+                label={t(`exportFilter.presets.${preset}`)}
+                description={presetPatterns[preset]?.join(", ")}
+                checked={value.presets.includes(preset)}
+                onChange={(e) => togglePreset(preset, e.target.checked)}
+                disabled={disabled}
+                data-testid={`export-filter-preset-${preset}`}
+              />
+            ))}
+          </div>
+
+          <TextArea
+            label={t('exportFilter.excludeLabel')}
+            helperText={t('exportFilter.excludeHelp')}
+            value={toLines(value.exclude)}
+            onChange={(e) => onChange({ ...value, exclude: fromLines(e.target.value) })}
+            placeholder={"node_modules\n~/Downloads\n/var/log/*.gz"}
+            rows={3}
+            className="font-mono"
+            disabled={disabled}
+            data-testid="export-filter-exclude"
+          />
+          <TextArea
+            label={t('exportFilter.includeLabel')}
+            helperText={t('exportFilter.includeHelp')}
+            value={toLines(value.include)}
+            onChange={(e) => onChange({ ...value, include: fromLines(e.target.value) })}
+            placeholder="~/.cache/important"
+            rows={2}
+            className="font-mono"
+            disabled={disabled}
+            data-testid="export-filter-include"
+          />
+        </div>
+      )}
+    </div>
+  );
+}
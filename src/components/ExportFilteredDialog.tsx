@@ -0,0 +1,118 @@
+import { useState, useEffect } from "react";
+import { useTranslation } from "react-i18next";
+import { wslService } from "../services/wslService";
+import { useNotificationStore } from "../store/notificationStore";
+import { cleanExportFilter, EMPTY_EXPORT_FILTER, formatExcludedBytes } from "../types/exportFilter";
+import type { ExportFilter } from "../types/exportFilter";
+import { ExportFilterEditor } from "./ExportFilterEditor";
+import { UploadIcon } from "./icons";
+import { Portal } from "./ui/Portal";
+
+interface ExportFilteredDialogProps {
+  isOpen: boolean;
+  distroName: string;
+  onClose: () => void;
+}
+
+export function ExportFilteredDialog({ isOpen, distroName, onClose }: ExportFilteredDialogProps) {
+  const { t } = useTranslation("dialogs");
+  const { addNotification } = useNotificationStore();
+  const [filter, setFilter] = useState<ExportFilter>({ ...EMPTY_EXPORT_FILTER, presets: ["devCaches", "packageManagerCaches"] });
+  const [isExporting, setIsExporting] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (isOpen) {
+      setFilter({ ...EMPTY_EXPORT_FILTER, presets: ["devCaches", "packageManagerCaches"] });
+      setError(null);
+    }
+  }, [isOpen, distroName]);
+
+  if (!isOpen) return null;
+
+  const cleaned = cleanExportFilter(filter);
+
+  const handleExport = async () => {
+    if (!cleaned) return;
+    setError(null);
+    setIsExporting(true);
+    try {
+      const result = await wslService.exportDistributionFiltered(distroName, cleaned);
+      if (!result) return;
+      addNotification({
+        type: "success",
+        title: t('exportFiltered.successTitle'),
+        message: t('exportFiltered.successMessage', {
+          path: result.path,
+          excluded: formatExcludedBytes(result.report.excludedBytes),
+          count: result.report.excludedEntries,
+        }),
+      });
+      onClose();
+    } catch (err) {
+      const errorMessage = typeof err === "string" ? err : err instanceof Error ? err.message : t('exportFiltered.errorFailed');
+      setError(errorMessage);
+    } finally {
+      setIsExporting(false);
+    }
+  };
+
+  return (
+    <Portal>
+      <div className="fixed inset-0 z-[100] flex items-center justify-center">
+        <div className="absolute inset-0 bg-theme-bg-primary/80 backdrop-blur-xs" onClick={isExporting ? undefined : onClose} />
+
+        <div
+          role="dialog"
+          aria-modal="true"
+          data-testid="export-filtered-dialog"
+          className="relative bg-theme-bg-secondary border border-theme-border-secondary rounded-xl shadow-2xl shadow-black/50 max-w-lg w-full mx-4 p-6 max-h-[90vh] overflow-y-auto"
+        >
+          <h2 className="text-xl font-semibold text-theme-text-primary mb-2">{t('exportFiltered.title')}</h2>
+          <p className="text-sm text-theme-text-secondary mb-4">
+            {t('exportFiltered.subtitle')} <span className="text-theme-status-warning font-medium">{distroName}</span>
+          </p>
+
+          {error && (
+            <div
+              data-testid="export-filtered-error"
+              className="mb-4 p-3 bg-[rgba(var(--status-error-rgb),0.2)] border border-[rgba(var(--status-error-rgb),0.4)] rounded-lg text-theme-status-error text-sm"
+            >
+              {error}
+            </div>
+          )}
+
+          <div className="mb-6">
+            <ExportFilterEditor value={filter} onChange={setFilter} disabled={isExporting} defaultExpanded />
+            {!cleaned && <p className="mt-2 text-xs text-theme-text-muted">{t('exportFiltered.nothingExcluded')}</p>}
+          </div>
+
+          <div className="flex items-center justify-end gap-3">
+            <button
+              onClick={onClose}
+              disabled={isExporting}
+              className="px-4 py-2 text-sm font-medium text-theme-text-secondary bg-theme-bg-tertiary hover:bg-theme-bg-hover rounded-lg transition-colors disabled:opacity-50"
+            >
+              {t('common:button.cancel')}
+            </button>
+            <button
+              onClick={handleExport}
+              disabled={isExporting || !cleaned}
+              data-testid="export-filtered-confirm-button"
+              className="px-4 py-2 text-sm font-medium bg-theme-accent-primary hover:opacity-90 text-theme-bg-primary rounded-lg transition-colors disabled:opacity-50 disabled:cursor-not-allowed flex items-center gap-2"
+            >
+              {isExporting ? (
+                t('exportFiltered.exporting')
+              ) : (
+                <>
+                  <UploadIcon size="sm" />
+                  {t('exportFiltered.export')}
+                </>
+              )}
+            </button>
+          </div>
+        </div>
+      </div>
+    </Portal>
+  );
+}
@@ -0,0 +1,262 @@
+import { useState, useEffect } from "react";
+import { useTranslation } from "react-i18next";
+import { open } from "@tauri-apps/plugin-dialog";
+import { wslService } from "../services/wslService";
+import { useNotificationStore } from "../store/notificationStore";
+import { formatBytes } from "../types/distribution";
+import type { WslPackageOptions } from "../types/distribution";
+import { UploadIcon } from "./icons";
+import { Portal } from "./ui/Portal";
+import { Checkbox, Input, PathInput, Select, TextArea } from "./ui/Input";
+
+interface ExportWslPackageDialogProps {
+  isOpen: boolean;
+  distroName: string;
+  onClose: () => void;
+}
+
+const COLOR_SCHEMES = ["Campbell", "Campbell Powershell", "One Half Dark", "One Half Light", "Solarized Dark", "Solarized Light", "Tango Dark", "Tango Light", "Vintage"];
+
+const DEFAULT_OOBE_SCRIPT = `#!/bin/bash
+set -e
+read -rp "Username: " username
+adduser --gecos "" "$username"
+usermod -aG sudo "$username" 2>/dev/null || usermod -aG wheel "$username"
+`;
+
+const HEX_COLOR = /^#[0-9a-fA-F]{6}$/;
+
+export function ExportWslPackageDialog({ isOpen, distroName, onClose }: ExportWslPackageDialogProps) {
+  const { t } = useTranslation("dialogs");
+  const { addNotification } = useNotificationStore();
+  const [friendlyName, setFriendlyName] = useState(distroName);
+  const [oobeEnabled, setOobeEnabled] = useState(true);
+  const [oobeScript, setOobeScript] = useState(DEFAULT_OOBE_SCRIPT);
+  const [defaultUid, setDefaultUid] = useState("1000");
+  const [iconPath, setIconPath] = useState("");
+  const [terminalEnabled, setTerminalEnabled] = useState(true);
+  const [colorScheme, setColorScheme] = useState("");
+  const [background, setBackground] = useState("");
+  const [foreground, setForeground] = useState("");
+  const [isExporting, setIsExporting] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (isOpen) {
+      setFriendlyName(distroName);
+      setOobeEnabled(true);
+      setOobeScript(DEFAULT_OOBE_SCRIPT);
+      setDefaultUid("1000");
+      setIconPath("");
+      setTerminalEnabled(true);
+      setColorScheme("");
+      setBackground("");
+      setForeground("");
+      setError(null);
+    }
+  }, [isOpen, distroName]);
+
+  const validationError = (() => {
+    if (friendlyName.trim() && !/^[a-zA-Z0-9._-]+$/.test(friendlyName.trim())) {
+      return t('common:validation.invalidChars') as string;
+    }
+    if (oobeEnabled && defaultUid.trim() && !/^\d+$/.test(defaultUid.trim())) {
+      return t('exportPackage.errorUid') as string;
+    }
+    if (terminalEnabled && [background, foreground].some((c) => c.trim() && !HEX_COLOR.test(c.trim()))) {
+      return t('exportPackage.errorColor') as string;
+    }
+    return null;
+  })();
+
+  if (!isOpen) return null;
+
+  const handleBrowseIcon = async () => {
+    const selected = await open({
+      multiple: false,
+      filters: [{ name: "Icon", extensions: ["ico"] }],
+      title: t('exportPackage.iconBrowseTitle'),
+    });
+    if (selected && !Array.isArray(selected)) {
+      setIconPath(selected);
+    }
+  };
+
+  const handleExport = async () => {
+    if (validationError) return;
+
+    const options: WslPackageOptions = {
+      friendlyName: friendlyName.trim() || undefined,
+      oobeScript: oobeEnabled && oobeScript.trim() ? oobeScript : undefined,
+      defaultUid: oobeEnabled && defaultUid.trim() ? Number(defaultUid.trim()) : undefined,
+      iconPath: iconPath.trim() || undefined,
+      terminal: terminalEnabled
+        ? {
+            colorScheme: colorScheme || undefined,
+            background: background.trim() || undefined,
+            foreground: foreground.trim() || undefined,
+          }
+        : undefined,
+    };
+
+    setError(null);
+    setIsExporting(true);
+    try {
+      const report = await wslService.exportWslPackage(distroName, options);
+      if (!report) return;
+      addNotification({
+        type: "success",
+        title: t('exportPackage.successTitle'),
+        message: t('exportPackage.successMessage', {
+          path: report.path,
+          size: formatBytes(report.sizeBytes),
+          count: report.stripped.length,
+        }),
+      });
+      onClose();
+    } catch (err) {
+      const errorMessage = typeof err === "string" ? err : err instanceof Error ? err.message : t('exportPackage.errorFailed');
+      setError(errorMessage);
+    } finally {
+      setIsExporting(false);
+    }
+  };
+
+  return (
+    <Portal>
+      <div className="fixed inset-0 z-[100] flex items-center justify-center">
+        <div className="absolute inset-0 bg-theme-bg-primary/80 backdrop-blur-xs" onClick={isExporting ? undefined : onClose} />
+
+        <div
+          role="dialog"
+          aria-modal="true"
+          data-testid="export-package-dialog"
+          className="relative bg-theme-bg-secondary border border-theme-border-secondary rounded-xl shadow-2xl shadow-black/50 max-w-lg w-full mx-4 p-6 max-h-[90vh] overflow-y-auto"
+        >
+          <h2 className="text-xl font-semibold text-theme-text-primary mb-2">{t('exportPackage.title')}</h2>
+          <p className="text-sm text-theme-text-secondary mb-4">
+            {t('exportPackage.subtitle')} <span className="text-theme-status-warning font-medium">{distroName}</span>
+          </p>
+
+          {error && (
+            <div
+              data-testid="export-package-error"
+              className="mb-4 p-3 bg-[rgba(var(--status-error-rgb),0.2)] border border-[rgba(var(--status-error-rgb),0.4)] rounded-lg text-theme-status-error text-sm"
+            >
+              {error}
+            </div>
+          )}
+
+          <div className="space-y-4 mb-6">
+            <Input
+              label={t('exportPackage.friendlyNameLabel')}
+              helperText={t('exportPackage.friendlyNameHelp')}
+              value={friendlyName}
+              onChange={(e) => setFriendlyName(e.target.value)}
+              disabled={isExporting}
+              data-testid="export-package-name-input"
+            />
+
+            <Checkbox
+              label={t('exportPackage.oobeLabel')}
+              description={t('exportPackage.oobeHelp')}
+              checked={oobeEnabled}
+              onChange={(e) => setOobeEnabled(e.target.checked)}
+              disabled={isExporting}
+            />
+            {oobeEnabled && (
+              <>
+                <TextArea
+                  value={oobeScript}
+                  onChange={(e) => setOobeScript(e.target.value)}
+                  rows={6}
+                  className="font-mono"
+                  disabled={isExporting}
+                  data-testid="export-package-oobe-input"
+                />
+                <Input
+                  label={t('exportPackage.defaultUidLabel')}
+                  helperText={t('exportPackage.defaultUidHelp')}
+                  value={defaultUid}
+                  onChange={(e) => setDefaultUid(e.target.value)}
+                  disabled={isExporting}
+                />
+              </>
+            )}
+
+            <PathInput
+              label={t('exportPackage.iconLabel')}
+              helperText={t('exportPackage.iconHelp')}
+              value={iconPath}
+              onChange={(e) => setIconPath(e.target.value)}
+              onBrowse={handleBrowseIcon}
+              disabled={isExporting}
+            />
+
+            <Checkbox
+              label={t('exportPackage.terminalLabel')}
+              checked={terminalEnabled}
+              onChange={(e) => setTerminalEnabled(e.target.checked)}
+              disabled={isExporting}
+            />
+            {terminalEnabled && (
+              <div className="grid grid-cols-3 gap-3">
+                <Select
+                  label={t('exportPackage.colorSchemeLabel')}
+                  value={colorScheme}
+                  onChange={(e) => setColorScheme(e.target.value)}
+                  options={[{ value: "", label: t('exportPackage.colorSchemeDefault') }, ...COLOR_SCHEMES.map((s) => ({ value: s, label: s }))]}
+                  disabled={isExporting}
+                />
+                <Input
+                  label={t('exportPackage.backgroundLabel')}
+                  value={background}
+                  onChange={(e) => setBackground(e.target.value)}
+                  placeholder="#0C0C0C"
+                  disabled={isExporting}
+                />
+                <Input
+                  label={t('exportPackage.foregroundLabel')}
+                  value={foreground}
+                  onChange={(e) => setForeground(e.target.value)}
+                  placeholder="#CCCCCC"
+                  disabled={isExporting}
+                />
+              </div>
+            )}
+
+            <p className="text-xs text-theme-text-muted">{t('exportPackage.strippedNote')}</p>
+            {validationError && (
+              <p data-testid="export-package-validation-error" className="text-xs text-theme-status-error">{validationError}</p>
+            )}
+          </div>
+
+          <div className="flex items-center justify-end gap-3">
+            <button
+              onClick={onClose}
+              disabled={isExporting}
+              className="px-4 py-2 text-sm font-medium text-theme-text-secondary bg-theme-bg-tertiary hover:bg-theme-bg-hover rounded-lg transition-colors disabled:opacity-50"
+            >
+              {t('common:button.cancel')}
+            </button>
+            <button
+              onClick={handleExport}
+              disabled={isExporting || !!validationError}
+              data-testid="export-package-confirm-button"
+              className="px-4 py-2 text-sm font-medium bg-theme-accent-primary hover:opacity-90 text-theme-bg-primary rounded-lg transition-colors disabled:opacity-50 disabled:cursor-not-allowed flex items-center gap-2"
+            >
+              {isExporting ? (
+                t('exportPackage.exporting')
+              ) : (
+                <>
+                  <UploadIcon size="sm" />
+                  {t('exportPackage.export')}
+                </>
+              )}
+            </button>
+          </div>
+        </div>
+      </div>
+    </Portal>
+  );
+}
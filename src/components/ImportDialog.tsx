@@ -1,17 +1,28 @@
-import { useState } from "react";
+import { useState, useCallback } from "react";
 import { useTranslation } from "react-i18next";
 import { invoke } from "@tauri-apps/api/core";
 import { open } from "@tauri-apps/plugin-dialog";
 import { useDistroStore } from "../store/distroStore";
+import { wslService } from "../services/wslService";
+import type { DecryptionKey, EncryptionKind } from "../types/encryption";
+import type { RootfsCustomization, RootfsInspection } from "../types/rootfs";
 import { DownloadIcon } from "./icons";
+import { RootfsCustomizationEditor } from "./RootfsCustomizationEditor";
+import { RootfsInspectionPanel } from "./RootfsInspectionPanel";
 import { Portal } from "./ui/Portal";
-import { Input, PathInput } from "./ui/Input";
+import { Input, PathInput, TextArea } from "./ui/Input";
 
 interface ImportDialogProps {
   isOpen: boolean;
   onClose: () => void;
 }
 
+/** Suggest a distribution name from an archive filename */
+function nameFromFile(path: string): string {
+  const filename = path.split(/[/\\]/).pop() || "";
+  return filename.replace(/\.(tar(\.gz)?|tgz|wsl|vhdx)(\.age)?$/i, "").replace(/-\d{4}-\d{2}-\d{2}$/, "");
+}
+
 export function ImportDialog({ isOpen, onClose }: ImportDialogProps) {
   const { t } = useTranslation("dialogs");
   const [name, setName] = useState("");
@@ -19,8 +30,28 @@ export function ImportDialog({ isOpen, onClose }: ImportDialogProps) {
   const [installLocation, setInstallLocation] = useState("");
   const [isImporting, setIsImporting] = useState(false);
   const [error, setError] = useState<string | null>(null);
+  const [inspection, setInspection] = useState<RootfsInspection | null>(null);
+  const [isInspecting, setIsInspecting] = useState(false);
+  const [inspectionError, setInspectionError] = useState<string | null>(null);
+  const [customization, setCustomization] = useState<RootfsCustomization | undefined>();
+  const [customizationError, setCustomizationError] = useState<string | null>(null);
+  const [inPlace, setInPlace] = useState(false);
+  const [encryption, setEncryption] = useState<EncryptionKind | null>(null);
+  const [decryptionSecret, setDecryptionSecret] = useState("");
   const { fetchDistros, distributions } = useDistroStore();
 
+  const handleCustomizationChange = useCallback((value: RootfsCustomization | undefined, err: string | null) => {
+    setCustomization(value);
+    setCustomizationError(err);
+  }, []);
+
+  // .vhdx disks are imported as-is: no inspection or customization
+  const isVhd = /\.vhdx$/i.test(tarPath);
+  const needsLocation = !(isVhd && inPlace);
+  // Encrypted archives are decrypted straight into wsl --import, so they
+  // cannot be inspected or customized first
+  const isEncrypted = !isVhd && encryption !== null;
+
   // Check if name already exists
   const nameExists = distributions.some(
     (d) => d.name.toLowerCase() === name.trim().toLowerCase()
@@ -30,7 +61,7 @@ export function ImportDialog({ isOpen, onClose }: ImportDialogProps) {
 
   const handleBrowseTar = async () => {
     const path = await open({
-      filters: [{ name: t('import.tarFilterName'), extensions: ["tar"] }],
+      filters: [{ name: t('import.tarFilterName'), extensions: ["tar", "gz", "tgz", "wsl", "vhdx", "age"] }],
       title: t('import.browseTarTitle'),
       multiple: false,
     });
@@ -39,13 +70,39 @@ export function ImportDialog({ isOpen, onClose }: ImportDialogProps) {
       setTarPath(path);
       // Auto-suggest name from filename
       if (!name) {
-        const filename = path.split(/[/\\]/).pop() || "";
-        const suggestedName = filename.replace(/\.tar$/i, "").replace(/-\d{4}-\d{2}-\d{2}$/, "");
-        setName(suggestedName);
+        setName(nameFromFile(path));
+      }
+      setInspection(null);
+      setInspectionError(null);
+      setEncryption(null);
+      setDecryptionSecret("");
+      if (!/\.vhdx$/i.test(path)) {
+        const kind = await wslService.detectArchiveEncryption(path).catch(() => null);
+        setEncryption(kind);
+        if (!kind) {
+          inspectArchive(path);
+        }
       }
     }
   };
 
+  // Read os-release etc. from the archive without extracting it
+  const inspectArchive = async (path: string) => {
+    setInspection(null);
+    setInspectionError(null);
+    setIsInspecting(true);
+    try {
+      const result = await wslService.inspectRootfs(path);
+      setInspection(result);
+      // Keep a name the user typed; replace the filename-based guess
+      setName((current) => (current && current !== nameFromFile(path) ? current : result.suggestedName));
+    } catch (err) {
+      setInspectionError(typeof err === "string" ? err : err instanceof Error ? err.message : String(err));
+    } finally {
+      setIsInspecting(false);
+    }
+  };
+
   const handleBrowseLocation = async () => {
     const path = await open({
       directory: true,
@@ -70,20 +127,37 @@ export function ImportDialog({ isOpen, onClose }: ImportDialogProps) {
       setError(t('import.errorNoTar'));
       return;
     }
-    if (!installLocation) {
+    if (needsLocation && !installLocation) {
       setError(t('import.errorNoLocation'));
       return;
     }
+    if (isEncrypted && !decryptionSecret.trim()) {
+      setError(encryption === "passphrase" ? t('import.errorNoPassphrase') : t('import.errorNoSecretKey'));
+      return;
+    }
 
     setError(null);
     setIsImporting(true);
 
     try {
-      await invoke("import_distribution", {
-        name: name.trim(),
-        installLocation,
-        tarPath,
-      });
+      if (isVhd && inPlace) {
+        await wslService.registerVhdInPlace(name.trim(), tarPath);
+      } else if (isVhd) {
+        await wslService.importDistributionVhd(name.trim(), installLocation, tarPath);
+      } else if (isEncrypted) {
+        const key: DecryptionKey =
+          encryption === "passphrase"
+            ? { kind: "passphrase", passphrase: decryptionSecret }
+            : { kind: "identity", secretKey: decryptionSecret.trim() };
+        await wslService.importEncryptedDistribution(name.trim(), installLocation, tarPath, key);
+      } else {
+        await invoke("import_distribution", {
+          name: name.trim(),
+          installLocation,
+          tarPath,
+          customization: customization || null,
+        });
+      }
       await fetchDistros();
       handleClose();
     } catch (err) {
@@ -98,7 +172,12 @@ export function ImportDialog({ isOpen, onClose }: ImportDialogProps) {
   const handleClose = () => {
     setName("");
     setTarPath("");
+    setInspection(null);
+    setInspectionError(null);
     setInstallLocation("");
+    setInPlace(false);
+    setEncryption(null);
+    setDecryptionSecret("");
     setError(null);
     onClose();
   };
@@ -147,17 +226,72 @@ export function ImportDialog({ isOpen, onClose }: ImportDialogProps) {
             />
           </div>
 
+          {(isInspecting || inspection || inspectionError) && (
+            <RootfsInspectionPanel inspection={inspection} isInspecting={isInspecting} error={inspectionError} />
+          )}
+
+          {isEncrypted && (
+            <div>
+              {encryption === "passphrase" ? (
+                <Input
+                  type="password"
+                  label={t('import.passphraseLabel')}
+                  value={decryptionSecret}
+                  onChange={(e) => setDecryptionSecret(e.target.value)}
+                  autoComplete="off"
+                  disabled={isImporting}
+                  data-testid="import-passphrase-input"
+                />
+              ) : (
+                <TextArea
+                  label={t('import.secretKeyLabel')}
+                  value={decryptionSecret}
+                  onChange={(e) => setDecryptionSecret(e.target.value)}
+                  placeholder="AGE-SECRET-KEY-1..."
+                  rows={2}
+                  className="font-mono"
+                  disabled={isImporting}
+                  data-testid="import-secret-key-input"
+                />
+              )}
+              <p className="mt-1 text-xs text-theme-text-muted">{t('import.encryptedHelper')}</p>
+            </div>
+          )}
+
+          {isVhd && (
+            <div>
+              <label className="flex items-center gap-2 cursor-pointer">
+                <input
+                  type="checkbox"
+                  checked={inPlace}
+                  onChange={(e) => setInPlace(e.target.checked)}
+                  disabled={isImporting}
+                  data-testid="import-vhd-in-place"
+                  className="w-4 h-4 rounded border-theme-border-secondary bg-theme-bg-tertiary text-theme-accent-primary focus:ring-theme-accent-primary focus:ring-offset-0"
+                />
+                <span className="text-sm text-theme-text-secondary">{t('import.vhdInPlace')}</span>
+              </label>
+              <p className="mt-1 text-xs text-theme-text-muted">
+                {inPlace ? t('import.vhdInPlaceHelper') : t('import.vhdCopyHelper')}
+              </p>
+            </div>
+          )}
+
           {/* Install Location */}
-          <div>
-            <PathInput
-              label={t('import.locationLabel')}
-              value={installLocation}
-              readOnly
-              placeholder={t('import.locationPlaceholder')}
-              onBrowse={handleBrowseLocation}
-              helperText={t('import.locationHelper')}
-            />
-          </div>
+          {needsLocation && (
+            <div>
+              <PathInput
+                label={t('import.locationLabel')}
+                value={installLocation}
+                readOnly
+                placeholder={t('import.locationPlaceholder')}
+                onBrowse={handleBrowseLocation}
+                helperText={t('import.locationHelper')}
+              />
+            </div>
+          )}
+
+          {!isVhd && !isEncrypted && <RootfsCustomizationEditor onChange={handleCustomizationChange} disabled={isImporting} />}
         </div>
 
         <div className="flex items-center justify-end gap-3 mt-6">
@@ -170,7 +304,7 @@ export function ImportDialog({ isOpen, onClose }: ImportDialogProps) {
           </button>
           <button
             onClick={handleImport}
-            disabled={isImporting || !name.trim() || nameExists || !tarPath || !installLocation}
+            disabled={isImporting || !name.trim() || nameExists || !tarPath || (needsLocation && !installLocation) || (isEncrypted && !decryptionSecret.trim()) || (!isVhd && !isEncrypted && !!customizationError)}
             className="px-4 py-2 text-sm font-medium bg-theme-accent-primary hover:opacity-90 text-theme-bg-primary rounded-lg transition-colors disabled:opacity-50 disabled:cursor-not-allowed flex items-center gap-2"
           >
             {isImporting ? (
@@ -1,9 +1,14 @@
-import { useState, useEffect } from "react";
+import { useState, useEffect, useCallback } from "react";
 import { useTranslation } from "react-i18next";
 import { open } from "@tauri-apps/plugin-dialog";
 import { wslService } from "../services/wslService";
 import { useDistroStore } from "../store/distroStore";
+import type { ProvisioningSpec } from "../types/provisioning";
+import type { RootfsCustomization } from "../types/rootfs";
+import { CloudConfigEditor } from "./CloudConfigEditor";
 import { DownloadIcon } from "./icons";
+import { ProvisioningEditor } from "./ProvisioningEditor";
+import { RootfsCustomizationEditor } from "./RootfsCustomizationEditor";
 import { Portal } from "./ui/Portal";
 import { Input, PathInput, RadioButton } from "./ui/Input";
 
@@ -11,6 +16,10 @@ export interface InstallConfig {
   distroName: string;
   installLocation?: string;
   wslVersion: 1 | 2;
+  customization?: RootfsCustomization;
+  provisioning?: ProvisioningSpec;
+  /** cloud-config user data applied after install */
+  userData?: string;
 }
 
 interface InstallConfigDialogProps {
@@ -40,8 +49,29 @@ export function InstallConfigDialog({
   const [pathError, setPathError] = useState<string | null>(null);
   const [isValidatingPath, setIsValidatingPath] = useState(false);
   const [wslVersion, setWslVersion] = useState<2 | 1>(2);
+  const [customization, setCustomization] = useState<RootfsCustomization | undefined>();
+  const [customizationError, setCustomizationError] = useState<string | null>(null);
+  const [provisioning, setProvisioning] = useState<ProvisioningSpec | undefined>();
+  const [provisioningError, setProvisioningError] = useState<string | null>(null);
+  const [userData, setUserData] = useState<string | undefined>();
+  const [userDataError, setUserDataError] = useState<string | null>(null);
   const { distributions } = useDistroStore();
 
+  const handleCustomizationChange = useCallback((value: RootfsCustomization | undefined, err: string | null) => {
+    setCustomization(value);
+    setCustomizationError(err);
+  }, []);
+
+  const handleProvisioningChange = useCallback((value: ProvisioningSpec | undefined, err: string | null) => {
+    setProvisioning(value);
+    setProvisioningError(err);
+  }, []);
+
+  const handleUserDataChange = useCallback((value: string | undefined, err: string | null) => {
+    setUserData(value);
+    setUserDataError(err);
+  }, []);
+
   // The actual path that will be used (either custom or default)
   const effectivePath = isCustomPath ? customPath : defaultPath;
 
@@ -151,7 +181,7 @@ export function InstallConfigDialog({
 
   const handleInstall = () => {
     const trimmedName = distroName.trim();
-    if (!trimmedName || nameValidationError || pathError) {
+    if (!trimmedName || nameValidationError || pathError || customizationError || provisioningError || userDataError) {
       return;
     }
 
@@ -159,6 +189,9 @@ export function InstallConfigDialog({
       distroName: trimmedName,
       installLocation: isCustomPath && customPath.trim() ? customPath.trim() : undefined,
       wslVersion,
+      customization,
+      provisioning,
+      userData,
     });
     onClose();
   };
@@ -211,7 +244,7 @@ export function InstallConfigDialog({
 
   const colors = modeColors[mode];
 
-  const canInstall = distroName.trim() && !nameValidationError && !pathError && !isValidatingPath;
+  const canInstall = distroName.trim() && !nameValidationError && !pathError && !isValidatingPath && !customizationError && !provisioningError && !userDataError;
 
   return (
     <Portal>
@@ -224,7 +257,7 @@ export function InstallConfigDialog({
           role="dialog"
           aria-modal="true"
           data-testid="install-config-dialog"
-          className="relative bg-theme-bg-secondary border border-theme-border-secondary rounded-xl shadow-2xl shadow-black/50 max-w-md w-full mx-4 p-6"
+          className="relative bg-theme-bg-secondary border border-theme-border-secondary rounded-xl shadow-2xl shadow-black/50 max-w-md w-full mx-4 p-6 max-h-[90vh] overflow-y-auto"
         >
           {/* Header */}
           <h2 className="text-xl font-semibold text-theme-text-primary mb-2">{t('installConfig.title')}</h2>
@@ -285,7 +318,7 @@ export function InstallConfigDialog({
           </div>
 
           {/* WSL Version */}
-          <div className="mb-6">
+          <div className="mb-4">
             <label className="block text-sm font-medium text-theme-text-primary mb-2">
               {t('installConfig.wslVersion')}
             </label>
@@ -311,6 +344,21 @@ export function InstallConfigDialog({
             </div>
           </div>
 
+          {/* Rootfs customization */}
+          <div className="mb-4">
+            <RootfsCustomizationEditor onChange={handleCustomizationChange} />
+          </div>
+
+          {/* Default user */}
+          <div className="mb-4">
+            <ProvisioningEditor onChange={handleProvisioningChange} />
+          </div>
+
+          {/* cloud-config user data */}
+          <div className="mb-6">
+            <CloudConfigEditor onChange={handleUserDataChange} />
+          </div>
+
           {/* Actions */}
           <div className="flex items-center justify-end gap-3">
             <button
@@ -0,0 +1,253 @@
+import { useState, useEffect } from "react";
+import { useTranslation } from "react-i18next";
+import { wslService } from "../services/wslService";
+import { useDistroStore } from "../store/distroStore";
+import { useNotificationStore } from "../store/notificationStore";
+import { formatBytes } from "../types/distribution";
+import { DEFAULT_MIGRATION_SELECTION } from "../types/migration";
+import type { MigrationPreview, MigrationSelection } from "../types/migration";
+import { UserIcon } from "./icons";
+import { Checkbox, Select, TextArea } from "./ui/Input";
+import { Portal } from "./ui/Portal";
+
+interface MigrateDataDialogProps {
+  isOpen: boolean;
+  distroName: string;
+  onClose: () => void;
+}
+
+const formatSize = (bytes: number) => (bytes === 0 ? "0 B" : formatBytes(bytes));
+
+/** Copy the default user's home, crontab and other paths into another distribution */
+export function MigrateDataDialog({ isOpen, distroName, onClose }: MigrateDataDialogProps) {
+  const { t } = useTranslation("dialogs");
+  const { distributions } = useDistroStore();
+  const { addNotification } = useNotificationStore();
+  const [target, setTarget] = useState("");
+  const [selection, setSelection] = useState<MigrationSelection>(DEFAULT_MIGRATION_SELECTION);
+  const [pathsText, setPathsText] = useState("");
+  const [preview, setPreview] = useState<MigrationPreview | null>(null);
+  const [isPreviewing, setIsPreviewing] = useState(false);
+  const [isMigrating, setIsMigrating] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (isOpen) {
+      setTarget("");
+      setSelection(DEFAULT_MIGRATION_SELECTION);
+      setPathsText("");
+      setPreview(null);
+      setError(null);
+    }
+  }, [isOpen, distroName]);
+
+  if (!isOpen) return null;
+
+  const targets = distributions.filter((d) => d.name !== distroName);
+  const busy = isPreviewing || isMigrating;
+  const request: MigrationSelection = {
+    ...selection,
+    paths: pathsText.split("\n").map((p) => p.trim()).filter(Boolean),
+  };
+  const nothingSelected = !request.home && !request.sshHostKeys && !request.crontab && request.paths.length === 0;
+
+  const update = (changes: Partial<MigrationSelection>) => {
+    setSelection({ ...selection, ...changes });
+    setPreview(null);
+  };
+
+  const describeError = (err: unknown) =>
+    typeof err === "string" ? err : err instanceof Error ? err.message : t('migrateData.errorFailed');
+
+  const handlePreview = async () => {
+    setError(null);
+    setIsPreviewing(true);
+    try {
+      setPreview(await wslService.previewDataMigration(distroName, target, request));
+    } catch (err) {
+      setError(describeError(err));
+    } finally {
+      setIsPreviewing(false);
+    }
+  };
+
+  const handleMigrate = async () => {
+    setError(null);
+    setIsMigrating(true);
+    try {
+      const report = await wslService.migrateUserData(distroName, target, request);
+      addNotification({
+        type: "success",
+        title: t('migrateData.successTitle'),
+        message: t('migrateData.successMessage', { size: formatSize(report.bytes), source: distroName, target }),
+      });
+      onClose();
+    } catch (err) {
+      setError(describeError(err));
+    } finally {
+      setIsMigrating(false);
+    }
+  };
+
+  return (
+    <Portal>
+      <div className="fixed inset-0 z-[100] flex items-center justify-center">
+        <div className="absolute inset-0 bg-theme-bg-primary/80 backdrop-blur-xs" onClick={busy ? undefined : onClose} />
+
+        <div
+          role="dialog"
+          aria-modal="true"
+          data-testid="migrate-data-dialog"
+          className="relative bg-theme-bg-secondary border border-theme-border-secondary rounded-xl shadow-2xl shadow-black/50 max-w-lg w-full mx-4 p-6 max-h-[90vh] overflow-y-auto"
+        >
+          <h2 className="text-xl font-semibold text-theme-text-primary mb-2">{t('migrateData.title')}</h2>
+          <p className="text-sm text-theme-text-secondary mb-4">
+            {t('migrateData.subtitle')} <span className="text-theme-status-warning font-medium">{distroName}</span>
+          </p>
+
+          {error && (
+            <div
+              data-testid="migrate-data-error"
+              className="mb-4 p-3 bg-[rgba(var(--status-error-rgb),0.2)] border border-[rgba(var(--status-error-rgb),0.4)] rounded-lg text-theme-status-error text-sm"
+            >
+              {error}
+            </div>
+          )}
+
+          <div className="space-y-4 mb-6">
+            {targets.length === 0 ? (
+              <p className="text-sm text-theme-text-muted">{t('migrateData.noTargets')}</p>
+            ) : (
+              <Select
+                label={t('migrateData.targetLabel')}
+                placeholder={t('migrateData.targetPlaceholder')}
+                options={targets.map((d) => ({ value: d.name, label: d.name }))}
+                value={target}
+                onChange={(e) => {
+                  setTarget(e.target.value);
+                  setPreview(null);
+                }}
+                disabled={busy}
+                data-testid="migrate-data-target"
+              />
+            )}
+
+            <div className="space-y-2">
+              <Checkbox
+                label={t('migrateData.home')}
+                description={t('migrateData.homeDescription')}
+                checked={selection.home}
+                onChange={(e) => update({ home: e.target.checked })}
+                disabled={busy}
+                data-testid="migrate-data-home"
+              />
+              <Checkbox
+                label={t('migrateData.crontab')}
+                description={t('migrateData.crontabDescription')}
+                checked={selection.crontab}
+                onChange={(e) => update({ crontab: e.target.checked })}
+                disabled={busy}
+                data-testid="migrate-data-crontab"
+              />
+              <Checkbox
+                label={t('migrateData.sshHostKeys')}
+                description={t('migrateData.sshHostKeysDescription')}
+                checked={selection.sshHostKeys}
+                onChange={(e) => update({ sshHostKeys: e.target.checked })}
+                disabled={busy}
+                data-testid="migrate-data-ssh-host-keys"
+              />
+            </div>
+
+            <TextArea
+              label={t('migrateData.pathsLabel')}
+              helperText={t('migrateData.pathsHelp')}
+              value={pathsText}
+              onChange={(e) => {
+                setPathsText(e.target.value);
+                setPreview(null);
+              }}
+              placeholder={"/opt/tools\n/etc/hosts"}
+              rows={2}
+              className="font-mono"
+              disabled={busy}
+              data-testid="migrate-data-paths"
+            />
+
+            {preview && (
+              <div data-testid="migrate-data-preview" className="p-3 bg-theme-bg-tertiary rounded-lg text-sm space-y-2">
+                <p className="font-medium text-theme-text-primary">
+                  {t('migrateData.summary', { size: formatSize(preview.totalBytes), files: preview.totalFiles })}
+                </p>
+                {preview.items.length === 0 ? (
+                  <p className="text-theme-text-muted">{t('migrateData.nothingFound')}</p>
+                ) : (
+                  <ul className="space-y-1 font-mono text-xs text-theme-text-secondary">
+                    {preview.items.map((item) => (
+                      <li key={item.sourcePath} className="flex justify-between gap-3">
+                        <span className="truncate" title={item.sourcePath}>
+                          {item.sourcePath}
+                          {item.targetPath !== item.sourcePath && <> &rarr; {item.targetPath}</>}
+                        </span>
+                        <span className="shrink-0">{formatSize(item.bytes)}</span>
+                      </li>
+                    ))}
+                  </ul>
+                )}
+                {preview.missing.length > 0 && (
+                  <p className="text-xs text-theme-text-muted">{t('migrateData.missing', { paths: preview.missing.join(", ") })}</p>
+                )}
+                {preview.remapsOwner && (
+                  <p className="text-xs text-theme-text-muted">
+                    {t('migrateData.remapNote', {
+                      source: preview.sourceUser.name,
+                      target: preview.targetUser.name,
+                      uid: preview.targetUser.uid,
+                    })}
+                  </p>
+                )}
+                <p className="text-xs text-theme-status-warning">{t('migrateData.overwriteWarning')}</p>
+              </div>
+            )}
+          </div>
+
+          <div className="flex items-center justify-end gap-3">
+            <button
+              onClick={onClose}
+              disabled={busy}
+              className="px-4 py-2 text-sm font-medium text-theme-text-secondary bg-theme-bg-tertiary hover:bg-theme-bg-hover rounded-lg transition-colors disabled:opacity-50"
+            >
+              {t('common:button.cancel')}
+            </button>
+            {preview ? (
+              <button
+                onClick={handleMigrate}
+                disabled={busy || preview.items.length === 0}
+                data-testid="migrate-data-confirm-button"
+                className="px-4 py-2 text-sm font-medium bg-theme-accent-primary hover:opacity-90 text-theme-bg-primary rounded-lg transition-colors disabled:opacity-50 disabled:cursor-not-allowed flex items-center gap-2"
+              >
+                {isMigrating ? (
+                  t('migrateData.migrating')
+                ) : (
+                  <>
+                    <UserIcon size="sm" />
+                    {t('migrateData.migrate')}
+                  </>
+                )}
+              </button>
+            ) : (
+              <button
+                onClick={handlePreview}
+                disabled={busy || !target || nothingSelected}
+                data-testid="migrate-data-preview-button"
+                className="px-4 py-2 text-sm font-medium bg-theme-accent-primary hover:opacity-90 text-theme-bg-primary rounded-lg transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+              >
+                {isPreviewing ? t('migrateData.previewing') : t('migrateData.preview')}
+              </button>
+            )}
+          </div>
+        </div>
+      </div>
+    </Portal>
+  );
+}
@@ -13,6 +13,10 @@ import { LxcCatalogBrowser } from "./LxcCatalogBrowser";
 import { Portal } from "./ui/Portal";
 import { Button, IconButton } from "./ui/Button";
 import { InstallConfigDialog, type InstallConfig } from "./InstallConfigDialog";
+import { RootfsInspectionPanel } from "./RootfsInspectionPanel";
+import type { RootfsInspection } from "../types/rootfs";
+import type { ProvisioningSpec } from "../types/provisioning";
+import { ProvisioningEditor } from "./ProvisioningEditor";
 
 // Note: Backend now handles metadata creation for all install operations.
 // Frontend no longer needs to call saveInstallMetadata.
@@ -84,6 +88,9 @@ export function NewDistroDialog({ isOpen, onClose }: NewDistroDialogProps) {
   const [useCustomImage, setUseCustomImage] = useState(false);
   const [customUrl, setCustomUrl] = useState("");
   const [useCustomUrl, setUseCustomUrl] = useState(false);
+  const [urlInspection, setUrlInspection] = useState<RootfsInspection | null>(null);
+  const [isInspectingUrl, setIsInspectingUrl] = useState(false);
+  const [urlInspectionError, setUrlInspectionError] = useState<string | null>(null);
   const [isCreating, setIsCreating] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [progress, setProgress] = useState<string | null>(null);
@@ -92,6 +99,8 @@ export function NewDistroDialog({ isOpen, onClose }: NewDistroDialogProps) {
   // Install config dialog state
   const [showInstallConfig, setShowInstallConfig] = useState(false);
   const [pendingInstallItem, setPendingInstallItem] = useState<PendingInstallItem | null>(null);
+  const [quickProvisioning, setQuickProvisioning] = useState<ProvisioningSpec | undefined>();
+  const [quickProvisioningError, setQuickProvisioningError] = useState<string | null>(null);
   const unlistenRef = useRef<(() => void) | null>(null);
   const closeTimeoutRef = useRef<number | null>(null);
   const contentRef = useRef<HTMLDivElement>(null);
@@ -109,8 +118,8 @@ export function NewDistroDialog({ isOpen, onClose }: NewDistroDialogProps) {
     }
   }, []);
 
-  // Get enabled download distros from catalog (sorted alphabetically)
-  const downloadableDistros = (catalog?.downloadDistros.filter(d => d.enabled) || [])
+  // Get enabled download distros installable on this architecture (sorted alphabetically)
+  const downloadableDistros = (catalog?.downloadDistros.filter(d => d.enabled && d.available !== false) || [])
     .sort((a, b) => a.name.localeCompare(b.name));
 
   // Get enabled container images from catalog (sorted alphabetically)
@@ -175,8 +184,43 @@ export function NewDistroDialog({ isOpen, onClose }: NewDistroDialogProps) {
     }
   }, [progress]);
 
+  const handleQuickProvisioningChange = useCallback((spec: ProvisioningSpec | undefined, err: string | null) => {
+    setQuickProvisioning(spec);
+    setQuickProvisioningError(err);
+  }, []);
+
   if (!isOpen) return null;
 
+  // Create the default user once the distribution is registered; a failed
+  // step is surfaced like an install error
+  const provisionIfRequested = async (name: string, spec?: ProvisioningSpec) => {
+    if (!spec) return;
+    setProgress(t('progress.provisioning', { user: spec.username }));
+    const report = await wslService.provisionDistribution(name, spec);
+    const failed = report.steps.find((step) => !step.success && !step.skipped);
+    if (failed) {
+      throw new Error(t('dialogs:provisioning.stepFailed', {
+        step: t(`dialogs:provisioning.steps.${failed.kind}`),
+        message: failed.message,
+      }));
+    }
+  };
+
+  // Apply cloud-config user data; module failures are reported together and
+  // can be re-run from the distribution's info dialog
+  const applyCloudConfigIfRequested = async (name: string, userData?: string) => {
+    if (!userData) return;
+    setProgress(t('progress.cloudConfig'));
+    const report = await wslService.applyCloudConfig(name, userData);
+    const failed = report.modules.filter((m) => !m.success);
+    if (failed.length > 0) {
+      throw new Error(t('dialogs:cloudConfig.modulesFailed', {
+        modules: failed.map((m) => t(`dialogs:cloudConfig.modules.${m.module}`)).join(", "),
+        output: failed[0].output.split("\n").slice(-3).join("\n"),
+      }));
+    }
+  };
+
   // Handle quick install (Microsoft Store) - no config dialog needed
   const handleQuickInstall = async () => {
     setError(null);
@@ -199,6 +243,7 @@ export function NewDistroDialog({ isOpen, onClose }: NewDistroDialogProps) {
 
     try {
       await wslService.quickInstallDistribution(selectedDistro);
+      await provisionIfRequested(selectedDistro, quickProvisioning);
       setProgress(t('progress.success'));
       await fetchDistros();
       await markFirstInstallComplete();
@@ -263,6 +308,7 @@ export function NewDistroDialog({ isOpen, onClose }: NewDistroDialogProps) {
             config.distroName,
             config.installLocation,
             config.wslVersion,
+            config.customization,
           );
         } else {
           await wslService.customInstallWithProgress(
@@ -270,8 +316,11 @@ export function NewDistroDialog({ isOpen, onClose }: NewDistroDialogProps) {
             config.distroName,
             config.installLocation,
             config.wslVersion,
+            config.customization,
           );
         }
+        await provisionIfRequested(config.distroName, config.provisioning);
+        await applyCloudConfigIfRequested(config.distroName, config.userData);
         setProgress(t('progress.success'));
 
         // Save custom URL to catalog for future use
@@ -352,12 +401,15 @@ export function NewDistroDialog({ isOpen, onClose }: NewDistroDialogProps) {
       unlistenRef.current = unlisten;
 
       try {
-        await wslService.installFromRootfsUrl(
-          selectedLxcDistro.downloadUrl,
+        await wslService.installLxcImage(
+          selectedLxcDistro.id,
           config.distroName,
           config.installLocation,
           config.wslVersion,
+          config.customization,
         );
+        await provisionIfRequested(config.distroName, config.provisioning);
+        await applyCloudConfigIfRequested(config.distroName, config.userData);
         setProgress(t('progress.success'));
         setSelectedLxcDistro(null);
         await fetchDistros();
@@ -399,7 +451,10 @@ export function NewDistroDialog({ isOpen, onClose }: NewDistroDialogProps) {
           config.distroName,
           config.installLocation,
           config.wslVersion,
+          config.customization,
         );
+        await provisionIfRequested(config.distroName, config.provisioning);
+        await applyCloudConfigIfRequested(config.distroName, config.userData);
         setProgress(t('progress.success'));
 
         // Save custom image to catalog for future use
@@ -489,6 +544,21 @@ export function NewDistroDialog({ isOpen, onClose }: NewDistroDialogProps) {
     setShowInstallConfig(true);
   };
 
+  // Stream the custom URL through the rootfs inspector before installing
+  const handleInspectCustomUrl = async () => {
+    if (!customUrl.trim()) return;
+    setUrlInspection(null);
+    setUrlInspectionError(null);
+    setIsInspectingUrl(true);
+    try {
+      setUrlInspection(await wslService.inspectRootfs(customUrl.trim()));
+    } catch (err) {
+      setUrlInspectionError(typeof err === "string" ? err : err instanceof Error ? err.message : String(err));
+    } finally {
+      setIsInspectingUrl(false);
+    }
+  };
+
   // Open config dialog for custom URL
   const handleSelectCustomUrl = () => {
     if (!customUrl.trim()) return;
@@ -496,7 +566,8 @@ export function NewDistroDialog({ isOpen, onClose }: NewDistroDialogProps) {
     setSelectedDistro(null);
     const urlObj = new URL(customUrl.trim());
     const filename = urlObj.pathname.split('/').pop() || 'custom';
-    const suggestedName = filename.replace(/\.(tar\.gz|tar\.xz|tar|rootfs)$/i, '').replace(/[^a-zA-Z0-9]/g, '-');
+    const suggestedName = urlInspection?.suggestedName
+      || filename.replace(/\.(tar\.gz|tar\.xz|tar|rootfs)$/i, '').replace(/[^a-zA-Z0-9]/g, '-');
     setPendingInstallItem({
       name: t('customUrlName'),
       suggestedName: suggestedName || "custom-distro",
@@ -570,7 +641,7 @@ export function NewDistroDialog({ isOpen, onClose }: NewDistroDialogProps) {
   };
 
   // Calculate if can install (only for quick mode - other modes use config dialog)
-  const canQuickInstall = !isCreating && mode === "quick" && !!selectedDistro;
+  const canQuickInstall = !isCreating && mode === "quick" && !!selectedDistro && !quickProvisioningError;
 
   return (
     <Portal>
@@ -783,6 +854,10 @@ export function NewDistroDialog({ isOpen, onClose }: NewDistroDialogProps) {
                   </p>
                 </div>
 
+                <div className="mb-4">
+                  <ProvisioningEditor onChange={handleQuickProvisioningChange} disabled={isCreating} />
+                </div>
+
                 {loadingDistros ? (
                   <div className="flex flex-col items-center justify-center py-16 text-theme-text-muted">
                     <svg className="w-8 h-8 animate-spin mb-3 text-theme-accent-primary" viewBox="0 0 24 24">
@@ -1026,6 +1101,16 @@ export function NewDistroDialog({ isOpen, onClose }: NewDistroDialogProps) {
                           <div className="font-medium text-theme-text-primary text-sm">{t('customUrl.label')}</div>
                           <div className="text-xs text-theme-text-muted">{t('customUrl.description')}</div>
                         </div>
+                        {customUrl.trim() && (
+                          <button
+                            onClick={handleInspectCustomUrl}
+                            disabled={isCreating || isInspectingUrl}
+                            data-testid="inspect-url-button"
+                            className="px-3 py-1.5 text-xs font-medium bg-theme-bg-tertiary hover:bg-theme-bg-hover text-theme-text-secondary border border-theme-border-secondary rounded-lg transition-colors disabled:opacity-50"
+                          >
+                            {t('inspectUrl')}
+                          </button>
+                        )}
                         {customUrl.trim() && (
                           <button
                             onClick={handleSelectCustomUrl}
@@ -1039,11 +1124,16 @@ export function NewDistroDialog({ isOpen, onClose }: NewDistroDialogProps) {
                       <input
                         type="text"
                         value={customUrl}
-                        onChange={(e) => { setCustomUrl(e.target.value); setSelectedDistro(null); }}
+                        onChange={(e) => { setCustomUrl(e.target.value); setSelectedDistro(null); setUrlInspection(null); setUrlInspectionError(null); }}
                         placeholder={t('placeholder.url')}
                         disabled={isCreating}
                         className="w-full px-3 py-2.5 bg-theme-bg-primary border border-theme-border-secondary rounded-lg text-theme-text-primary placeholder-theme-text-muted text-sm font-mono focus:outline-none focus:border-blue-500/50"
                       />
+                      {(isInspectingUrl || urlInspection || urlInspectionError) && (
+                        <div className="mt-3">
+                          <RootfsInspectionPanel inspection={urlInspection} isInspecting={isInspectingUrl} error={urlInspectionError} />
+                        </div>
+                      )}
                     </div>
                   </div>
                 )}
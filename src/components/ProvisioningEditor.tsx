@@ -0,0 +1,133 @@
+import { useState, useEffect } from "react";
+import { useTranslation } from "react-i18next";
+import type { ProvisioningSpec, SudoPolicy } from "../types/provisioning";
+import { Checkbox, Input, Select } from "./ui/Input";
+
+interface ProvisioningEditorProps {
+  /**
+   * Called with the spec (undefined when disabled) and a validation error, if
+   * any. Must be stable (wrap in useCallback).
+   */
+  onChange: (spec: ProvisioningSpec | undefined, error: string | null) => void;
+  disabled?: boolean;
+}
+
+const ACCOUNT_NAME = /^[a-z][a-z0-9_-]{0,31}$/;
+const CRYPT_HASH = /^\$[A-Za-z0-9$./=,-]{3,}$/;
+
+/** Default user created after install instead of the interactive first-run setup */
+export function ProvisioningEditor({ onChange, disabled }: ProvisioningEditorProps) {
+  const { t } = useTranslation("dialogs");
+  const [enabled, setEnabled] = useState(false);
+  const [username, setUsername] = useState("");
+  const [uid, setUid] = useState("");
+  const [groups, setGroups] = useState("");
+  const [sudo, setSudo] = useState<SudoPolicy>("password");
+  const [passwordHash, setPasswordHash] = useState("");
+  const [shell, setShell] = useState("");
+
+  const groupList = groups.split(/[\s,]+/).filter(Boolean);
+
+  const error = (() => {
+    if (!enabled) return null;
+    if (!ACCOUNT_NAME.test(username.trim()) || username.trim() === "root") return t('provisioning.errorUsername') as string;
+    if (uid.trim() && !/^\d+$/.test(uid.trim())) return t('provisioning.errorUid') as string;
+    if (groupList.some((g) => !ACCOUNT_NAME.test(g))) return t('provisioning.errorGroups') as string;
+    if (passwordHash.trim() && !CRYPT_HASH.test(passwordHash.trim())) return t('provisioning.errorPasswordHash') as string;
+    if (shell.trim() && !shell.trim().startsWith("/")) return t('provisioning.errorShell') as string;
+    return null;
+  })();
+
+  useEffect(() => {
+    const spec: ProvisioningSpec | undefined = enabled
+      ? {
+          username: username.trim(),
+          uid: uid.trim() ? Number(uid.trim()) : undefined,
+          groups: groups.split(/[\s,]+/).filter(Boolean),
+          sudo,
+          passwordHash: passwordHash.trim() || undefined,
+          shell: shell.trim() || undefined,
+        }
+      : undefined;
+    onChange(spec, error);
+  }, [enabled, username, uid, groups, sudo, passwordHash, shell, error, onChange]);
+
+  return (
+    <div data-testid="provisioning-editor" className="border border-theme-border-secondary rounded-lg p-3 space-y-3">
+      <Checkbox
+        label={t('provisioning.enableLabel')}
+        description={t('provisioning.enableHelp')}
+        checked={enabled}
+        onChange={(e) => setEnabled(e.target.checked)}
+        disabled={disabled}
+        data-testid="provisioning-enable"
+      />
+
+      {enabled && (
+        <>
+          <div className="grid grid-cols-2 gap-3">
+            <Input
+              label={t('provisioning.usernameLabel')}
+              value={username}
+              onChange={(e) => setUsername(e.target.value)}
+              placeholder="dev"
+              disabled={disabled}
+              className="font-mono"
+              data-testid="provisioning-username"
+            />
+            <Input
+              label={t('provisioning.uidLabel')}
+              value={uid}
+              onChange={(e) => setUid(e.target.value)}
+              placeholder="1000"
+              disabled={disabled}
+              className="font-mono"
+            />
+          </div>
+          <Input
+            label={t('provisioning.groupsLabel')}
+            helperText={t('provisioning.groupsHelp')}
+            value={groups}
+            onChange={(e) => setGroups(e.target.value)}
+            placeholder="docker, video"
+            disabled={disabled}
+            className="font-mono"
+          />
+          <div className="grid grid-cols-2 gap-3">
+            <Select
+              label={t('provisioning.sudoLabel')}
+              value={sudo}
+              onChange={(e) => setSudo(e.target.value as SudoPolicy)}
+              options={[
+                { value: "password", label: t('provisioning.sudoPassword') },
+                { value: "noPassword", label: t('provisioning.sudoNoPassword') },
+                { value: "none", label: t('provisioning.sudoNone') },
+              ]}
+              disabled={disabled}
+            />
+            <Input
+              label={t('provisioning.shellLabel')}
+              value={shell}
+              onChange={(e) => setShell(e.target.value)}
+              placeholder="/bin/bash"
+              disabled={disabled}
+              className="font-mono"
+            />
+          </div>
+          <Input
+            label={t('provisioning.passwordHashLabel')}
+            helperText={t('provisioning.passwordHashHelp')}
+            value={passwordHash}
+            onChange={(e) => setPasswordHash(e.target.value)}
+            placeholder="$6$..."
+            disabled={disabled}
+            className="font-mono"
+          />
+          {error && (
+            <p data-testid="provisioning-error" className="text-xs text-theme-status-error">{error}</p>
+          )}
+        </>
+      )}
+    </div>
+  );
+}
@@ -6,6 +6,10 @@ import { useActionsStore } from "../store/actionsStore";
 import { useNotificationStore } from "../store/notificationStore";
 import { wslService } from "../services/wslService";
 import { CloneDialog } from "./CloneDialog";
+import { ExportEncryptedDialog } from "./ExportEncryptedDialog";
+import { ExportFilteredDialog } from "./ExportFilteredDialog";
+import { ExportWslPackageDialog } from "./ExportWslPackageDialog";
+import { MigrateDataDialog } from "./MigrateDataDialog";
 import { MoveDistroDialog } from "./MoveDistroDialog";
 import { ResizeDistroDialog } from "./ResizeDistroDialog";
 import { CompactDistroDialog } from "./CompactDistroDialog";
@@ -14,6 +18,7 @@ import { SetVersionDialog } from "./SetVersionDialog";
 import { RenameDialog } from "./RenameDialog";
 import { StopAndActionDialog } from "./StopAndActionDialog";
 import { DistroInfoDialog } from "./DistroInfoDialog";
+import { SnapshotsDialog } from "./SnapshotsDialog";
 import { ACTION_ICONS } from "../types/actions";
 import type { CustomAction } from "../types/actions";
 import { ConfirmDialog } from "./ConfirmDialog";
@@ -40,6 +45,8 @@ import {
   PowerIcon,
   InfoIcon,
   CompressIcon,
+  ClockIcon,
+  LockIcon,
 } from "./icons";
 
 interface QuickActionsMenuProps {
@@ -69,6 +76,10 @@ export function QuickActionsMenu({ distro, disabled, onOpenChange }: QuickAction
   };
   const [showManageSubmenu, setShowManageSubmenu] = useState(false);
   const [showCloneDialog, setShowCloneDialog] = useState(false);
+  const [showExportPackageDialog, setShowExportPackageDialog] = useState(false);
+  const [showExportEncryptedDialog, setShowExportEncryptedDialog] = useState(false);
+  const [showExportFilteredDialog, setShowExportFilteredDialog] = useState(false);
+  const [showMigrateDialog, setShowMigrateDialog] = useState(false);
   const [showMoveDialog, setShowMoveDialog] = useState(false);
   const [showResizeDialog, setShowResizeDialog] = useState(false);
   const [showCompactDialog, setShowCompactDialog] = useState(false);
@@ -76,6 +87,7 @@ export function QuickActionsMenu({ distro, disabled, onOpenChange }: QuickAction
   const [showSetVersionDialog, setShowSetVersionDialog] = useState(false);
   const [showRenameDialog, setShowRenameDialog] = useState(false);
   const [showInfoDialog, setShowInfoDialog] = useState(false);
+  const [showSnapshotsDialog, setShowSnapshotsDialog] = useState(false);
   const [sparseEnabled, setSparseEnabled] = useState(false);
   const [isTogglingSprase, setIsTogglingSprase] = useState(false);
   const [showConfirmDialog, setShowConfirmDialog] = useState<{ actionId: string; actionName: string } | null>(null);
@@ -89,6 +101,7 @@ export function QuickActionsMenu({ distro, disabled, onOpenChange }: QuickAction
     openIDE,
     restartDistro,
     exportDistro,
+    exportDistroVhd,
     actionInProgress,
     setActionInProgress,
   } = useDistroStore();
@@ -364,6 +377,58 @@ export function QuickActionsMenu({ distro, disabled, onOpenChange }: QuickAction
         setIsOpen(false);
       },
     },
+    {
+      id: "export-filtered",
+      label: t('quickActions.exportFiltered'),
+      icon: <UploadIcon size="sm" />,
+      requiresStopped: true,
+      action: () => {
+        executeWithStopCheck(distro, "Export", () => {
+          setShowExportFilteredDialog(true);
+        });
+        setIsOpen(false);
+      },
+    },
+    {
+      id: "export-package",
+      label: t('quickActions.exportPackage'),
+      icon: <UploadIcon size="sm" />,
+      requiresStopped: true,
+      action: () => {
+        executeWithStopCheck(distro, "Export", () => {
+          setShowExportPackageDialog(true);
+        });
+        setIsOpen(false);
+      },
+    },
+    {
+      id: "export-encrypted",
+      label: t('quickActions.exportEncrypted'),
+      icon: <LockIcon size="sm" />,
+      requiresStopped: true,
+      action: () => {
+        executeWithStopCheck(distro, "Export", () => {
+          setShowExportEncryptedDialog(true);
+        });
+        setIsOpen(false);
+      },
+    },
+    ...(distro.version === 2
+      ? [
+          {
+            id: "export-vhd",
+            label: t('quickActions.exportVhd'),
+            icon: <UploadIcon size="sm" />,
+            requiresStopped: true,
+            action: () => {
+              executeWithStopCheck(distro, "Export", () => {
+                exportDistroVhd(distro.name);
+              });
+              setIsOpen(false);
+            },
+          },
+        ]
+      : []),
     {
       id: "clone",
       label: t('quickActions.clone'),
@@ -376,6 +441,27 @@ export function QuickActionsMenu({ distro, disabled, onOpenChange }: QuickAction
         setIsOpen(false);
       },
     },
+    {
+      id: "migrate-data",
+      label: t('quickActions.migrateData'),
+      icon: <UserIcon size="sm" />,
+      action: () => {
+        setShowMigrateDialog(true);
+        setIsOpen(false);
+      },
+    },
+    {
+      id: "snapshots",
+      label: t('quickActions.snapshots'),
+      icon: <ClockIcon size="sm" />,
+      requiresStopped: true,
+      action: () => {
+        executeWithStopCheck(distro, "Snapshot", () => {
+          setShowSnapshotsDialog(true);
+        });
+        setIsOpen(false);
+      },
+    },
     {
       id: "default",
       label: distro.isDefault ? t('quickActions.alreadyDefault') : t('quickActions.setDefault'),
@@ -701,6 +787,36 @@ export function QuickActionsMenu({ distro, disabled, onOpenChange }: QuickAction
         onClose={() => setShowCloneDialog(false)}
       />
 
+      <ExportWslPackageDialog
+        isOpen={showExportPackageDialog}
+        distroName={distro.name}
+        onClose={() => setShowExportPackageDialog(false)}
+      />
+
+      <ExportEncryptedDialog
+        isOpen={showExportEncryptedDialog}
+        distroName={distro.name}
+        onClose={() => setShowExportEncryptedDialog(false)}
+      />
+
+      <ExportFilteredDialog
+        isOpen={showExportFilteredDialog}
+        distroName={distro.name}
+        onClose={() => setShowExportFilteredDialog(false)}
+      />
+
+      <MigrateDataDialog
+        isOpen={showMigrateDialog}
+        distroName={distro.name}
+        onClose={() => setShowMigrateDialog(false)}
+      />
+
+      <SnapshotsDialog
+        isOpen={showSnapshotsDialog}
+        distroName={distro.name}
+        onClose={() => setShowSnapshotsDialog(false)}
+      />
+
       <DistroInfoDialog
         isOpen={showInfoDialog}
         distro={distro}
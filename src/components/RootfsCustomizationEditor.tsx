@@ -0,0 +1,184 @@
+import { useState, useEffect } from "react";
+import { useTranslation } from "react-i18next";
+import { open } from "@tauri-apps/plugin-dialog";
+import type { HostsEntry, RootfsCustomization } from "../types/rootfs";
+import type { WslConf } from "../types/settings";
+import { ChevronDownIcon, CloseIcon } from "./icons";
+import { Checkbox, Input, TextArea } from "./ui/Input";
+
+interface RootfsCustomizationEditorProps {
+  /**
+   * Called with the customization (undefined when nothing is set) and a
+   * validation error, if any. Must be stable (wrap in useCallback).
+   */
+  onChange: (customization: RootfsCustomization | undefined, error: string | null) => void;
+  disabled?: boolean;
+}
+
+/** Parse `address hostname...` lines; returns the entries and the first invalid line */
+export function parseHostsText(text: string): { entries: HostsEntry[]; invalidLine: string | null } {
+  const entries: HostsEntry[] = [];
+  for (const raw of text.split("\n")) {
+    const line = raw.replace(/#.*$/, "").trim();
+    if (!line) continue;
+    const [address, ...hostnames] = line.split(/\s+/);
+    if (hostnames.length === 0 || !/^[0-9a-fA-F.:]+$/.test(address)) {
+      return { entries, invalidLine: raw.trim() };
+    }
+    entries.push({ address, hostnames });
+  }
+  return { entries, invalidLine: null };
+}
+
+const fileName = (path: string) => path.split(/[/\\]/).pop() || path;
+
+/** Collapsible editor for files and configuration injected into a rootfs before import */
+export function RootfsCustomizationEditor({ onChange, disabled }: RootfsCustomizationEditorProps) {
+  const { t } = useTranslation("dialogs");
+  const [expanded, setExpanded] = useState(false);
+  const [systemd, setSystemd] = useState(false);
+  const [defaultUser, setDefaultUser] = useState("");
+  const [hostname, setHostname] = useState("");
+  const [hostsText, setHostsText] = useState("");
+  const [caCertificates, setCaCertificates] = useState<string[]>([]);
+  const [skeletonFiles, setSkeletonFiles] = useState<string[]>([]);
+
+  const { invalidLine } = parseHostsText(hostsText);
+  const error = invalidLine ? (t('customizeRootfs.errorHostsLine', { line: invalidLine }) as string) : null;
+
+  useEffect(() => {
+    const hostsEntries = parseHostsText(hostsText).entries;
+    const wslConf: WslConf = {
+      bootSystemd: systemd || undefined,
+      userDefault: defaultUser.trim() || undefined,
+      networkHostname: hostname.trim() || undefined,
+    };
+    const hasWslConf = Object.values(wslConf).some((v) => v !== undefined);
+    const isEmpty = !hasWslConf && hostsEntries.length === 0 && caCertificates.length === 0 && skeletonFiles.length === 0;
+    onChange(
+      isEmpty ? undefined : { wslConf: hasWslConf ? wslConf : undefined, hostsEntries, caCertificates, skeletonFiles },
+      error,
+    );
+  }, [systemd, defaultUser, hostname, hostsText, caCertificates, skeletonFiles, error, onChange]);
+
+  const pickFiles = async (title: string, filters?: { name: string; extensions: string[] }[]) => {
+    const selected = await open({ multiple: true, title, filters });
+    if (!selected) return [];
+    return Array.isArray(selected) ? selected : [selected];
+  };
+
+  const handleAddCertificates = async () => {
+    const paths = await pickFiles(t('customizeRootfs.caBrowseTitle'), [
+      { name: "PEM", extensions: ["pem", "crt", "cer"] },
+    ]);
+    setCaCertificates((prev) => [...prev, ...paths.filter((p) => !prev.includes(p))]);
+  };
+
+  const handleAddDotfiles = async () => {
+    const paths = await pickFiles(t('customizeRootfs.dotfilesBrowseTitle'));
+    setSkeletonFiles((prev) => [...prev, ...paths.filter((p) => !prev.includes(p))]);
+  };
+
+  const fileList = (paths: string[], setPaths: (update: (prev: string[]) => string[]) => void) =>
+    paths.length > 0 && (
+      <ul className="mt-1 space-y-1">
+        {paths.map((path) => (
+          <li key={path} className="flex items-center justify-between gap-2 text-xs font-mono text-theme-text-secondary" title={path}>
+            <span className="truncate">{fileName(path)}</span>
+            <button
+              type="button"
+              onClick={() => setPaths((prev) => prev.filter((p) => p !== path))}
+              disabled={disabled}
+              aria-label={t('common:button.delete')}
+              className="text-theme-text-muted hover:text-theme-status-error"
+            >
+              <CloseIcon size="sm" />
+            </button>
+          </li>
+        ))}
+      </ul>
+    );
+
+  const addButtonClass =
+    "px-2 py-1 text-xs font-medium text-theme-text-secondary bg-theme-bg-tertiary hover:bg-theme-bg-hover rounded transition-colors disabled:opacity-50";
+
+  return (
+    <div data-testid="rootfs-customization" className="border border-theme-border-secondary rounded-lg">
+      <button
+        type="button"
+        onClick={() => setExpanded(!expanded)}
+        data-testid="rootfs-customization-toggle"
+        className="w-full flex items-center justify-between px-3 py-2 text-sm font-medium text-theme-text-primary"
+      >
+        {t('customizeRootfs.title')}
+        <ChevronDownIcon size="sm" className={`transition-transform ${expanded ? "rotate-180" : ""}`} />
+      </button>
+
+      {expanded && (
+        <div className="px-3 pb-3 space-y-3">
+          <p className="text-xs text-theme-text-muted">{t('customizeRootfs.description')}</p>
+
+          <Checkbox
+            label={t('customizeRootfs.systemdLabel')}
+            checked={systemd}
+            onChange={(e) => setSystemd(e.target.checked)}
+            disabled={disabled}
+          />
+          <div className="grid grid-cols-2 gap-3">
+            <Input
+              label={t('customizeRootfs.defaultUserLabel')}
+              value={defaultUser}
+              onChange={(e) => setDefaultUser(e.target.value)}
+              disabled={disabled}
+              className="font-mono"
+            />
+            <Input
+              label={t('customizeRootfs.hostnameLabel')}
+              value={hostname}
+              onChange={(e) => setHostname(e.target.value)}
+              disabled={disabled}
+              className="font-mono"
+            />
+          </div>
+
+          <TextArea
+            label={t('customizeRootfs.hostsLabel')}
+            helperText={t('customizeRootfs.hostsHelp')}
+            value={hostsText}
+            onChange={(e) => setHostsText(e.target.value)}
+            placeholder="10.0.0.5 build.internal build"
+            rows={3}
+            className="font-mono"
+            disabled={disabled}
+            data-testid="rootfs-customization-hosts"
+          />
+
+          <div>
+            <div className="flex items-center justify-between">
+              <span className="text-sm font-medium text-theme-text-primary">{t('customizeRootfs.caLabel')}</span>
+              <button type="button" onClick={handleAddCertificates} disabled={disabled} className={addButtonClass}>
+                {t('customizeRootfs.add')}
+              </button>
+            </div>
+            {fileList(caCertificates, setCaCertificates)}
+          </div>
+
+          <div>
+            <div className="flex items-center justify-between">
+              <span className="text-sm font-medium text-theme-text-primary">{t('customizeRootfs.dotfilesLabel')}</span>
+              <button type="button" onClick={handleAddDotfiles} disabled={disabled} className={addButtonClass}>
+                {t('customizeRootfs.add')}
+              </button>
+            </div>
+            <p className="text-xs text-theme-text-muted">{t('customizeRootfs.dotfilesHelp')}</p>
+            {fileList(skeletonFiles, setSkeletonFiles)}
+          </div>
+
+          {error && (
+            <p data-testid="rootfs-customization-error" className="text-xs text-theme-status-error">{error}</p>
+          )}
+        </div>
+      )}
+    </div>
+  );
+}
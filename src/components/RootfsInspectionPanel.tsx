@@ -0,0 +1,71 @@
+import { useTranslation } from "react-i18next";
+import type { RootfsInspection } from "../types/rootfs";
+import { formatBytes } from "../types/distribution";
+import { getDistroLogo } from "./icons/DistroLogos";
+
+interface RootfsInspectionPanelProps {
+  inspection: RootfsInspection | null;
+  isInspecting?: boolean;
+  error?: string | null;
+}
+
+/** Summary of an inspected rootfs archive, shown before installing it */
+export function RootfsInspectionPanel({ inspection, isInspecting, error }: RootfsInspectionPanelProps) {
+  const { t } = useTranslation("dialogs");
+
+  if (isInspecting) {
+    return (
+      <div data-testid="rootfs-inspecting" className="p-3 bg-theme-bg-tertiary border border-theme-border-secondary rounded-lg text-sm text-theme-text-secondary">
+        {t('rootfsInspection.inspecting')}
+      </div>
+    );
+  }
+
+  if (error) {
+    return (
+      <div data-testid="rootfs-inspection-error" className="p-3 bg-theme-bg-tertiary border border-theme-border-secondary rounded-lg text-sm text-theme-text-muted">
+        {t('rootfsInspection.failed', { error })}
+      </div>
+    );
+  }
+
+  if (!inspection) return null;
+
+  const Logo = getDistroLogo(inspection.suggestedIcon || "linux");
+  const yesNo = (value: boolean) => (value ? t('common:label.yes') : t('common:label.no'));
+  const rows: [string, string][] = [
+    [t('rootfsInspection.version'), inspection.versionId || "-"],
+    [t('rootfsInspection.packageManager'), inspection.packageManager || t('rootfsInspection.unknown')],
+    [t('rootfsInspection.initSystem'), inspection.initSystem || t('rootfsInspection.unknown')],
+    [t('rootfsInspection.size'), formatBytes(inspection.uncompressedBytes)],
+    [t('rootfsInspection.files'), inspection.fileCount.toLocaleString()],
+    [t('rootfsInspection.wslConf'), yesNo(inspection.hasWslConf)],
+    [t('rootfsInspection.distributionConf'), yesNo(inspection.hasDistributionConf)],
+  ];
+
+  return (
+    <div data-testid="rootfs-inspection" className="p-3 bg-theme-bg-tertiary border border-theme-border-secondary rounded-lg text-sm">
+      <div className="flex items-center gap-3 mb-2">
+        <Logo size={28} />
+        <div className="font-medium text-theme-text-primary">
+          {inspection.prettyName || inspection.osId || t('rootfsInspection.unidentified')}
+        </div>
+      </div>
+      <dl className="grid grid-cols-2 gap-x-4 gap-y-1 text-xs">
+        {rows.map(([label, value]) => (
+          <div key={label} className="flex justify-between gap-2">
+            <dt className="text-theme-text-muted">{label}</dt>
+            <dd className="text-theme-text-secondary font-mono">{value}</dd>
+          </div>
+        ))}
+      </dl>
+      {inspection.warnings.length > 0 && (
+        <ul data-testid="rootfs-inspection-warnings" className="mt-2 space-y-1 text-xs text-theme-status-warning">
+          {inspection.warnings.map((warning) => (
+            <li key={warning.kind}>{t(`rootfsInspection.warnings.${warning.kind}`, { defaultValue: warning.message })}</li>
+          ))}
+        </ul>
+      )}
+    </div>
+  );
+}
@@ -24,6 +24,7 @@ import {
   SparklesIcon,
   GridIcon,
   DownloadIcon,
+  UploadIcon,
   InfoIcon,
   RefreshIcon,
   ClockIcon,
@@ -43,6 +44,8 @@ import {
   ContainerRuntimeSettings,
   PrivacySettings,
   LanguageSettings,
+  EnvironmentSettings,
+  BackupSettings,
   IDE_PRESETS,
   TERMINAL_PRESETS,
   SETTINGS_TABS,
@@ -64,6 +67,8 @@ const ICON_MAP: Record<SettingsIconName, React.FC<{ size?: "sm" | "md" | "lg"; c
   server: ServerIcon,
   folder: FolderIcon,
   sparkles: SparklesIcon,
+  code: CodeIcon,
+  upload: UploadIcon,
   grid: GridIcon,
   download: DownloadIcon,
   shield: ShieldIcon,
@@ -430,6 +435,10 @@ export function SettingsPage({ onBack }: SettingsPageProps) {
 
             {activeTab === "actions" && <CustomActionsSettings />}
 
+            {activeTab === "environment" && <EnvironmentSettings />}
+
+            {activeTab === "backups" && <BackupSettings />}
+
             {activeTab === "distros" && <DistroSourcesSettings />}
 
             {activeTab === "sources" && (
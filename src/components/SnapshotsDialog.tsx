@@ -0,0 +1,316 @@
+import { useState, useEffect, useCallback } from "react";
+import { useTranslation } from "react-i18next";
+import { wslService } from "../services/wslService";
+import { useDistroStore } from "../store/distroStore";
+import { useNotificationStore } from "../store/notificationStore";
+import { formatBytes } from "../types/distribution";
+import type { Snapshot } from "../types/snapshot";
+import { ClockIcon, CopyIcon, ResetIcon, TrashIcon } from "./icons";
+import { ConfirmDialog } from "./ConfirmDialog";
+import { Portal } from "./ui/Portal";
+import { Input } from "./ui/Input";
+
+interface SnapshotsDialogProps {
+  isOpen: boolean;
+  distroName: string;
+  onClose: () => void;
+}
+
+/** Format a snapshot timestamp for display */
+function formatDate(isoString: string): string {
+  try {
+    return new Date(isoString).toLocaleString(undefined, {
+      year: "numeric",
+      month: "short",
+      day: "numeric",
+      hour: "2-digit",
+      minute: "2-digit",
+    });
+  } catch {
+    return isoString;
+  }
+}
+
+const errorText = (err: unknown, fallback: string) =>
+  typeof err === "string" ? err : err instanceof Error ? err.message : fallback;
+
+export function SnapshotsDialog({ isOpen, distroName, onClose }: SnapshotsDialogProps) {
+  const { t } = useTranslation("dialogs");
+  const { distributions, fetchDistros } = useDistroStore();
+  const { addNotification } = useNotificationStore();
+  const [snapshots, setSnapshots] = useState<Snapshot[]>([]);
+  const [isLoading, setIsLoading] = useState(false);
+  const [name, setName] = useState("");
+  const [description, setDescription] = useState("");
+  // "create" while a snapshot is being taken, otherwise the ID being restored/deleted
+  const [busy, setBusy] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const [restoreAsNew, setRestoreAsNew] = useState<{ id: string; name: string } | null>(null);
+  const [confirm, setConfirm] = useState<{ kind: "restore" | "delete"; snapshot: Snapshot } | null>(null);
+
+  const loadSnapshots = useCallback(async () => {
+    setIsLoading(true);
+    try {
+      setSnapshots(await wslService.listSnapshots(distroName));
+    } catch (err) {
+      setError(errorText(err, t('snapshots.errorLoad')));
+    } finally {
+      setIsLoading(false);
+    }
+  }, [distroName, t]);
+
+  useEffect(() => {
+    if (isOpen) {
+      setName("");
+      setDescription("");
+      setError(null);
+      setRestoreAsNew(null);
+      loadSnapshots();
+    }
+  }, [isOpen, loadSnapshots]);
+
+  if (!isOpen) return null;
+
+  const newNameExists = restoreAsNew
+    ? distributions.some((d) => d.name.toLowerCase() === restoreAsNew.name.trim().toLowerCase())
+    : false;
+
+  const handleCreate = async () => {
+    if (!name.trim()) return;
+    setError(null);
+    setBusy("create");
+    try {
+      const snapshot = await wslService.createSnapshot(distroName, name.trim(), description.trim() || undefined);
+      addNotification({
+        type: "success",
+        title: t('snapshots.createdTitle'),
+        message: t('snapshots.createdMessage', { name: snapshot.name, size: formatBytes(snapshot.sizeBytes) }),
+      });
+      setName("");
+      setDescription("");
+      await loadSnapshots();
+    } catch (err) {
+      setError(errorText(err, t('snapshots.errorCreate')));
+    } finally {
+      setBusy(null);
+    }
+  };
+
+  const handleRestore = async (snapshot: Snapshot, newName?: string) => {
+    setError(null);
+    setBusy(snapshot.id);
+    try {
+      const result = await wslService.restoreSnapshot(
+        snapshot.id,
+        newName ? { mode: "newDistro", name: newName } : { mode: "inPlace" },
+      );
+      addNotification({
+        type: result.defaultUserRestored || !snapshot.defaultUser ? "success" : "warning",
+        title: t('snapshots.restoredTitle'),
+        message: result.defaultUserRestored || !snapshot.defaultUser
+          ? t('snapshots.restoredMessage', { name: snapshot.name, distro: result.distroName })
+          : t('snapshots.restoredNoUser', { name: snapshot.name, distro: result.distroName, user: snapshot.defaultUser }),
+      });
+      setRestoreAsNew(null);
+      await fetchDistros();
+      await loadSnapshots();
+    } catch (err) {
+      setError(errorText(err, t('snapshots.errorRestore')));
+    } finally {
+      setBusy(null);
+    }
+  };
+
+  const handleDelete = async (snapshot: Snapshot) => {
+    setError(null);
+    setBusy(snapshot.id);
+    try {
+      await wslService.deleteSnapshot(snapshot.id);
+      await loadSnapshots();
+    } catch (err) {
+      setError(errorText(err, t('snapshots.errorDelete')));
+    } finally {
+      setBusy(null);
+    }
+  };
+
+  const handleConfirm = () => {
+    if (!confirm) return;
+    const { kind, snapshot } = confirm;
+    setConfirm(null);
+    if (kind === "restore") {
+      handleRestore(snapshot);
+    } else {
+      handleDelete(snapshot);
+    }
+  };
+
+  return (
+    <Portal>
+      <div className="fixed inset-0 z-[100] flex items-center justify-center">
+        <div className="absolute inset-0 bg-theme-bg-primary/80 backdrop-blur-xs" onClick={busy ? undefined : onClose} />
+
+        <div
+          role="dialog"
+          aria-modal="true"
+          data-testid="snapshots-dialog"
+          className="relative bg-theme-bg-secondary border border-theme-border-secondary rounded-xl shadow-2xl shadow-black/50 max-w-2xl w-full mx-4 p-6 max-h-[90vh] overflow-y-auto"
+        >
+          <h2 className="text-xl font-semibold text-theme-text-primary mb-2">{t('snapshots.title')}</h2>
+          <p className="text-sm text-theme-text-secondary mb-4">
+            {t('snapshots.subtitle')} <span className="text-theme-status-warning font-medium">{distroName}</span>
+          </p>
+
+          {error && (
+            <div
+              data-testid="snapshots-error"
+              className="mb-4 p-3 bg-[rgba(var(--status-error-rgb),0.2)] border border-[rgba(var(--status-error-rgb),0.4)] rounded-lg text-theme-status-error text-sm"
+            >
+              {error}
+            </div>
+          )}
+
+          <div className="space-y-3 mb-6 p-4 bg-theme-bg-tertiary/50 border border-theme-border-secondary rounded-lg">
+            <Input
+              label={t('snapshots.nameLabel')}
+              value={name}
+              onChange={(e) => setName(e.target.value)}
+              placeholder={t('snapshots.namePlaceholder')}
+              disabled={!!busy}
+              data-testid="snapshot-name-input"
+            />
+            <Input
+              label={t('snapshots.descriptionLabel')}
+              value={description}
+              onChange={(e) => setDescription(e.target.value)}
+              disabled={!!busy}
+            />
+            <div className="flex items-center justify-between gap-3">
+              <p className="text-xs text-theme-text-muted">{t('snapshots.createHelp')}</p>
+              <button
+                onClick={handleCreate}
+                disabled={!!busy || !name.trim()}
+                data-testid="snapshot-create-button"
+                className="shrink-0 px-4 py-2 text-sm font-medium bg-theme-accent-primary hover:opacity-90 text-theme-bg-primary rounded-lg transition-colors disabled:opacity-50 disabled:cursor-not-allowed flex items-center gap-2"
+              >
+                <ClockIcon size="sm" />
+                {busy === "create" ? t('snapshots.creating') : t('snapshots.create')}
+              </button>
+            </div>
+          </div>
+
+          {isLoading && snapshots.length === 0 ? (
+            <p className="text-sm text-theme-text-muted">{t('snapshots.loading')}</p>
+          ) : snapshots.length === 0 ? (
+            <p data-testid="snapshots-empty" className="text-sm text-theme-text-muted">{t('snapshots.empty')}</p>
+          ) : (
+            <ul className="space-y-2" data-testid="snapshots-list">
+              {snapshots.map((snapshot) => (
+                <li
+                  key={snapshot.id}
+                  className="p-3 bg-theme-bg-tertiary border border-theme-border-secondary rounded-lg text-sm"
+                >
+                  <div className="flex items-start justify-between gap-3">
+                    <div className="min-w-0">
+                      <p className="font-medium text-theme-text-primary truncate">{snapshot.name}</p>
+                      <p className="text-xs text-theme-text-muted">
+                        {formatDate(snapshot.createdAt)} · {formatBytes(snapshot.sizeBytes)} · WSL {snapshot.wslVersion}
+                        {snapshot.defaultUser && ` · ${t('snapshots.user', { user: snapshot.defaultUser })}`}
+                      </p>
+                      {snapshot.description && (
+                        <p className="mt-1 text-xs text-theme-text-secondary">{snapshot.description}</p>
+                      )}
+                    </div>
+                    <div className="flex shrink-0 items-center gap-1">
+                      <button
+                        onClick={() => setConfirm({ kind: "restore", snapshot })}
+                        disabled={!!busy}
+                        title={t('snapshots.restoreInPlace')}
+                        data-testid="snapshot-restore-button"
+                        className="p-1.5 text-theme-text-secondary hover:text-theme-accent-primary hover:bg-theme-bg-hover rounded transition-colors disabled:opacity-50"
+                      >
+                        <ResetIcon size="sm" />
+                      </button>
+                      <button
+                        onClick={() => setRestoreAsNew({ id: snapshot.id, name: `${distroName}-restored` })}
+                        disabled={!!busy}
+                        title={t('snapshots.restoreAsNew')}
+                        className="p-1.5 text-theme-text-secondary hover:text-theme-accent-primary hover:bg-theme-bg-hover rounded transition-colors disabled:opacity-50"
+                      >
+                        <CopyIcon size="sm" />
+                      </button>
+                      <button
+                        onClick={() => setConfirm({ kind: "delete", snapshot })}
+                        disabled={!!busy}
+                        title={t('snapshots.delete')}
+                        className="p-1.5 text-theme-text-secondary hover:text-theme-status-error hover:bg-theme-bg-hover rounded transition-colors disabled:opacity-50"
+                      >
+                        <TrashIcon size="sm" />
+                      </button>
+                    </div>
+                  </div>
+
+                  {busy === snapshot.id && (
+                    <p className="mt-2 text-xs text-theme-text-muted">{t('snapshots.working')}</p>
+                  )}
+
+                  {restoreAsNew?.id === snapshot.id && (
+                    <div className="mt-3 flex items-end gap-2">
+                      <div className="flex-1">
+                        <Input
+                          label={t('snapshots.newNameLabel')}
+                          value={restoreAsNew.name}
+                          onChange={(e) => setRestoreAsNew({ id: snapshot.id, name: e.target.value })}
+                          error={newNameExists ? (t('common:validation.duplicateName', { name: restoreAsNew.name.trim() }) as string) : undefined}
+                          disabled={!!busy}
+                        />
+                      </div>
+                      <button
+                        onClick={() => handleRestore(snapshot, restoreAsNew.name.trim())}
+                        disabled={!!busy || !restoreAsNew.name.trim() || newNameExists}
+                        className="px-3 py-2 text-sm font-medium bg-theme-accent-primary hover:opacity-90 text-theme-bg-primary rounded-lg transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+                      >
+                        {t('snapshots.restore')}
+                      </button>
+                      <button
+                        onClick={() => setRestoreAsNew(null)}
+                        disabled={!!busy}
+                        className="px-3 py-2 text-sm font-medium text-theme-text-secondary bg-theme-bg-secondary hover:bg-theme-bg-hover rounded-lg transition-colors disabled:opacity-50"
+                      >
+                        {t('common:button.cancel')}
+                      </button>
+                    </div>
+                  )}
+                </li>
+              ))}
+            </ul>
+          )}
+
+          <div className="flex items-center justify-end mt-6">
+            <button
+              onClick={onClose}
+              disabled={!!busy}
+              className="px-4 py-2 text-sm font-medium text-theme-text-secondary bg-theme-bg-tertiary hover:bg-theme-bg-hover rounded-lg transition-colors disabled:opacity-50"
+            >
+              {t('common:button.close')}
+            </button>
+          </div>
+        </div>
+      </div>
+
+      <ConfirmDialog
+        isOpen={!!confirm}
+        title={confirm?.kind === "restore" ? t('snapshots.confirmRestoreTitle') : t('snapshots.confirmDeleteTitle')}
+        message={
+          confirm?.kind === "restore"
+            ? t('snapshots.confirmRestoreMessage', { name: confirm.snapshot.name, distro: distroName })
+            : t('snapshots.confirmDeleteMessage', { name: confirm?.snapshot.name ?? "" })
+        }
+        confirmLabel={confirm?.kind === "restore" ? t('snapshots.restore') : t('snapshots.delete')}
+        onConfirm={handleConfirm}
+        onCancel={() => setConfirm(null)}
+        danger
+      />
+    </Portal>
+  );
+}
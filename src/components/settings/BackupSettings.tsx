@@ -0,0 +1,409 @@
+/**
+ * Backup Settings Component
+ *
+ * Per-distribution backup schedules with retention, target, format and
+ * export filter, the running-distribution policy and backup folder, the last
+ * successful backup of each distribution, and the backup targets.
+ */
+
+import { useCallback, useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { open } from "@tauri-apps/plugin-dialog";
+import { wslService } from "../../services/wslService";
+import { useSettingsStore } from "../../store/settingsStore";
+import { useNotificationStore } from "../../store/notificationStore";
+import { DEFAULT_BACKUP_TARGET_ID } from "../../types/backup";
+import type { BackupFormat, BackupRunStatus, BackupSchedule, BackupTargetInfo, DistroBackupStatus } from "../../types/backup";
+import type { BackupRunningPolicy } from "../../types/settings";
+import { formatBytes } from "../../types/distribution";
+import { cleanExportFilter, EMPTY_EXPORT_FILTER, formatExcludedBytes } from "../../types/exportFilter";
+import { ExportFilterEditor } from "../ExportFilterEditor";
+import { Button } from "../ui/Button";
+import { UploadIcon } from "../icons";
+import { SettingPathInput, SettingSelect, Toggle } from "./FormControls";
+import { BackupTargetsSection } from "./BackupTargets";
+import { logger } from "../../utils/logger";
+
+const STATUS_CLASSES: Record<BackupRunStatus, string> = {
+  success: "text-theme-status-success",
+  failed: "text-theme-status-error",
+  skipped: "text-theme-status-warning",
+};
+
+const NEW_SCHEDULE: Omit<BackupSchedule, "distroName"> = {
+  enabled: true,
+  frequency: "daily",
+  time: "02:00",
+  weekday: 6,
+  keepDaily: 7,
+  keepWeekly: 4,
+};
+
+/** Format a backup timestamp for display */
+function formatDate(isoString: string): string {
+  try {
+    return new Date(isoString).toLocaleString(undefined, {
+      year: "numeric",
+      month: "short",
+      day: "numeric",
+      hour: "2-digit",
+      minute: "2-digit",
+    });
+  } catch {
+    return isoString;
+  }
+}
+
+/** Localized weekday names, Monday first (2024-01-01 was a Monday) */
+const weekdayName = (day: number) =>
+  new Date(2024, 0, 1 + day).toLocaleDateString(undefined, { weekday: "long" });
+
+const errorText = (err: unknown) =>
+  typeof err === "string" ? err : err instanceof Error ? err.message : String(err);
+
+const inputClass =
+  "w-full px-3 py-2 bg-theme-bg-secondary border border-theme-border-secondary rounded-lg text-sm text-theme-text-primary focus:outline-hidden focus:border-theme-accent-primary";
+
+interface BackupRowProps {
+  status: DistroBackupStatus;
+  targets: BackupTargetInfo[];
+  onChanged: () => Promise<void>;
+}
+
+function BackupRow({ status, targets, onChanged }: BackupRowProps) {
+  const { t } = useTranslation("settings");
+  const { addNotification } = useNotificationStore();
+  const [draft, setDraft] = useState<BackupSchedule>(
+    status.schedule ?? { ...NEW_SCHEDULE, distroName: status.distroName },
+  );
+  const [isEditing, setIsEditing] = useState(false);
+  const [isBusy, setIsBusy] = useState(false);
+  const [isRunning, setIsRunning] = useState(false);
+
+  useEffect(() => {
+    if (!isEditing) {
+      setDraft(status.schedule ?? { ...NEW_SCHEDULE, distroName: status.distroName });
+    }
+  }, [status.schedule, status.distroName, isEditing]);
+
+  const update = (changes: Partial<BackupSchedule>) => setDraft((d) => ({ ...d, ...changes }));
+
+  const handleSave = async () => {
+    setIsBusy(true);
+    try {
+      await wslService.saveBackupSchedule({ ...draft, filter: cleanExportFilter(draft.filter ?? EMPTY_EXPORT_FILTER) ?? EMPTY_EXPORT_FILTER });
+      setIsEditing(false);
+      await onChanged();
+    } catch (err) {
+      logger.error("Failed to save backup schedule:", "BackupSettings", err);
+      addNotification({ type: "error", title: t('backups.saveFailed'), message: errorText(err) });
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  const handleRemove = async () => {
+    setIsBusy(true);
+    try {
+      await wslService.deleteBackupSchedule(status.distroName);
+      setIsEditing(false);
+      await onChanged();
+    } catch (err) {
+      logger.error("Failed to remove backup schedule:", "BackupSettings", err);
+      addNotification({ type: "error", title: t('backups.saveFailed'), message: errorText(err) });
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  const handleRunNow = async () => {
+    setIsRunning(true);
+    try {
+      const run = await wslService.runBackupNow(status.distroName);
+      if (run.status === "success") {
+        addNotification({
+          type: "success",
+          title: t('backups.runSucceeded'),
+          message: [
+            run.storedBytes != null
+              ? t('backups.runSucceededRepository', {
+                  name: run.distroName,
+                  size: formatBytes(run.sizeBytes ?? 0),
+                  stored: formatBytes(run.storedBytes),
+                })
+              : t('backups.runSucceededMessage', { name: run.distroName, size: formatBytes(run.sizeBytes ?? 0) }),
+            run.excludedBytes != null ? t('backups.runExcluded', { size: formatExcludedBytes(run.excludedBytes) }) : null,
+          ].filter(Boolean).join(" "),
+        });
+      } else {
+        addNotification({
+          type: run.status === "skipped" ? "warning" : "error",
+          title: t(`backups.status.${run.status}`),
+          message: run.message ?? run.distroName,
+        });
+      }
+      await onChanged();
+    } catch (err) {
+      logger.error("Failed to run backup:", "BackupSettings", err);
+      addNotification({ type: "error", title: t('backups.status.failed'), message: errorText(err) });
+    } finally {
+      setIsRunning(false);
+    }
+  };
+
+  const { schedule, lastSuccess, lastRun, nextRun } = status;
+  const showLastRun = lastRun && lastRun.status !== "success";
+
+  return (
+    <li className="p-4 bg-theme-bg-tertiary border border-theme-border-secondary rounded-lg" data-testid={`backup-row-${status.distroName}`}>
+      <div className="flex items-start justify-between gap-3">
+        <div className="min-w-0 space-y-0.5">
+          <p className="text-sm font-medium text-theme-text-primary truncate">{status.distroName}</p>
+          <p className="text-xs text-theme-text-secondary">
+            {lastSuccess
+              ? t('backups.lastSuccess', {
+                  date: formatDate(lastSuccess.startedAt),
+                  size: formatBytes(lastSuccess.sizeBytes ?? 0),
+                })
+              : t('backups.neverBackedUp')}
+            {status.archiveCount > 0 && ` · ${t('backups.archiveCount', { count: status.archiveCount })}`}
+          </p>
+          {showLastRun && (
+            <p className={`text-xs ${STATUS_CLASSES[lastRun.status]}`}>
+              {t(`backups.status.${lastRun.status}`)} · {formatDate(lastRun.startedAt)}
+              {lastRun.message && ` · ${lastRun.message}`}
+            </p>
+          )}
+          <p className="text-xs text-theme-text-muted">
+            {schedule
+              ? schedule.enabled && nextRun
+                ? t('backups.nextRun', { date: formatDate(nextRun) })
+                : t('backups.paused')
+              : t('backups.noSchedule')}
+          </p>
+        </div>
+        <div className="flex gap-2 shrink-0">
+          <Button variant="secondary" size="sm" onClick={() => setIsEditing(!isEditing)} disabled={isBusy}>
+            {schedule ? t('backups.editSchedule') : t('backups.addSchedule')}
+          </Button>
+          <Button size="sm" onClick={handleRunNow} loading={isRunning} disabled={isRunning} data-testid={`backup-run-${status.distroName}`}>
+            {t('backups.runNow')}
+          </Button>
+        </div>
+      </div>
+
+      {isEditing && (
+        <div className="mt-4 pt-4 border-t border-theme-border-secondary space-y-3">
+          <Toggle
+            label={t('backups.enabled')}
+            checked={draft.enabled}
+            onChange={(enabled) => update({ enabled })}
+          />
+          <div className="grid grid-cols-2 gap-3 sm:grid-cols-3">
+            <label className="text-xs text-theme-text-secondary space-y-1">
+              <span>{t('backups.frequency')}</span>
+              <select
+                className={inputClass}
+                value={draft.frequency}
+                onChange={(e) => update({ frequency: e.target.value as BackupSchedule["frequency"] })}
+              >
+                <option value="daily">{t('backups.frequencies.daily')}</option>
+                <option value="weekly">{t('backups.frequencies.weekly')}</option>
+              </select>
+            </label>
+            {draft.frequency === "weekly" && (
+              <label className="text-xs text-theme-text-secondary space-y-1">
+                <span>{t('backups.weekday')}</span>
+                <select
+                  className={inputClass}
+                  value={draft.weekday}
+                  onChange={(e) => update({ weekday: Number(e.target.value) })}
+                >
+                  {[0, 1, 2, 3, 4, 5, 6].map((day) => (
+                    <option key={day} value={day}>{weekdayName(day)}</option>
+                  ))}
+                </select>
+              </label>
+            )}
+            <label className="text-xs text-theme-text-secondary space-y-1">
+              <span>{t('backups.time')}</span>
+              <input type="time" className={inputClass} value={draft.time} onChange={(e) => update({ time: e.target.value })} />
+            </label>
+            <label className="text-xs text-theme-text-secondary space-y-1">
+              <span>{t('backups.target')}</span>
+              <select
+                className={inputClass}
+                value={draft.targetId ?? DEFAULT_BACKUP_TARGET_ID}
+                onChange={(e) =>
+                  update({ targetId: e.target.value === DEFAULT_BACKUP_TARGET_ID ? null : e.target.value })
+                }
+                data-testid={`backup-target-select-${status.distroName}`}
+              >
+                {targets.map((target) => (
+                  <option key={target.id} value={target.id}>
+                    {target.builtIn ? t('backups.targets.builtInName') : target.name}
+                  </option>
+                ))}
+              </select>
+            </label>
+            <label className="text-xs text-theme-text-secondary space-y-1">
+              <span>{t('backups.format')}</span>
+              <select
+                className={inputClass}
+                value={draft.format ?? "archive"}
+                onChange={(e) => update({ format: e.target.value as BackupFormat })}
+                data-testid={`backup-format-select-${status.distroName}`}
+              >
+                <option value="archive">{t('backups.formats.archive')}</option>
+                <option value="repository">{t('backups.formats.repository')}</option>
+              </select>
+            </label>
+            <label className="text-xs text-theme-text-secondary space-y-1">
+              <span>{t('backups.keepDaily')}</span>
+              <input
+                type="number"
+                min={0}
+                className={inputClass}
+                value={draft.keepDaily}
+                onChange={(e) => update({ keepDaily: Math.max(0, Number(e.target.value) || 0) })}
+              />
+            </label>
+            <label className="text-xs text-theme-text-secondary space-y-1">
+              <span>{t('backups.keepWeekly')}</span>
+              <input
+                type="number"
+                min={0}
+                className={inputClass}
+                value={draft.keepWeekly}
+                onChange={(e) => update({ keepWeekly: Math.max(0, Number(e.target.value) || 0) })}
+              />
+            </label>
+          </div>
+          <p className="text-xs text-theme-text-muted">{t('backups.retentionHint')}</p>
+          {draft.format === "repository" && <p className="text-xs text-theme-text-muted">{t('backups.formats.repositoryHint')}</p>}
+          <ExportFilterEditor
+            value={draft.filter ?? EMPTY_EXPORT_FILTER}
+            onChange={(filter) => update({ filter })}
+            disabled={isBusy}
+          />
+          <div className="flex justify-end gap-2">
+            {schedule && (
+              <Button variant="secondary" size="sm" onClick={handleRemove} disabled={isBusy}>
+                {t('backups.removeSchedule')}
+              </Button>
+            )}
+            <Button
+              size="sm"
+              onClick={handleSave}
+              loading={isBusy}
+              disabled={isBusy || (draft.keepDaily === 0 && draft.keepWeekly === 0)}
+              data-testid={`backup-save-${status.distroName}`}
+            >
+              {t('backups.saveSchedule')}
+            </Button>
+          </div>
+        </div>
+      )}
+    </li>
+  );
+}
+
+export function BackupSettings() {
+  const { t } = useTranslation("settings");
+  const { settings, updateSetting } = useSettingsStore();
+  const [statuses, setStatuses] = useState<DistroBackupStatus[] | null>(null);
+  const [targets, setTargets] = useState<BackupTargetInfo[]>([]);
+  const [error, setError] = useState<string | null>(null);
+
+  const loadStatus = useCallback(async () => {
+    try {
+      const [loadedStatuses, loadedTargets] = await Promise.all([
+        wslService.getBackupStatus(),
+        wslService.listBackupTargets(),
+      ]);
+      setStatuses(loadedStatuses);
+      setTargets(loadedTargets);
+      setError(null);
+    } catch (err) {
+      logger.error("Failed to load backup status:", "BackupSettings", err);
+      setError(errorText(err));
+    }
+  }, []);
+
+  useEffect(() => {
+    loadStatus();
+    const unlisten = wslService.onBackupsUpdated(() => loadStatus());
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [loadStatus]);
+
+  const handleBrowseBackupDirectory = async () => {
+    const selectedPath = await open({
+      directory: true,
+      multiple: false,
+      title: t('backups.selectFolder'),
+    });
+
+    if (selectedPath && !Array.isArray(selectedPath)) {
+      updateSetting("backupDirectory", selectedPath);
+    }
+  };
+
+  return (
+    <div className="space-y-6" data-testid="backup-settings">
+      <section className="relative overflow-hidden bg-linear-to-br from-emerald-900/20 via-theme-bg-secondary/50 to-theme-bg-secondary/50 border border-emerald-800/30 rounded-xl p-6">
+        <div className="absolute inset-0 bg-[radial-gradient(ellipse_at_top_left,_var(--tw-gradient-stops))] from-emerald-500/5 via-transparent to-transparent" />
+        <div className="relative">
+          <div className="flex items-center gap-3 mb-4">
+            <div className="w-10 h-10 rounded-lg bg-linear-to-br from-emerald-500 to-teal-600 flex items-center justify-center shadow-lg shadow-emerald-900/30">
+              <UploadIcon size="md" className="text-white" />
+            </div>
+            <div>
+              <h2 className="text-lg font-medium text-theme-text-primary">{t('backups.title')}</h2>
+              <p className="text-sm text-theme-text-secondary">{t('backups.description')}</p>
+            </div>
+          </div>
+
+          <SettingSelect
+            label={t('backups.runningPolicy')}
+            description={t('backups.runningPolicyDesc')}
+            value={settings.backupRunningPolicy}
+            options={[
+              { value: "skip", label: t('backups.policies.skip') },
+              { value: "stopThenExport", label: t('backups.policies.stopThenExport') },
+            ]}
+            onChange={(v) => updateSetting("backupRunningPolicy", v as BackupRunningPolicy)}
+            testId="backup-running-policy"
+          />
+          <SettingPathInput
+            className="font-mono text-sm"
+            label={t('backups.directory')}
+            description={t('backups.directoryDesc')}
+            value={settings.backupDirectory}
+            placeholder="%LOCALAPPDATA%\wsl-ui\backups"
+            onChange={(v) => updateSetting("backupDirectory", v)}
+            onBrowse={handleBrowseBackupDirectory}
+          />
+        </div>
+      </section>
+
+      <section className="bg-theme-bg-secondary/50 border border-theme-border-secondary rounded-xl p-6 space-y-4">
+        <h3 className="text-sm font-medium text-theme-text-primary">{t('backups.distributions')}</h3>
+        {error && <p data-testid="backup-error" className="text-sm text-theme-status-error">{error}</p>}
+        {statuses && statuses.length === 0 && (
+          <p className="text-sm text-theme-text-muted">{t('backups.noDistributions')}</p>
+        )}
+        {statuses && statuses.length > 0 && (
+          <ul className="space-y-3">
+            {statuses.map((status) => (
+              <BackupRow key={status.distroName} status={status} targets={targets} onChanged={loadStatus} />
+            ))}
+          </ul>
+        )}
+      </section>
+
+      <BackupTargetsSection targets={targets} onChanged={loadStatus} />
+    </div>
+  );
+}
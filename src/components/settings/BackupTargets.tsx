@@ -0,0 +1,549 @@
+/**
+ * Backup Targets Component
+ *
+ * The backup folder plus network share and S3-compatible targets: editing
+ * targets and their credentials, connection tests, browsing and restoring
+ * the backups stored on any target, and checking and pruning a target's
+ * deduplicated repository.
+ */
+
+import { useCallback, useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { save } from "@tauri-apps/plugin-dialog";
+import { wslService } from "../../services/wslService";
+import { useDistroStore } from "../../store/distroStore";
+import { useNotificationStore } from "../../store/notificationStore";
+import type { BackupManifest, BackupTargetInfo, RepositoryCheckReport, TargetKind, TargetLocation } from "../../types/backup";
+import { formatBytes } from "../../types/distribution";
+import { Button } from "../ui/Button";
+import { Input } from "../ui/Input";
+import { ConfirmDialog } from "../ConfirmDialog";
+import { Toggle } from "./FormControls";
+import { logger } from "../../utils/logger";
+
+const EMPTY_LOCATIONS: Record<TargetKind, TargetLocation> = {
+  local: { kind: "local", path: "" },
+  networkShare: { kind: "networkShare", path: "" },
+  s3: { kind: "s3", endpoint: "", region: "us-east-1", bucket: "", prefix: "wsl/", pathStyle: false },
+};
+
+/** Format a backup timestamp for display */
+function formatDate(isoString: string): string {
+  try {
+    return new Date(isoString).toLocaleString(undefined, {
+      year: "numeric",
+      month: "short",
+      day: "numeric",
+      hour: "2-digit",
+      minute: "2-digit",
+    });
+  } catch {
+    return isoString;
+  }
+}
+
+const errorText = (err: unknown) =>
+  typeof err === "string" ? err : err instanceof Error ? err.message : String(err);
+
+/** One-line description of where a target stores archives */
+function describeLocation(location: TargetLocation): string {
+  switch (location.kind) {
+    case "local":
+    case "networkShare":
+      return location.path;
+    case "s3":
+      return `s3://${location.bucket}/${location.prefix} · ${location.endpoint}`;
+  }
+}
+
+const inputClass =
+  "w-full px-3 py-2 bg-theme-bg-secondary border border-theme-border-secondary rounded-lg text-sm text-theme-text-primary focus:outline-hidden focus:border-theme-accent-primary";
+
+interface TargetEditorProps {
+  target: BackupTargetInfo | null;
+  onSaved: () => Promise<void>;
+  onCancel: () => void;
+}
+
+function TargetEditor({ target, onSaved, onCancel }: TargetEditorProps) {
+  const { t } = useTranslation("settings");
+  const [name, setName] = useState(target?.name ?? "");
+  const [location, setLocation] = useState<TargetLocation>(target?.location ?? EMPTY_LOCATIONS.networkShare);
+  const [username, setUsername] = useState("");
+  const [secret, setSecret] = useState("");
+  const [isSaving, setIsSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const isS3 = location.kind === "s3";
+  const needsSecret = isS3 && !target?.hasCredentials;
+  const canSave = name.trim() !== "" && (!needsSecret || (username.trim() !== "" && secret !== ""));
+
+  const handleSave = async () => {
+    setIsSaving(true);
+    setError(null);
+    try {
+      const credentials = username.trim() && secret ? { username: username.trim(), secret } : undefined;
+      await wslService.saveBackupTarget({ id: target?.id ?? "", name: name.trim(), location }, credentials);
+      await onSaved();
+    } catch (err) {
+      logger.error("Failed to save backup target:", "BackupTargets", err);
+      setError(errorText(err));
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
+  return (
+    <div className="p-4 bg-theme-bg-tertiary border border-theme-border-secondary rounded-lg space-y-3" data-testid="backup-target-editor">
+      {error && <p className="text-sm text-theme-status-error">{error}</p>}
+      <div className="grid grid-cols-1 gap-3 sm:grid-cols-2">
+        <Input label={t('backups.targets.name')} value={name} onChange={(e) => setName(e.target.value)} data-testid="backup-target-name" />
+        <label className="text-xs text-theme-text-secondary space-y-1">
+          <span>{t('backups.targets.kind')}</span>
+          <select
+            className={inputClass}
+            value={location.kind}
+            disabled={!!target}
+            onChange={(e) => setLocation(EMPTY_LOCATIONS[e.target.value as TargetKind])}
+            data-testid="backup-target-kind"
+          >
+            <option value="local">{t('backups.targets.kinds.local')}</option>
+            <option value="networkShare">{t('backups.targets.kinds.networkShare')}</option>
+            <option value="s3">{t('backups.targets.kinds.s3')}</option>
+          </select>
+        </label>
+      </div>
+
+      {location.kind !== "s3" ? (
+        <Input
+          className="font-mono"
+          label={location.kind === "local" ? t('backups.targets.folder') : t('backups.targets.uncPath')}
+          placeholder={location.kind === "local" ? "D:\\Backups\\WSL" : "\\\\nas\\backups\\wsl"}
+          value={location.path}
+          onChange={(e) => setLocation({ ...location, path: e.target.value })}
+        />
+      ) : (
+        <div className="grid grid-cols-1 gap-3 sm:grid-cols-2">
+          <Input
+            label={t('backups.targets.endpoint')}
+            placeholder="https://s3.eu-west-1.amazonaws.com"
+            value={location.endpoint}
+            onChange={(e) => setLocation({ ...location, endpoint: e.target.value })}
+          />
+          <Input
+            label={t('backups.targets.region')}
+            value={location.region}
+            onChange={(e) => setLocation({ ...location, region: e.target.value })}
+          />
+          <Input
+            label={t('backups.targets.bucket')}
+            value={location.bucket}
+            onChange={(e) => setLocation({ ...location, bucket: e.target.value })}
+          />
+          <Input
+            label={t('backups.targets.prefix')}
+            value={location.prefix}
+            onChange={(e) => setLocation({ ...location, prefix: e.target.value })}
+          />
+          <div className="sm:col-span-2">
+            <Toggle
+              label={t('backups.targets.pathStyle')}
+              description={t('backups.targets.pathStyleDesc')}
+              checked={location.pathStyle}
+              onChange={(pathStyle) => setLocation({ ...location, pathStyle })}
+            />
+          </div>
+        </div>
+      )}
+
+      {location.kind !== "local" && (
+        <div className="grid grid-cols-1 gap-3 sm:grid-cols-2">
+          <Input
+            label={isS3 ? t('backups.targets.accessKey') : t('backups.targets.username')}
+            value={username}
+            onChange={(e) => setUsername(e.target.value)}
+            autoComplete="off"
+          />
+          <Input
+            type="password"
+            label={isS3 ? t('backups.targets.secretKey') : t('backups.targets.password')}
+            value={secret}
+            onChange={(e) => setSecret(e.target.value)}
+            autoComplete="new-password"
+          />
+          <p className="sm:col-span-2 text-xs text-theme-text-muted">
+            {target?.hasCredentials ? t('backups.targets.credentialsKept') : t('backups.targets.credentialsHint')}
+          </p>
+        </div>
+      )}
+
+      <div className="flex justify-end gap-2">
+        <Button variant="secondary" size="sm" onClick={onCancel} disabled={isSaving}>
+          {t('common:button.cancel')}
+        </Button>
+        <Button size="sm" onClick={handleSave} loading={isSaving} disabled={isSaving || !canSave} data-testid="backup-target-save">
+          {t('backups.targets.save')}
+        </Button>
+      </div>
+    </div>
+  );
+}
+
+function TargetBackups({ target }: { target: BackupTargetInfo }) {
+  const { t } = useTranslation("settings");
+  const { distributions, fetchDistros } = useDistroStore();
+  const { addNotification } = useNotificationStore();
+  const [backups, setBackups] = useState<BackupManifest[] | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const [busy, setBusy] = useState<string | null>(null);
+  const [restoreAsNew, setRestoreAsNew] = useState<{ id: string; name: string } | null>(null);
+  const [confirmRestore, setConfirmRestore] = useState<BackupManifest | null>(null);
+  const [repositoryTask, setRepositoryTask] = useState<"check" | "verify" | "prune" | null>(null);
+
+  const loadBackups = useCallback(async () => {
+    try {
+      setBackups(await wslService.listTargetBackups(target.id));
+      setError(null);
+    } catch (err) {
+      logger.error("Failed to list backups:", "BackupTargets", err);
+      setError(errorText(err));
+    }
+  }, [target.id]);
+
+  useEffect(() => {
+    loadBackups();
+  }, [loadBackups]);
+
+  const newNameExists = restoreAsNew
+    ? distributions.some((d) => d.name.toLowerCase() === restoreAsNew.name.trim().toLowerCase())
+    : false;
+
+  const handleRestore = async (backup: BackupManifest, newName?: string) => {
+    setBusy(backup.id);
+    setError(null);
+    try {
+      const result = await wslService.restoreBackup(
+        target.id,
+        backup.id,
+        newName ? { mode: "newDistro", name: newName } : { mode: "inPlace" },
+      );
+      const userRestored = result.defaultUserRestored || !backup.defaultUser;
+      addNotification({
+        type: userRestored ? "success" : "warning",
+        title: t('backups.browser.restoredTitle'),
+        message: userRestored
+          ? t('backups.browser.restoredMessage', { distro: result.distroName })
+          : t('backups.browser.restoredNoUser', { distro: result.distroName, user: backup.defaultUser }),
+      });
+      setRestoreAsNew(null);
+      await fetchDistros();
+    } catch (err) {
+      logger.error("Failed to restore backup:", "BackupTargets", err);
+      setError(errorText(err));
+    } finally {
+      setBusy(null);
+    }
+  };
+
+  const handleSaveTar = async (backup: BackupManifest) => {
+    const path = await save({
+      defaultPath: `${backup.id}.tar`,
+      filters: [{ name: "Tar", extensions: ["tar"] }],
+      title: t('backups.browser.saveTarTitle'),
+    });
+    if (!path) return;
+
+    setBusy(backup.id);
+    setError(null);
+    try {
+      await wslService.exportBackupTar(target.id, backup.id, path);
+      addNotification({
+        type: "success",
+        title: t('backups.browser.savedTarTitle'),
+        message: t('backups.browser.savedTarMessage', { path }),
+      });
+    } catch (err) {
+      logger.error("Failed to save backup as tar:", "BackupTargets", err);
+      setError(errorText(err));
+    } finally {
+      setBusy(null);
+    }
+  };
+
+  const describeCheck = (report: RepositoryCheckReport) =>
+    t('backups.repository.checkSummary', {
+      backups: report.backups,
+      chunks: report.chunks,
+      stored: formatBytes(report.storedBytes),
+      logical: formatBytes(report.logicalBytes),
+      unreferenced: report.unreferencedChunks,
+    });
+
+  const handleCheck = async (verifyData: boolean) => {
+    setRepositoryTask(verifyData ? "verify" : "check");
+    try {
+      const report = await wslService.checkBackupRepository(target.id, verifyData);
+      const healthy =
+        report.missingChunks.length === 0 && report.corruptChunks.length === 0 && report.unreadableIndexes.length === 0;
+      addNotification(
+        healthy
+          ? { type: "success", title: t('backups.repository.healthyTitle'), message: describeCheck(report) }
+          : {
+              type: "error",
+              title: t('backups.repository.damagedTitle'),
+              message: t('backups.repository.damagedMessage', {
+                missing: report.missingChunks.length,
+                corrupt: report.corruptChunks.length,
+                indexes: report.unreadableIndexes.length,
+                backups: new Set(report.missingChunks.map((c) => c.backupId)).size,
+              }),
+            },
+      );
+    } catch (err) {
+      logger.error("Failed to check repository:", "BackupTargets", err);
+      setError(errorText(err));
+    } finally {
+      setRepositoryTask(null);
+    }
+  };
+
+  const handlePrune = async () => {
+    setRepositoryTask("prune");
+    try {
+      const report = await wslService.pruneBackupRepository(target.id);
+      addNotification({
+        type: "success",
+        title: t('backups.repository.prunedTitle'),
+        message: t('backups.repository.prunedMessage', {
+          count: report.deletedChunks,
+          size: formatBytes(report.freedBytes),
+        }),
+      });
+    } catch (err) {
+      logger.error("Failed to prune repository:", "BackupTargets", err);
+      setError(errorText(err));
+    } finally {
+      setRepositoryTask(null);
+    }
+  };
+
+  const hasRepository = backups?.some((b) => b.format === "repository") ?? false;
+
+  return (
+    <div className="mt-3 pt-3 border-t border-theme-border-secondary space-y-2" data-testid={`backup-browser-${target.id}`}>
+      {error && <p className="text-sm text-theme-status-error">{error}</p>}
+      {hasRepository && (
+        <div className="flex flex-wrap items-center justify-between gap-2" data-testid={`backup-repository-${target.id}`}>
+          <p className="text-xs text-theme-text-secondary">{t('backups.repository.description')}</p>
+          <div className="flex shrink-0 gap-2">
+            <Button variant="secondary" size="sm" onClick={() => handleCheck(false)} loading={repositoryTask === "check"} disabled={repositoryTask !== null}>
+              {t('backups.repository.check')}
+            </Button>
+            <Button variant="secondary" size="sm" onClick={() => handleCheck(true)} loading={repositoryTask === "verify"} disabled={repositoryTask !== null}>
+              {t('backups.repository.verify')}
+            </Button>
+            <Button variant="secondary" size="sm" onClick={handlePrune} loading={repositoryTask === "prune"} disabled={repositoryTask !== null}>
+              {t('backups.repository.prune')}
+            </Button>
+          </div>
+        </div>
+      )}
+      {backups === null && !error && <p className="text-xs text-theme-text-muted">{t('backups.browser.loading')}</p>}
+      {backups && backups.length === 0 && <p className="text-xs text-theme-text-muted">{t('backups.browser.empty')}</p>}
+      {backups && backups.length > 0 && (
+        <ul className="space-y-2">
+          {backups.map((backup) => (
+            <li key={backup.id} className="p-3 bg-theme-bg-secondary border border-theme-border-secondary rounded-lg text-sm">
+              <div className="flex items-start justify-between gap-3">
+                <div className="min-w-0">
+                  <p className="font-medium text-theme-text-primary truncate">{backup.distroName}</p>
+                  <p className="text-xs text-theme-text-muted">
+                    {formatDate(backup.createdAt)} · {formatBytes(backup.sizeBytes)} · WSL {backup.wslVersion}
+                    {backup.format === "repository" && ` · ${t('backups.formats.repository')}`}
+                  </p>
+                </div>
+                <div className="flex shrink-0 gap-2">
+                  <Button variant="secondary" size="sm" onClick={() => setConfirmRestore(backup)} disabled={!!busy} loading={busy === backup.id}>
+                    {t('backups.browser.restoreInPlace')}
+                  </Button>
+                  <Button
+                    variant="secondary"
+                    size="sm"
+                    onClick={() => setRestoreAsNew({ id: backup.id, name: `${backup.distroName}-restored` })}
+                    disabled={!!busy}
+                  >
+                    {t('backups.browser.restoreAsNew')}
+                  </Button>
+                  <Button variant="secondary" size="sm" onClick={() => handleSaveTar(backup)} disabled={!!busy}>
+                    {t('backups.browser.saveTar')}
+                  </Button>
+                </div>
+              </div>
+              {restoreAsNew?.id === backup.id && (
+                <div className="mt-3 flex items-end gap-2">
+                  <div className="flex-1">
+                    <Input
+                      label={t('backups.browser.newName')}
+                      value={restoreAsNew.name}
+                      onChange={(e) => setRestoreAsNew({ id: backup.id, name: e.target.value })}
+                      error={newNameExists ? (t('common:validation.duplicateName', { name: restoreAsNew.name.trim() }) as string) : undefined}
+                      disabled={!!busy}
+                    />
+                  </div>
+                  <Button
+                    size="sm"
+                    onClick={() => handleRestore(backup, restoreAsNew.name.trim())}
+                    disabled={!!busy || !restoreAsNew.name.trim() || newNameExists}
+                  >
+                    {t('backups.browser.restore')}
+                  </Button>
+                  <Button variant="secondary" size="sm" onClick={() => setRestoreAsNew(null)} disabled={!!busy}>
+                    {t('common:button.cancel')}
+                  </Button>
+                </div>
+              )}
+            </li>
+          ))}
+        </ul>
+      )}
+
+      <ConfirmDialog
+        isOpen={!!confirmRestore}
+        title={t('backups.browser.confirmTitle')}
+        message={t('backups.browser.confirmMessage', {
+          distro: confirmRestore?.distroName ?? "",
+          date: confirmRestore ? formatDate(confirmRestore.createdAt) : "",
+        })}
+        confirmLabel={t('backups.browser.restore')}
+        onConfirm={() => {
+          const backup = confirmRestore;
+          setConfirmRestore(null);
+          if (backup) handleRestore(backup);
+        }}
+        onCancel={() => setConfirmRestore(null)}
+        danger
+      />
+    </div>
+  );
+}
+
+interface BackupTargetsSectionProps {
+  targets: BackupTargetInfo[];
+  onChanged: () => Promise<void>;
+}
+
+export function BackupTargetsSection({ targets, onChanged }: BackupTargetsSectionProps) {
+  const { t } = useTranslation("settings");
+  const { addNotification } = useNotificationStore();
+  // Target ID being edited, "new" while adding one
+  const [editing, setEditing] = useState<string | null>(null);
+  const [browsing, setBrowsing] = useState<string | null>(null);
+  const [testing, setTesting] = useState<string | null>(null);
+  const [confirmDelete, setConfirmDelete] = useState<BackupTargetInfo | null>(null);
+
+  const handleSaved = async () => {
+    setEditing(null);
+    await onChanged();
+  };
+
+  const handleTest = async (target: BackupTargetInfo) => {
+    setTesting(target.id);
+    try {
+      const count = await wslService.testBackupTarget(target.id);
+      addNotification({
+        type: "success",
+        title: t('backups.targets.testSucceeded'),
+        message: t('backups.targets.testSucceededMessage', { name: target.name, count }),
+      });
+    } catch (err) {
+      addNotification({ type: "error", title: t('backups.targets.testFailed'), message: errorText(err) });
+    } finally {
+      setTesting(null);
+    }
+  };
+
+  const handleDelete = async (target: BackupTargetInfo) => {
+    try {
+      await wslService.deleteBackupTarget(target.id);
+      await onChanged();
+    } catch (err) {
+      logger.error("Failed to remove backup target:", "BackupTargets", err);
+      addNotification({ type: "error", title: t('backups.targets.deleteFailed'), message: errorText(err) });
+    }
+  };
+
+  return (
+    <section className="bg-theme-bg-secondary/50 border border-theme-border-secondary rounded-xl p-6 space-y-4" data-testid="backup-targets">
+      <div className="flex items-start justify-between gap-3">
+        <div>
+          <h3 className="text-sm font-medium text-theme-text-primary">{t('backups.targets.title')}</h3>
+          <p className="text-xs text-theme-text-secondary">{t('backups.targets.description')}</p>
+        </div>
+        <Button variant="secondary" size="sm" onClick={() => setEditing("new")} disabled={editing !== null} data-testid="backup-target-add">
+          {t('backups.targets.add')}
+        </Button>
+      </div>
+
+      {editing === "new" && <TargetEditor target={null} onSaved={handleSaved} onCancel={() => setEditing(null)} />}
+
+      <ul className="space-y-3">
+        {targets.map((target) =>
+          editing === target.id ? (
+            <li key={target.id}>
+              <TargetEditor target={target} onSaved={handleSaved} onCancel={() => setEditing(null)} />
+            </li>
+          ) : (
+            <li
+              key={target.id}
+              className="p-4 bg-theme-bg-tertiary border border-theme-border-secondary rounded-lg"
+              data-testid={`backup-target-${target.id}`}
+            >
+              <div className="flex items-start justify-between gap-3">
+                <div className="min-w-0 space-y-0.5">
+                  <p className="text-sm font-medium text-theme-text-primary truncate">
+                    {target.builtIn ? t('backups.targets.builtInName') : target.name}
+                    <span className="ml-2 text-xs text-theme-text-muted">{t(`backups.targets.kinds.${target.location.kind}`)}</span>
+                  </p>
+                  <p className="text-xs font-mono text-theme-text-secondary truncate">{describeLocation(target.location)}</p>
+                </div>
+                <div className="flex shrink-0 gap-2">
+                  <Button variant="secondary" size="sm" onClick={() => handleTest(target)} loading={testing === target.id} disabled={testing !== null}>
+                    {t('backups.targets.test')}
+                  </Button>
+                  <Button variant="secondary" size="sm" onClick={() => setBrowsing(browsing === target.id ? null : target.id)}>
+                    {t('backups.targets.browse')}
+                  </Button>
+                  {!target.builtIn && (
+                    <>
+                      <Button variant="secondary" size="sm" onClick={() => setEditing(target.id)} disabled={editing !== null}>
+                        {t('backups.targets.edit')}
+                      </Button>
+                      <Button variant="secondary" size="sm" onClick={() => setConfirmDelete(target)}>
+                        {t('backups.targets.delete')}
+                      </Button>
+                    </>
+                  )}
+                </div>
+              </div>
+              {browsing === target.id && <TargetBackups target={target} />}
+            </li>
+          ),
+        )}
+      </ul>
+
+      <ConfirmDialog
+        isOpen={!!confirmDelete}
+        title={t('backups.targets.confirmDeleteTitle')}
+        message={t('backups.targets.confirmDeleteMessage', { name: confirmDelete?.name ?? "" })}
+        confirmLabel={t('backups.targets.delete')}
+        onConfirm={() => {
+          const target = confirmDelete;
+          setConfirmDelete(null);
+          if (target) handleDelete(target);
+        }}
+        onCancel={() => setConfirmDelete(null)}
+        danger
+      />
+    </section>
+  );
+}
@@ -0,0 +1,189 @@
+/**
+ * Environment Settings Component
+ *
+ * Loads a declarative environment file, shows the plan against the current
+ * state and applies it (optionally as a dry run).
+ */
+
+import { useState } from "react";
+import { useTranslation } from "react-i18next";
+import { open } from "@tauri-apps/plugin-dialog";
+import { wslService } from "../../services/wslService";
+import { useDistroStore } from "../../store/distroStore";
+import type { ApplyStatus, PlanItem, PlanKind } from "../../types/environment";
+import { Button } from "../ui/Button";
+import { CodeIcon, FolderIcon } from "../icons";
+import { logger } from "../../utils/logger";
+
+const KIND_CLASSES: Record<PlanKind, string> = {
+  create: "text-theme-status-success",
+  update: "text-theme-accent-primary",
+  drift: "text-theme-status-warning",
+};
+
+const STATUS_CLASSES: Record<ApplyStatus, string> = {
+  dryRun: "text-theme-text-muted",
+  applied: "text-theme-status-success",
+  failed: "text-theme-status-error",
+  skipped: "text-theme-status-warning",
+};
+
+interface ItemResult {
+  status: ApplyStatus;
+  message?: string | null;
+}
+
+export function EnvironmentSettings() {
+  const { t } = useTranslation("settings");
+  const { fetchDistros } = useDistroStore();
+  const [path, setPath] = useState<string | null>(null);
+  const [plan, setPlan] = useState<PlanItem[] | null>(null);
+  const [results, setResults] = useState<ItemResult[] | null>(null);
+  const [isBusy, setIsBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const loadPlan = async (filePath: string) => {
+    setIsBusy(true);
+    setError(null);
+    setResults(null);
+    try {
+      setPlan(await wslService.planEnvironment(filePath));
+    } catch (err) {
+      logger.error("Failed to plan environment:", "EnvironmentSettings", err);
+      setPlan(null);
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  const handleBrowse = async () => {
+    const selected = await open({
+      multiple: false,
+      title: t('environment.browseTitle'),
+      filters: [{ name: t('environment.fileFilter'), extensions: ["yaml", "yml", "json"] }],
+    });
+    if (selected && !Array.isArray(selected)) {
+      setPath(selected);
+      await loadPlan(selected);
+    }
+  };
+
+  const handleApply = async (dryRun: boolean) => {
+    if (!path) return;
+    setIsBusy(true);
+    setError(null);
+    try {
+      const applied = await wslService.applyEnvironment(path, dryRun);
+      setPlan(applied.map((r) => r.item));
+      setResults(applied.map(({ status, message }) => ({ status, message })));
+      if (!dryRun) {
+        await fetchDistros(true);
+      }
+    } catch (err) {
+      logger.error("Failed to apply environment:", "EnvironmentSettings", err);
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  const hasChanges = plan?.some((item) => item.kind !== "drift") ?? false;
+
+  return (
+    <div className="space-y-6" data-testid="environment-settings">
+      <section className="relative overflow-hidden bg-linear-to-br from-cyan-900/20 via-theme-bg-secondary/50 to-theme-bg-secondary/50 border border-cyan-800/30 rounded-xl p-6">
+        <div className="absolute inset-0 bg-[radial-gradient(ellipse_at_top_left,_var(--tw-gradient-stops))] from-cyan-500/5 via-transparent to-transparent" />
+        <div className="relative">
+          <div className="flex items-center gap-3 mb-4">
+            <div className="w-10 h-10 rounded-lg bg-linear-to-br from-cyan-500 to-blue-600 flex items-center justify-center shadow-lg shadow-cyan-900/30">
+              <CodeIcon size="md" className="text-white" />
+            </div>
+            <div>
+              <h2 className="text-lg font-medium text-theme-text-primary">{t('environment.title')}</h2>
+              <p className="text-sm text-theme-text-secondary">{t('environment.description')}</p>
+            </div>
+          </div>
+
+          <div className="flex items-center gap-2">
+            <div
+              className="flex-1 min-w-0 px-3 py-2 text-sm font-mono truncate bg-theme-bg-tertiary border border-theme-border-secondary rounded-lg text-theme-text-secondary"
+              data-testid="environment-path"
+            >
+              {path ?? t('environment.noFile')}
+            </div>
+            <Button variant="secondary" icon={<FolderIcon size="sm" />} onClick={handleBrowse} disabled={isBusy}>
+              {t('environment.browse')}
+            </Button>
+            <Button variant="secondary" onClick={() => path && loadPlan(path)} disabled={!path || isBusy}>
+              {t('environment.replan')}
+            </Button>
+          </div>
+
+          {error && (
+            <p data-testid="environment-error" className="mt-3 text-sm text-theme-status-error">{error}</p>
+          )}
+        </div>
+      </section>
+
+      {plan && (
+        <section className="bg-theme-bg-secondary/50 border border-theme-border-secondary rounded-xl p-6 space-y-4" data-testid="environment-plan">
+          <div className="flex items-center justify-between gap-3">
+            <h3 className="text-sm font-medium text-theme-text-primary">
+              {plan.length === 0 ? t('environment.inSync') : t('environment.planSummary', { count: plan.length })}
+            </h3>
+            <div className="flex gap-2">
+              <Button variant="secondary" size="sm" onClick={() => handleApply(true)} disabled={!hasChanges || isBusy}>
+                {t('environment.dryRun')}
+              </Button>
+              <Button size="sm" onClick={() => handleApply(false)} loading={isBusy} disabled={!hasChanges || isBusy} data-testid="environment-apply">
+                {t('environment.apply')}
+              </Button>
+            </div>
+          </div>
+
+          <ol className="space-y-2">
+            {plan.map((item, index) => {
+              const result = results?.[index];
+              return (
+                <li
+                  key={`${item.target}-${item.name}-${index}`}
+                  className="p-3 bg-theme-bg-tertiary border border-theme-border-secondary rounded-lg text-sm"
+                >
+                  <div className="flex items-center justify-between gap-3">
+                    <div className="flex items-center gap-2 min-w-0">
+                      <span className={`text-xs font-medium uppercase ${KIND_CLASSES[item.kind]}`}>
+                        {t(`environment.kinds.${item.kind}`)}
+                      </span>
+                      <span className="text-theme-text-secondary">{t(`environment.targets.${item.target}`)}</span>
+                      {item.name && <span className="font-mono text-theme-text-primary truncate">{item.name}</span>}
+                    </div>
+                    {result && (
+                      <span className={`text-xs ${STATUS_CLASSES[result.status]}`}>
+                        {t(`environment.status.${result.status}`)}
+                      </span>
+                    )}
+                  </div>
+                  <ul className="mt-2 space-y-0.5 font-mono text-xs text-theme-text-muted">
+                    {item.changes.map((change) => (
+                      <li key={change.field} className="break-all">
+                        {change.field}: {change.current ?? t('environment.unset')} → {change.desired ?? t('environment.unset')}
+                      </li>
+                    ))}
+                  </ul>
+                  {result?.message && (
+                    <p className="mt-2 text-xs text-theme-text-secondary">{result.message}</p>
+                  )}
+                </li>
+              );
+            })}
+          </ol>
+
+          {plan.some((item) => item.kind === "drift") && (
+            <p className="text-xs text-theme-text-muted">{t('environment.driftNote')}</p>
+          )}
+        </section>
+      )}
+    </div>
+  );
+}
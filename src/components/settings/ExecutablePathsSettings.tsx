@@ -26,6 +26,7 @@ export function ExecutablePathsSettings() {
   const handleResetDefaults = async () => {
     await updateSetting("executablePaths", DEFAULT_EXECUTABLE_PATHS);
     await updateSetting("defaultInstallBasePath", "");
+    await updateSetting("snapshotDirectory", "");
   };
 
   const handleBrowseInstallLocation = async () => {
@@ -40,6 +41,18 @@ export function ExecutablePathsSettings() {
     }
   };
 
+  const handleBrowseSnapshotDirectory = async () => {
+    const selectedPath = await open({
+      directory: true,
+      multiple: false,
+      title: t('executables.selectSnapshotFolder'),
+    });
+
+    if (selectedPath && !Array.isArray(selectedPath)) {
+      updateSetting("snapshotDirectory", selectedPath);
+    }
+  };
+
   return (
     <div className="space-y-8">
       <section className="relative overflow-hidden bg-linear-to-br from-cyan-900/20 via-theme-bg-secondary/50 to-theme-bg-secondary/50 border border-cyan-800/30 rounded-xl p-6">
@@ -137,6 +150,15 @@ export function ExecutablePathsSettings() {
               onChange={(v) => updateSetting("defaultInstallBasePath", v)}
               onBrowse={handleBrowseInstallLocation}
             />
+            <SettingPathInput
+              className="font-mono text-sm"
+              label={t('executables.snapshotDirectory')}
+              description={t('executables.snapshotDirectoryDesc')}
+              value={settings.snapshotDirectory}
+              placeholder="%LOCALAPPDATA%\wsl-ui\snapshots"
+              onChange={(v) => updateSetting("snapshotDirectory", v)}
+              onBrowse={handleBrowseSnapshotDirectory}
+            />
           </div>
 
           {/* Reset to Defaults */}
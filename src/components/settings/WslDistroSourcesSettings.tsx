@@ -10,11 +10,15 @@
 
 import { useCallback, useEffect, useState } from "react";
 import { useTranslation } from "react-i18next";
+import { save } from "@tauri-apps/plugin-dialog";
 import { wslService } from "../../services/wslService";
+import { useDistroStore } from "../../store/distroStore";
+import type { DownloadDistro } from "../../types/catalog";
 import {
   SUGGESTED_SOURCES,
   type DistroSource,
   type DistroSourceMode,
+  type ManifestEntryPreview,
   type ManifestPreview,
   type SuggestedSource,
 } from "../../types/distroSources";
@@ -28,6 +32,7 @@ interface Banner {
 
 export function WslDistroSourcesSettings() {
   const { t } = useTranslation("settings");
+  const { fetchDistros } = useDistroStore();
 
   const [currentSource, setCurrentSource] = useState<DistroSource | null>(null);
   const [isLoading, setIsLoading] = useState(true);
@@ -44,6 +49,11 @@ export function WslDistroSourcesSettings() {
   const [isClearing, setIsClearing] = useState(false);
   const [banner, setBanner] = useState<Banner | null>(null);
 
+  const [catalogDistros, setCatalogDistros] = useState<DownloadDistro[]>([]);
+  const [selectedDistroIds, setSelectedDistroIds] = useState<string[]>([]);
+  const [isGenerating, setIsGenerating] = useState(false);
+  const [installingEntry, setInstallingEntry] = useState<string | null>(null);
+
   // Refresh the registered source from the registry. Does not touch the
   // user's typed URL — seeding only happens once on initial mount via the
   // separate effect below. This prevents an in-flight apply from clobbering
@@ -83,6 +93,17 @@ export function WslDistroSourcesSettings() {
     };
   }, []);
 
+  useEffect(() => {
+    wslService
+      .getDistroCatalog()
+      .then((catalog) =>
+        setCatalogDistros(
+          catalog.downloadDistros.filter((d) => d.enabled !== false && d.available !== false)
+        )
+      )
+      .catch(() => setCatalogDistros([]));
+  }, []);
+
   const handlePreview = async (urlToPreview?: string) => {
     const url = (urlToPreview ?? urlInput).trim();
     if (!url) {
@@ -137,6 +158,72 @@ export function WslDistroSourcesSettings() {
     }
   };
 
+  const toggleDistro = (id: string) => {
+    setSelectedDistroIds((ids) =>
+      ids.includes(id) ? ids.filter((i) => i !== id) : [...ids, id]
+    );
+  };
+
+  const handleGenerate = async () => {
+    const path = await save({
+      defaultPath: "distributions.json",
+      filters: [{ name: "JSON", extensions: ["json"] }],
+      title: t("distroSources.generateTitle"),
+    });
+    if (!path) return;
+
+    setIsGenerating(true);
+    setBanner(null);
+    try {
+      const result = await wslService.generateDistroManifest(
+        catalogDistros
+          .filter((d) => selectedDistroIds.includes(d.id))
+          .map((d) => ({
+            flavor: d.id.split("-")[0],
+            name: d.id,
+            friendlyName: d.name,
+            source: { kind: "catalog" as const, distroId: d.id },
+          })),
+        path
+      );
+      setUrlInput(result.url);
+      setPreview(result.preview);
+      setPreviewError(null);
+      setBanner({
+        kind: "success",
+        message: t("distroSources.generated", { path: result.path }),
+      });
+    } catch (e) {
+      setBanner({ kind: "error", message: String(e) });
+    } finally {
+      setIsGenerating(false);
+    }
+  };
+
+  const handleInstallEntry = async (entry: ManifestEntryPreview) => {
+    if (!preview) return;
+    const key = `${entry.flavor}-${entry.name}`;
+    setInstallingEntry(key);
+    setBanner(null);
+    try {
+      await wslService.installManifestEntry(
+        preview.url,
+        entry.flavor,
+        entry.name,
+        entry.name
+      );
+      setBanner({
+        kind: "success",
+        message: t("distroSources.installed", { name: entry.name }),
+      });
+      await fetchDistros();
+    } catch (e) {
+      setBanner({ kind: "error", message: String(e) });
+    } finally {
+      setInstallingEntry(null);
+    }
+  };
+
   const handleAddSuggested = async (s: SuggestedSource) => {
     setUrlInput(s.url);
     setMode("append");
@@ -305,7 +392,9 @@ export function WslDistroSourcesSettings() {
             </p>
             {previewError ? (
               <p className="text-sm text-red-300">{previewError}</p>
-            ) : preview && preview.entries.length === 0 ? (
+            ) : preview &&
+              preview.entries.length === 0 &&
+              preview.legacyDistributions.length === 0 ? (
               <p className="text-sm text-theme-text-muted">
                 {t("distroSources.previewEmpty")}
               </p>
@@ -328,10 +417,66 @@ export function WslDistroSourcesSettings() {
                         .join(", ") || "no arch"}
                       {e.default ? `, ${t("distroSources.defaultTag")}` : ""})
                     </span>
+                    <button
+                      onClick={() => handleInstallEntry(e)}
+                      disabled={installingEntry !== null}
+                      title={t("distroSources.installHint")}
+                      className="ml-auto px-2 py-0.5 text-xs text-emerald-300 hover:text-emerald-200 border border-emerald-700/50 hover:border-emerald-500 rounded-md transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+                    >
+                      {installingEntry === `${e.flavor}-${e.name}`
+                        ? t("distroSources.installing")
+                        : t("distroSources.install")}
+                    </button>
                   </li>
                 ))}
               </ul>
             ) : null}
+            {preview && preview.legacyDistributions.length > 0 && (
+              <>
+                <p className="text-xs text-theme-text-muted mt-3 mb-2">
+                  {t("distroSources.previewLegacyLabel")}
+                </p>
+                <ul className="space-y-1 text-sm">
+                  {preview.legacyDistributions.map((d) => (
+                    <li
+                      key={`legacy-${d.name}`}
+                      className="flex flex-wrap items-baseline gap-2"
+                    >
+                      <span className="font-mono text-theme-text-primary">
+                        {d.name}
+                      </span>
+                      <span className="text-xs text-theme-text-muted">
+                        {d.friendlyName}
+                      </span>
+                      {d.storeAppId && (
+                        <span className="text-xs text-theme-text-muted font-mono">
+                          {d.storeAppId}
+                        </span>
+                      )}
+                    </li>
+                  ))}
+                </ul>
+              </>
+            )}
+            {preview && preview.warnings.length > 0 && (
+              <div className="mt-3 p-2 bg-amber-900/20 border border-amber-700/50 rounded-md">
+                <p className="text-xs font-medium text-amber-200 mb-1">
+                  {t("distroSources.previewWarningsLabel")}
+                </p>
+                <ul className="space-y-0.5 text-xs text-amber-200">
+                  {preview.warnings.map((w, i) => (
+                    <li key={`${w.kind}-${w.flavor}-${w.name}-${w.arch}-${i}`}>
+                      {t(`distroSources.warnings.${w.kind}`, {
+                        name: w.name ?? "",
+                        flavor: w.flavor ?? "",
+                        arch: w.arch ?? "",
+                        defaultValue: w.message,
+                      })}
+                    </li>
+                  ))}
+                </ul>
+              </div>
+            )}
           </div>
         )}
 
@@ -357,6 +502,51 @@ export function WslDistroSourcesSettings() {
           </button>
         </div>
 
+        {/* Manifest generator */}
+        <div className="pt-6 mb-6 border-t border-theme-border-secondary/50">
+          <p className="text-sm font-medium text-theme-text-primary mb-1">
+            {t("distroSources.generateTitle")}
+          </p>
+          <p className="text-xs text-theme-text-muted mb-3">
+            {t("distroSources.generateDesc")}
+          </p>
+          {catalogDistros.length === 0 ? (
+            <p className="text-xs text-theme-text-muted">
+              {t("distroSources.generateEmpty")}
+            </p>
+          ) : (
+            <>
+              <div className="space-y-1 mb-3">
+                {catalogDistros.map((d) => (
+                  <label
+                    key={d.id}
+                    className="flex items-center gap-2 text-sm text-theme-text-primary cursor-pointer"
+                  >
+                    <input
+                      type="checkbox"
+                      checked={selectedDistroIds.includes(d.id)}
+                      onChange={() => toggleDistro(d.id)}
+                    />
+                    <span>{d.name}</span>
+                    <span className="text-xs text-theme-text-muted font-mono">
+                      {d.id}
+                    </span>
+                  </label>
+                ))}
+              </div>
+              <button
+                onClick={handleGenerate}
+                disabled={isGenerating || selectedDistroIds.length === 0}
+                className="px-3 py-1.5 text-xs text-emerald-300 hover:text-emerald-200 border border-emerald-700/50 hover:border-emerald-500 rounded-md transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+              >
+                {isGenerating
+                  ? t("distroSources.generating")
+                  : t("distroSources.generate")}
+              </button>
+            </>
+          )}
+        </div>
+
         {/* Suggested sources */}
         <div className="pt-6 border-t border-theme-border-secondary/50">
           <p className="text-sm font-medium text-theme-text-primary mb-1">
@@ -61,9 +61,9 @@ export function getTerminalPresetsWithStatus(installedTerminals: InstalledTermin
   });
 }
 
-export type SettingsTab = "app" | "appearance" | "polling" | "timeouts" | "executables" | "wsl-global" | "wsl-distro" | "actions" | "distros" | "sources" | "privacy" | "about";
+export type SettingsTab = "app" | "appearance" | "polling" | "timeouts" | "executables" | "wsl-global" | "wsl-distro" | "actions" | "environment" | "backups" | "distros" | "sources" | "privacy" | "about";
 
-export type SettingsIconName = "settings" | "palette" | "refresh" | "clock" | "terminal" | "server" | "folder" | "sparkles" | "grid" | "download" | "shield" | "info";
+export type SettingsIconName = "settings" | "palette" | "refresh" | "clock" | "terminal" | "server" | "folder" | "sparkles" | "code" | "upload" | "grid" | "download" | "shield" | "info";
 
 export interface SettingsTabConfig {
   id: SettingsTab;
@@ -81,6 +81,8 @@ export const SETTINGS_TABS: SettingsTabConfig[] = [
   { id: "wsl-global", label: "WSL Global", labelKey: "settings:tabs.wslGlobal", icon: "server" },
   { id: "wsl-distro", label: "Per-Distribution", labelKey: "settings:tabs.wslDistro", icon: "folder" },
   { id: "actions", label: "Custom Actions", labelKey: "settings:tabs.actions", icon: "sparkles" },
+  { id: "environment", label: "Environment", labelKey: "settings:tabs.environment", icon: "code" },
+  { id: "backups", label: "Backups", labelKey: "settings:tabs.backups", icon: "upload" },
   { id: "distros", label: "Distro Catalog", labelKey: "settings:tabs.distros", icon: "grid" },
   { id: "sources", label: "Remote Sources", labelKey: "settings:tabs.sources", icon: "download" },
   { id: "privacy", label: "Privacy", labelKey: "settings:tabs.privacy", icon: "shield" },
@@ -14,6 +14,8 @@ export { WslDistroSourcesSettings } from './WslDistroSourcesSettings';
 export { ContainerRuntimeSettings } from './ContainerRuntimeSettings';
 export { PrivacySettings } from './PrivacySettings';
 export { LanguageSettings } from './LanguageSettings';
+export { EnvironmentSettings } from './EnvironmentSettings';
+export { BackupSettings } from './BackupSettings';
 export { IDE_PRESETS, TERMINAL_PRESETS, SETTINGS_TABS, getTerminalPresetsWithStatus } from './constants';
 export type { PresetOption, SettingsTab, SettingsIconName, SettingsTabConfig } from './constants';
 
@@ -1,101 +1,21 @@
-// LXC Catalog Service - Fetches and parses Linux Containers image catalog
+// LXC Catalog Service - Loads the Linux Containers image catalog from the backend
+// (the backend fetches simplestreams, filters by architecture/stability and caches on disk)
+import { invoke } from "@tauri-apps/api/core";
 import type {
-  LxcImagesResponse,
-  LxcProduct,
+  LxcCatalogResponse,
   LxcDistribution,
   LxcDistributionGroup,
   LxcDistributionRelease,
-  LxcCatalogCache,
   DistributionSourceSettings,
 } from "../types/lxcCatalog";
 import { getLxcDistroDisplayName, DEFAULT_DISTRIBUTION_SOURCE_SETTINGS } from "../types/lxcCatalog";
 import { debug, info, warn } from "../utils/logger";
 
-const CACHE_KEY = "lxc-catalog-cache";
-
-/**
- * Get the system architecture for filtering
- * WSL typically runs on amd64 (x86_64)
- */
-function getSystemArch(): string {
-  return "amd64";
-}
-
-/**
- * Parse a product key to extract components
- * Format: {distro}:{release}:{arch}:{variant}
- */
-function parseProductKey(key: string): { os: string; release: string; arch: string; variant: string } | null {
-  const parts = key.split(":");
-  if (parts.length !== 4) return null;
-  return {
-    os: parts[0],
-    release: parts[1],
-    arch: parts[2],
-    variant: parts[3],
-  };
-}
-
-/**
- * Check if a release is considered stable (not experimental)
- */
-function isStableRelease(os: string, release: string): boolean {
-  const lower = release.toLowerCase();
-
-  // Skip experimental, dev, edge releases unless explicitly wanted
-  if (lower.includes("edge") || lower.includes("dev") || lower.includes("devel")) {
-    return false;
-  }
-  if (lower.includes("rawhide") || lower.includes("sid") || lower.includes("unstable")) {
-    return false;
-  }
-  if (lower === "current" && os !== "archlinux") {
-    // "current" is fine for Arch, but might be rolling for others
-    return true;
-  }
-
-  return true;
-}
-
-/**
- * Convert raw LXC product to parsed distribution
- */
-function parseProduct(
-  productKey: string,
-  product: LxcProduct,
-  baseUrl: string
-): LxcDistribution | null {
-  const parsed = parseProductKey(productKey);
-  if (!parsed) return null;
-
-  // Get the latest version (versions are date-keyed, like "20241215_13:00")
-  const versionKeys = Object.keys(product.versions).sort().reverse();
-  if (versionKeys.length === 0) return null;
-
-  const latestVersion = product.versions[versionKeys[0]];
-  if (!latestVersion?.items) return null;
-
-  // Find the rootfs.tar.xz item
-  const rootfsItem = latestVersion.items["rootfs.tar.xz"] || latestVersion.items["root.tar.xz"];
-  if (!rootfsItem) return null;
-
-  // Build download URL
-  const downloadUrl = `${baseUrl}/${rootfsItem.path}`;
-
-  return {
-    id: productKey,
-    name: getLxcDistroDisplayName(product.os),
-    version: product.release,
-    releaseTitle: product.release_title || product.release,
-    arch: product.arch,
-    variant: product.variant || "default",
-    aliases: product.aliases ? product.aliases.split(",").map((a) => a.trim()) : [],
-    downloadUrl,
-    sizeBytes: rootfsItem.size,
-    sha256: rootfsItem.sha256,
-    buildDate: versionKeys[0],
-  };
-}
+/** Timestamps of the most recently loaded catalog */
+let lastCacheInfo: { lastUpdated: string | null; expiresAt: string | null } = {
+  lastUpdated: null,
+  expiresAt: null,
+};
 
 /**
  * Group distributions by OS for display
@@ -162,63 +82,6 @@ function groupDistributions(distributions: LxcDistribution[]): LxcDistributionGr
   return groups;
 }
 
-/**
- * Load cached catalog from localStorage
- */
-function loadCache(): LxcCatalogCache | null {
-  try {
-    const cached = localStorage.getItem(CACHE_KEY);
-    if (!cached) {
-      debug("[lxcCatalog] No cache found");
-      return null;
-    }
-
-    const data = JSON.parse(cached) as LxcCatalogCache;
-
-    // Check if cache has expired
-    if (new Date(data.expiresAt) < new Date()) {
-      debug("[lxcCatalog] Cache expired, removing");
-      localStorage.removeItem(CACHE_KEY);
-      return null;
-    }
-
-    debug(`[lxcCatalog] Cache hit: ${data.distributions.length} distributions`);
-    return data;
-  } catch {
-    warn("[lxcCatalog] Failed to load cache");
-    return null;
-  }
-}
-
-/**
- * Save catalog to cache
- */
-function saveCache(distributions: LxcDistribution[], cacheDurationHours: number): void {
-  try {
-    const now = new Date();
-    const expiresAt = new Date(now.getTime() + cacheDurationHours * 60 * 60 * 1000);
-
-    const cache: LxcCatalogCache = {
-      lastUpdated: now.toISOString(),
-      expiresAt: expiresAt.toISOString(),
-      distributions,
-    };
-
-    localStorage.setItem(CACHE_KEY, JSON.stringify(cache));
-    debug(`[lxcCatalog] Cache saved: ${distributions.length} distributions, expires ${expiresAt.toISOString()}`);
-  } catch {
-    warn("[lxcCatalog] Failed to save cache");
-  }
-}
-
-/**
- * Clear the catalog cache
- */
-function clearLxcCatalogCache(): void {
-  info("[lxcCatalog] Clearing cache");
-  localStorage.removeItem(CACHE_KEY);
-}
-
 /**
  * LXC Catalog Service
  */
@@ -232,51 +95,26 @@ export const lxcCatalogService = {
     forceRefresh = false
   ): Promise<{ distributions: LxcDistribution[]; groups: LxcDistributionGroup[] }> {
     const config = settings || DEFAULT_DISTRIBUTION_SOURCE_SETTINGS;
-    const systemArch = getSystemArch();
-
-    debug(`[lxcCatalog] Fetching catalog (forceRefresh=${forceRefresh})`);
-
-    // Check cache first (unless forcing refresh)
-    if (!forceRefresh) {
-      const cached = loadCache();
-      if (cached) {
-        debug("[lxcCatalog] Using cached catalog");
-        const groups = groupDistributions(cached.distributions);
-        return { distributions: cached.distributions, groups };
-      }
-    }
-
-    // Fetch from server
-    const catalogUrl = `${config.lxcBaseUrl}/streams/v1/images.json`;
-    info(`[lxcCatalog] Fetching from: ${catalogUrl}`);
-
-    const response = await fetch(catalogUrl);
-    if (!response.ok) {
-      throw new Error(`Failed to fetch LXC catalog: ${response.status} ${response.statusText}`);
-    }
-
-    const data = (await response.json()) as LxcImagesResponse;
-    debug(`[lxcCatalog] Received ${Object.keys(data.products).length} products`);
-
-    // Parse and filter products
-    const distributions: LxcDistribution[] = [];
-
-    for (const [key, product] of Object.entries(data.products)) {
-      // Only include matching architecture
-      if (product.arch !== systemArch) continue;
-
-      // Filter unstable releases if not enabled
-      if (!config.showUnstableReleases && !isStableRelease(product.os, product.release)) {
-        continue;
-      }
-
-      const parsed = parseProduct(key, product, config.lxcBaseUrl);
-      if (parsed) {
-        distributions.push(parsed);
-      }
-    }
-
-    info(`[lxcCatalog] Parsed ${distributions.length} distributions for ${systemArch}`);
+    debug(`[lxcCatalog] Fetching catalog from ${config.lxcBaseUrl} (forceRefresh=${forceRefresh})`);
+
+    const catalog = await invoke<LxcCatalogResponse>("get_lxc_catalog", { forceRefresh });
+    lastCacheInfo = { lastUpdated: catalog.lastUpdated, expiresAt: catalog.expiresAt };
+
+    const distributions: LxcDistribution[] = catalog.images.map((image) => ({
+      id: image.id,
+      name: getLxcDistroDisplayName(image.os),
+      version: image.release,
+      releaseTitle: image.releaseTitle,
+      arch: image.arch,
+      variant: image.variant,
+      aliases: image.aliases,
+      downloadUrl: image.downloadUrl,
+      sizeBytes: image.sizeBytes,
+      sha256: image.sha256 ?? undefined,
+      buildDate: image.buildDate,
+    }));
+
+    info(`[lxcCatalog] Loaded ${distributions.length} distributions`);
 
     // Sort by name, then version
     distributions.sort((a, b) => {
@@ -285,9 +123,6 @@ export const lxcCatalogService = {
       return b.version.localeCompare(a.version);
     });
 
-    // Save to cache
-    saveCache(distributions, config.cacheDurationHours);
-
     // Group for display
     const groups = groupDistributions(distributions);
 
@@ -318,17 +153,15 @@ export const lxcCatalogService = {
    * Get the last cache update time
    */
   getCacheInfo(): { lastUpdated: string | null; expiresAt: string | null } {
-    const cached = loadCache();
-    if (!cached) {
-      return { lastUpdated: null, expiresAt: null };
-    }
-    return { lastUpdated: cached.lastUpdated, expiresAt: cached.expiresAt };
+    return lastCacheInfo;
   },
 
   /**
    * Clear the cache
    */
   clearCache(): void {
-    clearLxcCatalogCache();
+    info("[lxcCatalog] Clearing cache");
+    lastCacheInfo = { lastUpdated: null, expiresAt: null };
+    invoke("clear_lxc_catalog_cache").catch((err) => warn(`[lxcCatalog] Failed to clear cache: ${err}`));
   },
 };
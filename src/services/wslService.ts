@@ -1,12 +1,23 @@
 import { invoke } from "@tauri-apps/api/core";
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import { save, open } from "@tauri-apps/plugin-dialog";
-import type { DistroCatalog, DownloadDistro, ContainerImage, MsStoreDistroInfo } from "../types/catalog";
-import type { Distribution, DistroMetadata } from "../types/distribution";
+import type { ArchiveListing, ArchiveSource, FileRestoreReport } from "../types/archiveFiles";
+import type { DistroCatalog, DownloadDistro, ContainerImage, MsStoreDistroInfo, CatalogSubscriptionStatus, CatalogSelection, CatalogImportReport } from "../types/catalog";
+import type { CloneResult, Distribution, DistroMetadata, WslPackageOptions, WslPackageReport } from "../types/distribution";
+import type { DecryptionKey, EncryptionKey, EncryptionKind, KeyPair } from "../types/encryption";
+import type { ApplyItemResult, PlanItem } from "../types/environment";
+import type { ExportFilter, FilterPresetInfo, FilterReport } from "../types/exportFilter";
+import type { MigrationPreview, MigrationReport, MigrationSelection } from "../types/migration";
+import type { BackupManifest, BackupRun, BackupSchedule, BackupTargetConfig, BackupTargetInfo, DistroBackupStatus, RepositoryCheckReport, RepositoryPruneReport, TargetCredentials } from "../types/backup";
+import type { CloudInitReport, ProvisioningReport, ProvisioningSpec } from "../types/provisioning";
+import type { RootfsCustomization, RootfsInspection } from "../types/rootfs";
+import type { RestoreResult, RestoreTarget, Snapshot } from "../types/snapshot";
 import type { RdpDetectionResult, WslConfigStatus, WslConfigPendingStatus } from "../types/rdp";
 import type { WslConfig, WslConf, GpuStatus, NvidiaContainerToolkitStatus, InstalledTerminal } from "../types/settings";
 import type {
   DistroSource,
+  GeneratedManifest,
+  ManifestEntryRequest,
   ManifestPreview,
 } from "../types/distroSources";
 import { debug, info } from "../utils/logger";
@@ -174,9 +185,206 @@ export const wslService = {
     await invoke("restart_distribution", { name, id });
   },
 
-  async cloneDistribution(source: string, newName: string, installLocation?: string): Promise<void> {
+  /**
+   * Create the default user of a new distribution (account, groups, sudo,
+   * password) and make it the default in wsl.conf
+   */
+  async provisionDistribution(name: string, spec: ProvisioningSpec): Promise<ProvisioningReport> {
+    info(`[wslService] Provisioning ${spec.username} in ${name}`);
+    return await invoke<ProvisioningReport>("provision_distribution", { name, spec });
+  },
+
+  /**
+   * Check cloud-config user data; resolves to the top-level keys that will be
+   * ignored, rejects when it is invalid
+   */
+  async validateCloudConfig(userData: string): Promise<string[]> {
+    return await invoke<string[]>("validate_cloud_config", { userData });
+  },
+
+  /**
+   * Apply cloud-config user data (users, write_files, packages, timezone,
+   * locale, runcmd) to an installed distribution
+   */
+  async applyCloudConfig(name: string, userData: string): Promise<CloudInitReport> {
+    info(`[wslService] Applying cloud-config to ${name}`);
+    return await invoke<CloudInitReport>("apply_cloud_config", { name, userData });
+  },
+
+  /**
+   * Re-run the cloud-config stored in the distribution's metadata
+   */
+  async rerunCloudConfig(name: string): Promise<CloudInitReport> {
+    info(`[wslService] Re-running cloud-config in ${name}`);
+    return await invoke<CloudInitReport>("rerun_cloud_config", { name });
+  },
+
+  async cloneDistribution(
+    source: string,
+    newName: string,
+    installLocation?: string,
+    customization?: RootfsCustomization,
+    filter?: ExportFilter,
+  ): Promise<CloneResult> {
     info(`[wslService] Cloning distribution: ${source} -> ${newName}`);
-    await invoke("clone_distribution", { source, newName, installLocation: installLocation || null });
+    return await invoke<CloneResult>("clone_distribution", {
+      source,
+      newName,
+      installLocation: installLocation || null,
+      customization: customization || null,
+      filter: filter || null,
+    });
+  },
+
+  /**
+   * List snapshots, newest first; pass a distro name to only get its snapshots
+   */
+  async listSnapshots(distroName?: string): Promise<Snapshot[]> {
+    debug(`[wslService] Listing snapshots${distroName ? ` of ${distroName}` : ""}`);
+    return await invoke<Snapshot[]>("list_snapshots", { distroName: distroName || null });
+  },
+
+  async createSnapshot(distroName: string, name: string, description?: string): Promise<Snapshot> {
+    info(`[wslService] Creating snapshot '${name}' of ${distroName}`);
+    return await invoke<Snapshot>("create_snapshot", { distroName, name, description: description || null });
+  },
+
+  async restoreSnapshot(id: string, target: RestoreTarget): Promise<RestoreResult> {
+    info(`[wslService] Restoring snapshot ${id} (${target.mode})`);
+    return await invoke<RestoreResult>("restore_snapshot", { id, target });
+  },
+
+  async deleteSnapshot(id: string): Promise<void> {
+    info(`[wslService] Deleting snapshot ${id}`);
+    await invoke("delete_snapshot", { id });
+  },
+
+  // Backup functions
+
+  /**
+   * Backup schedule, last successful backup and next run of each distribution
+   */
+  async getBackupStatus(): Promise<DistroBackupStatus[]> {
+    debug("[wslService] Getting backup status");
+    return await invoke<DistroBackupStatus[]>("get_backup_status");
+  },
+
+  async saveBackupSchedule(schedule: BackupSchedule): Promise<BackupSchedule> {
+    info(`[wslService] Saving ${schedule.frequency} backup schedule for ${schedule.distroName}`);
+    return await invoke<BackupSchedule>("save_backup_schedule", { schedule });
+  },
+
+  async deleteBackupSchedule(distroName: string): Promise<void> {
+    info(`[wslService] Removing backup schedule for ${distroName}`);
+    await invoke("delete_backup_schedule", { distroName });
+  },
+
+  /**
+   * Back up a distribution now; the returned run may be failed or skipped
+   */
+  async runBackupNow(distroName: string): Promise<BackupRun> {
+    info(`[wslService] Running backup of ${distroName}`);
+    return await invoke<BackupRun>("run_backup_now", { distroName });
+  },
+
+  /**
+   * The backup folder and the configured backup targets, without secrets
+   */
+  async listBackupTargets(): Promise<BackupTargetInfo[]> {
+    debug("[wslService] Listing backup targets");
+    return await invoke<BackupTargetInfo[]>("list_backup_targets");
+  },
+
+  /**
+   * Create (empty ID) or update a backup target; stored credentials are kept when none are given
+   */
+  async saveBackupTarget(target: BackupTargetConfig, credentials?: TargetCredentials): Promise<BackupTargetInfo> {
+    info(`[wslService] Saving ${target.location.kind} backup target ${target.name}`);
+    return await invoke<BackupTargetInfo>("save_backup_target", { target, credentials: credentials ?? null });
+  },
+
+  async deleteBackupTarget(targetId: string): Promise<void> {
+    info(`[wslService] Removing backup target ${targetId}`);
+    await invoke("delete_backup_target", { targetId });
+  },
+
+  /**
+   * Connect to a backup target; resolves to the number of backups found
+   */
+  async testBackupTarget(targetId: string): Promise<number> {
+    info(`[wslService] Testing backup target ${targetId}`);
+    return await invoke<number>("test_backup_target", { targetId });
+  },
+
+  async listTargetBackups(targetId: string): Promise<BackupManifest[]> {
+    debug(`[wslService] Listing backups on ${targetId}`);
+    return await invoke<BackupManifest[]>("list_target_backups", { targetId });
+  },
+
+  async restoreBackup(targetId: string, backupId: string, target: RestoreTarget): Promise<RestoreResult> {
+    info(`[wslService] Restoring backup ${backupId} from ${targetId} (${target.mode})`);
+    return await invoke<RestoreResult>("restore_backup", { targetId, backupId, target });
+  },
+
+  async exportBackupTar(targetId: string, backupId: string, destination: string): Promise<void> {
+    info(`[wslService] Saving backup ${backupId} from ${targetId} to ${destination}`);
+    await invoke("export_backup_tar", { targetId, backupId, destination });
+  },
+
+  async checkBackupRepository(targetId: string, verifyData: boolean): Promise<RepositoryCheckReport> {
+    info(`[wslService] Checking repository on ${targetId}${verifyData ? " (verifying data)" : ""}`);
+    return await invoke<RepositoryCheckReport>("check_backup_repository", { targetId, verifyData });
+  },
+
+  async pruneBackupRepository(targetId: string): Promise<RepositoryPruneReport> {
+    info(`[wslService] Pruning repository on ${targetId}`);
+    return await invoke<RepositoryPruneReport>("prune_backup_repository", { targetId });
+  },
+
+  /**
+   * List the files in an export or backup without extracting it
+   */
+  async listArchiveContents(source: ArchiveSource): Promise<ArchiveListing> {
+    return await invoke<ArchiveListing>("list_archive_contents", { source });
+  },
+
+  /**
+   * Restore selected archive paths into a distribution at an absolute Linux path
+   */
+  async restoreArchiveFilesToDistro(source: ArchiveSource, paths: string[], distroName: string, destination: string): Promise<FileRestoreReport> {
+    info(`[wslService] Restoring ${paths.length} archive paths into ${distroName}:${destination}`);
+    return await invoke<FileRestoreReport>("restore_archive_files_to_distro", { source, paths, distroName, destination });
+  },
+
+  /**
+   * Extract selected archive paths into a Windows folder
+   */
+  async extractArchiveFiles(source: ArchiveSource, paths: string[], destination: string): Promise<FileRestoreReport> {
+    info(`[wslService] Extracting ${paths.length} archive paths to ${destination}`);
+    return await invoke<FileRestoreReport>("extract_archive_files", { source, paths, destination });
+  },
+
+  /**
+   * Show what migrating user data from source to target would copy
+   */
+  async previewDataMigration(source: string, target: string, selection: MigrationSelection): Promise<MigrationPreview> {
+    return await invoke<MigrationPreview>("preview_data_migration", { source, target, selection });
+  },
+
+  /**
+   * Copy the selected user data from source into target
+   */
+  async migrateUserData(source: string, target: string, selection: MigrationSelection): Promise<MigrationReport> {
+    info(`[wslService] Migrating user data from ${source} to ${target}`);
+    return await invoke<MigrationReport>("migrate_user_data", { source, target, selection });
+  },
+
+  /**
+   * Listen for scheduled backup runs
+   * Returns an unlisten function to stop listening
+   */
+  async onBackupsUpdated(callback: () => void): Promise<UnlistenFn> {
+    return await listen("backups-updated", () => callback());
   },
 
   /**
@@ -231,6 +439,7 @@ export const wslService = {
     distroName: string,
     installLocation?: string,
     wslVersion?: number,
+    customization?: RootfsCustomization,
   ): Promise<void> {
     info(`[wslService] Creating from image: ${image} -> ${distroName}`);
     await invoke("create_from_image", {
@@ -238,6 +447,7 @@ export const wslService = {
       distroName,
       installLocation: installLocation || null,
       wslVersion: wslVersion || null,
+      customization: customization || null,
     });
   },
 
@@ -265,6 +475,7 @@ export const wslService = {
     customName: string,
     installLocation?: string,
     wslVersion?: number,
+    customization?: RootfsCustomization,
   ): Promise<void> {
     info(`[wslService] Custom installing: ${distroId} as ${customName}`);
     await invoke("custom_install_with_progress", {
@@ -272,6 +483,7 @@ export const wslService = {
       customName,
       installLocation: installLocation || null,
       wslVersion: wslVersion || null,
+      customization: customization || null,
     });
   },
 
@@ -309,6 +521,128 @@ export const wslService = {
     return path;
   },
 
+  /**
+   * Export a distribution without the paths the filter excludes - opens save dialog
+   * Returns the path and what was left out, or null if cancelled
+   */
+  async exportDistributionFiltered(name: string, filter: ExportFilter): Promise<{ path: string; report: FilterReport } | null> {
+    info(`[wslService] Filtered export dialog opened for: ${name}`);
+    const path = await save({
+      defaultPath: getDefaultExportFilename(name),
+      filters: [{ name: "TAR Archive", extensions: ["tar"] }],
+      title: `Export ${name}`,
+    });
+
+    if (!path) {
+      debug("[wslService] Filtered export cancelled by user");
+      return null;
+    }
+
+    info(`[wslService] Exporting ${name} with filter to: ${path}`);
+    const report = await invoke<FilterReport>("export_distribution_filtered", { name, path, filter });
+    return { path, report };
+  },
+
+  /**
+   * Built-in export filter presets and their patterns
+   */
+  async listExportFilterPresets(): Promise<FilterPresetInfo[]> {
+    return await invoke<FilterPresetInfo[]>("list_export_filter_presets");
+  },
+
+  /**
+   * Export a WSL 2 distribution's disk as a .vhdx - opens save dialog
+   * Returns the path if successful, null if cancelled
+   */
+  async exportDistributionVhd(name: string): Promise<string | null> {
+    info(`[wslService] VHD export dialog opened for: ${name}`);
+    const path = await save({
+      defaultPath: getDefaultExportFilename(name).replace(/\.tar$/, ".vhdx"),
+      filters: [{ name: "Virtual Hard Disk", extensions: ["vhdx"] }],
+      title: `Export ${name}`,
+    });
+
+    if (!path) {
+      debug("[wslService] VHD export cancelled by user");
+      return null;
+    }
+
+    info(`[wslService] Exporting ${name} as VHD to: ${path}`);
+    await invoke("export_distribution_vhd", { name, path });
+    return path;
+  },
+
+  /**
+   * Export a distribution as a .wsl package - opens save dialog
+   * Returns the report if successful, null if cancelled
+   */
+  async exportWslPackage(name: string, options: WslPackageOptions): Promise<WslPackageReport | null> {
+    info(`[wslService] Package export dialog opened for: ${name}`);
+    const path = await save({
+      defaultPath: getDefaultExportFilename(name).replace(/\.tar$/, ".wsl"),
+      filters: [{ name: "WSL Package", extensions: ["wsl"] }],
+      title: `Export ${name}`,
+    });
+
+    if (!path) {
+      debug("[wslService] Package export cancelled by user");
+      return null;
+    }
+
+    info(`[wslService] Exporting ${name} as package to: ${path}`);
+    return await invoke<WslPackageReport>("export_wsl_package", { name, path, options });
+  },
+
+  /**
+   * Export a distribution to an encrypted archive - opens save dialog
+   * Returns the path if successful, null if cancelled
+   */
+  async exportDistributionEncrypted(name: string, key: EncryptionKey): Promise<string | null> {
+    info(`[wslService] Encrypted export dialog opened for: ${name}`);
+    const path = await save({
+      defaultPath: `${getDefaultExportFilename(name)}.age`,
+      filters: [{ name: "Encrypted Archive", extensions: ["age"] }],
+      title: `Export ${name}`,
+    });
+
+    if (!path) {
+      debug("[wslService] Encrypted export cancelled by user");
+      return null;
+    }
+
+    info(`[wslService] Exporting ${name} encrypted (${key.kind}) to: ${path}`);
+    await invoke("export_distribution_encrypted", { name, path, key });
+    return path;
+  },
+
+  /**
+   * Generate a key pair for encrypting exports to a public key
+   */
+  async generateEncryptionKey(): Promise<KeyPair> {
+    return await invoke<KeyPair>("generate_encryption_key");
+  },
+
+  /**
+   * Whether an archive is encrypted; null for plain archives
+   */
+  async detectArchiveEncryption(path: string): Promise<EncryptionKind | null> {
+    return await invoke<EncryptionKind | null>("detect_archive_encryption", { path });
+  },
+
+  /**
+   * Import an encrypted archive, decrypting it straight into wsl --import
+   */
+  async importEncryptedDistribution(
+    name: string,
+    installLocation: string,
+    path: string,
+    key: DecryptionKey,
+    wslVersion?: number,
+  ): Promise<void> {
+    info(`[wslService] Importing encrypted archive ${path} as ${name}`);
+    await invoke("import_encrypted_distribution", { name, installLocation, path, key, wslVersion: wslVersion || null });
+  },
+
   /**
    * Import a distribution - opens file dialog to select tar
    * Returns the new distribution name if successful, null if cancelled
@@ -331,6 +665,37 @@ export const wslService = {
     return name;
   },
 
+  /**
+   * Import a .vhdx as a new distribution; WSL copies it into installLocation
+   */
+  async importDistributionVhd(name: string, installLocation: string, vhdPath: string): Promise<void> {
+    info(`[wslService] Importing ${name} from VHD: ${vhdPath}`);
+    await invoke("import_distribution_vhd", { name, installLocation, vhdPath });
+  },
+
+  /**
+   * Register an existing .vhdx as a distribution without copying it
+   */
+  async registerVhdInPlace(name: string, vhdPath: string): Promise<void> {
+    info(`[wslService] Registering ${name} in place from: ${vhdPath}`);
+    await invoke("register_vhd_in_place", { name, vhdPath });
+  },
+
+  /**
+   * Whether the installed WSL supports VHD import, export and in-place registration
+   */
+  async checkVhdSupport(): Promise<boolean> {
+    return await invoke<boolean>("check_vhd_support");
+  },
+
+  /**
+   * Inspect a local rootfs archive or an http(s) URL without extracting it
+   */
+  async inspectRootfs(source: string): Promise<RootfsInspection> {
+    info(`[wslService] Inspecting rootfs: ${source}`);
+    return await invoke<RootfsInspection>("inspect_rootfs", { source });
+  },
+
   /**
    * Install a distribution from a rootfs URL (e.g., from LXC catalog)
    * Downloads the rootfs and imports it into WSL
@@ -340,6 +705,7 @@ export const wslService = {
     name: string,
     installLocation?: string,
     wslVersion?: number,
+    customization?: RootfsCustomization,
   ): Promise<void> {
     info(`[wslService] Installing from URL: ${name} <- ${url}`);
     await invoke("install_from_rootfs_url", {
@@ -347,9 +713,75 @@ export const wslService = {
       name,
       installLocation: installLocation || null,
       wslVersion: wslVersion || null,
+      customization: customization || null,
+    });
+  },
+
+  /**
+   * Install an image from the LXC catalog
+   * The backend resolves the download URL and verifies the published sha256
+   */
+  async installLxcImage(
+    imageId: string,
+    name: string,
+    installLocation?: string,
+    wslVersion?: number,
+    customization?: RootfsCustomization,
+  ): Promise<void> {
+    info(`[wslService] Installing LXC image: ${name} <- ${imageId}`);
+    await invoke("install_lxc_image", {
+      imageId,
+      name,
+      installLocation: installLocation || null,
+      wslVersion: wslVersion || null,
+      customization: customization || null,
     });
   },
 
+  /**
+   * Install one entry of a distribution manifest directly, without
+   * registering the manifest in HKLM. The backend re-fetches the manifest,
+   * picks the host-architecture download and verifies its Sha256.
+   */
+  async installManifestEntry(
+    manifestUrl: string,
+    flavor: string,
+    entryName: string,
+    name: string,
+    installLocation?: string,
+    wslVersion?: number,
+    customization?: RootfsCustomization,
+  ): Promise<void> {
+    info(`[wslService] Installing manifest entry: ${name} <- ${flavor}/${entryName}`);
+    await invoke("install_manifest_entry", {
+      manifestUrl,
+      flavor,
+      entryName,
+      name,
+      installLocation: installLocation || null,
+      wslVersion: wslVersion || null,
+      customization: customization || null,
+    });
+  },
+
+  // Environment file functions
+
+  /**
+   * Compute the plan for an environment file (YAML or JSON) against the current state
+   */
+  async planEnvironment(path: string): Promise<PlanItem[]> {
+    debug(`[wslService] Planning environment: ${path}`);
+    return await invoke<PlanItem[]>("plan_environment", { path });
+  },
+
+  /**
+   * Apply an environment file in plan order; a dry run changes nothing
+   */
+  async applyEnvironment(path: string, dryRun: boolean): Promise<ApplyItemResult[]> {
+    info(`[wslService] Applying environment: ${path}${dryRun ? " (dry run)" : ""}`);
+    return await invoke<ApplyItemResult[]>("apply_environment", { path, dryRun });
+  },
+
   // WSL Configuration functions
 
   /**
@@ -421,6 +853,22 @@ export const wslService = {
     return await invoke<DistroCatalog>("get_distro_catalog");
   },
 
+  /**
+   * Refetch subscribed catalogs (only expired ones unless force is set)
+   */
+  async refreshCatalogSubscriptions(force: boolean): Promise<CatalogSubscriptionStatus[]> {
+    info(`[wslService] Refreshing catalog subscriptions (force: ${force})`);
+    return await invoke<CatalogSubscriptionStatus[]>("refresh_catalog_subscriptions", { force });
+  },
+
+  /**
+   * Get cached fetch state of every catalog subscription
+   */
+  async getCatalogSubscriptionStatus(): Promise<CatalogSubscriptionStatus[]> {
+    debug("[wslService] Getting catalog subscription status");
+    return await invoke<CatalogSubscriptionStatus[]>("get_catalog_subscription_status");
+  },
+
   /**
    * Reset catalog to defaults (removes all user overrides)
    */
@@ -517,6 +965,38 @@ export const wslService = {
     return await invoke<DistroCatalog>("delete_ms_store_distro", { distroId });
   },
 
+  /**
+   * Export the catalog (or the selected entries) as versioned JSON
+   */
+  async exportDistroCatalog(selection?: CatalogSelection): Promise<string> {
+    info("[wslService] Exporting distro catalog");
+    return await invoke<string>("export_distro_catalog", { selection: selection ?? null });
+  },
+
+  /**
+   * Export the catalog (or the selected entries) to a file
+   */
+  async exportDistroCatalogToFile(path: string, selection?: CatalogSelection): Promise<void> {
+    info(`[wslService] Exporting distro catalog to file: ${path}`);
+    await invoke<void>("export_distro_catalog_to_file", { path, selection: selection ?? null });
+  },
+
+  /**
+   * Import a catalog from JSON (merge keeps existing local entries)
+   */
+  async importDistroCatalog(json: string, merge: boolean): Promise<CatalogImportReport> {
+    info(`[wslService] Importing distro catalog (merge=${merge})`);
+    return await invoke<CatalogImportReport>("import_distro_catalog", { json, merge });
+  },
+
+  /**
+   * Import a catalog from a file
+   */
+  async importDistroCatalogFromFile(path: string, merge: boolean): Promise<CatalogImportReport> {
+    info(`[wslService] Importing distro catalog from file: ${path} (merge=${merge})`);
+    return await invoke<CatalogImportReport>("import_distro_catalog_from_file", { path, merge });
+  },
+
   // Resource monitoring
 
   /**
@@ -855,6 +1335,22 @@ export const wslService = {
     info("[wslService] Clearing distro source");
     await invoke("clear_distro_source");
   },
+
+  /**
+   * Generate a ModernDistributions manifest from catalog download entries
+   * and/or local tarballs and write it to disk. The returned file:// URL
+   * can be passed straight to applyDistroSource.
+   */
+  async generateDistroManifest(
+    entries: ManifestEntryRequest[],
+    outputPath: string
+  ): Promise<GeneratedManifest> {
+    info(`[wslService] Generating distro manifest: ${outputPath}`);
+    return invoke<GeneratedManifest>("generate_distro_manifest", {
+      entries,
+      outputPath,
+    });
+  },
 };
 
 /**
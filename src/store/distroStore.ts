@@ -38,6 +38,7 @@ interface DistroStore {
   openIDE: (name: string) => Promise<void>;
   restartDistro: (name: string, id?: string) => Promise<void>;
   exportDistro: (name: string) => Promise<string | null>;
+  exportDistroVhd: (name: string) => Promise<string | null>;
   importDistro: (name: string, installLocation: string) => Promise<string | null>;
   renameDistro: (
     id: string,
@@ -589,6 +590,20 @@ export const useDistroStore = create<DistroStore>((set, get) => ({
     }
   },
 
+  exportDistroVhd: async (name: string) => {
+    set({ actionInProgress: `Exporting ${name}...` });
+    try {
+      return await wslService.exportDistributionVhd(name);
+    } catch (error) {
+      const appError = parseError(error);
+      logError(appError, "distroStore.exportDistroVhd");
+      set({ error: formatError(appError) });
+      return null;
+    } finally {
+      set({ actionInProgress: null });
+    }
+  },
+
   importDistro: async (name: string, installLocation: string) => {
     set({ actionInProgress: `Importing ${name}...` });
     try {
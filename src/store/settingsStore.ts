@@ -52,6 +52,10 @@ export const useSettingsStore = create<SettingsStore>((set, get) => ({
           ...DEFAULT_SETTINGS.distributionSources,
           ...(loaded.distributionSources || {}),
         },
+        network: {
+          ...DEFAULT_SETTINGS.network,
+          ...(loaded.network || {}),
+        },
       };
       info("[SettingsStore] Settings loaded successfully");
       debug(`[SettingsStore] Loaded locale: ${settings.locale ?? "(undefined, will use auto)"}`);
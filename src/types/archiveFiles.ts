@@ -0,0 +1,41 @@
+// Archive browsing types (mirrors wsl::archive_files)
+
+import type { DecryptionKey } from "./encryption";
+
+/** Archive to browse: a local export (optionally encrypted) or a backup on a target */
+export type ArchiveSource =
+  | { kind: "file"; path: string; key?: DecryptionKey }
+  | { kind: "backup"; targetId: string; backupId: string };
+
+export type ArchiveEntryKind = "file" | "directory" | "symlink" | "hardlink" | "other";
+
+export interface ArchiveEntry {
+  /** Path without a leading "/", e.g. "etc/hostname" */
+  path: string;
+  kind: ArchiveEntryKind;
+  size: number;
+  /** Permission bits, e.g. 0o644 */
+  mode: number;
+  uid: number;
+  gid: number;
+  /** Seconds since the Unix epoch */
+  mtime: number;
+  linkTarget: string | null;
+}
+
+/** Archive contents sorted by path; every parent directory is listed */
+export interface ArchiveListing {
+  entries: ArchiveEntry[];
+  totalBytes: number;
+}
+
+export interface SkippedEntry {
+  path: string;
+  reason: string;
+}
+
+export interface FileRestoreReport {
+  restored: number;
+  bytes: number;
+  skipped: SkippedEntry[];
+}
@@ -0,0 +1,131 @@
+import type { ExportFilter } from "./exportFilter";
+
+export type BackupFrequency = "daily" | "weekly";
+
+/** How a backup is stored: a gzipped tarball, or chunks in the target's deduplicated repository */
+export type BackupFormat = "archive" | "repository";
+
+/** Backup schedule of one distribution */
+export interface BackupSchedule {
+  distroName: string;
+  enabled: boolean;
+  frequency: BackupFrequency;
+  time: string;            // Local time of day, "HH:MM"
+  weekday: number;         // Weekly schedules: 0 = Monday … 6 = Sunday
+  keepDaily: number;       // Days for which the newest archive is kept
+  keepWeekly: number;      // Weeks for which the newest archive is kept
+  updatedAt?: string;      // Set by the backend when saved
+  targetId?: string | null; // Backup target; the backup folder if unset
+  format?: BackupFormat;    // Defaults to "archive"
+  filter?: ExportFilter;    // Paths left out of the backups
+}
+
+export type BackupTrigger = "scheduled" | "manual";
+
+export type BackupRunStatus = "success" | "failed" | "skipped";
+
+/** Record of one backup run */
+export interface BackupRun {
+  id: string;
+  distroName: string;
+  trigger: BackupTrigger;
+  status: BackupRunStatus;
+  startedAt: string;       // RFC 3339 timestamp
+  finishedAt: string;
+  targetId?: string | null; // Backup target; the backup folder if unset
+  format?: BackupFormat;
+  fileName?: string | null; // Archive key on the target (index key for repository backups)
+  sizeBytes?: number | null;
+  storedBytes?: number | null; // Bytes a repository backup added after deduplication
+  excludedBytes?: number | null; // File data the schedule's filter left out
+  sha256?: string | null;
+  message?: string | null;  // Error or skip reason
+  pruned: boolean;          // Archive deleted by retention
+}
+
+/** Backup state of one distribution */
+export interface DistroBackupStatus {
+  distroName: string;
+  schedule: BackupSchedule | null;
+  lastSuccess: BackupRun | null;
+  lastRun: BackupRun | null;
+  nextRun: string | null;
+  archiveCount: number;
+}
+
+/** ID of the built-in target that stores archives in the backup folder */
+export const DEFAULT_BACKUP_TARGET_ID = "local";
+
+/** Where a backup target stores its archives */
+export type TargetLocation =
+  | { kind: "local"; path: string }
+  | { kind: "networkShare"; path: string }  // UNC path, e.g. \\nas\backups
+  | {
+      kind: "s3";
+      endpoint: string;    // e.g. https://s3.eu-west-1.amazonaws.com or http://nas:9000
+      region: string;
+      bucket: string;
+      prefix: string;      // Key prefix, e.g. "wsl/"
+      pathStyle: boolean;  // Bucket in the path instead of the host name (MinIO)
+    };
+
+export type TargetKind = TargetLocation["kind"];
+
+/** A configured backup target */
+export interface BackupTargetConfig {
+  id: string;              // Empty when creating a target
+  name: string;
+  location: TargetLocation;
+}
+
+/** A backup target as returned by the backend (secrets are never included) */
+export interface BackupTargetInfo extends BackupTargetConfig {
+  hasCredentials: boolean;
+  builtIn: boolean;        // The backup folder; cannot be edited or removed
+}
+
+/** User name and password (SMB) or access key ID and secret key (S3) */
+export interface TargetCredentials {
+  username: string;
+  secret: string;
+}
+
+/** A backup stored on a target */
+export interface BackupManifest {
+  id: string;
+  distroName: string;
+  distroId?: string | null;
+  createdAt: string;       // RFC 3339 timestamp
+  format?: BackupFormat;
+  fileName: string;
+  sizeBytes: number;
+  sha256: string;
+  wslVersion: number;
+  defaultUser?: string | null;
+  filter?: ExportFilter | null; // Set when the backup is not a full copy
+}
+
+/** A chunk a repository backup refers to that is missing */
+export interface MissingChunk {
+  backupId: string;
+  hash: string;
+}
+
+/** Result of a repository integrity check */
+export interface RepositoryCheckReport {
+  backups: number;
+  chunks: number;
+  storedBytes: number;     // Compressed size of all chunks
+  logicalBytes: number;    // Total size of all backups before deduplication
+  missingChunks: MissingChunk[];
+  corruptChunks: string[]; // Only filled when data was verified
+  unreferencedChunks: number;
+  unreadableIndexes: string[];
+  dataVerified: boolean;
+}
+
+/** Result of a repository prune */
+export interface RepositoryPruneReport {
+  deletedChunks: number;
+  freedBytes: number;
+}
@@ -7,6 +7,21 @@
 export interface MsStoreDistroInfo {
   description: string;
   enabled?: boolean;
+  /** Subscription that supplied this entry */
+  subscriptionId?: string;
+}
+
+/**
+ * CPU architecture of a rootfs download
+ */
+export type DistroArch = "amd64" | "arm64";
+
+/**
+ * Download URL and checksum for one architecture
+ */
+export interface ArchDownload {
+  url: string;
+  sha256?: string;
 }
 
 /**
@@ -16,10 +31,20 @@ export interface DownloadDistro {
   id: string;
   name: string;
   description: string;
+  /** Architecture-independent download (may be empty when architectures is set) */
   url: string;
   size?: string;
+  sha256?: string;
+  /** Per-architecture downloads; when present, hosts without an entry cannot install */
+  architectures?: Partial<Record<DistroArch, ArchDownload>>;
   enabled?: boolean;
   isBuiltIn?: boolean;
+  /** Subscription that supplied this entry (read-only; edits become local overrides) */
+  subscriptionId?: string;
+  /** True when a local entry overrides a built-in or subscribed one */
+  hasLocalOverride?: boolean;
+  /** Whether a download exists for this machine's architecture */
+  available?: boolean;
 }
 
 /**
@@ -32,6 +57,21 @@ export interface ContainerImage {
   image: string;
   enabled?: boolean;
   isBuiltIn?: boolean;
+  /** Subscription that supplied this entry (read-only; edits become local overrides) */
+  subscriptionId?: string;
+  /** True when a local entry overrides a built-in or subscribed one */
+  hasLocalOverride?: boolean;
+}
+
+/**
+ * An entry replaced or dropped while merging subscribed catalogs
+ */
+export interface CatalogConflict {
+  kind: "msStoreDistro" | "downloadDistro" | "containerImage";
+  id: string;
+  /** Source whose entry is used ("built-in" or a subscription ID) */
+  keptSource: string;
+  ignoredSource: string;
 }
 
 /**
@@ -42,6 +82,54 @@ export interface DistroCatalog {
   msStoreDistros: Record<string, MsStoreDistroInfo>;
   downloadDistros: DownloadDistro[];
   containerImages: ContainerImage[];
+  conflicts?: CatalogConflict[];
+}
+
+/**
+ * IDs to include when exporting a subset of the catalog
+ */
+export interface CatalogSelection {
+  msStoreDistros?: string[];
+  downloadDistros?: string[];
+  containerImages?: string[];
+}
+
+/**
+ * An entry reported by a catalog import
+ */
+export interface CatalogImportEntry {
+  kind: CatalogConflict["kind"];
+  id: string;
+  /** Why the entry was skipped */
+  reason?: string;
+}
+
+/**
+ * Outcome of a catalog import
+ */
+export interface CatalogImportReport {
+  added: CatalogImportEntry[];
+  updated: CatalogImportEntry[];
+  skipped: CatalogImportEntry[];
+  /** Merged catalog after the import */
+  catalog: DistroCatalog;
+}
+
+/**
+ * Fetch state of a subscribed catalog
+ */
+export interface CatalogSubscriptionStatus {
+  id: string;
+  name: string;
+  url: string;
+  enabled: boolean;
+  lastFetched: string | null;
+  stale: boolean;
+  lastError: string | null;
+  downloadDistroCount: number;
+  containerImageCount: number;
+  /** Entries left out of the catalog because they failed validation */
+  invalidEntries: CatalogImportEntry[];
 }
 
 /**
@@ -1,3 +1,6 @@
+import type { ProvisioningStatus } from "./provisioning";
+import type { FilterReport } from "./exportFilter";
+
 export interface Distribution {
   id?: string; // Distribution ID (GUID from Windows Registry)
   name: string;
@@ -16,6 +19,57 @@ export type DistroState = "Running" | "Stopped" | "Installing" | "Unknown";
 /** Installation source types */
 export type InstallSource = "store" | "container" | "download" | "lxc" | "import" | "clone" | "unknown";
 
+/** How a `.vhdx` import registered the disk: copied, or used in place */
+export type VhdImportMode = "copy" | "inPlace";
+
+/** How a clone copied the source: as a `.vhdx` disk or through a tarball */
+export type CloneMethod = "vhd" | "tar";
+
+/** Why a clone fell back to tar */
+export type CloneFallback = "wsl1Source" | "vhdUnsupported" | "customized" | "vhdExportFailed" | "filtered";
+
+/** Result of cloning a distribution */
+export interface CloneResult {
+  method: CloneMethod;
+  fallback?: CloneFallback | null;
+  filterReport?: FilterReport;  // What the export filter left out
+}
+
+/** Parsed `/etc/wsl-distribution.conf` from a `.wsl` package */
+export interface DistributionConf {
+  oobeCommand?: string | null;
+  oobeDefaultUid?: number | null;
+  oobeDefaultName?: string | null;
+  shortcutEnabled: boolean;
+  shortcutIcon?: string | null;
+  terminalProfileEnabled: boolean;
+  terminalProfileTemplate?: string | null;
+}
+
+/** Windows Terminal colors for a generated `.wsl` package profile */
+export interface TerminalProfileOptions {
+  colorScheme?: string;
+  background?: string;  // #RRGGBB
+  foreground?: string;  // #RRGGBB
+  cursorColor?: string; // #RRGGBB
+}
+
+/** Choices for exporting a distribution as a `.wsl` package */
+export interface WslPackageOptions {
+  friendlyName?: string;
+  oobeScript?: string;
+  defaultUid?: number;
+  iconPath?: string;
+  terminal?: TerminalProfileOptions; // omitted = no terminal profile
+}
+
+/** Result of a `.wsl` package export */
+export interface WslPackageReport {
+  path: string;
+  sizeBytes: number;
+  stripped: string[]; // Machine-specific files removed or emptied
+}
+
 /** Metadata for tracking how a distribution was installed */
 export interface DistroMetadata {
   distroId: string;       // Distribution ID (GUID) - primary key
@@ -27,6 +81,14 @@ export interface DistroMetadata {
   catalogEntry?: string;  // Reference to catalog entry ID
   clonedFrom?: string;    // Source distro ID for cloned distros
   importPath?: string;    // Original tar file path for imported distros
+  manifestUrl?: string;   // Distribution manifest a direct install came from
+  manifestFlavor?: string; // Flavor of that manifest entry
+  distributionConf?: DistributionConf; // Parsed /etc/wsl-distribution.conf (.wsl packages)
+  iconPath?: string;      // Icon extracted from a .wsl package
+  terminalFragmentPath?: string; // Windows Terminal fragment generated for the package
+  provisioning?: ProvisioningStatus; // Last cloud-config run (kept for re-runs)
+  vhdImport?: VhdImportMode; // Set for .vhdx imports; importPath holds the source disk
+  restoredFromSnapshot?: string; // ID of the snapshot this distro was last restored from
 }
 
 /** Colors for installation source indicators (matches NewDistroDialog tabs) */
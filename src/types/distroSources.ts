@@ -11,6 +11,7 @@ export interface DistroSource {
   mode: DistroSourceMode;
 }
 
+/** Entry from the manifest's `ModernDistributions` section */
 export interface ManifestEntryPreview {
   flavor: string;
   name: string;
@@ -18,11 +19,66 @@ export interface ManifestEntryPreview {
   default: boolean;
   hasAmd64: boolean;
   hasArm64: boolean;
+  amd64Url?: string | null;
+  /** Lowercase hex, without the manifest's 0x prefix */
+  amd64Sha256?: string | null;
+  arm64Url?: string | null;
+  arm64Sha256?: string | null;
+}
+
+/** Entry from the legacy `Distributions` array (Store-packaged distros) */
+export interface LegacyDistributionPreview {
+  name: string;
+  friendlyName: string;
+  storeAppId?: string | null;
+  packageFamilyName?: string | null;
+  amd64: boolean;
+  arm64: boolean;
+  amd64PackageUrl?: string | null;
+  arm64PackageUrl?: string | null;
+}
+
+export type ManifestWarningKind =
+  | "missingHash"
+  | "invalidHash"
+  | "duplicateName"
+  | "multipleDefaults";
+
+export interface ManifestWarning {
+  kind: ManifestWarningKind;
+  flavor?: string | null;
+  name?: string | null;
+  arch?: "amd64" | "arm64" | null;
+  /** English fallback text */
+  message: string;
 }
 
 export interface ManifestPreview {
   url: string;
   entries: ManifestEntryPreview[];
+  legacyDistributions: LegacyDistributionPreview[];
+  warnings: ManifestWarning[];
+}
+
+/** Where a generated manifest entry gets its downloads from */
+export type ManifestEntrySource =
+  | { kind: "catalog"; distroId: string }
+  | { kind: "localFiles"; amd64Path?: string; arm64Path?: string };
+
+/** One distribution to include in a generated manifest */
+export interface ManifestEntryRequest {
+  flavor: string;
+  name: string;
+  friendlyName?: string;
+  default?: boolean;
+  source: ManifestEntrySource;
+}
+
+/** A manifest written to disk, ready to apply as a file:// source */
+export interface GeneratedManifest {
+  path: string;
+  url: string;
+  preview: ManifestPreview;
 }
 
 /**
@@ -0,0 +1,19 @@
+// Encrypted export archive types (mirrors wsl::encryption)
+
+/** How to encrypt an export: a passphrase or a recipient public key (age1...) */
+export type EncryptionKey =
+  | { kind: "passphrase"; passphrase: string }
+  | { kind: "recipient"; publicKey: string };
+
+/** How to open an encrypted archive: its passphrase or a secret key (AGE-SECRET-KEY-1...) */
+export type DecryptionKey =
+  | { kind: "passphrase"; passphrase: string }
+  | { kind: "identity"; secretKey: string };
+
+/** What an encrypted archive needs to be opened */
+export type EncryptionKind = "passphrase" | "recipient";
+
+export interface KeyPair {
+  publicKey: string;
+  secretKey: string;
+}
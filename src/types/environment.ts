@@ -0,0 +1,28 @@
+// Declarative environment file types (mirrors environment.rs)
+
+export type PlanKind = "create" | "update" | "drift";
+
+export type PlanTarget = "wslConfig" | "distro" | "wslVersion" | "wslConf" | "action";
+
+export interface FieldChange {
+  field: string;
+  current?: string | null;
+  desired?: string | null;
+}
+
+/** One step of an environment plan, in apply order */
+export interface PlanItem {
+  kind: PlanKind;
+  target: PlanTarget;
+  /** Distribution name or action ID (empty for .wslconfig) */
+  name: string;
+  changes: FieldChange[];
+}
+
+export type ApplyStatus = "dryRun" | "applied" | "failed" | "skipped";
+
+export interface ApplyItemResult {
+  item: PlanItem;
+  status: ApplyStatus;
+  message?: string | null;
+}
@@ -0,0 +1,45 @@
+// Export filter types (mirrors wsl::export_filter)
+
+import { formatBytes } from "./distribution";
+
+/** Built-in exclude lists */
+export type FilterPreset = "devCaches" | "packageManagerCaches" | "containerData";
+
+export const FILTER_PRESETS: FilterPreset[] = ["devCaches", "packageManagerCaches", "containerData"];
+
+/**
+ * Paths to leave out of an export, clone or backup. Patterns are globs
+ * relative to the distribution root ("node_modules", "~/.cache", "/var/log");
+ * include patterns keep paths an exclude pattern would leave out.
+ */
+export interface ExportFilter {
+  presets: FilterPreset[];
+  exclude: string[];
+  include: string[];
+}
+
+export const EMPTY_EXPORT_FILTER: ExportFilter = { presets: [], exclude: [], include: [] };
+
+export interface FilterPresetInfo {
+  preset: FilterPreset;
+  patterns: string[];
+}
+
+/** What a filter kept and left out */
+export interface FilterReport {
+  excludedEntries: number;
+  excludedBytes: number;
+  includedBytes: number;
+}
+
+/** Drop blank patterns; returns undefined when the filter excludes nothing */
+export function cleanExportFilter(filter: ExportFilter): ExportFilter | undefined {
+  const clean = (patterns: string[]) => patterns.map((p) => p.trim()).filter(Boolean);
+  const result = { presets: filter.presets, exclude: clean(filter.exclude), include: clean(filter.include) };
+  return result.presets.length === 0 && result.exclude.length === 0 ? undefined : result;
+}
+
+/** Excluded size for display; formatBytes shows 0 as unknown */
+export function formatExcludedBytes(bytes: number): string {
+  return bytes > 0 ? formatBytes(bytes) : "0 B";
+}
@@ -79,6 +79,32 @@ export interface LxcItem {
   combined_rootxz_sha256?: string;
 }
 
+/**
+ * Rootfs image as returned by the backend `get_lxc_catalog` command
+ */
+export interface LxcImage {
+  id: string;
+  os: string;
+  release: string;
+  releaseTitle: string;
+  arch: string;
+  variant: string;
+  aliases: string[];
+  downloadUrl: string;
+  sizeBytes: number;
+  sha256: string | null;
+  buildDate: string;
+}
+
+/**
+ * Backend LXC catalog response (host architecture, stability already filtered)
+ */
+export interface LxcCatalogResponse {
+  images: LxcImage[];
+  lastUpdated: string;
+  expiresAt: string;
+}
+
 // ============= Parsed/Display Types =============
 
 /**
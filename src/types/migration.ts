@@ -0,0 +1,59 @@
+// User data migration types (mirrors wsl::migrate)
+
+/** What to copy from the source distribution */
+export interface MigrationSelection {
+  /** The default user's home, copied into the target user's home */
+  home: boolean;
+  /** /etc/ssh/ssh_host_*, replacing the target's host keys */
+  sshHostKeys: boolean;
+  /** The default user's crontab */
+  crontab: boolean;
+  /** Further absolute paths, copied to the same place */
+  paths: string[];
+}
+
+export const DEFAULT_MIGRATION_SELECTION: MigrationSelection = {
+  home: true,
+  sshHostKeys: false,
+  crontab: true,
+  paths: [],
+};
+
+/** The user `wsl -d <name>` logs in as */
+export interface DistroUser {
+  name: string;
+  uid: number;
+  gid: number;
+  group: string;
+  home: string;
+}
+
+export type MigrationItemKind = "home" | "sshHostKey" | "crontab" | "path";
+
+export interface MigrationItem {
+  kind: MigrationItemKind;
+  sourcePath: string;
+  /** Differs from sourcePath for the home directory and crontab when the users differ */
+  targetPath: string;
+  bytes: number;
+  files: number;
+}
+
+export interface MigrationPreview {
+  sourceUser: DistroUser;
+  targetUser: DistroUser;
+  items: MigrationItem[];
+  /** Selected paths that do not exist in the source */
+  missing: string[];
+  totalBytes: number;
+  totalFiles: number;
+  /** Whether files of the source user get the target user's IDs */
+  remapsOwner: boolean;
+}
+
+export interface MigrationReport {
+  entries: number;
+  bytes: number;
+  /** Entries whose owner was changed to the target user */
+  remapped: number;
+}
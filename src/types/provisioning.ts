@@ -0,0 +1,64 @@
+// First-boot provisioning types (mirrors wsl::provision and wsl::cloud_init)
+
+export type SudoPolicy = "none" | "password" | "noPassword";
+
+/**
+ * Default user created after install, in place of the interactive first-run setup
+ */
+export interface ProvisioningSpec {
+  username: string;
+  /** Defaults to the next free UID (usually 1000) */
+  uid?: number;
+  /** Supplementary groups, created when missing */
+  groups?: string[];
+  sudo?: SudoPolicy;
+  /** crypt(3) hash such as `$6$...` (`openssl passwd -6`) */
+  passwordHash?: string;
+  /** Defaults to /bin/bash, or /bin/sh when bash is missing */
+  shell?: string;
+}
+
+export type ProvisionStepKind = "createUser" | "groups" | "sudo" | "password" | "defaultUser" | "verify";
+
+export interface ProvisionStep {
+  kind: ProvisionStepKind;
+  success: boolean;
+  /** Not run because an earlier step failed */
+  skipped: boolean;
+  message: string;
+}
+
+export interface ProvisioningReport {
+  distroName: string;
+  username: string;
+  steps: ProvisionStep[];
+  success: boolean;
+}
+
+export type CloudInitModule = "users" | "writeFiles" | "packages" | "timezone" | "locale" | "runcmd";
+
+export interface CloudInitModuleResult {
+  module: CloudInitModule;
+  success: boolean;
+  /** Tail of the module's output */
+  output: string;
+}
+
+export interface CloudInitReport {
+  distroName: string;
+  modules: CloudInitModuleResult[];
+  /** Top-level keys outside the supported subset */
+  ignoredKeys: string[];
+  success: boolean;
+}
+
+export type ProvisioningState = "running" | "succeeded" | "failed";
+
+/** Last cloud-config run, stored in the distribution's metadata */
+export interface ProvisioningStatus {
+  state: ProvisioningState;
+  /** The user data, kept for re-runs */
+  userData: string;
+  failedModules: CloudInitModule[];
+  updatedAt: string;
+}
@@ -0,0 +1,67 @@
+// Rootfs archive inspection types (mirrors wsl::rootfs_inspect)
+
+import type { WslConf } from "./settings";
+
+export type RootfsWarningKind = "empty" | "missingShell" | "missingOsRelease" | "nestedRoot";
+
+export interface RootfsWarning {
+  kind: RootfsWarningKind;
+  message: string;
+}
+
+/**
+ * What a rootfs tarball or .wsl package contains, read without extracting it
+ */
+export interface RootfsInspection {
+  /** os-release ID */
+  osId?: string | null;
+  /** os-release VERSION_ID */
+  versionId?: string | null;
+  /** os-release PRETTY_NAME */
+  prettyName?: string | null;
+  /** apt, dnf, yum, zypper, pacman, apk, xbps, emerge or nix */
+  packageManager?: string | null;
+  /** systemd, openrc, runit, s6, busybox or sysvinit */
+  initSystem?: string | null;
+  uncompressedBytes: number;
+  fileCount: number;
+  hasWslConf: boolean;
+  hasDistributionConf: boolean;
+  /** Name that does not clash with installed distributions */
+  suggestedName: string;
+  /** Key for getDistroLogo */
+  suggestedIcon?: string | null;
+  warnings: RootfsWarning[];
+}
+
+// Rootfs customization types (mirrors wsl::customize)
+
+export interface HostsEntry {
+  address: string;
+  hostnames: string[];
+}
+
+/** A file written at an absolute path inside the distribution */
+export interface InjectedFile {
+  path: string;
+  content: string;
+  /** Octal permission bits, defaults to 0644 */
+  mode?: number;
+  uid?: number;
+  gid?: number;
+}
+
+/**
+ * Files and configuration written into a rootfs before `wsl --import`
+ */
+export interface RootfsCustomization {
+  /** Replaces /etc/wsl.conf */
+  wslConf?: WslConf;
+  /** Appended to /etc/hosts (also disables generateHosts) */
+  hostsEntries?: HostsEntry[];
+  /** Local PEM files added as trusted CA certificates */
+  caCertificates?: string[];
+  /** Local files copied into /etc/skel and existing home directories */
+  skeletonFiles?: string[];
+  files?: InjectedFile[];
+}
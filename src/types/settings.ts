@@ -69,6 +69,54 @@ export const DEFAULT_EXECUTABLE_PATHS: ExecutablePaths = {
   wslUncPrefix: "\\\\wsl$",
 };
 
+/**
+ * How outbound HTTP requests pick a proxy
+ * - "system": Use the system proxy (Windows Internet Settings / HTTP(S)_PROXY)
+ * - "none": Connect directly
+ * - "manual": Use the configured proxy URL
+ */
+export type ProxyMode = "system" | "none" | "manual";
+
+/**
+ * Network settings shared by downloads, registry pulls and manifest fetches
+ */
+export interface NetworkSettings {
+  proxyMode: ProxyMode;
+  /** Proxy URL for manual mode (e.g. "http://proxy.corp:8080") */
+  proxyUrl: string | null;
+  /** Hosts that bypass the manual proxy */
+  noProxy: string[];
+  /** Paths to PEM files with extra trusted CA certificates */
+  extraCaCertificates: string[];
+  /** User-Agent override (null = "wsl-ui/<version>") */
+  userAgent: string | null;
+  /** Download bandwidth cap in KiB/s (null or 0 = unlimited) */
+  bandwidthLimitKbps: number | null;
+}
+
+export const DEFAULT_NETWORK_SETTINGS: NetworkSettings = {
+  proxyMode: "system",
+  proxyUrl: null,
+  noProxy: [],
+  extraCaCertificates: [],
+  userAgent: null,
+  bandwidthLimitKbps: null,
+};
+
+/**
+ * A remote distro catalog merged into the local catalog
+ * (http://, https://, or file:// for a network share)
+ */
+export interface CatalogSubscription {
+  /** Stable identifier, shown as the provenance of subscribed entries */
+  id: string;
+  name: string;
+  url: string;
+  enabled: boolean;
+  /** How long a fetched copy is used before it is refreshed */
+  cacheTtlHours: number;
+}
+
 /**
  * Container runtime for pulling OCI images
  * - "builtin": Use built-in OCI implementation (no external dependencies)
@@ -92,6 +140,13 @@ const DEFAULT_CONTAINER_RUNTIME: ContainerRuntime = "builtin";
  */
 export type CloseAction = 'ask' | 'minimize' | 'quit';
 
+/**
+ * What a scheduled backup does when its distribution is running
+ * - 'skip': Record the run as skipped and try again at the next slot
+ * - 'stopThenExport': Terminate the distribution, export it and start it again
+ */
+export type BackupRunningPolicy = 'skip' | 'stopThenExport';
+
 /**
  * Review prompt state for tracking Microsoft Store review requests
  * - 'pending': User hasn't been prompted yet (will show after first install)
@@ -126,11 +181,23 @@ export interface AppSettings {
   executablePaths: ExecutablePaths;
   // Distribution source settings (LXC catalog)
   distributionSources: DistributionSourceSettings;
+  // Proxy, CA certificate and bandwidth settings for outbound HTTP
+  network: NetworkSettings;
+  // Remote distro catalogs, in priority order (earlier entries win conflicts)
+  catalogSubscriptions: CatalogSubscription[];
   // Container runtime for OCI image pulling
   containerRuntime: ContainerRuntime;
   // Default base path for new WSL installations (supports %ENV_VAR% expansion)
   // Empty string means use system default (%LOCALAPPDATA%\wsl)
   defaultInstallBasePath: string;
+  // Directory for distribution snapshots (supports %ENV_VAR% expansion)
+  // Empty string means use the app config directory
+  snapshotDirectory: string;
+  // Directory for scheduled backup archives (supports %ENV_VAR% expansion)
+  // Empty string means use the app config directory
+  backupDirectory: string;
+  // How scheduled backups treat running distributions
+  backupRunningPolicy: BackupRunningPolicy;
   // Enable debug logging (more verbose logs for troubleshooting)
   debugLogging: boolean;
   // Review prompt tracking
@@ -157,8 +224,13 @@ export const DEFAULT_SETTINGS: AppSettings = {
   wslTimeouts: DEFAULT_WSL_TIMEOUTS,
   executablePaths: DEFAULT_EXECUTABLE_PATHS,
   distributionSources: DEFAULT_DISTRIBUTION_SOURCE_SETTINGS,
+  network: DEFAULT_NETWORK_SETTINGS,
+  catalogSubscriptions: [],
   containerRuntime: DEFAULT_CONTAINER_RUNTIME,
   defaultInstallBasePath: "",
+  snapshotDirectory: "",
+  backupDirectory: "",
+  backupRunningPolicy: "skip",
   debugLogging: false,
   reviewPromptState: "pending",
   reviewPromptLaunchCount: 0,
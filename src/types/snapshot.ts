@@ -0,0 +1,26 @@
+/** A stored point-in-time snapshot of a distribution */
+export interface Snapshot {
+  id: string;
+  name: string;
+  description?: string | null;
+  distroName: string;      // Distribution name when the snapshot was taken
+  distroId?: string | null; // Distribution GUID (follows in-place restores)
+  createdAt: string;       // ISO 8601 timestamp
+  fileName: string;        // Relative to the snapshot directory
+  sizeBytes: number;
+  sha256: string;
+  wslVersion: number;
+  defaultUser?: string | null;
+}
+
+/** Where a snapshot is restored to */
+export type RestoreTarget =
+  | { mode: "inPlace" }
+  | { mode: "newDistro"; name: string; location?: string | null };
+
+/** Result of restoring a snapshot */
+export interface RestoreResult {
+  distroName: string;
+  distroId?: string | null;
+  defaultUserRestored: boolean;
+}